@@ -5,8 +5,10 @@ use {
         print::Print,
     },
     edit::{edit_file, edit_with_builder, Builder},
+    futures::{executor::block_on, StreamExt, TryStreamExt},
     gluesql_core::{
-        prelude::Glue,
+        prelude::{plan, translate_sql, Glue, Payload, PayloadStream},
+        result::Result as GlueResult,
         store::{GStore, GStoreMut},
     },
     rustyline::{error::ReadlineError, Editor},
@@ -161,26 +163,67 @@ where
     }
 
     fn execute(&mut self, sql: impl AsRef<str>) -> Result<()> {
-        match self.glue.execute(sql) {
-            Ok(payloads) => self.print.payloads(&payloads)?,
-            Err(e) => {
-                println!("[error] {}\n", e);
-            }
-        };
+        if let Err(e) = block_on(self.execute_async(sql.as_ref()))? {
+            println!("[error] {}\n", e);
+        }
 
         Ok(())
     }
 
+    /// Statements are planned and executed one by one, the rows of `SELECT` are printed as
+    /// they are read from the storage unless the tabular output needs the whole result.
+    /// Errors of the output are returned apart from the errors of the statements.
+    async fn execute_async(&mut self, sql: &str) -> Result<GlueResult<()>> {
+        let statements = match translate_sql(sql) {
+            Ok(statements) => statements,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        for statement in statements {
+            let storage = self.glue.storage.as_ref().unwrap();
+            let statement = match plan(storage, statement).await {
+                Ok(statement) => statement,
+                Err(e) => return Ok(Err(e)),
+            };
+
+            let (labels, mut rows) = match self.glue.execute_stream(&statement).await {
+                Ok(PayloadStream::Select { labels, rows }) => (labels, rows),
+                Ok(PayloadStream::Payload(payload)) => {
+                    self.print.payload(&payload)?;
+                    continue;
+                }
+                Err(e) => return Ok(Err(e)),
+            };
+
+            if self.print.option.tabular {
+                let rows = match rows.try_collect().await {
+                    Ok(rows) => rows,
+                    Err(e) => return Ok(Err(e)),
+                };
+
+                self.print.payload(&Payload::Select { labels, rows })?;
+                continue;
+            }
+
+            self.print.labels(&labels)?;
+            while let Some(row) = rows.next().await {
+                match row {
+                    Ok(row) => self.print.row(&row)?,
+                    Err(e) => return Ok(Err(e)),
+                }
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
     pub fn load<P: AsRef<Path>>(&mut self, filename: P) -> Result<()> {
         let mut sqls = String::new();
         File::open(filename)?.read_to_string(&mut sqls)?;
         for sql in sqls.split(';').filter(|sql| !sql.trim().is_empty()) {
-            match self.glue.execute(sql) {
-                Ok(payloads) => self.print.payloads(&payloads)?,
-                Err(e) => {
-                    println!("[error] {}\n", e);
-                    break;
-                }
+            if let Err(e) = block_on(self.execute_async(sql))? {
+                println!("[error] {}\n", e);
+                break;
             }
         }
 
@@ -11,8 +11,9 @@ use {
     clap::Parser,
     futures::executor::block_on,
     gluesql_core::{
-        ast::{Expr, SetExpr, Statement, ToSql, Values},
-        prelude::Row,
+        ast::{ColumnDef, Expr, SetExpr, Statement, ToSql, Values},
+        data::{Schema, Sequence, View},
+        prelude::{Row, Value},
         store::Transaction,
         store::{GStore, GStoreMut, Store},
     },
@@ -84,10 +85,32 @@ pub fn dump_database(storage: SledStorage, dump_path: PathBuf) -> Result<SledSto
 
     block_on(async {
         let (storage, _) = storage.begin(true).await.map_err(|(_, error)| error)?;
-        let schemas = storage.fetch_all_schemas().await?;
-        for schema in schemas {
+        for namespace in storage.fetch_namespaces().await? {
+            let create_statement = Statement::CreateSchema {
+                if_not_exists: false,
+                name: namespace,
+            };
+
+            writeln!(&file, "{}", create_statement.to_sql())?;
+        }
+
+        let schemas = sort_by_references(storage.fetch_all_schemas().await?);
+        for schema in &schemas {
             writeln!(&file, "{}", schema.clone().to_ddl())?;
 
+            // generated columns are computed again as the rows are inserted
+            let (indexes, columns): (Vec<_>, Vec<_>) = schema
+                .column_defs
+                .iter()
+                .enumerate()
+                .filter(|(_, column_def)| column_def.get_generated().is_none())
+                .map(|(index, ColumnDef { name, .. })| (index, name.to_owned()))
+                .unzip();
+            let columns = match columns.len() == schema.column_defs.len() {
+                true => Vec::new(),
+                false => columns,
+            };
+
             let rows_list = storage
                 .scan_data(&schema.table_name)
                 .await?
@@ -98,9 +121,13 @@ pub fn dump_database(storage: SledStorage, dump_path: PathBuf) -> Result<SledSto
                 let exprs_list = rows
                     .map(|result| {
                         result.map(|Row(values)| {
-                            values
-                                .into_iter()
-                                .map(|value| Ok(Expr::try_from(value)?))
+                            indexes
+                                .iter()
+                                .map(|index| {
+                                    let value = values.get(*index).cloned().unwrap_or(Value::Null);
+
+                                    Ok(Expr::try_from(value)?)
+                                })
                                 .collect::<Result<Vec<_>>>()
                         })?
                     })
@@ -108,13 +135,20 @@ pub fn dump_database(storage: SledStorage, dump_path: PathBuf) -> Result<SledSto
 
                 let insert_statement = Statement::Insert {
                     table_name: schema.table_name.clone(),
-                    columns: Vec::new(),
+                    columns: columns.clone(),
+                    overriding_system_value: schema
+                        .column_defs
+                        .iter()
+                        .any(ColumnDef::is_always_identity),
                     source: gluesql_core::ast::Query {
+                        with: None,
                         body: SetExpr::Values(Values(exprs_list)),
                         order_by: Vec::new(),
                         limit: None,
                         offset: None,
                     },
+                    on_conflict: None,
+                    returning: Vec::new(),
                 }
                 .to_sql();
 
@@ -124,6 +158,104 @@ pub fn dump_database(storage: SledStorage, dump_path: PathBuf) -> Result<SledSto
             writeln!(&file)?;
         }
 
+        // views are created in the order they were, after the tables and views they read
+        let mut views = storage.fetch_all_views().await?;
+        views.sort_by_key(|View { created, .. }| *created);
+
+        for View {
+            name,
+            columns,
+            query,
+            ..
+        } in views
+        {
+            let create_statement = Statement::CreateView {
+                or_replace: false,
+                name,
+                columns,
+                query: Box::new(query),
+            };
+
+            writeln!(&file, "{}", create_statement.to_sql())?;
+        }
+
+        // sequences of identity columns are created along with their tables, they are
+        // created again to start over from where they were
+        let identity_sequences = schemas
+            .iter()
+            .flat_map(|schema| {
+                let table_name = &schema.table_name;
+
+                schema
+                    .column_defs
+                    .iter()
+                    .filter(|column_def| column_def.is_identity())
+                    .map(move |column_def| Sequence::identity(table_name, &column_def.name).name)
+            })
+            .collect::<Vec<_>>();
+
+        for sequence in storage.fetch_all_sequences().await? {
+            let Sequence {
+                name,
+                start,
+                increment,
+            } = sequence;
+
+            let start = match storage.current_sequence_value(&name).await? {
+                Some(current) => current.saturating_add(increment),
+                None => start,
+            };
+
+            if identity_sequences.contains(&name) {
+                let drop_statement = Statement::DropSequence {
+                    if_exists: false,
+                    names: vec![name.clone()],
+                };
+
+                writeln!(&file, "{}", drop_statement.to_sql())?;
+            }
+
+            let create_statement = Statement::CreateSequence {
+                if_not_exists: false,
+                name,
+                increment: Some(Expr::try_from(Value::I64(increment))?),
+                start: Some(Expr::try_from(Value::I64(start))?),
+            };
+
+            writeln!(&file, "{}", create_statement.to_sql())?;
+        }
+
         Ok(storage)
     })
 }
+
+/// Orders the tables so that the tables referenced by the foreign keys of a table are
+/// created and filled before it.
+fn sort_by_references(mut schemas: Vec<Schema>) -> Vec<Schema> {
+    let mut sorted: Vec<Schema> = Vec::with_capacity(schemas.len());
+
+    while !schemas.is_empty() {
+        let (ready, pending): (Vec<_>, Vec<_>) = schemas.into_iter().partition(|schema| {
+            schema.foreign_keys.iter().all(|foreign_key| {
+                let referenced = &foreign_key.referenced_table_name;
+
+                referenced == &schema.table_name
+                    || sorted
+                        .iter()
+                        .any(|Schema { table_name, .. }| table_name == referenced)
+            })
+        });
+
+        // tables left in a cycle, which `CREATE TABLE` does not make, keep their order
+        if ready.is_empty() {
+            sorted.extend(pending);
+
+            break;
+        }
+
+        sorted.extend(ready);
+        schemas = pending;
+    }
+
+    sorted
+}
@@ -1,6 +1,6 @@
 use {
     crate::command::{SetOption, ShowOption},
-    gluesql_core::prelude::{Payload, PayloadVariable},
+    gluesql_core::prelude::{ExplainNode, Payload, PayloadVariable, Row},
     std::{
         fmt::Display,
         fs::File,
@@ -91,10 +91,6 @@ impl<'a, W: Write> Print<W> {
         }
     }
 
-    pub fn payloads(&mut self, payloads: &[Payload]) -> IOResult<()> {
-        payloads.iter().try_for_each(|p| self.payload(p))
-    }
-
     pub fn payload(&mut self, payload: &Payload) -> IOResult<()> {
         let mut affected = |n: usize, msg: &str| -> IOResult<()> {
             let payload = format!("{} row{} {}", n, if n > 1 { "s" } else { "" }, msg);
@@ -104,6 +100,13 @@ impl<'a, W: Write> Print<W> {
         match payload {
             Payload::Create => self.write("Table created")?,
             Payload::DropTable => self.write("Table dropped")?,
+            Payload::CreateSchema => self.write("Schema created")?,
+            Payload::DropSchema => self.write("Schema dropped")?,
+            Payload::CreateSequence => self.write("Sequence created")?,
+            Payload::DropSequence => self.write("Sequence dropped")?,
+            Payload::CreateView => self.write("View created")?,
+            Payload::DropView => self.write("View dropped")?,
+            Payload::Analyze => self.write("Table analyzed")?,
             Payload::AlterTable => self.write("Table altered")?,
             Payload::CreateIndex => self.write("Index created")?,
             Payload::DropIndex => self.write("Index dropped")?,
@@ -130,53 +133,75 @@ impl<'a, W: Write> Print<W> {
                 let table = self.build_table(table);
                 self.write(table)?;
             }
-            Payload::Select { labels, rows } => {
-                let PrintOption {
-                    tabular,
-                    colsep,
-                    colwrap,
-                    heading,
-                } = &self.option;
-
-                match tabular {
-                    true => {
-                        let labels = labels.iter().map(AsRef::as_ref);
-                        let mut table = self.get_table(labels);
-                        for row in rows {
-                            let row: Vec<String> = row.iter().map(Into::into).collect();
-
-                            table.add_record(row);
-                        }
-                        let table = self.build_table(table);
-                        self.write(table)?;
+            Payload::Select { labels, rows } => match self.option.tabular {
+                true => {
+                    let labels = labels.iter().map(AsRef::as_ref);
+                    let mut table = self.get_table(labels);
+                    for row in rows {
+                        let row: Vec<String> = row.iter().map(Into::into).collect();
+
+                        table.add_record(row);
                     }
-                    false => {
-                        let labels = labels
-                            .iter()
-                            .map(|v| format!("{colwrap}{v}{colwrap}"))
-                            .collect::<Vec<_>>()
-                            .join(colsep.as_str());
-                        if *heading {
-                            writeln!(self.output, "{}", labels)?;
-                        }
-
-                        for row in rows {
-                            let row = row
-                                .iter()
-                                .map(Into::into)
-                                .map(|v: String| format!("{colwrap}{v}{colwrap}"))
-                                .collect::<Vec<_>>()
-                                .join(colsep.as_str());
-                            writeln!(self.output, "{}", row)?
-                        }
+                    let table = self.build_table(table);
+                    self.write(table)?;
+                }
+                false => {
+                    self.labels(labels)?;
+
+                    for row in rows {
+                        self.row(row)?;
                     }
                 }
+            },
+            Payload::Explain(node) => {
+                let mut lines = Vec::new();
+                explain_lines(node, 0, &mut lines);
+
+                self.write(lines.join("\n"))?;
             }
         };
 
         Ok(())
     }
 
+    /// Heading of the rows which are printed one by one as they are read, only used by the
+    /// non-tabular output as the table lays out the whole result at once.
+    pub fn labels(&mut self, labels: &[String]) -> IOResult<()> {
+        let PrintOption {
+            colsep,
+            colwrap,
+            heading,
+            ..
+        } = &self.option;
+
+        if !*heading {
+            return Ok(());
+        }
+
+        let labels = labels
+            .iter()
+            .map(|v| format!("{colwrap}{v}{colwrap}"))
+            .collect::<Vec<_>>()
+            .join(colsep.as_str());
+
+        writeln!(self.output, "{}", labels)
+    }
+
+    pub fn row(&mut self, row: &Row) -> IOResult<()> {
+        let PrintOption {
+            colsep, colwrap, ..
+        } = &self.option;
+
+        let row = row
+            .iter()
+            .map(Into::into)
+            .map(|v: String| format!("{colwrap}{v}{colwrap}"))
+            .collect::<Vec<_>>()
+            .join(colsep.as_str());
+
+        writeln!(self.output, "{}", row)
+    }
+
     fn write(&mut self, payload: impl Display) -> IOResult<()> {
         if let Some(file) = &self.spool_file {
             writeln!(file.to_owned(), "{}\n", payload)?;
@@ -249,6 +274,39 @@ impl<'a, W: Write> Print<W> {
     }
 }
 
+/// One line per operator, the children are indented below their parent.
+fn explain_lines(node: &ExplainNode, depth: usize, lines: &mut Vec<String>) {
+    let ExplainNode {
+        name,
+        detail,
+        children,
+        analyzed,
+    } = node;
+
+    let arrow = match depth {
+        0 => "".to_owned(),
+        _ => format!("{}-> ", "   ".repeat(depth - 1)),
+    };
+    let detail = match detail.is_empty() {
+        true => "".to_owned(),
+        false => format!(": {detail}"),
+    };
+    let analyzed = match analyzed {
+        Some(analyzed) => format!(
+            " (rows={} elapsed={:.3}ms)",
+            analyzed.rows,
+            analyzed.elapsed as f64 / 1000.0
+        ),
+        None => "".to_owned(),
+    };
+
+    lines.push(format!("{arrow}{name}{detail}{analyzed}"));
+
+    for child in children {
+        explain_lines(child, depth + 1, lines);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -290,7 +348,8 @@ mod tests {
     fn print_payload() {
         use gluesql_core::{
             ast::DataType,
-            prelude::{Payload, PayloadVariable, Row, Value},
+            executor::Analyzed,
+            prelude::{ExplainNode, Payload, PayloadVariable, Row, Value},
         };
 
         let mut print = Print::new(Vec::new(), None, Default::default());
@@ -313,6 +372,13 @@ mod tests {
 
         test!(&Payload::Create, "Table created");
         test!(&Payload::DropTable, "Table dropped");
+        test!(&Payload::CreateSchema, "Schema created");
+        test!(&Payload::DropSchema, "Schema dropped");
+        test!(&Payload::CreateSequence, "Sequence created");
+        test!(&Payload::DropSequence, "Sequence dropped");
+        test!(&Payload::CreateView, "View created");
+        test!(&Payload::DropView, "View dropped");
+        test!(&Payload::Analyze, "Table analyzed");
         test!(&Payload::AlterTable, "Table altered");
         test!(&Payload::CreateIndex, "Index created");
         test!(&Payload::DropIndex, "Index dropped");
@@ -464,6 +530,33 @@ mod tests {
 | mylist | LIST      |"
         );
 
+        let node = |name: &str, detail: &str, children, analyzed| ExplainNode {
+            name: name.to_owned(),
+            detail: detail.to_owned(),
+            children,
+            analyzed,
+        };
+        test!(
+            &Payload::Explain(node(
+                "Limit",
+                "LIMIT 1",
+                vec![node(
+                    "Project",
+                    "name",
+                    vec![node("Table Scan", "Item", Vec::new(), None)],
+                    None
+                )],
+                Some(Analyzed {
+                    rows: 1,
+                    elapsed: 1500
+                })
+            )),
+            "
+Limit: LIMIT 1 (rows=1 elapsed=1.500ms)
+-> Project: name
+   -> Table Scan: Item"
+        );
+
         // ".set tabular OFF" should print SELECTED payload without tabular option
         print.set_option(SetOption::Tabular(false));
         test!(
@@ -7,14 +7,7 @@ use {
 
 #[tokio::test]
 async fn dump_and_import() {
-    let data_path = "tmp/src";
-    let dump_path = PathBuf::from("tmp/dump.sql");
-
-    let config = sled::Config::default().path(data_path).temporary(true);
-    let source_storage = SledStorage::try_from(config).unwrap();
-    let mut source_glue = Glue::new(source_storage);
-
-    let sqls = vec![
+    let sqls = [
         "CREATE TABLE Foo (
             boolean BOOLEAN,
             int8 INT8,
@@ -56,14 +49,39 @@ async fn dump_and_import() {
         "CREATE TABLE Bar AS SELECT N FROM SERIES(101);",
     ];
 
+    let (mut source_glue, mut target_glue) = dump_and_load("foo", &sqls);
+
+    // schemas should be identical
+    let sql = "SELECT OBJECT_TYPE, OBJECT_NAME FROM GLUE_OBJECTS";
+    let source_data = source_glue.execute(sql).unwrap();
+    let target_data = target_glue.execute(sql).unwrap();
+    assert_eq!(source_data, target_data);
+
+    // data should be identical
+    let sql = "SELECT * FROM Foo JOIN Bar;";
+    let source_data = source_glue.execute(sql).unwrap();
+    let target_data = target_glue.execute(sql).unwrap();
+    assert_eq!(source_data, target_data);
+}
+
+/// Runs `sqls` on a new database, dumps it and loads the dump into another new database.
+fn dump_and_load(name: &str, sqls: &[&str]) -> (Glue<SledStorage>, Glue<SledStorage>) {
+    let config = sled::Config::default()
+        .path(format!("tmp/{name}_src"))
+        .temporary(true);
+    let source_storage = SledStorage::try_from(config).unwrap();
+    let mut source_glue = Glue::new(source_storage);
+
     for sql in sqls {
         source_glue.execute(sql).unwrap();
     }
 
+    let dump_path = PathBuf::from(format!("tmp/{name}_dump.sql"));
     let source_storage = dump_database(source_glue.storage.unwrap(), dump_path.clone()).unwrap();
 
-    let data_path = "tmp/target";
-    let config = sled::Config::default().path(data_path).temporary(true);
+    let config = sled::Config::default()
+        .path(format!("tmp/{name}_target"))
+        .temporary(true);
     let target_storage = SledStorage::try_from(config).unwrap();
     let mut target_glue = Glue::new(target_storage);
 
@@ -77,17 +95,115 @@ async fn dump_and_import() {
         target_glue.execute(sql).unwrap();
     }
 
-    let mut source_glue = Glue::new(source_storage);
+    (Glue::new(source_storage), target_glue)
+}
 
-    // schemas should be identical
-    let sql = "SELECT OBJECT_TYPE, OBJECT_NAME FROM GLUE_OBJECTS";
-    let source_data = source_glue.execute(sql).unwrap();
-    let target_data = target_glue.execute(sql).unwrap();
-    assert_eq!(source_data, target_data);
+#[tokio::test]
+async fn dump_sequences() {
+    let sqls = [
+        "CREATE SEQUENCE Counter INCREMENT BY 5 START WITH 10;",
+        "SELECT NEXTVAL('Counter');",
+        "CREATE SEQUENCE Unused START WITH 3;",
+        "CREATE TABLE Item (id INTEGER GENERATED ALWAYS AS IDENTITY, name TEXT);",
+        "INSERT INTO Item (name) VALUES ('a'), ('b');",
+    ];
+    let (mut source_glue, mut target_glue) = dump_and_load("sequence", &sqls);
 
-    // data should be identical
-    let sql = "SELECT * FROM Foo JOIN Bar;";
-    let source_data = source_glue.execute(sql).unwrap();
-    let target_data = target_glue.execute(sql).unwrap();
-    assert_eq!(source_data, target_data);
+    for sql in [
+        "SELECT NEXTVAL('Counter') AS v;",
+        "SELECT NEXTVAL('Unused') AS v;",
+        "INSERT INTO Item (name) VALUES ('c');",
+        "SELECT id, name FROM Item ORDER BY id;",
+    ] {
+        let source_data = source_glue.execute(sql).unwrap();
+        let target_data = target_glue.execute(sql).unwrap();
+        assert_eq!(source_data, target_data);
+    }
+}
+
+#[tokio::test]
+async fn dump_generated_columns() {
+    let sqls = [
+        "CREATE TABLE Sale (
+            price INTEGER,
+            quantity INTEGER,
+            total INTEGER GENERATED ALWAYS AS (price * quantity) STORED
+         );",
+        "INSERT INTO Sale (price, quantity) VALUES (3, 2), (5, 4);",
+    ];
+    let (mut source_glue, mut target_glue) = dump_and_load("generated", &sqls);
+
+    for sql in [
+        "SELECT price, quantity, total FROM Sale ORDER BY price;",
+        "UPDATE Sale SET quantity = 10 WHERE price = 3;",
+        "SELECT price, quantity, total FROM Sale ORDER BY price;",
+    ] {
+        let source_data = source_glue.execute(sql).unwrap();
+        let target_data = target_glue.execute(sql).unwrap();
+        assert_eq!(source_data, target_data);
+    }
+}
+
+#[tokio::test]
+async fn dump_views() {
+    let sqls = [
+        "CREATE TABLE Item (id INTEGER, price INTEGER);",
+        "INSERT INTO Item VALUES (1, 100), (2, 300);",
+        "CREATE VIEW Expensive AS SELECT id, price FROM Item WHERE price > 200;",
+        "CREATE VIEW ExpensiveId (item_id) AS SELECT id FROM Expensive;",
+    ];
+    let (mut source_glue, mut target_glue) = dump_and_load("view", &sqls);
+
+    for sql in [
+        "SELECT OBJECT_TYPE, OBJECT_NAME FROM GLUE_OBJECTS",
+        "SELECT * FROM Expensive;",
+        "SELECT item_id FROM ExpensiveId;",
+    ] {
+        let source_data = source_glue.execute(sql).unwrap();
+        let target_data = target_glue.execute(sql).unwrap();
+        assert_eq!(source_data, target_data);
+    }
+}
+
+#[tokio::test]
+async fn dump_schemas() {
+    let sqls = [
+        "CREATE SCHEMA analytics;",
+        "CREATE TABLE analytics.Event (id INTEGER, name TEXT);",
+        "INSERT INTO analytics.Event VALUES (1, 'open'), (2, 'close');",
+    ];
+    let (mut source_glue, mut target_glue) = dump_and_load("schema", &sqls);
+
+    for sql in [
+        "SELECT OBJECT_TYPE, OBJECT_NAME FROM GLUE_OBJECTS",
+        "SELECT id, name FROM analytics.Event ORDER BY id;",
+    ] {
+        let source_data = source_glue.execute(sql).unwrap();
+        let target_data = target_glue.execute(sql).unwrap();
+        assert_eq!(source_data, target_data);
+    }
+}
+
+#[tokio::test]
+async fn dump_foreign_keys() {
+    let sqls = [
+        "CREATE TABLE Zone (id INTEGER PRIMARY KEY, name TEXT);",
+        "CREATE TABLE Account (
+            id INTEGER PRIMARY KEY,
+            zone_id INTEGER,
+            FOREIGN KEY (zone_id) REFERENCES Zone (id)
+         );",
+        "INSERT INTO Zone VALUES (1, 'north'), (2, 'south');",
+        "INSERT INTO Account VALUES (10, 2), (20, 1);",
+    ];
+    let (mut source_glue, mut target_glue) = dump_and_load("foreign_key", &sqls);
+
+    for sql in [
+        "SELECT OBJECT_TYPE, OBJECT_NAME FROM GLUE_OBJECTS",
+        "SELECT Account.id, Zone.name FROM Account JOIN Zone ON Account.zone_id = Zone.id;",
+    ] {
+        let source_data = source_glue.execute(sql).unwrap();
+        let target_data = target_glue.execute(sql).unwrap();
+        assert_eq!(source_data, target_data);
+    }
 }
@@ -2,6 +2,7 @@ use {
     super::{DataType, Expr},
     crate::ast::ToSql,
     serde::{Deserialize, Serialize},
+    strum_macros::Display,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -36,6 +37,34 @@ pub enum ColumnOption {
     Default(Expr),
     /// `{ PRIMARY KEY | UNIQUE }`
     Unique { is_primary: bool },
+    /// `CHECK (<expr>)`
+    Check(Expr),
+    /// `AUTO_INCREMENT` or `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY`, values are
+    /// generated by the sequence of the column, which are the only ones it takes when `always`
+    Identity { always: bool },
+    /// `GENERATED ALWAYS AS (<expr>) STORED`, values are computed from the other columns
+    Generated(Expr),
+}
+
+/// `FOREIGN KEY (<columns>) REFERENCES <table> (<columns>)` table constraint
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub referenced_table_name: String,
+    /// Empty until `CREATE TABLE` resolves it to the referenced primary key
+    pub referenced_columns: Vec<String>,
+    pub on_delete: ReferentialAction,
+    pub on_update: ReferentialAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
 }
 
 impl ToSql for AlterTableOperation {
@@ -97,13 +126,55 @@ impl ToSql for ColumnOption {
                 true => "PRIMARY KEY".to_owned(),
                 false => "UNIQUE".to_owned(),
             },
+            ColumnOption::Check(expr) => format!("CHECK ({})", expr.to_sql()),
+            ColumnOption::Identity { always: false } => "AUTO_INCREMENT".to_owned(),
+            ColumnOption::Identity { always: true } => "GENERATED ALWAYS AS IDENTITY".to_owned(),
+            ColumnOption::Generated(expr) => {
+                format!("GENERATED ALWAYS AS ({}) STORED", expr.to_sql())
+            }
         }
     }
 }
 
+impl ToSql for ForeignKey {
+    fn to_sql(&self) -> String {
+        let ForeignKey {
+            columns,
+            referenced_table_name,
+            referenced_columns,
+            on_delete,
+            on_update,
+        } = self;
+
+        let columns = columns.join(", ");
+        let referenced_columns = match referenced_columns.is_empty() {
+            true => String::new(),
+            false => format!(" ({})", referenced_columns.join(", ")),
+        };
+        let actions = [("DELETE", on_delete), ("UPDATE", on_update)]
+            .into_iter()
+            .filter(|(_, action)| **action != ReferentialAction::NoAction)
+            .map(|(event, action)| format!(" ON {event} {}", action.to_sql()))
+            .collect::<String>();
+
+        format!(
+            "FOREIGN KEY ({columns}) REFERENCES {referenced_table_name}{referenced_columns}{actions}"
+        )
+    }
+}
+
+impl ToSql for ReferentialAction {
+    fn to_sql(&self) -> String {
+        self.to_string().replace('_', " ")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::ast::{AstLiteral, ColumnDef, ColumnOption, DataType, Expr, ToSql};
+    use crate::ast::{
+        AstLiteral, BinaryOperator, ColumnDef, ColumnOption, DataType, Expr, ForeignKey,
+        ReferentialAction, ToSql,
+    };
 
     #[test]
     fn to_sql_column_def() {
@@ -152,5 +223,87 @@ mod tests {
             }
             .to_sql()
         );
+
+        assert_eq!(
+            "name TEXT NULL CHECK (name <> '')",
+            ColumnDef {
+                name: "name".to_owned(),
+                data_type: DataType::Text,
+                nullable: true,
+                options: vec![ColumnOption::Check(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("name".to_owned())),
+                    op: BinaryOperator::NotEq,
+                    right: Box::new(Expr::Literal(AstLiteral::QuotedString(String::new()))),
+                })]
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "id INT NOT NULL AUTO_INCREMENT PRIMARY KEY",
+            ColumnDef {
+                name: "id".to_owned(),
+                data_type: DataType::Int,
+                nullable: false,
+                options: vec![
+                    ColumnOption::Identity { always: false },
+                    ColumnOption::Unique { is_primary: true }
+                ]
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "id INT NOT NULL GENERATED ALWAYS AS IDENTITY",
+            ColumnDef {
+                name: "id".to_owned(),
+                data_type: DataType::Int,
+                nullable: false,
+                options: vec![ColumnOption::Identity { always: true }]
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "total INT NULL GENERATED ALWAYS AS (price * qty) STORED",
+            ColumnDef {
+                name: "total".to_owned(),
+                data_type: DataType::Int,
+                nullable: true,
+                options: vec![ColumnOption::Generated(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("price".to_owned())),
+                    op: BinaryOperator::Multiply,
+                    right: Box::new(Expr::Identifier("qty".to_owned())),
+                })]
+            }
+            .to_sql()
+        );
+    }
+
+    #[test]
+    fn to_sql_foreign_key() {
+        assert_eq!(
+            "FOREIGN KEY (parent_id) REFERENCES Parent (id)",
+            ForeignKey {
+                columns: vec!["parent_id".to_owned()],
+                referenced_table_name: "Parent".to_owned(),
+                referenced_columns: vec!["id".to_owned()],
+                on_delete: ReferentialAction::NoAction,
+                on_update: ReferentialAction::NoAction,
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "FOREIGN KEY (a, b) REFERENCES Parent ON DELETE CASCADE ON UPDATE SET NULL",
+            ForeignKey {
+                columns: vec!["a".to_owned(), "b".to_owned()],
+                referenced_table_name: "Parent".to_owned(),
+                referenced_columns: Vec::new(),
+                on_delete: ReferentialAction::Cascade,
+                on_update: ReferentialAction::SetNull,
+            }
+            .to_sql()
+        );
     }
 }
@@ -1,7 +1,7 @@
 use {
     super::{
         Aggregate, AstLiteral, BinaryOperator, DataType, DateTimeField, Function, Query, ToSql,
-        UnaryOperator,
+        UnaryOperator, Window,
     },
     serde::{Deserialize, Serialize},
 };
@@ -52,17 +52,25 @@ pub enum Expr {
     },
     Nested(Box<Expr>),
     Literal(AstLiteral),
+    /// bind parameter, e.g. `$1`
+    Placeholder(String),
     TypedString {
         data_type: DataType,
         value: String,
     },
     Function(Box<Function>),
     Aggregate(Box<Aggregate>),
+    Window(Box<Window>),
     Exists {
         subquery: Box<Query>,
         negated: bool,
     },
-    Subquery(Box<Query>),
+    Subquery {
+        subquery: Box<Query>,
+        /// Set by the planner for the subquery which neither refers to the outer rows nor calls
+        /// a volatile function, it then runs once and its value is reused for each row
+        cached: bool,
+    },
     Case {
         operand: Option<Box<Expr>>,
         when_then: Vec<(Expr, Expr)>,
@@ -153,6 +161,7 @@ impl ToSql for Expr {
             },
             Expr::Nested(expr) => format!("({})", expr.to_sql()),
             Expr::Literal(s) => s.to_sql(),
+            Expr::Placeholder(name) => name.to_owned(),
             Expr::TypedString { data_type, value } => format!("{data_type} '{value}'"),
             Expr::Case {
                 operand,
@@ -178,6 +187,7 @@ impl ToSql for Expr {
                 [operand, when_then, else_result, "END".to_owned()].join("\n")
             }
             Expr::Aggregate(a) => a.to_sql(),
+            Expr::Window(w) => w.to_sql(),
             Expr::Function(func) => func.to_sql(),
             Expr::InSubquery {
                 expr,
@@ -200,7 +210,7 @@ impl ToSql for Expr {
                     .join("");
                 format!("{obj}{indexes}")
             }
-            Expr::Subquery(query) => format!("({})", query.to_sql()),
+            Expr::Subquery { subquery, .. } => format!("({})", subquery.to_sql()),
             Expr::Interval {
                 expr,
                 leading_field,
@@ -380,7 +390,9 @@ mod tests {
             Expr::InSubquery {
                 expr: Box::new(Expr::Identifier("id".to_owned())),
                 subquery: Box::new(Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
                         projection: vec![SelectItem::Wildcard],
                         from: TableWithJoins {
                             relation: TableFactor::Table {
@@ -408,7 +420,9 @@ mod tests {
             Expr::InSubquery {
                 expr: Box::new(Expr::Identifier("id".to_owned())),
                 subquery: Box::new(Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
                         projection: vec![SelectItem::Wildcard],
                         from: TableWithJoins {
                             relation: TableFactor::Table {
@@ -435,7 +449,9 @@ mod tests {
             "EXISTS(SELECT * FROM FOO)",
             Expr::Exists {
                 subquery: Box::new(Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
                         projection: vec![SelectItem::Wildcard],
                         from: TableWithJoins {
                             relation: TableFactor::Table {
@@ -462,7 +478,9 @@ mod tests {
             "NOT EXISTS(SELECT * FROM FOO)",
             Expr::Exists {
                 subquery: Box::new(Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
                         projection: vec![SelectItem::Wildcard],
                         from: TableWithJoins {
                             relation: TableFactor::Table {
@@ -487,25 +505,30 @@ mod tests {
 
         assert_eq!(
             "(SELECT * FROM FOO)",
-            Expr::Subquery(Box::new(Query {
-                body: SetExpr::Select(Box::new(Select {
-                    projection: vec![SelectItem::Wildcard],
-                    from: TableWithJoins {
-                        relation: TableFactor::Table {
-                            name: "FOO".to_owned(),
-                            alias: None,
-                            index: None,
+            Expr::Subquery {
+                subquery: Box::new(Query {
+                    with: None,
+                    body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
+                        projection: vec![SelectItem::Wildcard],
+                        from: TableWithJoins {
+                            relation: TableFactor::Table {
+                                name: "FOO".to_owned(),
+                                alias: None,
+                                index: None,
+                            },
+                            joins: Vec::new(),
                         },
-                        joins: Vec::new(),
-                    },
-                    selection: None,
-                    group_by: Vec::new(),
-                    having: None,
-                })),
-                order_by: Vec::new(),
-                limit: None,
-                offset: None,
-            }))
+                        selection: None,
+                        group_by: Vec::new(),
+                        having: None,
+                    })),
+                    order_by: Vec::new(),
+                    limit: None,
+                    offset: None,
+                }),
+                cached: false,
+            }
             .to_sql()
         );
 
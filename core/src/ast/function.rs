@@ -117,6 +117,8 @@ pub enum Function {
         selector: Expr,
     },
     GenerateUuid(),
+    Nextval(Expr),
+    Currval(Expr),
     Format {
         expr: Expr,
         format: Expr,
@@ -269,6 +271,8 @@ impl ToSql for Function {
                 format!("UNWRAP({}, {})", expr.to_sql(), selector.to_sql())
             }
             Function::GenerateUuid() => "GENERATE_UUID()".to_owned(),
+            Function::Nextval(e) => format!("NEXTVAL({})", e.to_sql()),
+            Function::Currval(e) => format!("CURRVAL({})", e.to_sql()),
             Function::Format { expr, format } => {
                 format!("FORMAT({}, {})", expr.to_sql(), format.to_sql())
             }
@@ -295,7 +299,13 @@ impl ToSql for Function {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Aggregate {
+pub struct Aggregate {
+    pub func: AggregateFunction,
+    pub distinct: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AggregateFunction {
     Count(CountArgExpr),
     Sum(Expr),
     Max(Expr),
@@ -305,16 +315,31 @@ pub enum Aggregate {
     Stdev(Expr),
 }
 
+impl Aggregate {
+    pub fn new(func: AggregateFunction, distinct: bool) -> Self {
+        Self { func, distinct }
+    }
+}
+
+impl From<AggregateFunction> for Aggregate {
+    fn from(func: AggregateFunction) -> Self {
+        Self::new(func, false)
+    }
+}
+
 impl ToSql for Aggregate {
     fn to_sql(&self) -> String {
-        match self {
-            Aggregate::Count(cae) => format!("COUNT({})", cae.to_sql()),
-            Aggregate::Sum(e) => format!("SUM({})", e.to_sql()),
-            Aggregate::Max(e) => format!("MAX({})", e.to_sql()),
-            Aggregate::Min(e) => format!("MIN({})", e.to_sql()),
-            Aggregate::Avg(e) => format!("AVG({})", e.to_sql()),
-            Aggregate::Variance(e) => format!("VARIANCE({})", e.to_sql()),
-            Aggregate::Stdev(e) => format!("STDEV({})", e.to_sql()),
+        let Aggregate { func, distinct } = self;
+        let distinct = if *distinct { "DISTINCT " } else { "" };
+
+        match func {
+            AggregateFunction::Count(cae) => format!("COUNT({distinct}{})", cae.to_sql()),
+            AggregateFunction::Sum(e) => format!("SUM({distinct}{})", e.to_sql()),
+            AggregateFunction::Max(e) => format!("MAX({distinct}{})", e.to_sql()),
+            AggregateFunction::Min(e) => format!("MIN({distinct}{})", e.to_sql()),
+            AggregateFunction::Avg(e) => format!("AVG({distinct}{})", e.to_sql()),
+            AggregateFunction::Variance(e) => format!("VARIANCE({distinct}{})", e.to_sql()),
+            AggregateFunction::Stdev(e) => format!("STDEV({distinct}{})", e.to_sql()),
         }
     }
 }
@@ -338,8 +363,8 @@ impl ToSql for CountArgExpr {
 mod tests {
     use {
         crate::ast::{
-            Aggregate, AstLiteral, CountArgExpr, DataType, DateTimeField, Expr, Function, ToSql,
-            TrimWhereField,
+            Aggregate, AggregateFunction, AstLiteral, CountArgExpr, DataType, DateTimeField, Expr,
+            Function, ToSql, TrimWhereField,
         },
         bigdecimal::BigDecimal,
         std::str::FromStr,
@@ -794,6 +819,22 @@ mod tests {
             &Expr::Function(Box::new(Function::GenerateUuid())).to_sql()
         );
 
+        assert_eq!(
+            "NEXTVAL('Foo_seq')",
+            &Expr::Function(Box::new(Function::Nextval(Expr::Literal(
+                AstLiteral::QuotedString("Foo_seq".to_owned())
+            ))))
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CURRVAL('Foo_seq')",
+            &Expr::Function(Box::new(Function::Currval(Expr::Literal(
+                AstLiteral::QuotedString("Foo_seq".to_owned())
+            ))))
+            .to_sql()
+        );
+
         assert_eq!(
             "FORMAT(DATE '2022-10-12', '%Y-%m')",
             &Expr::Function(Box::new(Function::Format {
@@ -870,45 +911,61 @@ mod tests {
 
     #[test]
     fn to_sql_aggregate() {
+        let aggregate = |func: AggregateFunction| Expr::Aggregate(Box::new(Aggregate::from(func)));
+
         assert_eq!(
             "MAX(id)",
-            Expr::Aggregate(Box::new(Aggregate::Max(Expr::Identifier("id".to_owned())))).to_sql()
+            aggregate(AggregateFunction::Max(Expr::Identifier("id".to_owned()))).to_sql()
         );
 
         assert_eq!(
             "COUNT(*)",
-            Expr::Aggregate(Box::new(Aggregate::Count(CountArgExpr::Wildcard))).to_sql()
+            aggregate(AggregateFunction::Count(CountArgExpr::Wildcard)).to_sql()
         );
 
         assert_eq!(
             "MIN(id)",
-            Expr::Aggregate(Box::new(Aggregate::Min(Expr::Identifier("id".to_owned())))).to_sql()
+            aggregate(AggregateFunction::Min(Expr::Identifier("id".to_owned()))).to_sql()
         );
 
         assert_eq!(
             "SUM(price)",
-            &Expr::Aggregate(Box::new(Aggregate::Sum(Expr::Identifier(
-                "price".to_owned()
-            ))))
-            .to_sql()
+            aggregate(AggregateFunction::Sum(Expr::Identifier("price".to_owned()))).to_sql()
         );
 
         assert_eq!(
             "AVG(pay)",
-            &Expr::Aggregate(Box::new(Aggregate::Avg(Expr::Identifier("pay".to_owned())))).to_sql()
+            aggregate(AggregateFunction::Avg(Expr::Identifier("pay".to_owned()))).to_sql()
         );
         assert_eq!(
             "VARIANCE(pay)",
-            &Expr::Aggregate(Box::new(Aggregate::Variance(Expr::Identifier(
+            aggregate(AggregateFunction::Variance(Expr::Identifier(
                 "pay".to_owned()
-            ))))
+            )))
             .to_sql()
         );
         assert_eq!(
             "STDEV(total)",
-            &Expr::Aggregate(Box::new(Aggregate::Stdev(Expr::Identifier(
+            aggregate(AggregateFunction::Stdev(Expr::Identifier(
                 "total".to_owned()
-            ))))
+            )))
+            .to_sql()
+        );
+
+        assert_eq!(
+            "COUNT(DISTINCT id)",
+            Expr::Aggregate(Box::new(Aggregate::new(
+                AggregateFunction::Count(CountArgExpr::Expr(Expr::Identifier("id".to_owned()))),
+                true
+            )))
+            .to_sql()
+        );
+        assert_eq!(
+            "SUM(DISTINCT price)",
+            Expr::Aggregate(Box::new(Aggregate::new(
+                AggregateFunction::Sum(Expr::Identifier("price".to_owned())),
+                true
+            )))
             .to_sql()
         );
     }
@@ -5,14 +5,16 @@ mod expr;
 mod function;
 mod operator;
 mod query;
+mod window;
 
 pub use ast_literal::{AstLiteral, DateTimeField, TrimWhereField};
 pub use data_type::DataType;
 pub use ddl::*;
 pub use expr::Expr;
-pub use function::{Aggregate, CountArgExpr, Function};
+pub use function::{Aggregate, AggregateFunction, CountArgExpr, Function};
 pub use operator::*;
 pub use query::*;
+pub use window::*;
 
 use serde::{Deserialize, Serialize};
 
@@ -33,24 +35,42 @@ pub enum Statement {
         table_name: String,
         /// COLUMNS
         columns: Vec<String>,
+        /// `OVERRIDING SYSTEM VALUE`, keeps the values given to `GENERATED ALWAYS` identity columns
+        overriding_system_value: bool,
         /// A SQL query that specifies what to insert
         source: Query,
+        /// `ON CONFLICT` clause
+        on_conflict: Option<OnConflict>,
+        /// RETURNING
+        returning: Vec<SelectItem>,
     },
     /// UPDATE
     Update {
         /// TABLE
         table_name: String,
+        /// Alias of the target table, by which the other clauses refer to it
+        alias: Option<String>,
         /// Column assignments
         assignments: Vec<Assignment>,
+        /// Tables joined to the target table, by `JOIN` or `FROM`
+        joins: Vec<Join>,
         /// WHERE
         selection: Option<Expr>,
+        /// RETURNING
+        returning: Vec<SelectItem>,
     },
     /// DELETE
     Delete {
         /// FROM
         table_name: String,
+        /// Alias of the target table, by which the other clauses refer to it
+        alias: Option<String>,
+        /// Tables joined to the target table, by `USING`
+        joins: Vec<Join>,
         /// WHERE
         selection: Option<Expr>,
+        /// RETURNING
+        returning: Vec<SelectItem>,
     },
     /// CREATE TABLE
     CreateTable {
@@ -59,6 +79,11 @@ pub enum Statement {
         name: String,
         /// Optional schema
         columns: Vec<ColumnDef>,
+        /// Multi-column `PRIMARY KEY (..)` table constraint
+        primary_key: Option<Vec<String>>,
+        foreign_keys: Vec<ForeignKey>,
+        /// `CHECK (..)` table constraints
+        checks: Vec<Expr>,
         source: Option<Box<Query>>,
     },
     /// ALTER TABLE
@@ -68,6 +93,43 @@ pub enum Statement {
         name: String,
         operation: AlterTableOperation,
     },
+    /// CREATE SCHEMA
+    CreateSchema {
+        if_not_exists: bool,
+        name: String,
+    },
+    /// DROP SCHEMA
+    DropSchema {
+        if_exists: bool,
+        names: Vec<String>,
+        /// Drops the tables of the schemas as well
+        cascade: bool,
+    },
+    /// CREATE SEQUENCE
+    CreateSequence {
+        if_not_exists: bool,
+        name: String,
+        increment: Option<Expr>,
+        start: Option<Expr>,
+    },
+    /// DROP SEQUENCE
+    DropSequence {
+        if_exists: bool,
+        names: Vec<String>,
+    },
+    /// CREATE VIEW
+    CreateView {
+        or_replace: bool,
+        name: String,
+        /// Column aliases
+        columns: Vec<String>,
+        query: Box<Query>,
+    },
+    /// DROP VIEW
+    DropView {
+        if_exists: bool,
+        names: Vec<String>,
+    },
     /// DROP TABLE
     DropTable {
         /// An optional `IF EXISTS` clause. (Non-standard.)
@@ -80,7 +142,7 @@ pub enum Statement {
     CreateIndex {
         name: String,
         table_name: String,
-        column: OrderByExpr,
+        columns: Vec<OrderByExpr>,
     },
     /// DROP INDEX
     #[cfg(feature = "index")]
@@ -101,6 +163,16 @@ pub enum Statement {
     ShowVariable(Variable),
     #[cfg(feature = "index")]
     ShowIndexes(String),
+    /// ANALYZE TABLE
+    Analyze {
+        table_name: String,
+    },
+    /// EXPLAIN [ANALYZE]
+    Explain {
+        /// Runs the statement and reports the rows and the time of each operator
+        analyze: bool,
+        statement: Box<Statement>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -109,6 +181,25 @@ pub struct Assignment {
     pub value: Expr,
 }
 
+/// `ON CONFLICT [ (<columns>) ] <action>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OnConflict {
+    /// Conflict target, empty to resolve against every PRIMARY KEY and UNIQUE column
+    pub columns: Vec<String>,
+    pub action: OnConflictAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OnConflictAction {
+    /// `DO NOTHING`
+    DoNothing,
+    /// `DO UPDATE SET <assignments> [ WHERE <expr> ]`
+    DoUpdate {
+        assignments: Vec<Assignment>,
+        selection: Option<Expr>,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Variable {
     Tables,
@@ -124,46 +215,96 @@ impl ToSql for Statement {
             Statement::Insert {
                 table_name,
                 columns,
+                overriding_system_value,
                 source,
+                on_conflict,
+                returning,
             } => {
                 let columns = match columns.is_empty() {
                     true => "".to_owned(),
                     false => format!("({}) ", columns.join(", ")),
                 };
+                let overriding = match overriding_system_value {
+                    true => "OVERRIDING SYSTEM VALUE ",
+                    false => "",
+                };
+                let on_conflict = match on_conflict {
+                    Some(on_conflict) => format!(" {}", on_conflict.to_sql()),
+                    None => "".to_owned(),
+                };
+                let returning = returning_to_sql(returning);
 
-                format!("INSERT INTO {table_name} {columns}{};", source.to_sql())
+                format!(
+                    "INSERT INTO {table_name} {columns}{overriding}{}{on_conflict}{returning};",
+                    source.to_sql()
+                )
             }
             Statement::Update {
                 table_name,
+                alias,
                 assignments,
+                joins,
                 selection,
+                returning,
             } => {
+                let table_name = target_to_sql(table_name, alias);
                 let assignments = assignments
                     .iter()
                     .map(ToSql::to_sql)
                     .collect::<Vec<_>>()
                     .join(", ");
+                let joins = joins
+                    .iter()
+                    .map(|join| format!(" {}", join.to_sql()))
+                    .collect::<String>();
+                let returning = returning_to_sql(returning);
                 match selection {
                     Some(expr) => {
                         format!(
-                            "UPDATE {table_name} SET {assignments} WHERE {};",
+                            "UPDATE {table_name}{joins} SET {assignments} WHERE {}{returning};",
                             expr.to_sql()
                         )
                     }
-                    None => format!("UPDATE {table_name} SET {assignments};"),
+                    None => format!("UPDATE {table_name}{joins} SET {assignments}{returning};"),
                 }
             }
             Statement::Delete {
                 table_name,
+                alias,
+                joins,
                 selection,
-            } => match selection {
-                Some(expr) => format!("DELETE FROM {table_name} WHERE {};", expr.to_sql()),
-                None => format!("DELETE FROM {table_name};"),
-            },
+                returning,
+            } => {
+                let table_name = target_to_sql(table_name, alias);
+                let using = match joins.split_first() {
+                    Some((Join { relation, .. }, joins)) => {
+                        let joins = joins
+                            .iter()
+                            .map(|join| format!(" {}", join.to_sql()))
+                            .collect::<String>();
+
+                        format!(" USING {}{joins}", relation.to_sql())
+                    }
+                    None => "".to_owned(),
+                };
+                let returning = returning_to_sql(returning);
+                match selection {
+                    Some(expr) => {
+                        format!(
+                            "DELETE FROM {table_name}{using} WHERE {}{returning};",
+                            expr.to_sql()
+                        )
+                    }
+                    None => format!("DELETE FROM {table_name}{using}{returning};"),
+                }
+            }
             Statement::CreateTable {
                 if_not_exists,
                 name,
                 columns,
+                primary_key,
+                foreign_keys,
+                checks,
                 source,
             } => match source {
                 Some(query) => match if_not_exists {
@@ -171,9 +312,19 @@ impl ToSql for Statement {
                     false => format!("CREATE TABLE {name} AS {};", query.to_sql()),
                 },
                 None => {
+                    let primary_key = primary_key
+                        .as_ref()
+                        .map(|primary_key| format!("PRIMARY KEY ({})", primary_key.join(", ")));
                     let columns = columns
                         .iter()
                         .map(ToSql::to_sql)
+                        .chain(primary_key)
+                        .chain(foreign_keys.iter().map(ToSql::to_sql))
+                        .chain(
+                            checks
+                                .iter()
+                                .map(|check| format!("CHECK ({})", check.to_sql())),
+                        )
                         .collect::<Vec<_>>()
                         .join(", ");
                     match if_not_exists {
@@ -186,6 +337,83 @@ impl ToSql for Statement {
             Statement::AlterTable { name, operation } => {
                 format!("ALTER TABLE {name} {};", operation.to_sql())
             }
+            Statement::CreateSchema {
+                if_not_exists,
+                name,
+            } => match if_not_exists {
+                true => format!("CREATE SCHEMA IF NOT EXISTS {name};"),
+                false => format!("CREATE SCHEMA {name};"),
+            },
+            Statement::DropSchema {
+                if_exists,
+                names,
+                cascade,
+            } => {
+                let names = names.join(", ");
+                let cascade = match cascade {
+                    true => " CASCADE",
+                    false => "",
+                };
+                match if_exists {
+                    true => format!("DROP SCHEMA IF EXISTS {names}{cascade};"),
+                    false => format!("DROP SCHEMA {names}{cascade};"),
+                }
+            }
+            Statement::CreateSequence {
+                if_not_exists,
+                name,
+                increment,
+                start,
+            } => {
+                let if_not_exists = match if_not_exists {
+                    true => " IF NOT EXISTS",
+                    false => "",
+                };
+                let increment = match increment {
+                    Some(increment) => format!(" INCREMENT BY {}", increment.to_sql()),
+                    None => String::new(),
+                };
+                let start = match start {
+                    Some(start) => format!(" START WITH {}", start.to_sql()),
+                    None => String::new(),
+                };
+
+                format!("CREATE SEQUENCE{if_not_exists} {name}{increment}{start};")
+            }
+            Statement::DropSequence { if_exists, names } => {
+                let names = names.join(", ");
+                match if_exists {
+                    true => format!("DROP SEQUENCE IF EXISTS {names};"),
+                    false => format!("DROP SEQUENCE {names};"),
+                }
+            }
+            Statement::CreateView {
+                or_replace,
+                name,
+                columns,
+                query,
+            } => {
+                let or_replace = match or_replace {
+                    true => " OR REPLACE",
+                    false => "",
+                };
+                let columns = match columns.is_empty() {
+                    true => String::new(),
+                    false => format!(" ({})", columns.join(", ")),
+                };
+
+                format!(
+                    "CREATE{or_replace} VIEW {name}{columns} AS {};",
+                    query.to_sql()
+                )
+            }
+            Statement::DropView { if_exists, names } => {
+                let names = names.join(", ");
+                match if_exists {
+                    true => format!("DROP VIEW IF EXISTS {names};"),
+                    false => format!("DROP VIEW {names};"),
+                }
+            }
             Statement::DropTable { if_exists, names } => {
                 let names = names.join(", ");
                 match if_exists {
@@ -197,9 +425,15 @@ impl ToSql for Statement {
             Statement::CreateIndex {
                 name,
                 table_name,
-                column,
+                columns,
             } => {
-                format!("CREATE INDEX {name} ON {table_name} {};", column.to_sql())
+                let columns = columns
+                    .iter()
+                    .map(ToSql::to_sql)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("CREATE INDEX {name} ON {table_name} ({columns});")
             }
             #[cfg(feature = "index")]
             Statement::DropIndex { name, table_name } => {
@@ -219,6 +453,12 @@ impl ToSql for Statement {
             Statement::ShowIndexes(object_name) => {
                 format!("SHOW INDEXES FROM {object_name};")
             }
+            Statement::Analyze { table_name } => format!("ANALYZE TABLE {table_name};"),
+            Statement::Explain { analyze, statement } => {
+                let analyze = if *analyze { "ANALYZE " } else { "" };
+
+                format!("EXPLAIN {analyze}{}", statement.to_sql())
+            }
             _ => "(..statement..)".to_owned(),
         }
     }
@@ -230,6 +470,60 @@ impl ToSql for Assignment {
     }
 }
 
+fn target_to_sql(table_name: &str, alias: &Option<String>) -> String {
+    match alias {
+        Some(alias) => format!("{table_name} AS {alias}"),
+        None => table_name.to_owned(),
+    }
+}
+
+fn returning_to_sql(returning: &[SelectItem]) -> String {
+    match returning.is_empty() {
+        true => "".to_owned(),
+        false => {
+            let items = returning
+                .iter()
+                .map(ToSql::to_sql)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(" RETURNING {items}")
+        }
+    }
+}
+
+impl ToSql for OnConflict {
+    fn to_sql(&self) -> String {
+        let OnConflict { columns, action } = self;
+        let columns = match columns.is_empty() {
+            true => "".to_owned(),
+            false => format!("({}) ", columns.join(", ")),
+        };
+
+        match action {
+            OnConflictAction::DoNothing => format!("ON CONFLICT {columns}DO NOTHING"),
+            OnConflictAction::DoUpdate {
+                assignments,
+                selection,
+            } => {
+                let assignments = assignments
+                    .iter()
+                    .map(ToSql::to_sql)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                match selection {
+                    Some(expr) => format!(
+                        "ON CONFLICT {columns}DO UPDATE SET {assignments} WHERE {}",
+                        expr.to_sql()
+                    ),
+                    None => format!("ON CONFLICT {columns}DO UPDATE SET {assignments}"),
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "alter-table")]
@@ -240,9 +534,10 @@ mod tests {
 
     use {
         crate::ast::{
-            Assignment, AstLiteral, BinaryOperator, ColumnDef, ColumnOption, DataType, Expr, Query,
-            Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, ToSql, Values,
-            Variable,
+            Assignment, AstLiteral, BinaryOperator, ColumnDef, ColumnOption, DataType, Expr,
+            ForeignKey, Join, JoinConstraint, JoinExecutor, JoinOperator, OnConflict,
+            OnConflictAction, Query, ReferentialAction, Select, SelectItem, SetExpr, Statement,
+            TableFactor, TableWithJoins, ToSql, UnaryOperator, Values, Variable,
         },
         bigdecimal::BigDecimal,
         std::str::FromStr,
@@ -266,7 +561,9 @@ mod tests {
             Statement::Insert {
                 table_name: "Test".into(),
                 columns: vec!["id".to_owned(), "num".to_owned(), "name".to_owned()],
+                overriding_system_value: false,
                 source: Query {
+                    with: None,
                     body: SetExpr::Values(Values(vec![vec![
                         Expr::Literal(AstLiteral::Number(BigDecimal::from_str("1").unwrap())),
                         Expr::Literal(AstLiteral::Number(BigDecimal::from_str("2").unwrap())),
@@ -275,7 +572,93 @@ mod tests {
                     order_by: vec![],
                     limit: None,
                     offset: None
-                }
+                },
+                on_conflict: None,
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "INSERT INTO Test VALUES (1) ON CONFLICT (id) DO NOTHING;",
+            Statement::Insert {
+                table_name: "Test".into(),
+                columns: Vec::new(),
+                overriding_system_value: false,
+                source: Query {
+                    with: None,
+                    body: SetExpr::Values(Values(vec![vec![Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("1").unwrap()
+                    ))]])),
+                    order_by: vec![],
+                    limit: None,
+                    offset: None
+                },
+                on_conflict: Some(OnConflict {
+                    columns: vec!["id".to_owned()],
+                    action: OnConflictAction::DoNothing
+                }),
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "INSERT INTO Test VALUES (1) ON CONFLICT DO UPDATE SET num = EXCLUDED.num WHERE num < 10;",
+            Statement::Insert {
+                table_name: "Test".into(),
+                columns: Vec::new(),
+                overriding_system_value: false,
+                source: Query {
+                    with: None,
+                    body: SetExpr::Values(Values(vec![vec![Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("1").unwrap()
+                    ))]])),
+                    order_by: vec![],
+                    limit: None,
+                    offset: None
+                },
+                on_conflict: Some(OnConflict {
+                    columns: Vec::new(),
+                    action: OnConflictAction::DoUpdate {
+                        assignments: vec![Assignment {
+                            id: "num".to_owned(),
+                            value: Expr::CompoundIdentifier {
+                                alias: "EXCLUDED".to_owned(),
+                                ident: "num".to_owned()
+                            }
+                        }],
+                        selection: Some(Expr::BinaryOp {
+                            left: Box::new(Expr::Identifier("num".to_owned())),
+                            op: BinaryOperator::Lt,
+                            right: Box::new(Expr::Literal(AstLiteral::Number(
+                                BigDecimal::from_str("10").unwrap()
+                            )))
+                        })
+                    }
+                }),
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "INSERT INTO Test (id) OVERRIDING SYSTEM VALUE VALUES (1);",
+            Statement::Insert {
+                table_name: "Test".into(),
+                columns: vec!["id".to_owned()],
+                overriding_system_value: true,
+                source: Query {
+                    with: None,
+                    body: SetExpr::Values(Values(vec![vec![Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("1").unwrap()
+                    ))]])),
+                    order_by: vec![],
+                    limit: None,
+                    offset: None
+                },
+                on_conflict: None,
+                returning: Vec::new()
             }
             .to_sql()
         );
@@ -287,6 +670,8 @@ mod tests {
             "UPDATE Foo SET id = 4, color = 'blue';",
             Statement::Update {
                 table_name: "Foo".into(),
+                alias: None,
+                joins: Vec::new(),
                 assignments: vec![
                     Assignment {
                         id: "id".to_owned(),
@@ -299,7 +684,8 @@ mod tests {
                         value: Expr::Literal(AstLiteral::QuotedString("blue".to_owned()))
                     }
                 ],
-                selection: None
+                selection: None,
+                returning: Vec::new()
             }
             .to_sql()
         );
@@ -308,6 +694,8 @@ mod tests {
             "UPDATE Foo SET name = 'first' WHERE a > b;",
             Statement::Update {
                 table_name: "Foo".into(),
+                alias: None,
+                joins: Vec::new(),
                 assignments: vec![Assignment {
                     id: "name".to_owned(),
                     value: Expr::Literal(AstLiteral::QuotedString("first".to_owned()))
@@ -316,10 +704,73 @@ mod tests {
                     left: Box::new(Expr::Identifier("a".to_owned())),
                     op: BinaryOperator::Gt,
                     right: Box::new(Expr::Identifier("b".to_owned()))
-                })
+                }),
+                returning: Vec::new()
             }
             .to_sql()
-        )
+        );
+
+        assert_eq!(
+            "UPDATE Foo INNER JOIN Bar ON Foo.id = Bar.id SET name = Bar.name;",
+            Statement::Update {
+                table_name: "Foo".into(),
+                alias: None,
+                joins: vec![Join {
+                    relation: TableFactor::Table {
+                        name: "Bar".to_owned(),
+                        alias: None,
+                        index: None,
+                    },
+                    join_operator: JoinOperator::Inner(JoinConstraint::On(Expr::BinaryOp {
+                        left: Box::new(Expr::CompoundIdentifier {
+                            alias: "Foo".to_owned(),
+                            ident: "id".to_owned()
+                        }),
+                        op: BinaryOperator::Eq,
+                        right: Box::new(Expr::CompoundIdentifier {
+                            alias: "Bar".to_owned(),
+                            ident: "id".to_owned()
+                        })
+                    })),
+                    join_executor: JoinExecutor::NestedLoop,
+                }],
+                assignments: vec![Assignment {
+                    id: "name".to_owned(),
+                    value: Expr::CompoundIdentifier {
+                        alias: "Bar".to_owned(),
+                        ident: "name".to_owned()
+                    }
+                }],
+                selection: None,
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "UPDATE Foo AS f SET name = 'first' WHERE f.id = 1;",
+            Statement::Update {
+                table_name: "Foo".into(),
+                alias: Some("f".to_owned()),
+                joins: Vec::new(),
+                assignments: vec![Assignment {
+                    id: "name".to_owned(),
+                    value: Expr::Literal(AstLiteral::QuotedString("first".to_owned()))
+                }],
+                selection: Some(Expr::BinaryOp {
+                    left: Box::new(Expr::CompoundIdentifier {
+                        alias: "f".to_owned(),
+                        ident: "id".to_owned()
+                    }),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("1").unwrap()
+                    )))
+                }),
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
     }
 
     #[test]
@@ -328,7 +779,10 @@ mod tests {
             "DELETE FROM Foo;",
             Statement::Delete {
                 table_name: "Foo".into(),
-                selection: None
+                alias: None,
+                joins: Vec::new(),
+                selection: None,
+                returning: Vec::new()
             }
             .to_sql()
         );
@@ -337,11 +791,68 @@ mod tests {
             "DELETE FROM Foo WHERE item = 'glue';",
             Statement::Delete {
                 table_name: "Foo".into(),
+                alias: None,
+                joins: Vec::new(),
                 selection: Some(Expr::BinaryOp {
                     left: Box::new(Expr::Identifier("item".to_owned())),
                     op: BinaryOperator::Eq,
                     right: Box::new(Expr::Literal(AstLiteral::QuotedString("glue".to_owned())))
-                })
+                }),
+                returning: Vec::new()
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DELETE FROM Foo WHERE id = 1 RETURNING *, name AS label;",
+            Statement::Delete {
+                table_name: "Foo".into(),
+                alias: None,
+                joins: Vec::new(),
+                selection: Some(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("id".to_owned())),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("1").unwrap()
+                    )))
+                }),
+                returning: vec![
+                    SelectItem::Wildcard,
+                    SelectItem::Expr {
+                        expr: Expr::Identifier("name".to_owned()),
+                        label: "label".to_owned()
+                    }
+                ]
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DELETE FROM Foo USING Bar WHERE Foo.id = Bar.id;",
+            Statement::Delete {
+                table_name: "Foo".into(),
+                alias: None,
+                joins: vec![Join {
+                    relation: TableFactor::Table {
+                        name: "Bar".to_owned(),
+                        alias: None,
+                        index: None,
+                    },
+                    join_operator: JoinOperator::Cross,
+                    join_executor: JoinExecutor::NestedLoop,
+                }],
+                selection: Some(Expr::BinaryOp {
+                    left: Box::new(Expr::CompoundIdentifier {
+                        alias: "Foo".to_owned(),
+                        ident: "id".to_owned()
+                    }),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(Expr::CompoundIdentifier {
+                        alias: "Bar".to_owned(),
+                        ident: "id".to_owned()
+                    })
+                }),
+                returning: Vec::new()
             }
             .to_sql()
         );
@@ -355,6 +866,9 @@ mod tests {
                 if_not_exists: true,
                 name: "Foo".into(),
                 columns: vec![],
+                primary_key: None,
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
                 source: None
             }
             .to_sql()
@@ -385,6 +899,73 @@ mod tests {
                         options: vec![]
                     }
                 ],
+                primary_key: None,
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
+                source: None
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CREATE TABLE Foo (id INT NOT NULL, name TEXT NOT NULL, PRIMARY KEY (id, name));",
+            Statement::CreateTable {
+                if_not_exists: false,
+                name: "Foo".into(),
+                columns: vec![
+                    ColumnDef {
+                        name: "id".to_owned(),
+                        data_type: DataType::Int,
+                        nullable: false,
+                        options: vec![]
+                    },
+                    ColumnDef {
+                        name: "name".to_owned(),
+                        data_type: DataType::Text,
+                        nullable: false,
+                        options: vec![]
+                    }
+                ],
+                primary_key: Some(vec!["id".to_owned(), "name".to_owned()]),
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
+                source: None
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CREATE TABLE Child (id INT NOT NULL, parent_id INT NULL, FOREIGN KEY (parent_id) REFERENCES Parent (id) ON DELETE CASCADE, CHECK (parent_id <> id));",
+            Statement::CreateTable {
+                if_not_exists: false,
+                name: "Child".into(),
+                columns: vec![
+                    ColumnDef {
+                        name: "id".to_owned(),
+                        data_type: DataType::Int,
+                        nullable: false,
+                        options: vec![]
+                    },
+                    ColumnDef {
+                        name: "parent_id".to_owned(),
+                        data_type: DataType::Int,
+                        nullable: true,
+                        options: vec![]
+                    }
+                ],
+                primary_key: None,
+                foreign_keys: vec![ForeignKey {
+                    columns: vec!["parent_id".to_owned()],
+                    referenced_table_name: "Parent".to_owned(),
+                    referenced_columns: vec!["id".to_owned()],
+                    on_delete: ReferentialAction::Cascade,
+                    on_update: ReferentialAction::NoAction,
+                }],
+                checks: vec![Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("parent_id".to_owned())),
+                    op: BinaryOperator::NotEq,
+                    right: Box::new(Expr::Identifier("id".to_owned())),
+                }],
                 source: None
             }
             .to_sql()
@@ -399,8 +980,13 @@ mod tests {
                 if_not_exists: false,
                 name: "Foo".into(),
                 columns: vec![],
+                primary_key: None,
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
                 source: Some(Box::new(Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
                         projection: vec![
                             SelectItem::Expr {
                                 expr: Expr::Identifier("id".to_owned()),
@@ -437,7 +1023,11 @@ mod tests {
                 if_not_exists: true,
                 name: "Foo".into(),
                 columns: vec![],
+                primary_key: None,
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
                 source: Some(Box::new(Query {
+                    with: None,
                     body: SetExpr::Values(Values(vec![vec![Expr::Literal(AstLiteral::Boolean(
                         true
                     ))]])),
@@ -549,18 +1139,165 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_sql_schema() {
+        assert_eq!(
+            "CREATE SCHEMA analytics;",
+            Statement::CreateSchema {
+                if_not_exists: false,
+                name: "analytics".to_owned(),
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CREATE SCHEMA IF NOT EXISTS analytics;",
+            Statement::CreateSchema {
+                if_not_exists: true,
+                name: "analytics".to_owned(),
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DROP SCHEMA analytics, billing CASCADE;",
+            Statement::DropSchema {
+                if_exists: false,
+                names: vec!["analytics".to_owned(), "billing".to_owned()],
+                cascade: true,
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DROP SCHEMA IF EXISTS analytics;",
+            Statement::DropSchema {
+                if_exists: true,
+                names: vec!["analytics".to_owned()],
+                cascade: false,
+            }
+            .to_sql()
+        );
+    }
+
+    #[test]
+    fn to_sql_sequence() {
+        assert_eq!(
+            "CREATE SEQUENCE Foo;",
+            Statement::CreateSequence {
+                if_not_exists: false,
+                name: "Foo".to_owned(),
+                increment: None,
+                start: None,
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CREATE SEQUENCE IF NOT EXISTS Foo INCREMENT BY -2 START WITH 100;",
+            Statement::CreateSequence {
+                if_not_exists: true,
+                name: "Foo".to_owned(),
+                increment: Some(Expr::UnaryOp {
+                    op: UnaryOperator::Minus,
+                    expr: Box::new(Expr::Literal(AstLiteral::Number(
+                        BigDecimal::from_str("2").unwrap()
+                    ))),
+                }),
+                start: Some(Expr::Literal(AstLiteral::Number(
+                    BigDecimal::from_str("100").unwrap()
+                ))),
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DROP SEQUENCE IF EXISTS Foo, Bar;",
+            Statement::DropSequence {
+                if_exists: true,
+                names: vec!["Foo".to_owned(), "Bar".to_owned()],
+            }
+            .to_sql()
+        );
+    }
+
+    #[test]
+    fn to_sql_view() {
+        assert_eq!(
+            "CREATE OR REPLACE VIEW Foo (a) AS SELECT id FROM Bar;",
+            Statement::CreateView {
+                or_replace: true,
+                name: "Foo".to_owned(),
+                columns: vec!["a".to_owned()],
+                query: Box::new(Query {
+                    with: None,
+                    body: SetExpr::Select(Box::new(Select {
+                        distinct: false,
+                        projection: vec![SelectItem::Expr {
+                            expr: Expr::Identifier("id".to_owned()),
+                            label: "".to_owned()
+                        }],
+                        from: TableWithJoins {
+                            relation: TableFactor::Table {
+                                name: "Bar".to_owned(),
+                                alias: None,
+                                index: None
+                            },
+                            joins: vec![]
+                        },
+                        selection: None,
+                        group_by: vec![],
+                        having: None
+                    })),
+                    order_by: vec![],
+                    limit: None,
+                    offset: None
+                })
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "DROP VIEW Foo, Bar;",
+            Statement::DropView {
+                if_exists: false,
+                names: vec!["Foo".to_owned(), "Bar".to_owned()],
+            }
+            .to_sql()
+        );
+    }
+
     #[test]
     #[cfg(feature = "index")]
     fn to_sql_create_index() {
         assert_eq!(
-            "CREATE INDEX idx_name ON Test LastName;",
+            "CREATE INDEX idx_name ON Test (LastName);",
             Statement::CreateIndex {
                 name: "idx_name".into(),
                 table_name: "Test".into(),
-                column: OrderByExpr {
+                columns: vec![OrderByExpr {
                     expr: Expr::Identifier("LastName".to_owned()),
                     asc: None
-                }
+                }]
+            }
+            .to_sql()
+        );
+
+        assert_eq!(
+            "CREATE INDEX idx_tenant ON Test (tenant_id, created_at DESC);",
+            Statement::CreateIndex {
+                name: "idx_tenant".into(),
+                table_name: "Test".into(),
+                columns: vec![
+                    OrderByExpr {
+                        expr: Expr::Identifier("tenant_id".to_owned()),
+                        asc: None
+                    },
+                    OrderByExpr {
+                        expr: Expr::Identifier("created_at".to_owned()),
+                        asc: Some(false)
+                    }
+                ]
             }
             .to_sql()
         );
@@ -608,6 +1345,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_sql_analyze() {
+        assert_eq!(
+            "ANALYZE TABLE Foo;",
+            Statement::Analyze {
+                table_name: "Foo".into(),
+            }
+            .to_sql()
+        );
+    }
+
+    #[test]
+    fn to_sql_explain() {
+        let statement = Statement::Delete {
+            table_name: "Foo".into(),
+            alias: None,
+            joins: Vec::new(),
+            selection: None,
+            returning: Vec::new(),
+        };
+
+        assert_eq!(
+            "EXPLAIN DELETE FROM Foo;",
+            Statement::Explain {
+                analyze: false,
+                statement: Box::new(statement.clone()),
+            }
+            .to_sql()
+        );
+        assert_eq!(
+            "EXPLAIN ANALYZE DELETE FROM Foo;",
+            Statement::Explain {
+                analyze: true,
+                statement: Box::new(statement),
+            }
+            .to_sql()
+        );
+    }
+
     #[test]
     fn to_sql_assignment() {
         assert_eq!(
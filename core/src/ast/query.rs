@@ -8,20 +8,49 @@ use {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Query {
+    pub with: Option<With>,
     pub body: SetExpr,
     pub order_by: Vec<OrderByExpr>,
     pub limit: Option<Expr>,
     pub offset: Option<Expr>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct With {
+    pub recursive: bool,
+    pub cte_tables: Vec<Cte>,
+}
+
+/// A single common table expression, `alias [(columns)] AS (query)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Cte {
+    pub alias: TableAlias,
+    pub query: Query,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SetExpr {
     Select(Box<Select>),
     Values(Values),
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<SetExpr>,
+        right: Box<SetExpr>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum SetOperator {
+    Union,
+    Except,
+    Intersect,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Select {
+    pub distinct: bool,
     pub projection: Vec<SelectItem>,
     pub from: TableWithJoins,
     /// WHERE
@@ -48,10 +77,13 @@ pub struct TableWithJoins {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IndexItem {
-    PrimaryKey(Expr),
+    /// Values of the PRIMARY KEY columns, in the order of the key
+    PrimaryKey(Vec<Expr>),
     NonClustered {
         name: String,
         asc: Option<bool>,
+        /// Equality values for the leading columns of a composite index
+        prefix_exprs: Vec<Expr>,
         cmp_expr: Option<(IndexOperator, Expr)>,
     },
 }
@@ -114,11 +146,20 @@ pub enum JoinExecutor {
 pub enum JoinOperator {
     Inner(JoinConstraint),
     LeftOuter(JoinConstraint),
+    RightOuter(JoinConstraint),
+    FullOuter(JoinConstraint),
+    Cross,
+    /// Keeps the left rows which have a match, planned from `IN` and `EXISTS` subqueries
+    Semi(JoinConstraint),
+    /// Keeps the left rows which have no match, planned from `NOT EXISTS` subqueries
+    Anti(JoinConstraint),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum JoinConstraint {
     On(Expr),
+    Using(Vec<String>),
+    Natural,
     None,
 }
 
@@ -134,6 +175,7 @@ pub struct Values(pub Vec<Vec<Expr>>);
 impl ToSql for Query {
     fn to_sql(&self) -> String {
         let Query {
+            with,
             body,
             order_by,
             limit,
@@ -164,10 +206,43 @@ impl ToSql for Query {
             .filter(|sql| !sql.is_empty())
             .join(" ");
 
+        let body = match with {
+            Some(with) => format!("{} {}", with.to_sql(), body.to_sql()),
+            None => body.to_sql(),
+        };
+
         if string.is_empty() {
-            body.to_sql()
+            body
         } else {
-            format!("{} {}", body.to_sql(), string)
+            format!("{body} {string}")
+        }
+    }
+}
+
+impl ToSql for With {
+    fn to_sql(&self) -> String {
+        let With {
+            recursive,
+            cte_tables,
+        } = self;
+        let recursive = if *recursive { "RECURSIVE " } else { "" };
+        let cte_tables = cte_tables.iter().map(ToSql::to_sql).join(", ");
+
+        format!("WITH {recursive}{cte_tables}")
+    }
+}
+
+impl ToSql for Cte {
+    fn to_sql(&self) -> String {
+        let Cte {
+            alias: TableAlias { name, columns },
+            query,
+        } = self;
+
+        if columns.is_empty() {
+            format!("{name} AS ({})", query.to_sql())
+        } else {
+            format!("{name}({}) AS ({})", columns.join(", "), query.to_sql())
         }
     }
 }
@@ -177,6 +252,20 @@ impl ToSql for SetExpr {
         match self {
             SetExpr::Select(expr) => expr.to_sql(),
             SetExpr::Values(value) => format!("VALUES {}", value.to_sql()),
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let all = if *all { " ALL" } else { "" };
+                let operand = |set_expr: &SetExpr| match set_expr {
+                    SetExpr::SetOperation { .. } => format!("({})", set_expr.to_sql()),
+                    _ => set_expr.to_sql(),
+                };
+
+                format!("{} {op}{all} {}", operand(left), operand(right))
+            }
         }
     }
 }
@@ -184,12 +273,14 @@ impl ToSql for SetExpr {
 impl ToSql for Select {
     fn to_sql(&self) -> String {
         let Select {
+            distinct,
             projection,
             from,
             selection,
             group_by,
             having,
         } = self;
+        let distinct = if *distinct { "DISTINCT " } else { "" };
         let projection = projection.iter().map(|item| item.to_sql()).join(", ");
 
         let selection = match selection {
@@ -217,9 +308,12 @@ impl ToSql for Select {
             .join(" ");
 
         if condition.is_empty() {
-            format!("SELECT {projection} FROM {}", from.to_sql())
+            format!("SELECT {distinct}{projection} FROM {}", from.to_sql())
         } else {
-            format!("SELECT {projection} FROM {} {condition}", from.to_sql())
+            format!(
+                "SELECT {distinct}{projection} FROM {} {condition}",
+                from.to_sql()
+            )
         }
     }
 }
@@ -292,27 +386,30 @@ impl ToSql for Join {
             join_executor,
         } = self;
 
-        match join_operator {
-            JoinOperator::Inner(constraint) => {
-                let constraint = vec![constraint.to_sql(), join_executor.to_sql()]
-                    .iter()
-                    .filter(|sql| !sql.is_empty())
-                    .join(" AND ");
-                if constraint.is_empty() {
-                    format!("INNER JOIN {}", relation.to_sql())
-                } else {
-                    format!("INNER JOIN {} ON {constraint}", relation.to_sql())
-                }
+        let (operator, constraint) = match join_operator {
+            JoinOperator::Inner(constraint) => ("INNER JOIN", constraint),
+            JoinOperator::LeftOuter(constraint) => ("LEFT OUTER JOIN", constraint),
+            JoinOperator::RightOuter(constraint) => ("RIGHT OUTER JOIN", constraint),
+            JoinOperator::FullOuter(constraint) => ("FULL OUTER JOIN", constraint),
+            JoinOperator::Cross => return format!("CROSS JOIN {}", relation.to_sql()),
+            JoinOperator::Semi(constraint) => ("SEMI JOIN", constraint),
+            JoinOperator::Anti(constraint) => ("ANTI JOIN", constraint),
+        };
+
+        match constraint {
+            JoinConstraint::Natural => format!("NATURAL {operator} {}", relation.to_sql()),
+            JoinConstraint::Using(_) => {
+                format!("{operator} {} {}", relation.to_sql(), constraint.to_sql())
             }
-            JoinOperator::LeftOuter(constraint) => {
+            JoinConstraint::On(_) | JoinConstraint::None => {
                 let constraint = vec![constraint.to_sql(), join_executor.to_sql()]
                     .iter()
                     .filter(|sql| !sql.is_empty())
                     .join(" AND ");
                 if constraint.is_empty() {
-                    format!("LEFT OUTER JOIN {}", relation.to_sql())
+                    format!("{operator} {}", relation.to_sql())
                 } else {
-                    format!("LEFT OUTER JOIN {} ON {constraint}", relation.to_sql())
+                    format!("{operator} {} ON {constraint}", relation.to_sql())
                 }
             }
         }
@@ -342,7 +439,8 @@ impl ToSql for JoinConstraint {
     fn to_sql(&self) -> String {
         match self {
             JoinConstraint::On(expr) => expr.to_sql(),
-            JoinConstraint::None => "".to_owned(),
+            JoinConstraint::Using(columns) => format!("USING ({})", columns.join(", ")),
+            JoinConstraint::Natural | JoinConstraint::None => "".to_owned(),
         }
     }
 }
@@ -376,9 +474,9 @@ mod tests {
     use {
         crate::{
             ast::{
-                AstLiteral, BinaryOperator, Dictionary, Expr, Join, JoinConstraint, JoinExecutor,
-                JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr, TableAlias,
-                TableFactor, TableWithJoins, ToSql, Values,
+                AstLiteral, BinaryOperator, Cte, Dictionary, Expr, Join, JoinConstraint,
+                JoinExecutor, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+                SetOperator, TableAlias, TableFactor, TableWithJoins, ToSql, Values, With,
             },
             parse_sql::parse_expr,
             translate::translate_expr,
@@ -401,7 +499,9 @@ mod tests {
         }];
         let actual = "SELECT * FROM FOO AS F ORDER BY name ASC LIMIT 10 OFFSET 3".to_owned();
         let expected = Query {
+            with: None,
             body: SetExpr::Select(Box::new(Select {
+                distinct: false,
                 projection: vec![SelectItem::Wildcard],
                 from: TableWithJoins {
                     relation: TableFactor::Table {
@@ -428,12 +528,44 @@ mod tests {
         }
         .to_sql();
         assert_eq!(actual, expected);
+
+        let values = |n: &str| Query {
+            with: None,
+            body: SetExpr::Values(Values(vec![vec![Expr::Literal(AstLiteral::Number(
+                BigDecimal::from_str(n).unwrap(),
+            ))]])),
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        };
+        let cte = |name: &str, columns: Vec<String>, query: Query| Cte {
+            alias: TableAlias {
+                name: name.to_owned(),
+                columns,
+            },
+            query,
+        };
+
+        let actual = "WITH RECURSIVE T(n) AS (VALUES (1)), U AS (VALUES (2)) VALUES (3)".to_owned();
+        let expected = Query {
+            with: Some(With {
+                recursive: true,
+                cte_tables: vec![
+                    cte("T", vec!["n".to_owned()], values("1")),
+                    cte("U", Vec::new(), values("2")),
+                ],
+            }),
+            ..values("3")
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
     }
 
     #[test]
     fn to_sql_set_expr() {
         let actual = "SELECT * FROM FOO AS F INNER JOIN PlayerItem".to_owned();
         let expected = SetExpr::Select(Box::new(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
@@ -476,12 +608,44 @@ mod tests {
         ]))
         .to_sql();
         assert_eq!(actual, expected);
+
+        let values = |n: &str| {
+            Box::new(SetExpr::Values(Values(vec![vec![Expr::Literal(
+                AstLiteral::Number(BigDecimal::from_str(n).unwrap()),
+            )]])))
+        };
+
+        let actual = "VALUES (1) UNION ALL VALUES (2)".to_owned();
+        let expected = SetExpr::SetOperation {
+            op: SetOperator::Union,
+            all: true,
+            left: values("1"),
+            right: values("2"),
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
+
+        let actual = "(VALUES (1) EXCEPT VALUES (2)) INTERSECT VALUES (3)".to_owned();
+        let expected = SetExpr::SetOperation {
+            op: SetOperator::Intersect,
+            all: false,
+            left: Box::new(SetExpr::SetOperation {
+                op: SetOperator::Except,
+                all: false,
+                left: values("1"),
+                right: values("2"),
+            }),
+            right: values("3"),
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
     }
 
     #[test]
     fn to_sql_select() {
         let actual = "SELECT * FROM FOO AS F GROUP BY name HAVING name = 'glue'".to_owned();
         let expected = Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
@@ -507,6 +671,7 @@ mod tests {
 
         let actual = "SELECT * FROM FOO WHERE name = 'glue'".to_owned();
         let expected = Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
@@ -526,6 +691,28 @@ mod tests {
         }
         .to_sql();
         assert_eq!(actual, expected);
+
+        let actual = "SELECT DISTINCT name AS n FROM FOO".to_owned();
+        let expected = Select {
+            distinct: true,
+            projection: vec![SelectItem::Expr {
+                expr: Expr::Identifier("name".to_owned()),
+                label: "n".to_owned(),
+            }],
+            from: TableWithJoins {
+                relation: TableFactor::Table {
+                    name: "FOO".to_owned(),
+                    alias: None,
+                    index: None,
+                },
+                joins: Vec::new(),
+            },
+            selection: None,
+            group_by: Vec::new(),
+            having: None,
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
     }
 
     #[test]
@@ -582,7 +769,9 @@ mod tests {
         let actual = "(SELECT * FROM FOO) AS F";
         let expected = TableFactor::Derived {
             subquery: Query {
+                with: None,
                 body: SetExpr::Select(Box::new(Select {
+                    distinct: false,
                     projection: vec![SelectItem::Wildcard],
                     from: TableWithJoins {
                         relation: TableFactor::Table {
@@ -722,6 +911,80 @@ mod tests {
         }
         .to_sql();
         assert_eq!(actual, expected);
+
+        let actual = "RIGHT OUTER JOIN PlayerItem ON PlayerItem.user_id = Player.id";
+        let expected = Join {
+            relation: TableFactor::Table {
+                name: "PlayerItem".to_owned(),
+                alias: None,
+                index: None,
+            },
+            join_operator: JoinOperator::RightOuter(JoinConstraint::On(expr(
+                "PlayerItem.user_id = Player.id",
+            ))),
+            join_executor: JoinExecutor::NestedLoop,
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
+
+        let actual = "FULL OUTER JOIN PlayerItem USING (user_id, name)";
+        let expected = Join {
+            relation: TableFactor::Table {
+                name: "PlayerItem".to_owned(),
+                alias: None,
+                index: None,
+            },
+            join_operator: JoinOperator::FullOuter(JoinConstraint::Using(vec![
+                "user_id".to_owned(),
+                "name".to_owned(),
+            ])),
+            join_executor: JoinExecutor::NestedLoop,
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
+
+        let actual = "NATURAL INNER JOIN PlayerItem";
+        let expected = Join {
+            relation: TableFactor::Table {
+                name: "PlayerItem".to_owned(),
+                alias: None,
+                index: None,
+            },
+            join_operator: JoinOperator::Inner(JoinConstraint::Natural),
+            join_executor: JoinExecutor::NestedLoop,
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
+
+        let actual = "CROSS JOIN PlayerItem";
+        let expected = Join {
+            relation: TableFactor::Table {
+                name: "PlayerItem".to_owned(),
+                alias: None,
+                index: None,
+            },
+            join_operator: JoinOperator::Cross,
+            join_executor: JoinExecutor::NestedLoop,
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
+
+        let actual = "SEMI JOIN PlayerItem ON PlayerItem.user_id = Player.id";
+        let expected = Join {
+            relation: TableFactor::Table {
+                name: "PlayerItem".to_owned(),
+                alias: None,
+                index: None,
+            },
+            join_operator: JoinOperator::Semi(JoinConstraint::None),
+            join_executor: JoinExecutor::Hash {
+                key_expr: expr("PlayerItem.user_id"),
+                value_expr: expr("Player.id"),
+                where_clause: None,
+            },
+        }
+        .to_sql();
+        assert_eq!(actual, expected);
     }
 
     #[test]
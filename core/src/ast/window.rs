@@ -0,0 +1,195 @@
+use {
+    super::{Aggregate, Expr, OrderByExpr, ToSql},
+    serde::{Deserialize, Serialize},
+    strum_macros::Display,
+};
+
+/// A window function call, `func OVER (spec)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Window {
+    pub func: WindowFunction,
+    pub spec: WindowSpec,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag {
+        expr: Expr,
+        offset: Option<Expr>,
+        default: Option<Expr>,
+    },
+    Lead {
+        expr: Expr,
+        offset: Option<Expr>,
+        default: Option<Expr>,
+    },
+    FirstValue(Expr),
+    LastValue(Expr),
+    Aggregate(Aggregate),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowSpec {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub frame: Option<WindowFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    /// `None` for the short form, `ROWS <start>`, which ends at the current row
+    pub end: Option<WindowFrameBound>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+/// `Preceding(None)` and `Following(None)` are the `UNBOUNDED` bounds
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFrameBound {
+    CurrentRow,
+    Preceding(Option<Expr>),
+    Following(Option<Expr>),
+}
+
+impl ToSql for Window {
+    fn to_sql(&self) -> String {
+        let Window { func, spec } = self;
+
+        format!("{} OVER ({})", func.to_sql(), spec.to_sql())
+    }
+}
+
+impl ToSql for WindowFunction {
+    fn to_sql(&self) -> String {
+        let offset_args = |expr: &Expr, offset: &Option<Expr>, default: &Option<Expr>| {
+            std::iter::once(Some(expr))
+                .chain([offset.as_ref(), default.as_ref()])
+                .flatten()
+                .map(ToSql::to_sql)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            WindowFunction::RowNumber => "ROW_NUMBER()".to_owned(),
+            WindowFunction::Rank => "RANK()".to_owned(),
+            WindowFunction::DenseRank => "DENSE_RANK()".to_owned(),
+            WindowFunction::Lag {
+                expr,
+                offset,
+                default,
+            } => format!("LAG({})", offset_args(expr, offset, default)),
+            WindowFunction::Lead {
+                expr,
+                offset,
+                default,
+            } => format!("LEAD({})", offset_args(expr, offset, default)),
+            WindowFunction::FirstValue(expr) => format!("FIRST_VALUE({})", expr.to_sql()),
+            WindowFunction::LastValue(expr) => format!("LAST_VALUE({})", expr.to_sql()),
+            WindowFunction::Aggregate(aggr) => aggr.to_sql(),
+        }
+    }
+}
+
+impl ToSql for WindowSpec {
+    fn to_sql(&self) -> String {
+        let WindowSpec {
+            partition_by,
+            order_by,
+            frame,
+        } = self;
+
+        let partition_by = match partition_by.is_empty() {
+            true => None,
+            false => Some(format!(
+                "PARTITION BY {}",
+                partition_by
+                    .iter()
+                    .map(ToSql::to_sql)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+        let order_by = match order_by.is_empty() {
+            true => None,
+            false => Some(format!(
+                "ORDER BY {}",
+                order_by
+                    .iter()
+                    .map(ToSql::to_sql)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+        let frame = frame.as_ref().map(ToSql::to_sql);
+
+        [partition_by, order_by, frame]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl ToSql for WindowFrame {
+    fn to_sql(&self) -> String {
+        let WindowFrame { units, start, end } = self;
+
+        match end {
+            Some(end) => format!("{units} BETWEEN {} AND {}", start.to_sql(), end.to_sql()),
+            None => format!("{units} {}", start.to_sql()),
+        }
+    }
+}
+
+impl ToSql for WindowFrameBound {
+    fn to_sql(&self) -> String {
+        match self {
+            WindowFrameBound::CurrentRow => "CURRENT ROW".to_owned(),
+            WindowFrameBound::Preceding(None) => "UNBOUNDED PRECEDING".to_owned(),
+            WindowFrameBound::Preceding(Some(expr)) => format!("{} PRECEDING", expr.to_sql()),
+            WindowFrameBound::Following(None) => "UNBOUNDED FOLLOWING".to_owned(),
+            WindowFrameBound::Following(Some(expr)) => format!("{} FOLLOWING", expr.to_sql()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::ToSql, parse_sql::parse_expr, translate::translate_expr};
+
+    fn assert_to_sql(sql: &str) {
+        let parsed = parse_expr(sql).expect(sql);
+        let expr = translate_expr(&parsed).expect(sql);
+
+        assert_eq!(sql, expr.to_sql());
+    }
+
+    #[test]
+    fn to_sql_window() {
+        assert_to_sql("ROW_NUMBER() OVER ()");
+        assert_to_sql("RANK() OVER (ORDER BY score DESC)");
+        assert_to_sql("DENSE_RANK() OVER (PARTITION BY team ORDER BY score DESC, name)");
+        assert_to_sql("LAG(price) OVER (ORDER BY day)");
+        assert_to_sql("LEAD(price, 2, 0) OVER (PARTITION BY item ORDER BY day)");
+        assert_to_sql("FIRST_VALUE(name) OVER (PARTITION BY team ORDER BY score)");
+        assert_to_sql(
+            "LAST_VALUE(name) OVER (ORDER BY score ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)",
+        );
+        assert_to_sql("SUM(amount) OVER (ORDER BY day ROWS 2 PRECEDING)");
+        assert_to_sql(
+            "AVG(amount) OVER (PARTITION BY item ORDER BY day RANGE BETWEEN 1 PRECEDING AND 1 FOLLOWING)",
+        );
+        assert_to_sql("COUNT(*) OVER (PARTITION BY item)");
+    }
+}
@@ -38,6 +38,9 @@ impl Build for CreateTableNode {
             name: table_name,
             if_not_exists: self.if_not_exists,
             columns,
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
             source: None,
         })
     }
@@ -34,7 +34,10 @@ impl<'a> Build for DeleteNode<'a> {
 
         Ok(Statement::Delete {
             table_name,
+            alias: None,
+            joins: Vec::new(),
             selection,
+            returning: Vec::new(),
         })
     }
 }
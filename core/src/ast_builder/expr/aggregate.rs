@@ -1,7 +1,7 @@
 use {
     super::ExprNode,
     crate::{
-        ast::{Aggregate, CountArgExpr},
+        ast::{Aggregate, AggregateFunction, CountArgExpr},
         parse_sql::parse_expr,
         result::{Error, Result},
         translate::translate_expr,
@@ -9,7 +9,13 @@ use {
 };
 
 #[derive(Clone)]
-pub enum AggregateNode<'a> {
+pub struct AggregateNode<'a> {
+    func: AggregateFunctionNode<'a>,
+    distinct: bool,
+}
+
+#[derive(Clone)]
+pub enum AggregateFunctionNode<'a> {
     Count(CountArgExprNode<'a>),
     Sum(ExprNode<'a>),
     Min(ExprNode<'a>),
@@ -53,24 +59,52 @@ impl<'a> TryFrom<CountArgExprNode<'a>> for CountArgExpr {
     }
 }
 
-impl<'a> TryFrom<AggregateNode<'a>> for Aggregate {
+impl<'a> TryFrom<AggregateFunctionNode<'a>> for AggregateFunction {
     type Error = Error;
 
-    fn try_from(aggr_node: AggregateNode<'a>) -> Result<Self> {
-        match aggr_node {
-            AggregateNode::Count(count_arg_expr_node) => {
-                count_arg_expr_node.try_into().map(Aggregate::Count)
+    fn try_from(func_node: AggregateFunctionNode<'a>) -> Result<Self> {
+        match func_node {
+            AggregateFunctionNode::Count(count_arg_expr_node) => {
+                count_arg_expr_node.try_into().map(AggregateFunction::Count)
+            }
+            AggregateFunctionNode::Sum(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Sum)
+            }
+            AggregateFunctionNode::Min(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Min)
+            }
+            AggregateFunctionNode::Max(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Max)
+            }
+            AggregateFunctionNode::Avg(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Avg)
+            }
+            AggregateFunctionNode::Variance(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Variance)
+            }
+            AggregateFunctionNode::Stdev(expr_node) => {
+                expr_node.try_into().map(AggregateFunction::Stdev)
             }
-            AggregateNode::Sum(expr_node) => expr_node.try_into().map(Aggregate::Sum),
-            AggregateNode::Min(expr_node) => expr_node.try_into().map(Aggregate::Min),
-            AggregateNode::Max(expr_node) => expr_node.try_into().map(Aggregate::Max),
-            AggregateNode::Avg(expr_node) => expr_node.try_into().map(Aggregate::Avg),
-            AggregateNode::Variance(expr_node) => expr_node.try_into().map(Aggregate::Variance),
-            AggregateNode::Stdev(expr_node) => expr_node.try_into().map(Aggregate::Stdev),
         }
     }
 }
 
+impl<'a> TryFrom<AggregateNode<'a>> for Aggregate {
+    type Error = Error;
+
+    fn try_from(aggr_node: AggregateNode<'a>) -> Result<Self> {
+        let AggregateNode { func, distinct } = aggr_node;
+
+        func.try_into().map(|func| Aggregate::new(func, distinct))
+    }
+}
+
+impl<'a> AggregateNode<'a> {
+    fn new(func: AggregateFunctionNode<'a>, distinct: bool) -> Self {
+        Self { func, distinct }
+    }
+}
+
 impl<'a> ExprNode<'a> {
     pub fn count(self) -> Self {
         count(self)
@@ -99,39 +133,70 @@ impl<'a> ExprNode<'a> {
     pub fn stdev(self) -> Self {
         stdev(self)
     }
+
+    pub fn count_distinct(self) -> Self {
+        count_distinct(self)
+    }
+
+    pub fn sum_distinct(self) -> Self {
+        sum_distinct(self)
+    }
+
+    pub fn avg_distinct(self) -> Self {
+        avg_distinct(self)
+    }
+}
+
+fn aggregate<'a>(func: AggregateFunctionNode<'a>, distinct: bool) -> ExprNode<'a> {
+    ExprNode::Aggregate(Box::new(AggregateNode::new(func, distinct)))
 }
 
 pub fn count<'a, T: Into<CountArgExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Count(expr.into())))
+    aggregate(AggregateFunctionNode::Count(expr.into()), false)
 }
 
 pub fn sum<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Sum(expr.into())))
+    aggregate(AggregateFunctionNode::Sum(expr.into()), false)
 }
 
 pub fn min<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Min(expr.into())))
+    aggregate(AggregateFunctionNode::Min(expr.into()), false)
 }
 
 pub fn max<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Max(expr.into())))
+    aggregate(AggregateFunctionNode::Max(expr.into()), false)
 }
 
 pub fn avg<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Avg(expr.into())))
+    aggregate(AggregateFunctionNode::Avg(expr.into()), false)
 }
 
 pub fn variance<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Variance(expr.into())))
+    aggregate(AggregateFunctionNode::Variance(expr.into()), false)
 }
 
 pub fn stdev<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
-    ExprNode::Aggregate(Box::new(AggregateNode::Stdev(expr.into())))
+    aggregate(AggregateFunctionNode::Stdev(expr.into()), false)
+}
+
+pub fn count_distinct<'a, T: Into<CountArgExprNode<'a>>>(expr: T) -> ExprNode<'a> {
+    aggregate(AggregateFunctionNode::Count(expr.into()), true)
+}
+
+pub fn sum_distinct<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
+    aggregate(AggregateFunctionNode::Sum(expr.into()), true)
+}
+
+pub fn avg_distinct<'a, T: Into<ExprNode<'a>>>(expr: T) -> ExprNode<'a> {
+    aggregate(AggregateFunctionNode::Avg(expr.into()), true)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::ast_builder::{avg, col, count, max, min, stdev, sum, test_expr, variance};
+    use crate::ast_builder::{
+        avg, avg_distinct, col, count, count_distinct, max, min, stdev, sum, sum_distinct,
+        test_expr, variance,
+    };
 
     #[test]
     fn aggregate() {
@@ -193,5 +258,29 @@ mod tests {
         let actual = stdev("scatterplot");
         let expected = "STDEV(scatterplot)";
         test_expr(actual, expected);
+
+        let actual = col("id").count_distinct();
+        let expected = "COUNT(DISTINCT id)";
+        test_expr(actual, expected);
+
+        let actual = count_distinct("id");
+        let expected = "COUNT(DISTINCT id)";
+        test_expr(actual, expected);
+
+        let actual = col("amount").sum_distinct();
+        let expected = "SUM(DISTINCT amount)";
+        test_expr(actual, expected);
+
+        let actual = sum_distinct("amount");
+        let expected = "SUM(DISTINCT amount)";
+        test_expr(actual, expected);
+
+        let actual = col("grade").avg_distinct();
+        let expected = "AVG(DISTINCT grade)";
+        test_expr(actual, expected);
+
+        let actual = avg_distinct("grade");
+        let expected = "AVG(DISTINCT grade)";
+        test_expr(actual, expected);
     }
 }
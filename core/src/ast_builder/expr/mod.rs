@@ -240,7 +240,12 @@ impl<'a> TryFrom<ExprNode<'a>> for Expr {
                 .map(Box::new)
                 .map(|subquery| Expr::Exists { subquery, negated }),
             ExprNode::Subquery(subquery) => {
-                Query::try_from(*subquery).map(Box::new).map(Expr::Subquery)
+                Query::try_from(*subquery)
+                    .map(Box::new)
+                    .map(|subquery| Expr::Subquery {
+                        subquery,
+                        cached: false,
+                    })
             }
             ExprNode::Case {
                 operand,
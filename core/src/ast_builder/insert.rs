@@ -58,7 +58,10 @@ impl<'a> Build for InsertSourceNode<'a> {
         Ok(Statement::Insert {
             table_name,
             columns,
+            overriding_system_value: false,
             source,
+            on_conflict: None,
+            returning: Vec::new(),
         })
     }
 }
@@ -68,7 +68,10 @@ pub use {index::CreateIndexNode, index::DropIndexNode};
 
 /// Available aggregate or normal SQL functions
 pub use expr::{
-    aggregate::{avg, count, max, min, stdev, sum, variance, AggregateNode},
+    aggregate::{
+        avg, avg_distinct, count, count_distinct, max, min, stdev, sum, sum_distinct, variance,
+        AggregateFunctionNode, AggregateNode,
+    },
     function::{
         abs, acos, asin, atan, cast, ceil, concat, concat_ws, cos, degrees, divide, exp, extract,
         floor, format, gcd, generate_uuid, ifnull, lcm, left, ln, log, log10, log2, lower, lpad,
@@ -81,6 +81,7 @@ impl<'a> TryFrom<QueryNode<'a>> for Query {
                     .collect::<Result<Vec<_>>>()?;
 
                 return Ok(Query {
+                    with: None,
                     body: SetExpr::Values(Values(values)),
                     order_by: Vec::new(),
                     limit: None,
@@ -156,6 +157,7 @@ mod test {
                 },
             };
             let select = Select {
+                distinct: false,
                 projection: SelectItemList::from("*").try_into().unwrap(),
                 from: TableWithJoins {
                     relation: TableFactor::Table {
@@ -171,6 +173,7 @@ mod test {
             };
 
             Query {
+                with: None,
                 body: SetExpr::Select(Box::new(select)),
                 order_by: Vec::new(),
                 limit: None,
@@ -41,6 +41,7 @@ pub trait Prebuild {
 
 #[derive(Clone)]
 pub struct NodeData {
+    pub distinct: bool,
     pub projection: Vec<SelectItem>,
     pub relation: TableFactor,
     pub joins: Vec<Join>,
@@ -56,6 +57,7 @@ pub struct NodeData {
 impl NodeData {
     pub fn build_query(self) -> Query {
         let NodeData {
+            distinct,
             projection,
             relation,
             group_by,
@@ -71,6 +73,7 @@ impl NodeData {
         let from = TableWithJoins { relation, joins };
 
         let select = Select {
+            distinct,
             projection,
             from,
             selection,
@@ -79,6 +82,7 @@ impl NodeData {
         };
 
         Query {
+            with: None,
             body: SetExpr::Select(Box::new(select)),
             order_by,
             offset,
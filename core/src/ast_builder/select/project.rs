@@ -120,6 +120,7 @@ impl<'a> From<OrderByNode<'a>> for PrevNode<'a> {
 #[derive(Clone)]
 pub struct ProjectNode<'a> {
     prev_node: PrevNode<'a>,
+    distinct: bool,
     select_items_list: Vec<SelectItemList<'a>>,
 }
 
@@ -130,6 +131,7 @@ impl<'a> ProjectNode<'a> {
     ) -> Self {
         Self {
             prev_node: prev_node.into(),
+            distinct: false,
             select_items_list: vec![select_items.into()],
         }
     }
@@ -139,11 +141,18 @@ impl<'a> ProjectNode<'a> {
 
         self
     }
+
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+
+        self
+    }
 }
 
 impl<'a> Prebuild for ProjectNode<'a> {
     fn prebuild(self) -> Result<NodeData> {
         let mut select_data = self.prev_node.prebuild()?;
+        select_data.distinct = self.distinct;
         select_data.projection = self
             .select_items_list
             .into_iter()
@@ -205,6 +214,21 @@ mod tests {
             .build();
         let expected = "SELECT 1 + 1 as col1, col2 FROM Aliased";
         test(actual, expected);
+
+        // select node -> project node -> distinct -> build
+        let actual = table("Foo").select().project("name").distinct().build();
+        let expected = "SELECT DISTINCT name FROM Foo";
+        test(actual, expected);
+
+        // group by node -> project node -> distinct -> build
+        let actual = table("Bar")
+            .select()
+            .group_by("city")
+            .project("COUNT(name)")
+            .distinct()
+            .build();
+        let expected = "SELECT DISTINCT COUNT(name) FROM Bar GROUP BY city";
+        test(actual, expected);
     }
 
     #[test]
@@ -307,6 +331,7 @@ mod tests {
                 },
             };
             let select = Select {
+                distinct: false,
                 projection: SelectItemList::from("Player.name, PlayerItem.name")
                     .try_into()
                     .unwrap(),
@@ -324,6 +349,7 @@ mod tests {
             };
 
             Ok(Statement::Query(Query {
+                with: None,
                 body: SetExpr::Select(Box::new(select)),
                 order_by: Vec::new(),
                 limit: None,
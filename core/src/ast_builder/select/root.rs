@@ -87,6 +87,7 @@ impl Prebuild for SelectNode {
         };
 
         Ok(NodeData {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             relation,
             filter: None,
@@ -6,7 +6,7 @@ use super::{
 use super::AlterTableNode;
 
 #[cfg(feature = "index")]
-use super::{CreateIndexNode, DropIndexNode, OrderByExprNode};
+use super::{CreateIndexNode, DropIndexNode, OrderByExprList};
 
 #[derive(Clone)]
 pub struct TableNode {
@@ -35,12 +35,12 @@ impl TableNode {
     }
 
     #[cfg(feature = "index")]
-    pub fn create_index<'a, T: Into<OrderByExprNode<'a>>>(
+    pub fn create_index<'a, T: Into<OrderByExprList<'a>>>(
         self,
         name: &str,
-        column: T,
+        columns: T,
     ) -> CreateIndexNode<'a> {
-        CreateIndexNode::new(self.table_name, name.to_owned(), column.into())
+        CreateIndexNode::new(self.table_name, name.to_owned(), columns.into())
     }
 
     pub fn show_columns(self) -> ShowColumnsNode {
@@ -45,8 +45,11 @@ impl<'a> Build for UpdateNode<'a> {
             .collect::<Result<Vec<_>>>()?;
         Ok(Statement::Update {
             table_name,
+            alias: None,
             assignments,
+            joins: Vec::new(),
             selection,
+            returning: Vec::new(),
         })
     }
 }
@@ -40,6 +40,8 @@ pub enum Key {
     Time(NaiveTime),
     Interval(Interval),
     Uuid(u128),
+    /// Multi-column PRIMARY KEY
+    Composite(Vec<Key>),
     None,
 }
 
@@ -61,6 +63,7 @@ impl PartialOrd for Key {
             (Key::Time(l), Key::Time(r)) => Some(l.cmp(r)),
             (Key::Interval(l), Key::Interval(r)) => l.partial_cmp(r),
             (Key::Uuid(l), Key::Uuid(r)) => Some(l.cmp(r)),
+            (Key::Composite(l), Key::Composite(r)) => l.iter().partial_cmp(r.iter()),
             _ => None,
         }
     }
@@ -105,6 +108,21 @@ impl TryFrom<&Value> for Key {
     }
 }
 
+impl Key {
+    /// Builds a primary key from the values of its columns, in the order of the key.
+    pub fn from_primary_values(values: Vec<Value>) -> Result<Self> {
+        let mut keys = values
+            .into_iter()
+            .map(Key::try_from)
+            .collect::<Result<Vec<_>>>()?;
+
+        match keys.len() {
+            1 => Ok(keys.swap_remove(0)),
+            _ => Ok(Key::Composite(keys)),
+        }
+    }
+}
+
 const VALUE: u8 = 0;
 const NONE: u8 = 1;
 
@@ -246,6 +264,18 @@ impl Key {
                 .chain(v.to_be_bytes().iter())
                 .copied()
                 .collect::<Vec<_>>(),
+            Key::Composite(keys) => keys
+                .iter()
+                .flat_map(|key| {
+                    key.to_cmp_be_bytes()
+                        .into_iter()
+                        .flat_map(|v| match v {
+                            0 => vec![0, u8::MAX],
+                            _ => vec![v],
+                        })
+                        .chain([0, 0])
+                })
+                .collect::<Vec<_>>(),
             Key::None => vec![NONE],
         }
     }
@@ -546,4 +576,30 @@ mod tests {
         assert_eq!(cmp(&n2, &n1), Ordering::Greater);
         assert_eq!(cmp(&n1, &null), Ordering::Less);
     }
+    #[test]
+    fn cmp_composite() {
+        use crate::data::Key::*;
+
+        let n1 = Composite(vec![I64(1), Str("a".to_owned())]).to_cmp_be_bytes();
+        let n2 = Composite(vec![I64(1), Str("ab".to_owned())]).to_cmp_be_bytes();
+        let n3 = Composite(vec![I64(2), Str("a".to_owned())]).to_cmp_be_bytes();
+        let n4 = Composite(vec![Str("a".to_owned()), I64(3)]).to_cmp_be_bytes();
+        let n5 = Composite(vec![Str("ab".to_owned()), I64(1)]).to_cmp_be_bytes();
+        let n6 = Composite(vec![I64(1), None]).to_cmp_be_bytes();
+
+        assert_eq!(cmp(&n1, &n1), Ordering::Equal);
+        assert_eq!(cmp(&n1, &n2), Ordering::Less);
+        assert_eq!(cmp(&n2, &n3), Ordering::Less);
+        assert_eq!(cmp(&n4, &n5), Ordering::Less);
+        assert_eq!(cmp(&n2, &n6), Ordering::Less);
+
+        assert_eq!(
+            Composite(vec![I64(1), I64(2)]).partial_cmp(&Composite(vec![I64(1), I64(3)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            Composite(vec![I64(1), I64(2)]).partial_cmp(&Composite(vec![I64(1), Bool(true)])),
+            Option::None
+        );
+    }
 }
@@ -3,8 +3,10 @@ mod interval;
 mod key;
 mod literal;
 mod row;
+mod sequence;
 mod string_ext;
 mod table;
+mod view;
 
 pub mod schema;
 pub mod value;
@@ -15,8 +17,10 @@ pub use {
     key::{Key, KeyError},
     literal::{Literal, LiteralError},
     row::{Row, RowError},
-    schema::{Schema, SchemaIndex, SchemaIndexOrd},
+    schema::{ColumnStatistics, Schema, SchemaIndex, SchemaIndexOrd, Statistics},
+    sequence::{Sequence, SequenceError},
     string_ext::{StringExt, StringExtError},
     table::{get_alias, get_index, TableError},
     value::{NumericBinaryOperator, Value, ValueError},
+    view::{View, ViewError},
 };
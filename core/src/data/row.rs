@@ -29,6 +29,12 @@ pub enum RowError {
 
     #[error("VALUES lists must all be the same length")]
     NumberOfValuesDifferent,
+
+    #[error("cannot insert a value into generated column: {0}")]
+    ValueOnGeneratedColumn(String),
+
+    #[error("cannot insert a value into GENERATED ALWAYS identity column: {0}")]
+    ValueOnAlwaysIdentityColumn(String),
 }
 
 #[derive(iter_enum::Iterator)]
@@ -97,10 +103,17 @@ impl Row {
                     .find(|(name, _)| name == &def_name)
                     .map(|(_, value)| value);
 
+                let generated = column_def.get_generated().is_some();
+
                 match (value, column_def.get_default(), nullable) {
+                    (Some(_), _, _) if generated => {
+                        Err(RowError::ValueOnGeneratedColumn(def_name.to_owned()).into())
+                    }
                     (Some(&expr), _, _) | (None, Some(expr), _) => {
                         evaluate_stateless(None, expr)?.try_into_value(data_type, *nullable)
                     }
+                    // filled with the next value of its sequence or computed by the executor
+                    (None, None, _) if generated || column_def.is_identity() => Ok(Value::Null),
                     (None, None, true) => Ok(Value::Null),
                     (None, None, false) => {
                         Err(RowError::LackOfRequiredColumn(def_name.to_owned()).into())
@@ -1,5 +1,5 @@
 use {
-    crate::ast::{ColumnDef, ColumnOption, Expr, Statement, ToSql},
+    crate::ast::{ColumnDef, ColumnOption, Expr, ForeignKey, Statement, ToSql},
     chrono::NaiveDateTime,
     serde::{Deserialize, Serialize},
     std::{fmt::Debug, iter},
@@ -17,7 +17,7 @@ pub enum SchemaIndexOrd {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SchemaIndex {
     pub name: String,
-    pub expr: Expr,
+    pub exprs: Vec<Expr>,
     pub order: SchemaIndexOrd,
     pub created: NaiveDateTime,
 }
@@ -26,15 +26,40 @@ pub struct SchemaIndex {
 pub struct Schema {
     pub table_name: String,
     pub column_defs: Vec<ColumnDef>,
+    /// Columns of a multi-column PRIMARY KEY
+    pub primary_key: Option<Vec<String>>,
+    pub foreign_keys: Vec<ForeignKey>,
+    /// `CHECK (..)` table constraints
+    pub checks: Vec<Expr>,
     pub indexes: Vec<SchemaIndex>,
+    /// Collected by `ANALYZE`, `None` until the table is analyzed
+    pub statistics: Option<Statistics>,
     pub created: NaiveDateTime,
 }
 
+/// Row count and per column estimates which the planner uses to order the joins.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Statistics {
+    pub row_count: usize,
+    pub columns: Vec<ColumnStatistics>,
+    pub analyzed: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ColumnStatistics {
+    pub name: String,
+    pub distinct_count: usize,
+    pub null_count: usize,
+}
+
 impl Schema {
     pub fn to_ddl(self) -> String {
         let Schema {
             table_name,
             column_defs: columns,
+            primary_key,
+            foreign_keys,
+            checks,
             indexes,
             ..
         } = self;
@@ -43,15 +68,19 @@ impl Schema {
             if_not_exists: false,
             name: table_name.clone(),
             columns,
+            primary_key,
+            foreign_keys,
+            checks,
             source: None,
         }
         .to_sql();
 
-        let create_indexes = indexes.iter().map(|SchemaIndex { name, expr, .. }| {
-            let expr = expr.to_sql();
+        let create_indexes = indexes.iter().map(|index| {
+            let SchemaIndex { name, .. } = index;
+            let exprs = index.exprs_to_sql();
             let table_name = &table_name;
 
-            format!("CREATE INDEX {name} ON {table_name} ({expr});")
+            format!("CREATE INDEX {name} ON {table_name} ({exprs});")
         });
 
         iter::once(create_table)
@@ -59,6 +88,59 @@ impl Schema {
             .collect::<Vec<_>>()
             .join("\n")
     }
+
+    /// Returns the schema (namespace) part of a qualified `schema.table` name.
+    pub fn namespace(&self) -> Option<&str> {
+        self.table_name
+            .split_once('.')
+            .map(|(namespace, _)| namespace)
+    }
+
+    /// Returns positions of the PRIMARY KEY columns in the order of the key.
+    pub fn primary_key_indexes(&self) -> Option<Vec<usize>> {
+        let position = |column_name: &String| {
+            self.column_defs
+                .iter()
+                .position(|ColumnDef { name, .. }| name == column_name)
+        };
+
+        match &self.primary_key {
+            Some(primary_key) => primary_key.iter().map(position).collect(),
+            None => self
+                .column_defs
+                .iter()
+                .position(|ColumnDef { options, .. }| {
+                    options.contains(&ColumnOption::Unique { is_primary: true })
+                })
+                .map(|i| vec![i]),
+        }
+    }
+
+    /// Returns CHECK constraints of both columns and the table.
+    pub fn all_checks(&self) -> impl Iterator<Item = &Expr> {
+        self.column_defs
+            .iter()
+            .flat_map(ColumnDef::get_checks)
+            .chain(&self.checks)
+    }
+}
+
+impl Statistics {
+    pub fn column(&self, column_name: &str) -> Option<&ColumnStatistics> {
+        self.columns
+            .iter()
+            .find(|ColumnStatistics { name, .. }| name == column_name)
+    }
+}
+
+impl SchemaIndex {
+    pub fn exprs_to_sql(&self) -> String {
+        self.exprs
+            .iter()
+            .map(ToSql::to_sql)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 impl ColumnDef {
@@ -68,16 +150,46 @@ impl ColumnDef {
             _ => None,
         })
     }
+
+    pub fn get_checks(&self) -> impl Iterator<Item = &Expr> {
+        self.options.iter().filter_map(|option| match option {
+            ColumnOption::Check(expr) => Some(expr),
+            _ => None,
+        })
+    }
+
+    pub fn get_generated(&self) -> Option<&Expr> {
+        self.options.iter().find_map(|option| match option {
+            ColumnOption::Generated(expr) => Some(expr),
+            _ => None,
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.options
+            .iter()
+            .any(|option| matches!(option, ColumnOption::Identity { .. }))
+    }
+
+    /// Identity column which does not take the values given to it
+    pub fn is_always_identity(&self) -> bool {
+        self.options
+            .contains(&ColumnOption::Identity { always: true })
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::{
-        ast::{AstLiteral, ColumnDef, ColumnOption, Expr},
-        chrono::Utc,
-        data::{Schema, SchemaIndex, SchemaIndexOrd},
-        prelude::DataType,
+    use {
+        crate::{
+            ast::{AstLiteral, BinaryOperator, ColumnDef, ColumnOption, Expr},
+            chrono::Utc,
+            data::{Schema, SchemaIndex, SchemaIndexOrd},
+            prelude::DataType,
+        },
+        bigdecimal::BigDecimal,
+        std::str::FromStr,
     };
 
     #[test]
@@ -100,7 +212,11 @@ mod tests {
                     ))],
                 },
             ],
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
             indexes: Vec::new(),
+            statistics: None,
             created: Utc::now().naive_utc(),
         };
 
@@ -120,7 +236,11 @@ mod tests {
                 nullable: false,
                 options: vec![ColumnOption::Unique { is_primary: true }],
             }],
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
             indexes: Vec::new(),
+            statistics: None,
             created: Utc::now().naive_utc(),
         };
 
@@ -130,6 +250,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn table_composite_primary() {
+        let schema = Schema {
+            table_name: "Membership".to_owned(),
+            column_defs: vec![
+                ColumnDef {
+                    name: "user_id".to_owned(),
+                    data_type: DataType::Int,
+                    nullable: false,
+                    options: Vec::new(),
+                },
+                ColumnDef {
+                    name: "group_id".to_owned(),
+                    data_type: DataType::Int,
+                    nullable: false,
+                    options: Vec::new(),
+                },
+            ],
+            primary_key: Some(vec!["group_id".to_owned(), "user_id".to_owned()]),
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
+            indexes: Vec::new(),
+            statistics: None,
+            created: Utc::now().naive_utc(),
+        };
+
+        assert_eq!(schema.primary_key_indexes(), Some(vec![1, 0]));
+        assert_eq!(
+            schema.to_ddl(),
+            "CREATE TABLE Membership (user_id INT NOT NULL, group_id INT NOT NULL, PRIMARY KEY (group_id, user_id));"
+        );
+    }
+
+    #[test]
+    fn table_check() {
+        let schema = Schema {
+            table_name: "Item".to_owned(),
+            column_defs: vec![
+                ColumnDef {
+                    name: "price".to_owned(),
+                    data_type: DataType::Int,
+                    nullable: false,
+                    options: vec![ColumnOption::Check(Expr::BinaryOp {
+                        left: Box::new(Expr::Identifier("price".to_owned())),
+                        op: BinaryOperator::Gt,
+                        right: Box::new(Expr::Literal(AstLiteral::Number(
+                            BigDecimal::from_str("0").unwrap(),
+                        ))),
+                    })],
+                },
+                ColumnDef {
+                    name: "discount".to_owned(),
+                    data_type: DataType::Int,
+                    nullable: false,
+                    options: Vec::new(),
+                },
+            ],
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: vec![Expr::BinaryOp {
+                left: Box::new(Expr::Identifier("discount".to_owned())),
+                op: BinaryOperator::Lt,
+                right: Box::new(Expr::Identifier("price".to_owned())),
+            }],
+            indexes: Vec::new(),
+            statistics: None,
+            created: Utc::now().naive_utc(),
+        };
+
+        assert_eq!(
+            schema.to_ddl(),
+            "CREATE TABLE Item (price INT NOT NULL CHECK (price > 0), discount INT NOT NULL, CHECK (discount < price));"
+        );
+    }
+
     #[test]
     fn table_with_index() {
         let schema = Schema {
@@ -148,20 +343,33 @@ mod tests {
                     options: Vec::new(),
                 },
             ],
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
             indexes: vec![
                 SchemaIndex {
                     name: "User_id".to_owned(),
-                    expr: Expr::Identifier("id".to_owned()),
+                    exprs: vec![Expr::Identifier("id".to_owned())],
                     order: SchemaIndexOrd::Both,
                     created: Utc::now().naive_utc(),
                 },
                 SchemaIndex {
                     name: "User_name".to_owned(),
-                    expr: Expr::Identifier("name".to_owned()),
+                    exprs: vec![Expr::Identifier("name".to_owned())],
+                    order: SchemaIndexOrd::Both,
+                    created: Utc::now().naive_utc(),
+                },
+                SchemaIndex {
+                    name: "User_id_name".to_owned(),
+                    exprs: vec![
+                        Expr::Identifier("id".to_owned()),
+                        Expr::Identifier("name".to_owned()),
+                    ],
                     order: SchemaIndexOrd::Both,
                     created: Utc::now().naive_utc(),
                 },
             ],
+            statistics: None,
             created: Utc::now().naive_utc(),
         };
 
@@ -169,7 +377,8 @@ mod tests {
             schema.to_ddl(),
             "CREATE TABLE User (id INT NOT NULL, name TEXT NOT NULL);
 CREATE INDEX User_id ON User (id);
-CREATE INDEX User_name ON User (name);"
+CREATE INDEX User_name ON User (name);
+CREATE INDEX User_id_name ON User (id, name);"
         );
     }
 }
@@ -0,0 +1,79 @@
+use {
+    crate::result::Result,
+    serde::{Deserialize, Serialize},
+    std::fmt::Debug,
+    thiserror::Error as ThisError,
+};
+
+#[derive(ThisError, Debug, PartialEq, Serialize)]
+pub enum SequenceError {
+    #[error("sequence already exists: {0}")]
+    AlreadyExists(String),
+
+    #[error("sequence does not exist: {0}")]
+    NotFound(String),
+
+    #[error("currval of sequence is not yet defined, call nextval first: {0}")]
+    CurrentValueNotDefined(String),
+
+    #[error("sequence has reached its limit: {0}")]
+    Exhausted(String),
+
+    #[error("INCREMENT must not be zero: {0}")]
+    ZeroIncrement(String),
+}
+
+/// Sequence created by `CREATE SEQUENCE` or by an identity column.
+///
+/// Storages keep the current value of a sequence apart from its definition, values are
+/// not rolled back with the transaction so that a value is never handed out twice.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Sequence {
+    pub name: String,
+    pub start: i64,
+    pub increment: i64,
+}
+
+impl Sequence {
+    /// Sequence which backs the identity column of a table.
+    pub fn identity(table_name: &str, column_name: &str) -> Self {
+        Self {
+            name: format!("{table_name}_{column_name}_seq"),
+            start: 1,
+            increment: 1,
+        }
+    }
+
+    /// Returns the value following `current`, `start` if no value has been generated yet.
+    pub fn next_value(&self, current: Option<i64>) -> Result<i64> {
+        match current {
+            Some(current) => current
+                .checked_add(self.increment)
+                .ok_or_else(|| SequenceError::Exhausted(self.name.to_owned()).into()),
+            None => Ok(self.start),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sequence, SequenceError};
+
+    #[test]
+    fn next_value() {
+        let sequence = Sequence {
+            name: "Foo".to_owned(),
+            start: 10,
+            increment: -3,
+        };
+
+        assert_eq!(sequence.next_value(None), Ok(10));
+        assert_eq!(sequence.next_value(Some(10)), Ok(7));
+        assert_eq!(
+            sequence.next_value(Some(i64::MIN)),
+            Err(SequenceError::Exhausted("Foo".to_owned()).into())
+        );
+
+        assert_eq!(Sequence::identity("Item", "id").name, "Item_id_seq");
+    }
+}
@@ -2,7 +2,7 @@ use {
     super::ValueError::ValueToExprConversionFailure,
     crate::{
         ast::AstLiteral,
-        ast::{DateTimeField, Expr},
+        ast::{DateTimeField, Expr, Function},
         chrono::{DateTime, Utc},
         data::Interval,
         prelude::DataType,
@@ -45,12 +45,18 @@ impl TryFrom<Value> for Expr {
                 BigDecimal::from_u16(v).ok_or(ValueToExprConversionFailure)?,
             )),
 
-            Value::F64(v) => Expr::Literal(AstLiteral::Number(
+            Value::F64(v) if v.is_finite() => Expr::Literal(AstLiteral::Number(
                 BigDecimal::from_f64(v).ok_or(ValueToExprConversionFailure)?,
             )),
+            // NaN and infinities have no number literal, they are cast from their names
+            Value::F64(v) => Expr::Function(Box::new(Function::Cast {
+                expr: Expr::Literal(AstLiteral::QuotedString(v.to_string())),
+                data_type: DataType::Float,
+            })),
             Value::Decimal(v) => Expr::Literal(AstLiteral::Number(
-                BigDecimal::from_f64(v.try_into().map_err(|_| ValueToExprConversionFailure)?)
-                    .ok_or(ValueToExprConversionFailure)?,
+                v.to_string()
+                    .parse()
+                    .map_err(|_| ValueToExprConversionFailure)?,
             )),
             Value::Str(v) => Expr::Literal(AstLiteral::QuotedString(v)),
             Value::Bytea(v) => Expr::Literal(AstLiteral::HexString(hex::encode(v))),
@@ -116,7 +122,7 @@ impl TryFrom<Value> for Expr {
 mod tests {
     use {
         crate::{
-            ast::{AstLiteral, DateTimeField, Expr},
+            ast::{AstLiteral, DateTimeField, Expr, Function},
             data::Interval,
             prelude::{DataType, Value},
         },
@@ -124,7 +130,7 @@ mod tests {
         bigdecimal::FromPrimitive,
         chrono::{NaiveDate, NaiveTime},
         rust_decimal::Decimal,
-        std::collections::HashMap,
+        std::{collections::HashMap, str::FromStr},
     };
 
     #[test]
@@ -176,12 +182,32 @@ mod tests {
                 BigDecimal::from_f64(64.4).unwrap()
             )))
         );
+        assert_eq!(
+            Value::F64(f64::NAN).try_into(),
+            Ok(Expr::Function(Box::new(Function::Cast {
+                expr: Expr::Literal(AstLiteral::QuotedString("NaN".to_owned())),
+                data_type: DataType::Float,
+            })))
+        );
+        assert_eq!(
+            Value::F64(f64::NEG_INFINITY).try_into(),
+            Ok(Expr::Function(Box::new(Function::Cast {
+                expr: Expr::Literal(AstLiteral::QuotedString("-inf".to_owned())),
+                data_type: DataType::Float,
+            })))
+        );
         assert_eq!(
             Value::Decimal(Decimal::new(315, 2)).try_into(),
             Ok(Expr::Literal(AstLiteral::Number(
                 BigDecimal::from_f64(3.15).unwrap()
             )))
         );
+        assert_eq!(
+            Value::Decimal(Decimal::from_str("12345678901234567890.123456789").unwrap()).try_into(),
+            Ok(Expr::Literal(AstLiteral::Number(
+                BigDecimal::from_str("12345678901234567890.123456789").unwrap()
+            )))
+        );
         assert_eq!(
             Value::Str("data".to_owned()).try_into(),
             Ok(Expr::Literal(AstLiteral::QuotedString("data".to_owned())))
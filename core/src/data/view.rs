@@ -0,0 +1,35 @@
+use {
+    crate::ast::Query,
+    chrono::NaiveDateTime,
+    serde::{Deserialize, Serialize},
+    std::fmt::Debug,
+    thiserror::Error as ThisError,
+};
+
+#[derive(ThisError, Debug, PartialEq, Serialize)]
+pub enum ViewError {
+    #[error("view already exists: {0}")]
+    AlreadyExists(String),
+
+    #[error("view does not exist: {0}")]
+    NotFound(String),
+
+    #[error("view refers to itself: {0}")]
+    RecursiveReference(String),
+}
+
+/// View created by `CREATE VIEW`, the planner expands it into a derived table of its query.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct View {
+    pub name: String,
+    /// Column aliases, the labels of the query are used for the rest of the columns
+    pub columns: Vec<String>,
+    pub query: Query,
+    pub created: NaiveDateTime,
+}
+
+impl View {
+    pub fn namespace(&self) -> Option<&str> {
+        self.name.split_once('.').map(|(namespace, _)| namespace)
+    }
+}
@@ -4,4 +4,7 @@ use {serde::Serialize, std::fmt::Debug, thiserror::Error};
 pub enum AggregateError {
     #[error("unreachable rc unwrap failure")]
     UnreachableRcUnwrapFailure,
+
+    #[error("unreachable merge of different aggregates")]
+    UnreachableAggrValueMismatch,
 }
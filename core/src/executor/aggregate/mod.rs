@@ -19,7 +19,7 @@ use {
     std::{convert::identity, pin::Pin, rc::Rc},
 };
 
-pub use error::AggregateError;
+pub use {error::AggregateError, state::AggrValue};
 
 pub struct Aggregator<'a> {
     storage: &'a dyn GStore,
@@ -158,18 +158,23 @@ impl<'a> Aggregator<'a> {
     }
 
     fn check_aggregate(&self) -> bool {
-        if !self.group_by.is_empty() {
-            return true;
-        }
+        check_aggregate(self.fields, self.group_by)
+    }
+}
 
-        self.fields
-            .iter()
-            .map(|field| match field {
-                SelectItem::Expr { expr, .. } => check(expr),
-                _ => false,
-            })
-            .any(identity)
+/// Whether the `SELECT` groups its rows, by `GROUP BY` or by aggregate functions.
+pub fn check_aggregate(fields: &[SelectItem], group_by: &[Expr]) -> bool {
+    if !group_by.is_empty() {
+        return true;
     }
+
+    fields
+        .iter()
+        .map(|field| match field {
+            SelectItem::Expr { expr, .. } => check(expr),
+            _ => false,
+        })
+        .any(identity)
 }
 
 #[async_recursion(?Send)]
@@ -221,7 +226,15 @@ async fn aggregate<'a>(
                 )
                 .await
         }
-        Expr::Aggregate(aggr) => state.accumulate(filter_context, aggr.as_ref()).await,
+        Expr::Aggregate(aggr) => state.accumulate(filter_context, expr, aggr.as_ref()).await,
+        Expr::Window(window) => {
+            stream::iter(window.as_exprs())
+                .fold(
+                    Ok(state),
+                    |state, expr| async move { aggr(state?, expr).await },
+                )
+                .await
+        }
         _ => Ok(state),
     }
 }
@@ -250,6 +263,7 @@ fn check(expr: &Expr) -> bool {
                     .unwrap_or(false)
         }
         Expr::Aggregate(_) => true,
+        Expr::Window(window) => window.as_exprs().any(check),
         _ => false,
     }
 }
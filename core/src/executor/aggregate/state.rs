@@ -1,8 +1,11 @@
 use {
     crate::{
-        ast::{Aggregate, CountArgExpr},
+        ast::{Aggregate, AggregateFunction, CountArgExpr, Expr},
         data::{Key, Value},
-        executor::{context::BlendContext, context::FilterContext, evaluate::evaluate},
+        executor::{
+            aggregate::AggregateError, context::BlendContext, context::FilterContext,
+            evaluate::evaluate,
+        },
         result::Result,
         store::GStore,
     },
@@ -13,10 +16,11 @@ use {
 };
 
 type Group = Rc<Vec<Key>>;
-type ValuesMap<'a> = HashMap<&'a Aggregate, Value>;
+type ValuesMap<'a> = HashMap<&'a Expr, Value>;
 type Context<'a> = Rc<BlendContext<'a>>;
 
-enum AggrValue {
+#[derive(Clone)]
+pub enum AggrValue {
     Count {
         wildcard: bool,
         count: i64,
@@ -41,31 +45,31 @@ enum AggrValue {
 }
 
 impl AggrValue {
-    fn new(aggr: &Aggregate, value: &Value) -> Result<Self> {
+    pub fn new(aggr: &Aggregate, value: &Value) -> Result<Self> {
         let value = value.clone();
 
-        Ok(match aggr {
-            Aggregate::Count(CountArgExpr::Wildcard) => AggrValue::Count {
+        Ok(match &aggr.func {
+            AggregateFunction::Count(CountArgExpr::Wildcard) => AggrValue::Count {
                 wildcard: true,
                 count: 1,
             },
-            Aggregate::Count(CountArgExpr::Expr(_)) => AggrValue::Count {
+            AggregateFunction::Count(CountArgExpr::Expr(_)) => AggrValue::Count {
                 wildcard: false,
                 count: if value.is_null() { 0 } else { 1 },
             },
-            Aggregate::Sum(_) => AggrValue::Sum(value),
-            Aggregate::Min(_) => AggrValue::Min(value),
-            Aggregate::Max(_) => AggrValue::Max(value),
-            Aggregate::Avg(_) => AggrValue::Avg {
+            AggregateFunction::Sum(_) => AggrValue::Sum(value),
+            AggregateFunction::Min(_) => AggrValue::Min(value),
+            AggregateFunction::Max(_) => AggrValue::Max(value),
+            AggregateFunction::Avg(_) => AggrValue::Avg {
                 sum: value,
                 count: 1,
             },
-            Aggregate::Variance(_) => AggrValue::Variance {
+            AggregateFunction::Variance(_) => AggrValue::Variance {
                 sum_square: value.multiply(&value)?,
                 sum: value,
                 count: 1,
             },
-            Aggregate::Stdev(_) => AggrValue::Stdev {
+            AggregateFunction::Stdev(_) => AggrValue::Stdev {
                 sum_square: value.multiply(&value)?,
                 sum: value,
                 count: 1,
@@ -73,7 +77,7 @@ impl AggrValue {
         })
     }
 
-    fn accumulate(&self, new_value: &Value) -> Result<Option<Self>> {
+    pub fn accumulate(&self, new_value: &Value) -> Result<Option<Self>> {
         match self {
             Self::Count { wildcard, count } => {
                 let wildcard = *wildcard;
@@ -121,7 +125,72 @@ impl AggrValue {
         }
     }
 
-    fn export(self) -> Result<Value> {
+    /// Combines the values accumulated from two runs of rows, the rows of `other` follow
+    /// those of `self`.
+    pub fn merge(&self, other: &Self) -> Result<Self> {
+        match (self, other) {
+            (
+                Self::Count { wildcard, count },
+                Self::Count {
+                    count: other_count, ..
+                },
+            ) => Ok(Self::Count {
+                wildcard: *wildcard,
+                count: count + other_count,
+            }),
+            (Self::Sum(value), Self::Sum(other_value)) => Ok(Self::Sum(value.add(other_value)?)),
+            (Self::Min(_), Self::Min(other_value)) | (Self::Max(_), Self::Max(other_value)) => {
+                Ok(self
+                    .accumulate(other_value)?
+                    .unwrap_or_else(|| self.clone()))
+            }
+            (
+                Self::Avg { sum, count },
+                Self::Avg {
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) => Ok(Self::Avg {
+                sum: sum.add(other_sum)?,
+                count: count + other_count,
+            }),
+            (
+                Self::Variance {
+                    sum_square,
+                    sum,
+                    count,
+                },
+                Self::Variance {
+                    sum_square: other_sum_square,
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) => Ok(Self::Variance {
+                sum_square: sum_square.add(other_sum_square)?,
+                sum: sum.add(other_sum)?,
+                count: count + other_count,
+            }),
+            (
+                Self::Stdev {
+                    sum_square,
+                    sum,
+                    count,
+                },
+                Self::Stdev {
+                    sum_square: other_sum_square,
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) => Ok(Self::Stdev {
+                sum_square: sum_square.add(other_sum_square)?,
+                sum: sum.add(other_sum)?,
+                count: count + other_count,
+            }),
+            _ => Err(AggregateError::UnreachableAggrValueMismatch.into()),
+        }
+    }
+
+    pub fn export(self) -> Result<Value> {
         let variance = |sum_square: Value, sum: Value, count: i64| {
             let sum_expr1 = sum_square.multiply(&Value::I64(count))?;
             let sum_expr2 = sum.multiply(&sum)?;
@@ -151,7 +220,8 @@ impl AggrValue {
 pub struct State<'a> {
     index: usize,
     group: Group,
-    values: IndexMap<(Group, &'a Aggregate), (usize, AggrValue)>,
+    values: IndexMap<(Group, &'a Expr), (usize, AggrValue)>,
+    distinct_values: HashSet<(Group, &'a Expr, Key)>,
     groups: HashSet<Group>,
     contexts: Vector<Rc<BlendContext<'a>>>,
     storage: &'a dyn GStore,
@@ -163,6 +233,7 @@ impl<'a> State<'a> {
             index: 0,
             group: Rc::new(vec![Key::None]),
             values: IndexMap::new(),
+            distinct_values: HashSet::new(),
             groups: HashSet::new(),
             contexts: Vector::new(),
             storage,
@@ -189,16 +260,16 @@ impl<'a> State<'a> {
         }
     }
 
-    fn update(self, aggr: &'a Aggregate, value: AggrValue) -> Self {
-        let key = (Rc::clone(&self.group), aggr);
+    fn update(self, expr: &'a Expr, value: AggrValue) -> Self {
+        let key = (Rc::clone(&self.group), expr);
         let (values, _) = self.values.insert(key, (self.index, value));
         Self { values, ..self }
     }
 
-    fn get(&self, aggr: &'a Aggregate) -> Option<&(usize, AggrValue)> {
+    fn get(&self, expr: &'a Expr) -> Option<&(usize, AggrValue)> {
         let group = Rc::clone(&self.group);
 
-        self.values.get(&(group, aggr))
+        self.values.get(&(group, expr))
     }
 
     pub fn export(self) -> Result<Vec<(Option<ValuesMap<'a>>, Option<Context<'a>>)>> {
@@ -224,10 +295,10 @@ impl<'a> State<'a> {
             .enumerate()
             .map(|(i, entries)| {
                 let aggregated = entries
-                    .map(|((_, aggr), (_, aggr_value))| {
-                        aggr_value.export().map(|value| (aggr, value))
+                    .map(|((_, expr), (_, aggr_value))| {
+                        aggr_value.export().map(|value| (expr, value))
                     })
-                    .collect::<Result<HashMap<&'a Aggregate, Value>>>()?;
+                    .collect::<Result<HashMap<&'a Expr, Value>>>()?;
                 let next = contexts.get(i).map(Rc::clone);
 
                 Ok((Some(aggregated), next))
@@ -238,28 +309,56 @@ impl<'a> State<'a> {
     pub async fn accumulate(
         self,
         filter_context: Option<Rc<FilterContext<'a>>>,
+        expr: &'a Expr,
         aggr: &'a Aggregate,
     ) -> Result<State<'a>> {
-        let value = match aggr {
-            Aggregate::Count(CountArgExpr::Wildcard) => Value::Null,
-            Aggregate::Count(CountArgExpr::Expr(expr))
-            | Aggregate::Sum(expr)
-            | Aggregate::Min(expr)
-            | Aggregate::Max(expr)
-            | Aggregate::Avg(expr)
-            | Aggregate::Variance(expr)
-            | Aggregate::Stdev(expr) => evaluate(self.storage, filter_context, None, expr)
+        let value = match &aggr.func {
+            AggregateFunction::Count(CountArgExpr::Wildcard) => Value::Null,
+            AggregateFunction::Count(CountArgExpr::Expr(arg))
+            | AggregateFunction::Sum(arg)
+            | AggregateFunction::Min(arg)
+            | AggregateFunction::Max(arg)
+            | AggregateFunction::Avg(arg)
+            | AggregateFunction::Variance(arg)
+            | AggregateFunction::Stdev(arg) => evaluate(self.storage, filter_context, None, arg)
                 .await?
                 .try_into()?,
         };
-        let aggr_value = match self.get(aggr) {
+
+        let state = if aggr.distinct {
+            let key = (Rc::clone(&self.group), expr, Key::try_from(&value)?);
+
+            if self.distinct_values.contains(&key) {
+                return Ok(self);
+            }
+
+            let distinct_values = self.distinct_values.update(key);
+
+            Self {
+                distinct_values,
+                ..self
+            }
+        } else {
+            self
+        };
+
+        state.accumulate_value(expr, aggr, value)
+    }
+
+    fn accumulate_value(
+        self,
+        expr: &'a Expr,
+        aggr: &'a Aggregate,
+        value: Value,
+    ) -> Result<State<'a>> {
+        let aggr_value = match self.get(expr) {
             Some((index, _)) if self.index <= *index => None,
             Some((_, aggr_value)) => aggr_value.accumulate(&value)?,
             None => Some(AggrValue::new(aggr, &value)?),
         };
 
         match aggr_value {
-            Some(aggr_value) => Ok(self.update(aggr, aggr_value)),
+            Some(aggr_value) => Ok(self.update(expr, aggr_value)),
             None => Ok(self),
         }
     }
@@ -1,20 +1,20 @@
 #![cfg(feature = "alter-table")]
 
 use {
-    super::validate,
+    super::{check_columns, validate, validate_namespace, AlterError},
     crate::{
-        ast::AlterTableOperation,
-        result::{MutResult, TrySelf},
+        ast::{AlterTableOperation, ColumnDef, ForeignKey},
+        data::{Schema, Sequence, ViewError},
+        result::{MutResult, Result, TrySelf},
         store::{GStore, GStoreMut},
     },
 };
 
 #[cfg(feature = "index")]
 use {
-    super::AlterError,
     crate::{
         ast::{Expr, Function},
-        data::{Schema, SchemaIndex},
+        data::SchemaIndex,
     },
     futures::stream::{self, TryStreamExt},
 };
@@ -27,17 +27,83 @@ pub async fn alter_table<T: GStore + GStoreMut>(
     match operation {
         AlterTableOperation::RenameTable {
             table_name: new_table_name,
-        } => storage.rename_schema(table_name, new_table_name).await,
+        } => {
+            let result = async {
+                validate_namespace(&storage, new_table_name).await?;
+
+                if storage.fetch_view(new_table_name).await?.is_some() {
+                    return Err(ViewError::AlreadyExists(new_table_name.to_owned()).into());
+                }
+
+                if let Some(schema) = storage.fetch_schema(table_name).await? {
+                    let identity_column = schema
+                        .column_defs
+                        .iter()
+                        .find(|column_def| column_def.is_identity());
+
+                    if let Some(ColumnDef { name, .. }) = identity_column {
+                        return Err(
+                            AlterError::AlterIdentityColumnNotSupported(name.to_owned()).into()
+                        );
+                    }
+                }
+
+                let referenced = storage.fetch_all_schemas().await?.iter().any(|schema| {
+                    schema
+                        .foreign_keys
+                        .iter()
+                        .any(|foreign_key| foreign_key.referenced_table_name == table_name)
+                });
+
+                match referenced {
+                    true => {
+                        Err(AlterError::TableReferencedByForeignKey(table_name.to_owned()).into())
+                    }
+                    false => Ok(()),
+                }
+            }
+            .await;
+
+            result
+                .try_self(storage)
+                .map(|(storage, _)| storage)?
+                .rename_schema(table_name, new_table_name)
+                .await
+        }
         AlterTableOperation::RenameColumn {
             old_column_name,
             new_column_name,
         } => {
-            storage
+            let result = async {
+                validate_foreign_key_column(&storage, table_name, old_column_name).await?;
+
+                match storage.fetch_schema(table_name).await? {
+                    Some(schema) => {
+                        validate_identity_column(&schema, old_column_name)?;
+                        validate_generated_column(&schema, old_column_name)?;
+                        validate_check_column(&schema, old_column_name, false)
+                    }
+                    None => Ok(()),
+                }
+            }
+            .await;
+
+            result
+                .try_self(storage)
+                .map(|(storage, _)| storage)?
                 .rename_column(table_name, old_column_name, new_column_name)
                 .await
         }
         AlterTableOperation::AddColumn { column_def } => {
-            validate(column_def)
+            let result = match column_def.is_identity() {
+                true => Err(AlterError::AlterIdentityColumnNotSupported(
+                    column_def.name.to_owned(),
+                )
+                .into()),
+                false => validate(column_def),
+            };
+
+            result
                 .try_self(storage)
                 .map(|(storage, _)| storage)?
                 .add_column(table_name, column_def)
@@ -47,24 +113,59 @@ pub async fn alter_table<T: GStore + GStoreMut>(
             column_name,
             if_exists,
         } => {
+            let schema = match storage.fetch_schema(table_name).await {
+                Ok(schema) => schema,
+                Err(e) => {
+                    return Err((storage, e));
+                }
+            };
+
+            let (storage, _) = validate_foreign_key_column(&storage, table_name, column_name)
+                .await
+                .try_self(storage)?;
+            let (storage, _) = schema
+                .as_ref()
+                .map(|schema| {
+                    validate_generated_column(schema, column_name)?;
+                    validate_check_column(schema, column_name, true)
+                })
+                .transpose()
+                .try_self(storage)?;
+
+            let identity = schema
+                .as_ref()
+                .map_or(false, |schema| is_identity_column(schema, column_name));
+
+            if let Some(Schema {
+                primary_key: Some(primary_key),
+                ..
+            }) = &schema
+            {
+                if primary_key.contains(column_name) {
+                    return Err((
+                        storage,
+                        AlterError::DropPrimaryKeyColumnNotSupported(column_name.to_owned()).into(),
+                    ));
+                }
+            }
+
             #[cfg(feature = "index")]
             let storage = {
-                let indexes = match storage.fetch_schema(table_name).await {
-                    Ok(Some(Schema { indexes, .. })) => indexes,
-                    Ok(None) => {
+                let indexes = match schema {
+                    Some(Schema { indexes, .. }) => indexes,
+                    None => {
                         return Err((
                             storage,
                             AlterError::TableNotFound(table_name.to_owned()).into(),
                         ));
                     }
-                    Err(e) => {
-                        return Err((storage, e));
-                    }
                 };
 
                 let indexes = indexes
                     .iter()
-                    .filter(|SchemaIndex { expr, .. }| find_column(expr, column_name))
+                    .filter(|SchemaIndex { exprs, .. }| {
+                        exprs.iter().any(|expr| find_column(expr, column_name))
+                    })
                     .map(Ok);
 
                 stream::iter(indexes)
@@ -77,11 +178,100 @@ pub async fn alter_table<T: GStore + GStoreMut>(
                     .await?
             };
 
-            storage
+            let (storage, _) = storage
                 .drop_column(table_name, column_name, *if_exists)
-                .await
+                .await?;
+
+            match identity {
+                true => {
+                    let Sequence { name, .. } = Sequence::identity(table_name, column_name);
+
+                    storage.delete_sequence(&name).await
+                }
+                false => Ok((storage, ())),
+            }
+        }
+    }
+}
+
+fn is_identity_column(schema: &Schema, column_name: &str) -> bool {
+    schema
+        .column_defs
+        .iter()
+        .any(|column_def| column_def.name == column_name && column_def.is_identity())
+}
+
+/// Identity columns cannot be renamed, their sequences are named after them.
+fn validate_identity_column(schema: &Schema, column_name: &str) -> Result<()> {
+    match is_identity_column(schema, column_name) {
+        true => Err(AlterError::AlterIdentityColumnNotSupported(column_name.to_owned()).into()),
+        false => Ok(()),
+    }
+}
+
+/// Columns of a FOREIGN KEY, on both the referencing and the referenced side,
+/// cannot be renamed or dropped.
+async fn validate_foreign_key_column<T: GStore>(
+    storage: &T,
+    table_name: &str,
+    column_name: &str,
+) -> Result<()> {
+    let schemas = storage.fetch_all_schemas().await?;
+    let found = schemas.iter().any(|schema| {
+        schema.foreign_keys.iter().any(
+            |ForeignKey {
+                 columns,
+                 referenced_table_name,
+                 referenced_columns,
+                 ..
+             }| {
+                (schema.table_name == table_name && columns.iter().any(|c| c == column_name))
+                    || (referenced_table_name == table_name
+                        && referenced_columns.iter().any(|c| c == column_name))
+            },
+        )
+    });
+
+    match found {
+        true => Err(AlterError::AlterForeignKeyColumnNotSupported(column_name.to_owned()).into()),
+        false => Ok(()),
+    }
+}
+
+/// Columns referred by generated columns cannot be renamed or dropped.
+fn validate_generated_column(schema: &Schema, column_name: &str) -> Result<()> {
+    let exprs = schema
+        .column_defs
+        .iter()
+        .filter_map(ColumnDef::get_generated);
+
+    for expr in exprs {
+        if check_columns(expr)?.contains(&column_name) {
+            return Err(
+                AlterError::AlterGeneratedColumnNotSupported(column_name.to_owned()).into(),
+            );
         }
     }
+
+    Ok(())
+}
+
+/// Columns referred by CHECK constraints cannot be renamed or dropped,
+/// except dropping a column along with its own CHECK constraint.
+fn validate_check_column(schema: &Schema, column_name: &str, drop: bool) -> Result<()> {
+    let column_checks = schema
+        .column_defs
+        .iter()
+        .filter(|ColumnDef { name, .. }| !drop || name != column_name)
+        .flat_map(ColumnDef::get_checks);
+
+    for expr in column_checks.chain(&schema.checks) {
+        if check_columns(expr)?.contains(&column_name) {
+            return Err(AlterError::AlterCheckColumnNotSupported(column_name.to_owned()).into());
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(feature = "index")]
@@ -11,10 +11,56 @@ pub enum AlterError {
     #[error("table already exists: {0}")]
     TableAlreadyExists(String),
 
-    // CREATE INDEX, DROP TABLE
+    // CREATE INDEX, DROP TABLE, ANALYZE
     #[error("table does not exist: {0}")]
     TableNotFound(String),
 
+    // CREATE SCHEMA
+    #[error("schema already exists: {0}")]
+    SchemaAlreadyExists(String),
+
+    // DROP SCHEMA, CREATE TABLE
+    #[error("schema does not exist: {0}")]
+    SchemaNotFound(String),
+
+    #[error("schema is not empty, use CASCADE to drop its tables: {0}")]
+    SchemaNotEmpty(String),
+
+    // FOREIGN KEY
+    #[error("referenced table does not exist: {0}")]
+    ReferencedTableNotFound(String),
+
+    #[error("referenced column does not exist: {0}")]
+    ReferencedColumnNotFound(String),
+
+    #[error("referenced columns must be the primary key or a unique column of table: {0}")]
+    ReferencedColumnsNotUnique(String),
+
+    #[error("foreign key has {columns} column(s) but references {referenced_columns} column(s)")]
+    ForeignKeyColumnCountMismatch {
+        columns: usize,
+        referenced_columns: usize,
+    },
+
+    #[error("foreign key column type does not match the referenced column: {0}")]
+    ForeignKeyTypeMismatch(String),
+
+    // DROP TABLE, ALTER TABLE
+    #[error("table is referenced by a foreign key: {0}")]
+    TableReferencedByForeignKey(String),
+
+    #[error("altering a column of a foreign key is not supported: {0}")]
+    AlterForeignKeyColumnNotSupported(String),
+
+    #[error("altering a column referred by a check constraint is not supported: {0}")]
+    AlterCheckColumnNotSupported(String),
+
+    #[error("altering an identity column is not supported: {0}")]
+    AlterIdentityColumnNotSupported(String),
+
+    #[error("altering a column referred by a generated column is not supported: {0}")]
+    AlterGeneratedColumnNotSupported(String),
+
     #[error("CTAS source table does not exist: {0}")]
     CtasSourceTableNotFound(String),
 
@@ -22,7 +68,22 @@ pub enum AlterError {
     #[error("column '{0}' of data type '{1:?}' is unsupported for unique constraint")]
     UnsupportedDataTypeForUniqueColumn(String, DataType),
 
+    #[error("column '{0}' of data type '{1:?}' is unsupported for identity column")]
+    UnsupportedDataTypeForIdentityColumn(String, DataType),
+
+    #[error("generated column cannot have a default value: {0}")]
+    GeneratedColumnWithDefault(String),
+
+    #[error("generated column cannot refer to a generated column: {0}")]
+    GeneratedColumnReferenced(String),
+
     // validate index expr
+    #[error("unsupported check constraint expr: {0}")]
+    UnsupportedCheckExpr(String),
+
+    #[error("unsupported generated column expr: {0}")]
+    UnsupportedGeneratedExpr(String),
+
     #[error("unsupported index expr: {0:#?}")]
     UnsupportedIndexExpr(Expr),
 
@@ -31,4 +92,8 @@ pub enum AlterError {
 
     #[error("duplicate column name: {0}")]
     DuplicateColumnName(String),
+
+    // DROP COLUMN
+    #[error("dropping a column of a multi-column primary key is not supported: {0}")]
+    DropPrimaryKeyColumnNotSupported(String),
 }
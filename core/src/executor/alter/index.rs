@@ -14,24 +14,25 @@ pub async fn create_index<T: GStore + GStoreMut>(
     storage: T,
     table_name: &str,
     index_name: &str,
-    column: &OrderByExpr,
+    columns: &[OrderByExpr],
 ) -> MutResult<T, ()> {
     let names = (|| async {
-        let expr = &column.expr;
         let Schema { column_defs, .. } = storage
             .fetch_schema(table_name)
             .await?
             .ok_or_else(|| AlterError::TableNotFound(table_name.to_owned()))?;
-        let columns = column_defs
+        let column_names = column_defs
             .into_iter()
             .map(|ColumnDef { name, .. }| name)
             .collect::<Vec<_>>();
 
-        let (valid, has_ident) = validate_index_expr(&columns, expr);
-        if !valid {
-            return Err(AlterError::UnsupportedIndexExpr(expr.clone()).into());
-        } else if !has_ident {
-            return Err(AlterError::IdentifierNotFound(expr.clone()).into());
+        for OrderByExpr { expr, .. } in columns {
+            let (valid, has_ident) = validate_index_expr(&column_names, expr);
+            if !valid {
+                return Err(AlterError::UnsupportedIndexExpr(expr.clone()).into());
+            } else if !has_ident {
+                return Err(AlterError::IdentifierNotFound(expr.clone()).into());
+            }
         }
 
         Ok((table_name, index_name))
@@ -45,7 +46,7 @@ pub async fn create_index<T: GStore + GStoreMut>(
         }
     };
 
-    storage.create_index(table_name, index_name, column).await
+    storage.create_index(table_name, index_name, columns).await
 }
 
 fn validate_index_expr(columns: &[String], expr: &Expr) -> (bool, bool) {
@@ -53,7 +54,7 @@ fn validate_index_expr(columns: &[String], expr: &Expr) -> (bool, bool) {
 
     match expr {
         Expr::Identifier(ident) => (columns.iter().any(|column| column == ident), true),
-        Expr::Literal(_) | Expr::TypedString { .. } => (true, false),
+        Expr::Literal(_) | Expr::Placeholder(_) | Expr::TypedString { .. } => (true, false),
         Expr::Nested(expr) => validate(expr),
         Expr::BinaryOp { left, right, .. } => {
             let (valid_l, has_ident_l) = validate(left);
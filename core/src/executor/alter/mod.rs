@@ -1,14 +1,27 @@
 mod alter_table;
 mod error;
 mod index;
+mod schema;
+mod sequence;
+mod statistics;
 mod table;
 mod validate;
+mod view;
 
-use validate::{validate, validate_column_names};
+use {
+    schema::validate_namespace,
+    sequence::{create_identity_sequences, drop_identity_sequences},
+    validate::{validate, validate_check, validate_column_names, validate_generated},
+};
 
 #[cfg(feature = "alter-table")]
 pub use alter_table::alter_table;
 pub use error::AlterError;
 #[cfg(feature = "index")]
 pub use index::create_index;
+pub use schema::{create_schema, drop_schema};
+pub use sequence::{create_sequence, drop_sequence};
+pub use statistics::analyze_table;
 pub use table::{create_table, drop_table};
+pub use validate::check_columns;
+pub use view::{create_view, drop_view};
@@ -0,0 +1,100 @@
+use {
+    super::{drop_table, drop_view, AlterError},
+    crate::{
+        result::{MutResult, Result, TrySelf},
+        store::{GStore, GStoreMut},
+    },
+    futures::stream::{self, TryStreamExt},
+};
+
+async fn namespace_exists<T: GStore>(storage: &T, namespace: &str) -> Result<bool> {
+    let namespaces = storage.fetch_namespaces().await?;
+
+    Ok(namespaces.iter().any(|name| name == namespace))
+}
+
+/// Checks the schema of a qualified `schema.table` name exists.
+pub async fn validate_namespace<T: GStore>(storage: &T, table_name: &str) -> Result<()> {
+    let namespace = match table_name.split_once('.') {
+        Some((namespace, _)) => namespace,
+        None => {
+            return Ok(());
+        }
+    };
+
+    match namespace_exists(storage, namespace).await? {
+        true => Ok(()),
+        false => Err(AlterError::SchemaNotFound(namespace.to_owned()).into()),
+    }
+}
+
+pub async fn create_schema<T: GStore + GStoreMut>(
+    storage: T,
+    name: &str,
+    if_not_exists: bool,
+) -> MutResult<T, ()> {
+    let (storage, exists) = namespace_exists(&storage, name).await.try_self(storage)?;
+
+    match (exists, if_not_exists) {
+        (false, _) => storage.insert_namespace(name).await,
+        (true, true) => Ok((storage, ())),
+        (true, false) => Err((
+            storage,
+            AlterError::SchemaAlreadyExists(name.to_owned()).into(),
+        )),
+    }
+}
+
+pub async fn drop_schema<T: GStore + GStoreMut>(
+    storage: T,
+    names: &[String],
+    if_exists: bool,
+    cascade: bool,
+) -> MutResult<T, ()> {
+    stream::iter(names.iter().map(Ok))
+        .try_fold((storage, ()), |(storage, _), name| async move {
+            let names = (|| async {
+                if !namespace_exists(&storage, name).await? {
+                    return match if_exists {
+                        true => Ok(None),
+                        false => Err(AlterError::SchemaNotFound(name.to_owned()).into()),
+                    };
+                }
+
+                let table_names = storage
+                    .fetch_all_schemas()
+                    .await?
+                    .into_iter()
+                    .filter(|schema| schema.namespace() == Some(name.as_str()))
+                    .map(|schema| schema.table_name)
+                    .collect::<Vec<_>>();
+                let view_names = storage
+                    .fetch_all_views()
+                    .await?
+                    .into_iter()
+                    .filter(|view| view.namespace() == Some(name.as_str()))
+                    .map(|view| view.name)
+                    .collect::<Vec<_>>();
+
+                if !cascade && !(table_names.is_empty() && view_names.is_empty()) {
+                    return Err(AlterError::SchemaNotEmpty(name.to_owned()).into());
+                }
+
+                Ok(Some((table_names, view_names)))
+            })()
+            .await;
+
+            let (storage, (table_names, view_names)) = match names.try_self(storage)? {
+                (storage, Some(names)) => (storage, names),
+                (storage, None) => {
+                    return Ok((storage, ()));
+                }
+            };
+
+            let (storage, _) = drop_view(storage, &view_names, false).await?;
+            let (storage, _) = drop_table(storage, &table_names, false).await?;
+
+            storage.delete_namespace(name).await
+        })
+        .await
+}
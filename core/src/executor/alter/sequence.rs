@@ -0,0 +1,109 @@
+use {
+    crate::{
+        ast::{ColumnDef, Expr},
+        data::{Schema, Sequence, SequenceError, Value},
+        executor::evaluate_stateless,
+        result::{MutResult, Result, TrySelf},
+        store::{GStore, GStoreMut},
+    },
+    futures::stream::{self, TryStreamExt},
+};
+
+fn evaluate_option(expr: Option<&Expr>, default: i64) -> Result<i64> {
+    match expr {
+        Some(expr) => evaluate_stateless(None, expr)
+            .and_then(Value::try_from)
+            .and_then(|value| i64::try_from(&value)),
+        None => Ok(default),
+    }
+}
+
+pub async fn create_sequence<T: GStore + GStoreMut>(
+    storage: T,
+    name: &str,
+    increment: Option<&Expr>,
+    start: Option<&Expr>,
+    if_not_exists: bool,
+) -> MutResult<T, ()> {
+    let sequence = (|| async {
+        let increment = evaluate_option(increment, 1)?;
+        let start = evaluate_option(start, 1)?;
+
+        if increment == 0 {
+            return Err(SequenceError::ZeroIncrement(name.to_owned()).into());
+        }
+
+        match (storage.fetch_sequence(name).await?, if_not_exists) {
+            (None, _) => Ok(Some(Sequence {
+                name: name.to_owned(),
+                start,
+                increment,
+            })),
+            (Some(_), true) => Ok(None),
+            (Some(_), false) => Err(SequenceError::AlreadyExists(name.to_owned()).into()),
+        }
+    })()
+    .await;
+
+    match sequence.try_self(storage)? {
+        (storage, Some(sequence)) => storage.insert_sequence(&sequence).await,
+        (storage, None) => Ok((storage, ())),
+    }
+}
+
+pub async fn drop_sequence<T: GStore + GStoreMut>(
+    storage: T,
+    names: &[String],
+    if_exists: bool,
+) -> MutResult<T, ()> {
+    stream::iter(names.iter().map(Ok))
+        .try_fold((storage, ()), |(storage, _), name| async move {
+            let (storage, sequence) = storage.fetch_sequence(name).await.try_self(storage)?;
+
+            match (sequence, if_exists) {
+                (Some(_), _) => storage.delete_sequence(name).await,
+                (None, true) => Ok((storage, ())),
+                (None, false) => Err((storage, SequenceError::NotFound(name.to_owned()).into())),
+            }
+        })
+        .await
+}
+
+/// Sequences which back the identity columns of the table.
+pub fn identity_sequences<'a>(
+    table_name: &'a str,
+    column_defs: &'a [ColumnDef],
+) -> impl Iterator<Item = Sequence> + 'a {
+    column_defs
+        .iter()
+        .filter(|column_def| column_def.is_identity())
+        .map(move |ColumnDef { name, .. }| Sequence::identity(table_name, name))
+}
+
+/// Creates the sequences of the identity columns along with the table.
+pub async fn create_identity_sequences<T: GStore + GStoreMut>(
+    storage: T,
+    schema: &Schema,
+) -> MutResult<T, ()> {
+    let sequences = identity_sequences(&schema.table_name, &schema.column_defs).map(Ok);
+
+    stream::iter(sequences)
+        .try_fold((storage, ()), |(storage, _), sequence| async move {
+            storage.insert_sequence(&sequence).await
+        })
+        .await
+}
+
+/// Drops the sequences of the identity columns along with the table.
+pub async fn drop_identity_sequences<T: GStore + GStoreMut>(
+    storage: T,
+    schema: &Schema,
+) -> MutResult<T, ()> {
+    let sequences = identity_sequences(&schema.table_name, &schema.column_defs).map(Ok);
+
+    stream::iter(sequences)
+        .try_fold((storage, ()), |(storage, _), Sequence { name, .. }| async move {
+            storage.delete_sequence(&name).await
+        })
+        .await
+}
@@ -0,0 +1,74 @@
+use {
+    super::AlterError,
+    crate::{
+        ast::ColumnDef,
+        data::{ColumnStatistics, Key, Row, Schema, Statistics, Value},
+        result::{MutResult, Result, TrySelf},
+        store::{GStore, GStoreMut},
+    },
+    chrono::Utc,
+    std::collections::HashSet,
+};
+
+/// Scans the whole table and stores its row count and the distinct and null counts of each
+/// column with the schema.
+pub async fn analyze_table<T: GStore + GStoreMut>(
+    storage: T,
+    table_name: &str,
+) -> MutResult<T, ()> {
+    let statistics = collect(&storage, table_name).await;
+    let (storage, statistics) = statistics.try_self(storage)?;
+
+    storage.update_statistics(table_name, &statistics).await
+}
+
+async fn collect(storage: &dyn GStore, table_name: &str) -> Result<Statistics> {
+    let Schema { column_defs, .. } = storage
+        .fetch_schema(table_name)
+        .await?
+        .ok_or_else(|| AlterError::TableNotFound(table_name.to_owned()))?;
+
+    let mut row_count = 0;
+    let mut keys = vec![HashSet::new(); column_defs.len()];
+    let mut unhashable_counts = vec![0; column_defs.len()];
+    let mut null_counts = vec![0; column_defs.len()];
+
+    for item in storage.scan_data(table_name).await? {
+        let (_, Row(values)) = item?;
+        row_count += 1;
+
+        for (i, value) in values.iter().take(column_defs.len()).enumerate() {
+            if matches!(value, Value::Null) {
+                null_counts[i] += 1;
+                continue;
+            }
+
+            // maps and lists cannot be hashed, each of them is counted as a distinct value
+            match Key::try_from(value) {
+                Ok(key) => {
+                    keys[i].insert(key);
+                }
+                Err(_) => unhashable_counts[i] += 1,
+            }
+        }
+    }
+
+    let columns = column_defs
+        .into_iter()
+        .zip(keys)
+        .zip(unhashable_counts.into_iter().zip(null_counts))
+        .map(
+            |((ColumnDef { name, .. }, keys), (unhashable_count, null_count))| ColumnStatistics {
+                name,
+                distinct_count: keys.len() + unhashable_count,
+                null_count,
+            },
+        )
+        .collect();
+
+    Ok(Statistics {
+        row_count,
+        columns,
+        analyzed: Utc::now().naive_utc(),
+    })
+}
@@ -1,9 +1,16 @@
 use {
-    super::{validate, validate_column_names, AlterError},
+    super::{
+        create_identity_sequences, drop_identity_sequences, validate, validate_check,
+        validate_column_names, validate_generated, validate_namespace, AlterError,
+    },
     crate::{
-        ast::{ColumnDef, Query, SetExpr, TableFactor, Values},
-        data::{Schema, TableError},
-        executor::{evaluate_stateless, select::select},
+        ast::{ColumnDef, ColumnOption, Expr, ForeignKey, Query, SetExpr, TableFactor, Values},
+        data::{Schema, TableError, ViewError},
+        executor::{
+            context::FilterContext,
+            evaluate_stateless,
+            select::{leftmost_operand, select},
+        },
         prelude::{DataType, Value},
         result::{Error, IntoControlFlow, MutResult, Result, TrySelf},
         store::{GStore, GStoreMut},
@@ -13,19 +20,25 @@ use {
     std::{
         iter,
         ops::ControlFlow::{Break, Continue},
+        rc::Rc,
     },
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_table<T: GStore + GStoreMut>(
     storage: T,
     target_table_name: &str,
     column_defs: &[ColumnDef],
+    primary_key: &Option<Vec<String>>,
+    foreign_keys: &[ForeignKey],
+    checks: &[Expr],
     if_not_exists: bool,
     source: &Option<Box<Query>>,
+    filter_context: Option<Rc<FilterContext<'_>>>,
 ) -> MutResult<T, ()> {
     let schema = (|| async {
         let target_columns_defs = match source.as_ref().map(AsRef::as_ref) {
-            Some(Query { body, .. }) => match body {
+            Some(Query { body, .. }) => match leftmost_operand(body) {
                 SetExpr::Select(select_query) => match &select_query.from.relation {
                     TableFactor::Table { name, .. } => {
                         let schema = storage.fetch_schema(name).await?;
@@ -37,6 +50,22 @@ pub async fn create_table<T: GStore + GStoreMut>(
                         })?;
 
                         source_column_defs
+                            .into_iter()
+                            .map(|column_def| ColumnDef {
+                                options: column_def
+                                    .options
+                                    .into_iter()
+                                    .filter(|option| {
+                                        !matches!(
+                                            option,
+                                            ColumnOption::Identity { .. }
+                                                | ColumnOption::Generated(_)
+                                        )
+                                    })
+                                    .collect(),
+                                ..column_def
+                            })
+                            .collect()
                     }
                     TableFactor::Series { .. } => {
                         let column_def = ColumnDef {
@@ -99,14 +128,21 @@ pub async fn create_table<T: GStore + GStoreMut>(
 
                     column_defs
                 }
+                SetExpr::SetOperation { .. } => {
+                    return Err(Error::Table(TableError::Unreachable));
+                }
             },
             None => column_defs.to_vec(),
         };
 
-        let schema = Schema {
+        let mut schema = Schema {
             table_name: target_table_name.to_owned(),
             column_defs: target_columns_defs,
+            primary_key: primary_key.clone(),
+            foreign_keys: Vec::new(),
+            checks: checks.to_vec(),
             indexes: vec![],
+            statistics: None,
             created: Utc::now().naive_utc(),
         };
 
@@ -116,6 +152,30 @@ pub async fn create_table<T: GStore + GStoreMut>(
             validate(column_def)?;
         }
 
+        for expr in schema.all_checks() {
+            validate_check(&schema.column_defs, expr)?;
+        }
+
+        for expr in schema
+            .column_defs
+            .iter()
+            .filter_map(ColumnDef::get_generated)
+        {
+            validate_generated(&schema.column_defs, expr)?;
+        }
+
+        for foreign_key in foreign_keys {
+            let foreign_key = validate_foreign_key(&storage, &schema, foreign_key).await?;
+
+            schema.foreign_keys.push(foreign_key);
+        }
+
+        validate_namespace(&storage, &schema.table_name).await?;
+
+        if storage.fetch_view(&schema.table_name).await?.is_some() {
+            return Err(ViewError::AlreadyExists(schema.table_name.to_owned()).into());
+        }
+
         match (
             storage.fetch_schema(&schema.table_name).await?,
             if_not_exists,
@@ -130,15 +190,24 @@ pub async fn create_table<T: GStore + GStoreMut>(
     .await;
 
     let storage = match schema.try_self(storage)? {
-        (storage, Some(schema)) => storage.insert_schema(&schema).await?.0,
+        (storage, Some(schema)) => {
+            let (storage, _) = storage.insert_schema(&schema).await?;
+
+            create_identity_sequences(storage, &schema).await?.0
+        }
         (storage, None) => storage,
     };
 
     match source {
         Some(q) => {
-            let (storage, rows) = async { select(&storage, q, None).await?.try_collect().await }
-                .await
-                .try_self(storage)?;
+            let (storage, rows) = async {
+                select(&storage, q, filter_context)
+                    .await?
+                    .try_collect()
+                    .await
+            }
+            .await
+            .try_self(storage)?;
 
             storage.append_data(target_table_name, rows).await
         }
@@ -146,6 +215,102 @@ pub async fn create_table<T: GStore + GStoreMut>(
     }
 }
 
+/// Resolves the referenced columns of a FOREIGN KEY, which must be the primary key
+/// or a unique column of the referenced table.
+async fn validate_foreign_key<T: GStore>(
+    storage: &T,
+    schema: &Schema,
+    foreign_key: &ForeignKey,
+) -> Result<ForeignKey> {
+    let ForeignKey {
+        columns,
+        referenced_table_name,
+        referenced_columns,
+        ..
+    } = foreign_key;
+
+    let referenced_schema = match referenced_table_name == &schema.table_name {
+        true => schema.clone(),
+        false => storage
+            .fetch_schema(referenced_table_name)
+            .await?
+            .ok_or_else(|| AlterError::ReferencedTableNotFound(referenced_table_name.to_owned()))?,
+    };
+    let primary_key = referenced_schema.primary_key_indexes().map(|indexes| {
+        indexes
+            .into_iter()
+            .map(|i| referenced_schema.column_defs[i].name.clone())
+            .collect::<Vec<_>>()
+    });
+
+    let referenced_columns = match (referenced_columns.is_empty(), &primary_key) {
+        (false, _) => referenced_columns.clone(),
+        (true, Some(primary_key)) => primary_key.clone(),
+        (true, None) => {
+            return Err(
+                AlterError::ReferencedColumnsNotUnique(referenced_table_name.to_owned()).into(),
+            );
+        }
+    };
+
+    if columns.len() != referenced_columns.len() {
+        return Err(AlterError::ForeignKeyColumnCountMismatch {
+            columns: columns.len(),
+            referenced_columns: referenced_columns.len(),
+        }
+        .into());
+    }
+
+    let find_column_def = |column_defs: &'_ [ColumnDef], column_name: &str| {
+        column_defs
+            .iter()
+            .find(|ColumnDef { name, .. }| name == column_name)
+            .cloned()
+    };
+
+    for (column_name, referenced_column_name) in columns.iter().zip(&referenced_columns) {
+        let column_def = find_column_def(&schema.column_defs, column_name)
+            .ok_or_else(|| AlterError::IdentifierNotFound(Expr::Identifier(column_name.clone())))?;
+        let referenced_column_def =
+            find_column_def(&referenced_schema.column_defs, referenced_column_name).ok_or_else(
+                || AlterError::ReferencedColumnNotFound(referenced_column_name.to_owned()),
+            )?;
+
+        if column_def.data_type != referenced_column_def.data_type {
+            return Err(AlterError::ForeignKeyTypeMismatch(column_name.to_owned()).into());
+        }
+    }
+
+    let is_primary_key = primary_key.map_or(false, |primary_key| {
+        primary_key.len() == referenced_columns.len()
+            && primary_key
+                .iter()
+                .all(|column_name| referenced_columns.contains(column_name))
+    });
+    let is_unique_column = match referenced_columns.as_slice() {
+        [column_name] => find_column_def(&referenced_schema.column_defs, column_name).map_or(
+            false,
+            |ColumnDef { options, .. }| {
+                options
+                    .iter()
+                    .any(|option| matches!(option, ColumnOption::Unique { .. }))
+            },
+        ),
+        _ => false,
+    };
+
+    if !is_primary_key && !is_unique_column {
+        return Err(
+            AlterError::ReferencedColumnsNotUnique(referenced_table_name.to_owned()).into(),
+        );
+    }
+
+    Ok(ForeignKey {
+        referenced_columns,
+        ..foreign_key.clone()
+    })
+}
+
 pub async fn drop_table<T: GStore + GStoreMut>(
     storage: T,
     table_names: &[String],
@@ -156,22 +321,39 @@ pub async fn drop_table<T: GStore + GStoreMut>(
             let schema = (|| async {
                 let schema = storage.fetch_schema(table_name).await?;
 
-                if !if_exists {
-                    schema.ok_or_else(|| AlterError::TableNotFound(table_name.to_owned()))?;
+                if !if_exists && schema.is_none() {
+                    return Err(AlterError::TableNotFound(table_name.to_owned()).into());
                 }
 
-                Ok(table_name)
+                let referenced = storage
+                    .fetch_all_schemas()
+                    .await?
+                    .into_iter()
+                    .any(|schema| {
+                        !table_names.contains(&schema.table_name)
+                            && schema
+                                .foreign_keys
+                                .iter()
+                                .any(|foreign_key| &foreign_key.referenced_table_name == table_name)
+                    });
+
+                if referenced {
+                    return Err(
+                        AlterError::TableReferencedByForeignKey(table_name.to_owned()).into(),
+                    );
+                }
+
+                Ok(schema)
             })()
             .await;
 
-            let schema = match schema {
-                Ok(s) => s,
-                Err(e) => {
-                    return Err((storage, e));
-                }
-            };
+            let (storage, schema) = schema.try_self(storage)?;
+            let (storage, _) = storage.delete_schema(table_name).await?;
 
-            storage.delete_schema(schema).await
+            match schema {
+                Some(schema) => drop_identity_sequences(storage, &schema).await,
+                None => Ok((storage, ())),
+            }
         })
         .await
 }
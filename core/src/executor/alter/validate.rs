@@ -1,8 +1,9 @@
 use {
     super::AlterError,
     crate::{
-        ast::{ColumnDef, ColumnOption, DataType},
+        ast::{ColumnDef, ColumnOption, DataType, Expr, ToSql},
         executor::evaluate_stateless,
+        plan::PlanExpr,
         result::Result,
     },
 };
@@ -28,6 +29,33 @@ pub fn validate(column_def: &ColumnDef) -> Result<()> {
         .into());
     }
 
+    // identity + data type
+    if column_def.is_identity()
+        && !matches!(
+            data_type,
+            DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int
+                | DataType::Int128
+                | DataType::Uint8
+                | DataType::Uint16
+        )
+    {
+        return Err(AlterError::UnsupportedDataTypeForIdentityColumn(
+            name.to_owned(),
+            data_type.clone(),
+        )
+        .into());
+    }
+
+    // generated + default
+    if column_def.get_generated().is_some()
+        && (column_def.get_default().is_some() || column_def.is_identity())
+    {
+        return Err(AlterError::GeneratedColumnWithDefault(name.to_owned()).into());
+    }
+
     let default = options.iter().find_map(|option| match option {
         ColumnOption::Default(expr) => Some(expr),
         _ => None,
@@ -40,6 +68,76 @@ pub fn validate(column_def: &ColumnDef) -> Result<()> {
     Ok(())
 }
 
+/// Checks every column referred by a CHECK constraint exists in the table.
+pub fn validate_check(column_defs: &[ColumnDef], expr: &Expr) -> Result<()> {
+    for column_name in check_columns(expr)? {
+        if !column_defs
+            .iter()
+            .any(|ColumnDef { name, .. }| name == column_name)
+        {
+            return Err(
+                AlterError::IdentifierNotFound(Expr::Identifier(column_name.to_owned())).into(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the expression of a generated column refers to the existing columns
+/// which are not generated.
+pub fn validate_generated(column_defs: &[ColumnDef], expr: &Expr) -> Result<()> {
+    let column_names =
+        check_columns(expr).map_err(|_| AlterError::UnsupportedGeneratedExpr(expr.to_sql()))?;
+
+    for column_name in column_names {
+        match column_defs
+            .iter()
+            .find(|ColumnDef { name, .. }| name == column_name)
+        {
+            None => {
+                return Err(AlterError::IdentifierNotFound(Expr::Identifier(
+                    column_name.to_owned(),
+                ))
+                .into());
+            }
+            Some(column_def) if column_def.get_generated().is_some() => {
+                return Err(AlterError::GeneratedColumnReferenced(column_name.to_owned()).into());
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Columns referred by a CHECK constraint, which cannot contain aggregates or subqueries.
+pub fn check_columns(expr: &Expr) -> Result<Vec<&str>> {
+    if matches!(expr, Expr::Aggregate(_) | Expr::Window(_)) {
+        return Err(AlterError::UnsupportedCheckExpr(expr.to_sql()).into());
+    }
+
+    let exprs = match PlanExpr::from(expr) {
+        PlanExpr::None => Vec::new(),
+        PlanExpr::Identifier(ident) | PlanExpr::CompoundIdentifier { ident, .. } => {
+            return Ok(vec![ident]);
+        }
+        PlanExpr::Expr(expr) => vec![expr],
+        PlanExpr::TwoExprs(expr, expr2) => vec![expr, expr2],
+        PlanExpr::ThreeExprs(expr, expr2, expr3) => vec![expr, expr2, expr3],
+        PlanExpr::MultiExprs(exprs) => exprs,
+        PlanExpr::Query(_) | PlanExpr::QueryAndExpr { .. } => {
+            return Err(AlterError::UnsupportedCheckExpr(expr.to_sql()).into());
+        }
+    };
+
+    exprs
+        .into_iter()
+        .map(check_columns)
+        .collect::<Result<Vec<_>>>()
+        .map(|columns| columns.concat())
+}
+
 pub fn validate_column_names(column_defs: &[ColumnDef]) -> Result<()> {
     let duplicate_colum_name = column_defs
         .iter()
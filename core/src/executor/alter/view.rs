@@ -0,0 +1,66 @@
+use {
+    super::{validate_namespace, AlterError},
+    crate::{
+        ast::Query,
+        data::{View, ViewError},
+        plan::check_view_recursion,
+        result::{MutResult, TrySelf},
+        store::{GStore, GStoreMut},
+    },
+    chrono::Utc,
+    futures::stream::{self, TryStreamExt},
+};
+
+pub async fn create_view<T: GStore + GStoreMut>(
+    storage: T,
+    name: &str,
+    columns: &[String],
+    query: &Query,
+    or_replace: bool,
+) -> MutResult<T, ()> {
+    let view = (|| async {
+        validate_namespace(&storage, name).await?;
+
+        if storage.fetch_schema(name).await?.is_some() {
+            return Err(AlterError::TableAlreadyExists(name.to_owned()).into());
+        }
+
+        if !or_replace && storage.fetch_view(name).await?.is_some() {
+            return Err(ViewError::AlreadyExists(name.to_owned()).into());
+        }
+
+        let view = View {
+            name: name.to_owned(),
+            columns: columns.to_vec(),
+            query: query.clone(),
+            created: Utc::now().naive_utc(),
+        };
+
+        check_view_recursion(&storage, &view).await?;
+
+        Ok(view)
+    })()
+    .await;
+
+    let (storage, view) = view.try_self(storage)?;
+
+    storage.insert_view(&view).await
+}
+
+pub async fn drop_view<T: GStore + GStoreMut>(
+    storage: T,
+    names: &[String],
+    if_exists: bool,
+) -> MutResult<T, ()> {
+    stream::iter(names.iter().map(Ok))
+        .try_fold((storage, ()), |(storage, _), name| async move {
+            let (storage, view) = storage.fetch_view(name).await.try_self(storage)?;
+
+            match (view, if_exists) {
+                (Some(_), _) => storage.delete_view(name).await,
+                (None, true) => Ok((storage, ())),
+                (None, false) => Err((storage, ViewError::NotFound(name.to_owned()).into())),
+            }
+        })
+        .await
+}
@@ -1,12 +1,12 @@
 use {
     super::BlendContext,
-    crate::{ast::Aggregate, data::Value},
+    crate::{ast::Expr, data::Value},
     im_rc::HashMap,
     std::{fmt::Debug, rc::Rc},
 };
 
 #[derive(Debug)]
 pub struct AggregateContext<'a> {
-    pub aggregated: Option<HashMap<&'a Aggregate, Value>>,
+    pub aggregated: Option<HashMap<&'a Expr, Value>>,
     pub next: Rc<BlendContext<'a>>,
 }
@@ -14,6 +14,8 @@ pub struct BlendContext<'a> {
     table_alias: &'a str,
     columns: Rc<[String]>,
     row: BlendContextRow,
+    /// Columns shared with the left side through `USING` or `NATURAL`
+    merged_columns: Rc<[String]>,
     next: Option<Rc<BlendContext<'a>>>,
 }
 
@@ -28,15 +30,52 @@ impl<'a> BlendContext<'a> {
             table_alias,
             columns,
             row,
+            merged_columns: Rc::from(Vec::new()),
             next,
         }
     }
 
+    pub fn with_merged_columns(self, merged_columns: Rc<[String]>) -> Self {
+        Self {
+            merged_columns,
+            ..self
+        }
+    }
+
+    /// Shared row of the leftmost table, which the rows of the other tables are joined to
+    pub fn get_leftmost_row(&self) -> Option<&Rc<Row>> {
+        match (&self.next, &self.row) {
+            (Some(next), _) => next.get_leftmost_row(),
+            (None, BlendContextRow::Shared(row)) => Some(row),
+            (None, BlendContextRow::Single(_)) => None,
+        }
+    }
+
+    fn is_merged(&self, target: &str) -> bool {
+        self.merged_columns.iter().any(|column| column == target)
+    }
+
     pub fn get_value(&'a self, target: &str) -> Option<&'a Value> {
+        if self.is_merged(target) {
+            let value = self
+                .next
+                .as_ref()
+                .and_then(|context| context.get_value(target))
+                .filter(|value| !value.is_null());
+
+            if value.is_some() {
+                return value;
+            }
+        }
+
         let value = match &self.row {
             BlendContextRow::Shared(row) => row.get_value(&self.columns, target),
             BlendContextRow::Single(Some(row)) => row.get_value(&self.columns, target),
-            BlendContextRow::Single(None) => Some(&Value::Null),
+            BlendContextRow::Single(None) => self
+                .columns
+                .iter()
+                .any(|column| column == target)
+                .then_some(&Value::Null),
         };
 
         if value.is_some() {
@@ -57,7 +96,11 @@ impl<'a> BlendContext<'a> {
             match &self.row {
                 BlendContextRow::Shared(row) => row.get_value(&self.columns, target),
                 BlendContextRow::Single(Some(row)) => row.get_value(&self.columns, target),
-                BlendContextRow::Single(None) => Some(&Value::Null),
+                BlendContextRow::Single(None) => self
+                    .columns
+                    .iter()
+                    .any(|column| column == target)
+                    .then_some(&Value::Null),
             }
         })();
 
@@ -87,19 +130,45 @@ impl<'a> BlendContext<'a> {
     }
 
     pub fn get_all_values(&'a self) -> Vec<Value> {
+        self.get_labeled_values()
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    fn get_labeled_values(&'a self) -> Vec<(&'a str, Value)> {
         let values: Vec<Value> = match &self.row {
             BlendContextRow::Shared(row) => row.0.clone(),
             BlendContextRow::Single(Some(row)) => row.0.clone(),
             BlendContextRow::Single(None) => self.columns.iter().map(|_| Value::Null).collect(),
         };
 
-        match &self.next {
-            Some(next) => next
-                .get_all_values()
-                .into_iter()
-                .chain(values.into_iter())
-                .collect(),
-            None => values,
+        let mut labeled_values = match &self.next {
+            Some(next) => next.get_labeled_values(),
+            None => Vec::new(),
+        };
+
+        for (column, value) in self.columns.iter().zip(values) {
+            let merged = self
+                .is_merged(column)
+                .then(|| {
+                    labeled_values
+                        .iter_mut()
+                        .rev()
+                        .find(|(label, _)| *label == column.as_str())
+                })
+                .flatten();
+
+            match merged {
+                Some((_, merged_value)) => {
+                    if merged_value.is_null() {
+                        *merged_value = value;
+                    }
+                }
+                None => labeled_values.push((column.as_str(), value)),
+            }
         }
+
+        labeled_values
     }
 }
@@ -1,7 +1,11 @@
 use {
     super::BlendContext,
-    crate::data::{Row, Value},
-    std::{fmt::Debug, rc::Rc},
+    crate::{
+        ast::Query,
+        data::{Row, Value},
+        executor::ExecuteOptions,
+    },
+    std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc},
 };
 
 #[derive(Debug)]
@@ -11,6 +15,10 @@ enum Content<'a> {
         columns: Rc<[String]>,
         row: &'a Row,
     },
+    /// Values of the scalar subqueries the planner marked as cached
+    Cache(RefCell<HashMap<*const Query, Value>>),
+    /// Options of the statement being executed, at the root of the contexts
+    Options(ExecuteOptions),
     None,
 }
 
@@ -50,6 +58,69 @@ impl<'a> FilterContext<'a> {
         }
     }
 
+    /// Scope of the cached scalar subqueries, which lasts as long as the storage seen by them
+    /// stays the same.
+    pub fn with_subquery_cache(next: Option<Rc<FilterContext<'a>>>) -> Self {
+        Self {
+            content: Content::Cache(RefCell::new(HashMap::new())),
+            next,
+            next2: None,
+        }
+    }
+
+    pub fn with_options(options: ExecuteOptions) -> Self {
+        Self {
+            content: Content::Options(options),
+            next: None,
+            next2: None,
+        }
+    }
+
+    /// Whether the context is the root, which carries the options but no outer rows.
+    pub fn is_root(&self) -> bool {
+        matches!(self.content, Content::Options(_))
+    }
+
+    /// Context of a query which does not see the outer rows, only the options are kept.
+    pub fn detach(context: Option<&FilterContext<'_>>) -> Self {
+        let options = context.map(FilterContext::options).unwrap_or_default();
+
+        Self::with_options(options)
+    }
+
+    /// Options of the statement, the default ones when it is executed without any.
+    pub fn options(&self) -> ExecuteOptions {
+        match (&self.content, &self.next) {
+            (Content::Options(options), _) => *options,
+            (_, Some(next)) => next.options(),
+            (_, None) => ExecuteOptions::default(),
+        }
+    }
+
+    /// `None` until the subquery is cached in the nearest scope.
+    pub fn get_cached_subquery(&self, query: &Query) -> Option<Value> {
+        match &self.content {
+            Content::Cache(cache) => cache.borrow().get(&(query as *const Query)).cloned(),
+            Content::Some { .. } | Content::Options(_) | Content::None => self
+                .next
+                .as_ref()
+                .and_then(|next| next.get_cached_subquery(query)),
+        }
+    }
+
+    pub fn cache_subquery(&self, query: &Query, value: Value) {
+        match &self.content {
+            Content::Cache(cache) => {
+                cache.borrow_mut().insert(query as *const Query, value);
+            }
+            Content::Some { .. } | Content::Options(_) | Content::None => {
+                if let Some(next) = &self.next {
+                    next.cache_subquery(query, value);
+                }
+            }
+        }
+    }
+
     pub fn get_value(&'a self, target: &str) -> Option<&'a Value> {
         if let Content::Some { columns, row, .. } = &self.content {
             let value = row.get_value(columns, target);
@@ -34,6 +34,9 @@ pub enum EvaluateError {
     #[error("function requires map value: {0}")]
     FunctionRequiresMapValue(String),
 
+    #[error("placeholder is not bound to any value: {0}")]
+    UnboundPlaceholder(String),
+
     #[error("value not found: {0}")]
     ValueNotFound(String),
 
@@ -52,6 +55,9 @@ pub enum EvaluateError {
     #[error("unreachable empty aggregate value: {0:?}")]
     UnreachableEmptyAggregateValue(Aggregate),
 
+    #[error("window functions are only allowed in SELECT and ORDER BY: {0}")]
+    WindowFunctionNotAllowed(String),
+
     #[error("the divisor should not be zero")]
     DivisorShouldNotBeZero,
 
@@ -2,8 +2,9 @@ use {
     super::{ChronoFormatError, EvaluateError, Evaluated},
     crate::{
         ast::{DataType, DateTimeField, TrimWhereField},
-        data::Value,
+        data::{SequenceError, Value},
         result::Result,
+        store::GStore,
     },
     std::{
         cmp::{max, min},
@@ -504,6 +505,43 @@ pub fn generate_uuid<'a>() -> Evaluated<'a> {
     Evaluated::from(Value::Uuid(Uuid::new_v4().as_u128()))
 }
 
+// --- sequence ---
+
+pub async fn nextval<'a>(
+    storage: &dyn GStore,
+    name: String,
+    expr: Evaluated<'_>,
+) -> Result<Evaluated<'a>> {
+    let sequence_name = eval_to_str!(name, expr);
+    let sequence = storage
+        .fetch_sequence(&sequence_name)
+        .await?
+        .ok_or(SequenceError::NotFound(sequence_name))?;
+
+    storage
+        .next_sequence_value(&sequence)
+        .await
+        .map(Value::I64)
+        .map(Evaluated::from)
+}
+
+pub async fn currval<'a>(
+    storage: &dyn GStore,
+    name: String,
+    expr: Evaluated<'_>,
+) -> Result<Evaluated<'a>> {
+    let sequence_name = eval_to_str!(name, expr);
+
+    if storage.fetch_sequence(&sequence_name).await?.is_none() {
+        return Err(SequenceError::NotFound(sequence_name).into());
+    }
+
+    match storage.current_sequence_value(&sequence_name).await? {
+        Some(value) => Ok(Evaluated::from(Value::I64(value))),
+        None => Err(SequenceError::CurrentValueNotDefined(sequence_name).into()),
+    }
+}
+
 pub fn format<'a>(
     name: String,
     expr: Evaluated<'_>,
@@ -7,7 +7,7 @@ mod stateless;
 use {
     super::{context::FilterContext, select::select},
     crate::{
-        ast::{Aggregate, Expr, Function},
+        ast::{Expr, Function, Query, ToSql},
         data::{Interval, Literal, Value},
         result::Result,
         store::GStore,
@@ -31,7 +31,7 @@ pub use {
 pub async fn evaluate<'a>(
     storage: &'a dyn GStore,
     context: Option<Rc<FilterContext<'a>>>,
-    aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+    aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
     expr: &'a Expr,
 ) -> Result<Evaluated<'a>> {
     let eval = |expr| {
@@ -43,6 +43,7 @@ pub async fn evaluate<'a>(
 
     match expr {
         Expr::Literal(ast_literal) => expr::literal(ast_literal),
+        Expr::Placeholder(name) => Err(EvaluateError::UnboundPlaceholder(name.to_owned()).into()),
         Expr::TypedString { data_type, value } => {
             expr::typed_string(data_type, Cow::Borrowed(value))
         }
@@ -67,23 +68,32 @@ pub async fn evaluate<'a>(
             }
             .map(Evaluated::from)
         }
-        Expr::Subquery(query) => {
-            let evaluations = select(storage, query, context.as_ref().map(Rc::clone))
-                .await?
-                .map_ok(|row| row.take_first_value().map(Evaluated::from))
-                .take(2)
-                .try_collect::<Vec<_>>()
-                .await?;
+        Expr::Subquery {
+            subquery,
+            cached: true,
+        } => {
+            if let Some(value) = context
+                .as_ref()
+                .and_then(|context| context.get_cached_subquery(subquery))
+            {
+                return Ok(Evaluated::from(value));
+            }
 
-            if evaluations.len() > 1 {
-                return Err(EvaluateError::MoreThanOneRowReturned.into());
+            // the planner caches only the subquery which runs the same without the outer rows
+            let detached = FilterContext::detach(context.as_deref());
+            let value = evaluate_subquery(storage, subquery, Some(Rc::new(detached))).await?;
+            if let Some(context) = &context {
+                context.cache_subquery(subquery, value.clone());
             }
 
-            evaluations
-                .into_iter()
-                .next()
-                .unwrap_or_else(|| Ok(Evaluated::from(Value::Null)))
+            Ok(Evaluated::from(value))
         }
+        Expr::Subquery {
+            subquery,
+            cached: false,
+        } => evaluate_subquery(storage, subquery, context)
+            .await
+            .map(Evaluated::from),
         Expr::BinaryOp { op, left, right } => {
             let left = eval(left).await?;
             let right = eval(right).await?;
@@ -97,11 +107,18 @@ pub async fn evaluate<'a>(
         }
         Expr::Aggregate(aggr) => match aggregated
             .as_ref()
-            .and_then(|aggregated| aggregated.get(aggr.as_ref()))
+            .and_then(|aggregated| aggregated.get(expr))
         {
             Some(value) => Ok(Evaluated::from(value.clone())),
             None => Err(EvaluateError::UnreachableEmptyAggregateValue(*aggr.clone()).into()),
         },
+        Expr::Window(window) => match aggregated
+            .as_ref()
+            .and_then(|aggregated| aggregated.get(expr))
+        {
+            Some(value) => Ok(Evaluated::from(value.clone())),
+            None => Err(EvaluateError::WindowFunctionNotAllowed(window.to_sql()).into()),
+        },
         Expr::Function(func) => {
             let context = context.as_ref().map(Rc::clone);
             let aggregated = aggregated.as_ref().map(Rc::clone);
@@ -251,7 +268,7 @@ pub async fn evaluate<'a>(
 async fn evaluate_function<'a>(
     storage: &'a dyn GStore,
     context: Option<Rc<FilterContext<'a>>>,
-    aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+    aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
     func: &'a Function,
 ) -> Result<Evaluated<'a>> {
     use function as f;
@@ -418,6 +435,8 @@ async fn evaluate_function<'a>(
         }
         Function::GenerateUuid() => Ok(f::generate_uuid()),
         Function::Now() => Ok(Evaluated::from(Value::Timestamp(Utc::now().naive_utc()))),
+        Function::Nextval(expr) => f::nextval(storage, name, eval(expr).await?).await,
+        Function::Currval(expr) => f::currval(storage, name, eval(expr).await?).await,
         Function::Format { expr, format } => {
             let expr = eval(expr).await?;
             let format = eval(format).await?;
@@ -457,3 +476,22 @@ async fn evaluate_function<'a>(
         }
     }
 }
+
+async fn evaluate_subquery<'a>(
+    storage: &'a dyn GStore,
+    query: &'a Query,
+    context: Option<Rc<FilterContext<'a>>>,
+) -> Result<Value> {
+    let values = select(storage, query, context)
+        .await?
+        .map_ok(|row| row.take_first_value())
+        .take(2)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    if values.len() > 1 {
+        return Err(EvaluateError::MoreThanOneRowReturned.into());
+    }
+
+    values.into_iter().next().unwrap_or_else(|| Ok(Value::Null))
+}
@@ -19,6 +19,7 @@ pub fn evaluate_stateless<'a>(
 
     match expr {
         Expr::Literal(ast_literal) => expr::literal(ast_literal),
+        Expr::Placeholder(name) => Err(EvaluateError::UnboundPlaceholder(name.to_owned()).into()),
         Expr::TypedString { data_type, value } => {
             expr::typed_string(data_type, Cow::Borrowed(value))
         }
@@ -298,6 +299,9 @@ fn evaluate_function<'a>(
         }
         Function::GenerateUuid() => Ok(f::generate_uuid()),
         Function::Now() => Ok(Evaluated::from(Value::Timestamp(Utc::now().naive_utc()))),
+        Function::Nextval(_) | Function::Currval(_) => Err(
+            EvaluateError::UnsupportedStatelessExpr(Expr::Function(Box::new(func.clone()))).into(),
+        ),
         Function::Format { expr, format } => {
             let expr = eval(expr)?;
             let format = eval(format)?;
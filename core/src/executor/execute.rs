@@ -1,24 +1,35 @@
 use {
     super::{
-        alter::{create_table, drop_table},
-        fetch::{fetch, fetch_columns},
-        select::{select, select_with_labels},
+        alter::{
+            analyze_table, create_schema, create_sequence, create_table, create_view, drop_schema,
+            drop_sequence, drop_table, drop_view,
+        },
+        context::FilterContext,
+        explain::{explain, ExplainNode},
+        fetch::{fetch, fetch_joined, FetchError},
+        foreign_key::{apply_references, validate_references},
+        generated::{fill_generated_values, generated_indexes, validate_generated_values},
+        identity::{evaluate_sequence_functions, fill_identity_values, validate_identity_values},
+        select::{select, select_returning, select_with_labels, DEFAULT_MAX_RECURSION_DEPTH},
         update::Update,
-        validate::{validate_unique, ColumnValidation},
+        upsert::{resolve_conflicts, Upsert},
+        validate::{
+            validate_checks, validate_composite_primary_key, validate_unique, ColumnValidation,
+        },
     },
     crate::{
         ast::{
-            ColumnDef, ColumnOption, DataType, Dictionary, Expr, Query, SelectItem, SetExpr,
-            Statement, TableAlias, TableFactor, TableWithJoins, Values, Variable,
+            ColumnDef, DataType, Dictionary, Expr, Query, SelectItem, SetExpr, Statement,
+            TableAlias, TableFactor, TableWithJoins, Values, Variable,
         },
         data::{Key, Row, Schema},
         executor::limit::Limit,
         result::{MutResult, Result},
         store::{GStore, GStoreMut},
     },
-    futures::stream::{self, TryStreamExt},
+    futures::stream::{self, Stream, TryStreamExt},
     serde::{Deserialize, Serialize},
-    std::{env::var, fmt::Debug, rc::Rc},
+    std::{env::var, fmt::Debug, pin::Pin, rc::Rc},
     thiserror::Error as ThisError,
 };
 
@@ -49,6 +60,13 @@ pub enum Payload {
     Delete(usize),
     Update(usize),
     DropTable,
+    CreateSchema,
+    DropSchema,
+    CreateSequence,
+    DropSequence,
+    CreateView,
+    DropView,
+    Analyze,
 
     #[cfg(feature = "alter-table")]
     AlterTable,
@@ -65,6 +83,7 @@ pub enum Payload {
     Rollback,
 
     ShowVariable(PayloadVariable),
+    Explain(ExplainNode),
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -73,20 +92,62 @@ pub enum PayloadVariable {
     Version(String),
 }
 
+/// Options of a statement execution, which are set on `Glue`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExecuteOptions {
+    /// How many times the recursive term of a `WITH RECURSIVE` query is evaluated before
+    /// `SelectError::RecursionDepthExceeded` is returned
+    pub max_recursion_depth: usize,
+}
+
+impl Default for ExecuteOptions {
+    fn default() -> Self {
+        Self {
+            max_recursion_depth: DEFAULT_MAX_RECURSION_DEPTH,
+        }
+    }
+}
+
+/// Rows of a `SELECT` which are produced as the stream is polled.
+pub type RowStream<'a> = Pin<Box<dyn Stream<Item = Result<Row>> + 'a>>;
+
+/// `Payload` whose `SELECT` rows are streamed from the storage instead of being collected.
+pub enum PayloadStream<'a> {
+    Select {
+        labels: Vec<String>,
+        rows: RowStream<'a>,
+    },
+    Payload(Payload),
+}
+
+/// Runs the query through a shared borrow of the storage, the rows are read from the
+/// storage as the returned stream is polled.
+pub async fn stream_query<'a>(
+    storage: &'a dyn GStore,
+    query: &'a Query,
+    options: ExecuteOptions,
+) -> Result<(Vec<String>, RowStream<'a>)> {
+    let filter_context = Some(Rc::new(FilterContext::with_options(options)));
+    let (labels, rows) = select_with_labels(storage, query, filter_context, true).await?;
+
+    Ok((labels, Box::pin(rows)))
+}
+
 #[cfg(feature = "transaction")]
 pub async fn execute_atomic<T: GStore + GStoreMut>(
     storage: T,
     statement: &Statement,
+    options: ExecuteOptions,
 ) -> MutResult<T, Payload> {
     if matches!(
         statement,
         Statement::StartTransaction | Statement::Rollback | Statement::Commit
     ) {
-        return execute(storage, statement).await;
+        return execute(storage, statement, options).await;
     }
 
     let (storage, autocommit) = storage.begin(true).await?;
-    let result = execute(storage, statement).await;
+    let result = execute(storage, statement, options).await;
 
     match (result, autocommit) {
         (Ok((storage, payload)), true) => {
@@ -106,6 +167,7 @@ pub async fn execute_atomic<T: GStore + GStoreMut>(
 pub async fn execute<T: GStore + GStoreMut>(
     storage: T,
     statement: &Statement,
+    options: ExecuteOptions,
 ) -> MutResult<T, Payload> {
     macro_rules! try_block {
         ($storage: expr, $block: block) => {{
@@ -118,23 +180,83 @@ pub async fn execute<T: GStore + GStoreMut>(
         }};
     }
 
+    // root of the contexts, which carries the options down to the queries of the statement
+    let root = || Some(Rc::new(FilterContext::with_options(options)));
+
     match statement {
         //- Modification
         //-- Tables
         Statement::CreateTable {
             name,
             columns,
+            primary_key,
+            foreign_keys,
+            checks,
             if_not_exists,
             source,
-            ..
-        } => create_table(storage, name, columns, *if_not_exists, source)
-            .await
-            .map(|(storage, _)| (storage, Payload::Create)),
+        } => create_table(
+            storage,
+            name,
+            columns,
+            primary_key,
+            foreign_keys,
+            checks,
+            *if_not_exists,
+            source,
+            root(),
+        )
+        .await
+        .map(|(storage, _)| (storage, Payload::Create)),
         Statement::DropTable {
             names, if_exists, ..
         } => drop_table(storage, names, *if_exists)
             .await
             .map(|(storage, _)| (storage, Payload::DropTable)),
+        //-- Schemas
+        Statement::CreateSchema {
+            if_not_exists,
+            name,
+        } => create_schema(storage, name, *if_not_exists)
+            .await
+            .map(|(storage, _)| (storage, Payload::CreateSchema)),
+        Statement::DropSchema {
+            if_exists,
+            names,
+            cascade,
+        } => drop_schema(storage, names, *if_exists, *cascade)
+            .await
+            .map(|(storage, _)| (storage, Payload::DropSchema)),
+        Statement::CreateSequence {
+            if_not_exists,
+            name,
+            increment,
+            start,
+        } => create_sequence(
+            storage,
+            name,
+            increment.as_ref(),
+            start.as_ref(),
+            *if_not_exists,
+        )
+        .await
+        .map(|(storage, _)| (storage, Payload::CreateSequence)),
+        Statement::DropSequence { if_exists, names } => drop_sequence(storage, names, *if_exists)
+            .await
+            .map(|(storage, _)| (storage, Payload::DropSequence)),
+        Statement::CreateView {
+            or_replace,
+            name,
+            columns,
+            query,
+        } => create_view(storage, name, columns, query, *or_replace)
+            .await
+            .map(|(storage, _)| (storage, Payload::CreateView)),
+        Statement::DropView { if_exists, names } => drop_view(storage, names, *if_exists)
+            .await
+            .map(|(storage, _)| (storage, Payload::DropView)),
+        Statement::Analyze { table_name } => analyze_table(storage, table_name)
+            .await
+            .map(|(storage, _)| (storage, Payload::Analyze)),
         #[cfg(feature = "alter-table")]
         Statement::AlterTable { name, operation } => alter_table(storage, name, operation)
             .await
@@ -143,8 +265,8 @@ pub async fn execute<T: GStore + GStoreMut>(
         Statement::CreateIndex {
             name,
             table_name,
-            column,
-        } => create_index(storage, table_name, name, column)
+            columns,
+        } => create_index(storage, table_name, name, columns)
             .await
             .map(|(storage, _)| (storage, Payload::CreateIndex)),
         #[cfg(feature = "index")]
@@ -172,20 +294,23 @@ pub async fn execute<T: GStore + GStoreMut>(
         Statement::Insert {
             table_name,
             columns,
+            overriding_system_value,
             source,
-            ..
+            on_conflict,
+            returning,
         } => {
             enum RowsData {
                 Append(Vec<Row>),
                 Insert(Vec<(Key, Row)>),
             }
 
-            let (rows, num_rows, table_name) = try_block!(storage, {
-                let Schema { column_defs, .. } = storage
+            let (schema, rows, updates, payload, table_name) = try_block!(storage, {
+                let schema = storage
                     .fetch_schema(table_name)
                     .await?
                     .ok_or_else(|| ExecuteError::TableNotFound(table_name.to_owned()))?;
-                let column_defs = Rc::from(column_defs);
+                let primary_key = schema.primary_key_indexes();
+                let column_defs = Rc::from(schema.column_defs.as_slice());
                 let column_validation = ColumnValidation::All(Rc::clone(&column_defs));
 
                 #[derive(futures_enum::Stream)]
@@ -194,23 +319,25 @@ pub async fn execute<T: GStore + GStoreMut>(
                     Select(I2),
                 }
 
-                let rows = match &source.body {
+                let mut rows = match &source.body {
                     SetExpr::Values(Values(values_list)) => {
                         let limit = Limit::new(source.limit.as_ref(), source.offset.as_ref())?;
-                        let rows = values_list
-                            .iter()
-                            .map(|values| Row::new(&column_defs, columns, values));
+                        let rows = evaluate_sequence_functions(&storage, values_list)
+                            .await?
+                            .into_iter()
+                            .map(|values| Row::new(&column_defs, columns, &values));
                         let rows = stream::iter(rows);
                         let rows = limit.apply(rows);
 
                         Rows::Values(rows)
                     }
-                    SetExpr::Select(_) => {
-                        let rows = select(&storage, source, None).await?.and_then(|row| {
+                    SetExpr::Select(_) | SetExpr::SetOperation { .. } => {
+                        let rows = select(&storage, source, root()).await?.and_then(|row| {
                             let column_defs = Rc::clone(&column_defs);
 
                             async move {
                                 row.validate(&column_defs)?;
+                                validate_generated_values(&column_defs, &row)?;
                                 Ok(row)
                             }
                         });
@@ -221,118 +348,296 @@ pub async fn execute<T: GStore + GStoreMut>(
                 .try_collect::<Vec<_>>()
                 .await?;
 
-                validate_unique(&storage, table_name, column_validation, rows.iter()).await?;
+                if !overriding_system_value {
+                    validate_identity_values(&schema.column_defs, &rows)?;
+                }
+
+                fill_identity_values(&storage, &schema, &mut rows).await?;
+
+                let generated = generated_indexes(&schema.column_defs);
+                let rows = rows
+                    .into_iter()
+                    .map(|row| fill_generated_values(&schema.column_defs, &generated, row))
+                    .collect::<Result<Vec<_>>>()?;
+
+                let (rows, updates) = match on_conflict {
+                    Some(on_conflict) => {
+                        let Upsert { inserts, updates } =
+                            resolve_conflicts(&storage, &schema, on_conflict, rows, root()).await?;
+
+                        let updated_rows = updates.iter().filter_map(|(_, _, row)| row.as_ref());
+                        validate_checks(&storage, &schema, updated_rows.clone()).await?;
+                        validate_references(&storage, &schema, updated_rows).await?;
+
+                        (inserts, updates)
+                    }
+                    None => {
+                        validate_unique(&storage, table_name, column_validation, rows.iter())
+                            .await?;
+
+                        (rows, Vec::new())
+                    }
+                };
+
+                validate_checks(&storage, &schema, rows.iter()).await?;
+                validate_references(&storage, &schema, rows.iter()).await?;
 
-                let num_rows = rows.len();
-                let primary_key = column_defs
+                let num_rows = rows.len() + updates.len();
+                let returned_rows = rows
                     .iter()
-                    .enumerate()
-                    .find(|(_, ColumnDef { options, .. })| {
-                        options
-                            .iter()
-                            .any(|option| option == &ColumnOption::Unique { is_primary: true })
-                    })
-                    .map(|(i, _)| i);
+                    .cloned()
+                    .chain(updates.iter().filter_map(|(_, _, row)| row.clone()));
+                let payload = returning_payload(
+                    &storage,
+                    &schema,
+                    table_name,
+                    returning,
+                    returned_rows,
+                    Payload::Insert(num_rows),
+                    root(),
+                )
+                .await?;
 
                 let rows = match primary_key {
-                    Some(i) => rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            row.0
-                                .get(i)
-                                .map(Key::try_from)
-                                .map(|result| result.map(|key| (key, row)))
-                        })
-                        .collect::<Result<Vec<_>>>()
-                        .map(RowsData::Insert)?,
+                    Some(primary_key) => {
+                        let rows = rows
+                            .into_iter()
+                            .map(|row| {
+                                let values = primary_key
+                                    .iter()
+                                    .filter_map(|i| row.get_value_by_index(*i))
+                                    .cloned()
+                                    .collect();
+
+                                Key::from_primary_values(values).map(|key| (key, row))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+
+                        if primary_key.len() > 1 && on_conflict.is_none() {
+                            let keys = rows.iter().map(|(key, _)| key);
+
+                            validate_composite_primary_key(&storage, table_name, keys).await?;
+                        }
+
+                        RowsData::Insert(rows)
+                    }
                     None => RowsData::Append(rows),
                 };
 
-                Ok((rows, num_rows, table_name))
+                Ok((schema, rows, updates, payload, table_name))
             });
 
+            let (storage, _) = apply_references(storage, &schema, &updates).await?;
+            let updates = updates
+                .into_iter()
+                .filter_map(|(key, _, row)| row.map(|row| (key, row)))
+                .collect::<Vec<_>>();
+            let storage = match updates.is_empty() {
+                true => storage,
+                false => storage.insert_data(table_name, updates).await?.0,
+            };
+
             match rows {
                 RowsData::Append(rows) => storage.append_data(table_name, rows).await,
                 RowsData::Insert(rows) => storage.insert_data(table_name, rows).await,
             }
-            .map(|(storage, _)| (storage, Payload::Insert(num_rows)))
+            .map(|(storage, _)| (storage, payload))
         }
         Statement::Update {
             table_name,
-            selection,
+            alias,
             assignments,
+            joins,
+            selection,
+            returning,
         } => {
-            let (table_name, rows) = try_block!(storage, {
-                let Schema { column_defs, .. } = storage
+            let table_alias = alias.as_deref().unwrap_or(table_name);
+            let (schema, rows, payload) = try_block!(storage, {
+                let schema = storage
                     .fetch_schema(table_name)
                     .await?
                     .ok_or_else(|| ExecuteError::TableNotFound(table_name.to_owned()))?;
-                let update = Update::new(&storage, table_name, assignments, &column_defs)?;
+                let Schema {
+                    column_defs,
+                    primary_key,
+                    ..
+                } = &schema;
+                let update = Update::new(
+                    &storage,
+                    table_alias,
+                    assignments,
+                    column_defs,
+                    primary_key.as_deref(),
+                    root(),
+                )?;
 
                 let all_columns = Rc::from(update.all_columns());
                 let columns_to_update = update.columns_to_update();
-                let rows = fetch(&storage, table_name, all_columns, selection.as_ref())
-                    .await?
-                    .and_then(|item| {
-                        let update = &update;
-                        let (_, key, row) = item;
+                let rows = match joins.is_empty() {
+                    true => {
+                        fetch(
+                            &storage,
+                            table_name,
+                            table_alias,
+                            all_columns,
+                            selection.as_ref(),
+                            root(),
+                        )
+                        .await?
+                        .and_then(|item| {
+                            let update = &update;
+                            let (_, key, row) = item;
+
+                            async move {
+                                let updated_row = update.apply(row.clone()).await?;
+                                Ok((key, row, Some(updated_row)))
+                            }
+                        })
+                        .try_collect::<Vec<_>>()
+                        .await?
+                    }
+                    false => {
+                        let joined_rows = fetch_joined(
+                            &storage,
+                            table_name,
+                            table_alias,
+                            all_columns,
+                            joins,
+                            selection.as_ref(),
+                            true,
+                            root(),
+                        )
+                        .await?;
 
-                        async move {
-                            let row = update.apply(row).await?;
-                            Ok((key, row))
+                        let mut rows = Vec::with_capacity(joined_rows.len());
+                        for (key, row, blend_context) in joined_rows {
+                            let updated_row =
+                                update.apply_joined(row.clone(), blend_context).await?;
+                            rows.push((key, row, Some(updated_row)));
                         }
-                    })
-                    .try_collect::<Vec<_>>()
-                    .await?;
 
-                let column_validation =
-                    ColumnValidation::SpecifiedColumns(Rc::from(column_defs), columns_to_update);
+                        rows
+                    }
+                };
+
+                let updated_rows = rows.iter().filter_map(|(_, _, row)| row.as_ref());
+                let column_validation = ColumnValidation::SpecifiedColumns(
+                    Rc::from(column_defs.as_slice()),
+                    columns_to_update,
+                );
                 validate_unique(
                     &storage,
                     table_name,
                     column_validation,
-                    rows.iter().map(|r| &r.1),
+                    updated_rows.clone(),
+                )
+                .await?;
+                validate_checks(&storage, &schema, updated_rows.clone()).await?;
+                validate_references(&storage, &schema, updated_rows.clone()).await?;
+
+                let payload = returning_payload(
+                    &storage,
+                    &schema,
+                    table_alias,
+                    returning,
+                    updated_rows.cloned(),
+                    Payload::Update(rows.len()),
+                    root(),
                 )
                 .await?;
 
-                Ok((table_name, rows))
+                Ok((schema, rows, payload))
             });
 
-            let num_rows = rows.len();
+            let (storage, _) = apply_references(storage, &schema, &rows).await?;
+            let rows = rows
+                .into_iter()
+                .filter_map(|(key, _, row)| row.map(|row| (key, row)))
+                .collect::<Vec<_>>();
 
             storage
                 .insert_data(table_name, rows)
                 .await
-                .map(|(storage, _)| (storage, Payload::Update(num_rows)))
+                .map(|(storage, _)| (storage, payload))
         }
         Statement::Delete {
             table_name,
+            alias,
+            joins,
             selection,
+            returning,
         } => {
-            let (table_name, keys) = try_block!(storage, {
-                let columns = Rc::from(fetch_columns(&storage, table_name).await?);
-
-                let keys = fetch(&storage, table_name, columns, selection.as_ref())
+            let table_alias = alias.as_deref().unwrap_or(table_name);
+            let (schema, rows, payload) = try_block!(storage, {
+                let schema = storage
+                    .fetch_schema(table_name)
+                    .await?
+                    .ok_or_else(|| FetchError::TableNotFound(table_name.to_owned()))?;
+                let columns = schema
+                    .column_defs
+                    .iter()
+                    .map(|ColumnDef { name, .. }| name.to_owned())
+                    .collect::<Rc<[String]>>();
+
+                let rows = match joins.is_empty() {
+                    true => {
+                        fetch(
+                            &storage,
+                            table_name,
+                            table_alias,
+                            columns,
+                            selection.as_ref(),
+                            root(),
+                        )
+                        .await?
+                        .map_ok(|(_, key, row)| (key, row, None))
+                        .try_collect::<Vec<_>>()
+                        .await?
+                    }
+                    false => fetch_joined(
+                        &storage,
+                        table_name,
+                        table_alias,
+                        columns,
+                        joins,
+                        selection.as_ref(),
+                        false,
+                        root(),
+                    )
                     .await?
-                    .map_ok(|(_, key, _)| key)
-                    .try_collect::<Vec<_>>()
-                    .await?;
+                    .into_iter()
+                    .map(|(key, row, _)| (key, row, None))
+                    .collect(),
+                };
+
+                let deleted_rows = rows.iter().map(|(_, row, _)| row.clone());
+                let payload = returning_payload(
+                    &storage,
+                    &schema,
+                    table_alias,
+                    returning,
+                    deleted_rows,
+                    Payload::Delete(rows.len()),
+                    root(),
+                )
+                .await?;
 
-                Ok((table_name, keys))
+                Ok((schema, rows, payload))
             });
 
-            let num_keys = keys.len();
+            let (storage, _) = apply_references(storage, &schema, &rows).await?;
+            let keys = rows.into_iter().map(|(key, _, _)| key).collect::<Vec<_>>();
 
             storage
                 .delete_data(table_name, keys)
                 .await
-                .map(|(storage, _)| (storage, Payload::Delete(num_keys)))
+                .map(|(storage, _)| (storage, payload))
         }
 
         //- Selection
         Statement::Query(query) => {
             let (labels, rows) = try_block!(storage, {
-                let (labels, rows) = select_with_labels(&storage, query, None, true).await?;
+                let (labels, rows) = select_with_labels(&storage, query, root(), true).await?;
                 let rows = rows.try_collect::<Vec<_>>().await?;
                 Ok((labels, rows))
             });
@@ -358,7 +663,9 @@ pub async fn execute<T: GStore + GStoreMut>(
         #[cfg(feature = "index")]
         Statement::ShowIndexes(table_name) => {
             let query = Query {
+                with: None,
                 body: SetExpr::Select(Box::new(crate::ast::Select {
+                    distinct: false,
                     projection: vec![SelectItem::Wildcard],
                     from: TableWithJoins {
                         relation: TableFactor::Dictionary {
@@ -402,7 +709,9 @@ pub async fn execute<T: GStore + GStoreMut>(
         Statement::ShowVariable(variable) => match variable {
             Variable::Tables => {
                 let query = Query {
+                    with: None,
                     body: SetExpr::Select(Box::new(crate::ast::Select {
+                        distinct: false,
                         projection: vec![SelectItem::Expr {
                             expr: Expr::Identifier("TABLE_NAME".to_owned()),
                             label: "TABLE_NAME".to_owned(),
@@ -453,5 +762,45 @@ pub async fn execute<T: GStore + GStoreMut>(
                 Ok((storage, payload))
             }
         },
+        Statement::Explain { analyze, statement } => {
+            let node = try_block!(storage, {
+                explain(&storage, statement, *analyze, root()).await
+            });
+
+            Ok((storage, Payload::Explain(node)))
+        }
+    }
+}
+
+/// Projects the changed rows with the `RETURNING` items, or returns the given
+/// count payload when there are none.
+async fn returning_payload(
+    storage: &dyn GStore,
+    schema: &Schema,
+    table_alias: &str,
+    returning: &[SelectItem],
+    rows: impl IntoIterator<Item = Row>,
+    payload: Payload,
+    filter_context: Option<Rc<FilterContext<'_>>>,
+) -> Result<Payload> {
+    if returning.is_empty() {
+        return Ok(payload);
     }
+
+    let columns = schema
+        .column_defs
+        .iter()
+        .map(|ColumnDef { name, .. }| name.to_owned())
+        .collect::<Rc<[String]>>();
+    let (labels, rows) = select_returning(
+        storage,
+        table_alias,
+        columns,
+        returning,
+        rows,
+        filter_context,
+    )
+    .await?;
+
+    Ok(Payload::Select { labels, rows })
 }
@@ -0,0 +1,453 @@
+use {
+    super::{aggregate::check_aggregate, context::FilterContext, select::select_analyzed},
+    crate::{
+        ast::{
+            BinaryOperator, Cte, Expr, IndexItem, Join, JoinConstraint, JoinExecutor, JoinOperator,
+            OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableFactor,
+            TableWithJoins, ToSql, Values, With,
+        },
+        result::Result,
+        store::GStore,
+    },
+    chrono::{DateTime, Utc},
+    futures::{
+        future::{self, Future},
+        stream::{self, Stream, TryStreamExt},
+    },
+    itertools::Itertools,
+    serde::{Deserialize, Serialize},
+    std::{cell::RefCell, collections::HashMap, rc::Rc, task::Poll},
+    thiserror::Error as ThisError,
+};
+
+#[derive(ThisError, Serialize, Debug, PartialEq, Eq)]
+pub enum ExplainError {
+    #[error("EXPLAIN supports only queries")]
+    UnsupportedStatement,
+}
+
+/// Operator of the plan described by `EXPLAIN`, rows flow from the children to their parent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExplainNode {
+    pub name: String,
+    pub detail: String,
+    pub children: Vec<ExplainNode>,
+    /// Measured by `EXPLAIN ANALYZE`
+    pub analyzed: Option<Analyzed>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Analyzed {
+    /// Rows produced by the operator
+    pub rows: usize,
+    /// Microseconds spent by the operator, including the operators below it
+    pub elapsed: i64,
+}
+
+/// Stages of the `SELECT` pipeline profiled by `EXPLAIN ANALYZE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Scan,
+    Join(usize),
+    Filter,
+    Aggregate,
+    Project,
+    Distinct,
+    Sort,
+    Limit,
+}
+
+/// Row counts and elapsed time of each stage, collected while the query runs.
+#[derive(Debug, Default)]
+pub struct Profile {
+    stats: RefCell<HashMap<Stage, Analyzed>>,
+}
+
+impl Profile {
+    fn record(&self, stage: Stage, rows: usize, started: DateTime<Utc>) {
+        let elapsed = (Utc::now() - started)
+            .num_microseconds()
+            .unwrap_or(i64::MAX);
+
+        let mut stats = self.stats.borrow_mut();
+        let analyzed = stats.entry(stage).or_default();
+        analyzed.rows += rows;
+        analyzed.elapsed = analyzed.elapsed.saturating_add(elapsed);
+    }
+}
+
+/// Counts the rows of the stream and the time spent polling it, the stream is returned as it
+/// is without a profile.
+pub fn measure<'a, S: Stream + 'a>(
+    profile: Option<&Rc<Profile>>,
+    stage: Stage,
+    rows: S,
+) -> impl Stream<Item = S::Item> + 'a {
+    #[derive(futures_enum::Stream)]
+    enum Measured<S1, S2> {
+        Plain(S1),
+        Profiled(S2),
+    }
+
+    let profile = match profile {
+        Some(profile) => Rc::clone(profile),
+        None => return Measured::Plain(rows),
+    };
+
+    let mut rows = Box::pin(rows);
+    let rows = stream::poll_fn(move |cx| {
+        let started = Utc::now();
+        let poll = rows.as_mut().poll_next(cx);
+        let produced = matches!(poll, Poll::Ready(Some(_)));
+        profile.record(stage, usize::from(produced), started);
+
+        poll
+    });
+
+    Measured::Profiled(rows)
+}
+
+/// Time spent by the eager part of a stage, such as sorting or building a hash table.
+pub async fn measure_future<F: Future>(
+    profile: Option<&Rc<Profile>>,
+    stage: Stage,
+    future: F,
+) -> F::Output {
+    let started = Utc::now();
+    let output = future.await;
+
+    if let Some(profile) = profile {
+        profile.record(stage, 0, started);
+    }
+
+    output
+}
+
+pub async fn explain(
+    storage: &dyn GStore,
+    statement: &Statement,
+    analyze: bool,
+    filter_context: Option<Rc<FilterContext<'_>>>,
+) -> Result<ExplainNode> {
+    let query = match statement {
+        Statement::Query(query) => query,
+        _ => return Err(ExplainError::UnsupportedStatement.into()),
+    };
+
+    if !analyze {
+        return Ok(Explainer { stats: None }.query(query));
+    }
+
+    let profile = Rc::new(Profile::default());
+    let started = Utc::now();
+    let rows = select_analyzed(storage, query, filter_context, Rc::clone(&profile))
+        .await?
+        .try_fold(0, |rows, _| future::ready(Ok(rows + 1)))
+        .await?;
+    let elapsed = (Utc::now() - started)
+        .num_microseconds()
+        .unwrap_or(i64::MAX);
+
+    let stats = profile.stats.take();
+    let mut node = Explainer {
+        stats: Some(&stats),
+    }
+    .query(query);
+    node.analyzed.get_or_insert(Analyzed { rows, elapsed });
+
+    Ok(node)
+}
+
+/// Builds the plan tree, `stats` are only given to the profiled outermost `SELECT`.
+struct Explainer<'a> {
+    stats: Option<&'a HashMap<Stage, Analyzed>>,
+}
+
+impl Explainer<'_> {
+    fn node(
+        &self,
+        stage: Option<Stage>,
+        name: &str,
+        detail: String,
+        children: Vec<ExplainNode>,
+    ) -> ExplainNode {
+        let analyzed = stage
+            .zip(self.stats)
+            .and_then(|(stage, stats)| stats.get(&stage))
+            .copied();
+
+        ExplainNode {
+            name: name.to_owned(),
+            detail,
+            children,
+            analyzed,
+        }
+    }
+
+    fn query(&self, query: &Query) -> ExplainNode {
+        let Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        } = query;
+
+        let node = self.set_expr(body);
+        let node = match order_by.is_empty() {
+            true => node,
+            false => {
+                let detail = order_by.iter().map(OrderByExpr::to_sql).join(", ");
+
+                self.node(Some(Stage::Sort), "Sort", detail, vec![node])
+            }
+        };
+        let node = match (limit, offset) {
+            (None, None) => node,
+            _ => {
+                let detail = limit
+                    .iter()
+                    .map(|limit| format!("LIMIT {}", limit.to_sql()))
+                    .chain(
+                        offset
+                            .iter()
+                            .map(|offset| format!("OFFSET {}", offset.to_sql())),
+                    )
+                    .join(" ");
+
+                self.node(Some(Stage::Limit), "Limit", detail, vec![node])
+            }
+        };
+
+        match with {
+            Some(With {
+                recursive,
+                cte_tables,
+            }) => {
+                let inner = Explainer { stats: None };
+                let detail = match recursive {
+                    true => "RECURSIVE".to_owned(),
+                    false => "".to_owned(),
+                };
+                let children = cte_tables
+                    .iter()
+                    .map(|Cte { alias, query }| {
+                        inner.node(None, "CTE", alias.name.to_owned(), vec![inner.query(query)])
+                    })
+                    .chain([node])
+                    .collect();
+
+                self.node(None, "With", detail, children)
+            }
+            None => node,
+        }
+    }
+
+    fn set_expr(&self, set_expr: &SetExpr) -> ExplainNode {
+        match set_expr {
+            SetExpr::Select(select) => self.select(select),
+            SetExpr::Values(Values(rows)) => {
+                let detail = match rows.len() {
+                    1 => "1 row".to_owned(),
+                    n => format!("{n} rows"),
+                };
+
+                self.node(None, "Values", detail, Vec::new())
+            }
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let operand = Explainer { stats: None };
+                let detail = match all {
+                    true => format!("{op} ALL"),
+                    false => op.to_string(),
+                };
+                let children = vec![operand.set_expr(left), operand.set_expr(right)];
+
+                self.node(None, "Set Operation", detail, children)
+            }
+        }
+    }
+
+    fn select(&self, select: &Select) -> ExplainNode {
+        let Select {
+            distinct,
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+        } = select;
+
+        let node = self.node(
+            Some(Stage::Scan),
+            scan_name(relation),
+            relation_detail(relation),
+            self.subquery(relation),
+        );
+        let node = joins.iter().enumerate().fold(node, |node, (i, join)| {
+            let right = self.node(
+                None,
+                scan_name(&join.relation),
+                relation_detail(&join.relation),
+                self.subquery(&join.relation),
+            );
+            let (name, detail) = join_detail(join);
+
+            self.node(Some(Stage::Join(i)), name, detail, vec![node, right])
+        });
+        let node = match selection {
+            Some(expr) => self.node(Some(Stage::Filter), "Filter", expr.to_sql(), vec![node]),
+            None => node,
+        };
+        let node = match check_aggregate(projection, group_by) {
+            true => {
+                let group_by = (!group_by.is_empty())
+                    .then(|| format!("GROUP BY {}", group_by.iter().map(ToSql::to_sql).join(", ")));
+                let having = having
+                    .as_ref()
+                    .map(|having| format!("HAVING {}", having.to_sql()));
+                let detail = group_by.into_iter().chain(having).join(" ");
+
+                self.node(Some(Stage::Aggregate), "Aggregate", detail, vec![node])
+            }
+            false => node,
+        };
+        let detail = projection.iter().map(item_detail).join(", ");
+        let node = self.node(Some(Stage::Project), "Project", detail, vec![node]);
+
+        match distinct {
+            true => self.node(Some(Stage::Distinct), "Distinct", "".to_owned(), vec![node]),
+            false => node,
+        }
+    }
+
+    fn subquery(&self, relation: &TableFactor) -> Vec<ExplainNode> {
+        match relation {
+            TableFactor::Derived { subquery, .. } => {
+                vec![Explainer { stats: None }.query(subquery)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Labels are left out when they only repeat the projected expression.
+fn item_detail(item: &SelectItem) -> String {
+    match item {
+        SelectItem::Expr { expr, label } => {
+            let sql = expr.to_sql();
+            let repeated = match expr {
+                Expr::CompoundIdentifier { ident, .. } => ident == label,
+                _ => &sql == label,
+            };
+
+            match repeated {
+                true => sql,
+                false => format!("{sql} AS {label}"),
+            }
+        }
+        _ => item.to_sql(),
+    }
+}
+
+fn scan_name(relation: &TableFactor) -> &'static str {
+    match relation {
+        TableFactor::Table { index: None, .. } => "Table Scan",
+        TableFactor::Table {
+            index: Some(IndexItem::PrimaryKey(_)),
+            ..
+        } => "Primary Key Lookup",
+        TableFactor::Table {
+            index: Some(IndexItem::NonClustered { .. }),
+            ..
+        } => "Index Scan",
+        TableFactor::Derived { .. } => "Subquery",
+        TableFactor::Series { .. } => "Series",
+        TableFactor::Dictionary { .. } => "Dictionary",
+    }
+}
+
+fn relation_detail(relation: &TableFactor) -> String {
+    let index = match relation {
+        TableFactor::Table {
+            index: Some(index), ..
+        } => index,
+        TableFactor::Derived { alias, .. } => return alias.name.to_owned(),
+        _ => return relation.to_sql(),
+    };
+
+    let relation = relation.to_sql();
+    match index {
+        IndexItem::PrimaryKey(exprs) => {
+            let key = exprs.iter().map(ToSql::to_sql).join(", ");
+
+            format!("{relation} KEY ({key})")
+        }
+        IndexItem::NonClustered {
+            name,
+            asc,
+            prefix_exprs,
+            cmp_expr,
+        } => {
+            let prefix = match prefix_exprs.is_empty() {
+                true => "".to_owned(),
+                false => format!(
+                    " PREFIX ({})",
+                    prefix_exprs.iter().map(ToSql::to_sql).join(", ")
+                ),
+            };
+            let cmp = match cmp_expr {
+                Some((op, expr)) => format!(
+                    " {} {}",
+                    BinaryOperator::from(op.clone()).to_sql(),
+                    expr.to_sql()
+                ),
+                None => "".to_owned(),
+            };
+            let order = match asc {
+                Some(true) => " ASC",
+                Some(false) => " DESC",
+                None => "",
+            };
+
+            format!("{relation} USING {name}{prefix}{cmp}{order}")
+        }
+    }
+}
+
+fn join_detail(join: &Join) -> (&'static str, String) {
+    let Join {
+        join_operator,
+        join_executor,
+        ..
+    } = join;
+
+    let (operator, constraint) = match join_operator {
+        JoinOperator::Inner(constraint) => ("INNER", Some(constraint)),
+        JoinOperator::LeftOuter(constraint) => ("LEFT OUTER", Some(constraint)),
+        JoinOperator::RightOuter(constraint) => ("RIGHT OUTER", Some(constraint)),
+        JoinOperator::FullOuter(constraint) => ("FULL OUTER", Some(constraint)),
+        JoinOperator::Cross => ("CROSS", None),
+        JoinOperator::Semi(constraint) => ("SEMI", Some(constraint)),
+        JoinOperator::Anti(constraint) => ("ANTI", Some(constraint)),
+    };
+
+    let constraint = match constraint {
+        Some(JoinConstraint::On(expr)) => format!(" ON {}", expr.to_sql()),
+        Some(constraint @ JoinConstraint::Using(_)) => format!(" {}", constraint.to_sql()),
+        Some(JoinConstraint::Natural) => " NATURAL".to_owned(),
+        Some(JoinConstraint::None) | None => "".to_owned(),
+    };
+
+    match join_executor {
+        JoinExecutor::NestedLoop => ("Nested Loop Join", format!("{operator}{constraint}")),
+        JoinExecutor::Hash { .. } => (
+            "Hash Join",
+            format!("{operator}{constraint} HASH {}", join_executor.to_sql()),
+        ),
+    }
+}
@@ -1,14 +1,25 @@
 use {
-    super::{context::FilterContext, evaluate_stateless, filter::check_expr},
+    super::{
+        context::{BlendContext, BlendContextRow::Shared, FilterContext},
+        evaluate_stateless,
+        filter::{check_expr, Filter},
+        join::Join as JoinExecutor,
+        pushdown::Pushdown,
+    },
     crate::{
         ast::{
-            ColumnDef, ColumnOption, Dictionary, Expr, IndexItem, Join, Query, Select, SetExpr,
-            TableAlias, TableFactor, TableWithJoins, ToSql, Values,
+            ColumnDef, Dictionary, Expr, IndexItem, Join, Query, Select, SetExpr, TableAlias,
+            TableFactor, TableWithJoins, Values,
         },
-        data::{get_alias, get_index, Key, Row, Value},
+        data::{get_alias, get_index, Key, Row, Schema, Value},
         executor::{
             evaluate::evaluate,
-            select::{get_labels, select},
+            join::get_merged_columns,
+            select::{
+                get_labels, leftmost_operand, projected_join_columns, select, select_with_labels,
+                SelectError,
+            },
+            ExecuteOptions,
         },
         result::{Error, Result},
         store::GStore,
@@ -18,7 +29,7 @@ use {
     iter_enum::Iterator,
     itertools::Itertools,
     serde::Serialize,
-    std::{fmt::Debug, rc::Rc},
+    std::{collections::HashMap, fmt::Debug, rc::Rc},
     thiserror::Error as ThisError,
 };
 
@@ -30,20 +41,26 @@ pub enum FetchError {
     SeriesSizeWrong(i64),
     #[error("table '{0}' has {1} columns available but {2} column aliases specified")]
     TooManyColumnAliases(String, usize, usize),
+    #[error("row of table '{0}' is joined to more than one row")]
+    MultipleJoinedRows(String),
 }
 
 pub async fn fetch<'a>(
     storage: &'a dyn GStore,
     table_name: &'a str,
+    table_alias: &'a str,
     columns: Rc<[String]>,
     where_clause: Option<&'a Expr>,
+    filter_context: Option<Rc<FilterContext<'a>>>,
 ) -> Result<impl TryStream<Ok = (Rc<[String]>, Key, Row), Error = Error> + 'a> {
+    let Pushdown { filter, .. } = Pushdown::from_where(table_alias, &columns, where_clause);
     let rows = storage
-        .scan_data(table_name)
+        .scan_filtered_data(table_name, None, filter.as_ref())
         .await
         .map(stream::iter)?
         .try_filter_map(move |(key, row)| {
             let columns = Rc::clone(&columns);
+            let filter_context = filter_context.as_ref().map(Rc::clone);
 
             async move {
                 let expr = match where_clause {
@@ -53,7 +70,8 @@ pub async fn fetch<'a>(
                     Some(expr) => expr,
                 };
 
-                let context = FilterContext::new(table_name, Rc::clone(&columns), &row, None);
+                let context =
+                    FilterContext::new(table_alias, Rc::clone(&columns), &row, filter_context);
 
                 check_expr(storage, Some(Rc::new(context)), None, expr)
                     .await
@@ -64,6 +82,100 @@ pub async fn fetch<'a>(
     Ok(rows)
 }
 
+/// Rows of the target table of `UPDATE` or `DELETE` with joins, each paired with
+/// the first of its joined rows which passes the `WHERE` clause.
+/// With `unique`, a target row which more than one joined row passes with is an error,
+/// as `UPDATE` could not tell which of them to take the new values from.
+#[allow(clippy::too_many_arguments)]
+pub async fn fetch_joined<'a>(
+    storage: &'a dyn GStore,
+    table_name: &'a str,
+    table_alias: &'a str,
+    columns: Rc<[String]>,
+    joins: &'a [Join],
+    where_clause: Option<&'a Expr>,
+    unique: bool,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+) -> Result<Vec<(Key, Row, Rc<BlendContext<'a>>)>> {
+    let targets = storage
+        .scan_data(table_name)
+        .await?
+        .map(|item| item.map(|(key, row)| (key, Rc::new(row))))
+        .collect::<Result<Vec<_>>>()?;
+    let indexes = targets
+        .iter()
+        .enumerate()
+        .map(|(index, (_, row))| (Rc::as_ptr(row), index))
+        .collect::<HashMap<_, _>>();
+    let rows = targets
+        .iter()
+        .map(|(_, row)| {
+            let row = Shared(Rc::clone(row));
+
+            Ok(BlendContext::new(
+                table_alias,
+                Rc::clone(&columns),
+                row,
+                None,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let options = filter_context
+        .as_deref()
+        .map(FilterContext::options)
+        .unwrap_or_default();
+    let join_columns = fetch_join_columns(joins, storage, options).await?;
+    let merged_columns = get_merged_columns(&columns, joins, &join_columns)?;
+    let join_columns = join_columns
+        .into_iter()
+        .map(|(_, columns)| Rc::from(columns))
+        .collect();
+    let merged_columns = merged_columns.into_iter().map(Rc::from).collect();
+    let join = JoinExecutor::new(
+        storage,
+        table_alias,
+        Rc::clone(&columns),
+        joins,
+        join_columns,
+        merged_columns,
+        filter_context.as_ref().map(Rc::clone),
+    );
+    let filter = Filter::new(storage, where_clause, filter_context, None);
+
+    let mut joined = join.apply(stream::iter(rows)).await?;
+    let mut matched = vec![None; targets.len()];
+    while let Some(blend_context) = joined.try_next().await? {
+        let index = blend_context
+            .get_leftmost_row()
+            .and_then(|row| indexes.get(&Rc::as_ptr(row)))
+            .copied();
+
+        let index = match index {
+            Some(index) if unique || matched[index].is_none() => index,
+            _ => continue,
+        };
+
+        if !filter.check(Rc::clone(&blend_context)).await? {
+            continue;
+        } else if matched[index].is_some() {
+            return Err(FetchError::MultipleJoinedRows(table_name.to_owned()).into());
+        }
+
+        matched[index] = Some(blend_context);
+    }
+
+    let rows = targets
+        .into_iter()
+        .zip(matched)
+        .filter_map(|((key, row), blend_context)| {
+            blend_context.map(|blend_context| (key, Row::clone(&row), blend_context))
+        })
+        .collect();
+
+    Ok(rows)
+}
+
 #[derive(futures_enum::Stream)]
 pub enum Rows<I1, I2, I3, I4> {
     Derived(I1),
@@ -76,6 +188,7 @@ pub async fn fetch_relation_rows<'a>(
     storage: &'a dyn GStore,
     table_factor: &'a TableFactor,
     filter_context: &Option<Rc<FilterContext<'a>>>,
+    pushdown: Option<Pushdown>,
 ) -> Result<impl TryStream<Ok = Row, Error = Error, Item = Result<Row>> + 'a> {
     match table_factor {
         TableFactor::Derived { subquery, .. } => {
@@ -105,8 +218,16 @@ pub async fn fetch_relation_rows<'a>(
                     Some(IndexItem::NonClustered {
                         name: index_name,
                         asc,
+                        prefix_exprs,
                         cmp_expr,
                     }) => {
+                        let mut prefix_values = Vec::with_capacity(prefix_exprs.len());
+                        for expr in prefix_exprs {
+                            let evaluated = evaluate(storage, None, None, expr).await?;
+
+                            prefix_values.push(evaluated.try_into()?);
+                        }
+
                         let cmp_value = match cmp_expr {
                             Some((op, expr)) => {
                                 let evaluated = evaluate(storage, None, None, expr).await?;
@@ -117,18 +238,23 @@ pub async fn fetch_relation_rows<'a>(
                         };
 
                         let rows = storage
-                            .scan_indexed_data(name, index_name, *asc, cmp_value)
+                            .scan_indexed_data(name, index_name, *asc, prefix_values, cmp_value)
                             .await?
                             .map_ok(|(_, row)| row);
 
                         Rows::Indexed(rows)
                     }
-                    Some(IndexItem::PrimaryKey(expr)) => {
-                        let filter_context = filter_context.as_ref().map(Rc::clone);
-                        let key = evaluate(storage, filter_context, None, expr)
-                            .await
-                            .and_then(Value::try_from)
-                            .and_then(Key::try_from)?;
+                    Some(IndexItem::PrimaryKey(exprs)) => {
+                        let mut values = Vec::with_capacity(exprs.len());
+                        for expr in exprs {
+                            let filter_context = filter_context.as_ref().map(Rc::clone);
+                            let value = evaluate(storage, filter_context, None, expr)
+                                .await
+                                .and_then(Value::try_from)?;
+
+                            values.push(value);
+                        }
+                        let key = Key::from_primary_values(values)?;
 
                         let rows = storage
                             .fetch_data(name, &key)
@@ -140,7 +266,15 @@ pub async fn fetch_relation_rows<'a>(
                         Rows::PrimaryKey(rows.into_iter())
                     }
                     _ => {
-                        let rows = storage.scan_data(name).await?.map_ok(|(_, row)| row);
+                        let rows = match pushdown {
+                            Some(Pushdown { columns, filter }) => {
+                                storage
+                                    .scan_filtered_data(name, columns.as_deref(), filter.as_ref())
+                                    .await?
+                            }
+                            None => storage.scan_data(name).await?,
+                        }
+                        .map_ok(|(_, row)| row);
 
                         Rows::FullScan(rows)
                     }
@@ -188,27 +322,63 @@ pub async fn fetch_relation_rows<'a>(
 
                             table_rows.into_iter().chain(index_rows)
                         });
+                        let views = storage.fetch_all_views().await?;
+                        let view_rows = views.into_iter().map(|view| {
+                            Ok(Row(vec![
+                                Value::Str(view.name),
+                                Value::Str("VIEW".to_owned()),
+                                Value::Timestamp(view.created),
+                            ]))
+                        });
 
-                        Rows::Objects(rows)
+                        Rows::Objects(rows.chain(view_rows))
                     }
                     Dictionary::GlueTables => {
                         let schemas = storage.fetch_all_schemas().await?;
-                        let rows = schemas
-                            .into_iter()
-                            .map(|schema| Ok(Row(vec![Value::Str(schema.table_name)])));
+                        let rows = schemas.into_iter().map(|schema| {
+                            let namespace = match schema.namespace() {
+                                Some(namespace) => Value::Str(namespace.to_owned()),
+                                None => Value::Null,
+                            };
+
+                            Ok(Row(vec![Value::Str(schema.table_name), namespace]))
+                        });
 
                         Rows::Tables(rows)
                     }
                     Dictionary::GlueTableColumns => {
                         let schemas = storage.fetch_all_schemas().await?;
                         let rows = schemas.into_iter().flat_map(|schema| {
-                            let table_name = schema.table_name;
-                            schema.column_defs.into_iter().enumerate().map(
+                            let Schema {
+                                table_name,
+                                column_defs,
+                                foreign_keys,
+                                ..
+                            } = schema;
+
+                            column_defs.into_iter().enumerate().map(
                                 move |(index, ColumnDef { name, .. })| -> Result<_> {
+                                    let referenced_column = foreign_keys
+                                        .iter()
+                                        .find_map(|foreign_key| {
+                                            foreign_key.columns.iter().position(|c| c == &name).map(
+                                                |i| {
+                                                    format!(
+                                                        "{}.{}",
+                                                        foreign_key.referenced_table_name,
+                                                        foreign_key.referenced_columns[i]
+                                                    )
+                                                },
+                                            )
+                                        })
+                                        .map(Value::Str)
+                                        .unwrap_or(Value::Null);
+
                                     Ok(Row(vec![
                                         Value::Str(table_name.clone()),
                                         Value::Str(name),
                                         Value::I64(index as i64 + 1),
+                                        referenced_column,
                                     ]))
                                 },
                             )
@@ -219,23 +389,21 @@ pub async fn fetch_relation_rows<'a>(
                     Dictionary::GlueIndexes => {
                         let schemas = storage.fetch_all_schemas().await?;
                         let rows = schemas.into_iter().flat_map(|schema| {
-                            let primary_column = schema.column_defs.iter().find_map(
-                                |ColumnDef { name, options, .. }| {
-                                    options
-                                        .iter()
-                                        .any(|option| {
-                                            option == &ColumnOption::Unique { is_primary: true }
-                                        })
-                                        .then_some(name)
-                                },
-                            );
+                            let primary_column = schema.primary_key_indexes().map(|indexes| {
+                                indexes
+                                    .into_iter()
+                                    .filter_map(|i| schema.column_defs.get(i))
+                                    .map(|ColumnDef { name, .. }| name.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            });
 
                             let clustered = match primary_column {
                                 Some(column_name) => vec![Ok(Row(vec![
                                     Value::Str(schema.table_name.clone()),
                                     Value::Str("PRIMARY".to_owned()),
                                     Value::Str("BOTH".to_owned()),
-                                    Value::Str(column_name.to_owned()),
+                                    Value::Str(column_name),
                                     Value::Bool(true),
                                 ]))],
                                 None => Vec::new(),
@@ -246,7 +414,7 @@ pub async fn fetch_relation_rows<'a>(
                                     Value::Str(schema.table_name.clone()),
                                     Value::Str(index.name),
                                     Value::Str(index.order.to_string()),
-                                    Value::Str(index.expr.to_sql()),
+                                    Value::Str(index.exprs_to_sql()),
                                     Value::Bool(false),
                                 ]))
                             });
@@ -279,6 +447,7 @@ pub async fn fetch_columns(storage: &dyn GStore, table_name: &str) -> Result<Vec
 pub async fn fetch_relation_columns(
     storage: &dyn GStore,
     table_factor: &TableFactor,
+    options: ExecuteOptions,
 ) -> Result<Vec<String>> {
     match table_factor {
         TableFactor::Table { name, .. } => fetch_columns(storage, name).await,
@@ -289,11 +458,12 @@ pub async fn fetch_relation_columns(
                 "OBJECT_TYPE".to_owned(),
                 "CREATED".to_owned(),
             ]),
-            Dictionary::GlueTables => Ok(vec!["TABLE_NAME".to_owned()]),
+            Dictionary::GlueTables => Ok(vec!["TABLE_NAME".to_owned(), "SCHEMA_NAME".to_owned()]),
             Dictionary::GlueTableColumns => Ok(vec![
                 "TABLE_NAME".to_owned(),
                 "COLUMN_NAME".to_owned(),
                 "COLUMN_ID".to_owned(),
+                "REFERENCED_COLUMN".to_owned(),
             ]),
             Dictionary::GlueIndexes => Ok(vec![
                 "TABLE_NAME".to_owned(),
@@ -303,10 +473,20 @@ pub async fn fetch_relation_columns(
                 "UNIQUENESS".to_owned(),
             ]),
         },
+        TableFactor::Derived {
+            subquery: subquery @ Query { with: Some(_), .. },
+            ..
+        } => {
+            let filter_context = Some(Rc::new(FilterContext::with_options(options)));
+
+            select_with_labels(storage, subquery, filter_context, true)
+                .await
+                .map(|(labels, _)| labels)
+        }
         TableFactor::Derived {
             subquery: Query { body, .. },
             alias: TableAlias { columns, name },
-        } => match body {
+        } => match leftmost_operand(body) {
             SetExpr::Select(statement) => {
                 let Select {
                     from:
@@ -317,13 +497,17 @@ pub async fn fetch_relation_columns(
                     ..
                 } = statement.as_ref();
 
-                let columns = fetch_relation_columns(storage, relation).await?;
-                let join_columns = fetch_join_columns(joins, storage).await?;
+                let columns = fetch_relation_columns(storage, relation, options).await?;
+                let join_columns = fetch_join_columns(joins, storage, options).await?;
+                let merged_columns = get_merged_columns(&columns, joins, &join_columns)?;
+                let (join_columns, merged_columns) =
+                    projected_join_columns(joins, &join_columns, &merged_columns);
                 let labels = get_labels(
                     projection,
                     get_alias(relation),
                     &columns,
                     Some(&join_columns),
+                    &merged_columns,
                 )?;
 
                 Ok(labels)
@@ -346,6 +530,7 @@ pub async fn fetch_relation_columns(
 
                 Ok(labels)
             }
+            SetExpr::SetOperation { .. } => Err(SelectError::Unreachable.into()),
         },
     }
 }
@@ -353,13 +538,14 @@ pub async fn fetch_relation_columns(
 pub async fn fetch_join_columns<'a>(
     joins: &'a [Join],
     storage: &dyn GStore,
+    options: ExecuteOptions,
 ) -> Result<Vec<(&'a String, Vec<String>)>> {
     stream::iter(joins.iter())
         .map(Ok::<_, Error>)
         .and_then(|join| async move {
             let relation = &join.relation;
             let alias = get_alias(relation);
-            let columns = fetch_relation_columns(storage, relation).await?;
+            let columns = fetch_relation_columns(storage, relation, options).await?;
             Ok((alias, columns))
         })
         .try_collect::<Vec<_>>()
@@ -3,12 +3,7 @@ use {
         context::{BlendContext, FilterContext},
         evaluate::evaluate,
     },
-    crate::{
-        ast::{Aggregate, Expr},
-        data::Value,
-        result::Result,
-        store::GStore,
-    },
+    crate::{ast::Expr, data::Value, result::Result, store::GStore},
     im_rc::HashMap,
     std::rc::Rc,
 };
@@ -17,7 +12,7 @@ pub struct Filter<'a> {
     storage: &'a dyn GStore,
     where_clause: Option<&'a Expr>,
     context: Option<Rc<FilterContext<'a>>>,
-    aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+    aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
 }
 
 impl<'a> Filter<'a> {
@@ -25,7 +20,7 @@ impl<'a> Filter<'a> {
         storage: &'a dyn GStore,
         where_clause: Option<&'a Expr>,
         context: Option<Rc<FilterContext<'a>>>,
-        aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+        aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
     ) -> Self {
         Self {
             storage,
@@ -53,7 +48,7 @@ impl<'a> Filter<'a> {
 pub async fn check_expr<'a>(
     storage: &'a dyn GStore,
     context: Option<Rc<FilterContext<'a>>>,
-    aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+    aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
     expr: &'a Expr,
 ) -> Result<bool> {
     evaluate(storage, context, aggregated, expr)
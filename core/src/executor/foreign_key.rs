@@ -0,0 +1,503 @@
+use {
+    super::{
+        evaluate_stateless,
+        generated::{dependent_generated_indexes, fill_generated_values},
+        validate::{
+            validate_checks, validate_composite_primary_key, validate_unique, ColumnValidation,
+        },
+    },
+    crate::{
+        ast::{ColumnDef, ForeignKey, ReferentialAction},
+        data::{Key, Row, Schema, Value},
+        result::{MutResult, Result, TrySelf},
+        store::{GStore, GStoreMut},
+    },
+    async_recursion::async_recursion,
+    serde::Serialize,
+    std::{
+        collections::{HashMap, HashSet},
+        fmt::Debug,
+        rc::Rc,
+    },
+    thiserror::Error as ThisError,
+};
+
+#[cfg(feature = "index")]
+use crate::{
+    ast::{Expr, IndexOperator},
+    data::SchemaIndex,
+};
+
+#[derive(ThisError, Serialize, Debug, PartialEq)]
+pub enum ForeignKeyError {
+    #[error("foreign key violation - referenced row not found in table: {0}")]
+    ReferencedRowNotFound(String),
+
+    #[error("foreign key violation - row is still referenced from table: {0}")]
+    RowStillReferenced(String),
+
+    #[error("conflict - foreign key column not found: {0}")]
+    ConflictOnColumnNotFound(String),
+}
+
+/// Row change made by `UPDATE` or `DELETE`, deleted rows have no new row.
+pub type RowChange = (Key, Row, Option<Row>);
+
+fn column_indexes(column_defs: &[ColumnDef], columns: &[String]) -> Result<Vec<usize>> {
+    columns
+        .iter()
+        .map(|column_name| {
+            column_defs
+                .iter()
+                .position(|ColumnDef { name, .. }| name == column_name)
+                .ok_or_else(|| ForeignKeyError::ConflictOnColumnNotFound(column_name.to_owned()))
+                .map_err(Into::into)
+        })
+        .collect()
+}
+
+/// Values of the columns as keys, `None` if any of them is `NULL`.
+pub fn row_keys(row: &Row, indexes: &[usize]) -> Result<Option<Vec<Key>>> {
+    indexes
+        .iter()
+        .map(|index| match row.get_value_by_index(*index) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => Key::try_from(value).map(Some),
+        })
+        .collect()
+}
+
+/// Values of the columns, taken from a row of which [`row_keys`] are not `None`.
+fn row_values(row: &Row, indexes: &[usize]) -> Vec<Value> {
+    indexes
+        .iter()
+        .filter_map(|index| row.get_value_by_index(*index))
+        .cloned()
+        .collect()
+}
+
+/// Key of the row after the change, `ON UPDATE CASCADE` may change the values of its
+/// primary key.
+fn changed_key(primary_key: Option<&[usize]>, key: &Key, row: &Row) -> Result<Key> {
+    match primary_key {
+        Some(primary_key) => Key::from_primary_values(row_values(row, primary_key)),
+        None => Ok(key.clone()),
+    }
+}
+
+/// Index of which every expression is one of the columns, along with the positions of
+/// the columns in the order of the index.
+#[cfg(feature = "index")]
+pub(super) fn find_index<'a>(
+    schema: &'a Schema,
+    columns: &[String],
+) -> Option<(&'a str, Vec<usize>)> {
+    schema
+        .indexes
+        .iter()
+        .find_map(|SchemaIndex { name, exprs, .. }| {
+            let positions = exprs
+                .iter()
+                .map(|expr| match expr {
+                    Expr::Identifier(ident) => columns.iter().position(|column| column == ident),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            (!positions.is_empty()).then_some((name.as_str(), positions))
+        })
+}
+
+/// Rows of the table having one of `values_list` in the columns. They are looked up
+/// through the primary key or an index on the columns, the table is scanned only if
+/// it has neither.
+async fn fetch_rows_by_values(
+    storage: &dyn GStore,
+    schema: &Schema,
+    columns: &[String],
+    values_list: &[Vec<Value>],
+) -> Result<Vec<(Key, Row)>> {
+    let indexes = column_indexes(&schema.column_defs, columns)?;
+
+    if let Some(primary_key) = schema.primary_key_indexes() {
+        let positions = primary_key
+            .iter()
+            .map(|i| indexes.iter().position(|index| index == i))
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(positions) = positions.filter(|_| primary_key.len() == indexes.len()) {
+            let mut rows = Vec::new();
+
+            for values in values_list {
+                let values = positions.iter().map(|p| values[*p].clone()).collect();
+                let key = Key::from_primary_values(values)?;
+
+                if let Some(row) = storage.fetch_data(&schema.table_name, &key).await? {
+                    rows.push((key, row));
+                }
+            }
+
+            return Ok(rows);
+        }
+    }
+
+    let targets = values_list
+        .iter()
+        .map(|values| values.iter().map(Key::try_from).collect())
+        .collect::<Result<HashSet<Vec<Key>>>>()?;
+    let matches = |row: &Row| -> Result<bool> {
+        Ok(row_keys(row, &indexes)?.map_or(false, |keys| targets.contains(&keys)))
+    };
+
+    #[cfg(feature = "index")]
+    if let Some((index_name, positions)) = find_index(schema, columns) {
+        let mut rows = Vec::new();
+
+        for values in values_list {
+            let mut prefix_values = positions
+                .iter()
+                .map(|p| values[*p].clone())
+                .collect::<Vec<_>>();
+            let cmp_value = prefix_values.pop().map(|value| (&IndexOperator::Eq, value));
+
+            for item in storage
+                .scan_indexed_data(
+                    &schema.table_name,
+                    index_name,
+                    None,
+                    prefix_values,
+                    cmp_value,
+                )
+                .await?
+            {
+                let (key, row) = item?;
+
+                if matches(&row)? {
+                    rows.push((key, row));
+                }
+            }
+        }
+
+        return Ok(rows);
+    }
+
+    storage
+        .scan_data(&schema.table_name)
+        .await?
+        .filter_map(|item| {
+            item.and_then(|(key, row)| Ok(matches(&row)?.then_some((key, row))))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Checks every row of `INSERT` or `UPDATE` refers to an existing row
+/// through each FOREIGN KEY of the table.
+pub async fn validate_references<'a>(
+    storage: &dyn GStore,
+    schema: &Schema,
+    rows: impl Iterator<Item = &'a Row> + Clone,
+) -> Result<()> {
+    for foreign_key in &schema.foreign_keys {
+        let ForeignKey {
+            columns,
+            referenced_table_name,
+            referenced_columns,
+            ..
+        } = foreign_key;
+
+        let indexes = column_indexes(&schema.column_defs, columns)?;
+        let mut keys_list = HashSet::new();
+        let mut values_list = Vec::new();
+
+        for row in rows.clone() {
+            if let Some(keys) = row_keys(row, &indexes)? {
+                if keys_list.insert(keys) {
+                    values_list.push(row_values(row, &indexes));
+                }
+            }
+        }
+
+        if values_list.is_empty() {
+            continue;
+        }
+
+        let fetched;
+        let referenced_schema = match referenced_table_name == &schema.table_name {
+            true => schema,
+            false => {
+                fetched = storage
+                    .fetch_schema(referenced_table_name)
+                    .await?
+                    .ok_or_else(|| {
+                        ForeignKeyError::ReferencedRowNotFound(referenced_table_name.to_owned())
+                    })?;
+
+                &fetched
+            }
+        };
+        let referenced_indexes =
+            column_indexes(&referenced_schema.column_defs, referenced_columns)?;
+        let mut referenced_keys =
+            fetch_rows_by_values(storage, referenced_schema, referenced_columns, &values_list)
+                .await?
+                .into_iter()
+                .filter_map(|(_, row)| row_keys(&row, &referenced_indexes).transpose())
+                .collect::<Result<HashSet<_>>>()?;
+
+        if referenced_table_name == &schema.table_name {
+            for row in rows.clone() {
+                if let Some(keys) = row_keys(row, &referenced_indexes)? {
+                    referenced_keys.insert(keys);
+                }
+            }
+        }
+
+        if !keys_list.is_subset(&referenced_keys) {
+            return Err(
+                ForeignKeyError::ReferencedRowNotFound(referenced_table_name.to_owned()).into(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies `ON DELETE` and `ON UPDATE` actions of the FOREIGN KEYs referencing
+/// the changed rows of the table.
+#[async_recursion(?Send)]
+pub async fn apply_references<T: GStore + GStoreMut>(
+    storage: T,
+    schema: &Schema,
+    changes: &[RowChange],
+) -> MutResult<T, ()> {
+    if changes.is_empty() {
+        return Ok((storage, ()));
+    }
+
+    let (mut storage, schemas) = storage.fetch_all_schemas().await.try_self(storage)?;
+    let referencing = schemas.iter().flat_map(|child| {
+        child
+            .foreign_keys
+            .iter()
+            .filter(|foreign_key| foreign_key.referenced_table_name == schema.table_name)
+            .map(move |foreign_key| (child, foreign_key))
+    });
+
+    for (child, foreign_key) in referencing {
+        let (next, (deleted, updated)) =
+            referencing_changes(&storage, schema, child, foreign_key, changes)
+                .await
+                .try_self(storage)?;
+        storage = next;
+
+        if !deleted.is_empty() {
+            let keys = deleted.iter().map(|(key, _, _)| key.clone()).collect();
+            let (next, _) = storage.delete_data(&child.table_name, keys).await?;
+
+            storage = apply_references(next, child, &deleted).await?.0;
+        }
+
+        if !updated.is_empty() {
+            let primary_key = child.primary_key_indexes();
+            let (next, rows) = updated
+                .iter()
+                .filter_map(|(key, _, row)| row.as_ref().map(|row| (key, row)))
+                .map(|(key, row)| {
+                    changed_key(primary_key.as_deref(), key, row).map(|new_key| (new_key, row))
+                })
+                .collect::<Result<Vec<_>>>()
+                .try_self(storage)?;
+            storage = next;
+
+            // rows of which the primary key is changed are moved to their new keys
+            let moved_keys = updated
+                .iter()
+                .zip(&rows)
+                .filter(|((key, _, _), (new_key, _))| key != new_key)
+                .map(|((key, _, _), _)| key.clone())
+                .collect::<Vec<_>>();
+            if !moved_keys.is_empty() {
+                storage = storage.delete_data(&child.table_name, moved_keys).await?.0;
+            }
+
+            let rows = rows
+                .into_iter()
+                .map(|(key, row)| (key, row.clone()))
+                .collect();
+            let (next, _) = storage.insert_data(&child.table_name, rows).await?;
+
+            storage = apply_references(next, child, &updated).await?.0;
+        }
+    }
+
+    Ok((storage, ()))
+}
+
+/// Finds the rows of `child` referencing the changed rows, and returns them
+/// split into rows to delete and rows to update by the referential actions.
+/// Updated rows are validated as `UPDATE` validates its rows.
+async fn referencing_changes<T: GStore>(
+    storage: &T,
+    schema: &Schema,
+    child: &Schema,
+    foreign_key: &ForeignKey,
+    changes: &[RowChange],
+) -> Result<(Vec<RowChange>, Vec<RowChange>)> {
+    let referenced_indexes = column_indexes(&schema.column_defs, &foreign_key.referenced_columns)?;
+    let mut changed = HashMap::new();
+    let mut old_values_list = Vec::new();
+
+    for (_, old_row, new_row) in changes {
+        let old_keys = match row_keys(old_row, &referenced_indexes)? {
+            Some(old_keys) => old_keys,
+            None => continue,
+        };
+
+        let new_values = match new_row {
+            Some(new_row)
+                if row_keys(new_row, &referenced_indexes)?.as_ref() == Some(&old_keys) =>
+            {
+                continue;
+            }
+            Some(new_row) => Some(row_values(new_row, &referenced_indexes)),
+            None => None,
+        };
+
+        if !changed.contains_key(&old_keys) {
+            old_values_list.push(row_values(old_row, &referenced_indexes));
+        }
+
+        changed.insert(old_keys, new_values);
+    }
+
+    if changed.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let changed_keys: HashSet<&Key> = match child.table_name == schema.table_name {
+        true => changes.iter().map(|(key, _, _)| key).collect(),
+        false => HashSet::new(),
+    };
+    let indexes = column_indexes(&child.column_defs, &foreign_key.columns)?;
+    let mut rows = Vec::new();
+
+    for (key, row) in
+        fetch_rows_by_values(storage, child, &foreign_key.columns, &old_values_list).await?
+    {
+        if changed_keys.contains(&key) {
+            continue;
+        }
+
+        let new_values = match row_keys(&row, &indexes)? {
+            Some(keys) => changed.get(&keys),
+            None => None,
+        };
+
+        if let Some(new_values) = new_values {
+            rows.push((key, row, new_values.clone()));
+        }
+    }
+
+    let generated = dependent_generated_indexes(&child.column_defs, &foreign_key.columns)?;
+    let mut deleted = Vec::new();
+    let mut updated = Vec::new();
+
+    for (key, row, new_values) in rows {
+        let action = match new_values {
+            Some(_) => foreign_key.on_update,
+            None => foreign_key.on_delete,
+        };
+
+        let values = match (action, new_values) {
+            (ReferentialAction::NoAction | ReferentialAction::Restrict, _) => {
+                return Err(
+                    ForeignKeyError::RowStillReferenced(child.table_name.to_owned()).into(),
+                );
+            }
+            (ReferentialAction::Cascade, None) => {
+                deleted.push((key, row, None));
+
+                continue;
+            }
+            (ReferentialAction::Cascade, Some(new_values)) => new_values,
+            (ReferentialAction::SetNull, _) => vec![Value::Null; indexes.len()],
+            (ReferentialAction::SetDefault, _) => indexes
+                .iter()
+                .map(|index| {
+                    let ColumnDef {
+                        data_type,
+                        nullable,
+                        ..
+                    } = &child.column_defs[*index];
+
+                    match child.column_defs[*index].get_default() {
+                        Some(expr) => {
+                            evaluate_stateless(None, expr)?.try_into_value(data_type, *nullable)
+                        }
+                        None => Ok(Value::Null),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        let mut new_row = row.clone();
+        for (index, value) in indexes.iter().zip(values) {
+            new_row.0[*index] = value;
+        }
+
+        let new_row = fill_generated_values(&child.column_defs, &generated, new_row)?;
+
+        new_row.validate(&child.column_defs)?;
+        updated.push((key, row, Some(new_row)));
+    }
+
+    let updated_rows = updated.iter().filter_map(|(_, _, row)| row.as_ref());
+    let columns = foreign_key
+        .columns
+        .iter()
+        .cloned()
+        .chain(
+            generated
+                .iter()
+                .map(|i| child.column_defs[*i].name.to_owned()),
+        )
+        .collect();
+    let column_validation =
+        ColumnValidation::SpecifiedColumns(Rc::from(child.column_defs.as_slice()), columns);
+    validate_unique(
+        storage,
+        &child.table_name,
+        column_validation,
+        updated_rows.clone(),
+    )
+    .await?;
+    validate_checks(storage, child, updated_rows.clone()).await?;
+
+    if child.primary_key.is_some() {
+        let primary_key = child.primary_key_indexes();
+        let mut moved_keys = Vec::new();
+
+        for (key, _, row) in &updated {
+            if let Some(row) = row {
+                let new_key = changed_key(primary_key.as_deref(), key, row)?;
+
+                if &new_key != key {
+                    moved_keys.push(new_key);
+                }
+            }
+        }
+
+        validate_composite_primary_key(storage, &child.table_name, moved_keys.iter()).await?;
+    }
+
+    if foreign_key.on_delete == ReferentialAction::SetDefault
+        || foreign_key.on_update == ReferentialAction::SetDefault
+    {
+        let rows = updated.iter().filter_map(|(_, _, row)| row.as_ref());
+
+        validate_references(storage, child, rows).await?;
+    }
+
+    Ok((deleted, updated))
+}
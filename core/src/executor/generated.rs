@@ -0,0 +1,90 @@
+use {
+    super::{alter::check_columns, evaluate_stateless},
+    crate::{
+        ast::ColumnDef,
+        data::{Row, RowError, Value},
+        result::Result,
+    },
+};
+
+/// Positions of the generated columns, which are computed on `INSERT`.
+pub fn generated_indexes(column_defs: &[ColumnDef]) -> Vec<usize> {
+    column_defs
+        .iter()
+        .enumerate()
+        .filter(|(_, column_def)| column_def.get_generated().is_some())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Positions of the generated columns referring to the changed columns,
+/// which are recomputed on `UPDATE` and by `ON UPDATE` actions.
+pub fn dependent_generated_indexes(
+    column_defs: &[ColumnDef],
+    changed_columns: &[String],
+) -> Result<Vec<usize>> {
+    let mut indexes = Vec::new();
+
+    for (index, column_def) in column_defs.iter().enumerate() {
+        let expr = match column_def.get_generated() {
+            Some(expr) => expr,
+            None => continue,
+        };
+
+        let column_names = check_columns(expr)?;
+        if changed_columns
+            .iter()
+            .any(|column_name| column_names.contains(&column_name.as_str()))
+        {
+            indexes.push(index);
+        }
+    }
+
+    Ok(indexes)
+}
+
+/// Rows of `INSERT .. SELECT` cannot have values of the generated columns.
+pub fn validate_generated_values(column_defs: &[ColumnDef], row: &Row) -> Result<()> {
+    for index in generated_indexes(column_defs) {
+        if !matches!(row.get_value_by_index(index), None | Some(Value::Null)) {
+            let column_name = column_defs[index].name.to_owned();
+
+            return Err(RowError::ValueOnGeneratedColumn(column_name).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the values of the generated columns at `indexes` from the other columns of the row.
+pub fn fill_generated_values(
+    column_defs: &[ColumnDef],
+    indexes: &[usize],
+    row: Row,
+) -> Result<Row> {
+    if indexes.is_empty() {
+        return Ok(row);
+    }
+
+    let columns = column_defs
+        .iter()
+        .map(|ColumnDef { name, .. }| name.to_owned())
+        .collect::<Vec<_>>();
+    let mut values = row.0.clone();
+
+    for index in indexes {
+        let column_def = &column_defs[*index];
+        let ColumnDef {
+            data_type,
+            nullable,
+            ..
+        } = column_def;
+
+        if let (Some(expr), Some(value)) = (column_def.get_generated(), values.get_mut(*index)) {
+            *value = evaluate_stateless(Some((columns.as_slice(), &row)), expr)?
+                .try_into_value(data_type, *nullable)?;
+        }
+    }
+
+    Ok(Row(values))
+}
@@ -0,0 +1,99 @@
+use {
+    super::evaluate::evaluate,
+    crate::{
+        ast::{ColumnDef, Expr, Function},
+        data::{Row, RowError, Schema, Sequence, SequenceError, Value},
+        result::Result,
+        store::GStore,
+    },
+};
+
+/// Evaluates `NEXTVAL` and `CURRVAL` of `INSERT .. VALUES` ahead of building the rows,
+/// which are built from stateless expressions only.
+pub async fn evaluate_sequence_functions(
+    storage: &dyn GStore,
+    values_list: &[Vec<Expr>],
+) -> Result<Vec<Vec<Expr>>> {
+    let mut evaluated = Vec::with_capacity(values_list.len());
+
+    for values in values_list {
+        let mut exprs = Vec::with_capacity(values.len());
+
+        for expr in values {
+            let expr = match expr {
+                Expr::Function(func)
+                    if matches!(func.as_ref(), Function::Nextval(_) | Function::Currval(_)) =>
+                {
+                    let value = evaluate(storage, None, None, expr)
+                        .await
+                        .and_then(Value::try_from)?;
+
+                    Expr::try_from(value)?
+                }
+                _ => expr.clone(),
+            };
+
+            exprs.push(expr);
+        }
+
+        evaluated.push(exprs);
+    }
+
+    Ok(evaluated)
+}
+
+/// Values of `GENERATED ALWAYS` identity columns come from their sequences only,
+/// unless `INSERT` is given `OVERRIDING SYSTEM VALUE`.
+pub fn validate_identity_values(column_defs: &[ColumnDef], rows: &[Row]) -> Result<()> {
+    for (index, column_def) in column_defs.iter().enumerate() {
+        if !column_def.is_always_identity() {
+            continue;
+        }
+
+        if rows
+            .iter()
+            .any(|row| !matches!(row.get_value_by_index(index), None | Some(Value::Null)))
+        {
+            let column_name = column_def.name.to_owned();
+
+            return Err(RowError::ValueOnAlwaysIdentityColumn(column_name).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Fills the omitted values of identity columns with the next values of their sequences.
+pub async fn fill_identity_values(
+    storage: &dyn GStore,
+    schema: &Schema,
+    rows: &mut [Row],
+) -> Result<()> {
+    for (index, column_def) in schema.column_defs.iter().enumerate() {
+        let ColumnDef {
+            name, data_type, ..
+        } = column_def;
+
+        if !column_def.is_identity() {
+            continue;
+        }
+
+        let Sequence { name, .. } = Sequence::identity(&schema.table_name, name);
+        let sequence = storage
+            .fetch_sequence(&name)
+            .await?
+            .ok_or(SequenceError::NotFound(name))?;
+
+        for Row(values) in rows.iter_mut() {
+            if !matches!(values[index], Value::Null) {
+                continue;
+            }
+
+            let value = storage.next_sequence_value(&sequence).await?;
+
+            values[index] = Value::I64(value).cast(data_type)?;
+        }
+    }
+
+    Ok(())
+}
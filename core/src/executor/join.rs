@@ -9,6 +9,7 @@ use {
         executor::{
             context::{BlendContext, BlendContextRow::Single, FilterContext},
             evaluate::evaluate,
+            explain::{measure, measure_future, Profile, Stage},
             filter::check_expr,
         },
         result::{Error, Result},
@@ -19,15 +20,27 @@ use {
         stream::{self, empty, once, Stream, StreamExt, TryStream, TryStreamExt},
     },
     itertools::Itertools,
+    serde::Serialize,
     std::{borrow::Cow, collections::HashMap, pin::Pin, rc::Rc},
+    thiserror::Error as ThisError,
     utils::OrStream,
 };
 
+#[derive(ThisError, Serialize, Debug, PartialEq, Eq)]
+pub enum JoinError {
+    #[error("column in USING join constraint not found in both tables: {0}")]
+    UsingColumnNotFound(String),
+}
+
 pub struct Join<'a> {
     storage: &'a dyn GStore,
+    table_alias: &'a str,
+    columns: Rc<[String]>,
     join_clauses: &'a [AstJoin],
     join_columns: Vec<Rc<[String]>>,
+    merged_columns: Vec<Rc<[String]>>,
     filter_context: Option<Rc<FilterContext<'a>>>,
+    profile: Option<Rc<Profile>>,
 }
 
 type JoinItem<'a> = Rc<BlendContext<'a>>;
@@ -37,61 +50,188 @@ type Joined<'a> =
 impl<'a> Join<'a> {
     pub fn new(
         storage: &'a dyn GStore,
+        table_alias: &'a str,
+        columns: Rc<[String]>,
         join_clauses: &'a [AstJoin],
         join_columns: Vec<Rc<[String]>>,
+        merged_columns: Vec<Rc<[String]>>,
         filter_context: Option<Rc<FilterContext<'a>>>,
     ) -> Self {
         Self {
             storage,
+            table_alias,
+            columns,
             join_clauses,
             join_columns,
+            merged_columns,
             filter_context,
+            profile: None,
         }
     }
 
+    /// Collects the rows and the elapsed time of each join for `EXPLAIN ANALYZE`.
+    pub fn with_profile(self, profile: Option<Rc<Profile>>) -> Self {
+        Self { profile, ..self }
+    }
+
     pub async fn apply(
         self,
         rows: impl Stream<Item = Result<BlendContext<'a>>> + 'a,
     ) -> Result<Joined<'a>> {
         let init_rows: Joined = Box::pin(rows.map(|row| row.map(Rc::new)));
+        let init_context = Rc::new(BlendContext::new(
+            self.table_alias,
+            Rc::clone(&self.columns),
+            Single(None),
+            None,
+        ));
         let joins = self
             .join_clauses
             .iter()
-            .zip(self.join_columns.iter().map(Rc::clone));
-        stream::iter(joins)
+            .zip(self.join_columns.iter().map(Rc::clone))
+            .zip(self.merged_columns.iter().map(Rc::clone))
+            .scan(
+                init_context,
+                |null_context, ((join_clause, columns), merged_columns)| {
+                    let left_context = Rc::clone(null_context);
+                    let context = BlendContext::new(
+                        get_alias(&join_clause.relation),
+                        Rc::clone(&columns),
+                        Single(None),
+                        Some(Rc::clone(&left_context)),
+                    )
+                    .with_merged_columns(Rc::clone(&merged_columns));
+                    *null_context = Rc::new(context);
+
+                    Some((join_clause, columns, merged_columns, left_context))
+                },
+            );
+        stream::iter(joins.enumerate())
             .map(Ok)
-            .try_fold(init_rows, |rows, (join_clause, join_columns)| {
-                let filter_context = self.filter_context.as_ref().map(Rc::clone);
+            .try_fold(
+                init_rows,
+                |rows, (i, (join_clause, join_columns, merged_columns, null_context))| {
+                    let filter_context = self.filter_context.as_ref().map(Rc::clone);
+                    let profile = self.profile.as_ref().map(Rc::clone);
 
-                async move {
-                    join(
-                        self.storage,
-                        filter_context,
-                        join_clause,
-                        join_columns,
-                        rows,
-                    )
-                    .await
-                }
-            })
+                    async move {
+                        let rows = join(
+                            self.storage,
+                            filter_context,
+                            join_clause,
+                            join_columns,
+                            merged_columns,
+                            null_context,
+                            rows,
+                        );
+                        let rows = measure_future(profile.as_ref(), Stage::Join(i), rows).await?;
+
+                        match profile {
+                            Some(_) => {
+                                let rows = measure(profile.as_ref(), Stage::Join(i), rows);
+
+                                Ok(Box::pin(rows) as Joined)
+                            }
+                            None => Ok(rows),
+                        }
+                    }
+                },
+            )
             .await
     }
 }
 
+/// Columns each join shares with its left side through `USING` or `NATURAL`
+pub fn get_merged_columns(
+    columns: &[String],
+    joins: &[AstJoin],
+    join_columns: &[(&String, Vec<String>)],
+) -> Result<Vec<Vec<String>>> {
+    let mut left_columns = columns.iter().collect::<Vec<_>>();
+
+    joins
+        .iter()
+        .zip(join_columns)
+        .map(|(join, (_, columns))| {
+            let merged_columns = match get_constraint(&join.join_operator) {
+                Some(JoinConstraint::Using(using_columns)) => using_columns
+                    .iter()
+                    .map(|column| {
+                        if columns.contains(column) && left_columns.contains(&column) {
+                            Ok(column.to_owned())
+                        } else {
+                            Err(JoinError::UsingColumnNotFound(column.to_owned()).into())
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                Some(JoinConstraint::Natural) => columns
+                    .iter()
+                    .filter(|column| left_columns.contains(column))
+                    .cloned()
+                    .collect(),
+                Some(JoinConstraint::On(_) | JoinConstraint::None) | None => Vec::new(),
+            };
+
+            left_columns.extend(columns);
+
+            Ok(merged_columns)
+        })
+        .collect()
+}
+
+fn get_constraint(join_operator: &AstJoinOperator) -> Option<&JoinConstraint> {
+    match join_operator {
+        AstJoinOperator::Inner(constraint)
+        | AstJoinOperator::LeftOuter(constraint)
+        | AstJoinOperator::RightOuter(constraint)
+        | AstJoinOperator::FullOuter(constraint)
+        | AstJoinOperator::Semi(constraint)
+        | AstJoinOperator::Anti(constraint) => Some(constraint),
+        AstJoinOperator::Cross => None,
+    }
+}
+
+/// Right side of a join with the constraint its rows are matched by
+struct JoinRelation<'a> {
+    relation: &'a TableFactor,
+    columns: Rc<[String]>,
+    merged_columns: Rc<[String]>,
+    where_clause: Option<&'a Expr>,
+}
+
+impl<'a> JoinRelation<'a> {
+    fn table_alias(&self) -> &'a str {
+        get_alias(self.relation)
+    }
+
+    fn blend(&self, row: Option<Row>, next: Rc<BlendContext<'a>>) -> Rc<BlendContext<'a>> {
+        let context = BlendContext::new(
+            self.table_alias(),
+            Rc::clone(&self.columns),
+            Single(row),
+            Some(next),
+        )
+        .with_merged_columns(Rc::clone(&self.merged_columns));
+
+        Rc::new(context)
+    }
+}
+
 async fn join<'a>(
     storage: &'a dyn GStore,
     filter_context: Option<Rc<FilterContext<'a>>>,
     ast_join: &'a AstJoin,
     columns: Rc<[String]>,
+    merged_columns: Rc<[String]>,
+    null_context: Rc<BlendContext<'a>>,
     left_rows: impl TryStream<Ok = JoinItem<'a>, Error = Error, Item = Result<JoinItem<'a>>> + 'a,
 ) -> Result<Joined<'a>> {
     let AstJoin {
         relation,
-        join_operator,
+        join_operator: ast_join_operator,
         join_executor,
     } = ast_join;
 
-    let table_alias = get_alias(relation);
     let join_executor = JoinExecutor::new(
         storage,
         relation,
@@ -102,27 +242,47 @@ async fn join<'a>(
     .await
     .map(Rc::new)?;
 
-    let (join_operator, where_clause) = match join_operator {
-        AstJoinOperator::Inner(JoinConstraint::None) => (JoinOperator::Inner, None),
-        AstJoinOperator::Inner(JoinConstraint::On(where_clause)) => {
-            (JoinOperator::Inner, Some(where_clause))
-        }
-        AstJoinOperator::LeftOuter(JoinConstraint::None) => (JoinOperator::LeftOuter, None),
-        AstJoinOperator::LeftOuter(JoinConstraint::On(where_clause)) => {
-            (JoinOperator::LeftOuter, Some(where_clause))
-        }
+    let join_operator = match ast_join_operator {
+        AstJoinOperator::Inner(_) | AstJoinOperator::Cross => JoinOperator::Inner,
+        AstJoinOperator::LeftOuter(_) => JoinOperator::LeftOuter,
+        AstJoinOperator::RightOuter(_) => JoinOperator::RightOuter,
+        AstJoinOperator::FullOuter(_) => JoinOperator::FullOuter,
+        AstJoinOperator::Semi(_) => JoinOperator::Semi,
+        AstJoinOperator::Anti(_) => JoinOperator::Anti,
+    };
+    let where_clause = match get_constraint(ast_join_operator) {
+        Some(JoinConstraint::On(where_clause)) => Some(where_clause),
+        _ => None,
     };
+    let join_relation = Rc::new(JoinRelation {
+        relation,
+        columns,
+        merged_columns,
+        where_clause,
+    });
+
+    if matches!(
+        join_operator,
+        JoinOperator::RightOuter | JoinOperator::FullOuter
+    ) {
+        return join_right(
+            storage,
+            filter_context,
+            join_relation,
+            join_executor,
+            join_operator,
+            null_context,
+            left_rows,
+        )
+        .await;
+    }
 
     let rows = left_rows.and_then(move |blend_context| {
         let filter_context = filter_context.as_ref().map(Rc::clone);
-        let columns = Rc::clone(&columns);
-        let init_context = Rc::new(BlendContext::new(
-            table_alias,
-            Rc::clone(&columns),
-            Single(None),
-            Some(Rc::clone(&blend_context)),
-        ));
+        let join_relation = Rc::clone(&join_relation);
+        let init_context = join_relation.blend(None, Rc::clone(&blend_context));
         let join_executor = Rc::clone(&join_executor);
+        let left_context = Rc::clone(&blend_context);
 
         async move {
             let filter_context = Some(Rc::new(FilterContext::concat(
@@ -138,27 +298,28 @@ async fn join<'a>(
             }
             let rows = match join_executor.as_ref() {
                 JoinExecutor::NestedLoop => {
-                    let rows = fetch_relation_rows(storage, relation, &filter_context).await?;
+                    let rows =
+                        fetch_relation_rows(storage, join_relation.relation, &filter_context, None)
+                            .await?;
                     let rows = rows
                         .and_then(|row| future::ok(Cow::Owned(row)))
                         .try_filter_map(move |row| {
-                            check_where_clause(
+                            check_constraint(
                                 storage,
-                                table_alias,
-                                Rc::clone(&columns),
+                                Rc::clone(&join_relation),
                                 filter_context.as_ref().map(Rc::clone),
-                                Some(&blend_context).map(Rc::clone),
-                                where_clause,
+                                Rc::clone(&blend_context),
                                 row,
                             )
                         });
                     Rows::NestedLoop(rows)
                 }
                 JoinExecutor::Hash {
+                    rows,
                     rows_map,
                     value_expr,
                 } => {
-                    let rows = evaluate(
+                    let indexes = evaluate(
                         storage,
                         filter_context.as_ref().map(Rc::clone),
                         None,
@@ -168,36 +329,50 @@ async fn join<'a>(
                     .map(Key::try_from)?
                     .map(|hash_key| rows_map.get(&hash_key))?;
 
-                    match rows {
+                    match indexes {
                         None => Rows::Empty(empty()),
-                        Some(rows) => {
-                            let rows = stream::iter(rows.iter().map(Cow::Borrowed).map(Ok));
-                            let rows = rows.try_filter_map(move |row| {
-                                check_where_clause(
+                        Some(indexes) => {
+                            let matched = indexes
+                                .iter()
+                                .map(|index| Cow::Borrowed(&rows[*index]))
+                                .map(Ok);
+                            let matched = stream::iter(matched).try_filter_map(move |row| {
+                                check_constraint(
                                     storage,
-                                    table_alias,
-                                    Rc::clone(&columns),
+                                    Rc::clone(&join_relation),
                                     filter_context.as_ref().map(Rc::clone),
-                                    Some(&blend_context).map(Rc::clone),
-                                    where_clause,
+                                    Rc::clone(&blend_context),
                                     row,
                                 )
                             });
-                            let rows = stream::iter(rows.collect::<Vec<_>>().await);
+                            let matched = stream::iter(matched.collect::<Vec<_>>().await);
 
-                            Rows::Hash(rows)
+                            Rows::Hash(matched)
                         }
                     }
                 }
             };
 
             let rows: Joined = match join_operator {
-                JoinOperator::Inner => Box::pin(rows),
                 JoinOperator::LeftOuter => {
                     let init_rows = once(async { Ok(init_context) });
 
                     Box::pin(OrStream::new(rows, init_rows))
                 }
+                // semi and anti joins only ask whether a match exists, the left row is kept as
+                // it is without the columns of the right side
+                JoinOperator::Semi | JoinOperator::Anti => {
+                    let matched = Box::pin(rows).try_next().await?.is_some();
+
+                    if matched == matches!(join_operator, JoinOperator::Semi) {
+                        Box::pin(once(async { Ok(left_context) }))
+                    } else {
+                        Box::pin(empty())
+                    }
+                }
+                JoinOperator::Inner | JoinOperator::RightOuter | JoinOperator::FullOuter => {
+                    Box::pin(rows)
+                }
             };
 
             Ok(rows)
@@ -207,16 +382,111 @@ async fn join<'a>(
     Ok(Box::pin(rows.try_flatten()))
 }
 
+/// Right and full outer joins have to see every left row before they know which right rows
+/// are left unmatched, so both sides are materialized.
+async fn join_right<'a>(
+    storage: &'a dyn GStore,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+    join_relation: Rc<JoinRelation<'a>>,
+    join_executor: Rc<JoinExecutor<'a>>,
+    join_operator: JoinOperator,
+    null_context: Rc<BlendContext<'a>>,
+    left_rows: impl TryStream<Ok = JoinItem<'a>, Error = Error, Item = Result<JoinItem<'a>>> + 'a,
+) -> Result<Joined<'a>> {
+    let right_rows = match join_executor.as_ref() {
+        JoinExecutor::NestedLoop => {
+            let rows = fetch_relation_rows(storage, join_relation.relation, &filter_context, None)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            Cow::Owned(rows)
+        }
+        JoinExecutor::Hash { rows, .. } => Cow::Borrowed(rows.as_slice()),
+    };
+    let left_rows = left_rows.try_collect::<Vec<_>>().await?;
+
+    let mut matched = vec![false; right_rows.len()];
+    let mut joined = Vec::new();
+
+    for blend_context in left_rows {
+        let filter_context = Some(Rc::new(FilterContext::concat(
+            filter_context.as_ref().map(Rc::clone),
+            Some(Rc::clone(&blend_context)),
+        )));
+
+        let indexes = match join_executor.as_ref() {
+            JoinExecutor::NestedLoop => (0..right_rows.len()).collect(),
+            JoinExecutor::Hash {
+                rows_map,
+                value_expr,
+                ..
+            } => {
+                let hash_key: Key = evaluate(
+                    storage,
+                    filter_context.as_ref().map(Rc::clone),
+                    None,
+                    value_expr,
+                )
+                .await?
+                .try_into()?;
+
+                rows_map.get(&hash_key).cloned().unwrap_or_default()
+            }
+        };
+
+        let mut found = false;
+        for index in indexes {
+            let row = check_constraint(
+                storage,
+                Rc::clone(&join_relation),
+                filter_context.as_ref().map(Rc::clone),
+                Rc::clone(&blend_context),
+                Cow::Borrowed(&right_rows[index]),
+            )
+            .await?;
+
+            if let Some(row) = row {
+                matched[index] = true;
+                found = true;
+                joined.push(row);
+            }
+        }
+
+        if !found && matches!(join_operator, JoinOperator::FullOuter) {
+            joined.push(join_relation.blend(None, blend_context));
+        }
+    }
+
+    let unmatched = right_rows
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(row, _)| join_relation.blend(Some(row.clone()), Rc::clone(&null_context)));
+    let rows = joined
+        .into_iter()
+        .chain(unmatched)
+        .map(Ok::<_, Error>)
+        .collect::<Vec<_>>();
+
+    Ok(Box::pin(stream::iter(rows)))
+}
+
 #[derive(Copy, Clone)]
 enum JoinOperator {
     Inner,
     LeftOuter,
+    RightOuter,
+    FullOuter,
+    Semi,
+    Anti,
 }
 
 enum JoinExecutor<'a> {
     NestedLoop,
     Hash {
-        rows_map: HashMap<Key, Vec<Row>>,
+        rows: Vec<Row>,
+        rows_map: HashMap<Key, Vec<usize>>,
         value_expr: &'a Expr,
     },
 }
@@ -238,9 +508,16 @@ impl<'a> JoinExecutor<'a> {
             } => (key_expr, value_expr, where_clause),
         };
 
-        let rows_map = fetch_relation_rows(storage, relation, &filter_context).await?;
-        let rows_map = rows_map
-            .try_filter_map(|row| {
+        let rows = fetch_relation_rows(storage, relation, &filter_context, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        // rows with a null key or failing the where clause never match, but are kept in
+        // `rows` so right and full outer joins can still emit them
+        let rows_map = stream::iter(rows.iter().enumerate())
+            .map(Ok::<_, Error>)
+            .try_filter_map(|(index, row)| {
                 let columns = Rc::clone(&columns);
                 let filter_context = filter_context.as_ref().map(Rc::clone);
 
@@ -248,7 +525,7 @@ impl<'a> JoinExecutor<'a> {
                     let filter_context = Rc::new(FilterContext::new(
                         get_alias(relation),
                         columns,
-                        &row,
+                        row,
                         filter_context,
                     ));
 
@@ -268,8 +545,8 @@ impl<'a> JoinExecutor<'a> {
                     match where_clause {
                         Some(expr) => check_expr(storage, Some(filter_context), None, expr)
                             .await
-                            .map(|pass| pass.then_some((hash_key, row))),
-                        None => Ok(Some((hash_key, row))),
+                            .map(|pass| pass.then_some((hash_key, index))),
+                        None => Ok(Some((hash_key, index))),
                     }
                 }
             })
@@ -277,38 +554,54 @@ impl<'a> JoinExecutor<'a> {
             .await?
             .into_iter()
             .into_group_map();
+
         Ok(Self::Hash {
+            rows,
             rows_map,
             value_expr,
         })
     }
 }
 
-async fn check_where_clause<'a, 'b>(
+async fn check_constraint<'a, 'b>(
     storage: &'a dyn GStore,
-    table_alias: &'a str,
-    columns: Rc<[String]>,
+    join_relation: Rc<JoinRelation<'a>>,
     filter_context: Option<Rc<FilterContext<'a>>>,
-    blend_context: Option<Rc<BlendContext<'a>>>,
-    where_clause: Option<&'a Expr>,
+    blend_context: Rc<BlendContext<'a>>,
     row: Cow<'b, Row>,
 ) -> Result<Option<Rc<BlendContext<'a>>>> {
-    let filter_context = FilterContext::new(table_alias, Rc::clone(&columns), &row, filter_context);
+    let JoinRelation {
+        columns,
+        merged_columns,
+        where_clause,
+        ..
+    } = join_relation.as_ref();
+
+    let using_matched = merged_columns.iter().all(|column| {
+        let left = blend_context.get_value(column);
+        let right = row.get_value(columns, column);
+
+        match (left, right) {
+            (Some(left), Some(right)) => !left.is_null() && !right.is_null() && left == right,
+            _ => false,
+        }
+    });
+    if !using_matched {
+        return Ok(None);
+    }
+
+    let filter_context = FilterContext::new(
+        join_relation.table_alias(),
+        Rc::clone(columns),
+        &row,
+        filter_context,
+    );
     let filter_context = Some(Rc::new(filter_context));
 
-    match where_clause {
+    let pass = match *where_clause {
         Some(expr) => check_expr(storage, filter_context, None, expr).await?,
         None => true,
-    }
-    .then(|| {
-        BlendContext::new(
-            table_alias,
-            columns,
-            Single(Some(row.into_owned())),
-            blend_context,
-        )
-    })
-    .map(Rc::new)
-    .map(Ok)
-    .transpose()
+    };
+
+    Ok(pass.then(|| join_relation.blend(Some(row.into_owned()), blend_context)))
 }
@@ -3,24 +3,38 @@ mod alter;
 mod context;
 mod evaluate;
 mod execute;
+mod explain;
 mod fetch;
 mod filter;
+mod foreign_key;
+mod generated;
+mod identity;
 mod join;
 mod limit;
+mod pushdown;
 mod select;
 mod sort;
 mod update;
+mod upsert;
 mod validate;
+mod window;
 
 pub use aggregate::AggregateError;
 pub use alter::AlterError;
 pub use evaluate::{evaluate_stateless, ChronoFormatError, EvaluateError};
-pub use execute::{ExecuteError, Payload, PayloadVariable};
+pub use execute::{
+    stream_query, ExecuteError, ExecuteOptions, Payload, PayloadStream, PayloadVariable, RowStream,
+};
+pub use explain::{Analyzed, ExplainError, ExplainNode};
 pub use fetch::FetchError;
-pub use select::SelectError;
+pub use foreign_key::ForeignKeyError;
+pub use join::JoinError;
+pub use select::{SelectError, DEFAULT_MAX_RECURSION_DEPTH};
 pub use sort::SortError;
 pub use update::UpdateError;
+pub use upsert::UpsertError;
 pub use validate::ValidateError;
+pub use window::WindowError;
 
 #[cfg(not(feature = "transaction"))]
 pub use execute::execute;
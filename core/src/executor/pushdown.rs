@@ -0,0 +1,578 @@
+use {
+    crate::{
+        ast::{
+            BinaryOperator, Cte, Expr, Function, Join, JoinConstraint, JoinExecutor, JoinOperator,
+            OrderByExpr, Query, Select, SelectItem, SetExpr, TableFactor, TableWithJoins, Values,
+            With,
+        },
+        data::get_alias,
+        plan::PlanExpr,
+        store::ScanFilter,
+    },
+    std::collections::HashSet,
+};
+
+/// Columns and filter of a full table scan, pushed down to `Store::scan_filtered_data`.
+#[derive(Debug, Default)]
+pub struct Pushdown {
+    pub columns: Option<Vec<usize>>,
+    pub filter: Option<ScanFilter>,
+}
+
+impl Pushdown {
+    /// Filter of the target rows of `UPDATE` and `DELETE`, the whole rows are fetched as they
+    /// are written back.
+    pub fn from_where(table_alias: &str, columns: &[String], where_clause: Option<&Expr>) -> Self {
+        let filter = where_clause.and_then(|expr| {
+            let target = Target {
+                table_alias,
+                columns,
+                qualified_only: false,
+            };
+
+            target.extract_filter(expr)
+        });
+        let filter = filter.map(|expr| ScanFilter {
+            column_names: columns.to_vec(),
+            expr,
+        });
+
+        Self {
+            columns: None,
+            filter,
+        }
+    }
+
+    /// Columns of the leftmost table referred to by the `SELECT` and the filter of its rows
+    /// taken from the `WHERE` clause.
+    pub fn from_select(select: &Select, order_by: &[OrderByExpr], columns: &[String]) -> Self {
+        let Select {
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+            ..
+        } = select;
+        let table_alias = get_alias(relation);
+
+        let wildcard = projection.iter().any(|item| match item {
+            SelectItem::Wildcard => true,
+            SelectItem::QualifiedWildcard(alias) => alias == table_alias,
+            SelectItem::Expr { .. } => false,
+        });
+        let natural = joins.iter().any(|Join { join_operator, .. }| {
+            matches!(
+                join_operator,
+                JoinOperator::Inner(JoinConstraint::Natural)
+                    | JoinOperator::LeftOuter(JoinConstraint::Natural)
+                    | JoinOperator::RightOuter(JoinConstraint::Natural)
+                    | JoinOperator::FullOuter(JoinConstraint::Natural)
+            )
+        });
+
+        let needed = match wildcard || natural {
+            true => None,
+            false => {
+                let mut names = HashSet::new();
+
+                for item in projection {
+                    if let SelectItem::Expr { expr, .. } = item {
+                        collect_expr(&mut names, expr);
+                    }
+                }
+
+                for join in joins {
+                    collect_join(&mut names, join);
+                }
+
+                selection
+                    .iter()
+                    .chain(group_by)
+                    .chain(having)
+                    .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+                    .for_each(|expr| collect_expr(&mut names, expr));
+
+                let needed = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| names.contains(column.as_str()))
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+
+                (needed.len() < columns.len()).then_some(needed)
+            }
+        };
+
+        // rows of the leftmost table may not be left out before the right or full outer joins
+        // take the unmatched rows, and a self join without an alias hides which table the
+        // qualified identifiers refer to
+        let outer = joins.iter().any(|Join { join_operator, .. }| {
+            matches!(
+                join_operator,
+                JoinOperator::RightOuter(_) | JoinOperator::FullOuter(_)
+            )
+        });
+        let shadowed = joins
+            .iter()
+            .any(|Join { relation, .. }| get_alias(relation) == table_alias);
+
+        // columns of the semi and anti joins are never seen by `WHERE`
+        let joined = joins.iter().any(|Join { join_operator, .. }| {
+            !matches!(join_operator, JoinOperator::Semi(_) | JoinOperator::Anti(_))
+        });
+
+        let filter = match (selection, outer || shadowed) {
+            (Some(expr), false) => {
+                let target = Target {
+                    table_alias,
+                    columns,
+                    qualified_only: joined,
+                };
+
+                target.extract_filter(expr)
+            }
+            _ => None,
+        };
+        let filter = filter.map(|expr| ScanFilter {
+            column_names: columns.to_vec(),
+            expr,
+        });
+
+        Self {
+            columns: needed,
+            filter,
+        }
+    }
+}
+
+/// Table whose rows are filtered by the pushed down conjuncts of the `WHERE` clause.
+struct Target<'a> {
+    table_alias: &'a str,
+    columns: &'a [String],
+    /// With joins, an unqualified identifier may refer to the columns of the joined tables
+    qualified_only: bool,
+}
+
+impl Target<'_> {
+    /// Conjuncts which the storage can evaluate against the rows of the table alone.
+    fn extract_filter(&self, expr: &Expr) -> Option<Expr> {
+        let mut conjuncts = Vec::new();
+        split_conjuncts(expr, &mut conjuncts);
+
+        conjuncts
+            .into_iter()
+            .filter(|expr| self.is_pushable(expr))
+            .map(|expr| {
+                let mut expr = expr.clone();
+                unqualify(&mut expr);
+
+                expr
+            })
+            .reduce(|left, right| Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            })
+    }
+
+    fn is_pushable(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Identifier(ident) => !self.qualified_only && self.columns.contains(ident),
+            Expr::CompoundIdentifier { alias, ident } => {
+                alias == self.table_alias && self.columns.contains(ident)
+            }
+            Expr::Placeholder(_) | Expr::Aggregate(_) | Expr::Window(_) => false,
+            Expr::Function(function)
+                if matches!(
+                    function.as_ref(),
+                    Function::Now()
+                        | Function::GenerateUuid()
+                        | Function::Nextval(_)
+                        | Function::Currval(_)
+                ) =>
+            {
+                false
+            }
+            _ => match PlanExpr::from(expr) {
+                PlanExpr::None => true,
+                PlanExpr::Identifier(_) | PlanExpr::CompoundIdentifier { .. } => false,
+                PlanExpr::Expr(expr) => self.is_pushable(expr),
+                PlanExpr::TwoExprs(expr, expr2) => {
+                    self.is_pushable(expr) && self.is_pushable(expr2)
+                }
+                PlanExpr::ThreeExprs(expr, expr2, expr3) => {
+                    self.is_pushable(expr) && self.is_pushable(expr2) && self.is_pushable(expr3)
+                }
+                PlanExpr::MultiExprs(exprs) => exprs.into_iter().all(|expr| self.is_pushable(expr)),
+                PlanExpr::Query(_) | PlanExpr::QueryAndExpr { .. } => false,
+            },
+        }
+    }
+}
+
+fn split_conjuncts<'a>(expr: &'a Expr, conjuncts: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjuncts(left, conjuncts);
+            split_conjuncts(right, conjuncts);
+        }
+        Expr::Nested(expr) => split_conjuncts(expr, conjuncts),
+        _ => conjuncts.push(expr),
+    }
+}
+
+/// Pushed down filters refer to the columns by their names only.
+fn unqualify(expr: &mut Expr) {
+    match expr {
+        Expr::CompoundIdentifier { ident, .. } => {
+            *expr = Expr::Identifier(ident.to_owned());
+        }
+        Expr::Identifier(_)
+        | Expr::Literal(_)
+        | Expr::Placeholder(_)
+        | Expr::TypedString { .. }
+        | Expr::Aggregate(_)
+        | Expr::Window(_)
+        | Expr::InSubquery { .. }
+        | Expr::Exists { .. }
+        | Expr::Subquery { .. } => {}
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Interval { expr, .. } => unqualify(expr),
+        Expr::InList { expr, list, .. } => {
+            unqualify(expr);
+            list.iter_mut().for_each(unqualify);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            unqualify(expr);
+            unqualify(low);
+            unqualify(high);
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            unqualify(expr);
+            unqualify(pattern);
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            unqualify(left);
+            unqualify(right);
+        }
+        Expr::Function(function) => function.as_exprs_mut().for_each(unqualify),
+        Expr::Case {
+            operand,
+            when_then,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                unqualify(operand);
+            }
+
+            for (when, then) in when_then.iter_mut() {
+                unqualify(when);
+                unqualify(then);
+            }
+
+            if let Some(else_result) = else_result {
+                unqualify(else_result);
+            }
+        }
+        Expr::ArrayIndex { obj, indexes } => {
+            unqualify(obj);
+            indexes.iter_mut().for_each(unqualify);
+        }
+    }
+}
+
+/// Column names referred to anywhere in the expression, including its subqueries which may be
+/// correlated with the table.
+fn collect_expr<'a>(names: &mut HashSet<&'a str>, expr: &'a Expr) {
+    match PlanExpr::from(expr) {
+        PlanExpr::None => {}
+        PlanExpr::Identifier(ident) | PlanExpr::CompoundIdentifier { ident, .. } => {
+            names.insert(ident);
+        }
+        PlanExpr::Expr(expr) => collect_expr(names, expr),
+        PlanExpr::TwoExprs(expr, expr2) => {
+            collect_expr(names, expr);
+            collect_expr(names, expr2);
+        }
+        PlanExpr::ThreeExprs(expr, expr2, expr3) => {
+            collect_expr(names, expr);
+            collect_expr(names, expr2);
+            collect_expr(names, expr3);
+        }
+        PlanExpr::MultiExprs(exprs) => exprs.into_iter().for_each(|expr| collect_expr(names, expr)),
+        PlanExpr::Query(query) => collect_query(names, query),
+        PlanExpr::QueryAndExpr { query, expr } => {
+            collect_query(names, query);
+            collect_expr(names, expr);
+        }
+    }
+}
+
+fn collect_query<'a>(names: &mut HashSet<&'a str>, query: &'a Query) {
+    let Query {
+        with,
+        body,
+        order_by,
+        limit,
+        offset,
+    } = query;
+
+    with.iter()
+        .flat_map(|With { cte_tables, .. }| cte_tables)
+        .for_each(|Cte { query, .. }| collect_query(names, query));
+
+    collect_set_expr(names, body);
+
+    order_by
+        .iter()
+        .map(|OrderByExpr { expr, .. }| expr)
+        .chain(limit)
+        .chain(offset)
+        .for_each(|expr| collect_expr(names, expr));
+}
+
+fn collect_set_expr<'a>(names: &mut HashSet<&'a str>, set_expr: &'a SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => {
+            let Select {
+                projection,
+                from: TableWithJoins { relation, joins },
+                selection,
+                group_by,
+                having,
+                ..
+            } = select.as_ref();
+
+            for item in projection {
+                if let SelectItem::Expr { expr, .. } = item {
+                    collect_expr(names, expr);
+                }
+            }
+
+            collect_table_factor(names, relation);
+
+            for join in joins {
+                collect_join(names, join);
+            }
+
+            selection
+                .iter()
+                .chain(group_by)
+                .chain(having)
+                .for_each(|expr| collect_expr(names, expr));
+        }
+        SetExpr::Values(Values(rows)) => rows
+            .iter()
+            .flatten()
+            .for_each(|expr| collect_expr(names, expr)),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr(names, left);
+            collect_set_expr(names, right);
+        }
+    }
+}
+
+fn collect_join<'a>(names: &mut HashSet<&'a str>, join: &'a Join) {
+    let Join {
+        relation,
+        join_operator,
+        join_executor,
+    } = join;
+
+    collect_table_factor(names, relation);
+
+    match join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint)
+        | JoinOperator::Semi(constraint)
+        | JoinOperator::Anti(constraint) => match constraint {
+            JoinConstraint::On(expr) => collect_expr(names, expr),
+            JoinConstraint::Using(columns) => {
+                names.extend(columns.iter().map(String::as_str));
+            }
+            JoinConstraint::Natural | JoinConstraint::None => {}
+        },
+        JoinOperator::Cross => {}
+    }
+
+    if let JoinExecutor::Hash {
+        key_expr,
+        value_expr,
+        where_clause,
+    } = join_executor
+    {
+        [key_expr, value_expr]
+            .into_iter()
+            .chain(where_clause)
+            .for_each(|expr| collect_expr(names, expr));
+    }
+}
+
+fn collect_table_factor<'a>(names: &mut HashSet<&'a str>, table_factor: &'a TableFactor) {
+    match table_factor {
+        TableFactor::Derived { subquery, .. } => collect_query(names, subquery),
+        TableFactor::Series { size, .. } => collect_expr(names, size),
+        TableFactor::Table { .. } | TableFactor::Dictionary { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "index")]
+    use crate::store::Index;
+    use {
+        crate::{
+            ast::{ColumnDef, DataType},
+            data::{Key, Row, Schema, Value},
+            executor::select::select,
+            parse_sql::{parse_expr, parse_query},
+            result::{Error, Result},
+            store::{project_scan_row, RowIter, ScanFilter, Store},
+            translate::{translate_expr, translate_query},
+        },
+        async_trait::async_trait,
+        chrono::Utc,
+        futures::{executor::block_on, TryStreamExt},
+        std::cell::RefCell,
+    };
+
+    /// Storage which records the columns and the filter pushed down to its scans
+    struct ScanStorage {
+        schema: Schema,
+        rows: Vec<Row>,
+        scans: RefCell<Vec<(Option<Vec<usize>>, Option<ScanFilter>)>>,
+    }
+
+    #[async_trait(?Send)]
+    impl Store for ScanStorage {
+        async fn fetch_all_schemas(&self) -> Result<Vec<Schema>> {
+            Ok(vec![self.schema.clone()])
+        }
+
+        async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
+            Ok((table_name == self.schema.table_name).then(|| self.schema.clone()))
+        }
+
+        async fn fetch_data(&self, _table_name: &str, _key: &Key) -> Result<Option<Row>> {
+            Err(Error::StorageMsg(
+                "[ScanStorage] fetch_data not supported".to_owned(),
+            ))
+        }
+
+        async fn scan_data(&self, table_name: &str) -> Result<RowIter> {
+            self.scan_filtered_data(table_name, None, None).await
+        }
+
+        async fn scan_filtered_data(
+            &self,
+            _table_name: &str,
+            columns: Option<&[usize]>,
+            filter: Option<&ScanFilter>,
+        ) -> Result<RowIter> {
+            self.scans
+                .borrow_mut()
+                .push((columns.map(<[usize]>::to_vec), filter.cloned()));
+
+            let rows = self
+                .rows
+                .iter()
+                .filter(|row| filter.map_or(true, |filter| filter.check(row)))
+                .enumerate()
+                .map(|(i, row)| {
+                    let row = match columns {
+                        Some(columns) => project_scan_row(columns, row),
+                        None => row.clone(),
+                    };
+
+                    Ok((Key::I64(i as i64), row))
+                })
+                .collect::<Vec<_>>();
+
+            Ok(Box::new(rows.into_iter()))
+        }
+    }
+
+    #[cfg(feature = "index")]
+    impl Index for ScanStorage {}
+
+    #[test]
+    fn scan_filtered_data() {
+        let column_names = ["id", "name", "price", "category"]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        let column_defs = column_names
+            .iter()
+            .map(|name| ColumnDef {
+                name: name.to_owned(),
+                data_type: DataType::Text,
+                nullable: true,
+                options: Vec::new(),
+            })
+            .collect();
+        let row = |id: i64, name: &str, price: i64, category: &str| {
+            Row(vec![
+                Value::I64(id),
+                Value::Str(name.to_owned()),
+                Value::I64(price),
+                Value::Str(category.to_owned()),
+            ])
+        };
+        let storage = ScanStorage {
+            schema: Schema {
+                table_name: "Item".to_owned(),
+                column_defs,
+                primary_key: None,
+                foreign_keys: Vec::new(),
+                checks: Vec::new(),
+                indexes: Vec::new(),
+                statistics: None,
+                created: Utc::now().naive_utc(),
+            },
+            rows: vec![
+                row(1, "apple", 50, "fruit"),
+                row(2, "carrot", 30, "vegetable"),
+                row(3, "banana", 150, "fruit"),
+            ],
+            scans: RefCell::new(Vec::new()),
+        };
+
+        let sql = "SELECT name FROM Item WHERE price > 40 ORDER BY id";
+        let query = translate_query(&parse_query(sql).unwrap()).unwrap();
+        let rows = block_on(async {
+            select(&storage, &query, None)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await
+        })
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Row(vec![Value::Str("apple".to_owned())]),
+                Row(vec![Value::Str("banana".to_owned())]),
+            ]
+        );
+
+        let filter = ScanFilter {
+            column_names,
+            expr: translate_expr(&parse_expr("price > 40").unwrap()).unwrap(),
+        };
+        assert_eq!(
+            storage.scans.into_inner(),
+            vec![(Some(vec![0, 1, 2]), Some(filter))]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use {
     super::SelectError,
     crate::{
-        ast::{Aggregate, SelectItem},
+        ast::{Expr, SelectItem},
         data::{Row, Value},
         executor::{
             context::{BlendContext, FilterContext},
@@ -36,7 +36,7 @@ impl<'a> Blend<'a> {
 
     pub async fn apply(
         &self,
-        aggregated: Option<Rc<HashMap<&'a Aggregate, Value>>>,
+        aggregated: Option<Rc<HashMap<&'a Expr, Value>>>,
         context: Rc<BlendContext<'a>>,
     ) -> Result<Row> {
         let filter_context = FilterContext::concat(
@@ -0,0 +1,344 @@
+use {
+    super::{distinct::DistinctRow, select_set_operand, select_with_labels, SelectError},
+    crate::{
+        ast::{
+            ColumnDef, Cte, DataType, Join, Query, SetExpr, SetOperator, TableAlias, TableFactor,
+            TableWithJoins, With,
+        },
+        data::{Row, Schema, Sequence, Value, View},
+        executor::{context::FilterContext, fetch::FetchError},
+        result::Result,
+        store::{GStore, RowIter, ScanFilter, Store},
+    },
+    async_trait::async_trait,
+    chrono::Utc,
+    futures::stream::TryStreamExt,
+    std::{
+        collections::{HashMap, HashSet},
+        rc::Rc,
+    },
+};
+
+#[cfg(feature = "index")]
+use crate::{ast::IndexOperator, store::Index};
+
+/// Default of how many times the recursive term of a `WITH RECURSIVE` query is evaluated
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 1000;
+
+struct CteTable {
+    columns: Vec<String>,
+    rows: Vec<Row>,
+}
+
+impl CteTable {
+    fn to_schema(&self, table_name: &str) -> Schema {
+        let column_defs = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let data_type = self
+                    .rows
+                    .iter()
+                    .find_map(|row| row.get_value_by_index(i).and_then(Value::get_type))
+                    .unwrap_or(DataType::Text);
+
+                ColumnDef {
+                    name: name.to_owned(),
+                    data_type,
+                    nullable: true,
+                    options: Vec::new(),
+                }
+            })
+            .collect();
+
+        Schema {
+            table_name: table_name.to_owned(),
+            column_defs,
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
+            indexes: Vec::new(),
+            statistics: None,
+            created: Utc::now().naive_utc(),
+        }
+    }
+}
+
+/// Materializes the common table expressions of a `WITH` clause and exposes them as tables
+/// on top of the wrapped storage.
+pub struct CteStorage<'a> {
+    storage: &'a dyn GStore,
+    tables: HashMap<String, Rc<CteTable>>,
+}
+
+impl<'a> CteStorage<'a> {
+    pub async fn new(
+        storage: &'a dyn GStore,
+        with: &'a With,
+        filter_context: Option<Rc<FilterContext<'a>>>,
+    ) -> Result<CteStorage<'a>> {
+        let With {
+            recursive,
+            cte_tables,
+        } = with;
+
+        let mut cte_storage = Self {
+            storage,
+            tables: HashMap::new(),
+        };
+
+        for Cte { alias, query } in cte_tables {
+            let filter_context = filter_context.as_ref().map(Rc::clone);
+            let table = match &query.body {
+                SetExpr::SetOperation {
+                    op: SetOperator::Union,
+                    all,
+                    left,
+                    right,
+                } if *recursive && references(right, &alias.name) => {
+                    cte_storage
+                        .iterate(alias, *all, left, right, filter_context)
+                        .await?
+                }
+                _ => {
+                    cte_storage
+                        .materialize(alias, query, filter_context)
+                        .await?
+                }
+            };
+
+            cte_storage
+                .tables
+                .insert(alias.name.to_owned(), Rc::new(table));
+        }
+
+        Ok(cte_storage)
+    }
+
+    async fn materialize(
+        &self,
+        alias: &TableAlias,
+        query: &Query,
+        filter_context: Option<Rc<FilterContext<'_>>>,
+    ) -> Result<CteTable> {
+        let (labels, rows) = select_with_labels(self, query, filter_context, true).await?;
+        let rows = rows.try_collect().await?;
+        let columns = get_columns(alias, labels)?;
+
+        Ok(CteTable { columns, rows })
+    }
+
+    /// Evaluates `anchor UNION [ALL] recursive_term` until the recursive term stops
+    /// producing new rows. Each iteration sees only the rows produced by the previous one.
+    /// A recursive term which keeps producing rows fails once the maximum depth set on the
+    /// execution options is reached.
+    async fn iterate(
+        &self,
+        alias: &TableAlias,
+        all: bool,
+        anchor: &SetExpr,
+        recursive_term: &SetExpr,
+        filter_context: Option<Rc<FilterContext<'_>>>,
+    ) -> Result<CteTable> {
+        let (labels, rows) =
+            select_set_operand(self, anchor, filter_context.as_ref().map(Rc::clone)).await?;
+        let columns = get_columns(alias, labels)?;
+
+        let mut keys = HashSet::new();
+        let mut rows = filter_new_rows(rows, all, &mut keys);
+        let mut working_rows = rows.clone();
+        let max_depth = filter_context
+            .as_deref()
+            .map(FilterContext::options)
+            .unwrap_or_default()
+            .max_recursion_depth;
+        let mut depth = 0;
+
+        while !working_rows.is_empty() {
+            if depth == max_depth {
+                return Err(SelectError::RecursionDepthExceeded {
+                    table_name: alias.name.to_owned(),
+                    depth,
+                }
+                .into());
+            }
+            depth += 1;
+
+            let working_table = CteTable {
+                columns: columns.clone(),
+                rows: working_rows,
+            };
+
+            let mut tables = self.tables.clone();
+            tables.insert(alias.name.to_owned(), Rc::new(working_table));
+
+            let cte_storage = CteStorage {
+                storage: self.storage,
+                tables,
+            };
+
+            let filter_context =
+                FilterContext::with_subquery_cache(filter_context.as_ref().map(Rc::clone));
+            let (labels, new_rows) =
+                select_set_operand(&cte_storage, recursive_term, Some(Rc::new(filter_context)))
+                    .await?;
+
+            if labels.len() != columns.len() {
+                return Err(SelectError::SetOperationColumnCountMismatch {
+                    operator: SetOperator::Union,
+                    left: columns.len(),
+                    right: labels.len(),
+                }
+                .into());
+            }
+
+            working_rows = filter_new_rows(new_rows, all, &mut keys);
+            rows.extend(working_rows.iter().cloned());
+        }
+
+        Ok(CteTable { columns, rows })
+    }
+}
+
+fn get_columns(alias: &TableAlias, labels: Vec<String>) -> Result<Vec<String>> {
+    let TableAlias { name, columns } = alias;
+
+    if columns.len() > labels.len() {
+        return Err(
+            FetchError::TooManyColumnAliases(name.to_owned(), labels.len(), columns.len()).into(),
+        );
+    }
+
+    let labels = labels.into_iter().skip(columns.len());
+
+    Ok(columns.iter().cloned().chain(labels).collect())
+}
+
+/// Without `ALL`, rows which were already produced by earlier iterations are discarded.
+fn filter_new_rows(rows: Vec<Row>, all: bool, keys: &mut HashSet<DistinctRow>) -> Vec<Row> {
+    if all {
+        return rows;
+    }
+
+    rows.into_iter()
+        .filter(|row| keys.insert(DistinctRow::from(row)))
+        .collect()
+}
+
+fn references(set_expr: &SetExpr, table_name: &str) -> bool {
+    let table_factor_references = |table_factor: &TableFactor| match table_factor {
+        TableFactor::Table { name, .. } => name == table_name,
+        TableFactor::Derived { subquery, .. } => references(&subquery.body, table_name),
+        TableFactor::Series { .. } | TableFactor::Dictionary { .. } => false,
+    };
+
+    match set_expr {
+        SetExpr::Select(select) => {
+            let TableWithJoins { relation, joins } = &select.from;
+
+            table_factor_references(relation)
+                || joins
+                    .iter()
+                    .any(|Join { relation, .. }| table_factor_references(relation))
+        }
+        SetExpr::Values(_) => false,
+        SetExpr::SetOperation { left, right, .. } => {
+            references(left, table_name) || references(right, table_name)
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Store for CteStorage<'_> {
+    async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
+        match self.tables.get(table_name) {
+            Some(table) => Ok(Some(table.to_schema(table_name))),
+            None => self.storage.fetch_schema(table_name).await,
+        }
+    }
+
+    async fn fetch_all_schemas(&self) -> Result<Vec<Schema>> {
+        self.storage.fetch_all_schemas().await
+    }
+
+    async fn fetch_data(&self, table_name: &str, key: &Key) -> Result<Option<Row>> {
+        match (self.tables.get(table_name), key) {
+            (Some(table), Key::I64(i)) => Ok(usize::try_from(*i)
+                .ok()
+                .and_then(|i| table.rows.get(i))
+                .cloned()),
+            (Some(_), _) => Ok(None),
+            (None, _) => self.storage.fetch_data(table_name, key).await,
+        }
+    }
+
+    async fn scan_data(&self, table_name: &str) -> Result<RowIter> {
+        match self.tables.get(table_name) {
+            Some(table) => {
+                let rows = table
+                    .rows
+                    .clone()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, row)| Ok((Key::I64(i as i64), row)));
+
+                Ok(Box::new(rows))
+            }
+            None => self.storage.scan_data(table_name).await,
+        }
+    }
+
+    async fn scan_filtered_data(
+        &self,
+        table_name: &str,
+        columns: Option<&[usize]>,
+        filter: Option<&ScanFilter>,
+    ) -> Result<RowIter> {
+        match self.tables.contains_key(table_name) {
+            true => self.scan_data(table_name).await,
+            false => {
+                self.storage
+                    .scan_filtered_data(table_name, columns, filter)
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_sequence(&self, name: &str) -> Result<Option<Sequence>> {
+        self.storage.fetch_sequence(name).await
+    }
+
+    async fn next_sequence_value(&self, sequence: &Sequence) -> Result<i64> {
+        self.storage.next_sequence_value(sequence).await
+    }
+
+    async fn current_sequence_value(&self, name: &str) -> Result<Option<i64>> {
+        self.storage.current_sequence_value(name).await
+    }
+
+    async fn fetch_view(&self, name: &str) -> Result<Option<View>> {
+        self.storage.fetch_view(name).await
+    }
+
+    async fn fetch_all_views(&self) -> Result<Vec<View>> {
+        self.storage.fetch_all_views().await
+    }
+}
+
+#[cfg(feature = "index")]
+#[async_trait(?Send)]
+impl Index for CteStorage<'_> {
+    async fn scan_indexed_data(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        asc: Option<bool>,
+        prefix_values: Vec<Value>,
+        cmp_value: Option<(&IndexOperator, Value)>,
+    ) -> Result<RowIter> {
+        self.storage
+            .scan_indexed_data(table_name, index_name, asc, prefix_values, cmp_value)
+            .await
+    }
+}
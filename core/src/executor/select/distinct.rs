@@ -0,0 +1,101 @@
+use {
+    crate::{data::Row, prelude::Value},
+    std::{
+        hash::{Hash, Hasher},
+        mem::discriminant,
+    },
+};
+
+/// Values of a row compared the way `DISTINCT` compares them, values of the same type are
+/// equal and `NULL`s or `NaN`s are not distinct from each other. Unlike `Key`, every type of
+/// value can be compared, `FLOAT`, `MAP` and `LIST` ones too.
+#[derive(Debug)]
+pub struct DistinctRow(Vec<Value>);
+
+impl From<&Row> for DistinctRow {
+    fn from(row: &Row) -> Self {
+        Self(row.0.clone())
+    }
+}
+
+impl PartialEq for DistinctRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(other.0.iter())
+                .all(|(l, r)| not_distinct(l, r))
+    }
+}
+
+impl Eq for DistinctRow {}
+
+impl Hash for DistinctRow {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for value in self.0.iter() {
+            hash_value(value, state);
+        }
+    }
+}
+
+fn not_distinct(l: &Value, r: &Value) -> bool {
+    match (l, r) {
+        (Value::F64(l), Value::F64(r)) => l == r || (l.is_nan() && r.is_nan()),
+        (Value::Map(l), Value::Map(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .all(|(key, l)| r.get(key).map_or(false, |r| not_distinct(l, r)))
+        }
+        (Value::List(l), Value::List(r)) => {
+            l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| not_distinct(l, r))
+        }
+        (Value::Null, Value::Null) => true,
+        _ => discriminant(l) == discriminant(r) && l == r,
+    }
+}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    discriminant(value).hash(state);
+
+    match value {
+        Value::Bool(v) => v.hash(state),
+        Value::I8(v) => v.hash(state),
+        Value::I16(v) => v.hash(state),
+        Value::I32(v) => v.hash(state),
+        Value::I64(v) => v.hash(state),
+        Value::I128(v) => v.hash(state),
+        Value::U8(v) => v.hash(state),
+        Value::U16(v) => v.hash(state),
+        Value::F64(v) => {
+            // every NaN is hashed alike, so is -0.0 which equals to 0.0
+            let v = if v.is_nan() {
+                f64::NAN
+            } else if *v == 0.0 {
+                0.0
+            } else {
+                *v
+            };
+
+            v.to_bits().hash(state);
+        }
+        Value::Decimal(v) => v.normalize().hash(state),
+        Value::Str(v) => v.hash(state),
+        Value::Bytea(v) => v.hash(state),
+        Value::Date(v) => v.hash(state),
+        Value::Timestamp(v) => v.hash(state),
+        Value::Time(v) => v.hash(state),
+        Value::Interval(v) => v.hash(state),
+        Value::Uuid(v) => v.hash(state),
+        // entries of a map are in no order, maps of the same size are told apart by `eq`
+        Value::Map(v) => v.len().hash(state),
+        Value::List(v) => {
+            v.len().hash(state);
+
+            for value in v.iter() {
+                hash_value(value, state);
+            }
+        }
+        Value::Null => {}
+    }
+}
@@ -1,4 +1,9 @@
-use {serde::Serialize, std::fmt::Debug, thiserror::Error as ThisError};
+use {
+    crate::ast::{DataType, SetOperator},
+    serde::Serialize,
+    std::fmt::Debug,
+    thiserror::Error as ThisError,
+};
 
 #[derive(ThisError, Serialize, Debug, PartialEq)]
 pub enum SelectError {
@@ -8,6 +13,26 @@ pub enum SelectError {
     #[error("table alias for blend not found: {0}")]
     BlendTableAliasNotFound(String),
 
+    #[error(
+        "each {operator} query must have the same number of columns, left: {left}, right: {right}"
+    )]
+    SetOperationColumnCountMismatch {
+        operator: SetOperator,
+        left: usize,
+        right: usize,
+    },
+
+    #[error("{operator} types do not match in column {column}, left: {left}, right: {right}")]
+    SetOperationTypeMismatch {
+        operator: SetOperator,
+        column: String,
+        left: DataType,
+        right: DataType,
+    },
+
+    #[error("recursion of {table_name} exceeded the maximum depth of {depth}")]
+    RecursionDepthExceeded { table_name: String, depth: usize },
+
     #[error("unreachable!")]
     Unreachable,
 }
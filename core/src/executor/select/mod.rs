@@ -1,32 +1,45 @@
 mod blend;
+mod cte;
+mod distinct;
 mod error;
 
-pub use error::SelectError;
+pub use {cte::DEFAULT_MAX_RECURSION_DEPTH, error::SelectError};
 
 use {
-    self::blend::Blend,
+    self::{blend::Blend, cte::CteStorage, distinct::DistinctRow},
     super::{
         aggregate::Aggregator,
         context::{AggregateContext, BlendContext, BlendContextRow::Single, FilterContext},
         evaluate_stateless,
+        explain::{measure, measure_future, Profile, Stage},
         fetch::{fetch_join_columns, fetch_relation_columns, fetch_relation_rows},
         filter::Filter,
-        join::Join,
+        join::{get_merged_columns, Join},
         limit::Limit,
+        pushdown::Pushdown,
         sort::Sort,
+        window::Window,
     },
     crate::{
-        ast::{Expr, OrderByExpr, Query, Select, SelectItem, SetExpr, TableWithJoins, Values},
+        ast::{
+            Expr, Join as AstJoin, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+            SetOperator, TableFactor, TableWithJoins, Values,
+        },
         data::{get_alias, Row, RowError},
         prelude::{DataType, Value},
         result::{Error, Result},
         store::GStore,
     },
     async_recursion::async_recursion,
-    futures::stream::{self, StreamExt, TryStream, TryStreamExt},
+    futures::{
+        future,
+        stream::{self, Stream, StreamExt, TryStream, TryStreamExt},
+    },
     iter_enum::Iterator,
     std::{
+        collections::{HashMap, HashSet},
         iter::{self, once},
+        pin::Pin,
         rc::Rc,
     },
     utils::Vector,
@@ -37,6 +50,7 @@ pub fn get_labels<'a>(
     table_alias: &str,
     columns: &'a [String],
     join_columns: Option<&'a [(&String, Vec<String>)]>,
+    merged_columns: &'a [Vec<String>],
 ) -> Result<Vec<String>> {
     #[derive(Iterator)]
     enum Labeled<I1, I2, I3, I4, I5> {
@@ -73,9 +87,11 @@ pub fn get_labels<'a>(
             SelectItem::Wildcard => {
                 let labels = to_labels(columns);
                 if let Some(join_columns) = join_columns {
-                    let join_labels = join_columns
-                        .iter()
-                        .flat_map(|(_, columns)| to_labels(columns));
+                    let join_labels = join_columns.iter().zip(merged_columns).flat_map(
+                        |((_, columns), merged_columns)| {
+                            to_labels(columns).filter(move |label| !merged_columns.contains(label))
+                        },
+                    );
                     let labels = labels.chain(join_labels).map(Ok);
                     return Labeled::Wildcard(Wildcard::WithJoin(labels));
                 };
@@ -107,6 +123,48 @@ pub fn get_labels<'a>(
         .collect::<Result<_>>()
 }
 
+/// Columns of the joins the labels are made of, semi and anti joins only filter the rows
+/// and their columns are never projected.
+pub fn projected_join_columns<'a>(
+    joins: &[AstJoin],
+    join_columns: &[(&'a String, Vec<String>)],
+    merged_columns: &[Vec<String>],
+) -> (Vec<(&'a String, Vec<String>)>, Vec<Vec<String>>) {
+    joins
+        .iter()
+        .zip(join_columns.iter().zip(merged_columns))
+        .filter(|(AstJoin { join_operator, .. }, _)| {
+            !matches!(join_operator, JoinOperator::Semi(_) | JoinOperator::Anti(_))
+        })
+        .map(|(_, ((alias, columns), merged_columns))| {
+            ((*alias, columns.clone()), merged_columns.clone())
+        })
+        .unzip()
+}
+
+/// Projects the rows inserted, updated or deleted by a statement with its
+/// `RETURNING` items.
+pub async fn select_returning<'a>(
+    storage: &'a dyn GStore,
+    table_name: &'a str,
+    columns: Rc<[String]>,
+    projection: &'a [SelectItem],
+    rows: impl IntoIterator<Item = Row>,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+) -> Result<(Vec<String>, Vec<Row>)> {
+    let labels = get_labels(projection, table_name, &columns, None, &[])?;
+    let blend = Blend::new(storage, filter_context, projection);
+
+    let mut projected = Vec::new();
+    for row in rows {
+        let context = BlendContext::new(table_name, Rc::clone(&columns), Single(Some(row)), None);
+
+        projected.push(blend.apply(None, Rc::new(context)).await?);
+    }
+
+    Ok((labels, projected))
+}
+
 fn rows_with_labels(exprs_list: &[Vec<Expr>]) -> (Vec<Result<Row>>, Vec<String>) {
     let first_len = exprs_list[0].len();
     let labels = (1..=first_len)
@@ -153,6 +211,14 @@ fn rows_with_labels(exprs_list: &[Vec<Expr>]) -> (Vec<Result<Row>>, Vec<String>)
     (rows, labels)
 }
 
+/// Columns of a set operation are named and typed after its leftmost operand.
+pub fn leftmost_operand(set_expr: &SetExpr) -> &SetExpr {
+    match set_expr {
+        SetExpr::SetOperation { left, .. } => leftmost_operand(left),
+        _ => set_expr,
+    }
+}
+
 fn sort_stateless(
     rows: Vec<Result<Row>>,
     labels: &Vec<String>,
@@ -183,7 +249,7 @@ fn sort_stateless(
 
     Ok(sorted)
 }
-#[async_recursion(?Send)]
+
 pub async fn select_with_labels<'a>(
     storage: &'a dyn GStore,
     query: &'a Query,
@@ -193,31 +259,136 @@ pub async fn select_with_labels<'a>(
     Vec<String>,
     impl TryStream<Ok = Row, Error = Error, Item = Result<Row>> + 'a,
 )> {
-    let Select {
-        from: table_with_joins,
-        selection: where_clause,
-        projection,
-        group_by,
-        having,
-    } = match &query.body {
+    select_query(storage, query, filter_context, with_labels, None).await
+}
+
+/// Runs the query for `EXPLAIN ANALYZE`, the row counts and the elapsed time of the stages of
+/// its outermost `SELECT` are collected into the profile.
+pub async fn select_analyzed<'a>(
+    storage: &'a dyn GStore,
+    query: &'a Query,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+    profile: Rc<Profile>,
+) -> Result<impl TryStream<Ok = Row, Error = Error, Item = Result<Row>> + 'a> {
+    select_query(storage, query, filter_context, false, Some(profile))
+        .await
+        .map(|(_, rows)| rows)
+}
+
+#[async_recursion(?Send)]
+async fn select_query<'a>(
+    storage: &'a dyn GStore,
+    query: &'a Query,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+    with_labels: bool,
+    profile: Option<Rc<Profile>>,
+) -> Result<(
+    Vec<String>,
+    impl TryStream<Ok = Row, Error = Error, Item = Result<Row>> + 'a,
+)> {
+    let limit = Limit::new(query.limit.as_ref(), query.offset.as_ref())?;
+
+    // the outermost query and each run of the common table expressions start a new scope of
+    // the cached scalar subqueries, as the storage seen by them may differ run by run
+    let filter_context = match (&query.with, filter_context) {
+        (None, Some(filter_context)) if !filter_context.is_root() => Some(filter_context),
+        (_, filter_context) => Some(Rc::new(FilterContext::with_subquery_cache(filter_context))),
+    };
+
+    if let Some(with) = &query.with {
+        let storage =
+            CteStorage::new(storage, with, filter_context.as_ref().map(Rc::clone)).await?;
+        let (labels, rows) = select_set_expr(
+            &storage,
+            &query.body,
+            &query.order_by,
+            limit,
+            filter_context,
+            with_labels,
+            profile,
+        )
+        .await?;
+        let rows = rows.try_collect::<Vec<_>>().await?;
+        let rows: Pin<Box<dyn Stream<Item = Result<Row>> + 'a>> =
+            Box::pin(stream::iter(rows.into_iter().map(Ok)));
+
+        return Ok((labels, rows));
+    }
+
+    select_set_expr(
+        storage,
+        &query.body,
+        &query.order_by,
+        limit,
+        filter_context,
+        with_labels,
+        profile,
+    )
+    .await
+}
+
+#[async_recursion(?Send)]
+async fn select_set_expr<'a>(
+    storage: &'a dyn GStore,
+    set_expr: &'a SetExpr,
+    order_by: &'a [OrderByExpr],
+    limit: Limit,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+    with_labels: bool,
+    profile: Option<Rc<Profile>>,
+) -> Result<(Vec<String>, Pin<Box<dyn Stream<Item = Result<Row>> + 'a>>)> {
+    let select = match set_expr {
         SetExpr::Select(statement) => statement.as_ref(),
         SetExpr::Values(Values(values_list)) => {
-            let limit = Limit::new(query.limit.as_ref(), query.offset.as_ref())?;
             let (rows, labels) = rows_with_labels(values_list);
-            let rows = sort_stateless(rows, &labels, &query.order_by)?;
+            let rows = sort_stateless(rows, &labels, order_by)?;
+            let rows = stream::iter(rows);
+            let rows = limit.apply(rows);
+
+            return Ok((labels, rows));
+        }
+        SetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+        } => {
+            let (labels, rows) =
+                select_set_operation(storage, op, *all, left, right, filter_context).await?;
+            let rows = rows.into_iter().map(Ok).collect();
+            let rows = sort_stateless(rows, &labels, order_by)?;
             let rows = stream::iter(rows);
             let rows = limit.apply(rows);
 
             return Ok((labels, rows));
         }
     };
+    let Select {
+        distinct,
+        from: table_with_joins,
+        selection: where_clause,
+        projection,
+        group_by,
+        having,
+    } = select;
 
     let TableWithJoins { relation, joins } = &table_with_joins;
-    let columns = fetch_relation_columns(storage, relation).await?;
+    let options = filter_context
+        .as_deref()
+        .map(FilterContext::options)
+        .unwrap_or_default();
+    let columns = fetch_relation_columns(storage, relation, options).await?;
+    let pushdown = matches!(relation, TableFactor::Table { .. })
+        .then(|| Pushdown::from_select(select, order_by, &columns));
     let columns = Rc::from(columns);
+    let profile = profile.as_ref();
     let rows = {
         let columns = Rc::clone(&columns);
-        fetch_relation_rows(storage, relation, &None)
+        // the relation does not see the outer rows
+        let relation_context = Some(Rc::new(FilterContext::with_options(options)));
+        let rows = fetch_relation_rows(storage, relation, &relation_context, pushdown);
+
+        measure_future(profile, Stage::Scan, rows)
             .await?
             .map(move |row| {
                 let row = Some(row?);
@@ -226,14 +397,20 @@ pub async fn select_with_labels<'a>(
                 Ok(BlendContext::new(alias, columns, Single(row), None))
             })
     };
+    let rows = measure(profile, Stage::Scan, rows);
 
-    let join_columns = fetch_join_columns(joins, storage).await?;
+    let join_columns = fetch_join_columns(joins, storage, options).await?;
+    let merged_columns = get_merged_columns(&columns, joins, &join_columns)?;
     let labels = if with_labels {
+        let (projected_columns, projected_merged_columns) =
+            projected_join_columns(joins, &join_columns, &merged_columns);
+
         get_labels(
             projection,
             get_alias(relation),
             &columns,
-            Some(&join_columns),
+            Some(&projected_columns),
+            &projected_merged_columns,
         )?
     } else {
         vec![]
@@ -244,12 +421,17 @@ pub async fn select_with_labels<'a>(
         .map(|(_, columns)| columns)
         .map(Rc::from)
         .collect::<Vec<_>>();
+    let merged_columns = merged_columns.into_iter().map(Rc::from).collect::<Vec<_>>();
     let join = Join::new(
         storage,
+        get_alias(relation),
+        Rc::clone(&columns),
         joins,
         join_columns,
+        merged_columns,
         filter_context.as_ref().map(Rc::clone),
-    );
+    )
+    .with_profile(profile.map(Rc::clone));
 
     let aggregate = Aggregator::new(
         storage,
@@ -269,8 +451,13 @@ pub async fn select_with_labels<'a>(
         filter_context.as_ref().map(Rc::clone),
         None,
     ));
-    let limit = Limit::new(query.limit.as_ref(), query.offset.as_ref())?;
-    let sort = Sort::new(storage, filter_context, &query.order_by);
+    let window = Window::new(
+        storage,
+        projection,
+        order_by,
+        filter_context.as_ref().map(Rc::clone),
+    );
+    let sort = Sort::new(storage, filter_context, order_by);
 
     let rows = join.apply(rows).await?;
     let rows = rows.try_filter_map(move |blend_context| {
@@ -283,8 +470,11 @@ pub async fn select_with_labels<'a>(
                 .map(|pass| pass.then_some(blend_context))
         }
     });
+    let rows = measure(profile, Stage::Filter, rows);
 
-    let rows = aggregate.apply(rows).await?;
+    let rows = measure_future(profile, Stage::Aggregate, aggregate.apply(rows)).await?;
+    let rows = measure(profile, Stage::Aggregate, rows);
+    let rows = measure_future(profile, Stage::Project, window.apply(rows)).await?;
 
     let rows = rows.and_then(move |aggregate_context| {
         let blend = Rc::clone(&blend);
@@ -299,19 +489,164 @@ pub async fn select_with_labels<'a>(
             Ok((aggregated, next, row))
         }
     });
+    let rows = measure(profile, Stage::Project, rows);
+
+    let distinct = *distinct;
+    let rows = rows
+        .scan(HashSet::new(), move |keys, item| {
+            let item = item.and_then(|(aggregated, next, row)| {
+                if !distinct {
+                    return Ok(Some((aggregated, next, row)));
+                }
+
+                let inserted = keys.insert(DistinctRow::from(&row));
+
+                Ok(inserted.then_some((aggregated, next, row)))
+            });
+
+            future::ready(Some(item.transpose()))
+        })
+        .filter_map(future::ready);
+    let rows = measure(profile, Stage::Distinct, rows);
 
     let labels = Rc::new(labels);
-    let rows = sort
-        .apply(rows, Rc::clone(&labels), get_alias(relation))
-        .await?;
+    let rows = sort.apply(rows, Rc::clone(&labels), get_alias(relation));
+    let rows = measure_future(profile, Stage::Sort, rows).await?;
+    let rows = measure(profile, Stage::Sort, rows);
 
     let rows = limit.apply(rows);
+    let rows: Pin<Box<dyn Stream<Item = Result<Row>> + 'a>> = match profile {
+        Some(_) => Box::pin(measure(profile, Stage::Limit, rows)),
+        None => rows,
+    };
 
     let labels = Rc::try_unwrap(labels).map_err(|_| SelectError::Unreachable)?;
 
     Ok((labels, rows))
 }
 
+async fn select_set_operand<'a>(
+    storage: &'a dyn GStore,
+    set_expr: &'a SetExpr,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+) -> Result<(Vec<String>, Vec<Row>)> {
+    let limit = Limit::new(None, None)?;
+    let (labels, rows) =
+        select_set_expr(storage, set_expr, &[], limit, filter_context, true, None).await?;
+    let rows = rows.try_collect().await?;
+
+    Ok((labels, rows))
+}
+
+async fn select_set_operation<'a>(
+    storage: &'a dyn GStore,
+    op: &SetOperator,
+    all: bool,
+    left: &'a SetExpr,
+    right: &'a SetExpr,
+    filter_context: Option<Rc<FilterContext<'a>>>,
+) -> Result<(Vec<String>, Vec<Row>)> {
+    let (labels, left_rows) =
+        select_set_operand(storage, left, filter_context.as_ref().map(Rc::clone)).await?;
+    let (right_labels, right_rows) = select_set_operand(storage, right, filter_context).await?;
+
+    if labels.len() != right_labels.len() {
+        return Err(SelectError::SetOperationColumnCountMismatch {
+            operator: op.clone(),
+            left: labels.len(),
+            right: right_labels.len(),
+        }
+        .into());
+    }
+
+    let column_types = |rows: &[Row]| {
+        (0..labels.len())
+            .map(|i| {
+                rows.iter()
+                    .find_map(|row| row.get_value_by_index(i).and_then(Value::get_type))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    labels
+        .iter()
+        .zip(column_types(&left_rows))
+        .zip(column_types(&right_rows))
+        .try_for_each(|((column, left), right)| -> Result<()> {
+            match (left, right) {
+                (Some(left), Some(right)) if left != right => {
+                    Err(SelectError::SetOperationTypeMismatch {
+                        operator: op.clone(),
+                        column: column.to_owned(),
+                        left,
+                        right,
+                    }
+                    .into())
+                }
+                _ => Ok(()),
+            }
+        })?;
+
+    let rows = combine_rows(op, all, left_rows, right_rows);
+
+    Ok((labels, rows))
+}
+
+/// Rows are compared as `DistinctRow`s, the same way `DISTINCT` does. Without `ALL` the
+/// result is deduplicated, with `ALL` duplicates are kept following multiset semantics.
+fn combine_rows(
+    op: &SetOperator,
+    all: bool,
+    left_rows: Vec<Row>,
+    right_rows: Vec<Row>,
+) -> Vec<Row> {
+    let (rows, mut right_counts) = match op {
+        SetOperator::Union if all => {
+            return left_rows.into_iter().chain(right_rows).collect();
+        }
+        SetOperator::Union => (
+            left_rows.into_iter().chain(right_rows).collect(),
+            HashMap::new(),
+        ),
+        SetOperator::Intersect | SetOperator::Except => {
+            let mut right_counts = HashMap::new();
+            for row in right_rows.iter() {
+                *right_counts
+                    .entry(DistinctRow::from(row))
+                    .or_insert(0_usize) += 1;
+            }
+
+            (left_rows, right_counts)
+        }
+    };
+
+    let mut keys = HashSet::new();
+
+    rows.into_iter()
+        .filter(|row| {
+            let key = DistinctRow::from(row);
+            let matched = match right_counts.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    if all {
+                        *count -= 1;
+                    }
+
+                    true
+                }
+                _ => false,
+            };
+
+            let keep = match op {
+                SetOperator::Union => true,
+                SetOperator::Intersect => matched,
+                SetOperator::Except => !matched,
+            };
+
+            keep && (all || keys.insert(key))
+        })
+        .collect()
+}
+
 pub async fn select<'a>(
     storage: &'a dyn GStore,
     query: &'a Query,
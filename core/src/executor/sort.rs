@@ -1,7 +1,7 @@
 use {
     super::{context::FilterContext, evaluate::evaluate},
     crate::{
-        ast::{Aggregate, AstLiteral, Expr, OrderByExpr, UnaryOperator},
+        ast::{AstLiteral, Expr, OrderByExpr, UnaryOperator},
         data::{Row, Value},
         executor::context::{BlendContext, BlendContextRow::Shared},
         result::{Error, Result},
@@ -47,7 +47,7 @@ impl<'a> Sort<'a> {
         &self,
         rows: impl Stream<
                 Item = Result<(
-                    Option<Rc<HashMap<&'a Aggregate, Value>>>,
+                    Option<Rc<HashMap<&'a Expr, Value>>>,
                     Rc<BlendContext<'a>>,
                     Row,
                 )>,
@@ -1,7 +1,8 @@
 use {
     super::{
-        context::FilterContext,
+        context::{BlendContext, FilterContext},
         evaluate::{evaluate, Evaluated},
+        generated::{dependent_generated_indexes, fill_generated_values},
     },
     crate::{
         ast::{Assignment, ColumnDef, ColumnOption},
@@ -23,54 +24,89 @@ pub enum UpdateError {
     #[error("update on primary key is not supported: {0}")]
     UpdateOnPrimaryKeyNotSupported(String),
 
+    #[error("update on generated column is not supported: {0}")]
+    UpdateOnGeneratedColumnNotSupported(String),
+
+    #[error("update on GENERATED ALWAYS identity column is not supported: {0}")]
+    UpdateOnAlwaysIdentityColumnNotSupported(String),
+
     #[error("conflict on schema, row data does not fit to schema")]
     ConflictOnSchema,
 }
 
 pub struct Update<'a> {
     storage: &'a dyn GStore,
-    table_name: &'a str,
+    table_alias: &'a str,
     fields: &'a [Assignment],
     column_defs: &'a [ColumnDef],
+    /// generated columns to recompute
+    generated: Vec<usize>,
+    filter_context: Option<Rc<FilterContext<'a>>>,
 }
 
 impl<'a> Update<'a> {
     pub fn new(
         storage: &'a dyn GStore,
-        table_name: &'a str,
+        table_alias: &'a str,
         fields: &'a [Assignment],
         column_defs: &'a [ColumnDef],
+        primary_key: Option<&[String]>,
+        filter_context: Option<Rc<FilterContext<'a>>>,
     ) -> Result<Self> {
         for assignment in fields.iter() {
             let Assignment { id, .. } = assignment;
 
             if column_defs.iter().all(|col_def| &col_def.name != id) {
                 return Err(UpdateError::ColumnNotFound(id.to_owned()).into());
-            } else if column_defs.iter().any(|ColumnDef { name, options, .. }| {
-                if name != id {
-                    return false;
-                }
+            } else if primary_key.unwrap_or_default().contains(id)
+                || column_defs.iter().any(|ColumnDef { name, options, .. }| {
+                    if name != id {
+                        return false;
+                    }
 
-                options
-                    .iter()
-                    .any(|option| option == &ColumnOption::Unique { is_primary: true })
-            }) {
+                    options
+                        .iter()
+                        .any(|option| option == &ColumnOption::Unique { is_primary: true })
+                })
+            {
                 return Err(UpdateError::UpdateOnPrimaryKeyNotSupported(id.to_owned()).into());
+            } else if column_defs
+                .iter()
+                .any(|column_def| &column_def.name == id && column_def.get_generated().is_some())
+            {
+                return Err(UpdateError::UpdateOnGeneratedColumnNotSupported(id.to_owned()).into());
+            } else if column_defs
+                .iter()
+                .any(|column_def| &column_def.name == id && column_def.is_always_identity())
+            {
+                return Err(
+                    UpdateError::UpdateOnAlwaysIdentityColumnNotSupported(id.to_owned()).into(),
+                );
             }
         }
 
+        let columns = fields
+            .iter()
+            .map(|Assignment { id, .. }| id.to_owned())
+            .collect::<Vec<_>>();
+        let generated = dependent_generated_indexes(column_defs, &columns)?;
+
         Ok(Self {
             storage,
-            table_name,
+            table_alias,
             fields,
             column_defs,
+            generated,
+            filter_context,
         })
     }
 
-    async fn find(&self, row: &Row, column_def: &ColumnDef) -> Result<Option<Value>> {
-        let all_columns = Rc::from(self.all_columns());
-        let context = FilterContext::new(self.table_name, Rc::clone(&all_columns), row, None);
-        let context = Some(Rc::new(context));
+    async fn find(
+        &self,
+        context: Rc<FilterContext<'_>>,
+        column_def: &ColumnDef,
+    ) -> Result<Option<Value>> {
+        let context = Some(context);
 
         match self
             .fields
@@ -102,6 +138,33 @@ impl<'a> Update<'a> {
     }
 
     pub async fn apply(&self, row: Row) -> Result<Row> {
+        self.apply_with(row, self.filter_context.as_ref().map(Rc::clone))
+            .await
+    }
+
+    /// Applies the assignments of `ON CONFLICT DO UPDATE`, in which the row
+    /// proposed for insertion is referred to as `EXCLUDED`.
+    pub async fn apply_excluded(&self, row: Row, excluded: &Row) -> Result<Row> {
+        let all_columns = Rc::from(self.all_columns());
+        let next = self.filter_context.as_ref().map(Rc::clone);
+        let excluded = FilterContext::new("EXCLUDED", all_columns, excluded, next);
+
+        self.apply_with(row, Some(Rc::new(excluded))).await
+    }
+
+    /// Applies the assignments of `UPDATE` with joins, which can refer to the
+    /// columns of the joined tables.
+    pub async fn apply_joined(&self, row: Row, joined: Rc<BlendContext<'_>>) -> Result<Row> {
+        let next = self.filter_context.as_ref().map(Rc::clone);
+        let joined = FilterContext::concat(next, Some(joined));
+
+        self.apply_with(row, Some(Rc::new(joined))).await
+    }
+
+    async fn apply_with(&self, row: Row, next: Option<Rc<FilterContext<'_>>>) -> Result<Row> {
+        let all_columns = Rc::from(self.all_columns());
+        let context = FilterContext::new(self.table_alias, all_columns, &row, next);
+        let context = Rc::new(context);
         let Row(values) = &row;
 
         let values = values.clone().into_iter().enumerate().map(|(i, value)| {
@@ -113,10 +176,10 @@ impl<'a> Update<'a> {
 
         stream::iter(values)
             .and_then(|(col_def, value)| {
-                let row = &row;
+                let context = Rc::clone(&context);
 
                 async move {
-                    self.find(row, col_def)
+                    self.find(context, col_def)
                         .await
                         .transpose()
                         .unwrap_or(Ok(value))
@@ -125,6 +188,7 @@ impl<'a> Update<'a> {
             .try_collect::<Vec<_>>()
             .await
             .map(Row)
+            .and_then(|row| fill_generated_values(self.column_defs, &self.generated, row))
     }
 
     pub fn all_columns(&self) -> Vec<String> {
@@ -0,0 +1,426 @@
+use {
+    super::{
+        context::FilterContext,
+        filter::check_expr,
+        foreign_key::{row_keys, RowChange},
+        update::Update,
+        validate::ValidateError,
+    },
+    crate::{
+        ast::{ColumnDef, ColumnOption, OnConflict, OnConflictAction},
+        data::{Key, Row, Schema},
+        result::{Error, Result},
+        store::GStore,
+    },
+    serde::Serialize,
+    std::{collections::HashMap, fmt::Debug, mem, rc::Rc},
+    thiserror::Error as ThisError,
+};
+
+#[cfg(feature = "index")]
+use {super::foreign_key::find_index, crate::ast::IndexOperator};
+
+#[derive(ThisError, Serialize, Debug, PartialEq)]
+pub enum UpsertError {
+    #[error("conflict target is not the primary key or a unique column: {0}")]
+    ConflictTargetNotUnique(String),
+
+    #[error("ON CONFLICT DO UPDATE cannot affect the same row a second time")]
+    RowAffectedTwice,
+}
+
+/// Rows of `INSERT .. ON CONFLICT`, split into new rows to insert and
+/// existing rows to update.
+pub struct Upsert {
+    pub inserts: Vec<Row>,
+    pub updates: Vec<RowChange>,
+}
+
+/// How the stored rows are looked up by the values of a constraint.
+enum Lookup {
+    /// by the key of the row
+    PrimaryKey,
+    /// through the index on the column
+    #[cfg(feature = "index")]
+    Index(String),
+    /// through the stored rows mapped by the values of the column, the table is scanned
+    /// once at the first lookup
+    Scan(Option<HashMap<Vec<Key>, (Key, Row)>>),
+}
+
+/// PRIMARY KEY or UNIQUE column, with the rows known to the statement indexed by its values.
+struct Constraint {
+    column_indexes: Vec<usize>,
+    is_primary: bool,
+    lookup: Lookup,
+    entries: HashMap<Vec<Key>, usize>,
+}
+
+impl Constraint {
+    fn duplicate_error(&self, column_defs: &[ColumnDef], row: &Row, keys: Vec<Key>) -> Error {
+        match (self.is_primary, self.column_indexes.as_slice()) {
+            (false, [index]) => ValidateError::DuplicateEntryOnUniqueField(
+                row.0[*index].clone(),
+                column_defs[*index].name.to_owned(),
+            )
+            .into(),
+            _ => {
+                let key = match keys.len() {
+                    1 => keys.into_iter().next().unwrap_or(Key::None),
+                    _ => Key::Composite(keys),
+                };
+
+                ValidateError::DuplicateEntryOnPrimaryKeyField(key).into()
+            }
+        }
+    }
+}
+
+enum Entry {
+    /// stored row which has not been affected by the statement yet
+    Stored(Key, Row),
+    /// row inserted or updated by the statement
+    Affected,
+}
+
+fn constraints(schema: &Schema) -> Vec<Constraint> {
+    let primary_key = schema
+        .primary_key_indexes()
+        .map(|column_indexes| Constraint {
+            column_indexes,
+            is_primary: true,
+            lookup: Lookup::PrimaryKey,
+            entries: HashMap::new(),
+        });
+    let unique_columns = schema
+        .column_defs
+        .iter()
+        .enumerate()
+        .filter(|(_, ColumnDef { options, .. })| {
+            options.contains(&ColumnOption::Unique { is_primary: false })
+        })
+        .map(|(index, column_def)| Constraint {
+            column_indexes: vec![index],
+            is_primary: false,
+            lookup: unique_lookup(schema, column_def),
+            entries: HashMap::new(),
+        });
+
+    primary_key.into_iter().chain(unique_columns).collect()
+}
+
+/// Rows of the statement and the stored rows they conflict with. Stored rows are looked up
+/// by the values of the constraints as they are needed, instead of reading the whole table.
+struct Resolver<'a> {
+    storage: &'a dyn GStore,
+    table_name: &'a str,
+    constraints: Vec<Constraint>,
+    entries: Vec<Entry>,
+    /// Positions in `entries` of the stored rows looked up, by their keys
+    stored: HashMap<Key, usize>,
+}
+
+impl Resolver<'_> {
+    /// Row which holds the values of `row` in the constraint, either a row known to the
+    /// statement or a stored row the statement has not changed yet.
+    async fn find(&mut self, index: usize, row: &Row) -> Result<Option<usize>> {
+        let constraint = &self.constraints[index];
+        let keys = match row_keys(row, &constraint.column_indexes)? {
+            Some(keys) => keys,
+            None => return Ok(None),
+        };
+
+        if let Some(entry_index) = constraint.entries.get(&keys) {
+            return Ok(Some(*entry_index));
+        }
+
+        let (key, stored_row) = match self.fetch(index, row, &keys).await? {
+            Some(fetched) => fetched,
+            None => return Ok(None),
+        };
+
+        // the values are stale, the statement has already changed the stored row
+        if self.stored.contains_key(&key) {
+            return Ok(None);
+        }
+
+        let entry_index = self.entries.len();
+        for constraint in self.constraints.iter_mut() {
+            if let Some(keys) = row_keys(&stored_row, &constraint.column_indexes)? {
+                constraint.entries.insert(keys, entry_index);
+            }
+        }
+
+        self.stored.insert(key.clone(), entry_index);
+        self.entries.push(Entry::Stored(key, stored_row));
+
+        Ok(Some(entry_index))
+    }
+
+    async fn fetch(&mut self, index: usize, row: &Row, keys: &[Key]) -> Result<Option<(Key, Row)>> {
+        let Constraint {
+            column_indexes,
+            lookup,
+            ..
+        } = &mut self.constraints[index];
+
+        match lookup {
+            Lookup::PrimaryKey => {
+                let values = column_indexes
+                    .iter()
+                    .filter_map(|index| row.get_value_by_index(*index))
+                    .cloned()
+                    .collect();
+                let key = Key::from_primary_values(values)?;
+
+                self.storage
+                    .fetch_data(self.table_name, &key)
+                    .await
+                    .map(|row| row.map(|row| (key, row)))
+            }
+            #[cfg(feature = "index")]
+            Lookup::Index(index_name) => {
+                let cmp_value = column_indexes
+                    .iter()
+                    .find_map(|index| row.get_value_by_index(*index))
+                    .map(|value| (&IndexOperator::Eq, value.clone()));
+
+                for item in self
+                    .storage
+                    .scan_indexed_data(self.table_name, index_name, None, Vec::new(), cmp_value)
+                    .await?
+                {
+                    let (key, row) = item?;
+
+                    if row_keys(&row, column_indexes)?.as_deref() == Some(keys) {
+                        return Ok(Some((key, row)));
+                    }
+                }
+
+                Ok(None)
+            }
+            Lookup::Scan(scanned) => {
+                let scanned = match scanned {
+                    Some(scanned) => scanned,
+                    None => {
+                        let mut rows = HashMap::new();
+                        for item in self.storage.scan_data(self.table_name).await? {
+                            let (key, row) = item?;
+
+                            if let Some(keys) = row_keys(&row, column_indexes)? {
+                                rows.insert(keys, (key, row));
+                            }
+                        }
+
+                        scanned.insert(rows)
+                    }
+                };
+
+                Ok(scanned.get(keys).cloned())
+            }
+        }
+    }
+}
+
+/// UNIQUE column is looked up through an index on it, the table is scanned otherwise.
+#[cfg_attr(not(feature = "index"), allow(unused_variables))]
+fn unique_lookup(schema: &Schema, column_def: &ColumnDef) -> Lookup {
+    #[cfg(feature = "index")]
+    if let Some((index_name, _)) = find_index(schema, &[column_def.name.to_owned()]) {
+        return Lookup::Index(index_name.to_owned());
+    }
+
+    Lookup::Scan(None)
+}
+
+/// Positions of the constraints the conflict target refers to,
+/// every constraint when no target is given.
+fn conflict_target(
+    schema: &Schema,
+    constraints: &[Constraint],
+    columns: &[String],
+) -> Result<Vec<usize>> {
+    if columns.is_empty() {
+        return Ok((0..constraints.len()).collect());
+    }
+
+    let column_indexes = columns
+        .iter()
+        .map(|column_name| {
+            schema
+                .column_defs
+                .iter()
+                .position(|ColumnDef { name, .. }| name == column_name)
+        })
+        .collect::<Option<Vec<_>>>();
+
+    let target = column_indexes.and_then(|column_indexes| {
+        constraints.iter().position(|constraint| {
+            constraint.column_indexes.len() == column_indexes.len()
+                && column_indexes
+                    .iter()
+                    .all(|index| constraint.column_indexes.contains(index))
+        })
+    });
+
+    match target {
+        Some(target) => Ok(vec![target]),
+        None => Err(UpsertError::ConflictTargetNotUnique(columns.join(", ")).into()),
+    }
+}
+
+/// Resolves the rows of `INSERT .. ON CONFLICT` against the stored rows and
+/// the rows inserted before them, and validates PRIMARY KEY and UNIQUE
+/// constraints of the resolved rows. Only the stored rows sharing the values
+/// of a constraint with the resolved rows are read.
+pub async fn resolve_conflicts(
+    storage: &dyn GStore,
+    schema: &Schema,
+    on_conflict: &OnConflict,
+    rows: Vec<Row>,
+    filter_context: Option<Rc<FilterContext<'_>>>,
+) -> Result<Upsert> {
+    let OnConflict { columns, action } = on_conflict;
+    let Schema {
+        table_name,
+        column_defs,
+        primary_key,
+        ..
+    } = schema;
+
+    let constraints = constraints(schema);
+    let target = conflict_target(schema, &constraints, columns)?;
+    let update = match action {
+        OnConflictAction::DoNothing => None,
+        OnConflictAction::DoUpdate {
+            assignments,
+            selection,
+        } => Some((
+            Update::new(
+                storage,
+                table_name,
+                assignments,
+                column_defs,
+                primary_key.as_deref(),
+                filter_context.as_ref().map(Rc::clone),
+            )?,
+            selection,
+        )),
+    };
+
+    let mut resolver = Resolver {
+        storage,
+        table_name,
+        constraints,
+        entries: Vec::new(),
+        stored: HashMap::new(),
+    };
+    let all_columns = column_defs
+        .iter()
+        .map(|ColumnDef { name, .. }| name.to_owned())
+        .collect::<Rc<[String]>>();
+    let mut inserts = Vec::new();
+    let mut updates = Vec::new();
+
+    for row in rows {
+        let mut conflict = None;
+        for index in &target {
+            conflict = resolver.find(*index, &row).await?;
+
+            if conflict.is_some() {
+                break;
+            }
+        }
+
+        let (entry_index, (update, selection)) = match (conflict, &update) {
+            (None, _) => {
+                let entry_index = resolver.entries.len();
+
+                for index in 0..resolver.constraints.len() {
+                    let keys = match row_keys(&row, &resolver.constraints[index].column_indexes)? {
+                        Some(keys) => keys,
+                        None => continue,
+                    };
+
+                    // the constraints of the target are already found to be free
+                    if !target.contains(&index) && resolver.find(index, &row).await?.is_some() {
+                        let constraint = &resolver.constraints[index];
+
+                        return Err(constraint.duplicate_error(column_defs, &row, keys));
+                    }
+
+                    resolver.constraints[index]
+                        .entries
+                        .insert(keys, entry_index);
+                }
+
+                resolver.entries.push(Entry::Affected);
+                inserts.push(row);
+                continue;
+            }
+            (Some(_), None) => continue,
+            (Some(entry_index), Some(update)) => (entry_index, update),
+        };
+
+        let old_row = match &resolver.entries[entry_index] {
+            Entry::Stored(_, old_row) => old_row.clone(),
+            Entry::Affected => {
+                return Err(UpsertError::RowAffectedTwice.into());
+            }
+        };
+
+        if let Some(expr) = selection {
+            let excluded = FilterContext::new(
+                "EXCLUDED",
+                Rc::clone(&all_columns),
+                &row,
+                filter_context.as_ref().map(Rc::clone),
+            );
+            let context = FilterContext::new(
+                table_name,
+                Rc::clone(&all_columns),
+                &old_row,
+                Some(Rc::new(excluded)),
+            );
+
+            if !check_expr(storage, Some(Rc::new(context)), None, expr).await? {
+                continue;
+            }
+        }
+
+        let new_row = update.apply_excluded(old_row.clone(), &row).await?;
+        for index in 0..resolver.constraints.len() {
+            let column_indexes = &resolver.constraints[index].column_indexes;
+            let old_keys = row_keys(&old_row, column_indexes)?;
+            let new_keys = row_keys(&new_row, column_indexes)?;
+
+            if old_keys == new_keys {
+                continue;
+            }
+
+            if let Some(old_keys) = old_keys {
+                resolver.constraints[index].entries.remove(&old_keys);
+            }
+
+            if let Some(new_keys) = new_keys {
+                if resolver.find(index, &new_row).await?.is_some() {
+                    let constraint = &resolver.constraints[index];
+
+                    return Err(constraint.duplicate_error(column_defs, &new_row, new_keys));
+                }
+
+                resolver.constraints[index]
+                    .entries
+                    .insert(new_keys, entry_index);
+            }
+        }
+
+        if let Entry::Stored(key, old_row) =
+            mem::replace(&mut resolver.entries[entry_index], Entry::Affected)
+        {
+            updates.push((key, old_row, Some(new_row)));
+        }
+    }
+
+    Ok(Upsert { inserts, updates })
+}
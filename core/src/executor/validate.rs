@@ -1,9 +1,10 @@
 use {
+    super::{context::FilterContext, evaluate::evaluate},
     crate::{
-        ast::{ColumnDef, ColumnOption},
-        data::{Key, Row, Value},
+        ast::{ColumnDef, ColumnOption, ToSql},
+        data::{Key, Row, Schema, Value},
         result::Result,
-        store::Store,
+        store::{GStore, Store},
     },
     im_rc::HashSet,
     serde::Serialize,
@@ -22,6 +23,9 @@ pub enum ValidateError {
 
     #[error("duplicate entry '{0:?}' for primary_key field")]
     DuplicateEntryOnPrimaryKeyField(Key),
+
+    #[error("check constraint violated: {0}")]
+    CheckConstraintViolated(String),
 }
 
 pub enum ColumnValidation {
@@ -162,6 +166,60 @@ pub async fn validate_unique(
     }
 }
 
+/// Checks that none of the keys of a multi-column PRIMARY KEY is duplicated,
+/// both within the given keys and against the stored rows.
+pub async fn validate_composite_primary_key(
+    storage: &dyn Store,
+    table_name: &str,
+    keys: impl Iterator<Item = &Key>,
+) -> Result<()> {
+    let mut visited = HashSet::new();
+
+    for key in keys {
+        if visited.contains(key) || storage.fetch_data(table_name, key).await?.is_some() {
+            return Err(ValidateError::DuplicateEntryOnPrimaryKeyField(key.clone()).into());
+        }
+
+        visited.insert(key.clone());
+    }
+
+    Ok(())
+}
+
+/// Checks every row satisfies the CHECK constraints of the table,
+/// a constraint evaluated to NULL is regarded as satisfied.
+pub async fn validate_checks(
+    storage: &dyn GStore,
+    schema: &Schema,
+    row_iter: impl Iterator<Item = &Row>,
+) -> Result<()> {
+    let checks = schema.all_checks().collect::<Vec<_>>();
+    if checks.is_empty() {
+        return Ok(());
+    }
+
+    let columns = schema
+        .column_defs
+        .iter()
+        .map(|ColumnDef { name, .. }| name.to_owned())
+        .collect::<Rc<[String]>>();
+
+    for row in row_iter {
+        let context = FilterContext::new(&schema.table_name, Rc::clone(&columns), row, None);
+        let context = Rc::new(context);
+
+        for expr in &checks {
+            let evaluated = evaluate(storage, Some(Rc::clone(&context)), None, expr).await?;
+
+            if !evaluated.is_null() && !bool::try_from(evaluated)? {
+                return Err(ValidateError::CheckConstraintViolated(expr.to_sql()).into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn create_unique_constraints<'a>(
     unique_columns: Vec<(usize, String)>,
     row_iter: impl Iterator<Item = &'a Row> + Clone,
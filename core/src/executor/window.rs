@@ -0,0 +1,601 @@
+use {
+    super::{
+        aggregate::AggrValue,
+        context::{AggregateContext, FilterContext},
+        evaluate::evaluate,
+        sort::Sort,
+    },
+    crate::{
+        ast::{
+            Aggregate, AggregateFunction, Expr, OrderByExpr, SelectItem, WindowFrame,
+            WindowFrameBound, WindowFrameUnits, WindowFunction, WindowSpec,
+        },
+        data::{Key, Value},
+        plan::PlanExpr,
+        result::{Error, Result},
+        store::GStore,
+    },
+    futures::stream::{self, Stream, TryStream, TryStreamExt},
+    im_rc::HashMap,
+    serde::Serialize,
+    std::{cmp::Ordering, collections::HashSet, fmt::Debug, pin::Pin, rc::Rc},
+    thiserror::Error as ThisError,
+};
+
+#[derive(ThisError, Serialize, Debug, PartialEq, Eq)]
+pub enum WindowError {
+    #[error("RANGE with offset PRECEDING or FOLLOWING requires exactly one ORDER BY expression")]
+    RangeOffsetRequiresSingleOrderBy,
+
+    #[error("unreachable!")]
+    Unreachable,
+}
+
+type Applied<'a> = dyn TryStream<Ok = AggregateContext<'a>, Error = Error, Item = Result<AggregateContext<'a>>>
+    + 'a;
+type Context<'a> = (Rc<FilterContext<'a>>, Option<Rc<HashMap<&'a Expr, Value>>>);
+
+/// Values of a single row which a window function needs from it
+struct WindowRow {
+    partition: Vec<Key>,
+    order: Vec<(Value, Option<bool>)>,
+    args: Vec<Value>,
+}
+
+enum FrameBound {
+    UnboundedPreceding,
+    Preceding(Value),
+    CurrentRow,
+    Following(Value),
+    UnboundedFollowing,
+}
+
+struct Frame {
+    units: WindowFrameUnits,
+    start: FrameBound,
+    end: FrameBound,
+}
+
+/// Evaluates window functions in the SELECT list and ORDER BY over the aggregated rows.
+/// Results are added to the aggregated values of each row, so `Blend` and `Sort` can
+/// read them the same way they read aggregates.
+pub struct Window<'a> {
+    storage: &'a dyn GStore,
+    fields: &'a [SelectItem],
+    order_by: &'a [OrderByExpr],
+    filter_context: Option<Rc<FilterContext<'a>>>,
+}
+
+impl<'a> Window<'a> {
+    pub fn new(
+        storage: &'a dyn GStore,
+        fields: &'a [SelectItem],
+        order_by: &'a [OrderByExpr],
+        filter_context: Option<Rc<FilterContext<'a>>>,
+    ) -> Self {
+        Self {
+            storage,
+            fields,
+            order_by,
+            filter_context,
+        }
+    }
+
+    pub async fn apply(
+        &self,
+        rows: impl Stream<Item = Result<AggregateContext<'a>>> + 'a,
+    ) -> Result<Pin<Box<Applied<'a>>>> {
+        let mut windows = Vec::new();
+        let exprs = self
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                SelectItem::Expr { expr, .. } => Some(expr),
+                _ => None,
+            })
+            .chain(self.order_by.iter().map(|OrderByExpr { expr, .. }| expr));
+
+        for expr in exprs {
+            collect_windows(expr, &mut windows);
+        }
+
+        if windows.is_empty() {
+            return Ok(Box::pin(rows));
+        }
+
+        let rows = rows.try_collect::<Vec<_>>().await?;
+        let contexts = rows
+            .iter()
+            .map(|AggregateContext { aggregated, next }| {
+                let filter_context = FilterContext::concat(
+                    self.filter_context.as_ref().map(Rc::clone),
+                    Some(Rc::clone(next)),
+                );
+
+                (Rc::new(filter_context), aggregated.clone().map(Rc::new))
+            })
+            .collect::<Vec<Context<'a>>>();
+
+        let mut results = vec![Vec::with_capacity(windows.len()); rows.len()];
+        for (expr, func, spec) in windows {
+            let values = self.evaluate_window(func, spec, &contexts).await?;
+
+            for (result, value) in results.iter_mut().zip(values) {
+                result.push((expr, value));
+            }
+        }
+
+        let rows =
+            rows.into_iter()
+                .zip(results)
+                .map(|(AggregateContext { aggregated, next }, values)| {
+                    let mut aggregated = aggregated.unwrap_or_default();
+                    aggregated.extend(values);
+
+                    Ok(AggregateContext {
+                        aggregated: Some(aggregated),
+                        next,
+                    })
+                });
+
+        Ok(Box::pin(stream::iter(rows)))
+    }
+
+    async fn evaluate_window(
+        &self,
+        func: &'a WindowFunction,
+        spec: &'a WindowSpec,
+        contexts: &[Context<'a>],
+    ) -> Result<Vec<Value>> {
+        let WindowSpec {
+            partition_by,
+            order_by,
+            frame,
+        } = spec;
+
+        let mut rows = Vec::with_capacity(contexts.len());
+        for context in contexts {
+            let mut partition = Vec::with_capacity(partition_by.len());
+            for expr in partition_by {
+                partition.push(Key::try_from(self.evaluate(context, expr).await?)?);
+            }
+
+            let mut order = Vec::with_capacity(order_by.len());
+            for OrderByExpr { expr, asc } in order_by {
+                order.push((self.evaluate(context, expr).await?, *asc));
+            }
+
+            let args = match func {
+                WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => {
+                    Vec::new()
+                }
+                WindowFunction::Lag {
+                    expr,
+                    offset,
+                    default,
+                }
+                | WindowFunction::Lead {
+                    expr,
+                    offset,
+                    default,
+                } => {
+                    let value = self.evaluate(context, expr).await?;
+                    let offset = match offset {
+                        Some(offset) => self.evaluate(context, offset).await?,
+                        None => Value::I64(1),
+                    };
+                    let default = match default {
+                        Some(default) => self.evaluate(context, default).await?,
+                        None => Value::Null,
+                    };
+
+                    vec![value, offset, default]
+                }
+                WindowFunction::FirstValue(expr) | WindowFunction::LastValue(expr) => {
+                    vec![self.evaluate(context, expr).await?]
+                }
+                WindowFunction::Aggregate(aggr) => match aggr.as_expr() {
+                    Some(expr) => vec![self.evaluate(context, expr).await?],
+                    None => vec![Value::Null],
+                },
+            };
+
+            rows.push(WindowRow {
+                partition,
+                order,
+                args,
+            });
+        }
+
+        let frame = self.frame(frame.as_ref(), order_by).await?;
+
+        let mut partitions: Vec<Vec<usize>> = Vec::new();
+        let mut partition_indexes = HashMap::new();
+        for (i, row) in rows.iter().enumerate() {
+            match partition_indexes.get(&row.partition).copied() {
+                Some(index) => partitions[index].push(i),
+                None => {
+                    partition_indexes.insert(&row.partition, partitions.len());
+                    partitions.push(vec![i]);
+                }
+            }
+        }
+
+        let mut values = vec![Value::Null; rows.len()];
+        for mut partition in partitions {
+            partition.sort_by(|a, b| Sort::sort_by(&rows[*a].order, &rows[*b].order));
+
+            let partition_rows = partition.iter().map(|i| &rows[*i]).collect::<Vec<_>>();
+            let partition_values = evaluate_partition(func, &frame, &partition_rows)?;
+
+            for (i, value) in partition.into_iter().zip(partition_values) {
+                values[i] = value;
+            }
+        }
+
+        Ok(values)
+    }
+
+    async fn evaluate(&self, context: &Context<'a>, expr: &'a Expr) -> Result<Value> {
+        let (filter_context, aggregated) = context;
+
+        evaluate(
+            self.storage,
+            Some(Rc::clone(filter_context)),
+            aggregated.as_ref().map(Rc::clone),
+            expr,
+        )
+        .await?
+        .try_into()
+    }
+
+    async fn frame(
+        &self,
+        frame: Option<&'a WindowFrame>,
+        order_by: &[OrderByExpr],
+    ) -> Result<Frame> {
+        let frame = match frame {
+            Some(frame) => frame,
+            None if order_by.is_empty() => {
+                return Ok(Frame {
+                    units: WindowFrameUnits::Range,
+                    start: FrameBound::UnboundedPreceding,
+                    end: FrameBound::UnboundedFollowing,
+                });
+            }
+            None => {
+                return Ok(Frame {
+                    units: WindowFrameUnits::Range,
+                    start: FrameBound::UnboundedPreceding,
+                    end: FrameBound::CurrentRow,
+                });
+            }
+        };
+
+        let WindowFrame { units, start, end } = frame;
+
+        let start = self.frame_bound(*units, order_by, start).await?;
+        let end = match end {
+            Some(end) => self.frame_bound(*units, order_by, end).await?,
+            None => FrameBound::CurrentRow,
+        };
+
+        Ok(Frame {
+            units: *units,
+            start,
+            end,
+        })
+    }
+
+    async fn frame_bound(
+        &self,
+        units: WindowFrameUnits,
+        order_by: &[OrderByExpr],
+        bound: &'a WindowFrameBound,
+    ) -> Result<FrameBound> {
+        let offset = match bound {
+            WindowFrameBound::CurrentRow => return Ok(FrameBound::CurrentRow),
+            WindowFrameBound::Preceding(None) => return Ok(FrameBound::UnboundedPreceding),
+            WindowFrameBound::Following(None) => return Ok(FrameBound::UnboundedFollowing),
+            WindowFrameBound::Preceding(Some(offset))
+            | WindowFrameBound::Following(Some(offset)) => offset,
+        };
+
+        if units == WindowFrameUnits::Range && order_by.len() != 1 {
+            return Err(WindowError::RangeOffsetRequiresSingleOrderBy.into());
+        }
+
+        let filter_context = self.filter_context.as_ref().map(Rc::clone);
+        let offset = evaluate(self.storage, filter_context, None, offset)
+            .await?
+            .try_into()?;
+
+        Ok(match bound {
+            WindowFrameBound::Preceding(_) => FrameBound::Preceding(offset),
+            _ => FrameBound::Following(offset),
+        })
+    }
+}
+
+fn collect_windows<'a>(
+    expr: &'a Expr,
+    windows: &mut Vec<(&'a Expr, &'a WindowFunction, &'a WindowSpec)>,
+) {
+    if let Expr::Window(window) = expr {
+        if windows.iter().all(|(collected, ..)| *collected != expr) {
+            windows.push((expr, &window.func, &window.spec));
+        }
+
+        return;
+    }
+
+    let exprs = match PlanExpr::from(expr) {
+        PlanExpr::None
+        | PlanExpr::Identifier(_)
+        | PlanExpr::CompoundIdentifier { .. }
+        | PlanExpr::Query(_) => Vec::new(),
+        PlanExpr::Expr(expr) | PlanExpr::QueryAndExpr { expr, .. } => vec![expr],
+        PlanExpr::TwoExprs(expr, expr2) => vec![expr, expr2],
+        PlanExpr::ThreeExprs(expr, expr2, expr3) => vec![expr, expr2, expr3],
+        PlanExpr::MultiExprs(exprs) => exprs,
+    };
+
+    for expr in exprs {
+        collect_windows(expr, windows);
+    }
+}
+
+fn evaluate_partition(
+    func: &WindowFunction,
+    frame: &Frame,
+    rows: &[&WindowRow],
+) -> Result<Vec<Value>> {
+    let len = rows.len();
+    let is_peer =
+        |a: usize, b: usize| Sort::sort_by(&rows[a].order, &rows[b].order) == Ordering::Equal;
+
+    let mut peer_start = vec![0; len];
+    for i in 1..len {
+        peer_start[i] = if is_peer(i - 1, i) {
+            peer_start[i - 1]
+        } else {
+            i
+        };
+    }
+
+    let mut peer_end = vec![len; len];
+    for i in (0..len.saturating_sub(1)).rev() {
+        peer_end[i] = if is_peer(i, i + 1) {
+            peer_end[i + 1]
+        } else {
+            i + 1
+        };
+    }
+
+    let frame_bounds = |i: usize| {
+        let start = locate(frame, &frame.start, true, i, rows, &peer_start, &peer_end)?;
+        let end = locate(frame, &frame.end, false, i, rows, &peer_start, &peer_end)?;
+
+        Ok::<_, Error>((start, end.max(start)))
+    };
+    let frame_rows = |i: usize| frame_bounds(i).map(|(start, end)| &rows[start..end]);
+
+    if let WindowFunction::Aggregate(aggr) = func {
+        let bounds = (0..len).map(&frame_bounds).collect::<Result<Vec<_>>>()?;
+
+        return aggregate(aggr, rows, &bounds);
+    }
+
+    let mut dense_rank = 0;
+
+    (0..len)
+        .map(|i| match func {
+            WindowFunction::RowNumber => Ok(Value::I64(i as i64 + 1)),
+            WindowFunction::Rank => Ok(Value::I64(peer_start[i] as i64 + 1)),
+            WindowFunction::DenseRank => {
+                if peer_start[i] == i {
+                    dense_rank += 1;
+                }
+
+                Ok(Value::I64(dense_rank))
+            }
+            WindowFunction::Lag { .. } | WindowFunction::Lead { .. } => {
+                let offset = usize::try_from(&rows[i].args[1])?;
+                let target = match func {
+                    WindowFunction::Lag { .. } => i.checked_sub(offset),
+                    _ => i.checked_add(offset).filter(|target| *target < len),
+                };
+
+                Ok(match target {
+                    Some(target) => rows[target].args[0].clone(),
+                    None => rows[i].args[2].clone(),
+                })
+            }
+            WindowFunction::FirstValue(_) => Ok(frame_rows(i)?
+                .first()
+                .map(|row| row.args[0].clone())
+                .unwrap_or(Value::Null)),
+            WindowFunction::LastValue(_) => Ok(frame_rows(i)?
+                .last()
+                .map(|row| row.args[0].clone())
+                .unwrap_or(Value::Null)),
+            WindowFunction::Aggregate(_) => Err(WindowError::Unreachable.into()),
+        })
+        .collect()
+}
+
+/// Returns the index where the frame starts, or the index right after the frame ends.
+fn locate(
+    frame: &Frame,
+    bound: &FrameBound,
+    is_start: bool,
+    i: usize,
+    rows: &[&WindowRow],
+    peer_start: &[usize],
+    peer_end: &[usize],
+) -> Result<usize> {
+    let len = rows.len();
+
+    let (offset, preceding) = match bound {
+        FrameBound::UnboundedPreceding => return Ok(0),
+        FrameBound::UnboundedFollowing => return Ok(len),
+        FrameBound::CurrentRow => match frame.units {
+            WindowFrameUnits::Rows if is_start => return Ok(i),
+            WindowFrameUnits::Rows => return Ok(i + 1),
+            WindowFrameUnits::Range if is_start => return Ok(peer_start[i]),
+            WindowFrameUnits::Range => return Ok(peer_end[i]),
+        },
+        FrameBound::Preceding(offset) => (offset, true),
+        FrameBound::Following(offset) => (offset, false),
+    };
+
+    if frame.units == WindowFrameUnits::Rows {
+        let offset = usize::try_from(offset)?;
+        let position = match (preceding, is_start) {
+            (true, true) => i.saturating_sub(offset),
+            (true, false) => (i + 1).saturating_sub(offset),
+            (false, true) => i.saturating_add(offset),
+            (false, false) => i.saturating_add(offset).saturating_add(1),
+        };
+
+        return Ok(position.min(len));
+    }
+
+    let (value, asc) = &rows[i].order[0];
+    if value.is_null() {
+        return Ok(if is_start { peer_start[i] } else { peer_end[i] });
+    }
+
+    let target = match (preceding, asc.unwrap_or(true)) {
+        (true, true) | (false, false) => value.subtract(offset)?,
+        (true, false) | (false, true) => value.add(offset)?,
+    };
+    let target = [(target, *asc)];
+
+    // rows of the partition are sorted, so the frame ends are found by binary search
+    let position = rows.partition_point(|row| {
+        let ordering = Sort::sort_by(&row.order, &target);
+
+        match is_start {
+            true => ordering == Ordering::Less,
+            false => ordering != Ordering::Greater,
+        }
+    });
+
+    Ok(position)
+}
+
+/// Aggregates the frames of all rows in a single pass. Both ends of the frames move forward
+/// as the rows are sorted, so the values entering a frame are pushed to the queue and the
+/// values leaving it are popped. With `DISTINCT`, the values seen so far are kept instead,
+/// which holds only while the start of the frame stays, so the frame is aggregated again
+/// once it moves.
+fn aggregate(
+    aggr: &Aggregate,
+    rows: &[&WindowRow],
+    bounds: &[(usize, usize)],
+) -> Result<Vec<Value>> {
+    let mut queue = AggrQueue::new(aggr);
+    let mut distinct_values = HashSet::new();
+    let (mut start, mut end) = (0, 0);
+
+    bounds
+        .iter()
+        .map(|&(frame_start, frame_end)| {
+            let moved_back = frame_start < start || frame_end < end;
+
+            if moved_back || (aggr.distinct && frame_start != start) {
+                queue = AggrQueue::new(aggr);
+                distinct_values.clear();
+                start = frame_start;
+                end = frame_start;
+            }
+
+            for row in &rows[end..frame_end] {
+                let value = &row.args[0];
+
+                if !aggr.distinct || distinct_values.insert(Key::try_from(value)?) {
+                    queue.push(value)?;
+                }
+            }
+            end = frame_end;
+
+            for _ in start..frame_start {
+                queue.pop()?;
+            }
+            start = frame_start;
+
+            queue.export()
+        })
+        .collect()
+}
+
+/// Queue of the values in a frame which keeps their aggregate in amortized constant time.
+/// Pushed values are accumulated at the back, and popping moves them to the front as the
+/// aggregates of each suffix, so no value has to be removed from an aggregate.
+struct AggrQueue<'a> {
+    aggr: &'a Aggregate,
+    front: Vec<AggrValue>,
+    back: Vec<&'a Value>,
+    back_value: Option<AggrValue>,
+}
+
+impl<'a> AggrQueue<'a> {
+    fn new(aggr: &'a Aggregate) -> Self {
+        Self {
+            aggr,
+            front: Vec::new(),
+            back: Vec::new(),
+            back_value: None,
+        }
+    }
+
+    fn push(&mut self, value: &'a Value) -> Result<()> {
+        let back_value = match self.back_value.take() {
+            Some(back_value) => back_value.accumulate(value)?.unwrap_or(back_value),
+            None => AggrValue::new(self.aggr, value)?,
+        };
+
+        self.back.push(value);
+        self.back_value = Some(back_value);
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<()> {
+        if self.front.is_empty() {
+            let mut suffix: Option<AggrValue> = None;
+
+            for value in self.back.drain(..).rev() {
+                let aggr_value = AggrValue::new(self.aggr, value)?;
+                let aggr_value = match suffix {
+                    Some(suffix) => aggr_value.merge(&suffix)?,
+                    None => aggr_value,
+                };
+
+                self.front.push(aggr_value.clone());
+                suffix = Some(aggr_value);
+            }
+
+            self.back_value = None;
+        }
+
+        self.front.pop();
+
+        Ok(())
+    }
+
+    fn export(&self) -> Result<Value> {
+        let aggr_value = match (self.front.last(), &self.back_value) {
+            (Some(front_value), Some(back_value)) => Some(front_value.merge(back_value)?),
+            (Some(aggr_value), None) | (None, Some(aggr_value)) => Some(aggr_value.clone()),
+            (None, None) => None,
+        };
+
+        match (aggr_value, &self.aggr.func) {
+            (Some(aggr_value), _) => aggr_value.export(),
+            (None, AggregateFunction::Count(_)) => Ok(Value::I64(0)),
+            (None, _) => Ok(Value::Null),
+        }
+    }
+}
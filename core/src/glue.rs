@@ -1,12 +1,12 @@
 use {
     crate::{
         ast::Statement,
-        executor::{execute, Payload},
-        parse_sql::parse,
-        plan::plan,
+        data::Value,
+        executor::{execute, stream_query, ExecuteOptions, Payload, PayloadStream},
+        plan::{plan, prepare, PlanError, Prepared},
         result::Result,
         store::{GStore, GStoreMut},
-        translate::translate,
+        translate::translate_sql,
     },
     futures::{
         executor::block_on,
@@ -15,40 +15,83 @@ use {
     },
 };
 
+#[cfg(feature = "transaction")]
+use {
+    crate::{ast::Query, store::Transaction},
+    futures::{
+        channel::{mpsc, oneshot},
+        future, SinkExt,
+    },
+};
+
 pub struct Glue<T: GStore + GStoreMut> {
     pub storage: Option<T>,
+    options: ExecuteOptions,
+    /// Set when the rows of a streamed `SELECT` are dropped before they are all read, its
+    /// autocommit transaction is then rolled back by the next statement
+    #[cfg(feature = "transaction")]
+    abandoned: bool,
 }
 
 impl<T: GStore + GStoreMut> Glue<T> {
     pub fn new(storage: T) -> Self {
         Self {
             storage: Some(storage),
+            options: ExecuteOptions::default(),
+            #[cfg(feature = "transaction")]
+            abandoned: false,
         }
     }
 
+    /// Sets how many times the recursive term of a `WITH RECURSIVE` query may be evaluated
+    /// before `SelectError::RecursionDepthExceeded` is returned.
+    pub fn set_max_recursion_depth(&mut self, depth: usize) {
+        self.options.max_recursion_depth = depth;
+    }
+
     pub async fn plan<Sql: AsRef<str>>(&self, sql: Sql) -> Result<Vec<Statement>> {
-        let parsed = parse(sql)?;
+        let statements = translate_sql(sql)?;
         let storage = self.storage.as_ref().unwrap();
-        stream::iter(parsed)
-            .map(|p| translate(&p))
-            .then(|statement| async move { plan(storage, statement?).await })
+        stream::iter(statements)
+            .then(|statement| async move { plan(storage, statement).await })
             .try_collect()
             .await
     }
 
+    /// Prepares and plans a single statement which contains bind parameters, `$1, $2, ..`
+    /// or `?`, it is executed through `PreparedStatement::execute`.
+    pub fn prepare<Sql: AsRef<str>>(&self, sql: Sql) -> Result<PreparedStatement> {
+        block_on(self.prepare_async(sql))
+    }
+
+    pub async fn prepare_async<Sql: AsRef<str>>(&self, sql: Sql) -> Result<PreparedStatement> {
+        let mut statements = translate_sql(sql)?;
+        if statements.len() != 1 {
+            return Err(PlanError::MultipleStatementsPrepared(statements.len()).into());
+        }
+
+        let statement = statements.remove(0);
+        let storage = self.storage.as_ref().unwrap();
+        let prepared = prepare(storage, statement).await?;
+
+        Ok(PreparedStatement { prepared })
+    }
+
     pub fn execute_stmt(&mut self, statement: &Statement) -> Result<Payload> {
         block_on(self.execute_stmt_async(statement))
     }
 
     pub fn execute<Sql: AsRef<str>>(&mut self, sql: Sql) -> Result<Vec<Payload>> {
-        let statements = block_on(self.plan(sql))?;
-        statements.iter().map(|s| self.execute_stmt(s)).collect()
+        block_on(self.execute_async(sql))
     }
 
     pub async fn execute_stmt_async(&mut self, statement: &Statement) -> Result<Payload> {
+        #[cfg(feature = "transaction")]
+        self.rollback_abandoned().await?;
+
         let storage = self.storage.take().unwrap();
 
-        match execute(storage, statement).await {
+        match execute(storage, statement, self.options).await {
             Ok((storage, payload)) => {
                 self.storage = Some(storage);
 
@@ -62,14 +105,213 @@ impl<T: GStore + GStoreMut> Glue<T> {
         }
     }
 
+    /// Each statement is planned right before it is executed, so that it sees the tables,
+    /// indexes and views created by the preceding statements.
     pub async fn execute_async<Sql: AsRef<str>>(&mut self, sql: Sql) -> Result<Vec<Payload>> {
-        let statements = self.plan(sql).await?;
+        let statements = translate_sql(sql)?;
         let mut payloads = Vec::<Payload>::new();
-        for statement in statements.iter() {
-            let payload = self.execute_stmt_async(statement).await?;
+        for statement in statements {
+            let storage = self.storage.as_ref().unwrap();
+            let statement = plan(storage, statement).await?;
+            let payload = self.execute_stmt_async(&statement).await?;
             payloads.push(payload);
         }
 
         Ok(payloads)
     }
+
+    /// Executes a planned statement, the rows of a `SELECT` are streamed from the storage
+    /// instead of being collected into `Payload::Select`, the other statements are executed
+    /// as usual. The autocommit transaction of the `SELECT` is committed once all of its rows
+    /// are read. When the rows are dropped before, it is rolled back by the next statement
+    /// executed through `Glue`.
+    pub async fn execute_stream<'a>(
+        &'a mut self,
+        statement: &'a Statement,
+    ) -> Result<PayloadStream<'a>> {
+        let query = match statement {
+            Statement::Query(query) => query,
+            _ => {
+                return self
+                    .execute_stmt_async(statement)
+                    .await
+                    .map(PayloadStream::Payload);
+            }
+        };
+
+        #[cfg(feature = "transaction")]
+        {
+            self.rollback_abandoned().await?;
+
+            let storage = self.storage.take().unwrap();
+            let (storage, autocommit) = match storage.begin(true).await {
+                Ok((storage, autocommit)) => (storage, Ok(autocommit)),
+                Err((storage, error)) => (storage, Err(error)),
+            };
+
+            self.storage = Some(storage);
+            if autocommit? {
+                return stream_in_transaction(
+                    &mut self.storage,
+                    &mut self.abandoned,
+                    query,
+                    self.options,
+                )
+                .await;
+            }
+        }
+
+        let storage = self.storage.as_ref().unwrap();
+        let (labels, rows) = stream_query(storage, query, self.options).await?;
+
+        Ok(PayloadStream::Select { labels, rows })
+    }
+
+    /// Rolls back the autocommit transaction of the streamed `SELECT` whose rows were dropped
+    /// before they were all read.
+    #[cfg(feature = "transaction")]
+    async fn rollback_abandoned(&mut self) -> Result<()> {
+        if !self.abandoned {
+            return Ok(());
+        }
+
+        self.abandoned = false;
+
+        let storage = self.storage.take().unwrap();
+        let (storage, result) = match storage.rollback().await {
+            Ok((storage, ())) => (storage, Ok(())),
+            Err((storage, error)) => (storage, Err(error)),
+        };
+        self.storage = Some(storage);
+
+        result
+    }
+}
+
+/// Autocommit transaction of a streamed `SELECT`, which is marked as abandoned when it is
+/// dropped before all the rows are read. It cannot be rolled back right away, as the drop
+/// happens while the caller is polling its own future.
+#[cfg(feature = "transaction")]
+struct StreamTransaction<'a, T: GStore + GStoreMut> {
+    storage: &'a mut Option<T>,
+    abandoned: &'a mut bool,
+    committed: bool,
+}
+
+#[cfg(feature = "transaction")]
+impl<T: GStore + GStoreMut> StreamTransaction<'_, T> {
+    async fn commit(&mut self) -> Result<()> {
+        self.committed = true;
+
+        let storage = self.storage.take().unwrap();
+        let (storage, result) = match storage.commit().await {
+            Ok((storage, ())) => (storage, Ok(())),
+            Err((storage, error)) => (storage, Err(error)),
+        };
+        *self.storage = Some(storage);
+
+        result
+    }
+}
+
+#[cfg(feature = "transaction")]
+impl<T: GStore + GStoreMut> Drop for StreamTransaction<'_, T> {
+    fn drop(&mut self) {
+        if !self.committed {
+            *self.abandoned = true;
+        }
+    }
+}
+
+/// Streams the rows of the query in the autocommit transaction begun for it. The rows are
+/// read by a future which owns the transaction and passes them on through a channel, so
+/// the transaction is committed once the rows are exhausted and abandoned once they are
+/// dropped.
+#[cfg(feature = "transaction")]
+async fn stream_in_transaction<'a, T: GStore + GStoreMut>(
+    storage: &'a mut Option<T>,
+    abandoned: &'a mut bool,
+    query: &'a Query,
+    options: ExecuteOptions,
+) -> Result<PayloadStream<'a>> {
+    let (labels_sender, labels_receiver) = oneshot::channel();
+    let (mut rows_sender, rows_receiver) = mpsc::channel(0);
+
+    let reader = async move {
+        let mut transaction = StreamTransaction {
+            storage,
+            abandoned,
+            committed: false,
+        };
+
+        {
+            let storage = transaction.storage.as_ref().unwrap();
+            let (labels, mut rows) = match stream_query(storage, query, options).await {
+                Ok(selected) => selected,
+                Err(error) => {
+                    let _ = labels_sender.send(Err(error));
+
+                    return;
+                }
+            };
+
+            if labels_sender.send(Ok(labels)).is_err() {
+                return;
+            }
+
+            while let Some(row) = rows.next().await {
+                if rows_sender.send(row).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        if let Err(error) = transaction.commit().await {
+            let _ = rows_sender.send(Err(error)).await;
+        }
+    };
+
+    // the reader yields no rows itself, it only drives the rows into the channel
+    let reader = stream::once(reader).filter_map(|()| future::ready(None));
+    let mut rows = Box::pin(stream::select(reader, rows_receiver).peekable());
+
+    // labels are sent before the first row
+    rows.as_mut().peek().await;
+    let labels = labels_receiver
+        .await
+        .expect("labels are sent before the reader ends")?;
+
+    Ok(PayloadStream::Select { labels, rows })
+}
+
+/// Statement planned by `Glue::prepare`, which can be executed many times with different
+/// parameters.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    prepared: Prepared,
+}
+
+impl PreparedStatement {
+    pub fn execute<T: GStore + GStoreMut>(
+        &mut self,
+        glue: &mut Glue<T>,
+        params: &[Value],
+    ) -> Result<Payload> {
+        block_on(self.execute_async(glue, params))
+    }
+
+    /// Binds `params` to the planned statement, which is planned again only when a schema or
+    /// a view it reads has changed since it was planned.
+    pub async fn execute_async<T: GStore + GStoreMut>(
+        &mut self,
+        glue: &mut Glue<T>,
+        params: &[Value],
+    ) -> Result<Payload> {
+        let storage = glue.storage.as_ref().unwrap();
+        self.prepared.replan(storage).await?;
+
+        let statement = self.prepared.bind(params)?;
+
+        glue.execute_stmt_async(&statement).await
+    }
 }
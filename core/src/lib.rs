@@ -20,10 +20,10 @@ pub mod prelude {
     pub use crate::{
         ast::DataType,
         data::{Key, Row, Value},
-        executor::{execute, Payload, PayloadVariable},
-        glue::Glue,
+        executor::{execute, ExplainNode, Payload, PayloadStream, PayloadVariable},
+        glue::{Glue, PreparedStatement},
         parse_sql::parse,
         plan::plan,
-        translate::translate,
+        translate::{translate, translate_sql},
     };
 }
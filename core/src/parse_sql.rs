@@ -2,20 +2,465 @@ use {
     crate::result::{Error, Result},
     sqlparser::{
         ast::{
-            Assignment as SqlAssignment, ColumnDef as SqlColumnDef, DataType as SqlDataType,
-            Expr as SqlExpr, Ident as SqlIdent, OrderByExpr as SqlOrderByExpr, Query as SqlQuery,
+            Assignment as SqlAssignment, ColumnDef as SqlColumnDef,
+            ColumnOption as SqlColumnOption, ColumnOptionDef as SqlColumnOptionDef,
+            DataType as SqlDataType, Expr as SqlExpr, Ident as SqlIdent,
+            ObjectName as SqlObjectName, OrderByExpr as SqlOrderByExpr, Query as SqlQuery,
             SelectItem as SqlSelectItem, Statement as SqlStatement,
         },
         dialect::GenericDialect,
-        parser::Parser,
-        tokenizer::Tokenizer,
+        keywords::Keyword,
+        parser::{Parser, ParserError},
+        tokenizer::{Token, Tokenizer, Word},
     },
+    std::{ops::Range, result::Result as StdResult},
 };
 
 const DIALECT: GenericDialect = GenericDialect {};
 
 pub fn parse<Sql: AsRef<str>>(sql: Sql) -> Result<Vec<SqlStatement>> {
-    Parser::parse_sql(&DIALECT, sql.as_ref()).map_err(|e| Error::Parser(format!("{:#?}", e)))
+    parse_with(sql, parse_statement)
+}
+
+/// Statement parsed by [`parse_extended`].
+///
+/// `sql-parser` does not parse `CREATE SEQUENCE`, `DROP SEQUENCE` and
+/// `OVERRIDING SYSTEM VALUE` of `INSERT` yet, so these are parsed here and kept apart
+/// from the other statements.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParsedStatement {
+    Sql(SqlStatement),
+    /// `INSERT` of which `OVERRIDING SYSTEM VALUE` is taken out of the statement
+    InsertOverridingSystemValue(SqlStatement),
+    CreateSequence {
+        if_not_exists: bool,
+        name: SqlObjectName,
+        increment: Option<SqlExpr>,
+        start: Option<SqlExpr>,
+    },
+    DropSequence {
+        if_exists: bool,
+        names: Vec<SqlObjectName>,
+    },
+}
+
+/// Parses the statements of [`parse`] along with the sequence statements.
+pub(crate) fn parse_extended<Sql: AsRef<str>>(sql: Sql) -> Result<Vec<ParsedStatement>> {
+    parse_with(sql, parse_extended_statement)
+}
+
+fn parse_with<Sql: AsRef<str>, T>(
+    sql: Sql,
+    parse_statement: fn(&mut Parser) -> StdResult<T, ParserError>,
+) -> Result<Vec<T>> {
+    let parser_error = |e| Error::Parser(format!("{:#?}", e));
+    let tokens = Tokenizer::new(&DIALECT, sql.as_ref())
+        .tokenize()
+        .map_err(|e| Error::Parser(format!("{:#?}", e)))?;
+    let mut parser = Parser::new(tokens, &DIALECT);
+    let mut statements = Vec::new();
+    let mut expecting_statement_delimiter = false;
+
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+
+        let token = parser.peek_token();
+        if token == Token::EOF {
+            break;
+        } else if expecting_statement_delimiter {
+            let message = format!("Expected end of statement, found: {token}");
+
+            return Err(parser_error(ParserError::ParserError(message)));
+        }
+
+        statements.push(parse_statement(&mut parser).map_err(parser_error)?);
+        expecting_statement_delimiter = true;
+    }
+
+    Ok(statements)
+}
+
+fn parse_extended_statement(parser: &mut Parser) -> StdResult<ParsedStatement, ParserError> {
+    if parser.parse_keyword(Keyword::CREATE) {
+        if parse_word(parser, "SEQUENCE") {
+            return parse_create_sequence(parser);
+        }
+
+        parser.prev_token();
+    } else if parser.parse_keyword(Keyword::DROP) {
+        if parse_word(parser, "SEQUENCE") {
+            return parse_drop_sequence(parser);
+        }
+
+        parser.prev_token();
+    } else if parser.parse_keyword(Keyword::INSERT) {
+        parser.prev_token();
+
+        return parse_insert(parser);
+    }
+
+    parse_statement(parser).map(ParsedStatement::Sql)
+}
+
+/// Parses `INSERT` after taking `OVERRIDING SYSTEM VALUE`, which may only follow the table
+/// name and its column list, out of it.
+fn parse_insert(parser: &mut Parser) -> StdResult<ParsedStatement, ParserError> {
+    let tokens = peek_statement(parser);
+    if !tokens
+        .iter()
+        .any(|token| is_token_word(token, "OVERRIDING"))
+    {
+        return parser.parse_statement().map(ParsedStatement::Sql);
+    }
+
+    let position = probe(&tokens, |parser| {
+        parser.expect_keyword(Keyword::INSERT)?;
+        parser.parse_keyword(Keyword::INTO);
+        parser.parse_object_name()?;
+
+        if parser.consume_token(&Token::LParen) {
+            parser.parse_comma_separated(Parser::parse_identifier)?;
+            parser.expect_token(&Token::RParen)?;
+        }
+
+        Ok(())
+    });
+
+    let overriding_system_value =
+        position
+            .ok()
+            .and_then(|((), position)| match tokens.get(position..position + 3)? {
+                [overriding, system, value]
+                    if is_token_word(overriding, "OVERRIDING")
+                        && is_token_word(system, "SYSTEM")
+                        && is_token_word(value, "VALUE") =>
+                {
+                    Some(position)
+                }
+                _ => None,
+            });
+
+    match overriding_system_value {
+        Some(position) => {
+            let len = tokens.len();
+            let tokens = [&tokens[..position], &tokens[position + 3..]].concat();
+
+            parse_replaced(parser, len, tokens).map(ParsedStatement::InsertOverridingSystemValue)
+        }
+        None => parser.parse_statement().map(ParsedStatement::Sql),
+    }
+}
+
+fn parse_statement(parser: &mut Parser) -> StdResult<SqlStatement, ParserError> {
+    if parser.parse_keywords(&[Keyword::CREATE, Keyword::TABLE]) {
+        parser.prev_token();
+        parser.prev_token();
+
+        return parse_create_table(parser);
+    }
+
+    parser.parse_statement()
+}
+
+/// Consumes the next token if it is the given word, which is not a keyword of `sql-parser`.
+fn parse_word(parser: &mut Parser, word: &str) -> bool {
+    match parser.peek_token() {
+        Token::Word(w) if is_word(&w, word) => {
+            parser.next_token();
+
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Tokens of the statement the parser is at, up to the `;` which ends it. The parser is left
+/// where it was.
+fn peek_statement(parser: &mut Parser) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    while !matches!(parser.peek_token(), Token::SemiColon | Token::EOF) {
+        tokens.push(parser.next_token());
+    }
+
+    for _ in 0..tokens.len() {
+        parser.prev_token();
+    }
+
+    tokens
+}
+
+/// Runs `parse` on `tokens` and returns its output along with the number of tokens it read.
+fn probe<T>(
+    tokens: &[Token],
+    parse: impl FnOnce(&mut Parser) -> StdResult<T, ParserError>,
+) -> StdResult<(T, usize), ParserError> {
+    let mut parser = Parser::new(tokens.to_vec(), &DIALECT);
+    let output = parse(&mut parser)?;
+
+    let mut rest = 0;
+    while parser.next_token() != Token::EOF {
+        rest += 1;
+    }
+
+    Ok((output, tokens.len() - rest))
+}
+
+/// Parses `tokens` as the statement the parser is at, which is made of `len` tokens.
+fn parse_replaced(
+    parser: &mut Parser,
+    len: usize,
+    tokens: Vec<Token>,
+) -> StdResult<SqlStatement, ParserError> {
+    let mut replaced_parser = Parser::new(tokens, &DIALECT);
+    let statement = replaced_parser.parse_statement()?;
+    let token = replaced_parser.peek_token();
+    if token != Token::EOF {
+        let message = format!("Expected end of statement, found: {token}");
+
+        return Err(ParserError::ParserError(message));
+    }
+
+    for _ in 0..len {
+        parser.next_token();
+    }
+
+    Ok(statement)
+}
+
+/// Parses `CREATE TABLE` after taking `GENERATED` column options, which `sql-parser` does not
+/// parse yet, out of it. Each option is looked for only where the options of a column are,
+/// after its data type, and is put back to its column as a `DialectSpecific` option read by
+/// [`parse_generated_option`].
+fn parse_create_table(parser: &mut Parser) -> StdResult<SqlStatement, ParserError> {
+    let tokens = peek_statement(parser);
+    if !tokens.iter().any(|token| is_token_word(token, "GENERATED")) {
+        return parser.parse_statement();
+    }
+
+    let generated_options = find_generated_options(&tokens)?;
+    if generated_options.is_empty() {
+        return parser.parse_statement();
+    }
+
+    let len = tokens.len();
+    let mut position = 0;
+    let mut replaced_tokens = Vec::new();
+    for (_, range) in generated_options.iter() {
+        replaced_tokens.extend_from_slice(&tokens[position..range.start]);
+        position = range.end;
+    }
+    replaced_tokens.extend_from_slice(&tokens[position..]);
+
+    let mut statement = parse_replaced(parser, len, replaced_tokens)?;
+    for (column, range) in generated_options {
+        let column_def = match &mut statement {
+            SqlStatement::CreateTable { columns, .. } => columns
+                .iter_mut()
+                .find(|SqlColumnDef { name, .. }| *name == column),
+            _ => None,
+        };
+
+        match column_def {
+            Some(SqlColumnDef { options, .. }) => options.push(SqlColumnOptionDef {
+                name: None,
+                option: SqlColumnOption::DialectSpecific(tokens[range].to_vec()),
+            }),
+            None => {
+                let message = format!("Expected column definition, found: {column}");
+
+                return Err(ParserError::ParserError(message));
+            }
+        }
+    }
+
+    Ok(statement)
+}
+
+/// Finds the `GENERATED` options of the columns of `CREATE TABLE` along with the range of
+/// their tokens.
+fn find_generated_options(
+    tokens: &[Token],
+) -> StdResult<Vec<(SqlIdent, Range<usize>)>, ParserError> {
+    let mut generated_options = Vec::new();
+    let (has_columns, mut position) = probe(tokens, |parser| {
+        parser.expect_keywords(&[Keyword::CREATE, Keyword::TABLE])?;
+        parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        parser.parse_object_name()?;
+
+        Ok(parser.consume_token(&Token::LParen))
+    })?;
+
+    if !has_columns {
+        return Ok(generated_options);
+    }
+
+    loop {
+        let (column, len) = probe(&tokens[position..], |parser| {
+            if parser.parse_optional_table_constraint()?.is_some() {
+                return Ok(None);
+            }
+
+            let name = parser.parse_identifier()?;
+            parser.parse_data_type()?;
+            if parser.parse_keyword(Keyword::COLLATE) {
+                parser.parse_object_name()?;
+            }
+
+            Ok(Some(name))
+        })?;
+        position += len;
+
+        if let Some(column) = column {
+            loop {
+                if matches!(tokens.get(position), Some(token) if is_token_word(token, "GENERATED"))
+                {
+                    let ((), len) = probe(&tokens[position..], parse_generated_tokens)?;
+                    generated_options.push((column.clone(), position..position + len));
+                    position += len;
+
+                    continue;
+                }
+
+                let (option, len) = probe(&tokens[position..], |parser| {
+                    if parser.parse_keyword(Keyword::CONSTRAINT) {
+                        parser.parse_identifier()?;
+                    }
+
+                    parser.parse_optional_column_option()
+                })?;
+                position += len;
+
+                if option.is_none() {
+                    break;
+                }
+            }
+        }
+
+        match tokens.get(position) {
+            Some(Token::Comma) => position += 1,
+            _ => return Ok(generated_options),
+        }
+    }
+}
+
+/// Reads `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY` or
+/// `GENERATED ALWAYS AS ( <expr> ) STORED`.
+fn parse_generated_tokens(parser: &mut Parser) -> StdResult<(), ParserError> {
+    let mut tokens = vec![parser.next_token()];
+    let mut depth = 0;
+
+    loop {
+        let token = parser.next_token();
+        match token {
+            Token::EOF => return parser.expected("IDENTITY or STORED", Token::EOF),
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            _ => {}
+        }
+
+        let end =
+            depth == 0 && (is_token_word(&token, "IDENTITY") || is_token_word(&token, "STORED"));
+        tokens.push(token);
+
+        if end {
+            break;
+        }
+    }
+
+    parse_generated_option(&tokens).map(|_| ())
+}
+
+/// Column option kept as `DialectSpecific` tokens by [`parse`]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GeneratedOption {
+    /// `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY`
+    Identity { always: bool },
+    /// `GENERATED ALWAYS AS ( <expr> ) STORED`
+    Stored(SqlExpr),
+}
+
+/// Reads the tokens of a `GENERATED` column option, `None` if they are not one.
+pub(crate) fn parse_generated_option(
+    tokens: &[Token],
+) -> StdResult<Option<GeneratedOption>, ParserError> {
+    let mut parser = Parser::new(tokens.to_vec(), &DIALECT);
+    if !parse_word(&mut parser, "GENERATED") {
+        return Ok(None);
+    }
+
+    let always = if parse_word(&mut parser, "ALWAYS") {
+        true
+    } else if parser.parse_keywords(&[Keyword::BY, Keyword::DEFAULT]) {
+        false
+    } else {
+        return parser.expected("ALWAYS or BY DEFAULT", parser.peek_token());
+    };
+    parser.expect_keyword(Keyword::AS)?;
+
+    let option = if parse_word(&mut parser, "IDENTITY") {
+        GeneratedOption::Identity { always }
+    } else if always {
+        parser.expect_token(&Token::LParen)?;
+        let expr = parser.parse_expr()?;
+        parser.expect_token(&Token::RParen)?;
+
+        match parse_word(&mut parser, "STORED") {
+            true => GeneratedOption::Stored(expr),
+            false => return parser.expected("STORED", parser.peek_token()),
+        }
+    } else {
+        return parser.expected("IDENTITY", parser.peek_token());
+    };
+
+    match parser.peek_token() {
+        Token::EOF => Ok(Some(option)),
+        token => parser.expected("end of column option", token),
+    }
+}
+
+fn is_word(w: &Word, word: &str) -> bool {
+    w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word)
+}
+
+fn is_token_word(token: &Token, word: &str) -> bool {
+    matches!(token, Token::Word(w) if is_word(w, word))
+}
+
+/// `CREATE SEQUENCE [ IF NOT EXISTS ] <name> [ INCREMENT [ BY ] <expr> ] [ START [ WITH ] <expr> ]`
+fn parse_create_sequence(parser: &mut Parser) -> StdResult<ParsedStatement, ParserError> {
+    let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let name = parser.parse_object_name()?;
+    let mut increment = None;
+    let mut start = None;
+
+    loop {
+        if parse_word(parser, "INCREMENT") {
+            parser.parse_keyword(Keyword::BY);
+            increment = Some(parser.parse_expr()?);
+        } else if parse_word(parser, "START") {
+            parser.parse_keyword(Keyword::WITH);
+            start = Some(parser.parse_expr()?);
+        } else {
+            break;
+        }
+    }
+
+    Ok(ParsedStatement::CreateSequence {
+        if_not_exists,
+        name,
+        increment,
+        start,
+    })
+}
+
+/// `DROP SEQUENCE [ IF EXISTS ] <name> [, ...]`
+fn parse_drop_sequence(parser: &mut Parser) -> StdResult<ParsedStatement, ParserError> {
+    let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+    let names = parser.parse_comma_separated(Parser::parse_object_name)?;
+
+    Ok(ParsedStatement::DropSequence { if_exists, names })
 }
 
 macro_rules! generate_parse_fn {
@@ -0,0 +1,298 @@
+use crate::ast::{
+    Cte, Expr, Join, JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr, Statement,
+    TableAlias, TableFactor, TableWithJoins, Values, With,
+};
+
+/// Turns the common table expression which the query reads only once, from its own `FROM`
+/// clause, into a derived table. Its rows are then streamed into the query instead of being
+/// collected before the query runs.
+pub fn plan(statement: Statement) -> Statement {
+    match statement {
+        Statement::Query(query) => Statement::Query(plan_query(query)),
+        _ => statement,
+    }
+}
+
+fn plan_query(mut query: Query) -> Query {
+    let index = match inlined_cte(&query) {
+        Some(index) => index,
+        None => return query,
+    };
+
+    if let (Some(With { cte_tables, .. }), SetExpr::Select(select)) =
+        (&mut query.with, &mut query.body)
+    {
+        let Cte {
+            alias: TableAlias { name, .. },
+            query: subquery,
+        } = cte_tables.remove(index);
+        let relation = &mut select.from.relation;
+        let alias = match &mut *relation {
+            TableFactor::Table { alias, .. } => alias.take(),
+            _ => None,
+        };
+
+        *relation = TableFactor::Derived {
+            subquery,
+            alias: alias.unwrap_or(TableAlias {
+                name,
+                columns: Vec::new(),
+            }),
+        };
+    }
+
+    if matches!(&query.with, Some(With { cte_tables, .. }) if cte_tables.is_empty()) {
+        query.with = None;
+    }
+
+    query
+}
+
+/// Common table expression read by the `FROM` clause of the query, unless it is read anywhere
+/// else too. Its column aliases are taken only when it is materialized, and it must not refer
+/// to itself or to the ones defined after it, which it would see where it is inlined.
+fn inlined_cte(query: &Query) -> Option<usize> {
+    let With { cte_tables, .. } = query.with.as_ref()?;
+    let name = match &query.body {
+        SetExpr::Select(select) => match &select.from.relation {
+            TableFactor::Table { name, alias, .. }
+                if alias
+                    .as_ref()
+                    .map_or(true, |alias| alias.columns.is_empty()) =>
+            {
+                name
+            }
+            _ => return None,
+        },
+        SetExpr::Values(_) | SetExpr::SetOperation { .. } => return None,
+    };
+
+    let index = cte_tables
+        .iter()
+        .rposition(|Cte { alias, .. }| &alias.name == name)?;
+    let Cte { alias, query: cte } = &cte_tables[index];
+
+    let inlined = alias.columns.is_empty()
+        && count_query(query, name) == 1
+        && cte_tables[index..]
+            .iter()
+            .all(|Cte { alias, .. }| count_query(cte, &alias.name) == 0);
+
+    inlined.then_some(index)
+}
+
+/// Counts the tables of the name the query reads, in its subqueries too.
+fn count_query(query: &Query, name: &str) -> usize {
+    let Query {
+        with,
+        body,
+        order_by,
+        limit,
+        offset,
+    } = query;
+
+    let cte_tables = with
+        .iter()
+        .flat_map(|With { cte_tables, .. }| cte_tables)
+        .map(|Cte { query, .. }| count_query(query, name))
+        .sum::<usize>();
+    let exprs = order_by
+        .iter()
+        .map(|order_by_expr| &order_by_expr.expr)
+        .chain(limit.iter())
+        .chain(offset.iter())
+        .map(|expr| count_expr(expr, name))
+        .sum::<usize>();
+
+    cte_tables + count_set_expr(body, name) + exprs
+}
+
+fn count_set_expr(set_expr: &SetExpr, name: &str) -> usize {
+    match set_expr {
+        SetExpr::Select(select) => count_select(select, name),
+        SetExpr::Values(Values(rows)) => rows
+            .iter()
+            .flatten()
+            .map(|expr| count_expr(expr, name))
+            .sum(),
+        SetExpr::SetOperation { left, right, .. } => {
+            count_set_expr(left, name) + count_set_expr(right, name)
+        }
+    }
+}
+
+fn count_select(select: &Select, name: &str) -> usize {
+    let Select {
+        projection,
+        from: TableWithJoins { relation, joins },
+        selection,
+        group_by,
+        having,
+        ..
+    } = select;
+
+    let projection = projection
+        .iter()
+        .map(|item| match item {
+            SelectItem::Expr { expr, .. } => count_expr(expr, name),
+            SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => 0,
+        })
+        .sum::<usize>();
+    let joins = joins
+        .iter()
+        .map(|join| count_join(join, name))
+        .sum::<usize>();
+    let exprs = selection
+        .iter()
+        .chain(group_by.iter())
+        .chain(having.iter())
+        .map(|expr| count_expr(expr, name))
+        .sum::<usize>();
+
+    projection + count_table_factor(relation, name) + joins + exprs
+}
+
+fn count_join(join: &Join, name: &str) -> usize {
+    let Join {
+        relation,
+        join_operator,
+        ..
+    } = join;
+
+    let constraint = match join_operator {
+        JoinOperator::Inner(JoinConstraint::On(expr))
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr))
+        | JoinOperator::Semi(JoinConstraint::On(expr))
+        | JoinOperator::Anti(JoinConstraint::On(expr)) => count_expr(expr, name),
+        JoinOperator::Inner(_)
+        | JoinOperator::LeftOuter(_)
+        | JoinOperator::RightOuter(_)
+        | JoinOperator::FullOuter(_)
+        | JoinOperator::Semi(_)
+        | JoinOperator::Anti(_)
+        | JoinOperator::Cross => 0,
+    };
+
+    count_table_factor(relation, name) + constraint
+}
+
+fn count_table_factor(table_factor: &TableFactor, name: &str) -> usize {
+    match table_factor {
+        TableFactor::Table {
+            name: table_name, ..
+        } => usize::from(table_name == name),
+        TableFactor::Derived { subquery, .. } => count_query(subquery, name),
+        TableFactor::Series { size, .. } => count_expr(size, name),
+        TableFactor::Dictionary { .. } => 0,
+    }
+}
+
+fn count_expr(expr: &Expr, name: &str) -> usize {
+    let count = |expr: &Expr| count_expr(expr, name);
+
+    match expr {
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier { .. }
+        | Expr::Literal(_)
+        | Expr::Placeholder(_)
+        | Expr::TypedString { .. } => 0,
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Interval { expr, .. } => count(expr),
+        Expr::InList { expr, list, .. } => count(expr) + list.iter().map(count).sum::<usize>(),
+        Expr::InSubquery { expr, subquery, .. } => count(expr) + count_query(subquery, name),
+        Expr::Between {
+            expr, low, high, ..
+        } => count(expr) + count(low) + count(high),
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            count(expr) + count(pattern)
+        }
+        Expr::BinaryOp { left, right, .. } => count(left) + count(right),
+        Expr::Function(function) => function.as_exprs().map(count).sum(),
+        Expr::Aggregate(aggregate) => aggregate.as_expr().map_or(0, count),
+        Expr::Window(window) => window.as_exprs().map(count).sum(),
+        Expr::Exists { subquery, .. } | Expr::Subquery { subquery, .. } => {
+            count_query(subquery, name)
+        }
+        Expr::Case {
+            operand,
+            when_then,
+            else_result,
+        } => {
+            operand.as_deref().map_or(0, count)
+                + when_then
+                    .iter()
+                    .map(|(when, then)| count(when) + count(then))
+                    .sum::<usize>()
+                + else_result.as_deref().map_or(0, count)
+        }
+        Expr::ArrayIndex { obj, indexes } => count(obj) + indexes.iter().map(count).sum::<usize>(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::plan,
+        crate::{ast::Statement, parse_sql::parse, translate::translate},
+    };
+
+    fn parse_statement(sql: &str) -> Statement {
+        let parsed = parse(sql).unwrap().remove(0);
+
+        translate(&parsed).unwrap()
+    }
+
+    #[test]
+    fn inline() {
+        let sql = "WITH Fruit AS (SELECT id FROM Item) SELECT id FROM Fruit";
+        let expected = "SELECT id FROM (SELECT id FROM Item) AS Fruit";
+        assert_eq!(
+            plan(parse_statement(sql)),
+            parse_statement(expected),
+            "read once"
+        );
+
+        let sql = "WITH Fruit AS (SELECT id FROM Item) SELECT F.id FROM Fruit AS F";
+        let expected = "SELECT F.id FROM (SELECT id FROM Item) AS F";
+        assert_eq!(
+            plan(parse_statement(sql)),
+            parse_statement(expected),
+            "alias of the table"
+        );
+
+        let sql = "
+            WITH A AS (SELECT id FROM Item), B AS (SELECT id FROM A)
+            SELECT id FROM B
+        ";
+        let expected = "WITH A AS (SELECT id FROM Item) SELECT id FROM (SELECT id FROM A) AS B";
+        assert_eq!(
+            plan(parse_statement(sql)),
+            parse_statement(expected),
+            "refers to the one defined before"
+        );
+    }
+
+    #[test]
+    fn materialize() {
+        let sqls = [
+            "WITH Fruit AS (SELECT id FROM Item) SELECT id FROM Fruit WHERE id IN (SELECT id FROM Fruit)",
+            "WITH Fruit AS (SELECT id FROM Item) SELECT * FROM Fruit JOIN Fruit AS F ON Fruit.id = F.id",
+            "WITH Fruit(num) AS (SELECT id FROM Item) SELECT num FROM Fruit",
+            "WITH Item AS (SELECT id FROM Item) SELECT id FROM Item",
+            "WITH A AS (SELECT id FROM B), B AS (SELECT id FROM Item) SELECT id FROM A",
+            "WITH RECURSIVE Counter(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM Counter WHERE n < 3) SELECT n FROM Counter",
+            "WITH Fruit AS (SELECT id FROM Item) SELECT id FROM Item JOIN Fruit ON Item.id = Fruit.id",
+        ];
+
+        for sql in sqls {
+            let statement = parse_statement(sql);
+
+            assert_eq!(plan(statement.clone()), statement, "{sql}");
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use {serde::Serialize, std::fmt::Debug, thiserror::Error as ThisError};
+use {
+    crate::{ast::DataType, data::Value},
+    serde::Serialize,
+    std::fmt::Debug,
+    thiserror::Error as ThisError,
+};
 
 #[derive(ThisError, Serialize, Debug, PartialEq)]
 pub enum PlanError {
@@ -6,4 +11,23 @@ pub enum PlanError {
     /// situation.
     #[error("column reference {0} is ambiguous, please specify the table name")]
     ColumnReferenceAmbiguous(String),
+
+    #[error("unsupported placeholder: {0}, use $1, $2, .. or ?")]
+    UnsupportedPlaceholder(String),
+
+    #[error("cannot mix positional ($1) and anonymous (?) placeholders")]
+    MixedPlaceholders,
+
+    #[error("only a single statement can be prepared, but {0} were given")]
+    MultipleStatementsPrepared(usize),
+
+    #[error("bind parameter count mismatch - expected: {expected}, found: {found}")]
+    ParamCountMismatch { expected: usize, found: usize },
+
+    #[error("bind parameter ${index} type mismatch - expected: {expected}, found: {found:?}")]
+    ParamTypeMismatch {
+        index: usize,
+        expected: DataType,
+        found: Value,
+    },
 }
@@ -1,8 +1,8 @@
 use {
     super::{context::Context, expr::PlanExpr},
     crate::ast::{
-        Expr, Join, JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr, TableAlias,
-        TableFactor, TableWithJoins, Values,
+        Cte, Expr, Join, JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr,
+        TableAlias, TableFactor, TableWithJoins, Values, With,
     },
     std::{convert::identity, rc::Rc},
 };
@@ -40,22 +40,23 @@ pub fn check_expr(context: Option<Rc<Context<'_>>>, expr: &Expr) -> bool {
 
 fn check_query(context: Option<Rc<Context<'_>>>, query: &Query) -> bool {
     let Query {
+        with,
         body,
         order_by,
         limit,
         offset,
     } = query;
 
-    let body = match body {
-        SetExpr::Select(select) => check_select(context.as_ref().map(Rc::clone), select),
-        SetExpr::Values(Values(rows)) => rows
-            .iter()
-            .flatten()
-            .map(|expr| check_expr(context.as_ref().map(Rc::clone), expr))
-            .all(identity),
-    };
+    let with = with
+        .iter()
+        .flat_map(|With { cte_tables, .. }| cte_tables)
+        .map(|Cte { query, .. }| check_query(context.as_ref().map(Rc::clone), query))
+        .all(identity);
+    if !with {
+        return false;
+    }
 
-    if !body {
+    if !check_set_expr(context.as_ref().map(Rc::clone), body) {
         return false;
     }
 
@@ -75,8 +76,23 @@ fn check_query(context: Option<Rc<Context<'_>>>, query: &Query) -> bool {
         .all(identity)
 }
 
+fn check_set_expr(context: Option<Rc<Context<'_>>>, set_expr: &SetExpr) -> bool {
+    match set_expr {
+        SetExpr::Select(select) => check_select(context, select),
+        SetExpr::Values(Values(rows)) => rows
+            .iter()
+            .flatten()
+            .map(|expr| check_expr(context.as_ref().map(Rc::clone), expr))
+            .all(identity),
+        SetExpr::SetOperation { left, right, .. } => {
+            check_set_expr(context.as_ref().map(Rc::clone), left) && check_set_expr(context, right)
+        }
+    }
+}
+
 fn check_select(context: Option<Rc<Context<'_>>>, select: &Select) -> bool {
     let Select {
+        distinct: _,
         projection,
         from,
         selection,
@@ -116,11 +132,20 @@ fn check_select(context: Option<Rc<Context<'_>>>, select: &Select) -> bool {
 
             match join_operator {
                 JoinOperator::Inner(JoinConstraint::On(expr))
-                | JoinOperator::LeftOuter(JoinConstraint::On(expr)) => {
+                | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                | JoinOperator::FullOuter(JoinConstraint::On(expr))
+                | JoinOperator::Semi(JoinConstraint::On(expr))
+                | JoinOperator::Anti(JoinConstraint::On(expr)) => {
                     check_expr(context.as_ref().map(Rc::clone), expr)
                 }
-                JoinOperator::Inner(JoinConstraint::None)
-                | JoinOperator::LeftOuter(JoinConstraint::None) => true,
+                JoinOperator::Inner(_)
+                | JoinOperator::LeftOuter(_)
+                | JoinOperator::RightOuter(_)
+                | JoinOperator::FullOuter(_)
+                | JoinOperator::Semi(_)
+                | JoinOperator::Anti(_)
+                | JoinOperator::Cross => true,
             }
         })
         .all(identity)
@@ -1,16 +1,29 @@
-use crate::ast::{Aggregate, CountArgExpr, Expr};
+use crate::ast::{Aggregate, AggregateFunction, CountArgExpr, Expr};
 
 impl Aggregate {
     pub fn as_expr(&self) -> Option<&Expr> {
-        match self {
-            Aggregate::Count(CountArgExpr::Wildcard) => None,
-            Aggregate::Count(CountArgExpr::Expr(expr))
-            | Aggregate::Sum(expr)
-            | Aggregate::Max(expr)
-            | Aggregate::Min(expr)
-            | Aggregate::Avg(expr)
-            | Aggregate::Variance(expr)
-            | Aggregate::Stdev(expr) => Some(expr),
+        match &self.func {
+            AggregateFunction::Count(CountArgExpr::Wildcard) => None,
+            AggregateFunction::Count(CountArgExpr::Expr(expr))
+            | AggregateFunction::Sum(expr)
+            | AggregateFunction::Max(expr)
+            | AggregateFunction::Min(expr)
+            | AggregateFunction::Avg(expr)
+            | AggregateFunction::Variance(expr)
+            | AggregateFunction::Stdev(expr) => Some(expr),
+        }
+    }
+
+    pub fn as_expr_mut(&mut self) -> Option<&mut Expr> {
+        match &mut self.func {
+            AggregateFunction::Count(CountArgExpr::Wildcard) => None,
+            AggregateFunction::Count(CountArgExpr::Expr(expr))
+            | AggregateFunction::Sum(expr)
+            | AggregateFunction::Max(expr)
+            | AggregateFunction::Min(expr)
+            | AggregateFunction::Avg(expr)
+            | AggregateFunction::Variance(expr)
+            | AggregateFunction::Stdev(expr) => Some(expr),
         }
     }
 }
@@ -60,5 +73,9 @@ mod tests {
         let actual = parse("VARIANCE(id)");
         let expected = Expr::Identifier("id".to_owned());
         assert_eq!(actual.as_expr(), Some(&expected));
+
+        let actual = parse("COUNT(DISTINCT id)");
+        let expected = Expr::Identifier("id".to_owned());
+        assert_eq!(actual.as_expr(), Some(&expected));
     }
 }
@@ -0,0 +1,102 @@
+use {
+    crate::ast::{
+        Expr, OrderByExpr, Window, WindowFrame, WindowFrameBound, WindowFunction, WindowSpec,
+    },
+    std::iter::once,
+};
+
+impl Window {
+    pub fn as_exprs(&self) -> impl Iterator<Item = &Expr> {
+        let Window {
+            func,
+            spec:
+                WindowSpec {
+                    partition_by,
+                    order_by,
+                    frame,
+                },
+        } = self;
+
+        let func_exprs = match func {
+            WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => {
+                Vec::new()
+            }
+            WindowFunction::Lag {
+                expr,
+                offset,
+                default,
+            }
+            | WindowFunction::Lead {
+                expr,
+                offset,
+                default,
+            } => once(expr).chain(offset).chain(default).collect(),
+            WindowFunction::FirstValue(expr) | WindowFunction::LastValue(expr) => vec![expr],
+            WindowFunction::Aggregate(aggregate) => aggregate.as_expr().into_iter().collect(),
+        };
+
+        let order_by = order_by.iter().map(|OrderByExpr { expr, .. }| expr);
+        let frame = frame
+            .iter()
+            .flat_map(|WindowFrame { start, end, .. }| once(start).chain(end))
+            .filter_map(|bound| match bound {
+                WindowFrameBound::CurrentRow => None,
+                WindowFrameBound::Preceding(expr) | WindowFrameBound::Following(expr) => {
+                    expr.as_ref()
+                }
+            });
+
+        func_exprs
+            .into_iter()
+            .chain(partition_by)
+            .chain(order_by)
+            .chain(frame)
+    }
+
+    pub fn as_exprs_mut(&mut self) -> impl Iterator<Item = &mut Expr> {
+        let Window {
+            func,
+            spec:
+                WindowSpec {
+                    partition_by,
+                    order_by,
+                    frame,
+                },
+        } = self;
+
+        let func_exprs = match func {
+            WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => {
+                Vec::new()
+            }
+            WindowFunction::Lag {
+                expr,
+                offset,
+                default,
+            }
+            | WindowFunction::Lead {
+                expr,
+                offset,
+                default,
+            } => once(expr).chain(offset).chain(default).collect(),
+            WindowFunction::FirstValue(expr) | WindowFunction::LastValue(expr) => vec![expr],
+            WindowFunction::Aggregate(aggregate) => aggregate.as_expr_mut().into_iter().collect(),
+        };
+
+        let order_by = order_by.iter_mut().map(|OrderByExpr { expr, .. }| expr);
+        let frame = frame
+            .iter_mut()
+            .flat_map(|WindowFrame { start, end, .. }| once(start).chain(end))
+            .filter_map(|bound| match bound {
+                WindowFrameBound::CurrentRow => None,
+                WindowFrameBound::Preceding(expr) | WindowFrameBound::Following(expr) => {
+                    expr.as_mut()
+                }
+            });
+
+        func_exprs
+            .into_iter()
+            .chain(partition_by)
+            .chain(order_by)
+            .chain(frame)
+    }
+}
@@ -1,14 +1,13 @@
 use {
     crate::{
         ast::{
-            AstLiteral, BinaryOperator, Expr, Function, IndexItem, IndexOperator, OrderByExpr,
-            Query, Select, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins,
+            AstLiteral, BinaryOperator, Cte, Expr, Function, IndexItem, IndexOperator, OrderByExpr,
+            Query, Select, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins, With,
         },
         data::{Schema, SchemaIndex, SchemaIndexOrd, TableError},
         result::{Error, Result},
     },
-    std::collections::HashMap,
-    utils::Vector,
+    std::{cmp::Reverse, collections::HashMap},
 };
 
 pub fn plan(schema_map: &HashMap<String, Schema>, statement: Statement) -> Result<Statement> {
@@ -24,48 +23,87 @@ impl Indexes {
     fn find(&self, target: &Expr) -> Option<String> {
         self.0
             .iter()
-            .find(|SchemaIndex { expr, .. }| expr == target)
+            .find(|SchemaIndex { exprs, .. }| exprs.first() == Some(target))
             .map(|SchemaIndex { name, .. }| name.to_owned())
     }
 
-    fn find_ordered(&self, target: &OrderByExpr) -> Option<String> {
+    /// Finds the index whose leading columns cover the longest trailing part of ORDER BY,
+    /// returns the index name with the number of covered ORDER BY items.
+    fn find_ordered(&self, order_by: &[OrderByExpr]) -> Option<(String, usize)> {
         self.0
             .iter()
-            .find(|SchemaIndex { expr, order, .. }| {
-                if expr != &target.expr {
-                    return false;
-                }
+            .filter_map(|index| {
+                let SchemaIndex {
+                    name, exprs, order, ..
+                } = index;
+
+                let num = (1..=exprs.len().min(order_by.len())).rev().find(|num| {
+                    let targets = &order_by[order_by.len() - num..];
+                    let asc = targets[0].asc.unwrap_or(true);
+
+                    targets.iter().zip(exprs).all(|(target, expr)| {
+                        expr == &target.expr
+                            && target.asc.unwrap_or(true) == asc
+                            && matches!(
+                                (target.asc, order),
+                                (_, SchemaIndexOrd::Both)
+                                    | (Some(true), SchemaIndexOrd::Asc)
+                                    | (None, SchemaIndexOrd::Asc)
+                                    | (Some(false), SchemaIndexOrd::Desc)
+                            )
+                    })
+                })?;
 
-                matches!(
-                    (target.asc, order),
-                    (_, SchemaIndexOrd::Both)
-                        | (Some(true), SchemaIndexOrd::Asc)
-                        | (None, SchemaIndexOrd::Asc)
-                        | (Some(false), SchemaIndexOrd::Desc)
-                )
+                Some((name.to_owned(), num))
             })
-            .map(|SchemaIndex { name, .. }| name.to_owned())
+            .min_by_key(|(_, num)| Reverse(*num))
     }
 }
 
 fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Query> {
     let Query {
+        with,
         body,
         order_by,
         limit,
         offset,
     } = query;
 
+    let with = with.map(|with| plan_with(schema_map, with)).transpose()?;
+
     let select = match body {
         SetExpr::Select(select) => select,
         SetExpr::Values(_) => {
             return Ok(Query {
+                with,
                 body,
                 order_by,
                 limit,
                 offset,
             });
         }
+        SetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+        } => {
+            let left = plan_set_operand(schema_map, *left)?;
+            let right = plan_set_operand(schema_map, *right)?;
+
+            return Ok(Query {
+                with,
+                body: SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                order_by,
+                limit,
+                offset,
+            });
+        }
     };
 
     let TableWithJoins { relation, .. } = &select.from;
@@ -73,6 +111,7 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
         TableFactor::Table { name, .. } => name,
         TableFactor::Derived { .. } => {
             return Ok(Query {
+                with,
                 body: SetExpr::Select(select),
                 order_by,
                 limit,
@@ -93,6 +132,7 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
         Some(Schema { indexes, .. }) => Indexes(indexes.clone()),
         None => {
             return Ok(Query {
+                with,
                 body: SetExpr::Select(select),
                 order_by,
                 limit,
@@ -101,19 +141,21 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
         }
     };
 
-    let index = order_by.last().and_then(|value_expr| {
-        indexes
-            .find_ordered(value_expr)
-            .map(|name| IndexItem::NonClustered {
-                name,
-                asc: value_expr.asc,
-                cmp_expr: None,
-            })
+    let index = indexes.find_ordered(&order_by).map(|(name, num)| {
+        let index = IndexItem::NonClustered {
+            name,
+            asc: order_by[order_by.len() - num].asc,
+            prefix_exprs: Vec::new(),
+            cmp_expr: None,
+        };
+
+        (index, num)
     });
 
     match index {
-        index if index.is_some() => {
+        Some((index, num)) => {
             let Select {
+                distinct,
                 projection,
                 from,
                 selection,
@@ -132,11 +174,16 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
             };
 
             let from = TableWithJoins {
-                relation: TableFactor::Table { name, alias, index },
+                relation: TableFactor::Table {
+                    name,
+                    alias,
+                    index: Some(index),
+                },
                 joins,
             };
 
             let select = Select {
+                distinct,
                 projection,
                 from,
                 selection,
@@ -144,17 +191,22 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
                 having,
             };
 
+            let mut order_by = order_by;
+            order_by.truncate(order_by.len() - num);
+
             Ok(Query {
+                with,
                 body: SetExpr::Select(Box::new(select)),
-                order_by: Vector::from(order_by).pop().0.into(),
+                order_by,
                 limit,
                 offset,
             })
         }
-        _ => {
+        None => {
             let select = plan_select(schema_map, &indexes, *select)?;
             let body = SetExpr::Select(Box::new(select));
             let query = Query {
+                with,
                 body,
                 order_by,
                 limit,
@@ -166,12 +218,44 @@ fn plan_query(schema_map: &HashMap<String, Schema>, query: Query) -> Result<Quer
     }
 }
 
+/// ORDER BY and LIMIT of a set operation apply to the combined result,
+/// so each operand is planned as a query without them.
+fn plan_set_operand(schema_map: &HashMap<String, Schema>, set_expr: SetExpr) -> Result<SetExpr> {
+    let query = Query {
+        with: None,
+        body: set_expr,
+        order_by: Vec::new(),
+        limit: None,
+        offset: None,
+    };
+
+    plan_query(schema_map, query).map(|Query { body, .. }| body)
+}
+
+fn plan_with(schema_map: &HashMap<String, Schema>, with: With) -> Result<With> {
+    let With {
+        recursive,
+        cte_tables,
+    } = with;
+
+    let cte_tables = cte_tables
+        .into_iter()
+        .map(|Cte { alias, query }| plan_query(schema_map, query).map(|query| Cte { alias, query }))
+        .collect::<Result<_>>()?;
+
+    Ok(With {
+        recursive,
+        cte_tables,
+    })
+}
+
 fn plan_select(
     schema_map: &HashMap<String, Schema>,
     indexes: &Indexes,
     select: Select,
 ) -> Result<Select> {
     let Select {
+        distinct,
         projection,
         from,
         selection,
@@ -183,6 +267,7 @@ fn plan_select(
         Some(expr) => expr,
         None => {
             return Ok(Select {
+                distinct,
                 projection,
                 from,
                 selection,
@@ -192,8 +277,14 @@ fn plan_select(
         }
     };
 
-    match plan_index(schema_map, indexes, selection)? {
+    let planned = match search_composite_index(indexes, selection) {
+        Planned::Expr(selection) => plan_index(schema_map, indexes, selection)?,
+        planned => planned,
+    };
+
+    match planned {
         Planned::Expr(selection) => Ok(Select {
+            distinct,
             projection,
             from,
             selection: Some(selection),
@@ -202,8 +293,8 @@ fn plan_select(
         }),
         Planned::IndexedExpr {
             index_name,
-            index_op,
-            index_value_expr,
+            index_prefix_exprs,
+            index_cmp_expr,
             selection,
         } => {
             let TableWithJoins { relation, joins } = from;
@@ -219,7 +310,8 @@ fn plan_select(
             let index = Some(IndexItem::NonClustered {
                 name: index_name,
                 asc: None,
-                cmp_expr: Some((index_op, index_value_expr)),
+                prefix_exprs: index_prefix_exprs,
+                cmp_expr: index_cmp_expr,
             });
             let from = TableWithJoins {
                 relation: TableFactor::Table { name, alias, index },
@@ -227,6 +319,7 @@ fn plan_select(
             };
 
             Ok(Select {
+                distinct,
                 projection,
                 from,
                 selection,
@@ -240,8 +333,8 @@ fn plan_select(
 enum Planned {
     IndexedExpr {
         index_name: String,
-        index_op: IndexOperator,
-        index_value_expr: Expr,
+        index_prefix_exprs: Vec<Expr>,
+        index_cmp_expr: Option<(IndexOperator, Expr)>,
         selection: Option<Expr>,
     },
     Expr(Expr),
@@ -256,9 +349,9 @@ fn plan_index(
         Expr::Nested(expr) => plan_index(schema_map, indexes, *expr),
         Expr::IsNull(expr) => Ok(search_is_null(indexes, true, expr)),
         Expr::IsNotNull(expr) => Ok(search_is_null(indexes, false, expr)),
-        Expr::Subquery(query) => plan_query(schema_map, *query)
+        Expr::Subquery { subquery, cached } => plan_query(schema_map, *subquery)
             .map(Box::new)
-            .map(Expr::Subquery)
+            .map(|subquery| Expr::Subquery { subquery, cached })
             .map(Planned::Expr),
         Expr::Exists { subquery, negated } => plan_query(schema_map, *subquery)
             .map(Box::new)
@@ -285,8 +378,8 @@ fn plan_index(
                 Planned::Expr(selection) => selection,
                 Planned::IndexedExpr {
                     index_name,
-                    index_value_expr,
-                    index_op,
+                    index_prefix_exprs,
+                    index_cmp_expr,
                     selection,
                 } => {
                     let selection = match selection {
@@ -300,8 +393,8 @@ fn plan_index(
 
                     return Ok(Planned::IndexedExpr {
                         index_name,
-                        index_op,
-                        index_value_expr,
+                        index_prefix_exprs,
+                        index_cmp_expr,
                         selection: Some(selection),
                     });
                 }
@@ -315,8 +408,8 @@ fn plan_index(
                 })),
                 Planned::IndexedExpr {
                     index_name,
-                    index_op,
-                    index_value_expr,
+                    index_prefix_exprs,
+                    index_cmp_expr,
                     selection,
                 } => {
                     let selection = match selection {
@@ -330,8 +423,8 @@ fn plan_index(
 
                     Ok(Planned::IndexedExpr {
                         index_name,
-                        index_value_expr,
-                        index_op,
+                        index_prefix_exprs,
+                        index_cmp_expr,
                         selection: Some(selection),
                     })
                 }
@@ -377,8 +470,8 @@ fn search_is_null(indexes: &Indexes, null: bool, expr: Box<Expr>) -> Planned {
 
             Planned::IndexedExpr {
                 index_name,
-                index_op,
-                index_value_expr: Expr::Literal(AstLiteral::Null),
+                index_prefix_exprs: Vec::new(),
+                index_cmp_expr: Some((index_op, Expr::Literal(AstLiteral::Null))),
                 selection: None,
             }
         }
@@ -406,9 +499,9 @@ fn search_index_op(
     {
         Planned::IndexedExpr {
             index_name,
-            index_op,
-            index_value_expr: *right,
-            selection: None,
+            index_prefix_exprs: Vec::new(),
+            selection: bound_later(&left, index_op.clone(), &right),
+            index_cmp_expr: Some((index_op, *right)),
         }
     } else if let Some(index_name) = indexes
         .find(right.as_ref())
@@ -416,9 +509,9 @@ fn search_index_op(
     {
         Planned::IndexedExpr {
             index_name,
-            index_op: index_op.reverse(),
-            index_value_expr: *left,
-            selection: None,
+            index_prefix_exprs: Vec::new(),
+            selection: bound_later(&left, index_op.clone(), &right),
+            index_cmp_expr: Some((index_op.reverse(), *left)),
         }
     } else if let Expr::Nested(left) = *left {
         search_index_op(indexes, index_op, left, right)
@@ -433,10 +526,140 @@ fn search_index_op(
     }
 }
 
+/// Keeps the comparison as a selection when it takes a bind parameter, as the index scan
+/// alone would match the `NULL` keys once `NULL` is bound.
+fn bound_later(left: &Expr, index_op: IndexOperator, right: &Expr) -> Option<Expr> {
+    (has_placeholder(left) || has_placeholder(right)).then(|| Expr::BinaryOp {
+        left: Box::new(left.clone()),
+        op: index_op.into(),
+        right: Box::new(right.clone()),
+    })
+}
+
+/// Plans a scan on a composite index using equality conditions on its leading columns and an
+/// optional range condition on the column right after them, e.g.
+/// `tenant_id = 1 AND created_at > '2022-01-01'` for an index on `(tenant_id, created_at)`.
+fn search_composite_index(indexes: &Indexes, selection: Expr) -> Planned {
+    let conditions = split_conjunction(&selection);
+
+    let found = indexes
+        .0
+        .iter()
+        .filter(|SchemaIndex { exprs, .. }| exprs.len() > 1)
+        .filter_map(|SchemaIndex { name, exprs, .. }| {
+            let mut used = Vec::new();
+            let mut prefix_exprs = Vec::new();
+
+            for index_expr in exprs {
+                let eq = conditions.iter().enumerate().find_map(|(i, condition)| {
+                    match get_comparison(index_expr, condition) {
+                        Some((IndexOperator::Eq, value)) if !used.contains(&i) => Some((i, value)),
+                        _ => None,
+                    }
+                });
+
+                match eq {
+                    Some((i, value)) => {
+                        used.push(i);
+                        prefix_exprs.push(value.clone());
+                    }
+                    None => break,
+                }
+            }
+
+            let cmp_expr = exprs.get(prefix_exprs.len()).and_then(|index_expr| {
+                conditions.iter().enumerate().find_map(|(i, condition)| {
+                    if used.contains(&i) {
+                        return None;
+                    }
+
+                    get_comparison(index_expr, condition).map(|(op, value)| (i, op, value.clone()))
+                })
+            });
+
+            let cmp_expr = cmp_expr.map(|(i, op, value)| {
+                used.push(i);
+
+                (op, value)
+            });
+
+            let num_prefix = prefix_exprs.len();
+            if num_prefix < 2 && (num_prefix == 0 || cmp_expr.is_none()) {
+                return None;
+            }
+
+            Some((name, used, prefix_exprs, cmp_expr))
+        })
+        .min_by_key(|(_, _, prefix_exprs, cmp_expr)| {
+            Reverse((prefix_exprs.len(), cmp_expr.is_some()))
+        });
+
+    let (index_name, used, index_prefix_exprs, index_cmp_expr) = match found {
+        Some(found) => found,
+        None => {
+            return Planned::Expr(selection);
+        }
+    };
+
+    let selection = conditions
+        .into_iter()
+        .enumerate()
+        .filter(|(i, condition)| !used.contains(i) || has_placeholder(condition))
+        .map(|(_, condition)| condition.clone())
+        .reduce(|left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        });
+
+    Planned::IndexedExpr {
+        index_name: index_name.to_owned(),
+        index_prefix_exprs,
+        index_cmp_expr,
+        selection,
+    }
+}
+
+pub(super) fn split_conjunction(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Nested(expr) => split_conjunction(expr),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => [split_conjunction(left), split_conjunction(right)].concat(),
+        _ => vec![expr],
+    }
+}
+
+fn get_comparison<'a>(index_expr: &Expr, condition: &'a Expr) -> Option<(IndexOperator, &'a Expr)> {
+    let (left, op, right) = match condition {
+        Expr::BinaryOp { left, op, right } => (left.as_ref(), op, right.as_ref()),
+        _ => return None,
+    };
+
+    let op = match op {
+        BinaryOperator::Eq => IndexOperator::Eq,
+        BinaryOperator::Gt => IndexOperator::Gt,
+        BinaryOperator::GtEq => IndexOperator::GtEq,
+        BinaryOperator::Lt => IndexOperator::Lt,
+        BinaryOperator::LtEq => IndexOperator::LtEq,
+        _ => return None,
+    };
+
+    if left == index_expr && is_stateless(right) {
+        Some((op, right))
+    } else if right == index_expr && is_stateless(left) {
+        Some((op.reverse(), left))
+    } else {
+        None
+    }
+}
+
 fn is_stateless(expr: &Expr) -> bool {
     match expr {
         Expr::Literal(AstLiteral::Null) => false,
-        Expr::Literal(_) => true,
+        Expr::Literal(_) | Expr::Placeholder(_) => true,
         Expr::TypedString { .. } => true,
         Expr::IsNull(expr)
         | Expr::IsNotNull(expr)
@@ -452,3 +675,19 @@ fn is_stateless(expr: &Expr) -> bool {
         _ => false,
     }
 }
+
+fn has_placeholder(expr: &Expr) -> bool {
+    match expr {
+        Expr::Placeholder(_) => true,
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr) => has_placeholder(expr),
+        Expr::Function(func) => match func.as_ref() {
+            Function::Cast { expr, .. } => has_placeholder(expr),
+            _ => false,
+        },
+        Expr::BinaryOp { left, right, .. } => has_placeholder(left) || has_placeholder(right),
+        _ => false,
+    }
+}
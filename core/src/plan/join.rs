@@ -3,7 +3,7 @@ use {
     crate::{
         ast::{
             BinaryOperator, Expr, Join, JoinConstraint, JoinExecutor, JoinOperator, Query, Select,
-            SetExpr, Statement, TableWithJoins,
+            SetExpr, Statement, TableAlias, TableFactor, TableWithJoins,
         },
         data::Schema,
     },
@@ -20,6 +20,42 @@ pub fn plan(schema_map: &HashMap<String, Schema>, statement: Statement) -> State
 
             Statement::Query(query)
         }
+        Statement::Update {
+            table_name,
+            alias,
+            assignments,
+            joins,
+            selection,
+            returning,
+        } => {
+            let joins = planner.target_joins(&table_name, alias.as_deref(), joins);
+
+            Statement::Update {
+                table_name,
+                alias,
+                assignments,
+                joins,
+                selection,
+                returning,
+            }
+        }
+        Statement::Delete {
+            table_name,
+            alias,
+            joins,
+            selection,
+            returning,
+        } => {
+            let joins = planner.target_joins(&table_name, alias.as_deref(), joins);
+
+            Statement::Delete {
+                table_name,
+                alias,
+                joins,
+                selection,
+                returning,
+            }
+        }
         _ => statement,
     }
 }
@@ -31,22 +67,18 @@ struct JoinPlanner<'a> {
 impl<'a> Planner<'a> for JoinPlanner<'a> {
     fn query(&self, outer_context: Option<Rc<Context<'a>>>, query: Query) -> Query {
         let Query {
+            with,
             body,
             order_by,
             limit,
             offset,
         } = query;
 
-        let body = match body {
-            SetExpr::Select(select) => {
-                let select = self.select(outer_context, *select);
-
-                SetExpr::Select(Box::new(select))
-            }
-            SetExpr::Values(_) => body,
-        };
+        let with = with.map(|with| self.with(outer_context.as_ref().map(Rc::clone), with));
+        let body = self.set_expr(outer_context, body);
 
         Query {
+            with,
             body,
             order_by,
             limit,
@@ -60,8 +92,36 @@ impl<'a> Planner<'a> for JoinPlanner<'a> {
 }
 
 impl<'a> JoinPlanner<'a> {
+    fn set_expr(&self, outer_context: Option<Rc<Context<'a>>>, set_expr: SetExpr) -> SetExpr {
+        match set_expr {
+            SetExpr::Select(select) => {
+                let select = self.select(outer_context, *select);
+
+                SetExpr::Select(Box::new(select))
+            }
+            SetExpr::Values(_) => set_expr,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let left = self.set_expr(outer_context.as_ref().map(Rc::clone), *left);
+                let right = self.set_expr(outer_context, *right);
+
+                SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        }
+    }
+
     fn select(&self, outer_context: Option<Rc<Context<'a>>>, select: Select) -> Select {
         let Select {
+            distinct,
             projection,
             from,
             selection,
@@ -73,6 +133,7 @@ impl<'a> JoinPlanner<'a> {
         let selection = selection.map(|expr| self.subquery_expr(outer_context, expr));
 
         Select {
+            distinct,
             projection,
             from,
             selection,
@@ -81,6 +142,26 @@ impl<'a> JoinPlanner<'a> {
         }
     }
 
+    /// Plans the tables joined to the target table of `UPDATE` or `DELETE`
+    fn target_joins(&self, table_name: &str, alias: Option<&str>, joins: Vec<Join>) -> Vec<Join> {
+        if joins.is_empty() {
+            return joins;
+        }
+
+        let relation = TableFactor::Table {
+            name: table_name.to_owned(),
+            alias: alias.map(|alias| TableAlias {
+                name: alias.to_owned(),
+                columns: Vec::new(),
+            }),
+            index: None,
+        };
+        let (_, TableWithJoins { joins, .. }) =
+            self.table_with_joins(None, TableWithJoins { relation, joins });
+
+        joins
+    }
+
     fn table_with_joins(
         &self,
         outer_context: Option<Rc<Context<'a>>>,
@@ -130,13 +211,22 @@ impl<'a> JoinPlanner<'a> {
         enum JoinOp {
             Inner,
             LeftOuter,
+            RightOuter,
+            FullOuter,
         }
 
         let (join_op, expr) = match join_operator {
             JoinOperator::Inner(JoinConstraint::On(expr)) => (JoinOp::Inner, expr),
             JoinOperator::LeftOuter(JoinConstraint::On(expr)) => (JoinOp::LeftOuter, expr),
-            JoinOperator::Inner(JoinConstraint::None)
-            | JoinOperator::LeftOuter(JoinConstraint::None) => {
+            JoinOperator::RightOuter(JoinConstraint::On(expr)) => (JoinOp::RightOuter, expr),
+            JoinOperator::FullOuter(JoinConstraint::On(expr)) => (JoinOp::FullOuter, expr),
+            JoinOperator::Inner(_)
+            | JoinOperator::LeftOuter(_)
+            | JoinOperator::RightOuter(_)
+            | JoinOperator::FullOuter(_)
+            | JoinOperator::Semi(_)
+            | JoinOperator::Anti(_)
+            | JoinOperator::Cross => {
                 let context = self.update_context(inner_context, &relation);
                 let join = Join {
                     relation,
@@ -161,6 +251,10 @@ impl<'a> JoinPlanner<'a> {
             (JoinOp::Inner, None) => JoinOperator::Inner(JoinConstraint::None),
             (JoinOp::LeftOuter, Some(expr)) => JoinOperator::LeftOuter(JoinConstraint::On(expr)),
             (JoinOp::LeftOuter, None) => JoinOperator::LeftOuter(JoinConstraint::None),
+            (JoinOp::RightOuter, Some(expr)) => JoinOperator::RightOuter(JoinConstraint::On(expr)),
+            (JoinOp::RightOuter, None) => JoinOperator::RightOuter(JoinConstraint::None),
+            (JoinOp::FullOuter, Some(expr)) => JoinOperator::FullOuter(JoinConstraint::On(expr)),
+            (JoinOp::FullOuter, None) => JoinOperator::FullOuter(JoinConstraint::None),
         };
 
         let context = self.update_context(inner_context, &relation);
@@ -344,13 +438,13 @@ impl<'a> JoinPlanner<'a> {
             Expr::Nested(expr) => {
                 self.join_expr(outer_context, inner_context, current_context, *expr)
             }
-            Expr::Subquery(query) => {
+            Expr::Subquery { subquery, cached } => {
                 let context = Context::concat(current_context, inner_context);
                 let context = Context::concat(Some(Rc::new(context)), outer_context);
                 let context = Some(Rc::new(context));
 
-                let query = self.query(context, *query);
-                let expr = Some(Expr::Subquery(Box::new(query)));
+                let subquery = Box::new(self.query(context, *subquery));
+                let expr = Some(Expr::Subquery { subquery, cached });
 
                 (JoinExecutor::NestedLoop, expr)
             }
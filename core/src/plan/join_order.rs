@@ -0,0 +1,781 @@
+use {
+    super::{context::Context, planner::Planner, PlanExpr},
+    crate::{
+        ast::{
+            BinaryOperator, ColumnDef, Cte, Expr, Join, JoinConstraint, JoinExecutor, JoinOperator,
+            OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableAlias, TableFactor,
+            TableWithJoins, Values, With,
+        },
+        data::{get_alias, Schema, Statistics},
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        rc::Rc,
+    },
+};
+
+/// Joins of more tables are left in the order they are written.
+const MAX_TABLES: usize = 12;
+
+/// Selectivity of a filter whose rows cannot be estimated from the statistics.
+const DEFAULT_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Reorders the inner joins of the analyzed tables by their estimated cost. Each joined table
+/// becomes the build side of a hash join, so the smaller inputs are moved to the right and the
+/// joins which shrink the rows the most are run first.
+pub fn plan(schema_map: &HashMap<String, Schema>, statement: Statement) -> Statement {
+    let planner = JoinOrderPlanner {
+        schema_map,
+        cte_names: Vec::new(),
+    };
+
+    match statement {
+        Statement::Query(query) => {
+            let query = planner.query(None, query);
+
+            Statement::Query(query)
+        }
+        _ => statement,
+    }
+}
+
+struct JoinOrderPlanner<'a> {
+    schema_map: &'a HashMap<String, Schema>,
+    /// Common table expressions in scope, which hide the tables of the same name
+    cte_names: Vec<String>,
+}
+
+impl<'a> Planner<'a> for JoinOrderPlanner<'a> {
+    fn query(&self, outer_context: Option<Rc<Context<'a>>>, query: Query) -> Query {
+        let Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        } = query;
+
+        let cte_names = with
+            .iter()
+            .flat_map(|With { cte_tables, .. }| cte_tables)
+            .map(|Cte { alias, .. }| alias.name.clone());
+        let planner = JoinOrderPlanner {
+            schema_map: self.schema_map,
+            cte_names: self.cte_names.iter().cloned().chain(cte_names).collect(),
+        };
+
+        let with = with.map(|with| planner.with(outer_context.as_ref().map(Rc::clone), with));
+        let body = planner.set_expr(outer_context, body, &order_by);
+
+        Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        }
+    }
+
+    fn get_schema(&self, name: &str) -> Option<&'a Schema> {
+        self.schema_map.get(name)
+    }
+}
+
+impl<'a> JoinOrderPlanner<'a> {
+    fn set_expr(
+        &self,
+        outer_context: Option<Rc<Context<'a>>>,
+        set_expr: SetExpr,
+        order_by: &[OrderByExpr],
+    ) -> SetExpr {
+        match set_expr {
+            SetExpr::Select(select) => {
+                let select = self.select(outer_context, *select, order_by);
+
+                SetExpr::Select(Box::new(select))
+            }
+            SetExpr::Values(_) => set_expr,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let left = self.set_expr(outer_context.as_ref().map(Rc::clone), *left, &[]);
+                let right = self.set_expr(outer_context, *right, &[]);
+
+                SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        }
+    }
+
+    fn select(
+        &self,
+        outer_context: Option<Rc<Context<'a>>>,
+        select: Select,
+        order_by: &[OrderByExpr],
+    ) -> Select {
+        let Select {
+            distinct,
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+        } = select;
+
+        let relation = self.table_factor(relation);
+        let joins = joins
+            .into_iter()
+            .map(|join| Join {
+                relation: self.table_factor(join.relation),
+                ..join
+            })
+            .collect();
+        let selection = selection.map(|expr| self.subquery_expr(outer_context, expr));
+
+        let select = Select {
+            distinct,
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+        };
+
+        match self.join_order(&select, order_by) {
+            Some((order, on_tables)) => reorder(select, &order, on_tables),
+            None => select,
+        }
+    }
+
+    fn table_factor(&self, table_factor: TableFactor) -> TableFactor {
+        match table_factor {
+            TableFactor::Derived { subquery, alias } => TableFactor::Derived {
+                subquery: self.query(None, subquery),
+                alias,
+            },
+            _ => table_factor,
+        }
+    }
+
+    /// Positions of the tables in the cheapest order with the tables referred to by each
+    /// conjunct of the `ON` clauses, `None` when the joins are kept as they are written.
+    fn join_order(
+        &self,
+        select: &Select,
+        order_by: &[OrderByExpr],
+    ) -> Option<(Vec<usize>, Vec<u32>)> {
+        let Select {
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+            ..
+        } = select;
+
+        if joins.is_empty() || joins.len() >= MAX_TABLES {
+            return None;
+        }
+
+        // the columns of `*` follow the order of the tables
+        if projection
+            .iter()
+            .any(|item| matches!(item, SelectItem::Wildcard))
+        {
+            return None;
+        }
+
+        let mut on_exprs = Vec::new();
+        for Join {
+            join_operator,
+            join_executor,
+            ..
+        } in joins
+        {
+            if !matches!(join_executor, JoinExecutor::NestedLoop) {
+                return None;
+            }
+
+            match join_operator {
+                JoinOperator::Inner(JoinConstraint::On(expr)) => on_exprs.push(expr),
+                JoinOperator::Inner(JoinConstraint::None) | JoinOperator::Cross => {}
+                _ => return None,
+            }
+        }
+
+        let tables = std::iter::once(relation)
+            .chain(joins.iter().map(|Join { relation, .. }| relation))
+            .map(|relation| self.table(relation))
+            .collect::<Option<Vec<_>>>()?;
+
+        let aliases = tables
+            .iter()
+            .map(|Table { alias, .. }| alias)
+            .collect::<HashSet<_>>();
+        if aliases.len() < tables.len() {
+            return None;
+        }
+
+        let mut idents = Vec::new();
+        for item in projection {
+            if let SelectItem::Expr { expr, .. } = item {
+                collect_expr(&mut idents, expr);
+            }
+        }
+
+        on_exprs
+            .iter()
+            .copied()
+            .chain(selection)
+            .chain(group_by)
+            .chain(having)
+            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+            .for_each(|expr| collect_expr(&mut idents, expr));
+
+        // an unqualified column of more than one table is bound by the order of the tables
+        let ambiguous = idents.iter().any(|(alias, ident)| match alias {
+            Some(_) => false,
+            None => {
+                tables
+                    .iter()
+                    .filter(|table| table.has_column(ident))
+                    .count()
+                    > 1
+            }
+        });
+        if ambiguous {
+            return None;
+        }
+
+        let mut on_conjuncts = Vec::new();
+        on_exprs
+            .into_iter()
+            .for_each(|expr| split_conjuncts(expr, &mut on_conjuncts));
+
+        let estimate = Estimate::new(&tables, &on_conjuncts, selection.as_ref());
+        let written = (0..tables.len()).collect::<Vec<_>>();
+        let (order, cost) = estimate.cheapest_order();
+
+        // costs of the orders alike may differ by the rounding only
+        if order == written || cost >= estimate.cost(&written) * (1.0 - 1e-9) {
+            return None;
+        }
+
+        let on_tables = on_conjuncts
+            .into_iter()
+            .map(|expr| referred_tables(&tables, expr))
+            .collect();
+
+        Some((order, on_tables))
+    }
+
+    fn table<'b>(&self, table_factor: &'b TableFactor) -> Option<Table<'b>>
+    where
+        'a: 'b,
+    {
+        // aliases of the columns hide the names the statistics are kept by
+        let name = match table_factor {
+            TableFactor::Table {
+                name,
+                alias,
+                index: None,
+            } if alias
+                .iter()
+                .all(|TableAlias { columns, .. }| columns.is_empty()) =>
+            {
+                name
+            }
+            _ => return None,
+        };
+
+        if self.cte_names.contains(name) {
+            return None;
+        }
+
+        let Schema {
+            column_defs,
+            statistics,
+            ..
+        } = self.get_schema(name)?;
+
+        Some(Table {
+            alias: get_alias(table_factor),
+            column_defs,
+            statistics: statistics.as_ref()?,
+        })
+    }
+}
+
+struct Table<'a> {
+    alias: &'a str,
+    column_defs: &'a [ColumnDef],
+    statistics: &'a Statistics,
+}
+
+impl Table<'_> {
+    fn has_column(&self, column_name: &str) -> bool {
+        self.column_defs
+            .iter()
+            .any(|ColumnDef { name, .. }| name == column_name)
+    }
+
+    fn distinct_count(&self, column_name: &str) -> f64 {
+        let distinct_count = self
+            .statistics
+            .column(column_name)
+            .map(|column| column.distinct_count)
+            .unwrap_or(self.statistics.row_count);
+
+        distinct_count.max(1) as f64
+    }
+}
+
+/// Predicate on two or more tables, applied once all of them are joined.
+struct JoinPredicate {
+    tables: u32,
+    selectivity: f64,
+    /// Equality of the columns in `ON` which the join can hash on
+    hashable: bool,
+}
+
+/// Estimated rows of the tables after their own filters, and the predicates joining them.
+struct Estimate {
+    rows: Vec<f64>,
+    predicates: Vec<JoinPredicate>,
+}
+
+impl Estimate {
+    fn new(tables: &[Table], on_conjuncts: &[&Expr], selection: Option<&Expr>) -> Self {
+        let mut rows = tables
+            .iter()
+            .map(|table| table.statistics.row_count as f64)
+            .collect::<Vec<_>>();
+        let mut predicates = Vec::new();
+
+        let mut where_conjuncts = Vec::new();
+        if let Some(expr) = selection {
+            split_conjuncts(expr, &mut where_conjuncts);
+        }
+
+        let conjuncts = on_conjuncts
+            .iter()
+            .map(|expr| (*expr, true))
+            .chain(where_conjuncts.into_iter().map(|expr| (expr, false)));
+
+        for (expr, in_on) in conjuncts {
+            let referred = referred_tables(tables, expr);
+
+            match referred.count_ones() {
+                0 => {}
+                1 => {
+                    let i = referred.trailing_zeros() as usize;
+
+                    rows[i] *= filter_selectivity(&tables[i], expr);
+                }
+                _ => {
+                    let columns = equal_columns(tables, expr);
+                    let selectivity = match columns {
+                        Some(((i, column), (j, column2))) => {
+                            let distinct_count = tables[i].distinct_count(column);
+                            let distinct_count2 = tables[j].distinct_count(column2);
+
+                            1.0 / distinct_count.max(distinct_count2)
+                        }
+                        None => DEFAULT_SELECTIVITY,
+                    };
+
+                    predicates.push(JoinPredicate {
+                        tables: referred,
+                        selectivity,
+                        hashable: in_on && columns.is_some(),
+                    });
+                }
+            }
+        }
+
+        Self { rows, predicates }
+    }
+
+    fn cardinality(&self, tables: u32) -> f64 {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| tables & (1 << i) != 0)
+            .map(|(_, rows)| rows)
+            .product::<f64>();
+        let selectivity = self
+            .predicates
+            .iter()
+            .filter(|predicate| predicate.tables & !tables == 0)
+            .map(|predicate| predicate.selectivity)
+            .product::<f64>();
+
+        (rows * selectivity).max(1.0)
+    }
+
+    /// Cost of joining the table `i` to the rows of the joined `tables`, the joined table is
+    /// hashed when an equality of the columns connects them and scanned for each row otherwise.
+    fn join_cost(&self, tables: u32, i: usize) -> f64 {
+        let joined = tables | (1 << i);
+        let left_rows = self.cardinality(tables);
+        let output_rows = self.cardinality(joined);
+
+        let hashable = self.predicates.iter().any(|predicate| {
+            predicate.hashable
+                && predicate.tables & (1 << i) != 0
+                && predicate.tables & !joined == 0
+        });
+
+        match hashable {
+            true => 2.0 * self.rows[i] + left_rows + output_rows,
+            false => left_rows * self.rows[i] + output_rows,
+        }
+    }
+
+    fn cost(&self, order: &[usize]) -> f64 {
+        order
+            .iter()
+            .fold((0, 0.0), |(tables, cost), i| match tables {
+                0 => (1 << i, cost),
+                _ => (tables | (1 << i), cost + self.join_cost(tables, *i)),
+            })
+            .1
+    }
+
+    /// Cheapest left deep order of the tables, found by dynamic programming over the subsets
+    /// of the tables.
+    fn cheapest_order(&self) -> (Vec<usize>, f64) {
+        let size = self.rows.len();
+        let mut best: Vec<Option<(f64, usize)>> = vec![None; 1 << size];
+
+        for tables in 1..(1_u32 << size) {
+            if tables.count_ones() == 1 {
+                best[tables as usize] = Some((0.0, tables.trailing_zeros() as usize));
+                continue;
+            }
+
+            for i in (0..size).filter(|i| tables & (1 << i) != 0) {
+                let rest = tables & !(1 << i);
+                let (rest_cost, _) = best[rest as usize].expect("subsets are visited first");
+                let cost = rest_cost + self.join_cost(rest, i);
+
+                if best[tables as usize].map_or(true, |(best_cost, _)| cost < best_cost) {
+                    best[tables as usize] = Some((cost, i));
+                }
+            }
+        }
+
+        let all = (1_u32 << size) - 1;
+        let (cost, _) = best[all as usize].expect("all tables are visited");
+
+        let mut order = Vec::with_capacity(size);
+        let mut tables = all;
+        while tables != 0 {
+            let (_, i) = best[tables as usize].expect("all tables are visited");
+
+            order.push(i);
+            tables &= !(1 << i);
+        }
+        order.reverse();
+
+        (order, cost)
+    }
+}
+
+/// Rebuilds the joins in the given order. Each conjunct of the `ON` clauses moves to the
+/// first join where all of its tables are joined.
+fn reorder(select: Select, order: &[usize], on_tables: Vec<u32>) -> Select {
+    let Select {
+        distinct,
+        projection,
+        from: TableWithJoins { relation, joins },
+        selection,
+        group_by,
+        having,
+    } = select;
+
+    let mut on_conjuncts = Vec::new();
+    let mut relations = vec![relation];
+    for Join {
+        relation,
+        join_operator,
+        ..
+    } in joins
+    {
+        if let JoinOperator::Inner(JoinConstraint::On(expr)) = join_operator {
+            split_owned_conjuncts(expr, &mut on_conjuncts);
+        }
+
+        relations.push(relation);
+    }
+
+    let mut on_conjuncts = on_tables.into_iter().zip(on_conjuncts).collect::<Vec<_>>();
+
+    let mut relations = relations.into_iter().map(Some).collect::<Vec<_>>();
+    let mut take = |i: usize| relations[i].take().expect("each table is joined once");
+
+    let relation = take(order[0]);
+    let mut joined: u32 = 1 << order[0];
+    let joins = order[1..]
+        .iter()
+        .map(|i| {
+            joined |= 1 << i;
+
+            let (conjuncts, rest) = on_conjuncts
+                .drain(..)
+                .partition::<Vec<_>, _>(|(tables, _)| tables & !joined == 0);
+            on_conjuncts = rest;
+
+            let join_operator = conjuncts
+                .into_iter()
+                .map(|(_, expr)| expr)
+                .reduce(|left, right| Expr::BinaryOp {
+                    left: Box::new(left),
+                    op: BinaryOperator::And,
+                    right: Box::new(right),
+                })
+                .map(|expr| JoinOperator::Inner(JoinConstraint::On(expr)))
+                .unwrap_or(JoinOperator::Cross);
+
+            Join {
+                relation: take(*i),
+                join_operator,
+                join_executor: JoinExecutor::NestedLoop,
+            }
+        })
+        .collect();
+
+    Select {
+        distinct,
+        projection,
+        from: TableWithJoins { relation, joins },
+        selection,
+        group_by,
+        having,
+    }
+}
+
+/// Selectivity of a filter on the rows of a single table.
+fn filter_selectivity(table: &Table, expr: &Expr) -> f64 {
+    let column = match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } => match (column_name(left), column_name(right)) {
+            (Some(column), None) | (None, Some(column)) => column,
+            _ => return DEFAULT_SELECTIVITY,
+        },
+        _ => return DEFAULT_SELECTIVITY,
+    };
+
+    1.0 / table.distinct_count(column)
+}
+
+/// Columns of the two tables compared by `a.x = b.y`.
+fn equal_columns<'b>(
+    tables: &[Table],
+    expr: &'b Expr,
+) -> Option<((usize, &'b str), (usize, &'b str))> {
+    let (left, right) = match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } => (left, right),
+        _ => return None,
+    };
+
+    let column = |expr: &'b Expr| {
+        let referred = referred_tables(tables, expr);
+
+        column_name(expr)
+            .filter(|_| referred.count_ones() == 1)
+            .map(|column| (referred.trailing_zeros() as usize, column))
+    };
+
+    match (column(left.as_ref())?, column(right.as_ref())?) {
+        ((i, _), (j, _)) if i == j => None,
+        columns => Some(columns),
+    }
+}
+
+fn column_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Identifier(ident) | Expr::CompoundIdentifier { ident, .. } => Some(ident.as_str()),
+        _ => None,
+    }
+}
+
+/// Tables whose columns the expression refers to, as bits of their positions.
+fn referred_tables(tables: &[Table], expr: &Expr) -> u32 {
+    let mut idents = Vec::new();
+    collect_expr(&mut idents, expr);
+
+    idents
+        .into_iter()
+        .flat_map(|(alias, ident)| {
+            tables
+                .iter()
+                .enumerate()
+                .filter(move |(_, table)| match alias {
+                    Some(alias) => table.alias == alias,
+                    None => table.has_column(ident),
+                })
+                .map(|(i, _)| 1_u32 << i)
+        })
+        .fold(0, |tables, table| tables | table)
+}
+
+fn split_conjuncts<'b>(expr: &'b Expr, conjuncts: &mut Vec<&'b Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjuncts(left, conjuncts);
+            split_conjuncts(right, conjuncts);
+        }
+        Expr::Nested(expr) => split_conjuncts(expr, conjuncts),
+        _ => conjuncts.push(expr),
+    }
+}
+
+fn split_owned_conjuncts(expr: Expr, conjuncts: &mut Vec<Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_owned_conjuncts(*left, conjuncts);
+            split_owned_conjuncts(*right, conjuncts);
+        }
+        Expr::Nested(expr) => split_owned_conjuncts(*expr, conjuncts),
+        _ => conjuncts.push(expr),
+    }
+}
+
+type Ident<'b> = (Option<&'b str>, &'b str);
+
+/// Identifiers referred to anywhere in the expression, including its subqueries which may be
+/// correlated with the tables.
+fn collect_expr<'b>(idents: &mut Vec<Ident<'b>>, expr: &'b Expr) {
+    match PlanExpr::from(expr) {
+        PlanExpr::None => {}
+        PlanExpr::Identifier(ident) => idents.push((None, ident)),
+        PlanExpr::CompoundIdentifier { alias, ident } => idents.push((Some(alias), ident)),
+        PlanExpr::Expr(expr) => collect_expr(idents, expr),
+        PlanExpr::TwoExprs(expr, expr2) => {
+            collect_expr(idents, expr);
+            collect_expr(idents, expr2);
+        }
+        PlanExpr::ThreeExprs(expr, expr2, expr3) => {
+            collect_expr(idents, expr);
+            collect_expr(idents, expr2);
+            collect_expr(idents, expr3);
+        }
+        PlanExpr::MultiExprs(exprs) => exprs
+            .into_iter()
+            .for_each(|expr| collect_expr(idents, expr)),
+        PlanExpr::Query(query) => collect_query(idents, query),
+        PlanExpr::QueryAndExpr { query, expr } => {
+            collect_query(idents, query);
+            collect_expr(idents, expr);
+        }
+    }
+}
+
+fn collect_query<'b>(idents: &mut Vec<Ident<'b>>, query: &'b Query) {
+    let Query {
+        with,
+        body,
+        order_by,
+        limit,
+        offset,
+    } = query;
+
+    with.iter()
+        .flat_map(|With { cte_tables, .. }| cte_tables)
+        .for_each(|Cte { query, .. }| collect_query(idents, query));
+
+    collect_set_expr(idents, body);
+
+    order_by
+        .iter()
+        .map(|OrderByExpr { expr, .. }| expr)
+        .chain(limit)
+        .chain(offset)
+        .for_each(|expr| collect_expr(idents, expr));
+}
+
+fn collect_set_expr<'b>(idents: &mut Vec<Ident<'b>>, set_expr: &'b SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => {
+            let Select {
+                projection,
+                from: TableWithJoins { relation, joins },
+                selection,
+                group_by,
+                having,
+                ..
+            } = select.as_ref();
+
+            for item in projection {
+                if let SelectItem::Expr { expr, .. } = item {
+                    collect_expr(idents, expr);
+                }
+            }
+
+            for relation in std::iter::once(relation).chain(joins.iter().map(|join| &join.relation))
+            {
+                match relation {
+                    TableFactor::Derived { subquery, .. } => collect_query(idents, subquery),
+                    TableFactor::Series { size, .. } => collect_expr(idents, size),
+                    TableFactor::Table { .. } | TableFactor::Dictionary { .. } => {}
+                }
+            }
+
+            for Join { join_operator, .. } in joins {
+                match join_operator {
+                    JoinOperator::Inner(JoinConstraint::On(expr))
+                    | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                    | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                    | JoinOperator::FullOuter(JoinConstraint::On(expr)) => {
+                        collect_expr(idents, expr)
+                    }
+                    JoinOperator::Inner(JoinConstraint::Using(columns))
+                    | JoinOperator::LeftOuter(JoinConstraint::Using(columns))
+                    | JoinOperator::RightOuter(JoinConstraint::Using(columns))
+                    | JoinOperator::FullOuter(JoinConstraint::Using(columns)) => {
+                        idents.extend(columns.iter().map(|column| (None, column.as_str())))
+                    }
+                    _ => {}
+                }
+            }
+
+            selection
+                .iter()
+                .chain(group_by)
+                .chain(having)
+                .for_each(|expr| collect_expr(idents, expr));
+        }
+        SetExpr::Values(Values(rows)) => rows
+            .iter()
+            .flatten()
+            .for_each(|expr| collect_expr(idents, expr)),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr(idents, left);
+            collect_set_expr(idents, right);
+        }
+    }
+}
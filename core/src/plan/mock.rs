@@ -9,7 +9,7 @@ use crate::store::{Index, IndexMut};
 use {
     crate::{
         data::{Key, Row, Schema},
-        executor::execute,
+        executor::{execute, ExecuteOptions},
         parse_sql::parse,
         result::{Error, MutResult, Result},
         store::{RowIter, Store, StoreMut},
@@ -29,7 +29,9 @@ pub fn run(sql: &str) -> MockStorage {
             let statement = translate(&parsed).unwrap();
 
             async move {
-                let (storage, _) = execute(storage, &statement).await.unwrap();
+                let (storage, _) = execute(storage, &statement, ExecuteOptions::default())
+                    .await
+                    .unwrap();
 
                 storage
             }
@@ -192,17 +194,20 @@ mod tests {
 
         #[cfg(feature = "index")]
         let storage = {
-            assert!(block_on(storage.scan_indexed_data("Foo", "idx_col", None, None)).is_err());
+            assert!(
+                block_on(storage.scan_indexed_data("Foo", "idx_col", None, Vec::new(), None))
+                    .is_err()
+            );
             let storage = test(storage.create_index(
                 "Foo",
                 "idx_col",
-                &OrderByExpr {
+                &[OrderByExpr {
                     expr: Expr::TypedString {
                         data_type: DataType::Boolean,
                         value: "true".to_owned(),
                     },
                     asc: None,
-                },
+                }],
             ));
             let storage = test(storage.drop_index("Foo", "idx_col"));
 
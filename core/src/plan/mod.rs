@@ -1,32 +1,76 @@
 mod context;
+mod cte;
 mod error;
 mod evaluable;
 mod expr;
 mod index;
 mod join;
+mod join_order;
+mod param;
 mod planner;
 mod primary_key;
 mod schema;
+mod semi_join;
+mod subquery;
 mod validate;
+mod view;
 
 #[cfg(test)]
 mod mock;
 
-use crate::{ast::Statement, result::Result, store::Store};
+pub(crate) use expr::PlanExpr;
+
+use {
+    crate::{ast::Statement, data::Schema, result::Result, store::Store},
+    std::collections::HashMap,
+};
 
 pub use {
-    self::validate::validate, error::*, index::plan as plan_index, join::plan as plan_join,
-    primary_key::plan as plan_primary_key, schema::fetch_schema_map,
+    self::validate::validate,
+    cte::plan as plan_cte,
+    error::*,
+    index::plan as plan_index,
+    join::plan as plan_join,
+    join_order::plan as plan_join_order,
+    param::{prepare, Prepared},
+    primary_key::plan as plan_primary_key,
+    schema::fetch_schema_map,
+    semi_join::plan as plan_semi_join,
+    subquery::plan as plan_subquery,
+    view::{check_recursion as check_view_recursion, expand as expand_views},
 };
 
 pub async fn plan(storage: &dyn Store, statement: Statement) -> Result<Statement> {
+    match statement {
+        Statement::Explain { analyze, statement } => {
+            let statement = plan_statement(storage, *statement).await?;
+
+            Ok(Statement::Explain {
+                analyze,
+                statement: Box::new(statement),
+            })
+        }
+        statement => plan_statement(storage, statement).await,
+    }
+}
+
+async fn plan_statement(storage: &dyn Store, statement: Statement) -> Result<Statement> {
+    let statement = expand_views(storage, statement).await?;
     let schema_map = fetch_schema_map(storage, &statement).await?;
 
-    let statement = validate(&schema_map, statement)?;
+    optimize(&schema_map, statement)
+}
+
+/// Validates a statement whose views are already expanded and plans how it is executed.
+fn optimize(schema_map: &HashMap<String, Schema>, statement: Statement) -> Result<Statement> {
+    let statement = validate(schema_map, statement)?;
+    let statement = plan_cte(statement);
 
-    let statement = plan_primary_key(&schema_map, statement);
-    let statement = plan_index(&schema_map, statement)?;
-    let statement = plan_join(&schema_map, statement);
+    let statement = plan_join_order(schema_map, statement);
+    let statement = plan_primary_key(schema_map, statement);
+    let statement = plan_index(schema_map, statement)?;
+    let statement = plan_join(schema_map, statement);
+    let statement = plan_semi_join(schema_map, statement);
 
-    Ok(statement)
+    plan_subquery(schema_map, statement)
 }
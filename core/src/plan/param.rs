@@ -0,0 +1,761 @@
+use {
+    super::{expand_views, fetch_schema_map, optimize, PlanError},
+    crate::{
+        ast::{
+            AstLiteral, BinaryOperator, Cte, DataType, Expr, Function, IndexItem, Join,
+            JoinConstraint, JoinExecutor, JoinOperator, OnConflict, OnConflictAction, Query,
+            Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Values, With,
+        },
+        data::{Schema, Value, View},
+        result::Result,
+        store::Store,
+    },
+    std::collections::HashMap,
+};
+
+/// Statement which contains bind parameters, `$1, $2, ..` or `?`, waiting for values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prepared {
+    statement: Statement,
+    /// Types expected by the planner, `None` accepts any value
+    param_types: Vec<Option<DataType>>,
+    /// Statement as it was given, planned again once the schemas below have changed
+    source: Statement,
+    /// Schemas of the tables the plan was made with
+    schema_map: HashMap<String, Schema>,
+    /// Views expanded into the plan
+    views: Vec<View>,
+}
+
+/// Numbers anonymous placeholders, infers the types of all bind parameters from the
+/// columns they are compared with or assigned to, and plans the statement once for all
+/// the values it is executed with.
+pub async fn prepare(storage: &dyn Store, statement: Statement) -> Result<Prepared> {
+    match statement {
+        Statement::Explain { analyze, statement } => {
+            let prepared = prepare_statement(storage, *statement).await?;
+
+            Ok(Prepared {
+                statement: Statement::Explain {
+                    analyze,
+                    statement: Box::new(prepared.statement),
+                },
+                source: Statement::Explain {
+                    analyze,
+                    statement: Box::new(prepared.source),
+                },
+                ..prepared
+            })
+        }
+        statement => prepare_statement(storage, statement).await,
+    }
+}
+
+async fn prepare_statement(storage: &dyn Store, source: Statement) -> Result<Prepared> {
+    let views = storage.fetch_all_views().await?;
+    let mut statement = expand_views(storage, source.clone()).await?;
+    let num_params = normalize(&mut statement)?;
+
+    let schema_map = fetch_schema_map(storage, &statement).await?;
+
+    let param_types = infer(&schema_map, &mut statement, num_params)?;
+    let statement = optimize(&schema_map, statement)?;
+
+    Ok(Prepared {
+        statement,
+        param_types,
+        source,
+        schema_map,
+        views,
+    })
+}
+
+impl Prepared {
+    pub fn param_types(&self) -> &[Option<DataType>] {
+        &self.param_types
+    }
+
+    /// Plans the statement again when a schema or a view it was planned with has changed, an
+    /// index created or dropped since then would otherwise be used or missed by the old plan.
+    pub async fn replan(&mut self, storage: &dyn Store) -> Result<()> {
+        if self.is_stale(storage).await? {
+            *self = prepare(storage, self.source.clone()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn is_stale(&self, storage: &dyn Store) -> Result<bool> {
+        if storage.fetch_all_views().await? != self.views {
+            return Ok(true);
+        }
+
+        for (table_name, schema) in self.schema_map.iter() {
+            if storage.fetch_schema(table_name).await?.as_ref() != Some(schema) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Returns the planned statement with every bind parameter replaced by the given value.
+    pub fn bind(&self, params: &[Value]) -> Result<Statement> {
+        if params.len() != self.param_types.len() {
+            return Err(PlanError::ParamCountMismatch {
+                expected: self.param_types.len(),
+                found: params.len(),
+            }
+            .into());
+        }
+
+        for (i, (value, data_type)) in params.iter().zip(&self.param_types).enumerate() {
+            if let Some(data_type) = data_type {
+                if value.validate_type(data_type).is_err() {
+                    return Err(PlanError::ParamTypeMismatch {
+                        index: i + 1,
+                        expected: data_type.clone(),
+                        found: value.clone(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        let mut statement = self.statement.clone();
+        walk_statement(&mut statement, &mut |expr| {
+            if let Expr::Placeholder(name) = expr {
+                let value = param_index(name)
+                    .and_then(|i| params.get(i))
+                    .ok_or_else(|| PlanError::UnsupportedPlaceholder(name.to_owned()))?;
+
+                *expr = bind_value(value)?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(statement)
+    }
+}
+
+/// Rewrites `?` into `$1, $2, ..` in the order of appearance and returns the number of
+/// parameters the statement takes.
+fn normalize(statement: &mut Statement) -> Result<usize> {
+    let mut num_anonymous = 0;
+    let mut max_position = 0;
+
+    walk_statement(statement, &mut |expr| {
+        let name = match expr {
+            Expr::Placeholder(name) => name,
+            _ => return Ok(()),
+        };
+
+        if *name == "?" {
+            if max_position > 0 {
+                return Err(PlanError::MixedPlaceholders.into());
+            }
+
+            num_anonymous += 1;
+            *name = format!("${num_anonymous}");
+            return Ok(());
+        }
+
+        if num_anonymous > 0 {
+            return Err(PlanError::MixedPlaceholders.into());
+        }
+
+        let position = param_index(name)
+            .ok_or_else(|| PlanError::UnsupportedPlaceholder(name.to_owned()))?
+            + 1;
+        max_position = max_position.max(position);
+
+        Ok(())
+    })?;
+
+    Ok(num_anonymous.max(max_position))
+}
+
+/// `$1` -> `Some(0)`
+fn param_index(name: &str) -> Option<usize> {
+    name.strip_prefix('$')
+        .and_then(|position| position.parse::<usize>().ok())
+        .and_then(|position| position.checked_sub(1))
+}
+
+/// Numbers are bound as typed literals, which keeps both the precision and the type of the value.
+fn bind_value(value: &Value) -> Result<Expr> {
+    match (Expr::try_from(value.clone())?, value.get_type()) {
+        (expr @ Expr::Literal(AstLiteral::Number(_)), Some(data_type)) => {
+            Ok(Expr::Function(Box::new(Function::Cast { expr, data_type })))
+        }
+        (expr, _) => Ok(expr),
+    }
+}
+
+fn infer(
+    schema_map: &HashMap<String, Schema>,
+    statement: &mut Statement,
+    num_params: usize,
+) -> Result<Vec<Option<DataType>>> {
+    let mut param_types = vec![None; num_params];
+    let mut record = |expr: &Expr, data_type: Option<DataType>| {
+        let index = match expr {
+            Expr::Placeholder(name) => param_index(name),
+            _ => None,
+        };
+
+        if let (Some(index), Some(data_type)) = (index, data_type) {
+            if let Some(param_type @ None) = param_types.get_mut(index) {
+                *param_type = Some(data_type);
+            }
+        }
+    };
+
+    match statement {
+        Statement::Insert {
+            table_name,
+            columns,
+            source,
+            on_conflict,
+            ..
+        } => {
+            let column_types: Vec<Option<DataType>> = schema_map
+                .get(table_name)
+                .map(|schema| match columns.is_empty() {
+                    true => schema
+                        .column_defs
+                        .iter()
+                        .map(|column_def| Some(column_def.data_type.clone()))
+                        .collect(),
+                    false => columns
+                        .iter()
+                        .map(|column| column_type(schema_map, Some(table_name.as_str()), column))
+                        .collect(),
+                })
+                .unwrap_or_else(Vec::new);
+
+            match &source.body {
+                SetExpr::Values(Values(rows)) => {
+                    for row in rows {
+                        for (expr, data_type) in row.iter().zip(&column_types) {
+                            record(expr, data_type.clone());
+                        }
+                    }
+                }
+                SetExpr::Select(select) => {
+                    for (item, data_type) in select.projection.iter().zip(&column_types) {
+                        if let SelectItem::Expr { expr, .. } = item {
+                            record(expr, data_type.clone());
+                        }
+                    }
+                }
+                SetExpr::SetOperation { .. } => {}
+            }
+
+            if let Some(OnConflict {
+                action: OnConflictAction::DoUpdate { assignments, .. },
+                ..
+            }) = on_conflict
+            {
+                for assignment in assignments {
+                    let data_type =
+                        column_type(schema_map, Some(table_name.as_str()), &assignment.id);
+
+                    record(&assignment.value, data_type);
+                }
+            }
+        }
+        Statement::Update {
+            table_name,
+            assignments,
+            ..
+        } => {
+            for assignment in assignments.iter() {
+                let data_type = column_type(schema_map, Some(table_name.as_str()), &assignment.id);
+
+                record(&assignment.value, data_type);
+            }
+        }
+        Statement::Query(Query { limit, offset, .. }) => {
+            for expr in limit.iter().chain(offset.iter()) {
+                record(expr, Some(DataType::Int));
+            }
+        }
+        _ => {}
+    }
+
+    walk_statement(statement, &mut |expr| {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op:
+                    BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq,
+                right,
+            } => {
+                record(left, expr_type(schema_map, right));
+                record(right, expr_type(schema_map, left));
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                let data_type = expr_type(schema_map, expr);
+
+                record(low, data_type.clone());
+                record(high, data_type);
+            }
+            Expr::InList { expr, list, .. } => {
+                let data_type = expr_type(schema_map, expr);
+
+                for item in list.iter() {
+                    record(item, data_type.clone());
+                }
+            }
+            Expr::Like { pattern, .. } | Expr::ILike { pattern, .. } => {
+                record(pattern, Some(DataType::Text));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    })?;
+
+    Ok(param_types)
+}
+
+/// Type of the column which `expr` refers to
+fn expr_type(schema_map: &HashMap<String, Schema>, expr: &Expr) -> Option<DataType> {
+    match expr {
+        Expr::Identifier(ident) => column_type(schema_map, None, ident),
+        Expr::CompoundIdentifier { alias, ident } => {
+            column_type(schema_map, Some(alias.as_str()), ident).or_else(|| {
+                (!schema_map.contains_key(alias))
+                    .then(|| column_type(schema_map, None, ident))
+                    .flatten()
+            })
+        }
+        Expr::Nested(expr) => expr_type(schema_map, expr),
+        _ => None,
+    }
+}
+
+/// Finds the column in `table_name`, or in every table when it is not given.
+/// Columns of the same name but different types make the type unknown.
+fn column_type(
+    schema_map: &HashMap<String, Schema>,
+    table_name: Option<&str>,
+    column: &str,
+) -> Option<DataType> {
+    let mut data_types = schema_map
+        .values()
+        .filter(|schema| table_name.map_or(true, |table_name| schema.table_name == table_name))
+        .filter_map(|schema| {
+            schema
+                .column_defs
+                .iter()
+                .find(|column_def| column_def.name == column)
+                .map(|column_def| &column_def.data_type)
+        });
+
+    let data_type = data_types.next()?;
+
+    data_types
+        .all(|other| other == data_type)
+        .then(|| data_type.clone())
+}
+
+type Visit<'a> = dyn FnMut(&mut Expr) -> Result<()> + 'a;
+
+pub(super) fn walk_statement(statement: &mut Statement, visit: &mut Visit) -> Result<()> {
+    match statement {
+        Statement::Query(query) => walk_query(query, visit),
+        Statement::Insert {
+            source,
+            on_conflict,
+            returning,
+            ..
+        } => {
+            walk_query(source, visit)?;
+
+            if let Some(OnConflict {
+                action:
+                    OnConflictAction::DoUpdate {
+                        assignments,
+                        selection,
+                    },
+                ..
+            }) = on_conflict
+            {
+                for assignment in assignments.iter_mut() {
+                    walk_expr(&mut assignment.value, visit)?;
+                }
+
+                selection
+                    .iter_mut()
+                    .try_for_each(|expr| walk_expr(expr, visit))?;
+            }
+
+            walk_projection(returning, visit)
+        }
+        Statement::Update {
+            assignments,
+            joins,
+            selection,
+            returning,
+            ..
+        } => {
+            for assignment in assignments.iter_mut() {
+                walk_expr(&mut assignment.value, visit)?;
+            }
+
+            for join in joins.iter_mut() {
+                walk_join(join, visit)?;
+            }
+
+            selection
+                .iter_mut()
+                .try_for_each(|expr| walk_expr(expr, visit))?;
+
+            walk_projection(returning, visit)
+        }
+        Statement::Delete {
+            joins,
+            selection,
+            returning,
+            ..
+        } => {
+            for join in joins.iter_mut() {
+                walk_join(join, visit)?;
+            }
+
+            selection
+                .iter_mut()
+                .try_for_each(|expr| walk_expr(expr, visit))?;
+
+            walk_projection(returning, visit)
+        }
+        Statement::CreateTable {
+            source: Some(source),
+            ..
+        } => walk_query(source, visit),
+        Statement::Explain { statement, .. } => walk_statement(statement, visit),
+        _ => Ok(()),
+    }
+}
+
+fn walk_query(query: &mut Query, visit: &mut Visit) -> Result<()> {
+    let Query {
+        with,
+        body,
+        order_by,
+        limit,
+        offset,
+    } = query;
+
+    for With { cte_tables, .. } in with.iter_mut() {
+        for Cte { query, .. } in cte_tables.iter_mut() {
+            walk_query(query, visit)?;
+        }
+    }
+
+    walk_set_expr(body, visit)?;
+
+    order_by
+        .iter_mut()
+        .map(|order_by_expr| &mut order_by_expr.expr)
+        .chain(limit.iter_mut())
+        .chain(offset.iter_mut())
+        .try_for_each(|expr| walk_expr(expr, visit))
+}
+
+fn walk_set_expr(set_expr: &mut SetExpr, visit: &mut Visit) -> Result<()> {
+    match set_expr {
+        SetExpr::Select(select) => walk_select(select, visit),
+        SetExpr::Values(Values(rows)) => rows
+            .iter_mut()
+            .flatten()
+            .try_for_each(|expr| walk_expr(expr, visit)),
+        SetExpr::SetOperation { left, right, .. } => {
+            walk_set_expr(left, visit)?;
+            walk_set_expr(right, visit)
+        }
+    }
+}
+
+fn walk_select(select: &mut Select, visit: &mut Visit) -> Result<()> {
+    let Select {
+        projection,
+        from: TableWithJoins { relation, joins },
+        selection,
+        group_by,
+        having,
+        ..
+    } = select;
+
+    walk_projection(projection, visit)?;
+    walk_table_factor(relation, visit)?;
+
+    for join in joins.iter_mut() {
+        walk_join(join, visit)?;
+    }
+
+    selection
+        .iter_mut()
+        .chain(group_by.iter_mut())
+        .chain(having.iter_mut())
+        .try_for_each(|expr| walk_expr(expr, visit))
+}
+
+fn walk_projection(projection: &mut [SelectItem], visit: &mut Visit) -> Result<()> {
+    projection.iter_mut().try_for_each(|item| match item {
+        SelectItem::Expr { expr, .. } => walk_expr(expr, visit),
+        SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => Ok(()),
+    })
+}
+
+fn walk_table_factor(table_factor: &mut TableFactor, visit: &mut Visit) -> Result<()> {
+    match table_factor {
+        TableFactor::Table {
+            index: Some(IndexItem::PrimaryKey(exprs)),
+            ..
+        } => exprs.iter_mut().try_for_each(|expr| walk_expr(expr, visit)),
+        TableFactor::Table {
+            index:
+                Some(IndexItem::NonClustered {
+                    prefix_exprs,
+                    cmp_expr,
+                    ..
+                }),
+            ..
+        } => prefix_exprs
+            .iter_mut()
+            .chain(cmp_expr.iter_mut().map(|(_, expr)| expr))
+            .try_for_each(|expr| walk_expr(expr, visit)),
+        TableFactor::Derived { subquery, .. } => walk_query(subquery, visit),
+        TableFactor::Series { size, .. } => walk_expr(size, visit),
+        TableFactor::Table { index: None, .. } | TableFactor::Dictionary { .. } => Ok(()),
+    }
+}
+
+fn walk_join(join: &mut Join, visit: &mut Visit) -> Result<()> {
+    let Join {
+        relation,
+        join_operator,
+        join_executor,
+    } = join;
+
+    walk_table_factor(relation, visit)?;
+
+    if let JoinOperator::Inner(JoinConstraint::On(expr))
+    | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+    | JoinOperator::RightOuter(JoinConstraint::On(expr))
+    | JoinOperator::FullOuter(JoinConstraint::On(expr))
+    | JoinOperator::Semi(JoinConstraint::On(expr))
+    | JoinOperator::Anti(JoinConstraint::On(expr)) = join_operator
+    {
+        walk_expr(expr, visit)?;
+    }
+
+    match join_executor {
+        JoinExecutor::NestedLoop => Ok(()),
+        JoinExecutor::Hash {
+            key_expr,
+            value_expr,
+            where_clause,
+        } => [key_expr, value_expr]
+            .into_iter()
+            .chain(where_clause.iter_mut())
+            .try_for_each(|expr| walk_expr(expr, visit)),
+    }
+}
+
+/// Visits `expr` first and then its sub expressions in the order they are written.
+fn walk_expr(expr: &mut Expr, visit: &mut Visit) -> Result<()> {
+    visit(expr)?;
+
+    let mut walk = |expr: &mut Expr| walk_expr(expr, visit);
+
+    match expr {
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier { .. }
+        | Expr::Literal(_)
+        | Expr::Placeholder(_)
+        | Expr::TypedString { .. } => Ok(()),
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Interval { expr, .. } => walk(expr),
+        Expr::InList { expr, list, .. } => {
+            walk(expr)?;
+            list.iter_mut().try_for_each(walk)
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            walk(expr)?;
+            walk_query(subquery, visit)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk(expr)?;
+            walk(low)?;
+            walk(high)
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            walk(expr)?;
+            walk(pattern)
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            walk(left)?;
+            walk(right)
+        }
+        Expr::Function(function) => function.as_exprs_mut().try_for_each(walk),
+        Expr::Aggregate(aggregate) => aggregate.as_expr_mut().map_or(Ok(()), walk),
+        Expr::Window(window) => window.as_exprs_mut().try_for_each(walk),
+        Expr::Exists { subquery, .. } | Expr::Subquery { subquery, .. } => {
+            walk_query(subquery, visit)
+        }
+        Expr::Case {
+            operand,
+            when_then,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                walk(operand)?;
+            }
+
+            for (when, then) in when_then.iter_mut() {
+                walk(when)?;
+                walk(then)?;
+            }
+
+            else_result.iter_mut().try_for_each(|expr| walk(expr))
+        }
+        Expr::ArrayIndex { obj, indexes } => {
+            walk(obj)?;
+            indexes.iter_mut().try_for_each(walk)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::prepare,
+        crate::{
+            ast::{DataType, Expr, SetExpr, Statement, ToSql},
+            data::Value,
+            parse_sql::parse,
+            plan::{mock::run, PlanError},
+            result::Result,
+            translate::translate,
+        },
+        futures::executor::block_on,
+    };
+
+    fn param_types(sql: &str) -> Result<Vec<Option<DataType>>> {
+        let storage = run("
+            CREATE TABLE Player (id INTEGER, name TEXT);
+            CREATE TABLE Score (player_id INTEGER, score FLOAT);
+        ");
+
+        let parsed = parse(sql).expect(sql).into_iter().next().unwrap();
+        let statement = translate(&parsed).unwrap();
+
+        block_on(prepare(&storage, statement)).map(|prepared| prepared.param_types().to_vec())
+    }
+
+    #[test]
+    fn infer() {
+        use DataType::*;
+
+        let test = |sql, expected: Vec<Option<DataType>>| {
+            assert_eq!(param_types(sql), Ok(expected), "{sql}");
+        };
+
+        test("SELECT * FROM Player WHERE id = $1", vec![Some(Int)]);
+        test(
+            "SELECT * FROM Player WHERE name LIKE ? AND ? < id LIMIT ?",
+            vec![Some(Text), Some(Int), Some(Int)],
+        );
+        test(
+            "SELECT * FROM Player p JOIN Score s ON p.id = s.player_id WHERE s.score BETWEEN $2 AND $3",
+            vec![None, Some(Float), Some(Float)],
+        );
+        test(
+            "SELECT * FROM Player WHERE id IN ($1, $2) OR $3 IS NULL",
+            vec![Some(Int), Some(Int), None],
+        );
+        test(
+            "INSERT INTO Player VALUES (?, ?)",
+            vec![Some(Int), Some(Text)],
+        );
+        test("INSERT INTO Score (score) VALUES (?)", vec![Some(Float)]);
+        test(
+            "UPDATE Score SET score = $1 WHERE player_id = $2",
+            vec![Some(Float), Some(Int)],
+        );
+        test("DELETE FROM Player WHERE name = $1", vec![Some(Text)]);
+        test("SELECT $1 + 1", vec![None]);
+
+        assert_eq!(
+            param_types("SELECT * FROM Player WHERE id = ? AND name = $2"),
+            Err(PlanError::MixedPlaceholders.into())
+        );
+        assert_eq!(
+            param_types("SELECT * FROM Player WHERE id = $a"),
+            Err(PlanError::UnsupportedPlaceholder("$a".to_owned()).into())
+        );
+    }
+
+    #[test]
+    fn bind() {
+        let storage = run("CREATE TABLE Player (id INTEGER, name TEXT);");
+        let parsed = parse("SELECT * FROM Player WHERE id = ? AND name = ?").unwrap();
+        let statement = translate(&parsed[0]).unwrap();
+        let prepared = block_on(prepare(&storage, statement)).unwrap();
+
+        let selection = |statement: Statement| match statement {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => select.selection.map(|expr| expr.to_sql()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let bound = prepared
+            .bind(&[Value::I64(1), Value::Str("Alice".to_owned())])
+            .map(selection);
+        assert_eq!(
+            bound,
+            Ok(Some("id = CAST(1 AS INT) AND name = 'Alice'".to_owned()))
+        );
+
+        let bound = prepared.bind(&[Value::Null, Value::Null]).map(selection);
+        assert_eq!(bound, Ok(Some("id = NULL AND name = NULL".to_owned())));
+
+        assert_eq!(
+            prepared.bind(&[Value::I64(1)]),
+            Err(PlanError::ParamCountMismatch {
+                expected: 2,
+                found: 1
+            }
+            .into())
+        );
+        assert_eq!(
+            prepared.bind(&[Value::I8(1), Value::Str("Alice".to_owned())]),
+            Err(PlanError::ParamTypeMismatch {
+                index: 1,
+                expected: DataType::Int,
+                found: Value::I8(1),
+            }
+            .into())
+        );
+        assert_eq!(Expr::Placeholder("$1".to_owned()).to_sql(), "$1");
+    }
+}
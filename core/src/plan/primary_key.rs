@@ -1,5 +1,8 @@
 use {
-    super::{context::Context, evaluable::check_expr as check_evaluable, planner::Planner},
+    super::{
+        context::Context, evaluable::check_expr as check_evaluable, index::split_conjunction,
+        planner::Planner,
+    },
     crate::{
         ast::{
             BinaryOperator, Expr, IndexItem, Query, Select, SetExpr, Statement, TableFactor,
@@ -29,16 +32,16 @@ struct PrimaryKeyPlanner<'a> {
 
 impl<'a> Planner<'a> for PrimaryKeyPlanner<'a> {
     fn query(&self, outer_context: Option<Rc<Context<'a>>>, query: Query) -> Query {
-        let body = match query.body {
-            SetExpr::Select(select) => {
-                let select = self.select(outer_context, *select);
-
-                SetExpr::Select(Box::new(select))
-            }
-            SetExpr::Values(_) => query.body,
-        };
-
-        Query { body, ..query }
+        let with = query
+            .with
+            .map(|with| self.with(outer_context.as_ref().map(Rc::clone), with));
+        let body = self.set_expr(outer_context, query.body);
+
+        Query {
+            with,
+            body,
+            ..query
+        }
     }
 
     fn get_schema(&self, name: &str) -> Option<&'a Schema> {
@@ -55,6 +58,33 @@ enum PrimaryKey {
 }
 
 impl<'a> PrimaryKeyPlanner<'a> {
+    fn set_expr(&self, outer_context: Option<Rc<Context<'a>>>, set_expr: SetExpr) -> SetExpr {
+        match set_expr {
+            SetExpr::Select(select) => {
+                let select = self.select(outer_context, *select);
+
+                SetExpr::Select(Box::new(select))
+            }
+            SetExpr::Values(_) => set_expr,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let left = self.set_expr(outer_context.as_ref().map(Rc::clone), *left);
+                let right = self.set_expr(outer_context, *right);
+
+                SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        }
+    }
+
     fn select(&self, outer_context: Option<Rc<Context<'a>>>, select: Select) -> Select {
         let current_context = self.update_context(None, &select.from.relation);
         let current_context = select
@@ -65,6 +95,31 @@ impl<'a> PrimaryKeyPlanner<'a> {
                 self.update_context(context, &join.relation)
             });
 
+        if let Some((index_item, selection)) = self.composite_primary_key(&select) {
+            let outer_context = Some(Rc::new(Context::concat(current_context, outer_context)));
+            let selection = selection.map(|expr| self.subquery_expr(outer_context, expr));
+            let from = match select.from.relation {
+                TableFactor::Table { name, alias, .. } => TableWithJoins {
+                    relation: TableFactor::Table {
+                        name,
+                        alias,
+                        index: Some(index_item),
+                    },
+                    ..select.from
+                },
+                relation => TableWithJoins {
+                    relation,
+                    ..select.from
+                },
+            };
+
+            return Select {
+                selection,
+                from,
+                ..select
+            };
+        }
+
         let (index, selection) = select
             .selection
             .map(|expr| self.expr(outer_context, current_context, expr))
@@ -98,6 +153,65 @@ impl<'a> PrimaryKeyPlanner<'a> {
         }
     }
 
+    /// Binds every column of a multi-column PRIMARY KEY to an equality condition,
+    /// returning the key lookup and the remaining conditions.
+    fn composite_primary_key(&self, select: &Select) -> Option<(IndexItem, Option<Expr>)> {
+        let (name, alias) = match &select.from.relation {
+            TableFactor::Table {
+                name,
+                alias,
+                index: None,
+            } => (name, alias),
+            _ => return None,
+        };
+        let columns = self.get_schema(name)?.primary_key.as_ref()?;
+        let table_alias = alias.as_ref().map(|alias| &alias.name).unwrap_or(name);
+        let is_key_column = |expr: &Expr, column: &str| match expr {
+            Expr::Identifier(ident) => select.from.joins.is_empty() && ident == column,
+            Expr::CompoundIdentifier { alias, ident } => alias == table_alias && ident == column,
+            _ => false,
+        };
+
+        let mut conditions = split_conjunction(select.selection.as_ref()?);
+        let mut values = Vec::with_capacity(columns.len());
+        for column in columns {
+            let (position, value) = conditions.iter().enumerate().find_map(
+                |(position, condition)| match condition {
+                    Expr::BinaryOp {
+                        left,
+                        op: BinaryOperator::Eq,
+                        right,
+                    } => {
+                        let value = if is_key_column(left, column) {
+                            right
+                        } else if is_key_column(right, column) {
+                            left
+                        } else {
+                            return None;
+                        };
+
+                        check_evaluable(None, value).then_some((position, value))
+                    }
+                    _ => None,
+                },
+            )?;
+
+            values.push(value.as_ref().clone());
+            conditions.remove(position);
+        }
+
+        let selection = conditions
+            .into_iter()
+            .cloned()
+            .reduce(|left, right| Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            });
+
+        Some((IndexItem::PrimaryKey(values), selection))
+    }
+
     fn expr(
         &self,
         outer_context: Option<Rc<Context<'a>>>,
@@ -131,7 +245,7 @@ impl<'a> PrimaryKeyPlanner<'a> {
                 && check_evaluable(current_context.as_ref().map(Rc::clone), &key)
                 && check_evaluable(None, &value) =>
             {
-                let index_item = IndexItem::PrimaryKey(*value);
+                let index_item = IndexItem::PrimaryKey(vec![*value]);
 
                 PrimaryKey::Found {
                     index_item,
@@ -220,8 +334,8 @@ mod tests {
         crate::{
             ast::{
                 AstLiteral, BinaryOperator, Expr, IndexItem, Join, JoinConstraint, JoinExecutor,
-                JoinOperator, Query, Select, SelectItem, SetExpr, Statement, TableFactor,
-                TableWithJoins, Values,
+                JoinOperator, Query, Select, SelectItem, SetExpr, Statement, TableAlias,
+                TableFactor, TableWithJoins, Values,
             },
             parse_sql::{parse, parse_expr},
             plan::{
@@ -243,6 +357,7 @@ mod tests {
 
     fn select(select: Select) -> Statement {
         Statement::Query(Query {
+            with: None,
             body: SetExpr::Select(Box::new(select)),
             limit: None,
             offset: None,
@@ -268,12 +383,13 @@ mod tests {
         let sql = "SELECT * FROM Player WHERE id = 1;";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: Vec::new(),
             },
@@ -286,12 +402,13 @@ mod tests {
         let sql = "SELECT * FROM Player WHERE 1 = id;";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: Vec::new(),
             },
@@ -304,12 +421,13 @@ mod tests {
         let sql = "SELECT * FROM Player WHERE id = 1 AND True;";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: Vec::new(),
             },
@@ -328,12 +446,13 @@ mod tests {
         ";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: Vec::new(),
             },
@@ -361,12 +480,13 @@ mod tests {
         ";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: Vec::new(),
             },
@@ -393,12 +513,13 @@ mod tests {
         let sql = "SELECT * FROM Player JOIN Badge WHERE Player.id = 1";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
                     name: "Player".to_owned(),
                     alias: None,
-                    index: Some(IndexItem::PrimaryKey(expr("1"))),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                 },
                 joins: vec![Join {
                     relation: TableFactor::Table {
@@ -419,6 +540,7 @@ mod tests {
         let sql = "SELECT * FROM Player JOIN Badge WHERE Player.id = Badge.user_id";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
@@ -450,13 +572,15 @@ mod tests {
         let actual = plan(&storage, sql);
         let expected = {
             let subquery = Query {
+                with: None,
                 body: SetExpr::Select(Box::new(Select {
+                    distinct: false,
                     projection: vec![SelectItem::Wildcard],
                     from: TableWithJoins {
                         relation: TableFactor::Table {
                             name: "Player".to_owned(),
                             alias: None,
-                            index: Some(IndexItem::PrimaryKey(expr("1"))),
+                            index: Some(IndexItem::PrimaryKey(vec![expr("1")])),
                         },
                         joins: Vec::new(),
                     },
@@ -470,6 +594,7 @@ mod tests {
             };
 
             select(Select {
+                distinct: false,
                 projection: vec![SelectItem::Wildcard],
                 from: TableWithJoins {
                     relation: TableFactor::Table {
@@ -504,7 +629,9 @@ mod tests {
         let actual = plan(&storage, sql);
         let expected = {
             let subquery = Query {
+                with: None,
                 body: SetExpr::Select(Box::new(Select {
+                    distinct: false,
                     projection: vec![SelectItem::Expr {
                         expr: Expr::Identifier("name".to_owned()),
                         label: "name".to_owned(),
@@ -527,6 +654,7 @@ mod tests {
             };
 
             select(Select {
+                distinct: false,
                 projection: vec![SelectItem::Wildcard],
                 from: TableWithJoins {
                     relation: TableFactor::Table {
@@ -539,7 +667,10 @@ mod tests {
                 selection: Some(Expr::BinaryOp {
                     left: Box::new(Expr::Identifier("name".to_owned())),
                     op: BinaryOperator::Eq,
-                    right: Box::new(Expr::Subquery(Box::new(subquery))),
+                    right: Box::new(Expr::Subquery {
+                        subquery: Box::new(subquery),
+                        cached: false,
+                    }),
                 }),
                 group_by: Vec::new(),
                 having: None,
@@ -555,7 +686,9 @@ mod tests {
         let actual = plan(&storage, sql);
         let expected = {
             let subquery = Query {
+                with: None,
                 body: SetExpr::Select(Box::new(Select {
+                    distinct: false,
                     projection: vec![SelectItem::Expr {
                         expr: Expr::Identifier("id".to_owned()),
                         label: "id".to_owned(),
@@ -578,6 +711,7 @@ mod tests {
             };
 
             select(Select {
+                distinct: false,
                 projection: vec![SelectItem::Wildcard],
                 from: TableWithJoins {
                     relation: TableFactor::Table {
@@ -602,17 +736,21 @@ mod tests {
         let actual = plan(&storage, sql);
         let expected = Statement::Delete {
             table_name: "Player".to_owned(),
+            alias: None,
+            joins: Vec::new(),
             selection: Some(Expr::BinaryOp {
                 left: Box::new(Expr::Identifier("id".to_owned())),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::Literal(AstLiteral::Number(1.into()))),
             }),
+            returning: Vec::new(),
         };
         assert_eq!(actual, expected, "delete statement:\n{sql}");
 
         let sql = "VALUES (1), (2);";
         let actual = plan(&storage, sql);
         let expected = Statement::Query(Query {
+            with: None,
             body: SetExpr::Values(Values(vec![
                 vec![Expr::Literal(AstLiteral::Number(1.into()))],
                 vec![Expr::Literal(AstLiteral::Number(2.into()))],
@@ -626,6 +764,7 @@ mod tests {
         let sql = "SELECT * FROM Player WHERE (name);";
         let actual = plan(&storage, sql);
         let expected = select(Select {
+            distinct: false,
             projection: vec![SelectItem::Wildcard],
             from: TableWithJoins {
                 relation: TableFactor::Table {
@@ -641,4 +780,76 @@ mod tests {
         });
         assert_eq!(actual, expected, "nested:\n{sql}");
     }
+
+    #[test]
+    fn composite() {
+        let storage = run("
+            CREATE TABLE Grid (
+                x INTEGER,
+                y INTEGER,
+                name TEXT,
+                PRIMARY KEY (x, y)
+            );
+        ");
+
+        let sql = "SELECT * FROM Grid WHERE y = 2 AND name = 'a' AND x = 1;";
+        let actual = plan(&storage, sql);
+        let expected = select(Select {
+            distinct: false,
+            projection: vec![SelectItem::Wildcard],
+            from: TableWithJoins {
+                relation: TableFactor::Table {
+                    name: "Grid".to_owned(),
+                    alias: None,
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1"), expr("2")])),
+                },
+                joins: Vec::new(),
+            },
+            selection: Some(expr("name = 'a'")),
+            group_by: Vec::new(),
+            having: None,
+        });
+        assert_eq!(actual, expected, "all key columns bound:\n{sql}");
+
+        let sql = "SELECT * FROM Grid G WHERE G.x = 1 AND (G.y = 2);";
+        let actual = plan(&storage, sql);
+        let expected = select(Select {
+            distinct: false,
+            projection: vec![SelectItem::Wildcard],
+            from: TableWithJoins {
+                relation: TableFactor::Table {
+                    name: "Grid".to_owned(),
+                    alias: Some(TableAlias {
+                        name: "G".to_owned(),
+                        columns: Vec::new(),
+                    }),
+                    index: Some(IndexItem::PrimaryKey(vec![expr("1"), expr("2")])),
+                },
+                joins: Vec::new(),
+            },
+            selection: None,
+            group_by: Vec::new(),
+            having: None,
+        });
+        assert_eq!(actual, expected, "compound identifiers:\n{sql}");
+
+        let sql = "SELECT * FROM Grid WHERE x = 1 AND name = 'a';";
+        let actual = plan(&storage, sql);
+        let expected = select(Select {
+            distinct: false,
+            projection: vec![SelectItem::Wildcard],
+            from: TableWithJoins {
+                relation: TableFactor::Table {
+                    name: "Grid".to_owned(),
+                    alias: None,
+                    index: None,
+                },
+                joins: Vec::new(),
+            },
+            selection: Some(expr("x = 1 AND name = 'a'")),
+            group_by: Vec::new(),
+            having: None,
+        });
+        assert_eq!(actual, expected, "partially bound key:\n{sql}");
+    }
 }
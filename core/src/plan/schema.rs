@@ -2,8 +2,8 @@ use {
     super::expr::PlanExpr,
     crate::{
         ast::{
-            Expr, Join, JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr,
-            Statement, TableFactor, TableWithJoins,
+            Cte, Expr, Join, JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr,
+            Statement, TableFactor, TableWithJoins, With,
         },
         data::Schema,
         result::Result,
@@ -45,6 +45,25 @@ pub async fn fetch_schema_map(
 
             Ok(schema_list)
         }
+        Statement::Update {
+            table_name, joins, ..
+        }
+        | Statement::Delete {
+            table_name, joins, ..
+        } => {
+            let table_schema = storage.fetch_schema(table_name).await?;
+            let join_schema_list = stream::iter(joins)
+                .then(|join| scan_join(storage, join))
+                .try_collect::<Vec<Vec<_>>>()
+                .await?;
+            let schema_list = table_schema
+                .into_iter()
+                .chain(join_schema_list.into_iter().flatten())
+                .map(|schema| (schema.table_name.clone(), schema))
+                .collect();
+
+            Ok(schema_list)
+        }
         Statement::DropTable { names, .. } => {
             stream::iter(names)
                 .map(Ok)
@@ -61,19 +80,26 @@ pub async fn fetch_schema_map(
     }
 }
 
+#[async_recursion(?Send)]
 async fn scan_query(storage: &dyn Store, query: &Query) -> Result<Vec<Schema>> {
     let Query {
+        with,
         body,
         limit,
         offset,
         ..
     } = query;
 
-    let schema_list = match body {
-        SetExpr::Select(select) => scan_select(storage, select).await?,
-        SetExpr::Values(_) => Vec::new(),
-    };
+    let cte_tables = with
+        .iter()
+        .flat_map(|With { cte_tables, .. }| cte_tables)
+        .collect::<Vec<_>>();
+    let cte_schema_list = stream::iter(&cte_tables)
+        .then(|Cte { query, .. }| scan_query(storage, query))
+        .try_collect::<Vec<_>>()
+        .await?;
 
+    let schema_list = scan_set_expr(storage, body).await?;
     let schema_list = match (limit, offset) {
         (Some(limit), Some(offset)) => schema_list
             .into_iter()
@@ -87,11 +113,39 @@ async fn scan_query(storage: &dyn Store, query: &Query) -> Result<Vec<Schema>> {
         (None, None) => schema_list,
     };
 
+    // tables shadowed by common table expressions are not planned against the storage schema
+    let schema_list = cte_schema_list
+        .into_iter()
+        .flatten()
+        .chain(schema_list)
+        .filter(|Schema { table_name, .. }| {
+            cte_tables
+                .iter()
+                .all(|Cte { alias, .. }| &alias.name != table_name)
+        })
+        .collect();
+
+    Ok(schema_list)
+}
+
+#[async_recursion(?Send)]
+async fn scan_set_expr(storage: &dyn Store, set_expr: &SetExpr) -> Result<Vec<Schema>> {
+    let schema_list = match set_expr {
+        SetExpr::Select(select) => scan_select(storage, select).await?,
+        SetExpr::Values(_) => Vec::new(),
+        SetExpr::SetOperation { left, right, .. } => scan_set_expr(storage, left)
+            .await?
+            .into_iter()
+            .chain(scan_set_expr(storage, right).await?)
+            .collect(),
+    };
+
     Ok(schema_list)
 }
 
 async fn scan_select(storage: &dyn Store, select: &Select) -> Result<Vec<Schema>> {
     let Select {
+        distinct: _,
         projection,
         from,
         selection,
@@ -153,13 +207,22 @@ async fn scan_join(storage: &dyn Store, join: &Join) -> Result<Vec<Schema>> {
     let schema_list = scan_table_factor(storage, relation).await?;
     let schema_list = match join_operator {
         JoinOperator::Inner(JoinConstraint::On(expr))
-        | JoinOperator::LeftOuter(JoinConstraint::On(expr)) => scan_expr(storage, expr)
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr))
+        | JoinOperator::Semi(JoinConstraint::On(expr))
+        | JoinOperator::Anti(JoinConstraint::On(expr)) => scan_expr(storage, expr)
             .await?
             .into_iter()
             .chain(schema_list)
             .collect(),
-        JoinOperator::Inner(JoinConstraint::None)
-        | JoinOperator::LeftOuter(JoinConstraint::None) => schema_list,
+        JoinOperator::Inner(_)
+        | JoinOperator::LeftOuter(_)
+        | JoinOperator::RightOuter(_)
+        | JoinOperator::FullOuter(_)
+        | JoinOperator::Semi(_)
+        | JoinOperator::Anti(_)
+        | JoinOperator::Cross => schema_list,
     };
 
     Ok(schema_list)
@@ -0,0 +1,464 @@
+use {
+    super::{context::Context, planner::Planner, PlanExpr},
+    crate::{
+        ast::{
+            AstLiteral, BinaryOperator, ColumnDef, Cte, DataType, Expr, Join, JoinConstraint,
+            JoinExecutor, JoinOperator, Query, Select, SelectItem, SetExpr, Statement, TableAlias,
+            TableFactor, TableWithJoins, With,
+        },
+        data::{get_alias, Schema},
+    },
+    std::{collections::HashMap, iter::once, rc::Rc},
+};
+
+/// Turns the `IN` and `EXISTS` subqueries of the `WHERE` clause into semi joins and the
+/// `NOT EXISTS` subqueries into anti joins. The table of the subquery is then read and hashed
+/// once, instead of running the subquery for each row.
+///
+/// Only a subquery on a single table whose columns are compared with the outer tables by the
+/// conjuncts of its `WHERE` clause is rewritten. `NOT IN` is left as it is, it is not the
+/// opposite of `IN` once the subquery returns `NULL`.
+pub fn plan(schema_map: &HashMap<String, Schema>, statement: Statement) -> Statement {
+    let planner = SemiJoinPlanner {
+        schema_map,
+        cte_names: Vec::new(),
+    };
+
+    match statement {
+        Statement::Query(query) => {
+            let query = planner.query(None, query);
+
+            Statement::Query(query)
+        }
+        _ => statement,
+    }
+}
+
+struct SemiJoinPlanner<'a> {
+    schema_map: &'a HashMap<String, Schema>,
+    /// Common table expressions in scope, which hide the tables of the same name
+    cte_names: Vec<String>,
+}
+
+impl<'a> Planner<'a> for SemiJoinPlanner<'a> {
+    fn query(&self, outer_context: Option<Rc<Context<'a>>>, query: Query) -> Query {
+        let Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        } = query;
+
+        let cte_names = with
+            .iter()
+            .flat_map(|With { cte_tables, .. }| cte_tables)
+            .map(|Cte { alias, .. }| alias.name.clone());
+        let planner = SemiJoinPlanner {
+            schema_map: self.schema_map,
+            cte_names: self.cte_names.iter().cloned().chain(cte_names).collect(),
+        };
+
+        let with = with.map(|with| planner.with(outer_context.as_ref().map(Rc::clone), with));
+        let body = planner.set_expr(outer_context, body);
+
+        Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        }
+    }
+
+    fn get_schema(&self, name: &str) -> Option<&'a Schema> {
+        self.schema_map.get(name)
+    }
+}
+
+impl<'a> SemiJoinPlanner<'a> {
+    fn set_expr(&self, outer_context: Option<Rc<Context<'a>>>, set_expr: SetExpr) -> SetExpr {
+        match set_expr {
+            SetExpr::Select(select) => {
+                let select = self.select(outer_context, *select);
+
+                SetExpr::Select(Box::new(select))
+            }
+            SetExpr::Values(_) => set_expr,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let left = self.set_expr(outer_context.as_ref().map(Rc::clone), *left);
+                let right = self.set_expr(outer_context, *right);
+
+                SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        }
+    }
+
+    fn select(&self, outer_context: Option<Rc<Context<'a>>>, select: Select) -> Select {
+        let Select {
+            distinct,
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+        } = select;
+
+        let relation = self.table_factor(relation);
+        let joins = joins
+            .into_iter()
+            .map(|join| Join {
+                relation: self.table_factor(join.relation),
+                ..join
+            })
+            .collect::<Vec<_>>();
+        let selection = selection.map(|expr| self.subquery_expr(outer_context, expr));
+
+        let tables = once(&relation)
+            .chain(joins.iter().map(|Join { relation, .. }| relation))
+            .map(|relation| self.table(relation))
+            .collect::<Option<Vec<_>>>();
+        let (joins, selection) = match (tables, selection) {
+            (Some(tables), Some(expr)) => self.semi_joins(&tables, joins, expr),
+            (_, selection) => (joins, selection),
+        };
+
+        Select {
+            distinct,
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+        }
+    }
+
+    fn table_factor(&self, table_factor: TableFactor) -> TableFactor {
+        match table_factor {
+            TableFactor::Derived { subquery, alias } => TableFactor::Derived {
+                subquery: self.query(None, subquery),
+                alias,
+            },
+            _ => table_factor,
+        }
+    }
+
+    fn table(&self, table_factor: &TableFactor) -> Option<Table<'a>> {
+        // aliases of the columns hide the names of the column definitions
+        let name = match table_factor {
+            TableFactor::Table { name, alias, .. }
+                if alias
+                    .iter()
+                    .all(|TableAlias { columns, .. }| columns.is_empty()) =>
+            {
+                name
+            }
+            _ => return None,
+        };
+
+        if self.cte_names.contains(name) {
+            return None;
+        }
+
+        let Schema { column_defs, .. } = self.get_schema(name)?;
+
+        Some(Table {
+            alias: get_alias(table_factor).to_owned(),
+            column_defs,
+        })
+    }
+
+    /// Moves the subqueries of the conjuncts of `WHERE` into the semi and anti joins appended
+    /// after the joins of the outer tables.
+    fn semi_joins(
+        &self,
+        tables: &[Table<'a>],
+        mut joins: Vec<Join>,
+        selection: Expr,
+    ) -> (Vec<Join>, Option<Expr>) {
+        let mut conjuncts = Vec::new();
+        split_conjuncts(selection, &mut conjuncts);
+
+        let mut aliases = tables
+            .iter()
+            .map(|Table { alias, .. }| alias.clone())
+            .collect::<Vec<_>>();
+        let mut rest = Vec::new();
+
+        for expr in conjuncts {
+            match self.semi_join(tables, &aliases, &expr) {
+                Some(join) => {
+                    aliases.push(get_alias(&join.relation).to_owned());
+                    joins.push(join);
+                }
+                None => rest.push(expr),
+            }
+        }
+
+        (joins, conjoin(rest))
+    }
+
+    fn semi_join(&self, tables: &[Table<'a>], aliases: &[String], expr: &Expr) -> Option<Join> {
+        let (subquery, in_expr, negated) = match expr {
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated: false,
+            } => (subquery, Some(expr.as_ref()), false),
+            Expr::Exists { subquery, negated } => (subquery, None, *negated),
+            _ => return None,
+        };
+
+        let select = match subquery.as_ref() {
+            Query {
+                with: None,
+                body: SetExpr::Select(select),
+                order_by,
+                limit: None,
+                offset: None,
+            } if order_by.is_empty() => select,
+            _ => return None,
+        };
+        let Select {
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+            ..
+        } = select.as_ref();
+
+        if !joins.is_empty() || !group_by.is_empty() || having.is_some() {
+            return None;
+        }
+
+        // a lookup by the index may be keyed by the outer rows, which the hash is built without
+        let inner = match relation {
+            TableFactor::Table { index: None, .. } => self.table(relation)?,
+            _ => return None,
+        };
+        if aliases.contains(&inner.alias) {
+            return None;
+        }
+
+        let scope = Scope {
+            inner: &inner,
+            outer: tables,
+        };
+
+        let mut hash_key = match (in_expr, projection.as_slice()) {
+            (Some(in_expr), [SelectItem::Expr { expr, .. }]) => {
+                Some(scope.hash_key(expr, in_expr)?)
+            }
+            (Some(_), _) => return None,
+            (None, items) => {
+                let plain = items.iter().all(|item| match item {
+                    SelectItem::Wildcard => true,
+                    SelectItem::QualifiedWildcard(alias) => alias == &inner.alias,
+                    SelectItem::Expr { expr, .. } => {
+                        matches!(expr, Expr::Literal(_)) || scope.resolve(expr).is_some()
+                    }
+                });
+                if !plain {
+                    return None;
+                }
+
+                None
+            }
+        };
+
+        let mut where_conjuncts = Vec::new();
+        let mut on_conjuncts = Vec::new();
+        if let Some(selection) = selection {
+            let mut conjuncts = Vec::new();
+            split_conjuncts(selection.clone(), &mut conjuncts);
+
+            for expr in conjuncts {
+                if !scope.refers_outer(&expr)? {
+                    where_conjuncts.push(expr);
+                    continue;
+                }
+
+                if hash_key.is_none() {
+                    hash_key = scope.equality(&expr);
+
+                    if hash_key.is_some() {
+                        continue;
+                    }
+                }
+
+                on_conjuncts.push(expr);
+            }
+        }
+
+        // without an equality the whole table hashed by a constant is matched to each row
+        let (key_expr, value_expr) = hash_key.unwrap_or_else(|| {
+            let key_expr = Expr::Literal(AstLiteral::Boolean(true));
+
+            (key_expr.clone(), key_expr)
+        });
+        let constraint = conjoin(on_conjuncts)
+            .map(JoinConstraint::On)
+            .unwrap_or(JoinConstraint::None);
+        let join_operator = match negated {
+            true => JoinOperator::Anti(constraint),
+            false => JoinOperator::Semi(constraint),
+        };
+
+        Some(Join {
+            relation: relation.clone(),
+            join_operator,
+            join_executor: JoinExecutor::Hash {
+                key_expr,
+                value_expr,
+                where_clause: conjoin(where_conjuncts),
+            },
+        })
+    }
+}
+
+struct Table<'a> {
+    alias: String,
+    column_defs: &'a [ColumnDef],
+}
+
+impl<'a> Table<'a> {
+    fn column(&self, column_name: &str) -> Option<&'a ColumnDef> {
+        self.column_defs
+            .iter()
+            .find(|ColumnDef { name, .. }| name == column_name)
+    }
+}
+
+enum Side<'a> {
+    Inner(&'a ColumnDef),
+    Outer(&'a ColumnDef),
+}
+
+/// Table of the subquery with the outer tables its columns are compared with.
+struct Scope<'a, 'b> {
+    inner: &'b Table<'a>,
+    outer: &'b [Table<'a>],
+}
+
+impl<'a> Scope<'a, '_> {
+    /// Column an identifier refers to, `None` for the columns of neither the subquery nor the
+    /// outer tables and for those of more than one outer table.
+    fn resolve(&self, expr: &Expr) -> Option<Side<'a>> {
+        match expr {
+            Expr::Identifier(ident) => {
+                if let Some(column_def) = self.inner.column(ident) {
+                    return Some(Side::Inner(column_def));
+                }
+
+                let mut column_defs = self.outer.iter().filter_map(|table| table.column(ident));
+
+                match (column_defs.next(), column_defs.next()) {
+                    (Some(column_def), None) => Some(Side::Outer(column_def)),
+                    _ => None,
+                }
+            }
+            Expr::CompoundIdentifier { alias, ident } => {
+                if alias == &self.inner.alias {
+                    return self.inner.column(ident).map(Side::Inner);
+                }
+
+                self.outer
+                    .iter()
+                    .find(|table| &table.alias == alias)
+                    .and_then(|table| table.column(ident))
+                    .map(Side::Outer)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the expression refers to the outer tables, `None` when it cannot be moved out
+    /// of the subquery.
+    fn refers_outer(&self, expr: &Expr) -> Option<bool> {
+        match PlanExpr::from(expr) {
+            PlanExpr::None => Some(false),
+            PlanExpr::Identifier(_) | PlanExpr::CompoundIdentifier { .. } => {
+                match self.resolve(expr)? {
+                    Side::Inner(_) => Some(false),
+                    Side::Outer(_) => Some(true),
+                }
+            }
+            PlanExpr::Expr(expr) => self.refers_outer(expr),
+            PlanExpr::TwoExprs(expr, expr2) => self.refers_any([expr, expr2]),
+            PlanExpr::ThreeExprs(expr, expr2, expr3) => self.refers_any([expr, expr2, expr3]),
+            PlanExpr::MultiExprs(exprs) => self.refers_any(exprs),
+            PlanExpr::Query(_) | PlanExpr::QueryAndExpr { .. } => None,
+        }
+    }
+
+    fn refers_any<'c>(&self, exprs: impl IntoIterator<Item = &'c Expr>) -> Option<bool> {
+        exprs
+            .into_iter()
+            .map(|expr| self.refers_outer(expr))
+            .try_fold(false, |refers, refers2| Some(refers2? || refers))
+    }
+
+    /// Key of the hash from `inner = outer` or `outer = inner`.
+    fn equality(&self, expr: &Expr) -> Option<(Expr, Expr)> {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } => self
+                .hash_key(left, right)
+                .or_else(|| self.hash_key(right, left)),
+            _ => None,
+        }
+    }
+
+    /// Columns of the subquery and of the outer tables of the same type, which compare equal
+    /// exactly when their keys do.
+    fn hash_key(&self, inner_expr: &Expr, outer_expr: &Expr) -> Option<(Expr, Expr)> {
+        let (inner, outer) = match (self.resolve(inner_expr)?, self.resolve(outer_expr)?) {
+            (Side::Inner(inner), Side::Outer(outer)) => (inner, outer),
+            _ => return None,
+        };
+
+        let hashable = !matches!(
+            inner.data_type,
+            DataType::Float | DataType::Map | DataType::List
+        );
+
+        (hashable && inner.data_type == outer.data_type)
+            .then(|| (inner_expr.clone(), outer_expr.clone()))
+    }
+}
+
+fn split_conjuncts(expr: Expr, conjuncts: &mut Vec<Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjuncts(*left, conjuncts);
+            split_conjuncts(*right, conjuncts);
+        }
+        _ => conjuncts.push(expr),
+    }
+}
+
+fn conjoin(conjuncts: Vec<Expr>) -> Option<Expr> {
+    conjuncts.into_iter().reduce(|left, right| Expr::BinaryOp {
+        left: Box::new(left),
+        op: BinaryOperator::And,
+        right: Box::new(right),
+    })
+}
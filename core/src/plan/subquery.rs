@@ -0,0 +1,347 @@
+use {
+    super::{param::walk_statement, PlanExpr},
+    crate::{
+        ast::{
+            Cte, Expr, Function, IndexItem, Join, JoinConstraint, JoinExecutor, JoinOperator,
+            OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableAlias, TableFactor,
+            TableWithJoins, Values, With,
+        },
+        data::{get_alias, Schema},
+        result::Result,
+    },
+    std::{collections::HashMap, iter::once},
+};
+
+/// Marks the scalar subqueries which run once per statement, their value is then reused for
+/// each row. A subquery is cached only when none of its column references resolves to the
+/// outer queries and it calls no volatile function, `NOW()`, `GENERATE_UUID()`, `NEXTVAL` or
+/// `CURRVAL`, whose value changes from one run to the next.
+pub fn plan(schema_map: &HashMap<String, Schema>, mut statement: Statement) -> Result<Statement> {
+    let scope = Scope {
+        schema_map,
+        tables: Vec::new(),
+    };
+
+    walk_statement(&mut statement, &mut |expr| {
+        if let Expr::Subquery { subquery, cached } = expr {
+            *cached = scope.query(subquery);
+        }
+
+        Ok(())
+    })?;
+
+    Ok(statement)
+}
+
+/// Table a column reference may resolve to, `columns` are `None` when they are not known
+/// before the table is read.
+#[derive(Clone)]
+struct Table<'a> {
+    alias: &'a str,
+    columns: Option<Vec<&'a str>>,
+}
+
+/// Tables of the subquery and of the queries nested in it which enclose the expressions
+/// being checked.
+#[derive(Clone)]
+struct Scope<'a> {
+    schema_map: &'a HashMap<String, Schema>,
+    tables: Vec<Table<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    /// Whether the query runs the same without the outer rows and returns the same rows on
+    /// each run.
+    fn query(&self, query: &'a Query) -> bool {
+        let Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        } = query;
+
+        with.iter()
+            .flat_map(|With { cte_tables, .. }| cte_tables)
+            .all(|Cte { query, .. }| self.query(query))
+            && self.set_expr(body, order_by)
+            && limit
+                .iter()
+                .chain(offset.iter())
+                .all(|expr| self.expr(expr))
+    }
+
+    /// `ORDER BY` of a set operation or of `VALUES` sorts by the labels, which are not
+    /// resolved here.
+    fn set_expr(&self, set_expr: &'a SetExpr, order_by: &'a [OrderByExpr]) -> bool {
+        match set_expr {
+            SetExpr::Select(select) => self.select(select, order_by),
+            SetExpr::Values(Values(rows)) => {
+                order_by.is_empty() && rows.iter().flatten().all(|expr| self.expr(expr))
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                order_by.is_empty() && self.set_expr(left, &[]) && self.set_expr(right, &[])
+            }
+        }
+    }
+
+    fn select(&self, select: &'a Select, order_by: &'a [OrderByExpr]) -> bool {
+        let Select {
+            projection,
+            from: TableWithJoins { relation, joins },
+            selection,
+            group_by,
+            having,
+            ..
+        } = select;
+
+        let relations = once(relation)
+            .chain(joins.iter().map(|Join { relation, .. }| relation))
+            .collect::<Vec<_>>();
+        if !relations
+            .iter()
+            .all(|relation| self.table_factor(*relation))
+        {
+            return false;
+        }
+
+        let mut scope = self.clone();
+        scope
+            .tables
+            .extend(relations.iter().map(|relation| self.table(*relation)));
+
+        projection.iter().all(|item| match item {
+            SelectItem::Expr { expr, .. } => scope.expr(expr),
+            SelectItem::QualifiedWildcard(alias) => scope.contains_alias(alias),
+            SelectItem::Wildcard => true,
+        }) && relations
+            .into_iter()
+            .flat_map(index_exprs)
+            .chain(joins.iter().flat_map(join_exprs))
+            .chain(selection.iter())
+            .chain(group_by.iter())
+            .chain(having.iter())
+            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+            .all(|expr| scope.expr(expr))
+    }
+
+    /// Queries and expressions of the table factor, which are run before its table is in
+    /// scope.
+    fn table_factor(&self, table_factor: &'a TableFactor) -> bool {
+        match table_factor {
+            TableFactor::Derived { subquery, .. } => self.query(subquery),
+            TableFactor::Series { size, .. } => self.expr(size),
+            TableFactor::Table { .. } | TableFactor::Dictionary { .. } => true,
+        }
+    }
+
+    fn table(&self, table_factor: &'a TableFactor) -> Table<'a> {
+        let alias = get_alias(table_factor);
+        let columns = match table_factor {
+            TableFactor::Table {
+                alias: Some(TableAlias { columns, .. }),
+                ..
+            }
+            | TableFactor::Derived {
+                alias: TableAlias { columns, .. },
+                ..
+            } if !columns.is_empty() => Some(columns.iter().map(String::as_str).collect()),
+            // common table expressions hide the schemas of the tables of the same name
+            TableFactor::Table { name, .. } => self.schema_map.get(name).map(|schema| {
+                schema
+                    .column_defs
+                    .iter()
+                    .map(|column_def| column_def.name.as_str())
+                    .collect()
+            }),
+            TableFactor::Derived { .. }
+            | TableFactor::Series { .. }
+            | TableFactor::Dictionary { .. } => None,
+        };
+
+        Table { alias, columns }
+    }
+
+    fn contains_alias(&self, target: &str) -> bool {
+        self.tables
+            .iter()
+            .any(|Table { alias, .. }| *alias == target)
+    }
+
+    /// An identifier resolves only to a known column, it may otherwise be one of the outer
+    /// queries.
+    fn contains_column(&self, target: &str) -> bool {
+        self.tables
+            .iter()
+            .filter_map(|Table { columns, .. }| columns.as_ref())
+            .flatten()
+            .any(|column| *column == target)
+    }
+
+    fn expr(&self, expr: &'a Expr) -> bool {
+        if let Expr::Function(function) = expr {
+            if matches!(
+                function.as_ref(),
+                Function::Now()
+                    | Function::GenerateUuid()
+                    | Function::Nextval(_)
+                    | Function::Currval(_)
+            ) {
+                return false;
+            }
+        }
+
+        match PlanExpr::from(expr) {
+            PlanExpr::None => true,
+            PlanExpr::Identifier(ident) => self.contains_column(ident),
+            PlanExpr::CompoundIdentifier { alias, .. } => self.contains_alias(alias),
+            PlanExpr::Expr(expr) => self.expr(expr),
+            PlanExpr::TwoExprs(expr, expr2) => self.expr(expr) && self.expr(expr2),
+            PlanExpr::ThreeExprs(expr, expr2, expr3) => {
+                self.expr(expr) && self.expr(expr2) && self.expr(expr3)
+            }
+            PlanExpr::MultiExprs(exprs) => exprs.into_iter().all(|expr| self.expr(expr)),
+            PlanExpr::Query(query) => self.query(query),
+            PlanExpr::QueryAndExpr { query, expr } => self.expr(expr) && self.query(query),
+        }
+    }
+}
+
+/// Values of the index lookup the planner moved out of `WHERE`.
+fn index_exprs(table_factor: &TableFactor) -> Vec<&Expr> {
+    match table_factor {
+        TableFactor::Table {
+            index: Some(IndexItem::PrimaryKey(exprs)),
+            ..
+        } => exprs.iter().collect(),
+        TableFactor::Table {
+            index:
+                Some(IndexItem::NonClustered {
+                    prefix_exprs,
+                    cmp_expr,
+                    ..
+                }),
+            ..
+        } => prefix_exprs
+            .iter()
+            .chain(cmp_expr.iter().map(|(_, expr)| expr))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn join_exprs(join: &Join) -> Vec<&Expr> {
+    let Join {
+        join_operator,
+        join_executor,
+        ..
+    } = join;
+
+    let constraint = match join_operator {
+        JoinOperator::Inner(JoinConstraint::On(expr))
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr))
+        | JoinOperator::Semi(JoinConstraint::On(expr))
+        | JoinOperator::Anti(JoinConstraint::On(expr)) => Some(expr),
+        _ => None,
+    };
+
+    match join_executor {
+        JoinExecutor::NestedLoop => constraint.into_iter().collect(),
+        JoinExecutor::Hash {
+            key_expr,
+            value_expr,
+            where_clause,
+        } => constraint
+            .into_iter()
+            .chain([key_expr, value_expr])
+            .chain(where_clause.iter())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::plan as plan_subquery,
+        crate::{
+            ast::Expr,
+            parse_sql::parse,
+            plan::{
+                fetch_schema_map,
+                mock::{run, MockStorage},
+                param::walk_statement,
+            },
+            translate::translate,
+        },
+        futures::executor::block_on,
+    };
+
+    /// Whether each scalar subquery is cached, in the order they are written
+    fn plan(storage: &MockStorage, sql: &str) -> Vec<bool> {
+        let parsed = parse(sql).expect(sql).into_iter().next().unwrap();
+        let statement = translate(&parsed).unwrap();
+        let schema_map = block_on(fetch_schema_map(storage, &statement)).unwrap();
+        let mut statement = plan_subquery(&schema_map, statement).unwrap();
+
+        let mut cached = Vec::new();
+        walk_statement(&mut statement, &mut |expr| {
+            if let Expr::Subquery { cached: value, .. } = expr {
+                cached.push(*value);
+            }
+
+            Ok(())
+        })
+        .unwrap();
+
+        cached
+    }
+
+    #[test]
+    fn correlated() {
+        let storage = run("
+            CREATE TABLE Item (id INTEGER, name TEXT);
+            CREATE TABLE Stock (item_id INTEGER, store TEXT);
+        ");
+
+        let sql = "SELECT * FROM Item WHERE id > (SELECT MIN(item_id) FROM Stock);";
+        assert_eq!(plan(&storage, sql), vec![true], "{sql}");
+
+        let sql = "SELECT (SELECT store FROM Stock s WHERE s.item_id = Item.id) FROM Item;";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        // `id` is not a column of Stock
+        let sql = "SELECT (SELECT store FROM Stock WHERE item_id = id) FROM Item;";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        // the inner subquery refers to the outer one only
+        let sql = "
+            SELECT (
+                SELECT MAX(item_id) FROM Stock s
+                WHERE store = (SELECT name FROM Item WHERE id = s.item_id)
+            ) FROM Item;
+        ";
+        assert_eq!(plan(&storage, sql), vec![true, false], "{sql}");
+    }
+
+    #[test]
+    fn volatile() {
+        let storage = run("CREATE TABLE Item (id INTEGER, name TEXT);");
+
+        let sql = "SELECT id, (SELECT NEXTVAL('Counter')) FROM Item;";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        let sql = "SELECT id, (SELECT CURRVAL('Counter')) FROM Item;";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        let sql = "SELECT id, (SELECT NOW()) FROM Item;";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        let sql = "SELECT id FROM Item WHERE name = (SELECT GENERATE_UUID());";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+
+        let sql = "SELECT (SELECT MAX(id) FROM Item WHERE id IN (SELECT NEXTVAL('Counter')));";
+        assert_eq!(plan(&storage, sql), vec![false], "{sql}");
+    }
+}
@@ -1,7 +1,7 @@
 use {
     super::PlanError,
     crate::{
-        ast::{Expr, SelectItem, SetExpr, Statement},
+        ast::{Expr, Join, JoinConstraint, JoinOperator, SelectItem, SetExpr, Statement},
         data::Schema,
         result::Result,
     },
@@ -22,6 +22,10 @@ pub fn validate(schema_map: &HashMap<String, Schema>, statement: Statement) -> R
                             ..
                         } = select_item
                         {
+                            if is_merged(&select.from.joins, ident) {
+                                return Ok(());
+                            }
+
                             let tables_with_given_col =
                                 schema_map.iter().filter_map(|(_, schema)| {
                                     schema.column_defs.iter().find(|col| &col.name == ident)
@@ -43,3 +47,20 @@ pub fn validate(schema_map: &HashMap<String, Schema>, statement: Statement) -> R
 
     Ok(statement)
 }
+
+/// Columns shared through `USING` or `NATURAL` are merged into one, so they are never ambiguous
+fn is_merged(joins: &[Join], ident: &str) -> bool {
+    joins.iter().any(|join| match &join.join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint)
+        | JoinOperator::Semi(constraint)
+        | JoinOperator::Anti(constraint) => match constraint {
+            JoinConstraint::Using(columns) => columns.iter().any(|column| column == ident),
+            JoinConstraint::Natural => true,
+            JoinConstraint::On(_) | JoinConstraint::None => false,
+        },
+        JoinOperator::Cross => false,
+    })
+}
@@ -0,0 +1,409 @@
+use {
+    crate::{
+        ast::{
+            Cte, Expr, Join, JoinConstraint, JoinOperator, OnConflict, OnConflictAction, Query,
+            Select, SelectItem, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins,
+            Values, With,
+        },
+        data::{View, ViewError},
+        result::Result,
+        store::Store,
+    },
+    std::collections::HashMap,
+};
+
+/// Names in scope while expanding a query.
+#[derive(Clone, Default)]
+struct Scope {
+    /// Common table expressions, which shadow the views of the same name
+    ctes: Vec<String>,
+    /// Views being expanded, a view refers to itself if its name is found here again
+    views: Vec<String>,
+}
+
+async fn fetch_views(storage: &dyn Store) -> Result<HashMap<String, View>> {
+    let views = storage
+        .fetch_all_views()
+        .await?
+        .into_iter()
+        .map(|view| (view.name.clone(), view))
+        .collect();
+
+    Ok(views)
+}
+
+/// Replaces the views referred to by the statement with derived tables of their queries.
+pub async fn expand(storage: &dyn Store, mut statement: Statement) -> Result<Statement> {
+    let views = fetch_views(storage).await?;
+    if views.is_empty() {
+        return Ok(statement);
+    }
+
+    let scope = Scope::default();
+    let mut expand = |expr: &mut Expr| expand_expr(&views, &scope, expr);
+
+    match &mut statement {
+        Statement::Query(query) => expand_query(&views, &scope, query)?,
+        Statement::Insert {
+            source,
+            on_conflict,
+            returning,
+            ..
+        } => {
+            expand_query(&views, &scope, source)?;
+
+            if let Some(OnConflict {
+                action:
+                    OnConflictAction::DoUpdate {
+                        assignments,
+                        selection,
+                    },
+                ..
+            }) = on_conflict
+            {
+                assignments
+                    .iter_mut()
+                    .map(|assignment| &mut assignment.value)
+                    .chain(selection.iter_mut())
+                    .try_for_each(&mut expand)?;
+            }
+
+            expand_projection(&views, &scope, returning)?;
+        }
+        Statement::Update {
+            assignments,
+            joins,
+            selection,
+            returning,
+            ..
+        } => {
+            for join in joins.iter_mut() {
+                expand_join(&views, &scope, join)?;
+            }
+
+            assignments
+                .iter_mut()
+                .map(|assignment| &mut assignment.value)
+                .chain(selection.iter_mut())
+                .try_for_each(&mut expand)?;
+
+            expand_projection(&views, &scope, returning)?;
+        }
+        Statement::Delete {
+            joins,
+            selection,
+            returning,
+            ..
+        } => {
+            for join in joins.iter_mut() {
+                expand_join(&views, &scope, join)?;
+            }
+
+            selection.iter_mut().try_for_each(&mut expand)?;
+
+            expand_projection(&views, &scope, returning)?;
+        }
+        Statement::CreateTable {
+            source: Some(source),
+            ..
+        } => expand_query(&views, &scope, source)?,
+        _ => {}
+    }
+
+    Ok(statement)
+}
+
+/// Checks the query of the view does not refer to the view itself, directly or through
+/// the other views.
+pub async fn check_recursion(storage: &dyn Store, view: &View) -> Result<()> {
+    let views = fetch_views(storage).await?;
+    let scope = Scope {
+        ctes: Vec::new(),
+        views: vec![view.name.clone()],
+    };
+
+    expand_query(&views, &scope, &mut view.query.clone())
+}
+
+fn expand_query(views: &HashMap<String, View>, scope: &Scope, query: &mut Query) -> Result<()> {
+    let Query {
+        with,
+        body,
+        order_by,
+        limit,
+        offset,
+    } = query;
+
+    let mut scope = scope.clone();
+
+    if let Some(With {
+        recursive,
+        cte_tables,
+    }) = with
+    {
+        for Cte { alias, query } in cte_tables.iter_mut() {
+            if *recursive {
+                scope.ctes.push(alias.name.clone());
+            }
+
+            expand_query(views, &scope, query)?;
+
+            if !*recursive {
+                scope.ctes.push(alias.name.clone());
+            }
+        }
+    }
+
+    expand_set_expr(views, &scope, body)?;
+
+    order_by
+        .iter_mut()
+        .map(|order_by_expr| &mut order_by_expr.expr)
+        .chain(limit.iter_mut())
+        .chain(offset.iter_mut())
+        .try_for_each(|expr| expand_expr(views, &scope, expr))
+}
+
+fn expand_set_expr(
+    views: &HashMap<String, View>,
+    scope: &Scope,
+    set_expr: &mut SetExpr,
+) -> Result<()> {
+    match set_expr {
+        SetExpr::Select(select) => expand_select(views, scope, select),
+        SetExpr::Values(Values(rows)) => rows
+            .iter_mut()
+            .flatten()
+            .try_for_each(|expr| expand_expr(views, scope, expr)),
+        SetExpr::SetOperation { left, right, .. } => {
+            expand_set_expr(views, scope, left)?;
+            expand_set_expr(views, scope, right)
+        }
+    }
+}
+
+fn expand_select(views: &HashMap<String, View>, scope: &Scope, select: &mut Select) -> Result<()> {
+    let Select {
+        projection,
+        from: TableWithJoins { relation, joins },
+        selection,
+        group_by,
+        having,
+        ..
+    } = select;
+
+    expand_projection(views, scope, projection)?;
+    expand_table_factor(views, scope, relation)?;
+
+    for join in joins.iter_mut() {
+        expand_join(views, scope, join)?;
+    }
+
+    selection
+        .iter_mut()
+        .chain(group_by.iter_mut())
+        .chain(having.iter_mut())
+        .try_for_each(|expr| expand_expr(views, scope, expr))
+}
+
+fn expand_projection(
+    views: &HashMap<String, View>,
+    scope: &Scope,
+    projection: &mut [SelectItem],
+) -> Result<()> {
+    projection.iter_mut().try_for_each(|item| match item {
+        SelectItem::Expr { expr, .. } => expand_expr(views, scope, expr),
+        SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => Ok(()),
+    })
+}
+
+fn expand_join(views: &HashMap<String, View>, scope: &Scope, join: &mut Join) -> Result<()> {
+    let Join {
+        relation,
+        join_operator,
+        ..
+    } = join;
+
+    expand_table_factor(views, scope, relation)?;
+
+    match join_operator {
+        JoinOperator::Inner(JoinConstraint::On(expr))
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr))
+        | JoinOperator::Semi(JoinConstraint::On(expr))
+        | JoinOperator::Anti(JoinConstraint::On(expr)) => expand_expr(views, scope, expr),
+        JoinOperator::Inner(_)
+        | JoinOperator::LeftOuter(_)
+        | JoinOperator::RightOuter(_)
+        | JoinOperator::FullOuter(_)
+        | JoinOperator::Semi(_)
+        | JoinOperator::Anti(_)
+        | JoinOperator::Cross => Ok(()),
+    }
+}
+
+fn expand_table_factor(
+    views: &HashMap<String, View>,
+    scope: &Scope,
+    table_factor: &mut TableFactor,
+) -> Result<()> {
+    let (view, alias) = match table_factor {
+        TableFactor::Table { name, alias, .. } => {
+            if scope.ctes.contains(name) {
+                return Ok(());
+            }
+
+            match views.get(name.as_str()) {
+                Some(view) if scope.views.contains(&view.name) => {
+                    return Err(ViewError::RecursiveReference(view.name.to_owned()).into());
+                }
+                Some(view) => (view, alias.take()),
+                None => return Ok(()),
+            }
+        }
+        TableFactor::Derived { subquery, .. } => return expand_query(views, scope, subquery),
+        TableFactor::Series { size, .. } => return expand_expr(views, scope, size),
+        TableFactor::Dictionary { .. } => return Ok(()),
+    };
+
+    let View {
+        name,
+        columns,
+        query,
+        ..
+    } = view;
+
+    // the query of a view does not see the common table expressions around it
+    let scope = Scope {
+        ctes: Vec::new(),
+        views: scope.views.iter().chain([name]).cloned().collect(),
+    };
+    let mut subquery = query.clone();
+    expand_query(views, &scope, &mut subquery)?;
+
+    let alias = alias.unwrap_or_else(|| TableAlias {
+        name: name.to_owned(),
+        columns: Vec::new(),
+    });
+    let columns = match alias.columns.is_empty() {
+        true => columns.clone(),
+        false => alias.columns.clone(),
+    };
+
+    *table_factor = TableFactor::Derived {
+        subquery: alias_columns(name, columns, subquery),
+        alias,
+    };
+
+    Ok(())
+}
+
+/// Wraps the query into a common table expression which takes the column aliases, labels of
+/// a derived table otherwise come from its projection.
+fn alias_columns(name: &str, columns: Vec<String>, query: Query) -> Query {
+    if columns.is_empty() {
+        return query;
+    }
+
+    let cte = Cte {
+        alias: TableAlias {
+            name: name.to_owned(),
+            columns,
+        },
+        query,
+    };
+    let select = Select {
+        distinct: false,
+        projection: vec![SelectItem::Wildcard],
+        from: TableWithJoins {
+            relation: TableFactor::Table {
+                name: name.to_owned(),
+                alias: None,
+                index: None,
+            },
+            joins: Vec::new(),
+        },
+        selection: None,
+        group_by: Vec::new(),
+        having: None,
+    };
+
+    Query {
+        with: Some(With {
+            recursive: false,
+            cte_tables: vec![cte],
+        }),
+        body: SetExpr::Select(Box::new(select)),
+        order_by: Vec::new(),
+        limit: None,
+        offset: None,
+    }
+}
+
+/// Expands the views referred to by the subqueries of the expression.
+fn expand_expr(views: &HashMap<String, View>, scope: &Scope, expr: &mut Expr) -> Result<()> {
+    let mut expand = |expr: &mut Expr| expand_expr(views, scope, expr);
+
+    match expr {
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier { .. }
+        | Expr::Literal(_)
+        | Expr::Placeholder(_)
+        | Expr::TypedString { .. } => Ok(()),
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Nested(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Interval { expr, .. } => expand(expr),
+        Expr::InList { expr, list, .. } => {
+            expand(expr)?;
+            list.iter_mut().try_for_each(expand)
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            expand(expr)?;
+            expand_query(views, scope, subquery)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            expand(expr)?;
+            expand(low)?;
+            expand(high)
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            expand(expr)?;
+            expand(pattern)
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            expand(left)?;
+            expand(right)
+        }
+        Expr::Function(function) => function.as_exprs_mut().try_for_each(expand),
+        Expr::Aggregate(aggregate) => aggregate.as_expr_mut().map_or(Ok(()), expand),
+        Expr::Window(window) => window.as_exprs_mut().try_for_each(expand),
+        Expr::Exists { subquery, .. } | Expr::Subquery { subquery, .. } => {
+            expand_query(views, scope, subquery)
+        }
+        Expr::Case {
+            operand,
+            when_then,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                expand(operand)?;
+            }
+
+            for (when, then) in when_then.iter_mut() {
+                expand(when)?;
+                expand(then)?;
+            }
+
+            else_result.iter_mut().try_for_each(|expr| expand(expr))
+        }
+        Expr::ArrayIndex { obj, indexes } => {
+            expand(obj)?;
+            indexes.iter_mut().try_for_each(expand)
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use {
     crate::{
         data::{
-            IntervalError, KeyError, LiteralError, RowError, StringExtError, TableError, ValueError,
+            IntervalError, KeyError, LiteralError, RowError, SequenceError, StringExtError,
+            TableError, ValueError, ViewError,
         },
         executor::{
-            AggregateError, AlterError, EvaluateError, ExecuteError, FetchError, SelectError,
-            SortError, UpdateError, ValidateError,
+            AggregateError, AlterError, EvaluateError, ExecuteError, ExplainError, FetchError,
+            ForeignKeyError, JoinError, SelectError, SortError, UpdateError, UpsertError,
+            ValidateError, WindowError,
         },
         plan::PlanError,
         store::{GStore, GStoreMut},
@@ -56,10 +58,14 @@ pub enum Error {
     #[error(transparent)]
     Select(#[from] SelectError),
     #[error(transparent)]
+    Join(#[from] JoinError),
+    #[error(transparent)]
     Aggregate(#[from] AggregateError),
     #[error(transparent)]
     Sort(#[from] SortError),
     #[error(transparent)]
+    Window(#[from] WindowError),
+    #[error(transparent)]
     Update(#[from] UpdateError),
     #[error(transparent)]
     Row(#[from] RowError),
@@ -68,6 +74,14 @@ pub enum Error {
     #[error(transparent)]
     Validate(#[from] ValidateError),
     #[error(transparent)]
+    ForeignKey(#[from] ForeignKeyError),
+    #[error(transparent)]
+    Upsert(#[from] UpsertError),
+    #[error(transparent)]
+    Sequence(#[from] SequenceError),
+    #[error(transparent)]
+    View(#[from] ViewError),
+    #[error(transparent)]
     Key(#[from] KeyError),
     #[error(transparent)]
     Value(#[from] ValueError),
@@ -79,6 +93,8 @@ pub enum Error {
     StringExt(#[from] StringExtError),
     #[error(transparent)]
     Plan(#[from] PlanError),
+    #[error(transparent)]
+    Explain(#[from] ExplainError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -101,18 +117,25 @@ impl PartialEq for Error {
             (Fetch(e), Fetch(e2)) => e == e2,
             (Evaluate(e), Evaluate(e2)) => e == e2,
             (Select(e), Select(e2)) => e == e2,
+            (Join(e), Join(e2)) => e == e2,
             (Aggregate(e), Aggregate(e2)) => e == e2,
             (Sort(e), Sort(e2)) => e == e2,
+            (Window(e), Window(e2)) => e == e2,
             (Update(e), Update(e2)) => e == e2,
             (Row(e), Row(e2)) => e == e2,
             (Table(e), Table(e2)) => e == e2,
             (Validate(e), Validate(e2)) => e == e2,
+            (ForeignKey(e), ForeignKey(e2)) => e == e2,
+            (Upsert(e), Upsert(e2)) => e == e2,
+            (Sequence(e), Sequence(e2)) => e == e2,
+            (View(e), View(e2)) => e == e2,
             (Key(e), Key(e2)) => e == e2,
             (Value(e), Value(e2)) => e == e2,
             (Literal(e), Literal(e2)) => e == e2,
             (Interval(e), Interval(e2)) => e == e2,
             (StringExt(e), StringExt(e2)) => e == e2,
             (Plan(e), Plan(e2)) => e == e2,
+            (Explain(e), Explain(e2)) => e == e2,
             _ => false,
         }
     }
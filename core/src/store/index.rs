@@ -45,6 +45,7 @@ pub trait Index {
         _table_name: &str,
         _index_name: &str,
         _asc: Option<bool>,
+        _prefix_values: Vec<Value>,
         _cmp_value: Option<(&IndexOperator, Value)>,
     ) -> Result<RowIter> {
         Err(Error::StorageMsg(
@@ -62,7 +63,7 @@ where
         self,
         _table_name: &str,
         _index_name: &str,
-        _column: &OrderByExpr,
+        _columns: &[OrderByExpr],
     ) -> MutResult<Self, ()> {
         let msg = "[Storage] Index::create_index is not supported".to_owned();
 
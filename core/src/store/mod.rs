@@ -21,6 +21,10 @@ cfg_if! {
     }
 }
 
+mod scan;
+
+pub use scan::{project_scan_row, ScanFilter};
+
 cfg_if! {
     if #[cfg(feature = "index")] {
         pub trait GStore: Store + Index {}
@@ -61,8 +65,8 @@ cfg_if! {
 
 use {
     crate::{
-        data::{Key, Row, Schema},
-        result::{MutResult, Result},
+        data::{Key, Row, Schema, Sequence, Statistics, View},
+        result::{Error, MutResult, Result},
     },
     async_trait::async_trait,
 };
@@ -79,6 +83,56 @@ pub trait Store {
     async fn fetch_data(&self, table_name: &str, key: &Key) -> Result<Option<Row>>;
 
     async fn scan_data(&self, table_name: &str) -> Result<RowIter>;
+
+    /// Scans the rows of the table with the columns and the filter pushed down from the query.
+    /// Only the values at the `columns` positions are read, the others may be left as `Null`.
+    /// The rows `filter` evaluates to `false` may be left out, the executor checks the rows
+    /// with the whole `WHERE` clause again.
+    async fn scan_filtered_data(
+        &self,
+        table_name: &str,
+        _columns: Option<&[usize]>,
+        _filter: Option<&ScanFilter>,
+    ) -> Result<RowIter> {
+        self.scan_data(table_name).await
+    }
+
+    /// Schemas (namespaces) created by `CREATE SCHEMA`, tables of a schema are
+    /// stored with the qualified `schema.table` name.
+    async fn fetch_namespaces(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_sequence(&self, _name: &str) -> Result<Option<Sequence>> {
+        Ok(None)
+    }
+
+    async fn fetch_all_sequences(&self) -> Result<Vec<Sequence>> {
+        Ok(Vec::new())
+    }
+
+    /// Advances the sequence and returns the new value. `NEXTVAL` is evaluated along with
+    /// the other expressions, so the value is generated through a shared reference and it
+    /// is not rolled back with the transaction.
+    async fn next_sequence_value(&self, _sequence: &Sequence) -> Result<i64> {
+        Err(Error::StorageMsg(
+            "[Storage] Store::next_sequence_value is not supported".to_owned(),
+        ))
+    }
+
+    /// Value last generated by the sequence, `None` if it has not been advanced yet.
+    async fn current_sequence_value(&self, _name: &str) -> Result<Option<i64>> {
+        Ok(None)
+    }
+
+    /// Views created by `CREATE VIEW`, stored alongside the schemas and expanded by the planner.
+    async fn fetch_view(&self, _name: &str) -> Result<Option<View>> {
+        Ok(None)
+    }
+
+    async fn fetch_all_views(&self) -> Result<Vec<View>> {
+        Ok(Vec::new())
+    }
 }
 
 /// By implementing `StoreMut` trait,
@@ -97,4 +151,60 @@ where
     async fn insert_data(self, table_name: &str, rows: Vec<(Key, Row)>) -> MutResult<Self, ()>;
 
     async fn delete_data(self, table_name: &str, keys: Vec<Key>) -> MutResult<Self, ()>;
+
+    async fn insert_namespace(self, _namespace: &str) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::insert_namespace is not supported".to_owned()),
+        ))
+    }
+
+    async fn delete_namespace(self, _namespace: &str) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::delete_namespace is not supported".to_owned()),
+        ))
+    }
+
+    /// Creates the sequence, its value starts over from `start`.
+    async fn insert_sequence(self, _sequence: &Sequence) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::insert_sequence is not supported".to_owned()),
+        ))
+    }
+
+    async fn delete_sequence(self, _name: &str) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::delete_sequence is not supported".to_owned()),
+        ))
+    }
+
+    /// Creates the view, or replaces the existing view of the same name.
+    async fn insert_view(self, _view: &View) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::insert_view is not supported".to_owned()),
+        ))
+    }
+
+    async fn delete_view(self, _name: &str) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::delete_view is not supported".to_owned()),
+        ))
+    }
+
+    /// Replaces the statistics of the table collected by `ANALYZE`, its rows are kept.
+    async fn update_statistics(
+        self,
+        _table_name: &str,
+        _statistics: &Statistics,
+    ) -> MutResult<Self, ()> {
+        Err((
+            self,
+            Error::StorageMsg("[Storage] StoreMut::update_statistics is not supported".to_owned()),
+        ))
+    }
 }
@@ -0,0 +1,39 @@
+use crate::{
+    ast::Expr,
+    data::{Row, Value},
+    executor::evaluate_stateless,
+};
+
+/// Filter pushed down to `Store::scan_filtered_data`. `expr` refers to the columns of the
+/// table by the names in `column_names`, which are in the order of the values of the rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanFilter {
+    pub column_names: Vec<String>,
+    pub expr: Expr,
+}
+
+impl ScanFilter {
+    /// Checks a scanned row, the row is kept unless the filter evaluates to `false`.
+    /// Errors are left to the executor to report.
+    pub fn check(&self, row: &Row) -> bool {
+        let evaluated = evaluate_stateless(Some((self.column_names.as_slice(), row)), &self.expr)
+            .and_then(bool::try_from);
+
+        !matches!(evaluated, Ok(false))
+    }
+}
+
+/// Copies the values at the `columns` positions of a scanned row, the others become `Null`.
+pub fn project_scan_row(columns: &[usize], row: &Row) -> Row {
+    let values = row
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, value)| match columns.contains(&index) {
+            true => value.clone(),
+            false => Value::Null,
+        })
+        .collect();
+
+    Row(values)
+}
@@ -1,19 +1,25 @@
 use {
-    super::{data_type::translate_data_type, expr::translate_expr, TranslateError},
+    super::{
+        data_type::translate_data_type, expr::translate_expr, translate_idents,
+        translate_object_name, TranslateError,
+    },
     crate::{
-        ast::{ColumnDef, ColumnOption},
-        result::Result,
+        ast::{ColumnDef, ColumnOption, DataType, Expr, ForeignKey, ReferentialAction},
+        parse_sql::{parse_generated_option, GeneratedOption},
+        result::{Error, Result},
     },
     sqlparser::ast::{
         ColumnDef as SqlColumnDef, ColumnOption as SqlColumnOption,
-        ColumnOptionDef as SqlColumnOptionDef,
+        ColumnOptionDef as SqlColumnOptionDef, DataType as SqlDataType, Ident as SqlIdent,
+        ObjectName as SqlObjectName, ReferentialAction as SqlReferentialAction,
+        TableConstraint as SqlTableConstraint,
     },
+    std::iter::once,
 };
 
 #[cfg(feature = "alter-table")]
 use {
-    super::translate_object_name, crate::ast::AlterTableOperation,
-    sqlparser::ast::AlterTableOperation as SqlAlterTableOperation,
+    crate::ast::AlterTableOperation, sqlparser::ast::AlterTableOperation as SqlAlterTableOperation,
 };
 
 #[cfg(feature = "alter-table")]
@@ -21,9 +27,25 @@ pub fn translate_alter_table_operation(
     sql_alter_table_operation: &SqlAlterTableOperation,
 ) -> Result<AlterTableOperation> {
     match sql_alter_table_operation {
-        SqlAlterTableOperation::AddColumn { column_def } => Ok(AlterTableOperation::AddColumn {
-            column_def: translate_column_def(column_def)?,
-        }),
+        SqlAlterTableOperation::AddColumn { column_def } => {
+            if let Some(SqlColumnOptionDef { option, .. }) =
+                column_def
+                    .options
+                    .iter()
+                    .find(|SqlColumnOptionDef { option, .. }| {
+                        matches!(
+                            option,
+                            SqlColumnOption::ForeignKey { .. } | SqlColumnOption::Check(_)
+                        )
+                    })
+            {
+                return Err(TranslateError::UnsupportedColumnOption(option.to_string()).into());
+            }
+
+            Ok(AlterTableOperation::AddColumn {
+                column_def: translate_column_def(column_def)?,
+            })
+        }
         SqlAlterTableOperation::DropColumn {
             column_name,
             if_exists,
@@ -59,25 +81,52 @@ pub fn translate_column_def(sql_column_def: &SqlColumnDef) -> Result<ColumnDef>
         ..
     } = sql_column_def;
 
+    let serial = is_serial(data_type);
+    let data_type = match serial {
+        true => DataType::Int,
+        false => translate_data_type(data_type)?,
+    };
+
     let nullable = !options.iter().any(|SqlColumnOptionDef { option, .. }| {
         option == &SqlColumnOption::NotNull
             || option == &SqlColumnOption::Unique { is_primary: true }
     });
 
+    let mut options = options
+        .iter()
+        .map(translate_column_option_def)
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    if serial {
+        options.insert(0, ColumnOption::Identity { always: false });
+    }
+
+    let identity = options
+        .iter()
+        .any(|option| matches!(option, ColumnOption::Identity { .. }));
+
     Ok(ColumnDef {
         name: name.value.to_owned(),
-        data_type: translate_data_type(data_type)?,
-        nullable,
-        options: options
-            .iter()
-            .map(translate_column_option_def)
-            .collect::<Result<Vec<_>>>()?
-            .into_iter()
-            .flatten()
-            .collect(),
+        data_type,
+        nullable: nullable && !identity,
+        options,
     })
 }
 
+/// `SERIAL` and `BIGSERIAL` are `INTEGER` identity columns
+fn is_serial(sql_data_type: &SqlDataType) -> bool {
+    match sql_data_type {
+        SqlDataType::Custom(SqlObjectName(idents)) => match idents.as_slice() {
+            [ident] => matches!(ident.value.to_uppercase().as_str(), "SERIAL" | "BIGSERIAL"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 /// Translate [`SqlColumnOptionDef`] to [`ColumnOption`].
 ///
 /// `sql-parser` parses column option as `{ name, option }` type,
@@ -96,8 +145,206 @@ fn translate_column_option_def(
         SqlColumnOption::Unique { .. } => {
             return Ok(vec![ColumnOption::Unique { is_primary: true }]);
         }
+        SqlColumnOption::Check(expr) => translate_expr(expr).map(ColumnOption::Check),
+        // translated along with table constraints by `translate_foreign_keys`
+        SqlColumnOption::ForeignKey { .. } => return Ok(Vec::new()),
+        SqlColumnOption::DialectSpecific(_)
+            if matches!(
+                option.to_string().to_uppercase().as_str(),
+                "AUTO_INCREMENT" | "AUTOINCREMENT"
+            ) =>
+        {
+            Ok(ColumnOption::Identity { always: false })
+        }
+        // `GENERATED` options, which `parse` takes out of `CREATE TABLE` for `sql-parser`
+        SqlColumnOption::DialectSpecific(tokens) => {
+            match parse_generated_option(tokens).map_err(|e| Error::Parser(format!("{:#?}", e)))? {
+                Some(GeneratedOption::Identity { always }) => Ok(ColumnOption::Identity { always }),
+                Some(GeneratedOption::Stored(expr)) => {
+                    translate_expr(&expr).map(ColumnOption::Generated)
+                }
+                None => Err(TranslateError::UnsupportedColumnOption(option.to_string()).into()),
+            }
+        }
         _ => Err(TranslateError::UnsupportedColumnOption(option.to_string()).into()),
     }?;
 
     Ok(vec![option])
 }
+
+/// Translate table constraints of `CREATE TABLE`.
+///
+/// Single column `PRIMARY KEY (..)` is applied to its column as a column option,
+/// and a multi-column one is returned as a composite primary key.
+pub fn translate_table_constraints(
+    column_defs: Vec<ColumnDef>,
+    sql_constraints: &[SqlTableConstraint],
+) -> Result<(Vec<ColumnDef>, Option<Vec<String>>)> {
+    let primary_keys = sql_constraints
+        .iter()
+        .filter_map(|sql_constraint| match sql_constraint {
+            SqlTableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => Some(Ok(translate_idents(columns))),
+            SqlTableConstraint::ForeignKey { .. } | SqlTableConstraint::Check { .. } => None,
+            _ => Some(Err(TranslateError::UnsupportedTableConstraint(
+                sql_constraint.to_string(),
+            )
+            .into())),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let has_primary_column = column_defs.iter().any(|ColumnDef { options, .. }| {
+        options.contains(&ColumnOption::Unique { is_primary: true })
+    });
+
+    if primary_keys.len() + usize::from(has_primary_column) > 1 {
+        return Err(TranslateError::MultiplePrimaryKeysNotAllowed.into());
+    }
+
+    let primary_key = match primary_keys.into_iter().next() {
+        Some(primary_key) => primary_key,
+        None => return Ok((column_defs, None)),
+    };
+
+    if let Some(column_name) = primary_key.iter().find(|column_name| {
+        !column_defs
+            .iter()
+            .any(|ColumnDef { name, .. }| name == *column_name)
+    }) {
+        return Err(TranslateError::PrimaryKeyColumnNotFound(column_name.to_owned()).into());
+    }
+
+    let column_defs = column_defs
+        .into_iter()
+        .map(|column_def| {
+            if !primary_key.contains(&column_def.name) {
+                return column_def;
+            }
+
+            let options = match primary_key.len() {
+                1 => column_def
+                    .options
+                    .into_iter()
+                    .chain(once(ColumnOption::Unique { is_primary: true }))
+                    .collect(),
+                _ => column_def.options,
+            };
+
+            ColumnDef {
+                nullable: false,
+                options,
+                ..column_def
+            }
+        })
+        .collect();
+
+    Ok((column_defs, (primary_key.len() > 1).then_some(primary_key)))
+}
+
+/// Translate `CHECK (..)` table constraints of `CREATE TABLE`.
+pub fn translate_checks(sql_constraints: &[SqlTableConstraint]) -> Result<Vec<Expr>> {
+    sql_constraints
+        .iter()
+        .filter_map(|sql_constraint| match sql_constraint {
+            SqlTableConstraint::Check { expr, .. } => Some(translate_expr(expr)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Translate `REFERENCES` column options and `FOREIGN KEY` table constraints of `CREATE TABLE`.
+pub fn translate_foreign_keys(
+    sql_column_defs: &[SqlColumnDef],
+    sql_constraints: &[SqlTableConstraint],
+) -> Result<Vec<ForeignKey>> {
+    let column_foreign_keys =
+        sql_column_defs
+            .iter()
+            .flat_map(|SqlColumnDef { name, options, .. }| {
+                options
+                    .iter()
+                    .filter_map(move |SqlColumnOptionDef { option, .. }| match option {
+                        SqlColumnOption::ForeignKey {
+                            foreign_table,
+                            referred_columns,
+                            on_delete,
+                            on_update,
+                        } => Some(translate_foreign_key(
+                            &[name.clone()],
+                            foreign_table,
+                            referred_columns,
+                            on_delete,
+                            on_update,
+                        )),
+                        _ => None,
+                    })
+            });
+
+    let table_foreign_keys =
+        sql_constraints
+            .iter()
+            .filter_map(|sql_constraint| match sql_constraint {
+                SqlTableConstraint::ForeignKey {
+                    columns,
+                    foreign_table,
+                    referred_columns,
+                    on_delete,
+                    on_update,
+                    ..
+                } => Some(translate_foreign_key(
+                    columns,
+                    foreign_table,
+                    referred_columns,
+                    on_delete,
+                    on_update,
+                )),
+                _ => None,
+            });
+
+    let foreign_keys = column_foreign_keys
+        .chain(table_foreign_keys)
+        .collect::<Result<Vec<_>>>()?;
+
+    for column_name in foreign_keys
+        .iter()
+        .flat_map(|foreign_key| &foreign_key.columns)
+    {
+        if !sql_column_defs
+            .iter()
+            .any(|SqlColumnDef { name, .. }| &name.value == column_name)
+        {
+            return Err(TranslateError::ForeignKeyColumnNotFound(column_name.to_owned()).into());
+        }
+    }
+
+    Ok(foreign_keys)
+}
+
+fn translate_foreign_key(
+    columns: &[SqlIdent],
+    foreign_table: &SqlObjectName,
+    referred_columns: &[SqlIdent],
+    on_delete: &Option<SqlReferentialAction>,
+    on_update: &Option<SqlReferentialAction>,
+) -> Result<ForeignKey> {
+    Ok(ForeignKey {
+        columns: translate_idents(columns),
+        referenced_table_name: translate_object_name(foreign_table)?,
+        referenced_columns: translate_idents(referred_columns),
+        on_delete: translate_referential_action(on_delete),
+        on_update: translate_referential_action(on_update),
+    })
+}
+
+fn translate_referential_action(action: &Option<SqlReferentialAction>) -> ReferentialAction {
+    match action {
+        None | Some(SqlReferentialAction::NoAction) => ReferentialAction::NoAction,
+        Some(SqlReferentialAction::Restrict) => ReferentialAction::Restrict,
+        Some(SqlReferentialAction::Cascade) => ReferentialAction::Cascade,
+        Some(SqlReferentialAction::SetNull) => ReferentialAction::SetNull,
+        Some(SqlReferentialAction::SetDefault) => ReferentialAction::SetDefault,
+    }
+}
@@ -5,15 +5,6 @@ pub enum TranslateError {
     #[error("unimplemented - select on two or more than tables are not supported")]
     TooManyTables,
 
-    #[error("unimplemented - select distinct is not supported")]
-    SelectDistinctNotSupported,
-
-    #[error("unimplemented - composite index is not supported")]
-    CompositeIndexNotSupported,
-
-    #[error("unimplemented - join on update not supported")]
-    JoinOnUpdateNotSupported,
-
     #[error("unimplemented - compound identifier on update not supported: {0}")]
     CompoundIdentOnUpdateNotSupported(String),
 
@@ -54,6 +45,21 @@ pub enum TranslateError {
     #[error("qualified wildcard is not supported - COUNT({0})")]
     QualifiedWildcardInCountNotSupported(String),
 
+    #[error("distinct wildcard is not supported - COUNT(DISTINCT *)")]
+    DistinctWildcardInCountNotSupported,
+
+    #[error("distinct is only supported in aggregate functions - {0}")]
+    DistinctInFunctionNotSupported(String),
+
+    #[error("distinct is only supported in aggregate window functions - {0}")]
+    DistinctInWindowFunctionNotSupported(String),
+
+    #[error("unsupported window function: {0}")]
+    UnsupportedWindowFunction(String),
+
+    #[error("unsupported window frame units: {0}")]
+    UnsupportedWindowFrameUnits(String),
+
     #[error("order by - NULLS (FIRST | LAST) is not supported")]
     OrderByNullsFirstOrLastNotSupported,
 
@@ -69,6 +75,9 @@ pub enum TranslateError {
     #[error("unsupported statement: {0}")]
     UnsupportedStatement(String),
 
+    #[error("unsupported on insert clause: {0}")]
+    UnsupportedOnInsert(String),
+
     #[error("unsupported expr: {0}")]
     UnsupportedExpr(String),
 
@@ -96,9 +105,6 @@ pub enum TranslateError {
     #[error("unsupported query table factor: {0}")]
     UnsupportedQueryTableFactor(String),
 
-    #[error("unsupported join constraint: {0}")]
-    UnsupportedJoinConstraint(String),
-
     #[error("unsupported join operator: {0}")]
     UnsupportedJoinOperator(String),
 
@@ -108,6 +114,18 @@ pub enum TranslateError {
     #[error("unsupported alter table operation: {0}")]
     UnsupportedAlterTableOperation(String),
 
+    #[error("unsupported table constraint: {0}")]
+    UnsupportedTableConstraint(String),
+
+    #[error("multiple primary keys for table are not allowed")]
+    MultiplePrimaryKeysNotAllowed,
+
+    #[error("primary key column not found: {0}")]
+    PrimaryKeyColumnNotFound(String),
+
+    #[error("foreign key column not found: {0}")]
+    ForeignKeyColumnNotFound(String),
+
     #[error("unsupported table factor: {0}")]
     UnsupportedTableFactor(String),
 
@@ -11,7 +11,7 @@ use {
         result::Result,
         translate::function::translate_trim,
     },
-    sqlparser::ast::{Expr as SqlExpr, OrderByExpr as SqlOrderByExpr},
+    sqlparser::ast::{Expr as SqlExpr, OrderByExpr as SqlOrderByExpr, Value as SqlValue},
 };
 
 /// # Description
@@ -25,14 +25,14 @@ use {
 pub fn translate_expr(sql_expr: &SqlExpr) -> Result<Expr> {
     match sql_expr {
         SqlExpr::Identifier(ident) => Ok(Expr::Identifier(ident.value.clone())),
-        SqlExpr::CompoundIdentifier(idents) => (idents.len() == 2)
-            .then(|| Expr::CompoundIdentifier {
-                alias: idents[0].value.clone(),
-                ident: idents[1].value.clone(),
-            })
-            .ok_or_else(|| {
-                TranslateError::UnsupportedExpr(translate_idents(idents).join(".")).into()
+        // `schema.table.column` refers to a column of the qualified table
+        SqlExpr::CompoundIdentifier(idents) => match idents.split_last() {
+            Some((ident, alias)) if matches!(alias.len(), 1 | 2) => Ok(Expr::CompoundIdentifier {
+                alias: translate_idents(alias).join("."),
+                ident: ident.value.clone(),
             }),
+            _ => Err(TranslateError::UnsupportedExpr(translate_idents(idents).join(".")).into()),
+        },
         SqlExpr::IsNull(expr) => translate_expr(expr).map(Box::new).map(Expr::IsNull),
         SqlExpr::IsNotNull(expr) => translate_expr(expr).map(Box::new).map(Expr::IsNotNull),
         SqlExpr::InList {
@@ -95,6 +95,7 @@ pub fn translate_expr(sql_expr: &SqlExpr) -> Result<Expr> {
         }),
         SqlExpr::Extract { field, expr } => translate_extract(field, expr),
         SqlExpr::Nested(expr) => translate_expr(expr).map(Box::new).map(Expr::Nested),
+        SqlExpr::Value(SqlValue::Placeholder(name)) => Ok(Expr::Placeholder(name.to_owned())),
         SqlExpr::Value(value) => translate_ast_literal(value).map(Expr::Literal),
         SqlExpr::TypedString { data_type, value } => Ok(Expr::TypedString {
             data_type: translate_data_type(data_type)?,
@@ -110,7 +111,10 @@ pub fn translate_expr(sql_expr: &SqlExpr) -> Result<Expr> {
             subquery: translate_query(subquery).map(Box::new)?,
             negated: *negated,
         }),
-        SqlExpr::Subquery(query) => translate_query(query).map(Box::new).map(Expr::Subquery),
+        SqlExpr::Subquery(query) => Ok(Expr::Subquery {
+            subquery: translate_query(query).map(Box::new)?,
+            cached: false,
+        }),
         SqlExpr::Case {
             operand,
             conditions,
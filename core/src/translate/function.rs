@@ -1,17 +1,22 @@
 use {
     super::{
         ast_literal::{translate_datetime_field, translate_trim_where_field},
-        expr::translate_expr,
+        expr::{translate_expr, translate_order_by_expr},
         translate_data_type, translate_object_name, TranslateError,
     },
     crate::{
-        ast::{Aggregate, CountArgExpr, Expr, Function},
+        ast::{
+            Aggregate, AggregateFunction, CountArgExpr, Expr, Function, Window, WindowFrame,
+            WindowFrameBound, WindowFrameUnits, WindowFunction, WindowSpec,
+        },
         result::Result,
     },
     sqlparser::ast::{
         DataType, DateTimeField as SqlDateTimeField, Expr as SqlExpr, Function as SqlFunction,
         FunctionArg as SqlFunctionArg, FunctionArgExpr as SqlFunctionArgExpr,
-        TrimWhereField as SqlTrimWhereField,
+        TrimWhereField as SqlTrimWhereField, WindowFrame as SqlWindowFrame,
+        WindowFrameBound as SqlWindowFrameBound, WindowFrameUnits as SqlWindowFrameUnits,
+        WindowSpec as SqlWindowSpec,
     },
 };
 
@@ -119,15 +124,17 @@ fn translate_function_one_arg<T: FnOnce(Expr) -> Function>(
         .map(Expr::Function)
 }
 
-fn translate_aggregate_one_arg<T: FnOnce(Expr) -> Aggregate>(
+fn translate_aggregate_one_arg<T: FnOnce(Expr) -> AggregateFunction>(
     func: T,
     args: Vec<&SqlExpr>,
     name: String,
+    distinct: bool,
 ) -> Result<Expr> {
     check_len(name, args.len(), 1)?;
 
     translate_expr(args[0])
         .map(func)
+        .map(|func| Aggregate::new(func, distinct))
         .map(Box::new)
         .map(Expr::Aggregate)
 }
@@ -166,9 +173,19 @@ pub fn translate_function_arg_exprs(
 }
 
 pub fn translate_function(sql_function: &SqlFunction) -> Result<Expr> {
-    let SqlFunction { name, args, .. } = sql_function;
+    let SqlFunction {
+        name,
+        args,
+        distinct,
+        over,
+        ..
+    } = sql_function;
     let name = translate_object_name(name)?.to_uppercase();
 
+    if let Some(over) = over {
+        return translate_window(sql_function, name, over);
+    }
+
     let function_arg_exprs = args
         .iter()
         .map(|arg| match arg {
@@ -193,18 +210,28 @@ pub fn translate_function(sql_function: &SqlFunction) -> Result<Expr> {
             SqlFunctionArgExpr::Wildcard => CountArgExpr::Wildcard,
         };
 
-        return Ok(Expr::Aggregate(Box::new(Aggregate::Count(count_arg))));
+        if *distinct && count_arg == CountArgExpr::Wildcard {
+            return Err(TranslateError::DistinctWildcardInCountNotSupported.into());
+        }
+
+        let count = Aggregate::new(AggregateFunction::Count(count_arg), *distinct);
+
+        return Ok(Expr::Aggregate(Box::new(count)));
     }
 
     let args = translate_function_arg_exprs(function_arg_exprs)?;
+    let distinct = *distinct;
 
     match name.as_str() {
-        "SUM" => translate_aggregate_one_arg(Aggregate::Sum, args, name),
-        "MIN" => translate_aggregate_one_arg(Aggregate::Min, args, name),
-        "MAX" => translate_aggregate_one_arg(Aggregate::Max, args, name),
-        "AVG" => translate_aggregate_one_arg(Aggregate::Avg, args, name),
-        "VARIANCE" => translate_aggregate_one_arg(Aggregate::Variance, args, name),
-        "STDEV" => translate_aggregate_one_arg(Aggregate::Stdev, args, name),
+        "SUM" => translate_aggregate_one_arg(AggregateFunction::Sum, args, name, distinct),
+        "MIN" => translate_aggregate_one_arg(AggregateFunction::Min, args, name, distinct),
+        "MAX" => translate_aggregate_one_arg(AggregateFunction::Max, args, name, distinct),
+        "AVG" => translate_aggregate_one_arg(AggregateFunction::Avg, args, name, distinct),
+        "VARIANCE" => {
+            translate_aggregate_one_arg(AggregateFunction::Variance, args, name, distinct)
+        }
+        "STDEV" => translate_aggregate_one_arg(AggregateFunction::Stdev, args, name, distinct),
+        _ if distinct => Err(TranslateError::DistinctInFunctionNotSupported(name).into()),
         "CONCAT" => {
             check_len_min(name, args.len(), 1)?;
             let exprs = args
@@ -407,6 +434,8 @@ pub fn translate_function(sql_function: &SqlFunction) -> Result<Expr> {
         "ABS" => translate_function_one_arg(Function::Abs, args, name),
         "SIGN" => translate_function_one_arg(Function::Sign, args, name),
         "GENERATE_UUID" => translate_function_zero_arg(Function::GenerateUuid(), args, name),
+        "NEXTVAL" => translate_function_one_arg(Function::Nextval, args, name),
+        "CURRVAL" => translate_function_one_arg(Function::Currval, args, name),
         "FORMAT" => {
             check_len(name, args.len(), 2)?;
 
@@ -458,3 +487,154 @@ pub fn translate_function(sql_function: &SqlFunction) -> Result<Expr> {
         _ => Err(TranslateError::UnsupportedFunction(name).into()),
     }
 }
+
+fn translate_window(
+    sql_function: &SqlFunction,
+    name: String,
+    over: &SqlWindowSpec,
+) -> Result<Expr> {
+    let SqlFunction { args, distinct, .. } = sql_function;
+
+    let aggregate = matches!(
+        name.as_str(),
+        "COUNT" | "SUM" | "MIN" | "MAX" | "AVG" | "VARIANCE" | "STDEV"
+    );
+    if *distinct && !aggregate {
+        return Err(TranslateError::DistinctInWindowFunctionNotSupported(name).into());
+    }
+
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            SqlFunctionArg::Named { .. } => {
+                Err(TranslateError::NamedFunctionArgNotSupported.into())
+            }
+            SqlFunctionArg::Unnamed(arg_expr) => Ok(arg_expr),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let func = match name.as_str() {
+        "ROW_NUMBER" | "RANK" | "DENSE_RANK" => {
+            check_len(name.clone(), args.len(), 0)?;
+
+            match name.as_str() {
+                "ROW_NUMBER" => WindowFunction::RowNumber,
+                "RANK" => WindowFunction::Rank,
+                _ => WindowFunction::DenseRank,
+            }
+        }
+        "LAG" | "LEAD" => {
+            check_len_range(name.clone(), args.len(), 1, 3)?;
+
+            let args = translate_function_arg_exprs(args)?;
+            let expr = translate_expr(args[0])?;
+            let offset = args.get(1).map(|arg| translate_expr(arg)).transpose()?;
+            let default = args.get(2).map(|arg| translate_expr(arg)).transpose()?;
+
+            match name.as_str() {
+                "LAG" => WindowFunction::Lag {
+                    expr,
+                    offset,
+                    default,
+                },
+                _ => WindowFunction::Lead {
+                    expr,
+                    offset,
+                    default,
+                },
+            }
+        }
+        "FIRST_VALUE" | "LAST_VALUE" => {
+            check_len(name.clone(), args.len(), 1)?;
+
+            let args = translate_function_arg_exprs(args)?;
+            let expr = translate_expr(args[0])?;
+
+            match name.as_str() {
+                "FIRST_VALUE" => WindowFunction::FirstValue(expr),
+                _ => WindowFunction::LastValue(expr),
+            }
+        }
+        "COUNT" | "SUM" | "MIN" | "MAX" | "AVG" | "VARIANCE" | "STDEV" => {
+            let sql_function = SqlFunction {
+                over: None,
+                ..sql_function.clone()
+            };
+
+            match translate_function(&sql_function)? {
+                Expr::Aggregate(aggr) => WindowFunction::Aggregate(*aggr),
+                _ => return Err(TranslateError::UnsupportedWindowFunction(name).into()),
+            }
+        }
+        _ => return Err(TranslateError::UnsupportedWindowFunction(name).into()),
+    };
+
+    let spec = translate_window_spec(over)?;
+
+    Ok(Expr::Window(Box::new(Window { func, spec })))
+}
+
+fn translate_window_spec(sql_window_spec: &SqlWindowSpec) -> Result<WindowSpec> {
+    let SqlWindowSpec {
+        partition_by,
+        order_by,
+        window_frame,
+    } = sql_window_spec;
+
+    let partition_by = partition_by
+        .iter()
+        .map(translate_expr)
+        .collect::<Result<Vec<_>>>()?;
+    let order_by = order_by
+        .iter()
+        .map(translate_order_by_expr)
+        .collect::<Result<Vec<_>>>()?;
+    let frame = window_frame
+        .as_ref()
+        .map(translate_window_frame)
+        .transpose()?;
+
+    Ok(WindowSpec {
+        partition_by,
+        order_by,
+        frame,
+    })
+}
+
+fn translate_window_frame(sql_window_frame: &SqlWindowFrame) -> Result<WindowFrame> {
+    let SqlWindowFrame {
+        units,
+        start_bound,
+        end_bound,
+    } = sql_window_frame;
+
+    let units = match units {
+        SqlWindowFrameUnits::Rows => WindowFrameUnits::Rows,
+        SqlWindowFrameUnits::Range => WindowFrameUnits::Range,
+        SqlWindowFrameUnits::Groups => {
+            return Err(TranslateError::UnsupportedWindowFrameUnits(units.to_string()).into());
+        }
+    };
+
+    let translate_bound = |bound: &SqlWindowFrameBound| -> Result<WindowFrameBound> {
+        let translate_offset = |offset: &Option<Box<SqlExpr>>| {
+            offset.as_ref().map(|expr| translate_expr(expr)).transpose()
+        };
+
+        Ok(match bound {
+            SqlWindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            SqlWindowFrameBound::Preceding(offset) => {
+                WindowFrameBound::Preceding(translate_offset(offset)?)
+            }
+            SqlWindowFrameBound::Following(offset) => {
+                WindowFrameBound::Following(translate_offset(offset)?)
+            }
+        })
+    };
+
+    Ok(WindowFrame {
+        units,
+        start: translate_bound(start_bound)?,
+        end: end_bound.as_ref().map(translate_bound).transpose()?,
+    })
+}
@@ -17,19 +17,80 @@ pub use self::{
 
 #[cfg(feature = "alter-table")]
 use ddl::translate_alter_table_operation;
+use ddl::{translate_checks, translate_foreign_keys, translate_table_constraints};
+use query::{translate_join, translate_table_factor};
 use sqlparser::ast::{TableFactor, TableWithJoins};
 
 use {
     crate::{
-        ast::{Assignment, Statement, Variable},
+        ast::{
+            Assignment, Join, JoinExecutor, JoinOperator, OnConflict, OnConflictAction, SelectItem,
+            Statement, Variable,
+        },
+        parse_sql::{parse_extended, ParsedStatement},
         result::Result,
     },
     sqlparser::ast::{
-        Assignment as SqlAssignment, Ident as SqlIdent, ObjectName as SqlObjectName,
-        ObjectType as SqlObjectType, Statement as SqlStatement,
+        Assignment as SqlAssignment, Ident as SqlIdent, Join as SqlJoin,
+        ObjectName as SqlObjectName, ObjectType as SqlObjectType, OnConflict as SqlOnConflict,
+        OnConflictAction as SqlOnConflictAction, OnInsert as SqlOnInsert,
+        SchemaName as SqlSchemaName, SelectItem as SqlSelectItem, Statement as SqlStatement,
+        TableAlias as SqlTableAlias,
     },
+    std::iter::once,
 };
 
+/// Parses and translates the statements of `sql`, including `CREATE SEQUENCE`,
+/// `DROP SEQUENCE` and `INSERT .. OVERRIDING SYSTEM VALUE` which
+/// [`parse`](crate::parse_sql::parse) leaves to `sql-parser`.
+pub fn translate_sql<Sql: AsRef<str>>(sql: Sql) -> Result<Vec<Statement>> {
+    parse_extended(sql)?.iter().map(translate_parsed).collect()
+}
+
+fn translate_parsed(parsed: &ParsedStatement) -> Result<Statement> {
+    match parsed {
+        ParsedStatement::Sql(sql_statement) => translate(sql_statement),
+        ParsedStatement::InsertOverridingSystemValue(sql_statement) => {
+            match translate(sql_statement)? {
+                Statement::Insert {
+                    table_name,
+                    columns,
+                    source,
+                    on_conflict,
+                    returning,
+                    ..
+                } => Ok(Statement::Insert {
+                    table_name,
+                    columns,
+                    overriding_system_value: true,
+                    source,
+                    on_conflict,
+                    returning,
+                }),
+                _ => Err(TranslateError::UnsupportedStatement(sql_statement.to_string()).into()),
+            }
+        }
+        ParsedStatement::CreateSequence {
+            if_not_exists,
+            name,
+            increment,
+            start,
+        } => Ok(Statement::CreateSequence {
+            if_not_exists: *if_not_exists,
+            name: translate_object_name(name)?,
+            increment: increment.as_ref().map(translate_expr).transpose()?,
+            start: start.as_ref().map(translate_expr).transpose()?,
+        }),
+        ParsedStatement::DropSequence { if_exists, names } => Ok(Statement::DropSequence {
+            if_exists: *if_exists,
+            names: names
+                .iter()
+                .map(translate_object_name)
+                .collect::<Result<Vec<_>>>()?,
+        }),
+    }
+}
+
 pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
     match sql_statement {
         SqlStatement::Query(query) => translate_query(query).map(Statement::Query),
@@ -37,53 +98,92 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
             table_name,
             columns,
             source,
+            on,
+            returning,
             ..
         } => Ok(Statement::Insert {
             table_name: translate_object_name(table_name)?,
             columns: translate_idents(columns),
+            overriding_system_value: false,
             source: translate_query(source)?,
+            on_conflict: on.as_ref().map(translate_on_insert).transpose()?,
+            returning: translate_returning(returning)?,
         }),
         SqlStatement::Update {
             table,
             assignments,
+            from,
             selection,
+            returning,
             ..
-        } => Ok(Statement::Update {
-            table_name: translate_table_with_join(table)?,
-            assignments: assignments
-                .iter()
-                .map(translate_assignment)
-                .collect::<Result<_>>()?,
-            selection: selection.as_ref().map(translate_expr).transpose()?,
-        }),
+        } => {
+            let (table_name, alias, mut joins) = translate_table_with_join(table)?;
+            if let Some(from) = from {
+                joins.extend(translate_from(&from.relation, &from.joins)?);
+            }
+
+            Ok(Statement::Update {
+                table_name,
+                alias,
+                assignments: assignments
+                    .iter()
+                    .map(translate_assignment)
+                    .collect::<Result<_>>()?,
+                joins,
+                selection: selection.as_ref().map(translate_expr).transpose()?,
+                returning: translate_returning(returning)?,
+            })
+        }
         SqlStatement::Delete {
-            table_name: TableFactor::Table {
-                name: table_name, ..
-            },
+            table_name:
+                TableFactor::Table {
+                    name: table_name,
+                    alias,
+                    ..
+                },
+            using,
             selection,
+            returning,
             ..
         } => Ok(Statement::Delete {
             table_name: translate_object_name(table_name)?,
+            alias: alias.as_ref().map(translate_target_alias),
+            joins: match using {
+                Some(using) => translate_from(using, &[])?,
+                None => Vec::new(),
+            },
             selection: selection.as_ref().map(translate_expr).transpose()?,
+            returning: translate_returning(returning)?,
         }),
         SqlStatement::CreateTable {
             if_not_exists,
             name,
             columns,
+            constraints,
             query,
             ..
-        } => Ok(Statement::CreateTable {
-            if_not_exists: *if_not_exists,
-            name: translate_object_name(name)?,
-            columns: columns
+        } => {
+            let foreign_keys = translate_foreign_keys(columns, constraints)?;
+            let checks = translate_checks(constraints)?;
+            let columns = columns
                 .iter()
                 .map(translate_column_def)
-                .collect::<Result<_>>()?,
-            source: match query {
-                Some(v) => Some(translate_query(v).map(Box::new)?),
-                None => None,
-            },
-        }),
+                .collect::<Result<_>>()?;
+            let (columns, primary_key) = translate_table_constraints(columns, constraints)?;
+
+            Ok(Statement::CreateTable {
+                if_not_exists: *if_not_exists,
+                name: translate_object_name(name)?,
+                columns,
+                primary_key,
+                foreign_keys,
+                checks,
+                source: match query {
+                    Some(v) => Some(translate_query(v).map(Box::new)?),
+                    None => None,
+                },
+            })
+        }
         #[cfg(feature = "alter-table")]
         SqlStatement::AlterTable {
             name, operation, ..
@@ -91,6 +191,27 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
             name: translate_object_name(name)?,
             operation: translate_alter_table_operation(operation)?,
         }),
+        SqlStatement::CreateSchema {
+            schema_name: SqlSchemaName::Simple(name),
+            if_not_exists,
+        } => Ok(Statement::CreateSchema {
+            if_not_exists: *if_not_exists,
+            name: translate_namespace(name)?,
+        }),
+        SqlStatement::Drop {
+            object_type: SqlObjectType::Schema,
+            if_exists,
+            names,
+            cascade,
+            ..
+        } => Ok(Statement::DropSchema {
+            if_exists: *if_exists,
+            names: names
+                .iter()
+                .map(translate_namespace)
+                .collect::<Result<Vec<_>>>()?,
+            cascade: *cascade,
+        }),
         SqlStatement::Drop {
             object_type: SqlObjectType::Table,
             if_exists,
@@ -103,6 +224,31 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
                 .map(translate_object_name)
                 .collect::<Result<Vec<_>>>()?,
         }),
+        SqlStatement::CreateView {
+            or_replace,
+            materialized: false,
+            name,
+            columns,
+            query,
+            ..
+        } => Ok(Statement::CreateView {
+            or_replace: *or_replace,
+            name: translate_object_name(name)?,
+            columns: columns.iter().map(|ident| ident.value.to_owned()).collect(),
+            query: translate_query(query).map(Box::new)?,
+        }),
+        SqlStatement::Drop {
+            object_type: SqlObjectType::View,
+            if_exists,
+            names,
+            ..
+        } => Ok(Statement::DropView {
+            if_exists: *if_exists,
+            names: names
+                .iter()
+                .map(translate_object_name)
+                .collect::<Result<Vec<_>>>()?,
+        }),
         #[cfg(feature = "index")]
         SqlStatement::CreateIndex {
             name,
@@ -110,10 +256,6 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
             columns,
             ..
         } => {
-            if columns.len() > 1 {
-                return Err(TranslateError::CompositeIndexNotSupported.into());
-            }
-
             let name = translate_object_name(name)?;
 
             if name.to_uppercase() == "PRIMARY" {
@@ -123,7 +265,10 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
             Ok(Statement::CreateIndex {
                 name,
                 table_name: translate_object_name(table_name)?,
-                column: translate_order_by_expr(&columns[0])?,
+                columns: columns
+                    .iter()
+                    .map(translate_order_by_expr)
+                    .collect::<Result<_>>()?,
             })
         }
         #[cfg(feature = "index")]
@@ -136,13 +281,15 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
                 return Err(TranslateError::TooManyParamsInDropIndex.into());
             }
 
-            let object_name = &names[0].0;
-            if object_name.len() != 2 {
-                return Err(TranslateError::InvalidParamsInDropIndex.into());
-            }
-
-            let table_name = object_name[0].value.to_owned();
-            let name = object_name[1].value.to_owned();
+            let (name, table_name) = match names[0].0.split_last() {
+                Some((name, table_name)) if matches!(table_name.len(), 1 | 2) => (
+                    name.value.to_owned(),
+                    translate_idents(table_name).join("."),
+                ),
+                _ => {
+                    return Err(TranslateError::InvalidParamsInDropIndex.into());
+                }
+            };
 
             if name.to_uppercase() == "PRIMARY" {
                 return Err(TranslateError::CannotDropPrimary.into());
@@ -187,10 +334,56 @@ pub fn translate(sql_statement: &SqlStatement) -> Result<Statement> {
         SqlStatement::ShowColumns { table_name, .. } => Ok(Statement::ShowColumns {
             table_name: translate_object_name(table_name)?,
         }),
+        SqlStatement::Analyze { table_name, .. } => Ok(Statement::Analyze {
+            table_name: translate_object_name(table_name)?,
+        }),
+        SqlStatement::Explain {
+            analyze, statement, ..
+        } => Ok(Statement::Explain {
+            analyze: *analyze,
+            statement: Box::new(translate(statement)?),
+        }),
         _ => Err(TranslateError::UnsupportedStatement(sql_statement.to_string()).into()),
     }
 }
 
+fn translate_on_insert(sql_on_insert: &SqlOnInsert) -> Result<OnConflict> {
+    let SqlOnConflict {
+        conflict_target,
+        action,
+    } = match sql_on_insert {
+        SqlOnInsert::OnConflict(sql_on_conflict) => sql_on_conflict,
+        SqlOnInsert::DuplicateKeyUpdate(_) => {
+            return Err(TranslateError::UnsupportedOnInsert(sql_on_insert.to_string()).into());
+        }
+    };
+
+    let action = match action {
+        SqlOnConflictAction::DoNothing => OnConflictAction::DoNothing,
+        // `sql-parser` does not parse the `WHERE` clause of `DO UPDATE` yet
+        SqlOnConflictAction::DoUpdate(assignments) => OnConflictAction::DoUpdate {
+            assignments: assignments
+                .iter()
+                .map(translate_assignment)
+                .collect::<Result<_>>()?,
+            selection: None,
+        },
+    };
+
+    Ok(OnConflict {
+        columns: translate_idents(conflict_target),
+        action,
+    })
+}
+
+fn translate_returning(sql_returning: &Option<Vec<SqlSelectItem>>) -> Result<Vec<SelectItem>> {
+    sql_returning
+        .iter()
+        .flatten()
+        .map(translate_select_item)
+        .collect()
+}
+
 pub fn translate_assignment(sql_assignment: &SqlAssignment) -> Result<Assignment> {
     let SqlAssignment { id, value } = sql_assignment;
 
@@ -210,27 +403,69 @@ pub fn translate_assignment(sql_assignment: &SqlAssignment) -> Result<Assignment
     })
 }
 
-fn translate_table_with_join(table: &TableWithJoins) -> Result<String> {
-    if !table.joins.is_empty() {
-        return Err(TranslateError::JoinOnUpdateNotSupported.into());
-    }
-    match &table.relation {
-        TableFactor::Table { name, .. } => translate_object_name(name),
-        t => Err(TranslateError::UnsupportedTableFactor(t.to_string()).into()),
-    }
+/// Translates the target table of `UPDATE` with the tables joined to it
+fn translate_table_with_join(
+    table: &TableWithJoins,
+) -> Result<(String, Option<String>, Vec<Join>)> {
+    let (table_name, alias) = match &table.relation {
+        TableFactor::Table { name, alias, .. } => (
+            translate_object_name(name)?,
+            alias.as_ref().map(translate_target_alias),
+        ),
+        t => return Err(TranslateError::UnsupportedTableFactor(t.to_string()).into()),
+    };
+    let joins = table
+        .joins
+        .iter()
+        .map(translate_join)
+        .collect::<Result<_>>()?;
+
+    Ok((table_name, alias, joins))
+}
+
+/// Alias of the target table of `UPDATE` or `DELETE`
+fn translate_target_alias(alias: &SqlTableAlias) -> String {
+    alias.name.value.to_owned()
+}
+
+/// Translates `FROM` of `UPDATE` or `USING` of `DELETE` into joins to the target table,
+/// the first table is cross joined and filtered by the `WHERE` clause
+fn translate_from(relation: &TableFactor, joins: &[SqlJoin]) -> Result<Vec<Join>> {
+    let cross_join = Join {
+        relation: translate_table_factor(relation)?,
+        join_operator: JoinOperator::Cross,
+        join_executor: JoinExecutor::NestedLoop,
+    };
+
+    once(Ok(cross_join))
+        .chain(joins.iter().map(translate_join))
+        .collect()
 }
 
+/// Translates `table` or `schema.table`, a qualified name is kept joined by `.`
 fn translate_object_name(sql_object_name: &SqlObjectName) -> Result<String> {
     let sql_object_name = &sql_object_name.0;
-    if sql_object_name.len() > 1 {
-        let compound_object_name = translate_idents(sql_object_name).join(".");
-        return Err(TranslateError::CompoundObjectNotSupported(compound_object_name).into());
+    match sql_object_name.len() {
+        0 => Err(TranslateError::UnreachableEmptyObject.into()),
+        1 | 2 => Ok(translate_idents(sql_object_name).join(".")),
+        _ => {
+            let compound_object_name = translate_idents(sql_object_name).join(".");
+
+            Err(TranslateError::CompoundObjectNotSupported(compound_object_name).into())
+        }
     }
+}
 
-    sql_object_name
-        .get(0)
-        .map(|v| v.value.to_owned())
-        .ok_or_else(|| TranslateError::UnreachableEmptyObject.into())
+fn translate_namespace(sql_object_name: &SqlObjectName) -> Result<String> {
+    match sql_object_name.0.as_slice() {
+        [] => Err(TranslateError::UnreachableEmptyObject.into()),
+        [name] => Ok(name.value.to_owned()),
+        idents => {
+            let compound_object_name = translate_idents(idents).join(".");
+
+            Err(TranslateError::CompoundObjectNotSupported(compound_object_name).into())
+        }
+    }
 }
 
 pub fn translate_idents(idents: &[SqlIdent]) -> Vec<String> {
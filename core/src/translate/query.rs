@@ -5,22 +5,24 @@ use {
     },
     crate::{
         ast::{
-            AstLiteral, Dictionary, Expr, Join, JoinConstraint, JoinExecutor, JoinOperator, Query,
-            Select, SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins, Values,
+            AstLiteral, Cte, Dictionary, Expr, Join, JoinConstraint, JoinExecutor, JoinOperator,
+            Query, Select, SelectItem, SetExpr, SetOperator, TableAlias, TableFactor,
+            TableWithJoins, Values, With,
         },
         result::Result,
     },
     sqlparser::ast::{
-        Expr as SqlExpr, FunctionArg as SqlFunctionArg, Join as SqlJoin,
+        Cte as SqlCte, Expr as SqlExpr, FunctionArg as SqlFunctionArg, Join as SqlJoin,
         JoinConstraint as SqlJoinConstraint, JoinOperator as SqlJoinOperator, Query as SqlQuery,
         Select as SqlSelect, SelectItem as SqlSelectItem, SetExpr as SqlSetExpr,
-        TableAlias as SqlTableAlias, TableFactor as SqlTableFactor,
-        TableWithJoins as SqlTableWithJoins,
+        SetOperator as SqlSetOperator, TableAlias as SqlTableAlias, TableFactor as SqlTableFactor,
+        TableWithJoins as SqlTableWithJoins, With as SqlWith,
     },
 };
 
 pub fn translate_query(sql_query: &SqlQuery) -> Result<Query> {
     let SqlQuery {
+        with,
         body,
         order_by,
         limit,
@@ -28,6 +30,7 @@ pub fn translate_query(sql_query: &SqlQuery) -> Result<Query> {
         ..
     } = sql_query;
 
+    let with = with.as_ref().map(translate_with).transpose()?;
     let body = translate_set_expr(body)?;
     let order_by = order_by
         .iter()
@@ -41,6 +44,7 @@ pub fn translate_query(sql_query: &SqlQuery) -> Result<Query> {
         .transpose()?;
 
     Ok(Query {
+        with,
         body,
         order_by,
         limit,
@@ -48,6 +52,33 @@ pub fn translate_query(sql_query: &SqlQuery) -> Result<Query> {
     })
 }
 
+fn translate_with(sql_with: &SqlWith) -> Result<With> {
+    let SqlWith {
+        recursive,
+        cte_tables,
+    } = sql_with;
+
+    let cte_tables = cte_tables
+        .iter()
+        .map(|SqlCte { alias, query, .. }| {
+            let SqlTableAlias { name, columns } = alias;
+
+            Ok(Cte {
+                alias: TableAlias {
+                    name: name.value.to_owned(),
+                    columns: translate_idents(columns),
+                },
+                query: translate_query(query)?,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(With {
+        recursive: *recursive,
+        cte_tables,
+    })
+}
+
 fn translate_set_expr(sql_set_expr: &SqlSetExpr) -> Result<SetExpr> {
     match sql_set_expr {
         SqlSetExpr::Select(select) => translate_select(select).map(Box::new).map(SetExpr::Select),
@@ -58,6 +89,30 @@ fn translate_set_expr(sql_set_expr: &SqlSetExpr) -> Result<SetExpr> {
             .collect::<Result<_>>()
             .map(Values)
             .map(SetExpr::Values),
+        SqlSetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+        } => Ok(SetExpr::SetOperation {
+            op: match op {
+                SqlSetOperator::Union => SetOperator::Union,
+                SqlSetOperator::Except => SetOperator::Except,
+                SqlSetOperator::Intersect => SetOperator::Intersect,
+            },
+            all: *all,
+            left: translate_set_expr(left).map(Box::new)?,
+            right: translate_set_expr(right).map(Box::new)?,
+        }),
+        SqlSetExpr::Query(query)
+            if query.with.is_none()
+                && query.order_by.is_empty()
+                && query.limit.is_none()
+                && query.offset.is_none()
+                && query.fetch.is_none() =>
+        {
+            translate_set_expr(&query.body)
+        }
         _ => Err(TranslateError::UnsupportedQuerySetExpr(sql_set_expr.to_string()).into()),
     }
 }
@@ -77,10 +132,6 @@ fn translate_select(sql_select: &SqlSelect) -> Result<Select> {
         return Err(TranslateError::TooManyTables.into());
     }
 
-    if *distinct {
-        return Err(TranslateError::SelectDistinctNotSupported.into());
-    }
-
     let from = match from.get(0) {
         Some(sql_table_with_joins) => translate_table_with_joins(sql_table_with_joins)?,
         None => TableWithJoins {
@@ -96,6 +147,7 @@ fn translate_select(sql_select: &SqlSelect) -> Result<Select> {
     };
 
     Ok(Select {
+        distinct: *distinct,
         projection: projection
             .iter()
             .map(translate_select_item)
@@ -154,7 +206,7 @@ fn translate_table_alias(alias: &Option<SqlTableAlias>) -> Option<TableAlias> {
         })
 }
 
-fn translate_table_factor(sql_table_factor: &SqlTableFactor) -> Result<TableFactor> {
+pub fn translate_table_factor(sql_table_factor: &SqlTableFactor) -> Result<TableFactor> {
     let translate_table_args = |args: &Option<Vec<SqlFunctionArg>>| -> Result<Expr> {
         let args = args
             .as_ref()
@@ -211,6 +263,15 @@ fn translate_table_factor(sql_table_factor: &SqlTableFactor) -> Result<TableFact
                     alias: alias_or_name,
                 }),
                 _ => {
+                    // qualified tables are referred to by their unqualified name
+                    let alias = match (alias, name.0.as_slice()) {
+                        (None, [_, table_name]) => Some(TableAlias {
+                            name: table_name.value.to_owned(),
+                            columns: Vec::new(),
+                        }),
+                        (alias, _) => alias,
+                    };
+
                     Ok(TableFactor::Table {
                         name: translate_object_name(name)?,
                         alias,
@@ -238,7 +299,7 @@ fn translate_table_factor(sql_table_factor: &SqlTableFactor) -> Result<TableFact
     }
 }
 
-fn translate_join(sql_join: &SqlJoin) -> Result<Join> {
+pub fn translate_join(sql_join: &SqlJoin) -> Result<Join> {
     let SqlJoin {
         relation,
         join_operator: sql_join_operator,
@@ -247,12 +308,8 @@ fn translate_join(sql_join: &SqlJoin) -> Result<Join> {
     let translate_constraint = |sql_join_constraint: &SqlJoinConstraint| match sql_join_constraint {
         SqlJoinConstraint::On(expr) => translate_expr(expr).map(JoinConstraint::On),
         SqlJoinConstraint::None => Ok(JoinConstraint::None),
-        SqlJoinConstraint::Using(_) => {
-            Err(TranslateError::UnsupportedJoinConstraint("USING".to_owned()).into())
-        }
-        SqlJoinConstraint::Natural => {
-            Err(TranslateError::UnsupportedJoinConstraint("NATURAL".to_owned()).into())
-        }
+        SqlJoinConstraint::Using(idents) => Ok(JoinConstraint::Using(translate_idents(idents))),
+        SqlJoinConstraint::Natural => Ok(JoinConstraint::Natural),
     };
 
     let join_operator = match sql_join_operator {
@@ -262,6 +319,13 @@ fn translate_join(sql_join: &SqlJoin) -> Result<Join> {
         SqlJoinOperator::LeftOuter(sql_join_constraint) => {
             translate_constraint(sql_join_constraint).map(JoinOperator::LeftOuter)
         }
+        SqlJoinOperator::RightOuter(sql_join_constraint) => {
+            translate_constraint(sql_join_constraint).map(JoinOperator::RightOuter)
+        }
+        SqlJoinOperator::FullOuter(sql_join_constraint) => {
+            translate_constraint(sql_join_constraint).map(JoinOperator::FullOuter)
+        }
+        SqlJoinOperator::CrossJoin => Ok(JoinOperator::Cross),
         _ => {
             Err(TranslateError::UnsupportedJoinOperator(format!("{:?}", sql_join_operator)).into())
         }
@@ -20,6 +20,13 @@ fn convert_payload(payload: Payload) -> Json {
     match payload {
         Payload::Create => json!({ "type": "CREATE TABLE" }),
         Payload::DropTable => json!({ "type": "DROP TABLE" }),
+        Payload::CreateSchema => json!({ "type": "CREATE SCHEMA" }),
+        Payload::DropSchema => json!({ "type": "DROP SCHEMA" }),
+        Payload::CreateSequence => json!({ "type": "CREATE SEQUENCE" }),
+        Payload::DropSequence => json!({ "type": "DROP SEQUENCE" }),
+        Payload::CreateView => json!({ "type": "CREATE VIEW" }),
+        Payload::DropView => json!({ "type": "DROP VIEW" }),
+        Payload::Analyze => json!({ "type": "ANALYZE" }),
         Payload::Select { labels, rows } => {
             let rows = rows
                 .into_iter()
@@ -90,5 +97,9 @@ fn convert_payload(payload: Payload) -> Json {
                 "tables": table_names
             })
         }
+        Payload::Explain(plan) => json!({
+            "type": "EXPLAIN",
+            "plan": plan
+        }),
     }
 }
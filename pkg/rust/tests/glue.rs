@@ -1,8 +1,11 @@
 #![cfg(any(feature = "memory-storage", feature = "sled-storage"))]
-use gluesql_core::{
-    executor::Payload,
-    prelude::{Glue, Value},
-    store::{GStore, GStoreMut},
+use {
+    futures::{executor::block_on, StreamExt, TryStreamExt},
+    gluesql_core::{
+        executor::{Payload, PayloadStream},
+        prelude::{Glue, Row, Value},
+        store::{GStore, GStoreMut},
+    },
 };
 
 fn basic<T: GStore + GStoreMut>(mut glue: Glue<T>) {
@@ -67,6 +70,82 @@ async fn basic_async<T: GStore + GStoreMut>(mut glue: Glue<T>) {
     );
 }
 
+async fn stream<T: GStore + GStoreMut>(mut glue: Glue<T>) {
+    glue.execute_async(
+        "
+            CREATE TABLE stream_test (id INTEGER, name TEXT);
+            INSERT INTO stream_test VALUES (1, 'a'), (2, 'b'), (3, 'c');
+        ",
+    )
+    .await
+    .unwrap();
+
+    let statement = glue
+        .plan("SELECT id FROM stream_test WHERE id > 1")
+        .await
+        .unwrap()
+        .remove(0);
+    match glue.execute_stream(&statement).await.unwrap() {
+        PayloadStream::Select { labels, rows } => {
+            assert_eq!(labels, vec![String::from("id")]);
+            assert_eq!(
+                rows.try_collect::<Vec<_>>().await,
+                Ok(vec![Row(vec![Value::I64(2)]), Row(vec![Value::I64(3)])])
+            );
+        }
+        PayloadStream::Payload(_) => panic!("SELECT is not streamed"),
+    }
+
+    // the statements after the stream run in their own transactions
+    assert_eq!(
+        glue.execute_async("DELETE FROM stream_test WHERE id = 1")
+            .await,
+        Ok(vec![Payload::Delete(1)])
+    );
+
+    let statement = glue.plan("DROP TABLE stream_test").await.unwrap().remove(0);
+    assert!(matches!(
+        glue.execute_stream(&statement).await,
+        Ok(PayloadStream::Payload(Payload::DropTable))
+    ));
+}
+
+/// Reads the first row of a `SELECT` and drops the rest of them
+async fn stream_drop<T: GStore + GStoreMut>(glue: &mut Glue<T>) {
+    glue.execute_async(
+        "
+            CREATE TABLE stream_drop (id INTEGER);
+            INSERT INTO stream_drop VALUES (1), (2), (3);
+        ",
+    )
+    .await
+    .unwrap();
+
+    let statement = glue
+        .plan("SELECT id FROM stream_drop")
+        .await
+        .unwrap()
+        .remove(0);
+    match glue.execute_stream(&statement).await.unwrap() {
+        PayloadStream::Select { mut rows, .. } => {
+            assert_eq!(rows.next().await, Some(Ok(Row(vec![Value::I64(1)]))));
+        }
+        PayloadStream::Payload(_) => panic!("SELECT is not streamed"),
+    }
+}
+
+async fn transaction_after_stream_drop<T: GStore + GStoreMut>(glue: &mut Glue<T>) {
+    assert_eq!(
+        glue.execute_async("BEGIN; DELETE FROM stream_drop WHERE id = 1; COMMIT;")
+            .await,
+        Ok(vec![
+            Payload::StartTransaction,
+            Payload::Delete(1),
+            Payload::Commit
+        ])
+    );
+}
+
 #[cfg(feature = "sled-storage")]
 #[test]
 fn sled_basic() {
@@ -96,7 +175,6 @@ fn memory_basic() {
 #[cfg(feature = "memory-storage")]
 #[test]
 fn memory_basic_async() {
-    use futures::executor::block_on;
     use memory_storage::MemoryStorage;
 
     let storage = MemoryStorage::default();
@@ -104,3 +182,63 @@ fn memory_basic_async() {
 
     block_on(basic_async(glue));
 }
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn sled_stream() {
+    use sled_storage::{sled, SledStorage};
+
+    let config = sled::Config::default().path("data/stream").temporary(true);
+
+    let storage = SledStorage::try_from(config).unwrap();
+    let glue = Glue::new(storage);
+
+    block_on(stream(glue));
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn sled_stream_drop() {
+    use sled_storage::{sled, SledStorage, State};
+
+    let config = sled::Config::default()
+        .path("data/stream_drop")
+        .temporary(true);
+
+    let storage = SledStorage::try_from(config).unwrap();
+    let mut glue = Glue::new(storage);
+
+    block_on(stream_drop(&mut glue));
+
+    // the transaction of the dropped stream is rolled back by the next statement
+    assert!(matches!(
+        glue.storage.as_ref().unwrap().state,
+        State::Transaction { .. }
+    ));
+
+    block_on(transaction_after_stream_drop(&mut glue));
+    assert!(matches!(glue.storage.as_ref().unwrap().state, State::Idle));
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn memory_stream() {
+    use memory_storage::MemoryStorage;
+
+    let storage = MemoryStorage::default();
+    let glue = Glue::new(storage);
+
+    block_on(stream(glue));
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn memory_stream_drop() {
+    use memory_storage::MemoryStorage;
+
+    let storage = MemoryStorage::default();
+    let mut glue = Glue::new(storage);
+
+    block_on(stream_drop(&mut glue));
+    block_on(transaction_after_stream_drop(&mut glue));
+}
@@ -54,6 +54,21 @@ impl MemoryStorage {
 
         column_def.name = new_column_name.to_owned();
 
+        if let Some(primary_key) = item.schema.primary_key.as_mut() {
+            primary_key
+                .iter_mut()
+                .filter(|column_name| column_name.as_str() == old_column_name)
+                .for_each(|column_name| *column_name = new_column_name.to_owned());
+        }
+
+        if let Some(statistics) = item.schema.statistics.as_mut() {
+            statistics
+                .columns
+                .iter_mut()
+                .filter(|column| column.name == old_column_name)
+                .for_each(|column| column.name = new_column_name.to_owned());
+        }
+
         Ok(())
     }
 
@@ -127,6 +142,12 @@ impl MemoryStorage {
             Some(column_index) => {
                 item.schema.column_defs.remove(column_index);
 
+                if let Some(statistics) = item.schema.statistics.as_mut() {
+                    statistics
+                        .columns
+                        .retain(|column| column.name != column_name);
+                }
+
                 item.rows.iter_mut().for_each(|(_, row)| {
                     if row.0.len() > column_index {
                         row.0.remove(column_index);
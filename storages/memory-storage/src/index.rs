@@ -1,49 +1,183 @@
 #![cfg(feature = "index")]
 
 use {
-    super::MemoryStorage,
+    super::{encode_index_key, MemoryStorage},
     async_trait::async_trait,
     gluesql_core::{
         ast::{IndexOperator, OrderByExpr},
-        data::Value,
-        result::{Error, MutResult, Result},
-        store::{Index, IndexMut, RowIter},
+        chrono::Utc,
+        data::{SchemaIndex, SchemaIndexOrd, Value},
+        result::{MutResult, Result},
+        store::{Index, IndexError, IndexMut, RowIter},
     },
+    std::{collections::BTreeMap, ops::Bound},
 };
 
+impl MemoryStorage {
+    pub fn create_index(
+        &mut self,
+        table_name: &str,
+        index_name: &str,
+        columns: &[OrderByExpr],
+    ) -> Result<()> {
+        let item = self
+            .items
+            .get_mut(table_name)
+            .ok_or_else(|| IndexError::TableNotFound(table_name.to_owned()))?;
+
+        if item
+            .schema
+            .indexes
+            .iter()
+            .any(|index| index.name == index_name)
+        {
+            return Err(IndexError::IndexNameAlreadyExists(index_name.to_owned()).into());
+        }
+
+        let exprs = columns
+            .iter()
+            .map(|column| column.expr.clone())
+            .collect::<Vec<_>>();
+
+        let mut index = BTreeMap::<_, Vec<_>>::new();
+        for (key, row) in item.rows.iter() {
+            let index_key = item.index_key(&exprs, row)?;
+
+            index.entry(index_key).or_default().push(key.clone());
+        }
+
+        item.schema.indexes.push(SchemaIndex {
+            name: index_name.to_owned(),
+            exprs,
+            order: SchemaIndexOrd::Both,
+            created: Utc::now().naive_utc(),
+        });
+        item.indexes.insert(index_name.to_owned(), index);
+
+        Ok(())
+    }
+
+    pub fn drop_index(&mut self, table_name: &str, index_name: &str) -> Result<()> {
+        let item = self
+            .items
+            .get_mut(table_name)
+            .ok_or_else(|| IndexError::TableNotFound(table_name.to_owned()))?;
+
+        if item
+            .schema
+            .indexes
+            .iter()
+            .all(|index| index.name != index_name)
+        {
+            return Err(IndexError::IndexNameDoesNotExist(index_name.to_owned()).into());
+        }
+
+        item.schema.indexes.retain(|index| index.name != index_name);
+        item.indexes.remove(index_name);
+
+        Ok(())
+    }
+}
+
 #[async_trait(?Send)]
 impl Index for MemoryStorage {
     async fn scan_indexed_data(
         &self,
-        _table_name: &str,
-        _index_name: &str,
-        _asc: Option<bool>,
-        _cmp_value: Option<(&IndexOperator, Value)>,
+        table_name: &str,
+        index_name: &str,
+        asc: Option<bool>,
+        prefix_values: Vec<Value>,
+        cmp_value: Option<(&IndexOperator, Value)>,
     ) -> Result<RowIter> {
-        Err(Error::StorageMsg(
-            "[MemoryStorage] index is not supported".to_owned(),
-        ))
+        let item = self
+            .items
+            .get(table_name)
+            .ok_or_else(|| IndexError::TableNotFound(table_name.to_owned()))?;
+        let index = item
+            .indexes
+            .get(index_name)
+            .ok_or_else(|| IndexError::IndexNameDoesNotExist(index_name.to_owned()))?;
+
+        let prefix = encode_index_key(prefix_values.clone())?;
+        let upper = match prefix.is_empty() {
+            true => Bound::Unbounded,
+            false => Bound::Excluded(incr(&prefix)),
+        };
+
+        let range = match cmp_value {
+            None => (Bound::Included(prefix), upper),
+            Some((op, value)) => {
+                let key = encode_index_key([prefix_values, vec![value]].concat())?;
+
+                match op {
+                    IndexOperator::Eq => {
+                        let upper = Bound::Excluded(incr(&key));
+
+                        (Bound::Included(key), upper)
+                    }
+                    IndexOperator::Gt => (Bound::Included(incr(&key)), upper),
+                    IndexOperator::GtEq => (Bound::Included(key), upper),
+                    IndexOperator::Lt => (Bound::Included(prefix), Bound::Excluded(key)),
+                    IndexOperator::LtEq => (Bound::Included(prefix), Bound::Excluded(incr(&key))),
+                }
+            }
+        };
+
+        let keys: Vec<_> = match asc {
+            Some(true) | None => index.range(range).flat_map(|(_, keys)| keys).collect(),
+            Some(false) => index
+                .range(range)
+                .rev()
+                .flat_map(|(_, keys)| keys)
+                .collect(),
+        };
+
+        let rows = keys
+            .into_iter()
+            .filter_map(|key| item.rows.get(key).map(|row| (key.clone(), row.clone())))
+            .map(Ok)
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(rows.into_iter()))
     }
 }
 
+/// Returns the smallest key greater than every key which starts with the given bytes.
+fn incr(key: &[u8]) -> Vec<u8> {
+    let mut key = key.to_vec();
+
+    while let Some(byte) = key.pop() {
+        if byte < u8::MAX {
+            key.push(byte + 1);
+            break;
+        }
+    }
+
+    key
+}
+
 #[async_trait(?Send)]
 impl IndexMut for MemoryStorage {
     async fn create_index(
         self,
-        _table_name: &str,
-        _index_name: &str,
-        _column: &OrderByExpr,
+        table_name: &str,
+        index_name: &str,
+        columns: &[OrderByExpr],
     ) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[MemoryStorage] index is not supported".to_owned()),
-        ))
+        let mut storage = self;
+
+        match MemoryStorage::create_index(&mut storage, table_name, index_name, columns) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
     }
 
-    async fn drop_index(self, _table_name: &str, _index_name: &str) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[MemoryStorage] index is not supported".to_owned()),
-        ))
+    async fn drop_index(self, table_name: &str, index_name: &str) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        match MemoryStorage::drop_index(&mut storage, table_name, index_name) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
     }
 }
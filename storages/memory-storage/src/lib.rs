@@ -7,25 +7,158 @@ mod transaction;
 use {
     async_trait::async_trait,
     gluesql_core::{
-        data::{Key, Row, Schema},
-        result::{MutResult, Result},
-        store::{RowIter, Store, StoreMut},
+        ast::Expr,
+        data::{Key, Row, Schema, SchemaIndex, Sequence, Statistics, Value, View},
+        executor::evaluate_stateless,
+        result::{Error, MutResult, Result},
+        store::{project_scan_row, RowIter, ScanFilter, Store, StoreMut},
     },
     indexmap::IndexMap,
     serde::{Deserialize, Serialize},
-    std::{collections::HashMap, iter::empty},
+    std::{
+        collections::{BTreeMap, BTreeSet, HashMap},
+        iter::empty,
+        sync::{Mutex, MutexGuard},
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub schema: Schema,
     pub rows: IndexMap<Key, Row>,
+    /// Ordered entries of each secondary index, from encoded index key to data keys
+    pub indexes: HashMap<String, BTreeMap<Vec<u8>, Vec<Key>>>,
+}
+
+impl Item {
+    fn index_key(&self, exprs: &[Expr], row: &Row) -> Result<Vec<u8>> {
+        let columns = self
+            .schema
+            .column_defs
+            .iter()
+            .map(|column_def| column_def.name.clone())
+            .collect::<Vec<_>>();
+
+        let values = exprs
+            .iter()
+            .map(|expr| {
+                evaluate_stateless(Some((columns.as_slice(), row)), expr).and_then(Value::try_from)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        encode_index_key(values)
+    }
+
+    fn index_keys(&self, row: &Row) -> Result<Vec<(String, Vec<u8>)>> {
+        self.schema
+            .indexes
+            .iter()
+            .map(|SchemaIndex { name, exprs, .. }| {
+                self.index_key(exprs, row)
+                    .map(|index_key| (name.clone(), index_key))
+            })
+            .collect()
+    }
+
+    fn delete_index_entries(&mut self, key: &Key) -> Result<()> {
+        let index_keys = match self.rows.get(key) {
+            Some(row) => self.index_keys(row)?,
+            None => return Ok(()),
+        };
+
+        for (index_name, index_key) in index_keys {
+            let index = match self.indexes.get_mut(&index_name) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            if let Some(keys) = index.get_mut(&index_key) {
+                keys.retain(|data_key| data_key != key);
+
+                if keys.is_empty() {
+                    index.remove(&index_key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_row(&mut self, key: Key, row: Row) -> Result<()> {
+        self.delete_index_entries(&key)?;
+
+        for (index_name, index_key) in self.index_keys(&row)? {
+            self.indexes
+                .entry(index_name)
+                .or_default()
+                .entry(index_key)
+                .or_default()
+                .push(key.clone());
+        }
+
+        self.rows.insert(key, row);
+
+        Ok(())
+    }
+
+    fn delete_row(&mut self, key: &Key) -> Result<()> {
+        self.delete_index_entries(key)?;
+        self.rows.remove(key);
+
+        Ok(())
+    }
+}
+
+/// Encodes index values into bytes which keep the order of the value tuples,
+/// so that the values of leading index columns form a prefix of the full key.
+fn encode_index_key(values: Vec<Value>) -> Result<Vec<u8>> {
+    values
+        .into_iter()
+        .map(Key::try_from)
+        .collect::<Result<Vec<_>>>()
+        .map(|keys| Key::Composite(keys).to_cmp_be_bytes())
+}
+
+/// Current values of the sequences, advanced through a shared reference and
+/// kept as they are on `ROLLBACK`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SequenceValues(Mutex<HashMap<String, i64>>);
+
+impl Clone for SequenceValues {
+    fn clone(&self) -> Self {
+        let values = self
+            .0
+            .lock()
+            .map(|values| values.clone())
+            .unwrap_or_default();
+
+        Self(Mutex::new(values))
+    }
+}
+
+impl SequenceValues {
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, i64>>> {
+        self.0.lock().map_err(|_| {
+            Error::StorageMsg("[MemoryStorage] sequence values are poisoned".to_owned())
+        })
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MemoryStorage {
     pub id_counter: i64,
     pub items: HashMap<String, Item>,
+    pub namespaces: BTreeSet<String>,
+    pub sequences: HashMap<String, Sequence>,
+    pub sequence_values: SequenceValues,
+    pub views: HashMap<String, View>,
+    /// Items, namespaces, sequences and views as of `BEGIN`, restored on `ROLLBACK`
+    pub snapshot: Option<(
+        HashMap<String, Item>,
+        BTreeSet<String>,
+        HashMap<String, Sequence>,
+        HashMap<String, View>,
+    )>,
 }
 
 #[async_trait(?Send)]
@@ -64,6 +197,72 @@ impl Store for MemoryStorage {
 
         Ok(rows)
     }
+
+    async fn scan_filtered_data(
+        &self,
+        table_name: &str,
+        columns: Option<&[usize]>,
+        filter: Option<&ScanFilter>,
+    ) -> Result<RowIter> {
+        let item = match self.items.get(table_name) {
+            Some(item) => item,
+            None => return Ok(Box::new(empty())),
+        };
+        let rows = item
+            .rows
+            .iter()
+            .filter(|(_, row)| filter.map_or(true, |filter| filter.check(row)))
+            .map(|(key, row)| {
+                let row = match columns {
+                    Some(columns) => project_scan_row(columns, row),
+                    None => row.clone(),
+                };
+
+                Ok((key.clone(), row))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(rows.into_iter()))
+    }
+
+    async fn fetch_namespaces(&self) -> Result<Vec<String>> {
+        Ok(self.namespaces.iter().cloned().collect())
+    }
+
+    async fn fetch_sequence(&self, name: &str) -> Result<Option<Sequence>> {
+        Ok(self.sequences.get(name).cloned())
+    }
+
+    async fn fetch_all_sequences(&self) -> Result<Vec<Sequence>> {
+        let mut sequences = self.sequences.values().cloned().collect::<Vec<_>>();
+        sequences.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(sequences)
+    }
+
+    async fn next_sequence_value(&self, sequence: &Sequence) -> Result<i64> {
+        let mut values = self.sequence_values.lock()?;
+        let value = sequence.next_value(values.get(&sequence.name).copied())?;
+
+        values.insert(sequence.name.clone(), value);
+
+        Ok(value)
+    }
+
+    async fn current_sequence_value(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self.sequence_values.lock()?.get(name).copied())
+    }
+
+    async fn fetch_view(&self, name: &str) -> Result<Option<View>> {
+        Ok(self.views.get(name).cloned())
+    }
+
+    async fn fetch_all_views(&self) -> Result<Vec<View>> {
+        let mut views = self.views.values().cloned().collect::<Vec<_>>();
+        views.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(views)
+    }
 }
 
 impl MemoryStorage {
@@ -72,6 +271,7 @@ impl MemoryStorage {
         let item = Item {
             schema: schema.clone(),
             rows: IndexMap::new(),
+            indexes: HashMap::new(),
         };
 
         self.items.insert(table_name, item);
@@ -81,30 +281,77 @@ impl MemoryStorage {
         self.items.remove(table_name);
     }
 
-    pub fn append_data(&mut self, table_name: &str, rows: Vec<Row>) {
+    pub fn insert_namespace(&mut self, namespace: &str) {
+        self.namespaces.insert(namespace.to_owned());
+    }
+
+    pub fn delete_namespace(&mut self, namespace: &str) {
+        self.namespaces.remove(namespace);
+    }
+
+    pub fn insert_sequence(&mut self, sequence: &Sequence) -> Result<()> {
+        self.reset_sequence_value(&sequence.name)?;
+        self.sequences
+            .insert(sequence.name.clone(), sequence.clone());
+
+        Ok(())
+    }
+
+    /// Forgets the current value, so that the sequence starts over.
+    pub fn reset_sequence_value(&self, name: &str) -> Result<()> {
+        self.sequence_values.lock()?.remove(name);
+
+        Ok(())
+    }
+
+    pub fn delete_sequence(&mut self, name: &str) {
+        self.sequences.remove(name);
+    }
+
+    pub fn insert_view(&mut self, view: &View) {
+        self.views.insert(view.name.clone(), view.clone());
+    }
+
+    pub fn delete_view(&mut self, name: &str) {
+        self.views.remove(name);
+    }
+
+    pub fn update_statistics(&mut self, table_name: &str, statistics: &Statistics) {
+        if let Some(item) = self.items.get_mut(table_name) {
+            item.schema.statistics = Some(statistics.clone());
+        }
+    }
+
+    pub fn append_data(&mut self, table_name: &str, rows: Vec<Row>) -> Result<()> {
         if let Some(item) = self.items.get_mut(table_name) {
             for row in rows {
                 self.id_counter += 1;
 
-                item.rows.insert(Key::I64(self.id_counter), row);
+                item.insert_row(Key::I64(self.id_counter), row)?;
             }
         }
+
+        Ok(())
     }
 
-    pub fn insert_data(&mut self, table_name: &str, rows: Vec<(Key, Row)>) {
+    pub fn insert_data(&mut self, table_name: &str, rows: Vec<(Key, Row)>) -> Result<()> {
         if let Some(item) = self.items.get_mut(table_name) {
             for (key, row) in rows {
-                item.rows.insert(key, row);
+                item.insert_row(key, row)?;
             }
         }
+
+        Ok(())
     }
 
-    pub fn delete_data(&mut self, table_name: &str, keys: Vec<Key>) {
+    pub fn delete_data(&mut self, table_name: &str, keys: Vec<Key>) -> Result<()> {
         if let Some(item) = self.items.get_mut(table_name) {
             for key in keys {
-                item.rows.remove(&key);
+                item.delete_row(&key)?;
             }
         }
+
+        Ok(())
     }
 }
 
@@ -129,23 +376,87 @@ impl StoreMut for MemoryStorage {
     async fn append_data(self, table_name: &str, rows: Vec<Row>) -> MutResult<Self, ()> {
         let mut storage = self;
 
-        MemoryStorage::append_data(&mut storage, table_name, rows);
+        match MemoryStorage::append_data(&mut storage, table_name, rows) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
+    }
+
+    async fn insert_data(self, table_name: &str, rows: Vec<(Key, Row)>) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        match MemoryStorage::insert_data(&mut storage, table_name, rows) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
+    }
+
+    async fn delete_data(self, table_name: &str, keys: Vec<Key>) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        match MemoryStorage::delete_data(&mut storage, table_name, keys) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
+    }
+
+    async fn insert_namespace(self, namespace: &str) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        MemoryStorage::insert_namespace(&mut storage, namespace);
 
         Ok((storage, ()))
     }
 
-    async fn insert_data(self, table_name: &str, rows: Vec<(Key, Row)>) -> MutResult<Self, ()> {
+    async fn delete_namespace(self, namespace: &str) -> MutResult<Self, ()> {
         let mut storage = self;
 
-        MemoryStorage::insert_data(&mut storage, table_name, rows);
+        MemoryStorage::delete_namespace(&mut storage, namespace);
 
         Ok((storage, ()))
     }
 
-    async fn delete_data(self, table_name: &str, keys: Vec<Key>) -> MutResult<Self, ()> {
+    async fn insert_sequence(self, sequence: &Sequence) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        match MemoryStorage::insert_sequence(&mut storage, sequence) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
+    }
+
+    async fn delete_sequence(self, name: &str) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        MemoryStorage::delete_sequence(&mut storage, name);
+
+        Ok((storage, ()))
+    }
+
+    async fn insert_view(self, view: &View) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        MemoryStorage::insert_view(&mut storage, view);
+
+        Ok((storage, ()))
+    }
+
+    async fn delete_view(self, name: &str) -> MutResult<Self, ()> {
+        let mut storage = self;
+
+        MemoryStorage::delete_view(&mut storage, name);
+
+        Ok((storage, ()))
+    }
+
+    async fn update_statistics(
+        self,
+        table_name: &str,
+        statistics: &Statistics,
+    ) -> MutResult<Self, ()> {
         let mut storage = self;
 
-        MemoryStorage::delete_data(&mut storage, table_name, keys);
+        MemoryStorage::update_statistics(&mut storage, table_name, statistics);
 
         Ok((storage, ()))
     }
@@ -4,35 +4,83 @@ use {
     super::MemoryStorage,
     async_trait::async_trait,
     gluesql_core::{
-        result::{Error, MutResult},
+        result::{Error, MutResult, Result},
         store::Transaction,
     },
 };
 
+impl MemoryStorage {
+    pub fn begin(&mut self, autocommit: bool) -> Result<bool> {
+        match (self.snapshot.is_some(), autocommit) {
+            (true, false) => Err(Error::StorageMsg(
+                "[MemoryStorage] nested transaction is not supported".to_owned(),
+            )),
+            (_, true) => Ok(false),
+            (false, false) => {
+                self.snapshot = Some((
+                    self.items.clone(),
+                    self.namespaces.clone(),
+                    self.sequences.clone(),
+                    self.views.clone(),
+                ));
+
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn rollback(&mut self) -> Result<()> {
+        match self.snapshot.take() {
+            Some((items, namespaces, sequences, views)) => {
+                self.items = items;
+                self.namespaces = namespaces;
+                self.sequences = sequences;
+                self.views = views;
+
+                Ok(())
+            }
+            None => Err(Error::StorageMsg(
+                "[MemoryStorage] no transaction to rollback".to_owned(),
+            )),
+        }
+    }
+
+    pub fn commit(&mut self) -> Result<()> {
+        match self.snapshot.take() {
+            Some(_) => Ok(()),
+            None => Err(Error::StorageMsg(
+                "[MemoryStorage] no transaction to commit".to_owned(),
+            )),
+        }
+    }
+}
+
 #[async_trait(?Send)]
 impl Transaction for MemoryStorage {
     async fn begin(self, autocommit: bool) -> MutResult<Self, bool> {
-        if autocommit {
-            return Ok((self, false));
-        }
+        let mut storage = self;
 
-        Err((
-            self,
-            Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned()),
-        ))
+        match MemoryStorage::begin(&mut storage, autocommit) {
+            Ok(autocommit) => Ok((storage, autocommit)),
+            Err(error) => Err((storage, error)),
+        }
     }
 
     async fn rollback(self) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned()),
-        ))
+        let mut storage = self;
+
+        match MemoryStorage::rollback(&mut storage) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
     }
 
     async fn commit(self) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned()),
-        ))
+        let mut storage = self;
+
+        match MemoryStorage::commit(&mut storage) {
+            Ok(()) => Ok((storage, ())),
+            Err(error) => Err((storage, error)),
+        }
     }
 }
@@ -19,9 +19,27 @@ impl Tester<MemoryStorage> for MemoryTester {
 
 generate_store_tests!(tokio::test, MemoryTester);
 
+#[cfg(feature = "index")]
+generate_index_tests!(tokio::test, MemoryTester);
+
 #[cfg(feature = "alter-table")]
 generate_alter_table_tests!(tokio::test, MemoryTester);
 
+#[cfg(all(feature = "alter-table", feature = "index"))]
+generate_alter_table_index_tests!(tokio::test, MemoryTester);
+
+#[cfg(feature = "transaction")]
+generate_transaction_tests!(tokio::test, MemoryTester);
+
+#[cfg(all(feature = "transaction", feature = "alter-table"))]
+generate_transaction_alter_table_tests!(tokio::test, MemoryTester);
+
+#[cfg(all(feature = "transaction", feature = "index"))]
+generate_transaction_index_tests!(tokio::test, MemoryTester);
+
+#[cfg(feature = "transaction")]
+generate_transaction_dictionary_tests!(tokio::test, MemoryTester);
+
 #[cfg(any(feature = "alter-table", feature = "index"))]
 macro_rules! exec {
     ($glue: ident $sql: literal) => {
@@ -42,8 +60,8 @@ fn memory_storage_index() {
     use futures::executor::block_on;
     use gluesql_core::{
         prelude::Glue,
-        result::{Error, Result},
-        store::{Index, Store},
+        result::Result,
+        store::{Index, IndexError, Store},
     };
 
     let storage = MemoryStorage::default();
@@ -58,22 +76,22 @@ fn memory_storage_index() {
     );
 
     assert_eq!(
-        block_on(storage.scan_indexed_data("Idx", "hello", None, None)).map(|_| ()),
-        Err(Error::StorageMsg(
-            "[MemoryStorage] index is not supported".to_owned()
-        ))
+        block_on(storage.scan_indexed_data("Idx", "hello", None, Vec::new(), None)).map(|_| ()),
+        Err(IndexError::TableNotFound("Idx".to_owned()).into())
     );
 
     let mut glue = Glue::new(storage);
 
     exec!(glue "CREATE TABLE Idx (id INTEGER);");
+    exec!(glue "CREATE INDEX idx_id ON Idx (id);");
     test!(
         glue "CREATE INDEX idx_id ON Idx (id);",
-        Err(Error::StorageMsg("[MemoryStorage] index is not supported".to_owned()))
+        Err(IndexError::IndexNameAlreadyExists("idx_id".to_owned()).into())
     );
+    exec!(glue "DROP INDEX Idx.idx_id;");
     test!(
         glue "DROP INDEX Idx.idx_id;",
-        Err(Error::StorageMsg("[MemoryStorage] index is not supported".to_owned()))
+        Err(IndexError::IndexNameDoesNotExist("idx_id".to_owned()).into())
     );
 }
 
@@ -86,7 +104,9 @@ fn memory_storage_transaction() {
     let mut glue = Glue::new(storage);
 
     exec!(glue "CREATE TABLE TxTest (id INTEGER);");
-    test!(glue "BEGIN", Err(Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned())));
-    test!(glue "COMMIT", Err(Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned())));
-    test!(glue "ROLLBACK", Err(Error::StorageMsg("[MemoryStorage] transaction is not supported".to_owned())));
+    exec!(glue "BEGIN");
+    test!(glue "BEGIN", Err(Error::StorageMsg("[MemoryStorage] nested transaction is not supported".to_owned())));
+    exec!(glue "COMMIT");
+    test!(glue "COMMIT", Err(Error::StorageMsg("[MemoryStorage] no transaction to commit".to_owned())));
+    test!(glue "ROLLBACK", Err(Error::StorageMsg("[MemoryStorage] no transaction to rollback".to_owned())));
 }
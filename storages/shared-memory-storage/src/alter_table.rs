@@ -1,23 +1,17 @@
 use {
-    super::SharedMemoryStorage,
+    super::{transaction::Operation, SharedMemoryStorage},
     async_trait::async_trait,
     gluesql_core::{ast::ColumnDef, result::MutResult, store::AlterTable},
-    memory_storage::MemoryStorage,
-    std::sync::Arc,
 };
 
 #[async_trait(?Send)]
 impl AlterTable for SharedMemoryStorage {
     async fn rename_schema(self, table_name: &str, new_table_name: &str) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        if let Err(error) = MemoryStorage::rename_schema(&mut database, table_name, new_table_name)
-        {
-            return Err((self, error));
-        }
-
-        Ok((self, ()))
+        self.apply(Operation::RenameSchema {
+            table_name: table_name.to_owned(),
+            new_table_name: new_table_name.to_owned(),
+        })
+        .await
     }
 
     async fn rename_column(
@@ -26,30 +20,20 @@ impl AlterTable for SharedMemoryStorage {
         old_column_name: &str,
         new_column_name: &str,
     ) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        if let Err(error) = MemoryStorage::rename_column(
-            &mut database,
-            table_name,
-            old_column_name,
-            new_column_name,
-        ) {
-            return Err((self, error));
-        }
-
-        Ok((self, ()))
+        self.apply(Operation::RenameColumn {
+            table_name: table_name.to_owned(),
+            old_column_name: old_column_name.to_owned(),
+            new_column_name: new_column_name.to_owned(),
+        })
+        .await
     }
 
     async fn add_column(self, table_name: &str, column_def: &ColumnDef) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        if let Err(error) = MemoryStorage::add_column(&mut database, table_name, column_def) {
-            return Err((self, error));
-        }
-
-        Ok((self, ()))
+        self.apply(Operation::AddColumn(
+            table_name.to_owned(),
+            column_def.clone(),
+        ))
+        .await
     }
 
     async fn drop_column(
@@ -58,15 +42,11 @@ impl AlterTable for SharedMemoryStorage {
         column_name: &str,
         if_exists: bool,
     ) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        if let Err(error) =
-            MemoryStorage::drop_column(&mut database, table_name, column_name, if_exists)
-        {
-            return Err((self, error));
-        }
-
-        Ok((self, ()))
+        self.apply(Operation::DropColumn {
+            table_name: table_name.to_owned(),
+            column_name: column_name.to_owned(),
+            if_exists,
+        })
+        .await
     }
 }
@@ -1,10 +1,10 @@
 use {
-    super::SharedMemoryStorage,
+    super::{touch_rows, transaction::Operation, SharedMemoryStorage},
     async_trait::async_trait,
     gluesql_core::{
         ast::{IndexOperator, OrderByExpr},
         data::Value,
-        result::{Error, MutResult, Result},
+        result::{MutResult, Result},
         store::{Index, IndexMut, RowIter},
     },
 };
@@ -13,14 +13,29 @@ use {
 impl Index for SharedMemoryStorage {
     async fn scan_indexed_data(
         &self,
-        _table_name: &str,
-        _index_name: &str,
-        _asc: Option<bool>,
-        _cmp_value: Option<(&IndexOperator, Value)>,
+        table_name: &str,
+        index_name: &str,
+        asc: Option<bool>,
+        prefix_values: Vec<Value>,
+        cmp_value: Option<(&IndexOperator, Value)>,
     ) -> Result<RowIter> {
-        Err(Error::StorageMsg(
-            "[Shared MemoryStorage] index is not supported".to_owned(),
-        ))
+        match &self.transaction {
+            Some(transaction) => {
+                let rows = transaction
+                    .storage
+                    .scan_indexed_data(table_name, index_name, asc, prefix_values, cmp_value)
+                    .await?;
+
+                touch_rows(transaction, table_name, rows)
+            }
+            None => {
+                self.database
+                    .read()
+                    .await
+                    .scan_indexed_data(table_name, index_name, asc, prefix_values, cmp_value)
+                    .await
+            }
+        }
     }
 }
 
@@ -28,20 +43,23 @@ impl Index for SharedMemoryStorage {
 impl IndexMut for SharedMemoryStorage {
     async fn create_index(
         self,
-        _table_name: &str,
-        _index_name: &str,
-        _column: &OrderByExpr,
+        table_name: &str,
+        index_name: &str,
+        columns: &[OrderByExpr],
     ) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[Shared MemoryStorage] index is not supported".to_owned()),
-        ))
+        self.apply(Operation::CreateIndex {
+            table_name: table_name.to_owned(),
+            index_name: index_name.to_owned(),
+            columns: columns.to_vec(),
+        })
+        .await
     }
 
-    async fn drop_index(self, _table_name: &str, _index_name: &str) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[Shared MemoryStorage] index is not supported".to_owned()),
-        ))
+    async fn drop_index(self, table_name: &str, index_name: &str) -> MutResult<Self, ()> {
+        self.apply(Operation::DropIndex {
+            table_name: table_name.to_owned(),
+            index_name: index_name.to_owned(),
+        })
+        .await
     }
 }
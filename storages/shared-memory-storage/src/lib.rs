@@ -7,27 +7,63 @@ mod transaction;
 use {
     async_trait::async_trait,
     gluesql_core::{
-        data::{Key, Row, Schema},
+        data::{Key, Row, Schema, Sequence, Statistics, View},
         result::{MutResult, Result},
-        store::{RowIter, Store, StoreMut},
+        store::{RowIter, ScanFilter, Store, StoreMut},
     },
     memory_storage::MemoryStorage,
-    std::sync::Arc,
+    std::sync::{Arc, Mutex},
     tokio::sync::RwLock,
+    transaction::{OpenTransaction, Operation, Versions},
 };
 
-#[derive(Clone, Debug)]
+/// Handle to a `MemoryStorage` shared between threads. Each clone is a separate handle which
+/// can open its own transaction, the other handles do not see its changes until `COMMIT`.
+/// `COMMIT` fails when the rows or tables used by the transaction were changed in the meantime.
+#[derive(Debug)]
 pub struct SharedMemoryStorage {
     pub database: Arc<RwLock<MemoryStorage>>,
+    versions: Arc<Mutex<Versions>>,
+    transaction: Option<Box<OpenTransaction>>,
 }
 
 impl SharedMemoryStorage {
     pub fn new() -> Self {
         let database = MemoryStorage::default();
-        let database = Arc::new(RwLock::new(database));
 
-        Self { database }
+        Self::from(database)
     }
+
+    /// Applies the change to the working copy of the transaction open on this handle, or to
+    /// the shared database right away.
+    async fn apply(mut self, operation: Operation) -> MutResult<Self, ()> {
+        let result = match self.transaction.as_mut() {
+            Some(transaction) => transaction.apply(operation),
+            None => {
+                let mut database = self.database.write().await;
+
+                operation
+                    .apply(&mut database)
+                    .map(|()| Versions::lock(&self.versions).record(&operation))
+            }
+        };
+
+        match result {
+            Ok(()) => Ok((self, ())),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+/// Collects the rows read by the transaction, so that `COMMIT` can find out whether they
+/// have been changed since `BEGIN`.
+fn touch_rows(transaction: &OpenTransaction, table_name: &str, rows: RowIter) -> Result<RowIter> {
+    let rows = rows.collect::<Result<Vec<_>>>()?;
+    for (key, _) in &rows {
+        transaction.touch(table_name, Some(key));
+    }
+
+    Ok(Box::new(rows.into_iter().map(Ok)))
 }
 
 impl Default for SharedMemoryStorage {
@@ -36,87 +72,246 @@ impl Default for SharedMemoryStorage {
     }
 }
 
+impl Clone for SharedMemoryStorage {
+    fn clone(&self) -> Self {
+        Self {
+            database: Arc::clone(&self.database),
+            versions: Arc::clone(&self.versions),
+            transaction: None,
+        }
+    }
+}
+
+impl Drop for SharedMemoryStorage {
+    fn drop(&mut self) {
+        if self.transaction.is_some() {
+            Versions::lock(&self.versions).close();
+        }
+    }
+}
+
 impl From<MemoryStorage> for SharedMemoryStorage {
     fn from(storage: MemoryStorage) -> Self {
         let database = Arc::new(RwLock::new(storage));
-        Self { database }
+
+        Self {
+            database,
+            versions: Arc::new(Mutex::new(Versions::default())),
+            transaction: None,
+        }
     }
 }
 
 #[async_trait(?Send)]
 impl Store for SharedMemoryStorage {
     async fn fetch_all_schemas(&self) -> Result<Vec<Schema>> {
-        let database = Arc::clone(&self.database);
-        let database = database.read().await;
-
-        database.fetch_all_schemas().await
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_all_schemas().await,
+            None => self.database.read().await.fetch_all_schemas().await,
+        }
     }
-    async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
-        let database = Arc::clone(&self.database);
-        let database = database.read().await;
 
-        database.fetch_schema(table_name).await
+    async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
+        match &self.transaction {
+            Some(transaction) => {
+                transaction.touch(table_name, None);
+                transaction.storage.fetch_schema(table_name).await
+            }
+            None => self.database.read().await.fetch_schema(table_name).await,
+        }
     }
 
     async fn fetch_data(&self, table_name: &str, key: &Key) -> Result<Option<Row>> {
-        let database = Arc::clone(&self.database);
-        let database = database.read().await;
-
-        database.fetch_data(table_name, key).await
+        match &self.transaction {
+            Some(transaction) => {
+                transaction.touch(table_name, Some(key));
+                transaction.storage.fetch_data(table_name, key).await
+            }
+            None => self.database.read().await.fetch_data(table_name, key).await,
+        }
     }
 
     async fn scan_data(&self, table_name: &str) -> Result<RowIter> {
-        let database = Arc::clone(&self.database);
-        let database = database.read().await;
+        match &self.transaction {
+            Some(transaction) => {
+                let rows = transaction.storage.scan_data(table_name).await?;
+
+                touch_rows(transaction, table_name, rows)
+            }
+            None => self.database.read().await.scan_data(table_name).await,
+        }
+    }
+
+    async fn scan_filtered_data(
+        &self,
+        table_name: &str,
+        columns: Option<&[usize]>,
+        filter: Option<&ScanFilter>,
+    ) -> Result<RowIter> {
+        match &self.transaction {
+            Some(transaction) => {
+                let rows = transaction
+                    .storage
+                    .scan_filtered_data(table_name, columns, filter)
+                    .await?;
+
+                touch_rows(transaction, table_name, rows)
+            }
+            None => {
+                self.database
+                    .read()
+                    .await
+                    .scan_filtered_data(table_name, columns, filter)
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_namespaces(&self) -> Result<Vec<String>> {
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_namespaces().await,
+            None => self.database.read().await.fetch_namespaces().await,
+        }
+    }
+
+    async fn fetch_sequence(&self, name: &str) -> Result<Option<Sequence>> {
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_sequence(name).await,
+            None => self.database.read().await.fetch_sequence(name).await,
+        }
+    }
 
-        database.scan_data(table_name).await
+    async fn fetch_all_sequences(&self) -> Result<Vec<Sequence>> {
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_all_sequences().await,
+            None => self.database.read().await.fetch_all_sequences().await,
+        }
+    }
+
+    /// Values of the sequences are not transactional, they are always advanced on the
+    /// shared database.
+    async fn next_sequence_value(&self, sequence: &Sequence) -> Result<i64> {
+        self.database
+            .read()
+            .await
+            .next_sequence_value(sequence)
+            .await
+    }
+
+    async fn current_sequence_value(&self, name: &str) -> Result<Option<i64>> {
+        self.database
+            .read()
+            .await
+            .current_sequence_value(name)
+            .await
+    }
+
+    async fn fetch_view(&self, name: &str) -> Result<Option<View>> {
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_view(name).await,
+            None => self.database.read().await.fetch_view(name).await,
+        }
+    }
+
+    async fn fetch_all_views(&self) -> Result<Vec<View>> {
+        match &self.transaction {
+            Some(transaction) => transaction.storage.fetch_all_views().await,
+            None => self.database.read().await.fetch_all_views().await,
+        }
     }
 }
 
 #[async_trait(?Send)]
 impl StoreMut for SharedMemoryStorage {
     async fn insert_schema(self, schema: &Schema) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        MemoryStorage::insert_schema(&mut database, schema);
-
-        Ok((self, ()))
+        self.apply(Operation::InsertSchema(schema.clone())).await
     }
 
     async fn delete_schema(self, table_name: &str) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
-
-        MemoryStorage::delete_schema(&mut database, table_name);
-
-        Ok((self, ()))
+        self.apply(Operation::DeleteSchema(table_name.to_owned()))
+            .await
     }
 
+    /// Within a transaction, the keys are taken from the shared database up front, so that
+    /// they do not collide with the rows appended by the other handles.
     async fn append_data(self, table_name: &str, rows: Vec<Row>) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
+        let table_name = table_name.to_owned();
+        let operation = match self.transaction {
+            Some(_) => {
+                let mut database = self.database.write().await;
+                let first_id = database.id_counter + 1;
+                database.id_counter += rows.len() as i64;
+
+                let rows = (first_id..).map(Key::I64).zip(rows).collect();
 
-        MemoryStorage::append_data(&mut database, table_name, rows);
+                Operation::InsertData(table_name, rows)
+            }
+            None => Operation::AppendData(table_name, rows),
+        };
 
-        Ok((self, ()))
+        self.apply(operation).await
     }
 
     async fn insert_data(self, table_name: &str, rows: Vec<(Key, Row)>) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
+        self.apply(Operation::InsertData(table_name.to_owned(), rows))
+            .await
+    }
 
-        MemoryStorage::insert_data(&mut database, table_name, rows);
+    async fn delete_data(self, table_name: &str, keys: Vec<Key>) -> MutResult<Self, ()> {
+        self.apply(Operation::DeleteData(table_name.to_owned(), keys))
+            .await
+    }
 
-        Ok((self, ()))
+    async fn insert_namespace(self, namespace: &str) -> MutResult<Self, ()> {
+        self.apply(Operation::InsertNamespace(namespace.to_owned()))
+            .await
     }
 
-    async fn delete_data(self, table_name: &str, keys: Vec<Key>) -> MutResult<Self, ()> {
-        let database = Arc::clone(&self.database);
-        let mut database = database.write().await;
+    async fn delete_namespace(self, namespace: &str) -> MutResult<Self, ()> {
+        self.apply(Operation::DeleteNamespace(namespace.to_owned()))
+            .await
+    }
+
+    /// Values of the sequences are kept on the shared database, so a sequence created
+    /// within a transaction starts over right away, unless it is still in use there.
+    async fn insert_sequence(self, sequence: &Sequence) -> MutResult<Self, ()> {
+        if self.transaction.is_some() {
+            let database = self.database.read().await;
+
+            if !database.sequences.contains_key(&sequence.name) {
+                if let Err(error) = database.reset_sequence_value(&sequence.name) {
+                    drop(database);
+
+                    return Err((self, error));
+                }
+            }
+        }
 
-        MemoryStorage::delete_data(&mut database, table_name, keys);
+        self.apply(Operation::InsertSequence(sequence.clone()))
+            .await
+    }
+
+    async fn delete_sequence(self, name: &str) -> MutResult<Self, ()> {
+        self.apply(Operation::DeleteSequence(name.to_owned())).await
+    }
+
+    async fn insert_view(self, view: &View) -> MutResult<Self, ()> {
+        self.apply(Operation::InsertView(view.clone())).await
+    }
+
+    async fn delete_view(self, name: &str) -> MutResult<Self, ()> {
+        self.apply(Operation::DeleteView(name.to_owned())).await
+    }
 
-        Ok((self, ()))
+    async fn update_statistics(
+        self,
+        table_name: &str,
+        statistics: &Statistics,
+    ) -> MutResult<Self, ()> {
+        self.apply(Operation::UpdateStatistics(
+            table_name.to_owned(),
+            statistics.clone(),
+        ))
+        .await
     }
 }
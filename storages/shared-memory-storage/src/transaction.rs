@@ -2,35 +2,349 @@ use {
     super::SharedMemoryStorage,
     async_trait::async_trait,
     gluesql_core::{
-        result::{Error, MutResult},
+        ast::{ColumnDef, OrderByExpr},
+        data::{Key, Row, Schema, Sequence, Statistics, View},
+        result::{Error, MutResult, Result},
         store::Transaction,
     },
+    memory_storage::MemoryStorage,
+    std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        sync::{Mutex, MutexGuard, PoisonError},
+    },
 };
 
+/// Change made through a `SharedMemoryStorage` handle
+#[derive(Debug)]
+pub(crate) enum Operation {
+    InsertSchema(Schema),
+    DeleteSchema(String),
+    AppendData(String, Vec<Row>),
+    InsertData(String, Vec<(Key, Row)>),
+    DeleteData(String, Vec<Key>),
+    InsertNamespace(String),
+    DeleteNamespace(String),
+    InsertSequence(Sequence),
+    DeleteSequence(String),
+    InsertView(View),
+    DeleteView(String),
+    UpdateStatistics(String, Statistics),
+    RenameSchema {
+        table_name: String,
+        new_table_name: String,
+    },
+    RenameColumn {
+        table_name: String,
+        old_column_name: String,
+        new_column_name: String,
+    },
+    AddColumn(String, ColumnDef),
+    DropColumn {
+        table_name: String,
+        column_name: String,
+        if_exists: bool,
+    },
+    CreateIndex {
+        table_name: String,
+        index_name: String,
+        columns: Vec<OrderByExpr>,
+    },
+    DropIndex {
+        table_name: String,
+        index_name: String,
+    },
+}
+
+impl Operation {
+    pub(crate) fn apply(&self, storage: &mut MemoryStorage) -> Result<()> {
+        match self {
+            Self::InsertSchema(schema) => storage.insert_schema(schema),
+            Self::DeleteSchema(table_name) => storage.delete_schema(table_name),
+            Self::AppendData(table_name, rows) => storage.append_data(table_name, rows.clone())?,
+            Self::InsertData(table_name, rows) => storage.insert_data(table_name, rows.clone())?,
+            Self::DeleteData(table_name, keys) => storage.delete_data(table_name, keys.clone())?,
+            Self::InsertNamespace(namespace) => storage.insert_namespace(namespace),
+            Self::DeleteNamespace(namespace) => storage.delete_namespace(namespace),
+            Self::InsertSequence(sequence) => storage.insert_sequence(sequence)?,
+            Self::DeleteSequence(name) => storage.delete_sequence(name),
+            Self::InsertView(view) => storage.insert_view(view),
+            Self::DeleteView(name) => storage.delete_view(name),
+            Self::UpdateStatistics(table_name, statistics) => {
+                storage.update_statistics(table_name, statistics)
+            }
+            Self::RenameSchema {
+                table_name,
+                new_table_name,
+            } => storage.rename_schema(table_name, new_table_name)?,
+            Self::RenameColumn {
+                table_name,
+                old_column_name,
+                new_column_name,
+            } => storage.rename_column(table_name, old_column_name, new_column_name)?,
+            Self::AddColumn(table_name, column_def) => {
+                storage.add_column(table_name, column_def)?
+            }
+            Self::DropColumn {
+                table_name,
+                column_name,
+                if_exists,
+            } => storage.drop_column(table_name, column_name, *if_exists)?,
+            Self::CreateIndex {
+                table_name,
+                index_name,
+                columns,
+            } => storage.create_index(table_name, index_name, columns)?,
+            Self::DropIndex {
+                table_name,
+                index_name,
+            } => storage.drop_index(table_name, index_name)?,
+        }
+
+        Ok(())
+    }
+
+    /// Tables the change is made on along with the keys of the rows it writes, `None` for
+    /// a change of the table itself. Appended rows are new, no one has read them before.
+    fn changes(&self) -> Vec<(&str, Option<&Key>)> {
+        match self {
+            Self::InsertData(table_name, rows) => rows
+                .iter()
+                .map(|(key, _)| (table_name.as_str(), Some(key)))
+                .collect(),
+            Self::DeleteData(table_name, keys) => keys
+                .iter()
+                .map(|key| (table_name.as_str(), Some(key)))
+                .collect(),
+            Self::InsertSchema(Schema { table_name, .. })
+            | Self::DeleteSchema(table_name)
+            | Self::AddColumn(table_name, _)
+            | Self::RenameColumn { table_name, .. }
+            | Self::DropColumn { table_name, .. }
+            | Self::CreateIndex { table_name, .. }
+            | Self::DropIndex { table_name, .. } => vec![(table_name.as_str(), None)],
+            Self::RenameSchema {
+                table_name,
+                new_table_name,
+            } => vec![(table_name.as_str(), None), (new_table_name.as_str(), None)],
+            Self::AppendData(..)
+            | Self::UpdateStatistics(..)
+            | Self::InsertNamespace(_)
+            | Self::DeleteNamespace(_)
+            | Self::InsertSequence(_)
+            | Self::DeleteSequence(_)
+            | Self::InsertView(_)
+            | Self::DeleteView(_) => Vec::new(),
+        }
+    }
+}
+
+/// Versions of the rows and tables changed on the shared database while transactions are
+/// open, by which a transaction finds at `COMMIT` the changes made since its `BEGIN`.
+#[derive(Debug, Default)]
+pub(crate) struct Versions {
+    /// incremented by each change
+    version: u64,
+    /// number of open transactions, the versions are forgotten once there is none
+    open: usize,
+    rows: HashMap<(String, Key), u64>,
+    tables: HashMap<String, u64>,
+}
+
+impl Versions {
+    pub(crate) fn lock(versions: &Mutex<Self>) -> MutexGuard<'_, Self> {
+        versions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records the change made on the shared database.
+    pub(crate) fn record(&mut self, operation: &Operation) {
+        if self.open == 0 {
+            return;
+        }
+
+        self.version += 1;
+        for (table_name, key) in operation.changes() {
+            match key {
+                Some(key) => self
+                    .rows
+                    .insert((table_name.to_owned(), key.clone()), self.version),
+                None => self.tables.insert(table_name.to_owned(), self.version),
+            };
+        }
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.open -= 1;
+
+        if self.open == 0 {
+            self.rows.clear();
+            self.tables.clear();
+        }
+    }
+}
+
+/// Transaction open on a single `SharedMemoryStorage` handle. It reads and writes its own
+/// copy of the database taken at `BEGIN`, and its changes are applied to the shared
+/// database at `COMMIT`, so the other handles never see them before.
+///
+/// The rows and tables it reads or writes are recorded, and `COMMIT` fails when any of
+/// them has been changed on the shared database since `BEGIN`, instead of overwriting the
+/// change.
+#[derive(Debug)]
+pub(crate) struct OpenTransaction {
+    pub(crate) storage: MemoryStorage,
+    operations: Vec<Operation>,
+    /// version of the shared database at `BEGIN`
+    version: u64,
+    rows: RefCell<HashSet<(String, Key)>>,
+    tables: RefCell<HashSet<String>>,
+}
+
+impl OpenTransaction {
+    pub(crate) fn apply(&mut self, operation: Operation) -> Result<()> {
+        operation.apply(&mut self.storage)?;
+
+        for (table_name, key) in operation.changes() {
+            self.touch(table_name, key);
+        }
+        self.operations.push(operation);
+
+        Ok(())
+    }
+
+    /// Records the row or the table read or written by the transaction.
+    pub(crate) fn touch(&self, table_name: &str, key: Option<&Key>) {
+        if !self.tables.borrow().contains(table_name) {
+            self.tables.borrow_mut().insert(table_name.to_owned());
+        }
+
+        if let Some(key) = key {
+            self.rows
+                .borrow_mut()
+                .insert((table_name.to_owned(), key.clone()));
+        }
+    }
+
+    /// Table whose rows or definition read or written by the transaction have been changed
+    /// since `BEGIN`.
+    fn conflict(&self, versions: &Versions) -> Option<String> {
+        let changed = |version: Option<&u64>| version.map_or(false, |v| *v > self.version);
+
+        let table_name = self
+            .tables
+            .borrow()
+            .iter()
+            .find(|table_name| changed(versions.tables.get(*table_name)))
+            .cloned();
+
+        table_name.or_else(|| {
+            self.rows
+                .borrow()
+                .iter()
+                .find(|row| changed(versions.rows.get(*row)))
+                .map(|(table_name, _)| table_name.clone())
+        })
+    }
+}
+
 #[async_trait(?Send)]
 impl Transaction for SharedMemoryStorage {
-    async fn begin(self, autocommit: bool) -> MutResult<Self, bool> {
-        if autocommit {
-            return Ok((self, false));
-        }
+    async fn begin(mut self, autocommit: bool) -> MutResult<Self, bool> {
+        match (self.transaction.is_some(), autocommit) {
+            (true, false) => Err((
+                self,
+                Error::StorageMsg(
+                    "[SharedMemoryStorage] nested transaction is not supported".to_owned(),
+                ),
+            )),
+            (_, true) => Ok((self, false)),
+            (false, false) => {
+                let database = self.database.read().await;
+                let version = {
+                    let mut versions = Versions::lock(&self.versions);
+                    versions.open += 1;
+
+                    versions.version
+                };
+                let storage = database.clone();
+                drop(database);
+
+                self.transaction = Some(Box::new(OpenTransaction {
+                    storage,
+                    operations: Vec::new(),
+                    version,
+                    rows: RefCell::new(HashSet::new()),
+                    tables: RefCell::new(HashSet::new()),
+                }));
 
-        Err((
-            self,
-            Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned()),
-        ))
+                Ok((self, false))
+            }
+        }
     }
 
-    async fn rollback(self) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned()),
-        ))
+    async fn rollback(mut self) -> MutResult<Self, ()> {
+        match self.transaction.take() {
+            Some(_) => {
+                Versions::lock(&self.versions).close();
+
+                Ok((self, ()))
+            }
+            None => Err((
+                self,
+                Error::StorageMsg("[SharedMemoryStorage] no transaction to rollback".to_owned()),
+            )),
+        }
     }
 
-    async fn commit(self) -> MutResult<Self, ()> {
-        Err((
-            self,
-            Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned()),
-        ))
+    /// Applies the changes of the transaction to the shared database in place, unless the
+    /// rows or tables it has read or written were changed by the other handles since
+    /// `BEGIN`, then it fails and the transaction is rolled back.
+    async fn commit(mut self) -> MutResult<Self, ()> {
+        let transaction = match self.transaction.take() {
+            Some(transaction) => transaction,
+            None => {
+                return Err((
+                    self,
+                    Error::StorageMsg("[SharedMemoryStorage] no transaction to commit".to_owned()),
+                ));
+            }
+        };
+
+        let result = {
+            let mut database = self.database.write().await;
+            let mut versions = Versions::lock(&self.versions);
+            let conflict = transaction.conflict(&versions);
+
+            let result = match conflict {
+                Some(table_name) => Err(Error::StorageMsg(format!(
+                    "[SharedMemoryStorage] transaction conflicts with a change committed on table '{table_name}'"
+                ))),
+                None => transaction.operations.iter().try_for_each(|operation| {
+                    match operation {
+                        // the value of a new sequence is reset on the shared database when
+                        // it is created in the transaction
+                        Operation::InsertSequence(sequence) => {
+                            database
+                                .sequences
+                                .insert(sequence.name.clone(), sequence.clone());
+                        }
+                        operation => operation.apply(&mut database)?,
+                    }
+
+                    versions.record(operation);
+
+                    Ok(())
+                }),
+            };
+
+            versions.close();
+
+            result
+        };
+
+        match result {
+            Ok(()) => Ok((self, ())),
+            Err(error) => Err((self, error)),
+        }
     }
 }
@@ -42,3 +42,107 @@ async fn concurrent_access() {
     }];
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn concurrent_transactions() {
+    use gluesql_core::prelude::{Glue, Payload};
+
+    let storage = SharedMemoryStorage::new();
+
+    let mut glue_1 = Glue::new(storage.clone());
+    let mut glue_2 = Glue::new(storage.clone());
+    let mut glue_3 = Glue::new(storage);
+
+    let select = |glue: &mut Glue<SharedMemoryStorage>| {
+        let rows = match glue
+            .execute("SELECT id FROM Tx ORDER BY id")
+            .unwrap()
+            .remove(0)
+        {
+            Payload::Select { rows, .. } => rows,
+            payload => panic!("unexpected payload: {payload:?}"),
+        };
+
+        rows.into_iter()
+            .map(|row| row.get_value_by_index(0).cloned().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    exec!(glue_1 "CREATE TABLE Tx (id INTEGER);");
+
+    // each handle opens its own transaction
+    exec!(glue_1 "BEGIN");
+    exec!(glue_2 "BEGIN");
+    exec!(glue_1 "INSERT INTO Tx VALUES (1);");
+    exec!(glue_2 "INSERT INTO Tx VALUES (2);");
+    exec!(glue_3 "INSERT INTO Tx VALUES (3);");
+
+    assert_eq!(select(&mut glue_1), vec![Value::I64(1)]);
+    assert_eq!(select(&mut glue_2), vec![Value::I64(2)]);
+    assert_eq!(select(&mut glue_3), vec![Value::I64(3)]);
+
+    // rollback keeps the rows committed by the other handles
+    exec!(glue_1 "ROLLBACK");
+    assert_eq!(select(&mut glue_1), vec![Value::I64(3)]);
+    assert_eq!(select(&mut glue_2), vec![Value::I64(2)]);
+
+    exec!(glue_2 "COMMIT");
+    assert_eq!(select(&mut glue_3), vec![Value::I64(2), Value::I64(3)]);
+
+    // commit ends only the transaction of its own handle
+    exec!(glue_1 "BEGIN");
+    exec!(glue_2 "BEGIN");
+    exec!(glue_2 "DELETE FROM Tx WHERE id = 2;");
+    exec!(glue_1 "COMMIT");
+    assert_eq!(select(&mut glue_1), vec![Value::I64(2), Value::I64(3)]);
+    assert_eq!(select(&mut glue_2), vec![Value::I64(3)]);
+
+    exec!(glue_2 "COMMIT");
+    assert_eq!(select(&mut glue_1), vec![Value::I64(3)]);
+}
+
+#[test]
+fn conflicting_transactions() {
+    use gluesql_core::{
+        prelude::{Glue, Payload},
+        result::Error,
+    };
+
+    let storage = SharedMemoryStorage::new();
+
+    let mut glue_1 = Glue::new(storage.clone());
+    let mut glue_2 = Glue::new(storage);
+
+    exec!(glue_1 "CREATE TABLE Tx (id INTEGER PRIMARY KEY, n INTEGER);");
+    exec!(glue_1 "INSERT INTO Tx VALUES (1, 0), (2, 0);");
+
+    // both handles increment the same row, the later commit fails instead of losing the update
+    exec!(glue_1 "BEGIN");
+    exec!(glue_2 "BEGIN");
+    exec!(glue_1 "UPDATE Tx SET n = n + 1 WHERE id = 1;");
+    exec!(glue_2 "UPDATE Tx SET n = n + 1 WHERE id = 1;");
+    exec!(glue_1 "COMMIT");
+
+    assert_eq!(
+        glue_2.execute("COMMIT"),
+        Err(Error::StorageMsg(
+            "[SharedMemoryStorage] transaction conflicts with a change committed on table 'Tx'"
+                .to_owned()
+        ))
+    );
+
+    // the changes of different rows are both committed
+    exec!(glue_1 "BEGIN");
+    exec!(glue_2 "BEGIN");
+    exec!(glue_1 "UPDATE Tx SET n = n + 1 WHERE id = 1;");
+    exec!(glue_2 "UPDATE Tx SET n = n + 1 WHERE id = 2;");
+    exec!(glue_1 "COMMIT");
+    exec!(glue_2 "COMMIT");
+
+    let actual = glue_1.execute("SELECT n FROM Tx ORDER BY id").unwrap();
+    let expected = vec![Payload::Select {
+        labels: vec!["n".to_owned()],
+        rows: vec![vec![Value::I64(2)].into(), vec![Value::I64(1)].into()],
+    }];
+    assert_eq!(actual, expected);
+}
@@ -20,8 +20,15 @@ impl Tester<SharedMemoryStorage> for SharedMemoryTester {
 }
 
 generate_store_tests!(tokio::test, SharedMemoryTester);
+generate_index_tests!(tokio::test, SharedMemoryTester);
 
 generate_alter_table_tests!(tokio::test, SharedMemoryTester);
+generate_alter_table_index_tests!(tokio::test, SharedMemoryTester);
+
+generate_transaction_tests!(tokio::test, SharedMemoryTester);
+generate_transaction_alter_table_tests!(tokio::test, SharedMemoryTester);
+generate_transaction_index_tests!(tokio::test, SharedMemoryTester);
+generate_transaction_dictionary_tests!(tokio::test, SharedMemoryTester);
 
 macro_rules! exec {
     ($glue: ident $sql: literal) => {
@@ -40,8 +47,8 @@ fn shared_memory_storage_index() {
     use futures::executor::block_on;
     use gluesql_core::{
         prelude::Glue,
-        result::{Error, Result},
-        store::{Index, Store},
+        result::Result,
+        store::{Index, IndexError, Store},
     };
 
     let storage = SharedMemoryStorage::new();
@@ -56,22 +63,22 @@ fn shared_memory_storage_index() {
     );
 
     assert_eq!(
-        block_on(storage.scan_indexed_data("Idx", "hello", None, None)).map(|_| ()),
-        Err(Error::StorageMsg(
-            "[Shared MemoryStorage] index is not supported".to_owned()
-        ))
+        block_on(storage.scan_indexed_data("Idx", "hello", None, Vec::new(), None)).map(|_| ()),
+        Err(IndexError::TableNotFound("Idx".to_owned()).into())
     );
 
     let mut glue = Glue::new(storage);
 
     exec!(glue "CREATE TABLE Idx (id INTEGER);");
+    exec!(glue "CREATE INDEX idx_id ON Idx (id);");
     test!(
         glue "CREATE INDEX idx_id ON Idx (id);",
-        Err(Error::StorageMsg("[Shared MemoryStorage] index is not supported".to_owned()))
+        Err(IndexError::IndexNameAlreadyExists("idx_id".to_owned()).into())
     );
+    exec!(glue "DROP INDEX Idx.idx_id;");
     test!(
         glue "DROP INDEX Idx.idx_id;",
-        Err(Error::StorageMsg("[Shared MemoryStorage] index is not supported".to_owned()))
+        Err(IndexError::IndexNameDoesNotExist("idx_id".to_owned()).into())
     );
 }
 
@@ -83,7 +90,9 @@ fn shared_memory_storage_transaction() {
     let mut glue = Glue::new(storage);
 
     exec!(glue "CREATE TABLE TxTest (id INTEGER);");
-    test!(glue "BEGIN", Err(Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned())));
-    test!(glue "COMMIT", Err(Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned())));
-    test!(glue "ROLLBACK", Err(Error::StorageMsg("[Shared MemoryStorage] transaction is not supported".to_owned())));
+    exec!(glue "BEGIN");
+    test!(glue "BEGIN", Err(Error::StorageMsg("[SharedMemoryStorage] nested transaction is not supported".to_owned())));
+    exec!(glue "COMMIT");
+    test!(glue "COMMIT", Err(Error::StorageMsg("[SharedMemoryStorage] no transaction to commit".to_owned())));
+    test!(glue "ROLLBACK", Err(Error::StorageMsg("[SharedMemoryStorage] no transaction to rollback".to_owned())));
 }
@@ -33,10 +33,13 @@ impl AlterTable for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -44,12 +47,17 @@ impl AlterTable for SledStorage {
             let schema_snapshot = schema_snapshot
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &schema_snapshot)?;
 
             // remove existing schema
             let (old_snapshot, old_schema) = schema_snapshot.delete(txid);
             let Schema {
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = old_schema
@@ -59,7 +67,11 @@ impl AlterTable for SledStorage {
             let new_schema = Schema {
                 table_name: new_table_name.to_owned(),
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
 
@@ -86,6 +98,7 @@ impl AlterTable for SledStorage {
                 let old_row_snapshot: Snapshot<Row> = bincode::deserialize(value)
                     .map_err(err_into)
                     .map_err(ConflictableTransactionError::Abort)?;
+                lock::validate_write(txid, &concurrent_txids, &old_row_snapshot)?;
 
                 let (old_row_snapshot, row) = old_row_snapshot.delete(txid);
                 let row = match row {
@@ -107,22 +120,18 @@ impl AlterTable for SledStorage {
                 tree.insert(old_key, old_row_snapshot)?;
                 tree.insert(new_key.as_bytes(), new_row_snapshot)?;
 
-                if !autocommit {
-                    let temp_old_key = key::temp_data(txid, old_key);
-                    let temp_new_key = key::temp_data_str(txid, &new_key);
+                let temp_old_key = key::temp_data(txid, old_key);
+                let temp_new_key = key::temp_data_str(txid, &new_key);
 
-                    tree.insert(temp_old_key, old_key)?;
-                    tree.insert(temp_new_key, new_key.as_bytes())?;
-                }
+                tree.insert(temp_old_key, old_key)?;
+                tree.insert(temp_new_key, new_key.as_bytes())?;
             }
 
-            if !autocommit {
-                let temp_old_key = key::temp_schema(txid, table_name);
-                let temp_new_key = key::temp_schema(txid, new_table_name);
+            let temp_old_key = key::temp_schema(txid, table_name);
+            let temp_new_key = key::temp_schema(txid, new_table_name);
 
-                tree.insert(temp_old_key, old_schema_key.as_bytes())?;
-                tree.insert(temp_new_key, new_schema_key.as_bytes())?;
-            }
+            tree.insert(temp_old_key, old_schema_key.as_bytes())?;
+            tree.insert(temp_new_key, new_schema_key.as_bytes())?;
 
             Ok(TxPayload::Success)
         });
@@ -142,10 +151,13 @@ impl AlterTable for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -153,14 +165,19 @@ impl AlterTable for SledStorage {
             let snapshot = snapshot
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &snapshot)?;
 
             let Schema {
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = snapshot
-                .get(txid, None)
+                .get(txid, &concurrent_txids)
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
 
@@ -194,11 +211,33 @@ impl AlterTable for SledStorage {
                 options,
             };
             let column_defs = Vector::from(column_defs).update(i, column_def).into();
+            let primary_key = primary_key.map(|primary_key| {
+                primary_key
+                    .into_iter()
+                    .map(|column_name| match column_name == old_column_name {
+                        true => new_column_name.to_owned(),
+                        false => column_name,
+                    })
+                    .collect()
+            });
+            let statistics = statistics.map(|mut statistics| {
+                statistics
+                    .columns
+                    .iter_mut()
+                    .filter(|column| column.name == old_column_name)
+                    .for_each(|column| column.name = new_column_name.to_owned());
+
+                statistics
+            });
 
             let schema = Schema {
                 table_name: table_name.to_owned(),
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
             let (snapshot, _) = snapshot.update(txid, schema);
@@ -207,11 +246,9 @@ impl AlterTable for SledStorage {
                 .map_err(ConflictableTransactionError::Abort)?;
             tree.insert(schema_key.as_bytes(), value)?;
 
-            if !autocommit {
-                let temp_key = key::temp_schema(txid, table_name);
+            let temp_key = key::temp_schema(txid, table_name);
 
-                tree.insert(temp_key, schema_key.as_bytes())?;
-            }
+            tree.insert(temp_key, schema_key.as_bytes())?;
 
             Ok(TxPayload::Success)
         });
@@ -234,10 +271,13 @@ impl AlterTable for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -245,15 +285,20 @@ impl AlterTable for SledStorage {
             let schema_snapshot = schema_snapshot
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &schema_snapshot)?;
 
             let Schema {
                 table_name,
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = schema_snapshot
-                .get(txid, None)
+                .get(txid, &concurrent_txids)
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
 
@@ -295,7 +340,8 @@ impl AlterTable for SledStorage {
                 let snapshot: Snapshot<Row> = bincode::deserialize(snapshot)
                     .map_err(err_into)
                     .map_err(ConflictableTransactionError::Abort)?;
-                let row = match snapshot.clone().extract(txid, None) {
+                lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+                let row = match snapshot.clone().extract(txid, &concurrent_txids) {
                     Some(row) => row,
                     None => {
                         continue;
@@ -310,11 +356,9 @@ impl AlterTable for SledStorage {
 
                 tree.insert(key, snapshot)?;
 
-                if !autocommit {
-                    let temp_key = key::temp_data(txid, key);
+                let temp_key = key::temp_data(txid, key);
 
-                    tree.insert(temp_key, key)?;
-                }
+                tree.insert(temp_key, key)?;
             }
 
             // update schema
@@ -328,7 +372,11 @@ impl AlterTable for SledStorage {
             let schema = Schema {
                 table_name,
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
             let (schema_snapshot, _) = schema_snapshot.update(txid, schema);
@@ -338,9 +386,7 @@ impl AlterTable for SledStorage {
 
             tree.insert(schema_key.as_bytes(), schema_value)?;
 
-            if !autocommit {
-                tree.insert(temp_key, schema_key.as_bytes())?;
-            }
+            tree.insert(temp_key, schema_key.as_bytes())?;
 
             Ok(TxPayload::Success)
         });
@@ -368,10 +414,13 @@ impl AlterTable for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -379,15 +428,20 @@ impl AlterTable for SledStorage {
             let schema_snapshot = schema_snapshot
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &schema_snapshot)?;
 
             let Schema {
                 table_name,
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = schema_snapshot
-                .get(txid, None)
+                .get(txid, &concurrent_txids)
                 .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
 
@@ -412,7 +466,8 @@ impl AlterTable for SledStorage {
                 let snapshot: Snapshot<Row> = bincode::deserialize(snapshot)
                     .map_err(err_into)
                     .map_err(ConflictableTransactionError::Abort)?;
-                let row = match snapshot.clone().extract(txid, None) {
+                lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+                let row = match snapshot.clone().extract(txid, &concurrent_txids) {
                     Some(row) => row,
                     None => {
                         continue;
@@ -432,11 +487,9 @@ impl AlterTable for SledStorage {
 
                 tree.insert(key, snapshot)?;
 
-                if !autocommit {
-                    let temp_key = key::temp_data(txid, key);
+                let temp_key = key::temp_data(txid, key);
 
-                    tree.insert(temp_key, key)?;
-                }
+                tree.insert(temp_key, key)?;
             }
 
             // update schema
@@ -445,13 +498,24 @@ impl AlterTable for SledStorage {
                 .enumerate()
                 .filter_map(|(i, v)| (i != column_index).then_some(v))
                 .collect::<Vec<ColumnDef>>();
+            let statistics = statistics.map(|mut statistics| {
+                statistics
+                    .columns
+                    .retain(|column| column.name != column_name);
+
+                statistics
+            });
 
             let temp_key = key::temp_schema(txid, &table_name);
 
             let schema = Schema {
                 table_name,
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
             let (schema_snapshot, _) = schema_snapshot.update(txid, schema);
@@ -460,9 +524,7 @@ impl AlterTable for SledStorage {
                 .map_err(ConflictableTransactionError::Abort)?;
             tree.insert(schema_key.as_bytes(), schema_value)?;
 
-            if !autocommit {
-                tree.insert(temp_key, schema_key.as_bytes())?;
-            }
+            tree.insert(temp_key, schema_key.as_bytes())?;
 
             Ok(TxPayload::Success)
         });
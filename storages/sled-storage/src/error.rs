@@ -15,6 +15,12 @@ pub enum StorageError {
     #[error(transparent)]
     Index(#[from] IndexError),
 
+    #[error(
+        "unsupported storage format version {0}, expected {}, dump the database with the release that wrote it and load it again",
+        crate::FORMAT_VERSION
+    )]
+    UnsupportedFormatVersion(u64),
+
     #[error(transparent)]
     Sled(#[from] sled::Error),
     #[error(transparent)]
@@ -39,6 +45,7 @@ impl From<StorageError> for Error {
             TryFromSlice(e) => Error::Storage(Box::new(e)),
             AlterTable(e) => e.into(),
             Index(e) => e.into(),
+            e @ UnsupportedFormatVersion(_) => Error::StorageMsg(e.to_string()),
         }
     }
 }
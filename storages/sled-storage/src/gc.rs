@@ -1,33 +1,34 @@
 use {
     super::{
         err_into, key,
-        lock::{get_txdata_key, Lock, TxData},
-        SledStorage, Snapshot,
+        lock::{self, get_txdata_key, TxData},
+        tx_err_into, SledStorage, Snapshot,
     },
     gluesql_core::{
-        data::{Row, Schema},
+        data::{Row, Schema, Sequence, View},
         result::Result,
     },
-    std::time::{SystemTime, UNIX_EPOCH},
+    std::{
+        iter::once,
+        time::{SystemTime, UNIX_EPOCH},
+    },
 };
 
 impl SledStorage {
     pub fn gc(&self) -> Result<()> {
-        let mut lock: Lock = self
-            .tree
-            .get("lock/")
-            .map_err(err_into)?
-            .map(|l| bincode::deserialize(&l))
-            .transpose()
-            .map_err(err_into)?
-            .unwrap_or_default();
-
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(err_into)?
             .as_millis();
 
-        let txids = self
+        let fetch_keys = |prefix| {
+            self.tree
+                .scan_prefix(prefix)
+                .map(|item| item.map_err(err_into))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let tx_datas = self
             .tree
             .scan_prefix("tx_data/")
             .map(|item| -> Result<TxData> {
@@ -35,43 +36,68 @@ impl SledStorage {
                     .map_err(err_into)?
                     .map_err(err_into)
             })
-            .take_while(|tx_data| match (tx_data, self.tx_timeout) {
-                (Ok(TxData { alive, .. }), None) => !alive,
-                (Ok(tx_data), Some(tx_timeout)) => {
-                    let TxData {
-                        txid,
-                        alive,
-                        created_at,
-                    } = tx_data;
-
-                    (!alive || now - created_at >= tx_timeout)
-                        && Some(txid) != lock.lock_txid.as_ref()
-                }
-                (Err(_), _) => false,
-            })
-            .map(|tx_data| tx_data.map(|TxData { txid, .. }| txid))
-            .collect::<Result<Vec<u64>>>()?;
+            .collect::<Result<Vec<_>>>()?;
+
+        // versions which are still visible to running transactions must be kept,
+        // so only transactions older than every running one can be cleaned up.
+        let mut horizon = u64::MAX;
+        let mut candidates = Vec::new();
+
+        for tx_data in tx_datas {
+            let TxData {
+                txid,
+                alive,
+                created_at,
+                concurrent_txids,
+            } = tx_data;
+
+            let expired = self
+                .tx_timeout
+                .map(|tx_timeout| now - created_at >= tx_timeout)
+                == Some(true);
+            let written = || -> Result<bool> {
+                Ok(!fetch_keys(key::temp_data_prefix(txid))?.is_empty()
+                    || !fetch_keys(key::temp_schema_prefix(txid))?.is_empty()
+                    || !fetch_keys(key::temp_namespace_prefix(txid))?.is_empty()
+                    || !fetch_keys(key::temp_sequence_prefix(txid))?.is_empty()
+                    || !fetch_keys(key::temp_view_prefix(txid))?.is_empty()
+                    || !fetch_keys(key::temp_index_prefix(txid))?.is_empty())
+            };
 
-        let max_txid = match txids.iter().last() {
-            Some(txid) => txid,
+            if !alive || (expired && !written()?) {
+                candidates.push(txid);
+            } else {
+                horizon = concurrent_txids
+                    .into_iter()
+                    .chain(once(txid))
+                    .fold(horizon, u64::min);
+            }
+        }
+
+        let txids = candidates
+            .into_iter()
+            .filter(|txid| *txid < horizon)
+            .collect::<Vec<_>>();
+
+        let max_txid = match txids.iter().max() {
+            Some(txid) => *txid,
             None => {
                 return Ok(());
             }
         };
 
-        lock.gc_txid = Some(*max_txid);
+        let gc_txids = &txids;
+        self.tree
+            .transaction(move |tree| {
+                let mut lock = lock::fetch_lock(tree)?;
 
-        bincode::serialize(&lock)
-            .map(|lock| self.tree.insert("lock/", lock))
-            .map_err(err_into)?
-            .map_err(err_into)?;
+                lock.gc_txid = Some(max_txid);
+                lock.active_txids
+                    .retain(|active_txid| !gc_txids.contains(active_txid));
 
-        let fetch_keys = |prefix| {
-            self.tree
-                .scan_prefix(prefix)
-                .map(|item| item.map_err(err_into))
-                .collect::<Result<Vec<_>>>()
-        };
+                lock::insert_lock(tree, &lock)
+            })
+            .map_err(tx_err_into)?;
 
         macro_rules! gc_txid {
             ($txid: expr, $prefix: expr, $T: ty) => {
@@ -111,6 +137,9 @@ impl SledStorage {
         for txid in txids {
             gc_txid!(txid, key::temp_data_prefix(txid), Row);
             gc_txid!(txid, key::temp_schema_prefix(txid), Schema);
+            gc_txid!(txid, key::temp_namespace_prefix(txid), String);
+            gc_txid!(txid, key::temp_sequence_prefix(txid), Sequence);
+            gc_txid!(txid, key::temp_view_prefix(txid), View);
 
             for (temp_key, data_key) in fetch_keys(key::temp_index_prefix(txid))? {
                 let snapshots: Option<Vec<Snapshot<Vec<u8>>>> = self
@@ -1,8 +1,8 @@
 use {
     super::{
         err_into,
-        index_sync::{build_index_key, build_index_key_prefix},
-        lock, SledStorage, Snapshot, State,
+        index_sync::{build_index_key, build_index_key_prefix, encode_index_value},
+        key, lock, SledStorage, Snapshot, State,
     },
     async_trait::async_trait,
     gluesql_core::{
@@ -14,7 +14,7 @@ use {
     },
     iter_enum::{DoubleEndedIterator, Iterator},
     sled::IVec,
-    std::iter::{empty, once},
+    std::iter::once,
     utils::Vector,
 };
 
@@ -25,30 +25,26 @@ impl Index for SledStorage {
         table_name: &str,
         index_name: &str,
         asc: Option<bool>,
+        prefix_values: Vec<Value>,
         cmp_value: Option<(&IndexOperator, Value)>,
     ) -> Result<RowIter> {
         let data_keys = {
             #[derive(Iterator, DoubleEndedIterator)]
-            enum DataIds<I1, I2, I3, I4> {
-                Empty(I1),
-                Once(I2),
-                Range(I3),
-                Full(I4),
+            enum DataIds<I1, I2> {
+                Prefix(I1),
+                Range(I2),
             }
 
             let map = |item: std::result::Result<_, _>| item.map(|(_, v)| v);
+            let prefix = build_index_key(table_name, index_name, &prefix_values)?;
 
             match cmp_value {
-                None => {
-                    let prefix = build_index_key_prefix(table_name, index_name);
-
-                    DataIds::Full(self.tree.scan_prefix(prefix).map(map))
-                }
+                None => DataIds::Prefix(self.tree.scan_prefix(prefix).map(map)),
                 Some((op, value)) => {
-                    let incr = |key: Vec<u8>| {
-                        key.into_iter()
+                    let incr = |key: &[u8]| -> Vec<u8> {
+                        key.iter()
                             .rev()
-                            .fold((false, Vector::new()), |(added, upper), v| {
+                            .fold((false, Vector::new()), |(added, upper), &v| {
                                 match (added, v) {
                                     (true, _) => (added, upper.push(v)),
                                     (false, u8::MAX) => (added, upper.push(v)),
@@ -59,24 +55,18 @@ impl Index for SledStorage {
                             .reverse()
                             .into()
                     };
-                    let lower = || build_index_key_prefix(table_name, index_name);
-                    let upper = || incr(build_index_key_prefix(table_name, index_name));
-                    let key = build_index_key(table_name, index_name, value)?;
+                    let key = [prefix.as_slice(), &encode_index_value(&value)?].concat();
+                    let upper = incr(&prefix);
 
                     match op {
-                        IndexOperator::Eq => match self.tree.get(&key).transpose() {
-                            Some(v) => DataIds::Once(once(v)),
-                            None => DataIds::Empty(empty()),
-                        },
+                        IndexOperator::Eq => DataIds::Prefix(self.tree.scan_prefix(key).map(map)),
                         IndexOperator::Gt => {
-                            DataIds::Range(self.tree.range(incr(key)..upper()).map(map))
-                        }
-                        IndexOperator::GtEq => {
-                            DataIds::Range(self.tree.range(key..upper()).map(map))
+                            DataIds::Range(self.tree.range(incr(&key)..upper).map(map))
                         }
-                        IndexOperator::Lt => DataIds::Range(self.tree.range(lower()..key).map(map)),
+                        IndexOperator::GtEq => DataIds::Range(self.tree.range(key..upper).map(map)),
+                        IndexOperator::Lt => DataIds::Range(self.tree.range(prefix..key).map(map)),
                         IndexOperator::LtEq => {
-                            DataIds::Range(self.tree.range(lower()..=key).map(map))
+                            DataIds::Range(self.tree.range(prefix..incr(&key)).map(map))
                         }
                     }
                 }
@@ -93,7 +83,8 @@ impl Index for SledStorage {
                 ));
             }
         };
-        let lock_txid = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        self.record_scan(txid, key::data_prefix(table_name).as_bytes());
 
         let prefix_len = build_index_key_prefix(table_name, index_name).len();
         let tree = self.tree.clone();
@@ -120,10 +111,11 @@ impl Index for SledStorage {
                 try_into!(bincode::deserialize(&keys).map_err(err_into));
 
             let tree2 = tree.clone();
+            let concurrent_txids = concurrent_txids.clone();
             let rows = keys
                 .into_iter()
                 .map(move |key_snapshot| -> Result<_> {
-                    let key = match key_snapshot.extract(txid, lock_txid) {
+                    let key = match key_snapshot.extract(txid, &concurrent_txids) {
                         Some(key) => key,
                         None => {
                             return Ok(None);
@@ -135,7 +127,7 @@ impl Index for SledStorage {
                         .map_err(err_into)?
                         .ok_or(IndexError::ConflictOnEmptyIndexValueScan)?;
                     let snapshot: Snapshot<Row> = bincode::deserialize(&value).map_err(err_into)?;
-                    let row = snapshot.extract(txid, lock_txid);
+                    let row = snapshot.extract(txid, &concurrent_txids);
                     let key = key.into_iter().skip(prefix_len).collect();
                     let item = row.map(|row| (Key::Bytea(key), row));
 
@@ -42,7 +42,7 @@ impl IndexMut for SledStorage {
         self,
         table_name: &str,
         index_name: &str,
-        column: &OrderByExpr,
+        columns: &[OrderByExpr],
     ) -> MutResult<Self, ()> {
         let (self, rows) = self.scan_data(table_name).await.try_self(self)?;
         let (self, rows) = rows.collect::<Result<Vec<_>>>().try_self(self)?;
@@ -50,24 +50,30 @@ impl IndexMut for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let txid = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, .. } => txid,
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
-            let index_expr = &column.expr;
-
             let (schema_key, schema_snapshot) = fetch_schema(tree, table_name)?;
             let schema_snapshot = schema_snapshot
                 .ok_or_else(|| IndexError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &schema_snapshot)?;
 
             let (schema_snapshot, schema) = schema_snapshot.delete(txid);
             let Schema {
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = schema
@@ -81,7 +87,7 @@ impl IndexMut for SledStorage {
 
             let index = SchemaIndex {
                 name: index_name.to_owned(),
-                expr: index_expr.clone(),
+                exprs: columns.iter().map(|column| column.expr.clone()).collect(),
                 order: SchemaIndexOrd::Both,
                 created: Utc::now().naive_utc(),
             };
@@ -94,7 +100,11 @@ impl IndexMut for SledStorage {
             let schema = Schema {
                 table_name: table_name.to_owned(),
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
 
@@ -120,7 +130,7 @@ impl IndexMut for SledStorage {
         });
 
         self.check_and_retry(tx_result, |storage| {
-            storage.create_index(table_name, index_name, column)
+            storage.create_index(table_name, index_name, columns)
         })
         .await
     }
@@ -132,10 +142,13 @@ impl IndexMut for SledStorage {
         let state = &self.state;
         let tx_timeout = self.tx_timeout;
         let tx_result = self.tree.transaction(move |tree| {
-            let txid = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, .. } => txid,
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -143,11 +156,16 @@ impl IndexMut for SledStorage {
             let schema_snapshot = schema_snapshot
                 .ok_or_else(|| IndexError::TableNotFound(table_name.to_owned()).into())
                 .map_err(ConflictableTransactionError::Abort)?;
+            lock::validate_write(txid, &concurrent_txids, &schema_snapshot)?;
 
             let (schema_snapshot, schema) = schema_snapshot.delete(txid);
             let Schema {
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
                 ..
             } = schema
@@ -169,7 +187,11 @@ impl IndexMut for SledStorage {
             let schema = Schema {
                 table_name: table_name.to_owned(),
                 column_defs,
+                primary_key,
+                foreign_keys,
+                checks,
                 indexes,
+                statistics,
                 created,
             };
 
@@ -65,7 +65,7 @@ impl<'a> IndexSync<'a> {
             ..
         } = fetch_schema(tree, table_name)
             .map(|(_, snapshot)| snapshot)?
-            .and_then(|snapshot| snapshot.extract(txid, None))
+            .and_then(|snapshot| snapshot.extract(txid, &[]))
             .ok_or_else(|| IndexError::ConflictTableNotFound(table_name.to_owned()))
             .map_err(err_into)
             .map_err(ConflictableTransactionError::Abort)?;
@@ -100,12 +100,12 @@ impl<'a> IndexSync<'a> {
     ) -> ConflictableTransactionResult<(), Error> {
         let SchemaIndex {
             name: index_name,
-            expr: index_expr,
+            exprs: index_exprs,
             ..
         } = index;
 
         let index_key =
-            &evaluate_index_key(self.table_name, index_name, index_expr, &self.columns, row)?;
+            &evaluate_index_key(self.table_name, index_name, index_exprs, &self.columns, row)?;
 
         self.insert_index_data(index_key, data_key)?;
 
@@ -121,14 +121,14 @@ impl<'a> IndexSync<'a> {
         for index in self.indexes.iter() {
             let SchemaIndex {
                 name: index_name,
-                expr: index_expr,
+                exprs: index_exprs,
                 ..
             } = index;
 
             let old_index_key = &evaluate_index_key(
                 self.table_name,
                 index_name,
-                index_expr,
+                index_exprs,
                 &self.columns,
                 old_row,
             )?;
@@ -136,7 +136,7 @@ impl<'a> IndexSync<'a> {
             let new_index_key = &evaluate_index_key(
                 self.table_name,
                 index_name,
-                index_expr,
+                index_exprs,
                 &self.columns,
                 new_row,
             )?;
@@ -164,12 +164,12 @@ impl<'a> IndexSync<'a> {
     ) -> ConflictableTransactionResult<(), Error> {
         let SchemaIndex {
             name: index_name,
-            expr: index_expr,
+            exprs: index_exprs,
             ..
         } = index;
 
         let index_key =
-            &evaluate_index_key(self.table_name, index_name, index_expr, &self.columns, row)?;
+            &evaluate_index_key(self.table_name, index_name, index_exprs, &self.columns, row)?;
 
         self.delete_index_data(index_key, data_key)?;
 
@@ -221,7 +221,7 @@ impl<'a> IndexSync<'a> {
         let data_keys = data_keys
             .into_iter()
             .map(|snapshot| {
-                let key = snapshot.get(self.txid, None);
+                let key = snapshot.get(self.txid, &[]);
 
                 if Some(data_key) == key.map(IVec::from).as_ref() {
                     snapshot.delete(self.txid).0
@@ -247,26 +247,49 @@ impl<'a> IndexSync<'a> {
 fn evaluate_index_key(
     table_name: &str,
     index_name: &str,
-    index_expr: &Expr,
+    index_exprs: &[Expr],
     columns: &[String],
     row: &Row,
 ) -> ConflictableTransactionResult<Vec<u8>, Error> {
-    let evaluated = evaluate_stateless(Some((columns, row)), index_expr)
-        .map_err(ConflictableTransactionError::Abort)?;
-    let value: Value = evaluated
-        .try_into()
+    let values = index_exprs
+        .iter()
+        .map(|index_expr| {
+            evaluate_stateless(Some((columns, row)), index_expr).and_then(Value::try_from)
+        })
+        .collect::<Result<Vec<_>>>()
         .map_err(ConflictableTransactionError::Abort)?;
 
-    build_index_key(table_name, index_name, value).map_err(ConflictableTransactionError::Abort)
+    build_index_key(table_name, index_name, &values).map_err(ConflictableTransactionError::Abort)
 }
 
 pub fn build_index_key_prefix(table_name: &str, index_name: &str) -> Vec<u8> {
     format!("index/{}/{}/", table_name, index_name).into_bytes()
 }
 
-pub fn build_index_key(table_name: &str, index_name: &str, value: Value) -> Result<Vec<u8>> {
-    Ok(build_index_key_prefix(table_name, index_name)
-        .into_iter()
-        .chain(value.to_cmp_be_bytes()?)
-        .collect::<Vec<_>>())
+pub fn build_index_key(table_name: &str, index_name: &str, values: &[Value]) -> Result<Vec<u8>> {
+    values.iter().try_fold(
+        build_index_key_prefix(table_name, index_name),
+        |key, value| Ok([key, encode_index_value(value)?].concat()),
+    )
+}
+
+/// Encodes a single column of an index key.
+///
+/// Every `0x00` byte of the comparable value bytes is escaped as `0x00 0xFF` and the value is
+/// terminated by `0x00 0x00`, so concatenated column values keep the tuple order and no encoded
+/// value is a prefix of another one.
+pub fn encode_index_value(value: &Value) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    for byte in value.to_cmp_be_bytes()? {
+        bytes.push(byte);
+
+        if byte == 0 {
+            bytes.push(u8::MAX);
+        }
+    }
+
+    bytes.extend([0, 0]);
+
+    Ok(bytes)
 }
@@ -2,12 +2,38 @@ use sled::IVec;
 
 const TEMP_DATA: &str = "temp_data/";
 const TEMP_SCHEMA: &str = "temp_schema/";
+const TEMP_NAMESPACE: &str = "temp_namespace/";
+const TEMP_SEQUENCE: &str = "temp_sequence/";
+const TEMP_VIEW: &str = "temp_view/";
 const TEMP_INDEX: &str = "temp_index/";
 
 pub fn data_prefix(table_name: &str) -> String {
     format!("data/{table_name}/")
 }
 
+pub const NAMESPACE_PREFIX: &str = "namespace/";
+
+pub fn namespace(namespace: &str) -> String {
+    format!("{NAMESPACE_PREFIX}{namespace}")
+}
+
+pub const SEQUENCE_PREFIX: &str = "sequence/";
+
+pub fn sequence(name: &str) -> String {
+    format!("{SEQUENCE_PREFIX}{name}")
+}
+
+/// Current value of a sequence, written outside of transactions
+pub fn sequence_value(name: &str) -> String {
+    format!("sequence_value/{name}")
+}
+
+pub const VIEW_PREFIX: &str = "view/";
+
+pub fn view(name: &str) -> String {
+    format!("{VIEW_PREFIX}{name}")
+}
+
 pub fn data(table_name: &str, key: Vec<u8>) -> IVec {
     let key = data_prefix(table_name)
         .into_bytes()
@@ -35,6 +61,18 @@ pub fn temp_schema_prefix(txid: u64) -> IVec {
     IVec::from_iter(prefix!(txid, TEMP_SCHEMA))
 }
 
+pub fn temp_namespace_prefix(txid: u64) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_NAMESPACE))
+}
+
+pub fn temp_sequence_prefix(txid: u64) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_SEQUENCE))
+}
+
+pub fn temp_view_prefix(txid: u64) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_VIEW))
+}
+
 pub fn temp_index_prefix(txid: u64) -> IVec {
     IVec::from_iter(prefix!(txid, TEMP_INDEX))
 }
@@ -51,6 +89,18 @@ pub fn temp_schema(txid: u64, table_name: &str) -> IVec {
     IVec::from_iter(prefix!(txid, TEMP_SCHEMA).chain(table_name.as_bytes().iter().copied()))
 }
 
+pub fn temp_namespace(txid: u64, namespace: &str) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_NAMESPACE).chain(namespace.as_bytes().iter().copied()))
+}
+
+pub fn temp_sequence(txid: u64, name: &str) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_SEQUENCE).chain(name.as_bytes().iter().copied()))
+}
+
+pub fn temp_view(txid: u64, name: &str) -> IVec {
+    IVec::from_iter(prefix!(txid, TEMP_VIEW).chain(name.as_bytes().iter().copied()))
+}
+
 pub fn temp_index(txid: u64, index_key: &[u8]) -> IVec {
     IVec::from_iter(prefix!(txid, TEMP_INDEX).chain(index_key.iter().copied()))
 }
@@ -8,6 +8,7 @@ mod index_mut;
 mod index_sync;
 mod key;
 mod lock;
+mod migrate;
 mod snapshot;
 mod store;
 mod store_mut;
@@ -18,7 +19,7 @@ pub use sled;
 
 use {
     self::snapshot::Snapshot,
-    error::{err_into, tx_err_into},
+    error::{err_into, tx_err_into, StorageError},
     gluesql_core::{
         data::Schema,
         result::{Error, Result},
@@ -29,11 +30,21 @@ use {
         },
         Config, Db,
     },
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+    },
 };
 
 /// default transaction timeout : 1 hour
 const DEFAULT_TX_TIMEOUT: u128 = 3600 * 1000;
 
+/// Version of the layout of the keys and values stored in the tree.
+///
+/// Version 1 changed the serialized `Lock`, `TxData` and `Schema` values and the encoding of
+/// the index keys. Trees written before it have no version, they are migrated when opened.
+pub const FORMAT_VERSION: u64 = 1;
+
 #[derive(Debug, Clone)]
 pub enum State {
     Idle,
@@ -51,6 +62,15 @@ pub struct SledStorage {
     pub state: State,
     /// transaction timeout in milliseconds
     pub tx_timeout: Option<u128>,
+    /// items read by the open transactions, shared by the clones of the storage
+    read_sets: Arc<Mutex<HashMap<u64, ReadSet>>>,
+}
+
+/// Keys and key prefixes read by a transaction, checked against concurrent writes on commit
+#[derive(Debug, Default)]
+struct ReadSet {
+    keys: HashSet<Vec<u8>>,
+    prefixes: HashSet<Vec<u8>>,
 }
 
 type ExportData<T> = (u64, Vec<(Vec<u8>, Vec<u8>, T)>);
@@ -58,6 +78,13 @@ type ExportData<T> = (u64, Vec<(Vec<u8>, Vec<u8>, T)>);
 impl SledStorage {
     pub fn new(filename: &str) -> Result<Self> {
         let tree = sled::open(filename).map_err(err_into)?;
+
+        Self::open(tree)
+    }
+
+    fn open(tree: Db) -> Result<Self> {
+        check_format_version(&tree)?;
+
         let id_offset = get_id_offset(&tree)?;
         let state = State::Idle;
         let tx_timeout = Some(DEFAULT_TX_TIMEOUT);
@@ -67,6 +94,7 @@ impl SledStorage {
             id_offset,
             state,
             tx_timeout,
+            read_sets: Arc::default(),
         })
     }
 
@@ -104,6 +132,7 @@ impl SledStorage {
             id_offset: self.id_offset,
             state,
             tx_timeout: self.tx_timeout,
+            read_sets: self.read_sets,
         }
     }
 }
@@ -113,16 +142,32 @@ impl TryFrom<Config> for SledStorage {
 
     fn try_from(config: Config) -> Result<Self> {
         let tree = config.open().map_err(err_into)?;
-        let id_offset = get_id_offset(&tree)?;
-        let state = State::Idle;
-        let tx_timeout = Some(DEFAULT_TX_TIMEOUT);
 
-        Ok(Self {
-            tree,
-            id_offset,
-            state,
-            tx_timeout,
+        Self::open(tree)
+    }
+}
+
+fn check_format_version(tree: &Db) -> Result<()> {
+    let version = tree
+        .get("format_version")
+        .map_err(err_into)?
+        .map(|version| {
+            version
+                .as_ref()
+                .try_into()
+                .map_err(err_into)
+                .map(u64::from_be_bytes)
         })
+        .transpose()?;
+
+    match version {
+        Some(FORMAT_VERSION) => Ok(()),
+        Some(version) => Err(StorageError::UnsupportedFormatVersion(version).into()),
+        None if tree.is_empty() => tree
+            .insert("format_version", &FORMAT_VERSION.to_be_bytes())
+            .map(|_| ())
+            .map_err(err_into),
+        None => migrate::from_v0(tree),
     }
 }
 
@@ -1,5 +1,5 @@
 use {
-    super::{err_into, State},
+    super::{err_into, tx_err_into, Snapshot, State},
     gluesql_core::result::{Error, Result},
     serde::{Deserialize, Serialize},
     sled::{
@@ -16,15 +16,17 @@ pub struct TxData {
     pub txid: u64,
     pub alive: bool,
     pub created_at: u128,
+    /// transactions which were still running when this transaction began
+    pub concurrent_txids: Vec<u64>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Lock {
-    pub lock_txid: Option<u64>,
-    pub lock_created_at: u128,
+    /// transactions which are currently running
+    pub active_txids: Vec<u64>,
     pub gc_txid: Option<u64>,
-    // TODO: support serializable transaction isolation level
-    // - prev_done_at: u128,
+    /// increased by every commit which has written something
+    pub commit_seq: u64,
 }
 
 pub fn get_txdata_key(txid: u64) -> Vec<u8> {
@@ -36,37 +38,76 @@ pub fn get_txdata_key(txid: u64) -> Vec<u8> {
         .collect::<Vec<_>>()
 }
 
-pub fn register(tree: &Db, id_offset: u64) -> Result<(u64, u128)> {
-    let txid = id_offset + tree.generate_id().map_err(err_into)?;
-    let key = get_txdata_key(txid);
-    let created_at = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(err_into)?
-        .as_millis();
-
-    let tx_data = TxData {
-        txid,
-        alive: true,
-        created_at,
-    };
+pub fn fetch_lock(tree: &TransactionalTree) -> ConflictableTransactionResult<Lock, Error> {
+    tree.get("lock/")?
+        .map(|l| bincode::deserialize(&l))
+        .transpose()
+        .map_err(err_into)
+        .map_err(ConflictableTransactionError::Abort)
+        .map(Option::unwrap_or_default)
+}
 
-    bincode::serialize(&tx_data)
+pub fn insert_lock(
+    tree: &TransactionalTree,
+    lock: &Lock,
+) -> ConflictableTransactionResult<(), Error> {
+    bincode::serialize(lock)
         .map_err(err_into)
-        .map(|tx_data| tree.insert(key, tx_data))?
-        .map_err(err_into)?;
+        .map_err(ConflictableTransactionError::Abort)
+        .map(|lock| tree.insert("lock/", lock))??;
 
-    Ok((txid, created_at))
+    Ok(())
 }
 
-pub fn fetch(
-    tree: &Db,
+fn fetch_tx_data(
+    tree: &TransactionalTree,
     txid: u64,
-    created_at: u128,
-    tx_timeout: Option<u128>,
-) -> Result<Option<u64>> {
-    let Lock {
-        lock_txid, gc_txid, ..
-    } = tree
+) -> ConflictableTransactionResult<Option<TxData>, Error> {
+    tree.get(get_txdata_key(txid))?
+        .map(|tx_data| bincode::deserialize(&tx_data))
+        .transpose()
+        .map_err(err_into)
+        .map_err(ConflictableTransactionError::Abort)
+}
+
+fn now() -> Result<u128> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(err_into)
+        .map(|now| now.as_millis())
+}
+
+pub fn register(tree: &Db, id_offset: u64) -> Result<(u64, u128)> {
+    let created_at = now()?;
+
+    tree.transaction(move |tree| {
+        let mut lock = fetch_lock(tree)?;
+        let txid = id_offset + tree.generate_id()?;
+
+        let tx_data = TxData {
+            txid,
+            alive: true,
+            created_at,
+            concurrent_txids: lock.active_txids.clone(),
+        };
+
+        bincode::serialize(&tx_data)
+            .map_err(err_into)
+            .map_err(ConflictableTransactionError::Abort)
+            .map(|tx_data| tree.insert(get_txdata_key(txid), tx_data))??;
+
+        lock.active_txids.push(txid);
+        insert_lock(tree, &lock)?;
+
+        Ok((txid, created_at))
+    })
+    .map_err(tx_err_into)
+}
+
+/// Returns transactions which were running when `txid` began.
+/// Changes made by these transactions are not visible to `txid`.
+pub fn fetch(tree: &Db, txid: u64, created_at: u128, tx_timeout: Option<u128>) -> Result<Vec<u64>> {
+    let Lock { gc_txid, .. } = tree
         .get("lock/")
         .map_err(err_into)?
         .map(|l| bincode::deserialize(&l))
@@ -74,10 +115,7 @@ pub fn fetch(
         .map_err(err_into)?
         .unwrap_or_default();
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(err_into)?
-        .as_millis();
+    let now = now()?;
 
     if tx_timeout.map(|tx_timeout| now - created_at >= tx_timeout) == Some(true) {
         return Err(Error::StorageMsg(
@@ -89,12 +127,26 @@ pub fn fetch(
         ));
     }
 
-    Ok(lock_txid)
+    let tx_data: Option<TxData> = tree
+        .get(get_txdata_key(txid))
+        .map_err(err_into)?
+        .map(|tx_data| bincode::deserialize(&tx_data))
+        .transpose()
+        .map_err(err_into)?;
+
+    Ok(tx_data
+        .map(|tx_data| tx_data.concurrent_txids)
+        .unwrap_or_default())
 }
 
 pub enum LockAcquired {
-    Success { txid: u64, autocommit: bool },
-    RollbackAndRetry { lock_txid: u64 },
+    Success {
+        txid: u64,
+        concurrent_txids: Vec<u64>,
+    },
+    RollbackAndRetry {
+        expired_txid: u64,
+    },
 }
 
 pub fn acquire(
@@ -103,23 +155,15 @@ pub fn acquire(
     tx_timeout: Option<u128>,
 ) -> ConflictableTransactionResult<LockAcquired, Error> {
     let Lock {
-        lock_txid,
-        lock_created_at,
+        active_txids,
         gc_txid,
-    } = tree
-        .get("lock/")?
-        .map(|l| bincode::deserialize(&l))
-        .transpose()
-        .map_err(err_into)
-        .map_err(ConflictableTransactionError::Abort)?
-        .unwrap_or_default();
+        ..
+    } = fetch_lock(tree)?;
 
-    let (txid, created_at, autocommit) = match state {
+    let (txid, created_at) = match state {
         State::Transaction {
-            txid,
-            created_at,
-            autocommit,
-        } => (*txid, *created_at, *autocommit),
+            txid, created_at, ..
+        } => (*txid, *created_at),
         State::Idle => {
             return Err(Error::StorageMsg(
                 "conflict - cannot acquire lock from idle state".to_owned(),
@@ -128,11 +172,7 @@ pub fn acquire(
         }
     };
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(err_into)
-        .map_err(ConflictableTransactionError::Abort)?
-        .as_millis();
+    let now = now().map_err(ConflictableTransactionError::Abort)?;
 
     if tx_timeout.map(|tx_timeout| now - created_at >= tx_timeout) == Some(true) {
         return Err(Error::StorageMsg(
@@ -146,88 +186,68 @@ pub fn acquire(
         .map_err(ConflictableTransactionError::Abort);
     }
 
-    let txid = match lock_txid {
-        Some(lock_txid) => {
-            if tx_timeout.map(|tx_timeout| now - lock_created_at >= tx_timeout) == Some(true) {
-                return Ok(LockAcquired::RollbackAndRetry { lock_txid });
-            } else if txid != lock_txid {
-                return Err(Error::StorageMsg("database is locked".to_owned()))
-                    .map_err(ConflictableTransactionError::Abort);
+    if let Some(tx_timeout) = tx_timeout {
+        for active_txid in active_txids
+            .into_iter()
+            .filter(|active_txid| *active_txid != txid)
+        {
+            let expired = fetch_tx_data(tree, active_txid)?
+                .map(|TxData { created_at, .. }| now - created_at >= tx_timeout)
+                .unwrap_or(true);
+
+            if expired {
+                return Ok(LockAcquired::RollbackAndRetry {
+                    expired_txid: active_txid,
+                });
             }
-
-            txid
-        }
-        None => {
-            let lock = Lock {
-                lock_txid: Some(txid),
-                lock_created_at: created_at,
-                gc_txid,
-            };
-
-            bincode::serialize(&lock)
-                .map_err(err_into)
-                .map_err(ConflictableTransactionError::Abort)
-                .map(|lock| tree.insert("lock/", lock))??;
-
-            txid
         }
-    };
-
-    Ok(LockAcquired::Success { txid, autocommit })
-}
+    }
 
-pub fn unregister(tree: &Db, txid: u64) -> Result<()> {
-    let key = get_txdata_key(txid);
-    let mut tx_data: TxData = tree
-        .get(&key)
-        .map_err(err_into)?
-        .ok_or_else(|| Error::StorageMsg("conflict - lock does not exist".to_owned()))
-        .map(|tx_data| bincode::deserialize(&tx_data))?
-        .map_err(err_into)?;
+    let concurrent_txids = fetch_tx_data(tree, txid)?
+        .map(|tx_data| tx_data.concurrent_txids)
+        .unwrap_or_default();
 
-    tx_data.alive = false;
+    Ok(LockAcquired::Success {
+        txid,
+        concurrent_txids,
+    })
+}
 
-    bincode::serialize(&tx_data)
-        .map(|tx_data| tree.insert(key, tx_data))
-        .map_err(err_into)?
-        .map_err(err_into)?;
+/// Rejects a write on top of the `snapshot` when its latest version has been
+/// created or deleted by a transaction whose changes `txid` cannot see.
+pub fn validate_write<T: Clone>(
+    txid: u64,
+    concurrent_txids: &[u64],
+    snapshot: &Snapshot<T>,
+) -> ConflictableTransactionResult<(), Error> {
+    match snapshot.last_txid() {
+        Some(last_txid)
+            if last_txid != txid && (last_txid > txid || concurrent_txids.contains(&last_txid)) =>
+        {
+            Err(ConflictableTransactionError::Abort(Error::StorageMsg(
+                "serialization failure - concurrent transaction has modified the same item"
+                    .to_owned(),
+            )))
+        }
+        _ => Ok(()),
+    }
+}
 
-    Ok(())
+pub fn unregister(tree: &Db, txid: u64) -> Result<()> {
+    tree.transaction(move |tree| release(tree, txid))
+        .map_err(tx_err_into)
 }
 
 pub fn release(tree: &TransactionalTree, txid: u64) -> ConflictableTransactionResult<(), Error> {
-    let Lock {
-        gc_txid, lock_txid, ..
-    } = tree
-        .get("lock/")?
-        .map(|l| bincode::deserialize(&l))
-        .transpose()
-        .map_err(err_into)
-        .map_err(ConflictableTransactionError::Abort)?
-        .unwrap_or_default();
+    let mut lock = fetch_lock(tree)?;
 
-    if Some(txid) == lock_txid {
-        let lock = Lock {
-            lock_txid: None,
-            lock_created_at: 0,
-            gc_txid,
-        };
+    if lock.active_txids.contains(&txid) {
+        lock.active_txids.retain(|active_txid| *active_txid != txid);
 
-        bincode::serialize(&lock)
-            .map_err(err_into)
-            .map_err(ConflictableTransactionError::Abort)
-            .map(|lock| tree.insert("lock/", lock))??;
+        insert_lock(tree, &lock)?;
     }
 
-    let key = get_txdata_key(txid);
-    let tx_data: Option<TxData> = tree
-        .get(&key)?
-        .map(|tx_data| bincode::deserialize(&tx_data))
-        .transpose()
-        .map_err(err_into)
-        .map_err(ConflictableTransactionError::Abort)?;
-
-    let mut tx_data = match tx_data {
+    let mut tx_data = match fetch_tx_data(tree, txid)? {
         Some(tx_data) => tx_data,
         None => {
             return Ok(());
@@ -239,7 +259,7 @@ pub fn release(tree: &TransactionalTree, txid: u64) -> ConflictableTransactionRe
     bincode::serialize(&tx_data)
         .map_err(err_into)
         .map_err(ConflictableTransactionError::Abort)
-        .map(|tx_data| tree.insert(key, tx_data))??;
+        .map(|tx_data| tree.insert(get_txdata_key(txid), tx_data))??;
 
     Ok(())
 }
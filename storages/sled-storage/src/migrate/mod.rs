@@ -0,0 +1,150 @@
+mod v0;
+
+use {
+    super::{err_into, get_id_offset, index_sync::IndexSync, key, tx_err_into, Snapshot},
+    crate::FORMAT_VERSION,
+    gluesql_core::{
+        data::{Row, Schema},
+        result::Result,
+    },
+    serde::Serialize,
+    sled::Db,
+};
+
+/// Keys of version 0 which are dropped, the index entries are built again and the
+/// bookkeeping of the transactions starts over.
+const OBSOLETE_PREFIXES: [&str; 7] = [
+    "index/",
+    "temp_data/",
+    "temp_schema/",
+    "temp_index/",
+    "tx_data/",
+    "lock/",
+    "gc_lock",
+];
+
+/// Rewrites a tree written before the format version was stamped.
+///
+/// Version 0 had a single writer lock, so the transaction holding it is the only one which can
+/// be unfinished, its changes are rolled back. The schemas are converted, the indexes are built
+/// again with the current key encoding and the version is stamped, all in one transaction.
+pub fn from_v0(tree: &Db) -> Result<()> {
+    let fetch_items = |prefix: &[u8]| {
+        tree.scan_prefix(prefix)
+            .map(|item| item.map_err(err_into))
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let v0::Lock { lock_txid } = tree
+        .get("lock/")
+        .map_err(err_into)?
+        .map(|lock| bincode::deserialize(&lock))
+        .transpose()
+        .map_err(err_into)?
+        .unwrap_or_default();
+
+    let schemas = fetch_items(b"schema/")?
+        .into_iter()
+        .map(|(key, value)| -> Result<_> {
+            let snapshot: Snapshot<v0::Schema> = bincode::deserialize(&value).map_err(err_into)?;
+            let snapshot = rollback(snapshot, lock_txid).map(|snapshot| snapshot.map(Schema::from));
+
+            Ok((key, snapshot))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rolled_back_rows = Vec::new();
+    if let Some(lock_txid) = lock_txid {
+        for (_, key) in fetch_items(&key::temp_data_prefix(lock_txid))? {
+            if let Some(value) = tree.get(&key).map_err(err_into)? {
+                let snapshot: Snapshot<Row> = bincode::deserialize(&value).map_err(err_into)?;
+
+                rolled_back_rows.push((key, snapshot.rollback(lock_txid)));
+            }
+        }
+    }
+
+    let txid = get_id_offset(tree)? + tree.generate_id().map_err(err_into)?;
+    let indexed_tables = schemas
+        .iter()
+        .filter_map(|(_, snapshot)| snapshot.as_ref()?.get(txid, &[]))
+        .filter(|schema| !schema.indexes.is_empty())
+        .map(|schema| -> Result<_> {
+            let rows = fetch_items(key::data_prefix(&schema.table_name).as_bytes())?
+                .into_iter()
+                .map(|(key, value)| -> Result<_> {
+                    let snapshot: Snapshot<Row> = bincode::deserialize(&value).map_err(err_into)?;
+                    let row = rollback(snapshot, lock_txid)
+                        .and_then(|snapshot| snapshot.extract(txid, &[]));
+
+                    Ok(row.map(|row| (key, row)))
+                })
+                .filter_map(Result::transpose)
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok((schema, rows))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let obsolete_keys = OBSOLETE_PREFIXES
+        .iter()
+        .map(|prefix| fetch_items(prefix.as_bytes()))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+
+    let schemas = schemas
+        .into_iter()
+        .map(|(key, snapshot)| Ok((key, serialize(snapshot)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let rolled_back_rows = rolled_back_rows
+        .into_iter()
+        .map(|(key, snapshot)| Ok((key, serialize(snapshot)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    tree.transaction(|tree| {
+        for key in obsolete_keys.iter() {
+            tree.remove(key)?;
+        }
+
+        for (key, value) in schemas.iter().chain(rolled_back_rows.iter()) {
+            match value {
+                Some(value) => tree.insert(key, value.as_slice())?,
+                None => tree.remove(key)?,
+            };
+        }
+
+        for (schema, rows) in indexed_tables.iter() {
+            let index_sync = IndexSync::from_schema(tree, txid, schema);
+
+            for (key, row) in rows.iter() {
+                index_sync.insert(key, row)?;
+            }
+        }
+
+        tree.insert("format_version", &FORMAT_VERSION.to_be_bytes())?;
+
+        Ok(())
+    })
+    .map_err(tx_err_into)?;
+
+    fetch_items(&key::temp_index_prefix(txid))?
+        .into_iter()
+        .try_for_each(|(key, _)| tree.remove(key).map(|_| ()).map_err(err_into))
+}
+
+fn rollback<T: Clone>(snapshot: Snapshot<T>, lock_txid: Option<u64>) -> Option<Snapshot<T>> {
+    match lock_txid {
+        Some(txid) => snapshot.rollback(txid),
+        None => Some(snapshot),
+    }
+}
+
+fn serialize<T: Serialize>(snapshot: Option<Snapshot<T>>) -> Result<Option<Vec<u8>>> {
+    snapshot
+        .map(|snapshot| bincode::serialize(&snapshot))
+        .transpose()
+        .map_err(err_into)
+}
@@ -0,0 +1,587 @@
+//! Values of format version 0 which the current types cannot read.
+//!
+//! Only the parts of the schema which have changed since are kept here. Version 0 could not
+//! store subqueries or aggregates in a schema, since `DEFAULT` values were evaluated without
+//! any rows and index expressions were limited to columns, literals and `CAST`.
+
+use {
+    gluesql_core::{
+        ast::{
+            self, AstLiteral, BinaryOperator, DataType, DateTimeField, TrimWhereField,
+            UnaryOperator,
+        },
+        chrono::NaiveDateTime,
+        data::{self, SchemaIndexOrd},
+    },
+    serde::{de, Deserialize, Deserializer},
+};
+
+/// Leading field of the lock, the transaction which was holding it when the tree was closed
+#[derive(Debug, Default, Deserialize)]
+pub struct Lock {
+    pub lock_txid: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schema {
+    pub table_name: String,
+    pub column_defs: Vec<ColumnDef>,
+    pub indexes: Vec<SchemaIndex>,
+    pub created: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaIndex {
+    pub name: String,
+    pub expr: Expr,
+    pub order: SchemaIndexOrd,
+    pub created: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnDef {
+    pub name: String,
+    pub data_type: DataType,
+    pub nullable: bool,
+    pub options: Vec<ColumnOption>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum ColumnOption {
+    Default(Expr),
+    Unique { is_primary: bool },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum Expr {
+    Identifier(String),
+    CompoundIdentifier {
+        alias: String,
+        ident: String,
+    },
+    IsNull(Box<Expr>),
+    IsNotNull(Box<Expr>),
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    InSubquery {
+        expr: Box<Expr>,
+        subquery: Unsupported,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        negated: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    Like {
+        expr: Box<Expr>,
+        negated: bool,
+        pattern: Box<Expr>,
+    },
+    ILike {
+        expr: Box<Expr>,
+        negated: bool,
+        pattern: Box<Expr>,
+    },
+    BinaryOp {
+        left: Box<Expr>,
+        op: BinaryOperator,
+        right: Box<Expr>,
+    },
+    UnaryOp {
+        op: UnaryOperator,
+        expr: Box<Expr>,
+    },
+    Nested(Box<Expr>),
+    Literal(AstLiteral),
+    TypedString {
+        data_type: DataType,
+        value: String,
+    },
+    Function(Box<Function>),
+    Aggregate(Unsupported),
+    Exists {
+        subquery: Unsupported,
+        negated: bool,
+    },
+    Subquery(Unsupported),
+    Case {
+        operand: Option<Box<Expr>>,
+        when_then: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+    ArrayIndex {
+        obj: Box<Expr>,
+        indexes: Vec<Expr>,
+    },
+    Interval {
+        expr: Box<Expr>,
+        leading_field: Option<DateTimeField>,
+        last_field: Option<DateTimeField>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum Function {
+    Abs(Expr),
+    Lower(Expr),
+    Upper(Expr),
+    Left {
+        expr: Expr,
+        size: Expr,
+    },
+    Right {
+        expr: Expr,
+        size: Expr,
+    },
+    Asin(Expr),
+    Acos(Expr),
+    Atan(Expr),
+    Lpad {
+        expr: Expr,
+        size: Expr,
+        fill: Option<Expr>,
+    },
+    Rpad {
+        expr: Expr,
+        size: Expr,
+        fill: Option<Expr>,
+    },
+    Cast {
+        expr: Expr,
+        data_type: DataType,
+    },
+    Ceil(Expr),
+    Concat(Vec<Expr>),
+    ConcatWs {
+        separator: Expr,
+        exprs: Vec<Expr>,
+    },
+    IfNull {
+        expr: Expr,
+        then: Expr,
+    },
+    Round(Expr),
+    Floor(Expr),
+    Trim {
+        expr: Expr,
+        filter_chars: Option<Expr>,
+        trim_where_field: Option<TrimWhereField>,
+    },
+    Exp(Expr),
+    Extract {
+        field: DateTimeField,
+        expr: Expr,
+    },
+    Ln(Expr),
+    Log {
+        antilog: Expr,
+        base: Expr,
+    },
+    Log2(Expr),
+    Log10(Expr),
+    Div {
+        dividend: Expr,
+        divisor: Expr,
+    },
+    Mod {
+        dividend: Expr,
+        divisor: Expr,
+    },
+    Gcd {
+        left: Expr,
+        right: Expr,
+    },
+    Lcm {
+        left: Expr,
+        right: Expr,
+    },
+    Sin(Expr),
+    Cos(Expr),
+    Tan(Expr),
+    Sqrt(Expr),
+    Power {
+        expr: Expr,
+        power: Expr,
+    },
+    Radians(Expr),
+    Degrees(Expr),
+    Now(),
+    Pi(),
+    Ltrim {
+        expr: Expr,
+        chars: Option<Expr>,
+    },
+    Rtrim {
+        expr: Expr,
+        chars: Option<Expr>,
+    },
+    Reverse(Expr),
+    Repeat {
+        expr: Expr,
+        num: Expr,
+    },
+    Sign(Expr),
+    Substr {
+        expr: Expr,
+        start: Expr,
+        count: Option<Expr>,
+    },
+    Unwrap {
+        expr: Expr,
+        selector: Expr,
+    },
+    GenerateUuid(),
+    Format {
+        expr: Expr,
+        format: Expr,
+    },
+    ToDate {
+        expr: Expr,
+        format: Expr,
+    },
+    ToTimestamp {
+        expr: Expr,
+        format: Expr,
+    },
+    ToTime {
+        expr: Expr,
+        format: Expr,
+    },
+    Position {
+        from_expr: Expr,
+        sub_expr: Expr,
+    },
+    Ascii(Expr),
+    Chr(Expr),
+}
+
+/// Subquery or aggregate, which no schema of version 0 holds.
+#[derive(Debug, Clone)]
+pub enum Unsupported {}
+
+impl<'de> Deserialize<'de> for Unsupported {
+    fn deserialize<D: Deserializer<'de>>(_: D) -> Result<Self, D::Error> {
+        Err(de::Error::custom(
+            "subqueries and aggregates of a schema cannot be migrated",
+        ))
+    }
+}
+
+impl From<Schema> for data::Schema {
+    fn from(schema: Schema) -> Self {
+        let Schema {
+            table_name,
+            column_defs,
+            indexes,
+            created,
+        } = schema;
+
+        data::Schema {
+            table_name,
+            column_defs: column_defs.into_iter().map(Into::into).collect(),
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            checks: Vec::new(),
+            indexes: indexes.into_iter().map(Into::into).collect(),
+            statistics: None,
+            created,
+        }
+    }
+}
+
+impl From<SchemaIndex> for data::SchemaIndex {
+    fn from(index: SchemaIndex) -> Self {
+        let SchemaIndex {
+            name,
+            expr,
+            order,
+            created,
+        } = index;
+
+        data::SchemaIndex {
+            name,
+            exprs: vec![expr.into()],
+            order,
+            created,
+        }
+    }
+}
+
+impl From<ColumnDef> for ast::ColumnDef {
+    fn from(column_def: ColumnDef) -> Self {
+        let ColumnDef {
+            name,
+            data_type,
+            nullable,
+            options,
+        } = column_def;
+
+        ast::ColumnDef {
+            name,
+            data_type,
+            nullable,
+            options: options.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ColumnOption> for ast::ColumnOption {
+    fn from(option: ColumnOption) -> Self {
+        match option {
+            ColumnOption::Default(expr) => ast::ColumnOption::Default(expr.into()),
+            ColumnOption::Unique { is_primary } => ast::ColumnOption::Unique { is_primary },
+        }
+    }
+}
+
+impl From<Box<Expr>> for Box<ast::Expr> {
+    fn from(expr: Box<Expr>) -> Self {
+        Box::new((*expr).into())
+    }
+}
+
+impl From<Expr> for ast::Expr {
+    fn from(expr: Expr) -> Self {
+        let exprs =
+            |exprs: Vec<Expr>| -> Vec<ast::Expr> { exprs.into_iter().map(Into::into).collect() };
+
+        match expr {
+            Expr::Identifier(ident) => ast::Expr::Identifier(ident),
+            Expr::CompoundIdentifier { alias, ident } => {
+                ast::Expr::CompoundIdentifier { alias, ident }
+            }
+            Expr::IsNull(expr) => ast::Expr::IsNull(expr.into()),
+            Expr::IsNotNull(expr) => ast::Expr::IsNotNull(expr.into()),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => ast::Expr::InList {
+                expr: expr.into(),
+                list: exprs(list),
+                negated,
+            },
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => ast::Expr::Between {
+                expr: expr.into(),
+                negated,
+                low: low.into(),
+                high: high.into(),
+            },
+            Expr::Like {
+                expr,
+                negated,
+                pattern,
+            } => ast::Expr::Like {
+                expr: expr.into(),
+                negated,
+                pattern: pattern.into(),
+            },
+            Expr::ILike {
+                expr,
+                negated,
+                pattern,
+            } => ast::Expr::ILike {
+                expr: expr.into(),
+                negated,
+                pattern: pattern.into(),
+            },
+            Expr::BinaryOp { left, op, right } => ast::Expr::BinaryOp {
+                left: left.into(),
+                op,
+                right: right.into(),
+            },
+            Expr::UnaryOp { op, expr } => ast::Expr::UnaryOp {
+                op,
+                expr: expr.into(),
+            },
+            Expr::Nested(expr) => ast::Expr::Nested(expr.into()),
+            Expr::Literal(literal) => ast::Expr::Literal(literal),
+            Expr::TypedString { data_type, value } => ast::Expr::TypedString { data_type, value },
+            Expr::Function(function) => ast::Expr::Function(Box::new((*function).into())),
+            Expr::Case {
+                operand,
+                when_then,
+                else_result,
+            } => ast::Expr::Case {
+                operand: operand.map(Into::into),
+                when_then: when_then
+                    .into_iter()
+                    .map(|(when, then)| (when.into(), then.into()))
+                    .collect(),
+                else_result: else_result.map(Into::into),
+            },
+            Expr::ArrayIndex { obj, indexes } => ast::Expr::ArrayIndex {
+                obj: obj.into(),
+                indexes: exprs(indexes),
+            },
+            Expr::Interval {
+                expr,
+                leading_field,
+                last_field,
+            } => ast::Expr::Interval {
+                expr: expr.into(),
+                leading_field,
+                last_field,
+            },
+            Expr::InSubquery { subquery, .. } | Expr::Exists { subquery, .. } => match subquery {},
+            Expr::Aggregate(unsupported) | Expr::Subquery(unsupported) => match unsupported {},
+        }
+    }
+}
+
+impl From<Function> for ast::Function {
+    fn from(function: Function) -> Self {
+        match function {
+            Function::Abs(expr) => ast::Function::Abs(expr.into()),
+            Function::Lower(expr) => ast::Function::Lower(expr.into()),
+            Function::Upper(expr) => ast::Function::Upper(expr.into()),
+            Function::Left { expr, size } => ast::Function::Left {
+                expr: expr.into(),
+                size: size.into(),
+            },
+            Function::Right { expr, size } => ast::Function::Right {
+                expr: expr.into(),
+                size: size.into(),
+            },
+            Function::Asin(expr) => ast::Function::Asin(expr.into()),
+            Function::Acos(expr) => ast::Function::Acos(expr.into()),
+            Function::Atan(expr) => ast::Function::Atan(expr.into()),
+            Function::Lpad { expr, size, fill } => ast::Function::Lpad {
+                expr: expr.into(),
+                size: size.into(),
+                fill: fill.map(Into::into),
+            },
+            Function::Rpad { expr, size, fill } => ast::Function::Rpad {
+                expr: expr.into(),
+                size: size.into(),
+                fill: fill.map(Into::into),
+            },
+            Function::Cast { expr, data_type } => ast::Function::Cast {
+                expr: expr.into(),
+                data_type,
+            },
+            Function::Ceil(expr) => ast::Function::Ceil(expr.into()),
+            Function::Concat(exprs) => {
+                ast::Function::Concat(exprs.into_iter().map(Into::into).collect())
+            }
+            Function::ConcatWs { separator, exprs } => ast::Function::ConcatWs {
+                separator: separator.into(),
+                exprs: exprs.into_iter().map(Into::into).collect(),
+            },
+            Function::IfNull { expr, then } => ast::Function::IfNull {
+                expr: expr.into(),
+                then: then.into(),
+            },
+            Function::Round(expr) => ast::Function::Round(expr.into()),
+            Function::Floor(expr) => ast::Function::Floor(expr.into()),
+            Function::Trim {
+                expr,
+                filter_chars,
+                trim_where_field,
+            } => ast::Function::Trim {
+                expr: expr.into(),
+                filter_chars: filter_chars.map(Into::into),
+                trim_where_field,
+            },
+            Function::Exp(expr) => ast::Function::Exp(expr.into()),
+            Function::Extract { field, expr } => ast::Function::Extract {
+                field,
+                expr: expr.into(),
+            },
+            Function::Ln(expr) => ast::Function::Ln(expr.into()),
+            Function::Log { antilog, base } => ast::Function::Log {
+                antilog: antilog.into(),
+                base: base.into(),
+            },
+            Function::Log2(expr) => ast::Function::Log2(expr.into()),
+            Function::Log10(expr) => ast::Function::Log10(expr.into()),
+            Function::Div { dividend, divisor } => ast::Function::Div {
+                dividend: dividend.into(),
+                divisor: divisor.into(),
+            },
+            Function::Mod { dividend, divisor } => ast::Function::Mod {
+                dividend: dividend.into(),
+                divisor: divisor.into(),
+            },
+            Function::Gcd { left, right } => ast::Function::Gcd {
+                left: left.into(),
+                right: right.into(),
+            },
+            Function::Lcm { left, right } => ast::Function::Lcm {
+                left: left.into(),
+                right: right.into(),
+            },
+            Function::Sin(expr) => ast::Function::Sin(expr.into()),
+            Function::Cos(expr) => ast::Function::Cos(expr.into()),
+            Function::Tan(expr) => ast::Function::Tan(expr.into()),
+            Function::Sqrt(expr) => ast::Function::Sqrt(expr.into()),
+            Function::Power { expr, power } => ast::Function::Power {
+                expr: expr.into(),
+                power: power.into(),
+            },
+            Function::Radians(expr) => ast::Function::Radians(expr.into()),
+            Function::Degrees(expr) => ast::Function::Degrees(expr.into()),
+            Function::Now() => ast::Function::Now(),
+            Function::Pi() => ast::Function::Pi(),
+            Function::Ltrim { expr, chars } => ast::Function::Ltrim {
+                expr: expr.into(),
+                chars: chars.map(Into::into),
+            },
+            Function::Rtrim { expr, chars } => ast::Function::Rtrim {
+                expr: expr.into(),
+                chars: chars.map(Into::into),
+            },
+            Function::Reverse(expr) => ast::Function::Reverse(expr.into()),
+            Function::Repeat { expr, num } => ast::Function::Repeat {
+                expr: expr.into(),
+                num: num.into(),
+            },
+            Function::Sign(expr) => ast::Function::Sign(expr.into()),
+            Function::Substr { expr, start, count } => ast::Function::Substr {
+                expr: expr.into(),
+                start: start.into(),
+                count: count.map(Into::into),
+            },
+            Function::Unwrap { expr, selector } => ast::Function::Unwrap {
+                expr: expr.into(),
+                selector: selector.into(),
+            },
+            Function::GenerateUuid() => ast::Function::GenerateUuid(),
+            Function::Format { expr, format } => ast::Function::Format {
+                expr: expr.into(),
+                format: format.into(),
+            },
+            Function::ToDate { expr, format } => ast::Function::ToDate {
+                expr: expr.into(),
+                format: format.into(),
+            },
+            Function::ToTimestamp { expr, format } => ast::Function::ToTimestamp {
+                expr: expr.into(),
+                format: format.into(),
+            },
+            Function::ToTime { expr, format } => ast::Function::ToTime {
+                expr: expr.into(),
+                format: format.into(),
+            },
+            Function::Position {
+                from_expr,
+                sub_expr,
+            } => ast::Function::Position {
+                from_expr: from_expr.into(),
+                sub_expr: sub_expr.into(),
+            },
+            Function::Ascii(expr) => ast::Function::Ascii(expr.into()),
+            Function::Chr(expr) => ast::Function::Chr(expr.into()),
+        }
+    }
+}
@@ -54,6 +54,20 @@ impl<T: Clone> Snapshot<T> {
         }
     }
 
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Snapshot<U> {
+        let items = self
+            .0
+            .into_iter()
+            .map(|item| SnapshotItem {
+                data: f(item.data),
+                created_by: item.created_by,
+                deleted_by: item.deleted_by,
+            })
+            .collect();
+
+        Snapshot(items)
+    }
+
     pub fn rollback(self, txid: u64) -> Option<Self> {
         let items = self
             .0
@@ -74,23 +88,17 @@ impl<T: Clone> Snapshot<T> {
         (!items.is_empty()).then_some(Snapshot(items))
     }
 
-    pub fn extract(self, txid: u64, lock_txid: Option<u64>) -> Option<T> {
-        let lock_txid = if Some(txid) == lock_txid {
-            None
-        } else {
-            lock_txid
-        };
+    pub fn extract(self, txid: u64, concurrent_txids: &[u64]) -> Option<T> {
+        let visible = |item_txid: u64| txid >= item_txid && !concurrent_txids.contains(&item_txid);
 
         for item in self.0 {
-            if Some(item.created_by) == lock_txid {
+            if !visible(item.created_by) {
                 continue;
             }
 
-            let deleted = item.deleted_by.is_some()
-                && item.deleted_by != lock_txid
-                && Some(txid) >= item.deleted_by;
+            let deleted = item.deleted_by.map(visible) == Some(true);
 
-            if txid >= item.created_by && !deleted {
+            if !deleted {
                 return Some(item.data);
             }
         }
@@ -98,23 +106,17 @@ impl<T: Clone> Snapshot<T> {
         None
     }
 
-    pub fn get(&self, txid: u64, lock_txid: Option<u64>) -> Option<T> {
-        let lock_txid = if Some(txid) == lock_txid {
-            None
-        } else {
-            lock_txid
-        };
+    pub fn get(&self, txid: u64, concurrent_txids: &[u64]) -> Option<T> {
+        let visible = |item_txid: u64| txid >= item_txid && !concurrent_txids.contains(&item_txid);
 
         for item in self.0.iter() {
-            if Some(item.created_by) == lock_txid {
+            if !visible(item.created_by) {
                 continue;
             }
 
-            let deleted = item.deleted_by.is_some()
-                && item.deleted_by != lock_txid
-                && Some(txid) >= item.deleted_by;
+            let deleted = item.deleted_by.map(visible) == Some(true);
 
-            if txid >= item.created_by && !deleted {
+            if !deleted {
                 return Some(item.data.clone());
             }
         }
@@ -122,6 +124,13 @@ impl<T: Clone> Snapshot<T> {
         None
     }
 
+    /// Transaction which has made the latest change to the snapshot
+    pub fn last_txid(&self) -> Option<u64> {
+        self.0
+            .first()
+            .map(|item| item.deleted_by.unwrap_or(item.created_by))
+    }
+
     pub fn gc(self, txid: u64) -> Option<Self> {
         let items = self
             .0
@@ -2,9 +2,9 @@ use {
     super::{err_into, key, lock, SledStorage, Snapshot, State},
     async_trait::async_trait,
     gluesql_core::{
-        data::{Key, Row, Schema},
+        data::{Key, Row, Schema, Sequence, View},
         result::{Error, Result},
-        store::{RowIter, Store},
+        store::{project_scan_row, RowIter, ScanFilter, Store},
     },
     std::str,
 };
@@ -14,25 +14,37 @@ const SCHEMA_PREFIX: &str = "schema/";
 #[async_trait(?Send)]
 impl Store for SledStorage {
     async fn fetch_all_schemas(&self) -> Result<Vec<Schema>> {
-        let (txid, created_at) = match self.state {
+        let (txid, created_at, temp) = match self.state {
             State::Transaction {
                 txid, created_at, ..
-            } => (txid, created_at),
-            State::Idle => lock::register(&self.tree, self.id_offset)?,
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
         };
-        let lock_txid = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
 
-        self.tree
+        if !temp {
+            self.record_scan(txid, SCHEMA_PREFIX.as_bytes());
+        }
+
+        let schemas = self
+            .tree
             .scan_prefix(SCHEMA_PREFIX)
-            .map(move |item| {
+            .map(|item| {
                 let (_, value) = item.map_err(err_into)?;
                 let snapshot: Snapshot<Schema> = bincode::deserialize(&value).map_err(err_into)?;
-                let schema = snapshot.extract(txid, lock_txid);
+                let schema = snapshot.extract(txid, &concurrent_txids);
 
                 Ok(schema)
             })
             .filter_map(|result| result.transpose())
-            .collect::<Result<Vec<_>>>()
+            .collect::<Result<Vec<_>>>()?;
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(schemas)
     }
 
     async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
@@ -43,9 +55,14 @@ impl Store for SledStorage {
             State::Idle => lock::register(&self.tree, self.id_offset)
                 .map(|(txid, created_at)| (txid, created_at, true))?,
         };
-        let lock_txid = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
 
         let key = format!("schema/{}", table_name);
+
+        if !temp {
+            self.record_read(txid, key.as_bytes());
+        }
+
         let schema = self
             .tree
             .get(key.as_bytes())
@@ -53,7 +70,7 @@ impl Store for SledStorage {
             .map(|v| bincode::deserialize(&v))
             .transpose()
             .map_err(err_into)?
-            .and_then(|snapshot: Snapshot<Schema>| snapshot.extract(txid, lock_txid));
+            .and_then(|snapshot: Snapshot<Schema>| snapshot.extract(txid, &concurrent_txids));
 
         if temp {
             lock::unregister(&self.tree, txid)?;
@@ -73,9 +90,11 @@ impl Store for SledStorage {
                 ));
             }
         };
-        let lock_txid = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
 
         let key = key::data(table_name, key.to_cmp_be_bytes());
+        self.record_read(txid, &key);
+
         let row = self
             .tree
             .get(&key)
@@ -83,12 +102,231 @@ impl Store for SledStorage {
             .map(|v| bincode::deserialize(&v))
             .transpose()
             .map_err(err_into)?
-            .and_then(|snapshot: Snapshot<Row>| snapshot.extract(txid, lock_txid));
+            .and_then(|snapshot: Snapshot<Row>| snapshot.extract(txid, &concurrent_txids));
 
         Ok(row)
     }
 
     async fn scan_data(&self, table_name: &str) -> Result<RowIter> {
+        self.scan_rows(table_name, None, None)
+    }
+
+    async fn scan_filtered_data(
+        &self,
+        table_name: &str,
+        columns: Option<&[usize]>,
+        filter: Option<&ScanFilter>,
+    ) -> Result<RowIter> {
+        self.scan_rows(table_name, columns.map(<[usize]>::to_vec), filter.cloned())
+    }
+
+    async fn fetch_namespaces(&self) -> Result<Vec<String>> {
+        let (txid, created_at, temp) = match self.state {
+            State::Transaction {
+                txid, created_at, ..
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
+        };
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+
+        if !temp {
+            self.record_scan(txid, key::NAMESPACE_PREFIX.as_bytes());
+        }
+
+        let namespaces = self
+            .tree
+            .scan_prefix(key::NAMESPACE_PREFIX)
+            .map(|item| {
+                let (_, value) = item.map_err(err_into)?;
+                let snapshot: Snapshot<String> = bincode::deserialize(&value).map_err(err_into)?;
+
+                Ok(snapshot.extract(txid, &concurrent_txids))
+            })
+            .filter_map(|result| result.transpose())
+            .collect::<Result<Vec<_>>>()?;
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(namespaces)
+    }
+
+    async fn fetch_sequence(&self, name: &str) -> Result<Option<Sequence>> {
+        let (txid, created_at, temp) = match self.state {
+            State::Transaction {
+                txid, created_at, ..
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
+        };
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+
+        let key = key::sequence(name);
+
+        if !temp {
+            self.record_read(txid, key.as_bytes());
+        }
+
+        let sequence = self
+            .tree
+            .get(key.as_bytes())
+            .map_err(err_into)?
+            .map(|v| bincode::deserialize(&v))
+            .transpose()
+            .map_err(err_into)?
+            .and_then(|snapshot: Snapshot<Sequence>| snapshot.extract(txid, &concurrent_txids));
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(sequence)
+    }
+
+    async fn fetch_all_sequences(&self) -> Result<Vec<Sequence>> {
+        let (txid, created_at, temp) = match self.state {
+            State::Transaction {
+                txid, created_at, ..
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
+        };
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+
+        if !temp {
+            self.record_scan(txid, key::SEQUENCE_PREFIX.as_bytes());
+        }
+
+        let sequences = self
+            .tree
+            .scan_prefix(key::SEQUENCE_PREFIX)
+            .map(|item| {
+                let (_, value) = item.map_err(err_into)?;
+                let snapshot: Snapshot<Sequence> =
+                    bincode::deserialize(&value).map_err(err_into)?;
+
+                Ok(snapshot.extract(txid, &concurrent_txids))
+            })
+            .filter_map(|result| result.transpose())
+            .collect::<Result<Vec<_>>>()?;
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(sequences)
+    }
+
+    async fn next_sequence_value(&self, sequence: &Sequence) -> Result<i64> {
+        let key = key::sequence_value(&sequence.name);
+
+        loop {
+            let current = self.tree.get(key.as_bytes()).map_err(err_into)?;
+            let value = current
+                .as_ref()
+                .map(|v| bincode::deserialize(v))
+                .transpose()
+                .map_err(err_into)?;
+            let value = sequence.next_value(value)?;
+            let new = bincode::serialize(&value).map_err(err_into)?;
+
+            let swapped = self
+                .tree
+                .compare_and_swap(key.as_bytes(), current, Some(new))
+                .map_err(err_into)?;
+
+            if swapped.is_ok() {
+                return Ok(value);
+            }
+        }
+    }
+
+    async fn current_sequence_value(&self, name: &str) -> Result<Option<i64>> {
+        self.tree
+            .get(key::sequence_value(name).as_bytes())
+            .map_err(err_into)?
+            .map(|v| bincode::deserialize(&v))
+            .transpose()
+            .map_err(err_into)
+    }
+
+    async fn fetch_view(&self, name: &str) -> Result<Option<View>> {
+        let (txid, created_at, temp) = match self.state {
+            State::Transaction {
+                txid, created_at, ..
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
+        };
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+
+        let key = key::view(name);
+
+        if !temp {
+            self.record_read(txid, key.as_bytes());
+        }
+
+        let view = self
+            .tree
+            .get(key.as_bytes())
+            .map_err(err_into)?
+            .map(|v| bincode::deserialize(&v))
+            .transpose()
+            .map_err(err_into)?
+            .and_then(|snapshot: Snapshot<View>| snapshot.extract(txid, &concurrent_txids));
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(view)
+    }
+
+    async fn fetch_all_views(&self) -> Result<Vec<View>> {
+        let (txid, created_at, temp) = match self.state {
+            State::Transaction {
+                txid, created_at, ..
+            } => (txid, created_at, false),
+            State::Idle => lock::register(&self.tree, self.id_offset)
+                .map(|(txid, created_at)| (txid, created_at, true))?,
+        };
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+
+        if !temp {
+            self.record_scan(txid, key::VIEW_PREFIX.as_bytes());
+        }
+
+        let views = self
+            .tree
+            .scan_prefix(key::VIEW_PREFIX)
+            .map(|item| {
+                let (_, value) = item.map_err(err_into)?;
+                let snapshot: Snapshot<View> = bincode::deserialize(&value).map_err(err_into)?;
+
+                Ok(snapshot.extract(txid, &concurrent_txids))
+            })
+            .filter_map(|result| result.transpose())
+            .collect::<Result<Vec<_>>>()?;
+
+        if temp {
+            lock::unregister(&self.tree, txid)?;
+        }
+
+        Ok(views)
+    }
+}
+
+impl SledStorage {
+    /// Scans the rows of the table, checking each of them against the filter and projecting
+    /// it to the columns as soon as it is decoded.
+    fn scan_rows(
+        &self,
+        table_name: &str,
+        columns: Option<Vec<usize>>,
+        filter: Option<ScanFilter>,
+    ) -> Result<RowIter> {
         let (txid, created_at) = match self.state {
             State::Transaction {
                 txid, created_at, ..
@@ -99,21 +337,29 @@ impl Store for SledStorage {
                 ));
             }
         };
-        let lock_txid = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
+        let concurrent_txids = lock::fetch(&self.tree, txid, created_at, self.tx_timeout)?;
 
         let prefix = key::data_prefix(table_name);
+        self.record_scan(txid, prefix.as_bytes());
+
         let prefix_len = prefix.len();
         let result_set = self
             .tree
             .scan_prefix(prefix.as_bytes())
             .map(move |item| {
                 let (key, value) = item.map_err(err_into)?;
-                let key = key.subslice(prefix_len, key.len() - prefix_len).to_vec();
                 let snapshot: Snapshot<Row> = bincode::deserialize(&value).map_err(err_into)?;
-                let row = snapshot.extract(txid, lock_txid);
-                let item = row.map(|row| (Key::Bytea(key), row));
+                let row = match snapshot.extract(txid, &concurrent_txids) {
+                    Some(row) if filter.as_ref().map_or(true, |filter| filter.check(&row)) => row,
+                    _ => return Ok(None),
+                };
+                let row = match &columns {
+                    Some(columns) => project_scan_row(columns, &row),
+                    None => row,
+                };
+                let key = key.subslice(prefix_len, key.len() - prefix_len).to_vec();
 
-                Ok(item)
+                Ok(Some((Key::Bytea(key), row)))
             })
             .filter_map(|item| item.transpose());
 
@@ -1,6 +1,6 @@
 use {
     super::{
-        err_into,
+        err_into, fetch_schema,
         index_sync::IndexSync,
         key,
         lock::{self, LockAcquired},
@@ -9,7 +9,7 @@ use {
     },
     async_trait::async_trait,
     gluesql_core::{
-        data::{Key, Row, Schema},
+        data::{Key, Row, Schema, Sequence, Statistics, View},
         result::MutResult,
         result::Result,
         store::{IndexError, StoreMut},
@@ -24,10 +24,13 @@ impl StoreMut for SledStorage {
         let tx_timeout = self.tx_timeout;
 
         let tx_result = self.tree.transaction(move |tree| {
-            let txid = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, .. } => txid,
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -43,7 +46,11 @@ impl StoreMut for SledStorage {
 
             let schema = schema.clone();
             let snapshot = match snapshot {
-                Some(snapshot) => snapshot.update(txid, schema).0,
+                Some(snapshot) => {
+                    lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+
+                    snapshot.update(txid, schema).0
+                }
                 None => Snapshot::<Schema>::new(txid, schema),
             };
             let snapshot = bincode::serialize(&snapshot)
@@ -78,10 +85,13 @@ impl StoreMut for SledStorage {
         let tx_timeout = self.tx_timeout;
 
         let tx_result = self.tree.transaction(move |tree| {
-            let txid = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, .. } => txid,
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -95,6 +105,10 @@ impl StoreMut for SledStorage {
                 .map_err(err_into)
                 .map_err(ConflictableTransactionError::Abort)?;
 
+            if let Some(snapshot) = &snapshot {
+                lock::validate_write(txid, &concurrent_txids, snapshot)?;
+            }
+
             let (snapshot, schema) = match snapshot.map(|snapshot| snapshot.delete(txid)) {
                 Some((snapshot, Some(schema))) => (snapshot, schema),
                 Some((_, None)) | None => {
@@ -115,6 +129,7 @@ impl StoreMut for SledStorage {
                 let row_snapshot: Snapshot<Row> = bincode::deserialize(row_snapshot)
                     .map_err(err_into)
                     .map_err(ConflictableTransactionError::Abort)?;
+                lock::validate_write(txid, &concurrent_txids, &row_snapshot)?;
 
                 let (row_snapshot, deleted_row) = row_snapshot.delete(txid);
                 let deleted_row = match deleted_row {
@@ -150,10 +165,10 @@ impl StoreMut for SledStorage {
         let tx_rows = &rows;
 
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let txid = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success { txid, .. } => txid,
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -173,11 +188,9 @@ impl StoreMut for SledStorage {
 
                 tree.insert(&key, snapshot)?;
 
-                if !autocommit {
-                    let temp_key = key::temp_data(txid, &key);
+                let temp_key = key::temp_data(txid, &key);
 
-                    tree.insert(temp_key, key)?;
-                }
+                tree.insert(temp_key, key)?;
             }
 
             Ok(TxPayload::Success)
@@ -193,10 +206,13 @@ impl StoreMut for SledStorage {
         let tx_rows = &rows;
 
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -209,6 +225,7 @@ impl StoreMut for SledStorage {
                         let snapshot: Snapshot<Row> = bincode::deserialize(&snapshot)
                             .map_err(err_into)
                             .map_err(ConflictableTransactionError::Abort)?;
+                        lock::validate_write(txid, &concurrent_txids, &snapshot)?;
 
                         let (snapshot, old_row) = snapshot.update(txid, new_row.clone());
                         let old_row = match old_row {
@@ -235,11 +252,9 @@ impl StoreMut for SledStorage {
 
                 tree.insert(&key, snapshot)?;
 
-                if !autocommit {
-                    let temp_key = key::temp_data(txid, &key);
+                let temp_key = key::temp_data(txid, &key);
 
-                    tree.insert(temp_key, key)?;
-                }
+                tree.insert(temp_key, key)?;
             }
 
             Ok(TxPayload::Success)
@@ -255,10 +270,13 @@ impl StoreMut for SledStorage {
         let tx_keys = &keys;
 
         let tx_result = self.tree.transaction(move |tree| {
-            let (txid, autocommit) = match lock::acquire(tree, state, tx_timeout)? {
-                LockAcquired::Success { txid, autocommit } => (txid, autocommit),
-                LockAcquired::RollbackAndRetry { lock_txid } => {
-                    return Ok(TxPayload::RollbackAndRetry(lock_txid));
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
                 }
             };
 
@@ -273,6 +291,7 @@ impl StoreMut for SledStorage {
                 let snapshot: Snapshot<Row> = bincode::deserialize(&snapshot)
                     .map_err(err_into)
                     .map_err(ConflictableTransactionError::Abort)?;
+                lock::validate_write(txid, &concurrent_txids, &snapshot)?;
 
                 let (snapshot, row) = snapshot.delete(txid);
                 let row = match row {
@@ -289,11 +308,9 @@ impl StoreMut for SledStorage {
 
                 index_sync.delete(&key, &row)?;
 
-                if !autocommit {
-                    let temp_key = key::temp_data(txid, &key);
+                let temp_key = key::temp_data(txid, &key);
 
-                    tree.insert(temp_key, key)?;
-                }
+                tree.insert(temp_key, key)?;
             }
 
             Ok(TxPayload::Success)
@@ -302,4 +319,350 @@ impl StoreMut for SledStorage {
         self.check_and_retry(tx_result, |storage| storage.delete_data(table_name, keys))
             .await
     }
+
+    async fn insert_namespace(self, namespace: &str) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::namespace(namespace);
+            let temp_key = key::temp_namespace(txid, namespace);
+
+            let snapshot: Option<Snapshot<String>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            let namespace = namespace.to_owned();
+            let snapshot = match snapshot {
+                Some(snapshot) => {
+                    lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+
+                    snapshot.update(txid, namespace).0
+                }
+                None => Snapshot::<String>::new(txid, namespace),
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.insert_namespace(namespace))
+            .await
+    }
+
+    async fn delete_namespace(self, namespace: &str) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::namespace(namespace);
+            let temp_key = key::temp_namespace(txid, namespace);
+
+            let snapshot: Option<Snapshot<String>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            if let Some(snapshot) = &snapshot {
+                lock::validate_write(txid, &concurrent_txids, snapshot)?;
+            }
+
+            let snapshot = match snapshot.map(|snapshot| snapshot.delete(txid)) {
+                Some((snapshot, Some(_))) => snapshot,
+                Some((_, None)) | None => {
+                    return Ok(TxPayload::Success);
+                }
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.delete_namespace(namespace))
+            .await
+    }
+
+    async fn insert_sequence(self, sequence: &Sequence) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::sequence(&sequence.name);
+            let temp_key = key::temp_sequence(txid, &sequence.name);
+
+            let snapshot: Option<Snapshot<Sequence>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            let snapshot = match snapshot {
+                Some(snapshot) => {
+                    lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+
+                    snapshot.update(txid, sequence.clone()).0
+                }
+                None => Snapshot::<Sequence>::new(txid, sequence.clone()),
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+            tree.remove(key::sequence_value(&sequence.name).as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.insert_sequence(sequence))
+            .await
+    }
+
+    async fn delete_sequence(self, name: &str) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::sequence(name);
+            let temp_key = key::temp_sequence(txid, name);
+
+            let snapshot: Option<Snapshot<Sequence>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            if let Some(snapshot) = &snapshot {
+                lock::validate_write(txid, &concurrent_txids, snapshot)?;
+            }
+
+            let snapshot = match snapshot.map(|snapshot| snapshot.delete(txid)) {
+                Some((snapshot, Some(_))) => snapshot,
+                Some((_, None)) | None => {
+                    return Ok(TxPayload::Success);
+                }
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.delete_sequence(name))
+            .await
+    }
+
+    async fn insert_view(self, view: &View) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::view(&view.name);
+            let temp_key = key::temp_view(txid, &view.name);
+
+            let snapshot: Option<Snapshot<View>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            let snapshot = match snapshot {
+                Some(snapshot) => {
+                    lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+
+                    snapshot.update(txid, view.clone()).0
+                }
+                None => Snapshot::<View>::new(txid, view.clone()),
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.insert_view(view))
+            .await
+    }
+
+    async fn delete_view(self, name: &str) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let key = key::view(name);
+            let temp_key = key::temp_view(txid, name);
+
+            let snapshot: Option<Snapshot<View>> = tree
+                .get(key.as_bytes())?
+                .map(|v| bincode::deserialize(&v))
+                .transpose()
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            if let Some(snapshot) = &snapshot {
+                lock::validate_write(txid, &concurrent_txids, snapshot)?;
+            }
+
+            let snapshot = match snapshot.map(|snapshot| snapshot.delete(txid)) {
+                Some((snapshot, Some(_))) => snapshot,
+                Some((_, None)) | None => {
+                    return Ok(TxPayload::Success);
+                }
+            };
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| storage.delete_view(name))
+            .await
+    }
+
+    async fn update_statistics(
+        self,
+        table_name: &str,
+        statistics: &Statistics,
+    ) -> MutResult<Self, ()> {
+        let state = &self.state;
+        let tx_timeout = self.tx_timeout;
+
+        let tx_result = self.tree.transaction(move |tree| {
+            let (txid, concurrent_txids) = match lock::acquire(tree, state, tx_timeout)? {
+                LockAcquired::Success {
+                    txid,
+                    concurrent_txids,
+                } => (txid, concurrent_txids),
+                LockAcquired::RollbackAndRetry { expired_txid } => {
+                    return Ok(TxPayload::RollbackAndRetry(expired_txid));
+                }
+            };
+
+            let (key, snapshot) = fetch_schema(tree, table_name)?;
+            let temp_key = key::temp_schema(txid, table_name);
+
+            let snapshot = match snapshot {
+                Some(snapshot) => snapshot,
+                None => {
+                    return Ok(TxPayload::Success);
+                }
+            };
+            lock::validate_write(txid, &concurrent_txids, &snapshot)?;
+
+            let schema = match snapshot.get(txid, &concurrent_txids) {
+                Some(schema) => Schema {
+                    statistics: Some(statistics.clone()),
+                    ..schema
+                },
+                None => {
+                    return Ok(TxPayload::Success);
+                }
+            };
+            let (snapshot, _) = snapshot.update(txid, schema);
+            let snapshot = bincode::serialize(&snapshot)
+                .map_err(err_into)
+                .map_err(ConflictableTransactionError::Abort)?;
+
+            tree.insert(key.as_bytes(), snapshot)?;
+            tree.insert(temp_key, key.as_bytes())?;
+
+            Ok(TxPayload::Success)
+        });
+
+        self.check_and_retry(tx_result, |storage| {
+            storage.update_statistics(table_name, statistics)
+        })
+        .await
+    }
 }
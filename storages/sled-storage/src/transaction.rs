@@ -3,12 +3,12 @@ use {
         err_into,
         error::StorageError,
         key,
-        lock::{self, Lock},
-        tx_err_into, SledStorage, Snapshot, State,
+        lock::{self, Lock, TxData},
+        tx_err_into, ReadSet, SledStorage, Snapshot, State,
     },
     async_trait::async_trait,
     gluesql_core::{
-        data::{Row, Schema},
+        data::{Row, Schema, Sequence, View},
         result::MutResult,
         result::{Error, Result},
         store::Transaction,
@@ -21,7 +21,11 @@ use {
         },
         IVec,
     },
-    std::result::Result as StdResult,
+    std::{
+        collections::HashMap,
+        result::Result as StdResult,
+        sync::{MutexGuard, PoisonError},
+    },
 };
 
 macro_rules! transaction {
@@ -38,6 +42,7 @@ macro_rules! transaction {
                     id_offset: $self.id_offset,
                     state: State::Idle,
                     tx_timeout: $self.tx_timeout,
+                    read_sets: $self.read_sets,
                 };
 
                 Ok((storage, v))
@@ -91,30 +96,9 @@ impl Transaction for SledStorage {
             }
         };
 
-        let rollback = || {
-            let Lock { lock_txid, .. } = self
-                .tree
-                .get("lock/")
-                .map_err(err_into)?
-                .map(|l| bincode::deserialize(&l))
-                .transpose()
-                .map_err(err_into)?
-                .unwrap_or_default();
-
-            if Some(txid) == lock_txid {
-                self.rollback_txid(txid).map(|_| lock_txid)
-            } else {
-                Ok(None)
-            }
-        };
-
-        match rollback() {
-            Ok(lock_txid) => transaction!(self, move |tree| {
-                lock_txid
-                    .map(|lock_txid| lock::release(tree, lock_txid))
-                    .transpose()
-            })
-            .map(|(storage, _)| (storage.update_state(State::Idle), ())),
+        match self.rollback_txid(txid) {
+            Ok(_) => transaction!(self, move |tree| lock::release(tree, txid))
+                .map(|(storage, _)| (storage.update_state(State::Idle), ())),
             Err(e) => Err((self, e)),
         }
     }
@@ -132,11 +116,31 @@ impl Transaction for SledStorage {
             }
         };
 
-        if let Err(e) = lock::fetch(&self.tree, txid, created_at, self.tx_timeout) {
-            return Err((self, e));
-        }
+        let concurrent_txids = match lock::fetch(&self.tree, txid, created_at, self.tx_timeout) {
+            Ok(concurrent_txids) => concurrent_txids,
+            Err(e) => {
+                return Err((self, e));
+            }
+        };
+
+        let storage = match self.validate_commit(txid, &concurrent_txids) {
+            Ok(true) => self,
+            Ok(false) => {
+                let (storage, _) = self.rollback().await?;
+
+                return Err((
+                    storage,
+                    Error::StorageMsg(
+                        "serialization failure - concurrent transaction has modified the read data"
+                            .to_owned(),
+                    ),
+                ));
+            }
+            Err(e) => {
+                return Err((self, e));
+            }
+        };
 
-        let (storage, _) = transaction!(self, move |tree| { lock::release(tree, txid) })?;
         let gc = || {
             if storage.tree.get("gc_lock").map_err(err_into)?.is_some() {
                 return Ok(());
@@ -152,13 +156,135 @@ impl Transaction for SledStorage {
         };
 
         match gc() {
-            Ok(_) => Ok((storage, ())),
-            Err(e) => Err((storage, e)),
+            Ok(_) => Ok((storage.update_state(State::Idle), ())),
+            Err(e) => Err((storage.update_state(State::Idle), e)),
         }
     }
 }
 
 impl SledStorage {
+    /// Records an item read by the transaction, checked against concurrent writes on commit
+    pub fn record_read(&self, txid: u64, read_key: &[u8]) {
+        self.read_sets()
+            .entry(txid)
+            .or_default()
+            .keys
+            .insert(read_key.to_vec());
+    }
+
+    /// Records a key prefix scanned by the transaction, checked against concurrent writes on commit
+    pub fn record_scan(&self, txid: u64, scan_prefix: &[u8]) {
+        self.read_sets()
+            .entry(txid)
+            .or_default()
+            .prefixes
+            .insert(scan_prefix.to_vec());
+    }
+
+    fn read_sets(&self) -> MutexGuard<'_, HashMap<u64, ReadSet>> {
+        self.read_sets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Releases the transaction unless a transaction it cannot see has committed a write
+    /// to what it has read, returns `false` in that case.
+    fn validate_commit(&self, txid: u64, concurrent_txids: &[u64]) -> Result<bool> {
+        let fetch_keys = |prefix| {
+            self.tree
+                .scan_prefix(prefix)
+                .map(|item| item.map_err(err_into))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let ReadSet { keys, prefixes } = self.read_sets().remove(&txid).unwrap_or_default();
+        let written = !fetch_keys(key::temp_data_prefix(txid))?.is_empty()
+            || !fetch_keys(key::temp_schema_prefix(txid))?.is_empty()
+            || !fetch_keys(key::temp_namespace_prefix(txid))?.is_empty()
+            || !fetch_keys(key::temp_sequence_prefix(txid))?.is_empty()
+            || !fetch_keys(key::temp_view_prefix(txid))?.is_empty();
+
+        loop {
+            let Lock { commit_seq, .. } = self
+                .tree
+                .get("lock/")
+                .map_err(err_into)?
+                .map(|l| bincode::deserialize(&l))
+                .transpose()
+                .map_err(err_into)?
+                .unwrap_or_default();
+
+            if written {
+                let committed_txids = self
+                    .tree
+                    .scan_prefix("tx_data/")
+                    .map(|item| -> Result<TxData> {
+                        item.map(|(_, v)| bincode::deserialize(&v))
+                            .map_err(err_into)?
+                            .map_err(err_into)
+                    })
+                    .filter_map(|tx_data| match tx_data {
+                        Ok(TxData {
+                            txid: committed_txid,
+                            alive: false,
+                            ..
+                        }) if committed_txid > txid
+                            || concurrent_txids.contains(&committed_txid) =>
+                        {
+                            Some(Ok(committed_txid))
+                        }
+                        Ok(_) => None,
+                        Err(e) => Some(Err(e)),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                for committed_txid in committed_txids {
+                    let written_items = fetch_keys(key::temp_data_prefix(committed_txid))?
+                        .into_iter()
+                        .chain(fetch_keys(key::temp_schema_prefix(committed_txid))?)
+                        .chain(fetch_keys(key::temp_namespace_prefix(committed_txid))?)
+                        .chain(fetch_keys(key::temp_sequence_prefix(committed_txid))?)
+                        .chain(fetch_keys(key::temp_view_prefix(committed_txid))?);
+
+                    for (_, written_key) in written_items {
+                        let conflict = keys.contains(written_key.as_ref())
+                            || prefixes
+                                .iter()
+                                .any(|prefix| written_key.starts_with(prefix));
+
+                        if conflict {
+                            return Ok(false);
+                        }
+                    }
+                }
+            }
+
+            let released = self
+                .tree
+                .transaction(move |tree| {
+                    let mut lock = lock::fetch_lock(tree)?;
+
+                    if written {
+                        if lock.commit_seq != commit_seq {
+                            return Ok(false);
+                        }
+
+                        lock.commit_seq += 1;
+                        lock::insert_lock(tree, &lock)?;
+                    }
+
+                    lock::release(tree, txid)?;
+
+                    Ok(true)
+                })
+                .map_err(tx_err_into)?;
+
+            if released {
+                return Ok(true);
+            }
+        }
+    }
+
     pub fn rollback_txid(&self, txid: u64) -> Result<()> {
         let fetch_items = |prefix| {
             self.tree
@@ -208,12 +334,19 @@ impl SledStorage {
 
         let data_items = fetch_items(key::temp_data_prefix(txid))?;
         let schema_items = fetch_items(key::temp_schema_prefix(txid))?;
+        let namespace_items = fetch_items(key::temp_namespace_prefix(txid))?;
+        let sequence_items = fetch_items(key::temp_sequence_prefix(txid))?;
+        let view_items = fetch_items(key::temp_view_prefix(txid))?;
         let index_items = fetch_items(key::temp_index_prefix(txid))?;
+        self.read_sets().remove(&txid);
 
         self.tree
             .transaction(move |tree| {
                 rollback_items::<Row>(tree, txid, &data_items)?;
                 rollback_items::<Schema>(tree, txid, &schema_items)?;
+                rollback_items::<String>(tree, txid, &namespace_items)?;
+                rollback_items::<Sequence>(tree, txid, &sequence_items)?;
+                rollback_items::<View>(tree, txid, &view_items)?;
 
                 for (temp_key, value_key) in index_items.iter() {
                     tree.remove(temp_key)?;
@@ -263,14 +396,14 @@ impl SledStorage {
     {
         match tx_result.map_err(tx_err_into) {
             Ok(TxPayload::Success) => Ok((self, ())),
-            Ok(TxPayload::RollbackAndRetry(lock_txid)) => {
-                if let Err(err) = self.rollback_txid(lock_txid) {
+            Ok(TxPayload::RollbackAndRetry(expired_txid)) => {
+                if let Err(err) = self.rollback_txid(expired_txid) {
                     return Err((self, err));
                 };
 
                 match self
                     .tree
-                    .transaction(move |tree| lock::release(tree, lock_txid))
+                    .transaction(move |tree| lock::release(tree, expired_txid))
                     .map_err(tx_err_into)
                 {
                     Ok(_) => retry_func(self).await,
@@ -0,0 +1,173 @@
+use {
+    gluesql_core::{
+        ast::{ColumnDef, DataType, Expr},
+        chrono::NaiveDateTime,
+        data::SchemaIndexOrd,
+        prelude::{Glue, Row, Value::*},
+        result::Error,
+    },
+    gluesql_sled_storage::{sled, SledStorage, FORMAT_VERSION},
+    serde::Serialize,
+    std::fs,
+    test_suite::*,
+};
+
+const PATH_PREFIX: &str = "tmp/format_version";
+
+#[test]
+fn open_new_database() {
+    let path = &format!("{}/new", PATH_PREFIX);
+    fs::remove_dir_all(path).unwrap_or(());
+
+    let storage = SledStorage::new(path).unwrap();
+    let version = storage.tree.get("format_version").unwrap().unwrap();
+    assert_eq!(version.as_ref(), FORMAT_VERSION.to_be_bytes());
+    drop(storage);
+
+    assert!(SledStorage::new(path).is_ok());
+}
+
+/// Layout of the values of format version 0, the parts of them which the test writes have
+/// the same encoding as the current types.
+#[derive(Serialize)]
+struct SnapshotItem<T> {
+    data: T,
+    created_by: u64,
+    deleted_by: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SchemaV0 {
+    table_name: String,
+    column_defs: Vec<ColumnDef>,
+    indexes: Vec<SchemaIndexV0>,
+    created: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+struct SchemaIndexV0 {
+    name: String,
+    expr: Expr,
+    order: SchemaIndexOrd,
+    created: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+struct LockV0 {
+    lock_txid: Option<u64>,
+    lock_created_at: u128,
+    gc_txid: Option<u64>,
+}
+
+#[test]
+fn open_unversioned_database() {
+    let path = &format!("{}/unversioned", PATH_PREFIX);
+    fs::remove_dir_all(path).unwrap_or(());
+
+    let tree = sled::open(path).unwrap();
+    let created = NaiveDateTime::default();
+    let schema = SchemaV0 {
+        table_name: "Foo".to_owned(),
+        column_defs: ["id", "name"]
+            .into_iter()
+            .zip([DataType::Int, DataType::Text])
+            .map(|(name, data_type)| ColumnDef {
+                name: name.to_owned(),
+                data_type,
+                nullable: false,
+                options: Vec::new(),
+            })
+            .collect(),
+        indexes: vec![SchemaIndexV0 {
+            name: "idx_id".to_owned(),
+            expr: Expr::Identifier("id".to_owned()),
+            order: SchemaIndexOrd::Both,
+            created,
+        }],
+        created,
+    };
+    let txid = tree.generate_id().unwrap();
+    let schema = bincode::serialize(&vec![SnapshotItem {
+        data: schema,
+        created_by: txid,
+        deleted_by: None,
+    }])
+    .unwrap();
+    tree.insert("schema/Foo", schema).unwrap();
+
+    // the third row was written by the transaction which was still holding the lock
+    let lock_txid = tree.generate_id().unwrap();
+    for (id, name, created_by) in [(1, "a", txid), (2, "b", txid), (3, "c", lock_txid)] {
+        let data_id = tree.generate_id().unwrap();
+        let data_key = [b"data/Foo/".as_slice(), &data_id.to_be_bytes()].concat();
+        let row = bincode::serialize(&vec![SnapshotItem {
+            data: Row(vec![I64(id), Str(name.to_owned())]),
+            created_by,
+            deleted_by: None,
+        }])
+        .unwrap();
+
+        tree.insert(&data_key, row).unwrap();
+
+        if created_by == lock_txid {
+            let temp_key = [
+                b"temp_data/".as_slice(),
+                &lock_txid.to_be_bytes(),
+                &data_key,
+            ]
+            .concat();
+            tree.insert(temp_key, data_key).unwrap();
+        }
+    }
+
+    let lock = LockV0 {
+        lock_txid: Some(lock_txid),
+        lock_created_at: 0,
+        gc_txid: None,
+    };
+    tree.insert("lock/", bincode::serialize(&lock).unwrap())
+        .unwrap();
+    tree.insert("index/Foo/idx_id/obsolete", &[0]).unwrap();
+    drop(tree);
+
+    let storage = SledStorage::new(path).unwrap();
+    let version = storage.tree.get("format_version").unwrap().unwrap();
+    assert_eq!(version.as_ref(), FORMAT_VERSION.to_be_bytes());
+    assert!(storage
+        .tree
+        .get("index/Foo/idx_id/obsolete")
+        .unwrap()
+        .is_none());
+    assert_eq!(storage.tree.scan_prefix("index/Foo/idx_id/").count(), 2);
+
+    let mut glue = Glue::new(storage);
+    assert_eq!(
+        glue.execute("SELECT id, name FROM Foo WHERE id > 1"),
+        Ok(vec![select!(id | name; I64 | Str; 2 "b".to_owned())])
+    );
+
+    glue.execute("INSERT INTO Foo VALUES (3, 'd')").unwrap();
+    assert_eq!(
+        glue.execute("SELECT id, name FROM Foo WHERE id = 3"),
+        Ok(vec![select!(id | name; I64 | Str; 3 "d".to_owned())])
+    );
+}
+
+#[test]
+fn open_newer_database() {
+    let path = &format!("{}/newer", PATH_PREFIX);
+    fs::remove_dir_all(path).unwrap_or(());
+
+    let tree = sled::open(path).unwrap();
+    tree.insert("format_version", &(FORMAT_VERSION + 1).to_be_bytes())
+        .unwrap();
+    drop(tree);
+
+    assert_eq!(
+        SledStorage::new(path).map(|_| ()),
+        Err(Error::StorageMsg(format!(
+            "unsupported storage format version {}, expected {FORMAT_VERSION}, dump the database with the release that wrote it and load it again",
+            FORMAT_VERSION + 1
+        )))
+    );
+}
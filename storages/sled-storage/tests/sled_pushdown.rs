@@ -0,0 +1,68 @@
+//! # SledStorage pushdown tests
+//!
+//! Filter and columns pushed down to `scan_filtered_data` are applied to the rows as they are
+//! decoded, the executor would otherwise hide whether the storage left any rows out.
+
+use {
+    gluesql_core::{
+        data::Row,
+        prelude::{Glue, Value::*},
+        result::Result,
+        store::{ScanFilter, Store},
+    },
+    gluesql_sled_storage::SledStorage,
+    std::fs,
+    test_suite::expr,
+};
+
+#[tokio::test]
+async fn sled_scan_filtered_data() {
+    let path = "tmp/gluesql/scan_filtered_data";
+    fs::remove_dir_all(path).unwrap_or(());
+
+    let storage = SledStorage::new(path).unwrap();
+    let mut glue = Glue::new(storage);
+
+    glue.execute_async("CREATE TABLE Item (id INTEGER, name TEXT, price INTEGER);")
+        .await
+        .unwrap();
+    glue.execute_async(
+        "INSERT INTO Item VALUES (1, 'apple', 50), (2, 'carrot', 30), (3, 'banana', 150);",
+    )
+    .await
+    .unwrap();
+
+    // rows are scanned in a transaction
+    glue.execute_async("BEGIN;").await.unwrap();
+
+    let storage = glue.storage.as_ref().unwrap();
+    let filter = ScanFilter {
+        column_names: vec!["id".to_owned(), "name".to_owned(), "price".to_owned()],
+        expr: expr("price > 40"),
+    };
+    let rows = storage
+        .scan_filtered_data("Item", Some([1].as_slice()), Some(&filter))
+        .await
+        .unwrap()
+        .map(|item| item.map(|(_, row)| row))
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            Row(vec![Null, Str("apple".to_owned()), Null]),
+            Row(vec![Null, Str("banana".to_owned()), Null]),
+        ]
+    );
+
+    let rows = storage
+        .scan_data("Item")
+        .await
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    glue.execute_async("COMMIT;").await.unwrap();
+}
@@ -38,6 +38,10 @@ test_case!(error, async move {
             "SELECT SUM(*) FROM Item;",
             TranslateError::WildcardFunctionArgNotAccepted.into(),
         ),
+        (
+            "SELECT ABS(DISTINCT id) FROM Item;",
+            TranslateError::DistinctInFunctionNotSupported("ABS".to_owned()).into(),
+        ),
     ];
 
     for (sql, error) in test_cases {
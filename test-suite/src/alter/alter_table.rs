@@ -3,8 +3,8 @@
 use {
     crate::*,
     gluesql_core::{
-        ast::*, data::Value::*, executor::AlterError, executor::EvaluateError, prelude::Payload,
-        store::AlterTableError, translate::TranslateError,
+        ast::*, data::SequenceError, data::Value::*, executor::AlterError, executor::EvaluateError,
+        prelude::Payload, store::AlterTableError, translate::TranslateError,
     },
 };
 
@@ -168,3 +168,108 @@ test_case!(alter_table_add_drop, async move {
         test!(sql, expected);
     }
 });
+
+test_case!(alter_table_check, async move {
+    let test_cases = [
+        (
+            "CREATE TABLE Item (price INTEGER CHECK (price > 0), memo TEXT CHECK (memo <> ''), discount INTEGER, CHECK (discount < price));",
+            Ok(Payload::Create),
+        ),
+        (
+            "ALTER TABLE Item ADD COLUMN amount INTEGER CHECK (amount > 0)",
+            Err(TranslateError::UnsupportedColumnOption("CHECK (amount > 0)".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item RENAME COLUMN price TO cost",
+            Err(AlterError::AlterCheckColumnNotSupported("price".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item DROP COLUMN discount",
+            Err(AlterError::AlterCheckColumnNotSupported("discount".to_owned()).into()),
+        ),
+        ("ALTER TABLE Item DROP COLUMN memo", Ok(Payload::AlterTable)),
+        (
+            "INSERT INTO Item VALUES (10, 5), (20, 1);",
+            Ok(Payload::Insert(2)),
+        ),
+        (
+            "SELECT * FROM Item;",
+            Ok(select!(
+                price | discount
+                I64   | I64;
+                10      5;
+                20      1
+            )),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
+
+test_case!(alter_table_identity, async move {
+    run!("CREATE TABLE Item (id SERIAL, name TEXT, memo TEXT);");
+
+    let test_cases = [
+        (
+            "ALTER TABLE Item RENAME TO Product",
+            Err(AlterError::AlterIdentityColumnNotSupported("id".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item RENAME COLUMN id TO item_id",
+            Err(AlterError::AlterIdentityColumnNotSupported("id".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item ADD COLUMN num INTEGER AUTO_INCREMENT",
+            Err(AlterError::AlterIdentityColumnNotSupported("num".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item RENAME COLUMN memo TO note",
+            Ok(Payload::AlterTable),
+        ),
+        (
+            "INSERT INTO Item (name) VALUES ('a');",
+            Ok(Payload::Insert(1)),
+        ),
+        ("ALTER TABLE Item DROP COLUMN id", Ok(Payload::AlterTable)),
+        (
+            "SELECT NEXTVAL('Item_id_seq') AS v;",
+            Err(SequenceError::NotFound("Item_id_seq".to_owned()).into()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
+
+test_case!(alter_table_generated, async move {
+    run!(
+        "CREATE TABLE Item (price INTEGER, qty INTEGER, total INTEGER GENERATED ALWAYS AS (price * qty) STORED);"
+    );
+
+    let test_cases = [
+        (
+            "ALTER TABLE Item RENAME COLUMN price TO cost",
+            Err(AlterError::AlterGeneratedColumnNotSupported("price".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item DROP COLUMN qty",
+            Err(AlterError::AlterGeneratedColumnNotSupported("qty".to_owned()).into()),
+        ),
+        (
+            "ALTER TABLE Item RENAME COLUMN total TO amount",
+            Ok(Payload::AlterTable),
+        ),
+        (
+            "ALTER TABLE Item DROP COLUMN amount",
+            Ok(Payload::AlterTable),
+        ),
+        ("ALTER TABLE Item DROP COLUMN qty", Ok(Payload::AlterTable)),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
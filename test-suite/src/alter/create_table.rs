@@ -61,8 +61,8 @@ test_case!(create_table, async move {
             Err(TranslateError::UnsupportedDataType("GLOBE".to_owned()).into()),
         ),
         (
-            "CREATE TABLE Gluery (id INTEGER CHECK (true));",
-            Err(TranslateError::UnsupportedColumnOption("CHECK (true)".to_owned()).into()),
+            "CREATE TABLE Gluery (id INTEGER CHECK (id IN (SELECT id FROM Wow)));",
+            Err(AlterError::UnsupportedCheckExpr("id IN (SELECT id FROM Wow)".to_owned()).into()),
         ),
         (
             "
@@ -47,7 +47,7 @@ test_case!(drop_indexed_table, async move {
         Err(TranslateError::InvalidParamsInDropIndex.into())
     );
     test!(
-        "DROP INDEX Test.idx_id.IndexC",
+        "DROP INDEX Foo.Test.idx_id.IndexC",
         Err(TranslateError::InvalidParamsInDropIndex.into())
     );
 });
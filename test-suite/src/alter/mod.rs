@@ -4,7 +4,10 @@ mod drop_indexed;
 mod drop_table;
 
 #[cfg(feature = "alter-table")]
-pub use alter_table::{alter_table_add_drop, alter_table_rename};
+pub use alter_table::{
+    alter_table_add_drop, alter_table_check, alter_table_generated, alter_table_identity,
+    alter_table_rename,
+};
 pub use create_table::create_table;
 #[cfg(all(feature = "alter-table", feature = "index"))]
 pub use drop_indexed::{drop_indexed_column, drop_indexed_table};
@@ -0,0 +1,124 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::AlterError,
+        prelude::{ExplainNode, Payload, Value::*},
+    },
+};
+
+fn node(name: &str, detail: &str, children: Vec<ExplainNode>) -> ExplainNode {
+    ExplainNode {
+        name: name.to_owned(),
+        detail: detail.to_owned(),
+        children,
+        analyzed: None,
+    }
+}
+
+fn scan(detail: &str) -> ExplainNode {
+    node("Table Scan", detail, Vec::new())
+}
+
+test_case!(analyze, async move {
+    run!("CREATE TABLE Category (name TEXT, label TEXT);");
+    run!("INSERT INTO Category VALUES ('fruit', 'Fruit'), ('meat', 'Meat');");
+    run!("CREATE TABLE Item (id INTEGER, name TEXT, category TEXT);");
+    run!(
+        "
+        INSERT INTO Item VALUES
+            (1, 'apple', 'fruit'),
+            (2, 'carrot', 'vegetable'),
+            (3, 'banana', 'fruit'),
+            (4, 'potato', 'vegetable');
+    "
+    );
+    run!("CREATE TABLE Stock (item_id INTEGER, store TEXT);");
+    run!(
+        "
+        INSERT INTO Stock VALUES
+            (1, 'north'), (1, 'south'), (2, 'north'), (2, 'south'),
+            (3, 'north'), (3, 'south'), (4, 'north'), (4, 'south');
+    "
+    );
+
+    let sql = "
+        SELECT c.label, i.name, s.store
+        FROM Category c
+        JOIN Item i ON i.category = c.name
+        JOIN Stock s ON s.item_id = i.id
+        ORDER BY s.store, i.name;
+    ";
+    let expected = || {
+        select!(
+            label               | name                 | store
+            Str                 | Str                  | Str;
+            "Fruit".to_owned()    "apple".to_owned()     "north".to_owned();
+            "Fruit".to_owned()    "banana".to_owned()    "north".to_owned();
+            "Fruit".to_owned()    "apple".to_owned()     "south".to_owned();
+            "Fruit".to_owned()    "banana".to_owned()    "south".to_owned()
+        )
+    };
+    let explained = |joins| {
+        node(
+            "Sort",
+            "s.store, i.name",
+            vec![node("Project", "c.label, i.name, s.store", vec![joins])],
+        )
+    };
+
+    // joins of the tables not analyzed yet are kept as they are written
+    test!(&format!("EXPLAIN {sql}"), {
+        let joins = node(
+            "Hash Join",
+            "INNER HASH i.category = c.name",
+            vec![scan("Category AS c"), scan("Item AS i")],
+        );
+        let joins = node(
+            "Hash Join",
+            "INNER HASH s.item_id = i.id",
+            vec![joins, scan("Stock AS s")],
+        );
+
+        Ok(Payload::Explain(explained(joins)))
+    });
+    test!(sql, Ok(expected()));
+
+    test!("ANALYZE TABLE Category;", Ok(Payload::Analyze));
+    test!("ANALYZE TABLE Item;", Ok(Payload::Analyze));
+    test!("ANALYZE TABLE Stock;", Ok(Payload::Analyze));
+    test!(
+        "ANALYZE TABLE Missing;",
+        Err(AlterError::TableNotFound("Missing".to_owned()).into())
+    );
+
+    // the smaller Category is hashed instead of Item
+    test!(&format!("EXPLAIN {sql}"), {
+        let joins = node(
+            "Hash Join",
+            "INNER HASH c.name = i.category",
+            vec![scan("Item AS i"), scan("Category AS c")],
+        );
+        let joins = node(
+            "Hash Join",
+            "INNER HASH s.item_id = i.id",
+            vec![joins, scan("Stock AS s")],
+        );
+
+        Ok(Payload::Explain(explained(joins)))
+    });
+    test!(sql, Ok(expected()));
+
+    // the columns of `*` follow the order of the tables
+    test!(
+        "EXPLAIN SELECT * FROM Category c JOIN Item i ON i.category = c.name;",
+        Ok(Payload::Explain(node(
+            "Project",
+            "*",
+            vec![node(
+                "Hash Join",
+                "INNER HASH i.category = c.name",
+                vec![scan("Category AS c"), scan("Item AS i")],
+            )]
+        )))
+    );
+});
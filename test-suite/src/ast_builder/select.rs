@@ -171,4 +171,24 @@ test_case!(select, async move {
         "Pineapple".to_owned()      40
     ));
     test(actual, expected);
+
+    // distinct
+    let actual = table("Item")
+        .select()
+        .order_by("category_id")
+        .project("category_id")
+        .distinct()
+        .execute(glue)
+        .await;
+    let expected = Ok(select!(category_id; I64; 1; 2; 3));
+    test(actual, expected);
+
+    // count distinct
+    let actual = table("Item")
+        .select()
+        .project(count_distinct("category_id"))
+        .execute(glue)
+        .await;
+    let expected = Ok(select!("COUNT(DISTINCT category_id)"; I64; 3));
+    test(actual, expected);
 });
@@ -58,8 +58,8 @@ CREATE TABLE TestA (
             Ok(select!(id | num; I64 | I64; 2 2; 2 9; 2 4; 2 7)),
         ),
         (
-            "SELECT id FROM FOO.Test",
-            Err(TranslateError::CompoundObjectNotSupported("FOO.Test".to_owned()).into()),
+            "SELECT id FROM FOO.BAR.Test",
+            Err(TranslateError::CompoundObjectNotSupported("FOO.BAR.Test".to_owned()).into()),
         ),
     ];
 
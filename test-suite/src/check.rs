@@ -0,0 +1,75 @@
+use {
+    crate::*,
+    gluesql_core::{
+        ast::Expr,
+        executor::{AlterError, ValidateError},
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(check, async move {
+    test!(
+        "CREATE TABLE Item (id INTEGER, price INTEGER CHECK (amount > 0));",
+        Err(AlterError::IdentifierNotFound(Expr::Identifier("amount".to_owned())).into())
+    );
+    test!(
+        "CREATE TABLE Item (id INTEGER, price INTEGER, CHECK (SUM(price) > 0));",
+        Err(AlterError::UnsupportedCheckExpr("SUM(price)".to_owned()).into())
+    );
+
+    run!(
+        "
+        CREATE TABLE Item (
+            id INTEGER PRIMARY KEY,
+            price INTEGER CHECK (price > 0),
+            discount INTEGER DEFAULT 0,
+            CHECK (discount <= price)
+        );
+    "
+    );
+
+    test!(
+        "INSERT INTO Item VALUES (1, 100, 10), (2, 50, 50);",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 0, 0);",
+        Err(ValidateError::CheckConstraintViolated("price > 0".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 10, 20);",
+        Err(ValidateError::CheckConstraintViolated("discount <= price".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Item (id, price) VALUES (3, 30);",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO Item VALUES (4, NULL, 5);",
+        Ok(Payload::Insert(1))
+    );
+
+    test!(
+        "UPDATE Item SET price = -1 WHERE id = 1;",
+        Err(ValidateError::CheckConstraintViolated("price > 0".to_owned()).into())
+    );
+    test!(
+        "UPDATE Item SET discount = discount + 10;",
+        Err(ValidateError::CheckConstraintViolated("discount <= price".to_owned()).into())
+    );
+    test!(
+        "UPDATE Item SET discount = 20 WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+
+    test!(
+        "SELECT id, price, discount FROM Item;",
+        Ok(select_with_null!(
+            id     | price    | discount;
+            I64(1)   I64(100)   I64(20);
+            I64(2)   I64(50)    I64(50);
+            I64(3)   I64(30)    I64(0);
+            I64(4)   Null       I64(5)
+        ))
+    );
+});
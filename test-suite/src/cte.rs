@@ -0,0 +1,207 @@
+use {
+    crate::*,
+    gluesql_core::{
+        ast::SetOperator,
+        executor::{FetchError, SelectError, DEFAULT_MAX_RECURSION_DEPTH},
+        prelude::Value::*,
+    },
+};
+
+test_case!(cte, async move {
+    run!(
+        "
+        CREATE TABLE Category (
+            id INTEGER,
+            parent_id INTEGER NULL,
+            name TEXT
+        );
+    "
+    );
+    run!(
+        "
+        INSERT INTO Category VALUES
+            (1, NULL, 'root'),
+            (2, 1, 'fruit'),
+            (3, 1, 'vegetable'),
+            (4, 2, 'apple'),
+            (5, 2, 'banana'),
+            (6, 4, 'fuji');
+    "
+    );
+
+    let test_cases = [
+        (
+            "WITH Fruit AS (SELECT id, name FROM Category WHERE parent_id = 2)
+            SELECT name FROM Fruit ORDER BY name DESC",
+            Ok(select!(name; Str; "banana".to_owned(); "apple".to_owned())),
+        ),
+        (
+            "WITH Small(num) AS (SELECT id FROM Category WHERE id < 3)
+            SELECT num FROM Small",
+            Ok(select!(num; I64; 1; 2)),
+        ),
+        (
+            "WITH
+                A AS (SELECT id FROM Category WHERE id > 2),
+                B AS (SELECT id FROM A WHERE id < 5)
+            SELECT * FROM B",
+            Ok(select!(id; I64; 3; 4)),
+        ),
+        (
+            "WITH Root AS (SELECT id FROM Category WHERE parent_id IS NULL)
+            SELECT Category.name FROM Category
+            JOIN Root ON Category.parent_id = Root.id",
+            Ok(select!(name; Str; "fruit".to_owned(); "vegetable".to_owned())),
+        ),
+        (
+            "WITH Fruit AS (SELECT id, name FROM Category WHERE parent_id = 2)
+            SELECT F.name, Category.name AS child FROM Fruit AS F
+            LEFT JOIN Category ON Category.parent_id = F.id
+            ORDER BY F.id",
+            Ok(select_with_null!(
+                name | child;
+                Str("apple".to_owned()) Str("fuji".to_owned());
+                Str("banana".to_owned()) Null
+            )),
+        ),
+        (
+            "SELECT name FROM Category WHERE id IN (
+                WITH Leaf AS (SELECT id FROM Category WHERE id > 4)
+                SELECT id FROM Leaf
+            )",
+            Ok(select!(name; Str; "banana".to_owned(); "fuji".to_owned())),
+        ),
+        (
+            "SELECT * FROM (
+                WITH Small AS (SELECT id FROM Category WHERE id < 3)
+                SELECT id FROM Small
+            ) AS Derived",
+            Ok(select!(id; I64; 1; 2)),
+        ),
+        (
+            "WITH Category AS (SELECT id FROM Category WHERE id = 1)
+            SELECT * FROM Category",
+            Ok(select!(id; I64; 1)),
+        ),
+        (
+            "WITH RECURSIVE Counter(n) AS (
+                SELECT 1
+                UNION ALL
+                SELECT n + 1 FROM Counter WHERE n < 5
+            )
+            SELECT SUM(n) FROM Counter",
+            Ok(select!("SUM(n)"; I64; 15)),
+        ),
+        (
+            "WITH RECURSIVE Cycle(n) AS (
+                SELECT 1
+                UNION
+                SELECT n % 3 + 1 FROM Cycle
+            )
+            SELECT n FROM Cycle ORDER BY n",
+            Ok(select!(n; I64; 1; 2; 3)),
+        ),
+        (
+            "WITH RECURSIVE Half(n) AS (
+                SELECT 0.8
+                UNION
+                SELECT n / 2 FROM Half WHERE n > 0.2
+            )
+            SELECT n FROM Half",
+            Ok(select!(n; F64; 0.8; 0.4; 0.2)),
+        ),
+        (
+            "WITH RECURSIVE Descendant(id, name, depth) AS (
+                SELECT id, name, 0 FROM Category WHERE id = 2
+                UNION ALL
+                SELECT Category.id, Category.name, Descendant.depth + 1
+                FROM Category
+                JOIN Descendant ON Category.parent_id = Descendant.id
+            )
+            SELECT name, depth FROM Descendant ORDER BY depth, name",
+            Ok(select!(
+                name                 | depth
+                Str                  | I64;
+                "fruit".to_owned()     0;
+                "apple".to_owned()     1;
+                "banana".to_owned()    1;
+                "fuji".to_owned()      2
+            )),
+        ),
+        (
+            "WITH RECURSIVE Ancestor AS (
+                SELECT id, parent_id, name FROM Category WHERE id = 6
+                UNION ALL
+                SELECT Category.id, Category.parent_id, Category.name
+                FROM Category
+                JOIN Ancestor ON Category.id = Ancestor.parent_id
+            )
+            SELECT name FROM Ancestor",
+            Ok(select!(
+                name
+                Str;
+                "fuji".to_owned();
+                "apple".to_owned();
+                "fruit".to_owned();
+                "root".to_owned()
+            )),
+        ),
+        (
+            "WITH Small(a, b) AS (SELECT id FROM Category) SELECT * FROM Small",
+            Err(FetchError::TooManyColumnAliases("Small".to_owned(), 1, 2).into()),
+        ),
+        (
+            "WITH RECURSIVE Counter(n) AS (
+                SELECT 1
+                UNION ALL
+                SELECT n, n FROM Counter WHERE n < 3
+            )
+            SELECT * FROM Counter",
+            Err(SelectError::SetOperationColumnCountMismatch {
+                operator: SetOperator::Union,
+                left: 1,
+                right: 2,
+            }
+            .into()),
+        ),
+        (
+            "WITH RECURSIVE Counter(n) AS (
+                SELECT 1
+                UNION ALL
+                SELECT n + 1 FROM Counter
+            )
+            SELECT * FROM Counter",
+            Err(SelectError::RecursionDepthExceeded {
+                table_name: "Counter".to_owned(),
+                depth: DEFAULT_MAX_RECURSION_DEPTH,
+            }
+            .into()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+
+    let sql = "
+        WITH RECURSIVE Counter(n) AS (
+            SELECT 1
+            UNION ALL
+            SELECT n + 1 FROM Counter WHERE n < 5
+        )
+        SELECT MAX(n) AS n FROM Counter
+    ";
+
+    get_glue!().set_max_recursion_depth(3);
+    test!(
+        sql,
+        Err(SelectError::RecursionDepthExceeded {
+            table_name: "Counter".to_owned(),
+            depth: 3,
+        }
+        .into())
+    );
+
+    get_glue!().set_max_recursion_depth(5);
+    test!(sql, Ok(select!(n; I64; 5)));
+});
@@ -43,26 +43,24 @@ test_case!(dictionary, async move {
 
     test!(
         "SELECT * FROM GLUE_TABLES",
-        Ok(select!(
-            TABLE_NAME;
-            Str;
-            "Bar".to_owned();
-            "Foo".to_owned();
-            "Zoo".to_owned()
+        Ok(select_with_null!(
+            TABLE_NAME              | SCHEMA_NAME;
+            Str("Bar".to_owned())     Null;
+            Str("Foo".to_owned())     Null;
+            Str("Zoo".to_owned())     Null
         ))
     );
 
     test!(
         "SELECT * FROM GLUE_TABLE_COLUMNS",
-        Ok(select!(
-            TABLE_NAME       | COLUMN_NAME      | COLUMN_ID;
-            Str              | Str              | I64;
-            "Bar".to_owned()   "id".to_owned()    1;
-            "Bar".to_owned()   "name".to_owned()  2;
-            "Foo".to_owned()   "id".to_owned()    1;
-            "Foo".to_owned()   "name".to_owned()  2;
-            "Foo".to_owned()   "type".to_owned()  3;
-            "Zoo".to_owned()   "id".to_owned()    1
+        Ok(select_with_null!(
+            TABLE_NAME              | COLUMN_NAME              | COLUMN_ID | REFERENCED_COLUMN;
+            Str("Bar".to_owned())     Str("id".to_owned())       I64(1)      Null;
+            Str("Bar".to_owned())     Str("name".to_owned())     I64(2)      Null;
+            Str("Foo".to_owned())     Str("id".to_owned())       I64(1)      Null;
+            Str("Foo".to_owned())     Str("name".to_owned())     I64(2)      Null;
+            Str("Foo".to_owned())     Str("type".to_owned())     I64(3)      Null;
+            Str("Zoo".to_owned())     Str("id".to_owned())       I64(1)      Null
         ))
     );
 });
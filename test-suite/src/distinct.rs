@@ -0,0 +1,135 @@
+use {
+    crate::*,
+    gluesql_core::{
+        data::KeyError,
+        prelude::{Value, Value::*},
+    },
+};
+
+test_case!(distinct, async move {
+    run!(
+        "
+        CREATE TABLE Item (
+            id INTEGER,
+            name TEXT,
+            category TEXT NULL,
+            price INTEGER,
+            ratio FLOAT
+        );
+    "
+    );
+    run!(
+        "
+        INSERT INTO Item (id, name, category, price, ratio) VALUES
+            (1, 'apple',  'fruit', 300, 0.5),
+            (2, 'banana', 'fruit', 100, 0.5),
+            (3, 'carrot', 'vegetable', 100, 1.5),
+            (4, 'apple',  'fruit', 300, 2.5),
+            (5, 'durian', NULL, 700, 2.5),
+            (6, 'egg',    NULL, 100, 3.5);
+    "
+    );
+
+    let test_cases = [
+        (
+            "SELECT DISTINCT name FROM Item",
+            Ok(select!(
+                name
+                Str;
+                "apple".to_owned();
+                "banana".to_owned();
+                "carrot".to_owned();
+                "durian".to_owned();
+                "egg".to_owned()
+            )),
+        ),
+        (
+            "SELECT DISTINCT category FROM Item",
+            Ok(select_with_null!(
+                category;
+                Str("fruit".to_owned());
+                Str("vegetable".to_owned());
+                Null
+            )),
+        ),
+        (
+            "SELECT DISTINCT name, price FROM Item WHERE id < 5",
+            Ok(select!(
+                name              | price
+                Str               | I64;
+                "apple".to_owned()  300;
+                "banana".to_owned() 100;
+                "carrot".to_owned() 100
+            )),
+        ),
+        (
+            "SELECT DISTINCT price FROM Item ORDER BY price DESC",
+            Ok(select!(price; I64; 700; 300; 100)),
+        ),
+        (
+            "SELECT DISTINCT price FROM Item ORDER BY price LIMIT 2",
+            Ok(select!(price; I64; 100; 300)),
+        ),
+        (
+            "SELECT DISTINCT COUNT(*) FROM Item GROUP BY price",
+            Ok(select!("COUNT(*)"; I64; 2; 3; 1)),
+        ),
+        (
+            "SELECT
+                COUNT(DISTINCT name),
+                COUNT(DISTINCT category),
+                SUM(DISTINCT price)
+            FROM Item",
+            Ok(select!(
+                "COUNT(DISTINCT name)" | "COUNT(DISTINCT category)" | "SUM(DISTINCT price)"
+                I64                    | I64                        | I64;
+                5                        2                            1100
+            )),
+        ),
+        (
+            "SELECT category, COUNT(DISTINCT price) AS cnt
+            FROM Item
+            WHERE category IS NOT NULL
+            GROUP BY category",
+            Ok(select!(
+                category               | cnt
+                Str                    | I64;
+                "fruit".to_owned()       2;
+                "vegetable".to_owned()   1
+            )),
+        ),
+        (
+            "SELECT COUNT(DISTINCT price), COUNT(price) FROM Item",
+            Ok(select!(
+                "COUNT(DISTINCT price)" | "COUNT(price)"
+                I64                     | I64;
+                3                         6
+            )),
+        ),
+        (
+            "SELECT DISTINCT ratio FROM Item",
+            Ok(select!(ratio; F64; 0.5; 1.5; 2.5; 3.5)),
+        ),
+        (
+            "SELECT COUNT(*) AS cnt FROM (
+                SELECT DISTINCT CAST('NaN' AS FLOAT) AS num FROM Item
+            ) AS Sub",
+            Ok(select!(cnt; I64; 1)),
+        ),
+        (
+            r#"SELECT DISTINCT CAST('{"a": [1, 2]}' AS MAP) AS doc FROM Item"#,
+            Ok(select_with_null!(
+                doc;
+                Value::parse_json_map(r#"{"a": [1, 2]}"#).unwrap()
+            )),
+        ),
+        (
+            "SELECT COUNT(DISTINCT ratio) FROM Item",
+            Err(KeyError::FloatTypeKeyNotSupported.into()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
@@ -0,0 +1,221 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::{Analyzed, ExplainError},
+        prelude::{ExplainNode, Payload},
+    },
+};
+
+fn node(name: &str, detail: &str, children: Vec<ExplainNode>) -> ExplainNode {
+    ExplainNode {
+        name: name.to_owned(),
+        detail: detail.to_owned(),
+        children,
+        analyzed: None,
+    }
+}
+
+fn analyzed(node: ExplainNode, rows: usize) -> ExplainNode {
+    ExplainNode {
+        analyzed: Some(Analyzed { rows, elapsed: 0 }),
+        ..node
+    }
+}
+
+/// Elapsed time differs run by run, only the row counts are compared.
+fn without_elapsed(payload: Payload) -> ExplainNode {
+    fn strip(node: ExplainNode) -> ExplainNode {
+        ExplainNode {
+            children: node.children.into_iter().map(strip).collect(),
+            analyzed: node
+                .analyzed
+                .map(|Analyzed { rows, .. }| Analyzed { rows, elapsed: 0 }),
+            ..node
+        }
+    }
+
+    match payload {
+        Payload::Explain(node) => strip(node),
+        payload => panic!("unexpected payload: {payload:?}"),
+    }
+}
+
+test_case!(explain, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT, category TEXT);");
+    run!(
+        "
+        INSERT INTO Item VALUES
+            (1, 'apple', 'fruit'),
+            (2, 'carrot', 'vegetable'),
+            (3, 'banana', 'fruit'),
+            (4, 'potato', 'vegetable');
+    "
+    );
+    run!("CREATE TABLE Category (name TEXT, label TEXT);");
+    run!("INSERT INTO Category VALUES ('fruit', 'Fruit'), ('meat', 'Meat');");
+
+    test!(
+        "EXPLAIN SELECT name FROM Item WHERE id > 1 ORDER BY name DESC LIMIT 2;",
+        Ok(Payload::Explain(node(
+            "Limit",
+            "LIMIT 2",
+            vec![node(
+                "Sort",
+                "name DESC",
+                vec![node(
+                    "Project",
+                    "name",
+                    vec![node(
+                        "Filter",
+                        "id > 1",
+                        vec![node("Table Scan", "Item", Vec::new())]
+                    )]
+                )]
+            )]
+        )))
+    );
+    test!(
+        "EXPLAIN SELECT * FROM Item WHERE id = 1;",
+        Ok(Payload::Explain(node(
+            "Project",
+            "*",
+            vec![node("Primary Key Lookup", "Item KEY (1)", Vec::new())]
+        )))
+    );
+    test!(
+        "
+        EXPLAIN SELECT c.label, COUNT(*) AS cnt
+        FROM Item i JOIN Category c ON c.name = i.category
+        GROUP BY c.label;
+    ",
+        Ok(Payload::Explain(node(
+            "Project",
+            "c.label, COUNT(*) AS cnt",
+            vec![node(
+                "Aggregate",
+                "GROUP BY c.label",
+                vec![node(
+                    "Hash Join",
+                    "INNER HASH c.name = i.category",
+                    vec![
+                        node("Table Scan", "Item AS i", Vec::new()),
+                        node("Table Scan", "Category AS c", Vec::new()),
+                    ]
+                )]
+            )]
+        )))
+    );
+    test!(
+        "EXPLAIN SELECT * FROM (SELECT name FROM Item) AS Sub CROSS JOIN Category;",
+        Ok(Payload::Explain(node(
+            "Project",
+            "*",
+            vec![node(
+                "Nested Loop Join",
+                "CROSS",
+                vec![
+                    node(
+                        "Subquery",
+                        "Sub",
+                        vec![node(
+                            "Project",
+                            "name",
+                            vec![node("Table Scan", "Item", Vec::new())]
+                        )]
+                    ),
+                    node("Table Scan", "Category", Vec::new()),
+                ]
+            )]
+        )))
+    );
+    test!(
+        "EXPLAIN SELECT id FROM Item UNION ALL VALUES (5);",
+        Ok(Payload::Explain(node(
+            "Set Operation",
+            "UNION ALL",
+            vec![
+                node(
+                    "Project",
+                    "id",
+                    vec![node("Table Scan", "Item", Vec::new())]
+                ),
+                node("Values", "1 row", Vec::new()),
+            ]
+        )))
+    );
+
+    // rows below LIMIT are counted as far as they are read
+    let found =
+        run!("EXPLAIN ANALYZE SELECT DISTINCT category FROM Item ORDER BY category LIMIT 1;");
+    assert_eq!(
+        without_elapsed(found),
+        analyzed(
+            node(
+                "Limit",
+                "LIMIT 1",
+                vec![analyzed(
+                    node(
+                        "Sort",
+                        "category",
+                        vec![analyzed(
+                            node(
+                                "Distinct",
+                                "",
+                                vec![analyzed(
+                                    node(
+                                        "Project",
+                                        "category",
+                                        vec![analyzed(node("Table Scan", "Item", Vec::new()), 4)]
+                                    ),
+                                    4
+                                )]
+                            ),
+                            2
+                        )]
+                    ),
+                    1
+                )]
+            ),
+            1
+        )
+    );
+
+    let found = run!(
+        "
+        EXPLAIN ANALYZE SELECT i.name
+        FROM Item i JOIN Category c ON c.name = i.category;
+    "
+    );
+    assert_eq!(
+        without_elapsed(found),
+        analyzed(
+            node(
+                "Project",
+                "i.name",
+                vec![analyzed(
+                    node(
+                        "Hash Join",
+                        "INNER HASH c.name = i.category",
+                        vec![
+                            analyzed(node("Table Scan", "Item AS i", Vec::new()), 4),
+                            node("Table Scan", "Category AS c", Vec::new()),
+                        ]
+                    ),
+                    2
+                )]
+            ),
+            2
+        )
+    );
+
+    let found = run!("EXPLAIN ANALYZE VALUES (1), (2);");
+    assert_eq!(
+        without_elapsed(found),
+        analyzed(node("Values", "2 rows", Vec::new()), 2)
+    );
+
+    test!(
+        "EXPLAIN DELETE FROM Item;",
+        Err(ExplainError::UnsupportedStatement.into())
+    );
+});
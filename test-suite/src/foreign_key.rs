@@ -0,0 +1,266 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::{AlterError, ForeignKeyError, ValidateError},
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(foreign_key, async move {
+    run!("CREATE TABLE Parent (id INTEGER PRIMARY KEY, code TEXT UNIQUE, name TEXT);");
+    run!("CREATE TABLE NoKey (id INTEGER);");
+
+    test!(
+        "CREATE TABLE Child (id INTEGER, parent_id INTEGER REFERENCES Missing);",
+        Err(AlterError::ReferencedTableNotFound("Missing".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Child (id INTEGER, parent_id INTEGER REFERENCES NoKey);",
+        Err(AlterError::ReferencedColumnsNotUnique("NoKey".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Child (id INTEGER, parent_name TEXT REFERENCES Parent (name));",
+        Err(AlterError::ReferencedColumnsNotUnique("Parent".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Child (id INTEGER, parent_id INTEGER REFERENCES Parent (uid));",
+        Err(AlterError::ReferencedColumnNotFound("uid".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Child (id INTEGER, parent_id TEXT REFERENCES Parent);",
+        Err(AlterError::ForeignKeyTypeMismatch("parent_id".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Child (id INTEGER, a INTEGER, b INTEGER, FOREIGN KEY (a, b) REFERENCES Parent (id));",
+        Err(AlterError::ForeignKeyColumnCountMismatch {
+            columns: 2,
+            referenced_columns: 1,
+        }
+        .into())
+    );
+
+    run!("INSERT INTO Parent VALUES (1, 'a', 'Alpha'), (2, 'b', 'Beta'), (3, 'c', 'Gamma');");
+    run!(
+        "
+        CREATE TABLE Child (
+            id INTEGER PRIMARY KEY,
+            parent_code TEXT,
+            FOREIGN KEY (parent_code) REFERENCES Parent (code) ON DELETE CASCADE ON UPDATE CASCADE
+        );
+    "
+    );
+    run!(
+        "
+        CREATE TABLE Restricted (
+            id INTEGER,
+            parent_code TEXT REFERENCES Parent (code) ON DELETE RESTRICT
+        );
+    "
+    );
+
+    test!(
+        "INSERT INTO Child VALUES (1, 'a'), (2, 'a'), (3, 'b'), (4, NULL);",
+        Ok(Payload::Insert(4))
+    );
+    test!(
+        "INSERT INTO Child VALUES (5, 'x');",
+        Err(ForeignKeyError::ReferencedRowNotFound("Parent".to_owned()).into())
+    );
+    test!(
+        "UPDATE Child SET parent_code = 'x' WHERE id = 1;",
+        Err(ForeignKeyError::ReferencedRowNotFound("Parent".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Restricted VALUES (1, 'c');",
+        Ok(Payload::Insert(1))
+    );
+
+    test!(
+        "DELETE FROM Parent WHERE id = 3;",
+        Err(ForeignKeyError::RowStillReferenced("Restricted".to_owned()).into())
+    );
+    test!(
+        "UPDATE Parent SET code = 'z' WHERE id = 3;",
+        Err(ForeignKeyError::RowStillReferenced("Restricted".to_owned()).into())
+    );
+    test!(
+        "DROP TABLE Parent;",
+        Err(AlterError::TableReferencedByForeignKey("Parent".to_owned()).into())
+    );
+
+    test!(
+        "UPDATE Parent SET code = 'A' WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+    test!("DELETE FROM Parent WHERE id = 2;", Ok(Payload::Delete(1)));
+    test!(
+        "SELECT id, parent_code FROM Child;",
+        Ok(select_with_null!(
+            id     | parent_code;
+            I64(1)   Str("A".to_owned());
+            I64(2)   Str("A".to_owned());
+            I64(4)   Null
+        ))
+    );
+
+    run!(
+        "
+        CREATE TABLE Orphan (
+            id INTEGER,
+            parent_code TEXT DEFAULT 'c' REFERENCES Parent (code)
+                ON DELETE SET DEFAULT ON UPDATE SET NULL
+        );
+    "
+    );
+    run!("INSERT INTO Orphan VALUES (1, 'A'), (2, 'c');");
+    test!(
+        "UPDATE Parent SET name = 'Delta' WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT id, parent_code FROM Orphan;",
+        Ok(select!(
+            id  | parent_code
+            I64 | Str;
+            1     "A".to_owned();
+            2     "c".to_owned()
+        ))
+    );
+    test!(
+        "UPDATE Parent SET code = 'B' WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT id, parent_code FROM Orphan;",
+        Ok(select_with_null!(
+            id     | parent_code;
+            I64(1)   Null;
+            I64(2)   Str("c".to_owned())
+        ))
+    );
+    run!("UPDATE Orphan SET parent_code = 'B' WHERE id = 1;");
+    test!("DELETE FROM Parent WHERE id = 1;", Ok(Payload::Delete(1)));
+    test!(
+        "SELECT id, parent_code FROM Orphan;",
+        Ok(select!(
+            id  | parent_code
+            I64 | Str;
+            1     "c".to_owned();
+            2     "c".to_owned()
+        ))
+    );
+    test!(
+        "SELECT id, parent_code FROM Child;",
+        Ok(select_with_null!(
+            id     | parent_code;
+            I64(4)   Null
+        ))
+    );
+
+    run!(
+        "
+        CREATE TABLE Employee (
+            id INTEGER PRIMARY KEY,
+            manager_id INTEGER REFERENCES Employee ON DELETE SET NULL
+        );
+    "
+    );
+    test!(
+        "INSERT INTO Employee VALUES (1, NULL), (2, 1), (3, 2);",
+        Ok(Payload::Insert(3))
+    );
+    test!(
+        "INSERT INTO Employee VALUES (4, 5);",
+        Err(ForeignKeyError::ReferencedRowNotFound("Employee".to_owned()).into())
+    );
+    test!("DELETE FROM Employee WHERE id = 2;", Ok(Payload::Delete(1)));
+    test!(
+        "SELECT id, manager_id FROM Employee;",
+        Ok(select_with_null!(
+            id     | manager_id;
+            I64(1)   Null;
+            I64(3)   Null
+        ))
+    );
+
+    test!(
+        "SELECT * FROM GLUE_TABLE_COLUMNS WHERE TABLE_NAME = 'Child';",
+        Ok(select_with_null!(
+            TABLE_NAME               | COLUMN_NAME                   | COLUMN_ID | REFERENCED_COLUMN;
+            Str("Child".to_owned())    Str("id".to_owned())            I64(1)      Null;
+            Str("Child".to_owned())    Str("parent_code".to_owned())   I64(2)      Str("Parent.code".to_owned())
+        ))
+    );
+
+    test!(
+        "DROP TABLE Parent, Child, Restricted, Orphan;",
+        Ok(Payload::DropTable)
+    );
+});
+
+test_case!(foreign_key_cascade, async move {
+    run!("CREATE TABLE Brand (id INTEGER PRIMARY KEY, code TEXT UNIQUE);");
+    run!("INSERT INTO Brand VALUES (1, 'ab'), (2, 'cd');");
+    run!(
+        "
+        CREATE TABLE Model (
+            brand_code TEXT REFERENCES Brand (code) ON UPDATE CASCADE,
+            name TEXT,
+            label TEXT GENERATED ALWAYS AS (brand_code || '-' || name) STORED,
+            CHECK (brand_code <> 'zz'),
+            PRIMARY KEY (brand_code, name)
+        );
+    "
+    );
+    run!("INSERT INTO Model (brand_code, name) VALUES ('ab', 'one'), ('cd', 'one');");
+
+    test!(
+        "UPDATE Brand SET code = 'zz' WHERE id = 1;",
+        Err(ValidateError::CheckConstraintViolated("brand_code <> 'zz'".to_owned()).into())
+    );
+    test!(
+        "UPDATE Brand SET code = 'ef' WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT brand_code, name, label FROM Model ORDER BY brand_code;",
+        Ok(select!(
+            brand_code      | name            | label
+            Str             | Str             | Str;
+            "cd".to_owned()   "one".to_owned()  "cd-one".to_owned();
+            "ef".to_owned()   "one".to_owned()  "ef-one".to_owned()
+        ))
+    );
+    // the row is moved to the key of its new primary key values
+    test!(
+        "SELECT label FROM Model WHERE brand_code = 'ef' AND name = 'one';",
+        Ok(select!(label Str; "ef-one".to_owned()))
+    );
+    test!(
+        "SELECT label FROM Model WHERE brand_code = 'ab' AND name = 'one';",
+        Ok(select!(label))
+    );
+
+    run!("CREATE TABLE Shop (id INTEGER PRIMARY KEY, code TEXT UNIQUE);");
+    run!("INSERT INTO Shop VALUES (1, 'north'), (2, 'south');");
+    run!(
+        "
+        CREATE TABLE Manager (
+            id INTEGER,
+            shop_code TEXT UNIQUE DEFAULT 'south'
+                REFERENCES Shop (code) ON DELETE SET DEFAULT
+        );
+    "
+    );
+    run!("INSERT INTO Manager VALUES (1, 'north'), (2, 'south');");
+    test!(
+        "DELETE FROM Shop WHERE id = 1;",
+        Err(ValidateError::DuplicateEntryOnUniqueField(
+            Str("south".to_owned()),
+            "shop_code".to_owned()
+        )
+        .into())
+    );
+
+    run!("DROP TABLE Model, Brand, Manager, Shop;");
+});
@@ -0,0 +1,81 @@
+use {
+    crate::*,
+    gluesql_core::{
+        data::RowError,
+        executor::{AlterError, UpdateError},
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(generated, async move {
+    test!(
+        "CREATE TABLE Wrong (a INTEGER, b INTEGER GENERATED ALWAYS AS (a + c) STORED);",
+        Err(AlterError::IdentifierNotFound(expr!("c")).into())
+    );
+    test!(
+        "
+        CREATE TABLE Wrong (
+            a INTEGER,
+            b INTEGER GENERATED ALWAYS AS (a + 1) STORED,
+            c INTEGER GENERATED ALWAYS AS (b + 1) STORED
+        );
+        ",
+        Err(AlterError::GeneratedColumnReferenced("b".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Wrong (a INTEGER, b INTEGER DEFAULT 0 GENERATED ALWAYS AS (a) STORED);",
+        Err(AlterError::GeneratedColumnWithDefault("b".to_owned()).into())
+    );
+
+    run!(
+        "
+        CREATE TABLE Item (
+            id INTEGER PRIMARY KEY,
+            price INTEGER,
+            qty INTEGER,
+            total INTEGER GENERATED ALWAYS AS (price * qty) STORED,
+            label TEXT GENERATED ALWAYS AS (LOWER(name)) STORED,
+            name TEXT
+        );
+    "
+    );
+
+    test!(
+        "INSERT INTO Item (id, price, qty, name) VALUES (1, 100, 2, 'Apple'), (2, 30, 5, NULL);",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 10, 1, 10, 'fake', 'Fake');",
+        Err(RowError::ValueOnGeneratedColumn("total".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Item (id, label) VALUES (3, 'fake');",
+        Err(RowError::ValueOnGeneratedColumn("label".to_owned()).into())
+    );
+    test!(
+        "UPDATE Item SET total = 0;",
+        Err(UpdateError::UpdateOnGeneratedColumnNotSupported("total".to_owned()).into())
+    );
+
+    test!(
+        "UPDATE Item SET qty = qty + 1, name = 'Banana' WHERE id = 2;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT id, price, qty, total, label FROM Item;",
+        Ok(select_with_null!(
+            id     | price    | qty    | total    | label;
+            I64(1)   I64(100)   I64(2)   I64(200)   Str("apple".to_owned());
+            I64(2)   I64(30)    I64(6)   I64(180)   Str("banana".to_owned())
+        ))
+    );
+
+    test!(
+        "UPDATE Item SET price = NULL WHERE id = 1;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT id, total FROM Item WHERE total IS NULL;",
+        Ok(select_with_null!(id | total; I64(1) Null))
+    );
+});
@@ -286,11 +286,6 @@ CREATE TABLE Test (
         "SELECT id, num, name FROM Test WHERE id < 20"
     );
 
-    test!(
-        "CREATE INDEX idx_com ON Test (id, num)",
-        Err(TranslateError::CompositeIndexNotSupported.into())
-    );
-
     test!(
         "DROP INDEX Test.idx_id, Test.idx_id2",
         Err(TranslateError::TooManyParamsInDropIndex.into())
@@ -0,0 +1,155 @@
+use {crate::*, gluesql_core::ast::IndexOperator::*, gluesql_core::prelude::*};
+
+test_case!(composite, async move {
+    run!(
+        "
+CREATE TABLE Orders (
+    tenant_id INTEGER,
+    created_at INTEGER,
+    amount INTEGER
+)"
+    );
+
+    run!(
+        "
+        INSERT INTO Orders VALUES
+            (2, 5, 35), (1, 3, 30), (3, 2, 50), (1, 1, 10),
+            (2, 1, 15), (1, 4, 40), (2, 3, 25), (1, 2, 20);
+    "
+    );
+
+    test!(
+        "CREATE INDEX idx_tenant_created ON Orders (tenant_id, created_at)",
+        Ok(Payload::CreateIndex)
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            1           3            30;
+            1           4            40
+        )),
+        idx!(idx_tenant_created, ["1"], Gt, "2"),
+        "SELECT * FROM Orders WHERE tenant_id = 1 AND created_at > 2"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            2           3            25;
+            2           5            35
+        )),
+        idx!(idx_tenant_created, ["2"], GtEq, "3"),
+        "SELECT * FROM Orders WHERE created_at >= 3 AND tenant_id = 2"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            1           3            30
+        )),
+        idx!(idx_tenant_created, ["1", "3"]),
+        "SELECT * FROM Orders WHERE tenant_id = 1 AND created_at = 3"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            1           2            20
+        )),
+        idx!(idx_tenant_created, ["1"], LtEq, "2"),
+        "SELECT * FROM Orders WHERE tenant_id = 1 AND created_at <= 2 AND amount <> 10"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            2           3            25;
+            2           5            35
+        )),
+        idx!(idx_tenant_created, Eq, "2"),
+        "SELECT * FROM Orders WHERE tenant_id = 2 AND amount > 20"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            3           2            50;
+            2           5            35;
+            2           3            25;
+            2           1            15;
+            1           4            40;
+            1           3            30;
+            1           2            20;
+            1           1            10
+        )),
+        idx!(idx_tenant_created, DESC),
+        "SELECT * FROM Orders ORDER BY tenant_id DESC, created_at DESC"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            1           1            10;
+            1           2            20;
+            1           3            30;
+            1           4            40;
+            2           1            15;
+            2           3            25;
+            2           5            35;
+            3           2            50
+        )),
+        idx!(idx_tenant_created, ASC),
+        "SELECT * FROM Orders ORDER BY tenant_id ASC"
+    );
+
+    test_idx!(
+        Ok(select!(
+            tenant_id | created_at | amount
+            I64       | I64        | I64;
+            1           1            10;
+            2           1            15;
+            1           2            20;
+            3           2            50;
+            1           3            30;
+            2           3            25;
+            1           4            40;
+            2           5            35
+        )),
+        idx!(idx_tenant_created),
+        "SELECT * FROM Orders ORDER BY created_at, tenant_id"
+    );
+
+    run!(
+        "
+CREATE TABLE Tags (
+    name TEXT,
+    id INTEGER
+)"
+    );
+
+    run!("INSERT INTO Tags VALUES ('ab', 2), ('a', 3), ('b', 4), ('a', 1);");
+
+    test!(
+        "CREATE INDEX idx_name_id ON Tags (name, id)",
+        Ok(Payload::CreateIndex)
+    );
+
+    test_idx!(
+        Ok(select!(
+            name            | id
+            Str             | I64;
+            "a".to_owned()    1;
+            "a".to_owned()    3
+        )),
+        idx!(idx_name_id, ["'a'"], Lt, "10"),
+        "SELECT * FROM Tags WHERE name = 'a' AND id < 10"
+    );
+});
@@ -0,0 +1,39 @@
+use {
+    crate::*,
+    gluesql_core::{
+        ast::IndexOperator::*,
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(generated, async move {
+    run!(
+        "
+        CREATE TABLE Account (
+            id INTEGER,
+            email TEXT,
+            lower_email TEXT GENERATED ALWAYS AS (LOWER(email)) STORED
+        );
+    "
+    );
+    run!("INSERT INTO Account (id, email) VALUES (1, 'Foo@Bar.com'), (2, 'hello@World.com');");
+
+    test!(
+        "CREATE INDEX idx_email ON Account (lower_email)",
+        Ok(Payload::CreateIndex)
+    );
+
+    test_idx!(
+        Ok(select!(id I64; 1)),
+        idx!(idx_email, Eq, "'foo@bar.com'"),
+        "SELECT id FROM Account WHERE lower_email = 'foo@bar.com'"
+    );
+
+    run!("UPDATE Account SET email = 'FOO@baz.com' WHERE id = 1;");
+
+    test_idx!(
+        Ok(select!(id I64; 1)),
+        idx!(idx_email, Eq, "'foo@baz.com'"),
+        "SELECT id FROM Account WHERE lower_email = 'foo@baz.com'"
+    );
+});
@@ -2,7 +2,9 @@
 
 mod and;
 mod basic;
+mod composite;
 mod expr;
+mod generated;
 mod nested;
 mod null;
 mod order_by;
@@ -11,7 +13,9 @@ mod value;
 
 pub use and::and;
 pub use basic::basic;
+pub use composite::composite;
 pub use expr::expr;
+pub use generated::generated;
 pub use nested::nested;
 pub use null::null;
 pub use order_by::order_by;
@@ -118,4 +118,38 @@ CREATE TABLE NullIdx (
         idx!(),
         "SELECT * FROM NullIdx WHERE id = NULL"
     );
+
+    // the planned index scan is kept for every execution of a prepared statement
+    let glue = get_glue!();
+    let mut select = glue
+        .prepare_async("SELECT id, flag FROM NullIdx WHERE id = $1")
+        .await
+        .unwrap();
+    test(
+        select.execute_async(glue, &[I64(2)]).await,
+        Ok(select_with_null!(
+            id     | flag;
+            I64(2)   Null
+        )),
+    );
+    test(
+        select.execute_async(glue, &[Null]).await,
+        Ok(Payload::Select {
+            labels: vec!["id".to_owned(), "flag".to_owned()],
+            rows: vec![],
+        }),
+    );
+
+    // until the index is dropped, the statement is then planned again
+    assert_eq!(
+        glue.execute_async("DROP INDEX NullIdx.idx_id").await,
+        Ok(vec![Payload::DropIndex])
+    );
+    test(
+        select.execute_async(glue, &[I64(2)]).await,
+        Ok(select_with_null!(
+            id     | flag;
+            I64(2)   Null
+        )),
+    );
 });
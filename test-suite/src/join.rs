@@ -1,6 +1,6 @@
 use {
     crate::*,
-    gluesql_core::{plan::PlanError, prelude::*, translate::TranslateError},
+    gluesql_core::{executor::JoinError, plan::PlanError, prelude::*, translate::TranslateError},
     Value::*,
 };
 
@@ -259,12 +259,8 @@ test_case!(blend, async move {
 
     let error_cases = [
         (
-            "SELECT * FROM TableA JOIN TableA USING (id);",
-            TranslateError::UnsupportedJoinConstraint("USING".to_owned()).into(),
-        ),
-        (
-            "SELECT * FROM TableA CROSS JOIN TableA as A;",
-            TranslateError::UnsupportedJoinOperator("CrossJoin".to_owned()).into(),
+            "SELECT * FROM TableA CROSS APPLY TableA as A;",
+            TranslateError::UnsupportedJoinOperator("CrossApply".to_owned()).into(),
         ),
         (
             "SELECT id FROM users JOIN testers ON users.id = testers.id;",
@@ -280,3 +276,235 @@ test_case!(blend, async move {
         test!(sql, Err(error));
     }
 });
+
+test_case!(outer, async move {
+    run!(
+        "
+        CREATE TABLE Player (
+            player_id INTEGER,
+            name TEXT
+        );
+    "
+    );
+    run!(
+        "
+        CREATE TABLE Item (
+            item_id INTEGER,
+            player_id INTEGER NULL,
+            quantity INTEGER
+        );
+    "
+    );
+    run!(
+        "
+        INSERT INTO Player (player_id, name) VALUES
+            (1, 'Taehoon'),
+            (2,    'Mike'),
+            (3,   'Jorno');
+    "
+    );
+    run!(
+        "
+        INSERT INTO Item (item_id, player_id, quantity) VALUES
+            (101,    1, 5),
+            (102,    1, 2),
+            (103,    3, 9),
+            (104,    4, 1),
+            (105, NULL, 7);
+    "
+    );
+
+    let test_cases = [
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            RIGHT JOIN Item ON Player.player_id = Item.player_id",
+            select_with_null!(
+                name                      | item_id;
+                Str("Taehoon".to_owned())   I64(101);
+                Str("Taehoon".to_owned())   I64(102);
+                Str("Jorno".to_owned())     I64(103);
+                Null                        I64(104);
+                Null                        I64(105)
+            ),
+        ),
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            RIGHT OUTER JOIN Item ON Player.player_id = Item.player_id AND Item.quantity > 3",
+            select_with_null!(
+                name                      | item_id;
+                Str("Taehoon".to_owned())   I64(101);
+                Str("Jorno".to_owned())     I64(103);
+                Null                        I64(102);
+                Null                        I64(104);
+                Null                        I64(105)
+            ),
+        ),
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            RIGHT JOIN Item ON Player.player_id = Item.player_id OR Item.quantity > 8",
+            select_with_null!(
+                name                      | item_id;
+                Str("Taehoon".to_owned())   I64(101);
+                Str("Taehoon".to_owned())   I64(102);
+                Str("Taehoon".to_owned())   I64(103);
+                Str("Mike".to_owned())      I64(103);
+                Str("Jorno".to_owned())     I64(103);
+                Null                        I64(104);
+                Null                        I64(105)
+            ),
+        ),
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            FULL JOIN Item ON Player.player_id = Item.player_id",
+            select_with_null!(
+                name                      | item_id;
+                Str("Taehoon".to_owned())   I64(101);
+                Str("Taehoon".to_owned())   I64(102);
+                Str("Mike".to_owned())      Null;
+                Str("Jorno".to_owned())     I64(103);
+                Null                        I64(104);
+                Null                        I64(105)
+            ),
+        ),
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            FULL OUTER JOIN Item ON Player.player_id < Item.player_id AND Item.quantity = 1",
+            select_with_null!(
+                name                      | item_id;
+                Str("Taehoon".to_owned())   I64(104);
+                Str("Mike".to_owned())      I64(104);
+                Str("Jorno".to_owned())     I64(104);
+                Null                        I64(101);
+                Null                        I64(102);
+                Null                        I64(103);
+                Null                        I64(105)
+            ),
+        ),
+        (
+            "SELECT Player.name, Item.item_id
+            FROM Player
+            CROSS JOIN Item
+            WHERE Player.player_id = 2 AND Item.quantity > 6",
+            select!(
+                name              | item_id
+                Str               | I64;
+                "Mike".to_owned()   103;
+                "Mike".to_owned()   105
+            ),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, Ok(expected));
+    }
+
+    count!(15, "SELECT * FROM Player CROSS JOIN Item");
+});
+
+test_case!(using, async move {
+    run!(
+        "
+        CREATE TABLE Player (
+            player_id INTEGER,
+            name TEXT
+        );
+    "
+    );
+    run!(
+        "
+        CREATE TABLE Item (
+            item_id INTEGER,
+            player_id INTEGER NULL,
+            quantity INTEGER
+        );
+    "
+    );
+    run!(
+        "
+        INSERT INTO Player (player_id, name) VALUES
+            (1, 'Taehoon'),
+            (2,    'Mike'),
+            (3,   'Jorno');
+    "
+    );
+    run!(
+        "
+        INSERT INTO Item (item_id, player_id, quantity) VALUES
+            (101,    1, 5),
+            (102,    1, 2),
+            (103,    3, 9),
+            (104,    4, 1),
+            (105, NULL, 7);
+    "
+    );
+
+    let test_cases = [
+        (
+            "SELECT * FROM Player JOIN Item USING (player_id)",
+            Ok(select!(
+                player_id | name                 | item_id | quantity
+                I64       | Str                  | I64     | I64;
+                1           "Taehoon".to_owned()   101       5;
+                1           "Taehoon".to_owned()   102       2;
+                3           "Jorno".to_owned()     103       9
+            )),
+        ),
+        (
+            "SELECT player_id, name, item_id FROM Player NATURAL LEFT JOIN Item",
+            Ok(select_with_null!(
+                player_id | name                      | item_id;
+                I64(1)      Str("Taehoon".to_owned())   I64(101);
+                I64(1)      Str("Taehoon".to_owned())   I64(102);
+                I64(2)      Str("Mike".to_owned())      Null;
+                I64(3)      Str("Jorno".to_owned())     I64(103)
+            )),
+        ),
+        (
+            "SELECT * FROM Player RIGHT JOIN Item USING (player_id)",
+            Ok(select_with_null!(
+                player_id | name                      | item_id  | quantity;
+                I64(1)      Str("Taehoon".to_owned())   I64(101)   I64(5);
+                I64(1)      Str("Taehoon".to_owned())   I64(102)   I64(2);
+                I64(3)      Str("Jorno".to_owned())     I64(103)   I64(9);
+                I64(4)      Null                        I64(104)   I64(1);
+                Null        Null                        I64(105)   I64(7)
+            )),
+        ),
+        (
+            "SELECT player_id, Player.player_id, Item.player_id, item_id
+            FROM Player
+            NATURAL FULL JOIN Item",
+            Ok(select_with_null!(
+                player_id | player_id | player_id | item_id;
+                I64(1)      I64(1)      I64(1)      I64(101);
+                I64(1)      I64(1)      I64(1)      I64(102);
+                I64(2)      I64(2)      Null        Null;
+                I64(3)      I64(3)      I64(3)      I64(103);
+                I64(4)      Null        I64(4)      I64(104);
+                Null        Null        Null        I64(105)
+            )),
+        ),
+        (
+            "SELECT Item.* FROM Player JOIN Item USING (player_id) WHERE quantity > 3",
+            Ok(select!(
+                item_id | player_id | quantity
+                I64     | I64       | I64;
+                101       1           5;
+                103       3           9
+            )),
+        ),
+        (
+            "SELECT * FROM Player JOIN Item USING (name)",
+            Err(JoinError::UsingColumnNotFound("name".to_owned()).into()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
@@ -2,18 +2,25 @@
 
 pub mod aggregate;
 pub mod alter;
+pub mod analyze;
 pub mod arithmetic;
 pub mod ast_builder;
 pub mod basic;
 pub mod blend;
 pub mod case;
+pub mod check;
 pub mod concat;
+pub mod cte;
 pub mod data_type;
 pub mod default;
 pub mod dictionary;
 pub mod dictionary_index;
+pub mod distinct;
+pub mod explain;
 pub mod filter;
+pub mod foreign_key;
 pub mod function;
+pub mod generated;
 pub mod index;
 pub mod inline_view;
 pub mod insert;
@@ -25,16 +32,27 @@ pub mod nested_select;
 pub mod nullable;
 pub mod order_by;
 pub mod ordering;
+pub mod prepared;
 pub mod primary_key;
+pub mod pushdown;
+pub mod returning;
+pub mod schema;
+pub mod semi_join;
+pub mod sequence;
 pub mod series;
+pub mod set_operation;
 pub mod show_columns;
 pub mod synthesize;
 pub mod transaction;
 pub mod type_match;
 pub mod unary_operator;
 pub mod update;
+pub mod update_join;
+pub mod upsert;
 pub mod validate;
 pub mod values;
+pub mod view;
+pub mod window;
 
 pub mod tester;
 
@@ -83,6 +101,11 @@ macro_rules! generate_store_tests {
         glue!(drop_table, alter::drop_table);
         glue!(default, default::default);
         glue!(limit, limit::limit);
+        glue!(prepared, prepared::prepared);
+        glue!(distinct, distinct::distinct);
+        glue!(set_operation, set_operation::set_operation);
+        glue!(cte, cte::cte);
+        glue!(window, window::window);
         glue!(like_ilike, like_ilike::like_ilike);
         glue!(filter, filter::filter);
         glue!(inline_view, inline_view::inline_view);
@@ -126,9 +149,12 @@ macro_rules! generate_store_tests {
         glue!(function_position, function::position::position);
         glue!(join, join::join);
         glue!(join_blend, join::blend);
+        glue!(join_outer, join::outer);
+        glue!(join_using, join::using);
         glue!(migrate, migrate::migrate);
         glue!(nested_select, nested_select::nested_select);
         glue!(primary_key, primary_key::primary_key);
+        glue!(primary_key_composite, primary_key::composite);
         glue!(series, series::series);
         glue!(nullable, nullable::nullable);
         glue!(nullable_text, nullable::nullable_text);
@@ -170,6 +196,21 @@ macro_rules! generate_store_tests {
         );
         glue!(type_match, type_match::type_match);
         glue!(dictionary, dictionary::dictionary);
+        glue!(schema, schema::schema);
+        glue!(foreign_key, foreign_key::foreign_key);
+        glue!(foreign_key_cascade, foreign_key::foreign_key_cascade);
+        glue!(check, check::check);
+        glue!(upsert, upsert::upsert);
+        glue!(returning, returning::returning);
+        glue!(update_join, update_join::update_join);
+        glue!(sequence, sequence::sequence);
+        glue!(identity, sequence::identity);
+        glue!(generated, generated::generated);
+        glue!(view, view::view);
+        glue!(pushdown, pushdown::pushdown);
+        glue!(explain, explain::explain);
+        glue!(analyze, analyze::analyze);
+        glue!(semi_join, semi_join::semi_join);
 
         // ast-builder
         glue!(ast_builder_basic, ast_builder::basic::basic);
@@ -192,6 +233,9 @@ macro_rules! generate_alter_table_tests {
 
         glue!(alter_table_rename, alter::alter_table_rename);
         glue!(alter_table_add_drop, alter::alter_table_add_drop);
+        glue!(alter_table_check, alter::alter_table_check);
+        glue!(alter_table_identity, alter::alter_table_identity);
+        glue!(alter_table_generated, alter::alter_table_generated);
     };
 }
 
@@ -206,15 +250,18 @@ macro_rules! generate_index_tests {
         }
 
         glue!(index_basic, index::basic);
+        glue!(index_composite, index::composite);
         glue!(index_and, index::and);
         glue!(index_nested, index::nested);
         glue!(index_null, index::null);
         glue!(index_expr, index::expr);
+        glue!(index_generated, index::generated);
         glue!(index_value, index::value);
         glue!(index_order_by, index::order_by);
         glue!(index_order_by_multi, index::order_by_multi);
         glue!(showindexes, index::showindexes);
         glue!(dictionary_index, dictionary_index::ditionary_index);
+        glue!(upsert_index, upsert::upsert_index);
     };
 }
 
@@ -233,6 +280,8 @@ macro_rules! generate_transaction_tests {
             transaction_create_drop_table,
             transaction::create_drop_table
         );
+        glue!(transaction_sequence, transaction::sequence);
+        glue!(transaction_view, transaction::view);
     };
 }
 
@@ -35,24 +35,13 @@ test_case!(migrate, async move {
             EvaluateError::UnsupportedStatelessExpr(expr!("a.b")).into(),
         ),
         (
-            "SELECT * FROM Test WHERE Here.User.id = 1",
-            TranslateError::UnsupportedExpr("Here.User.id".to_owned()).into(),
-        ),
-        (
-            "SELECT * FROM Test NATURAL JOIN Test",
-            TranslateError::UnsupportedJoinConstraint("NATURAL".to_owned()).into(),
+            "SELECT * FROM Test WHERE Here.Is.User.id = 1",
+            TranslateError::UnsupportedExpr("Here.Is.User.id".to_owned()).into(),
         ),
         (
             "SELECT 1 ^ 2 FROM Test;",
             TranslateError::UnsupportedBinaryOperator("^".to_owned()).into(),
         ),
-        (
-            "SELECT * FROM Test UNION SELECT * FROM Test;",
-            TranslateError::UnsupportedQuerySetExpr(
-                "SELECT * FROM Test UNION SELECT * FROM Test".to_owned(),
-            )
-            .into(),
-        ),
         (
             "SELECT * FROM Test WHERE noname = 1;",
             EvaluateError::ValueNotFound("noname".to_owned()).into(),
@@ -0,0 +1,149 @@
+use {
+    crate::*,
+    gluesql_core::{
+        ast::DataType,
+        executor::EvaluateError,
+        plan::PlanError,
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(prepared, async move {
+    let glue = get_glue!();
+
+    assert_eq!(
+        glue.execute_async("CREATE TABLE Item (id INTEGER, name TEXT, price DECIMAL);")
+            .await,
+        Ok(vec![Payload::Create])
+    );
+
+    let mut insert = glue
+        .prepare_async("INSERT INTO Item VALUES ($1, $2, $3);")
+        .await
+        .unwrap();
+    for (id, name, price) in [
+        (1, "apple", "1.25"),
+        (2, "banana", "0.5"),
+        (3, "cherry", "7"),
+    ] {
+        let params = [
+            I64(id),
+            Str(name.to_owned()),
+            Decimal(price.parse().unwrap()),
+        ];
+
+        assert_eq!(
+            insert.execute_async(glue, &params).await,
+            Ok(Payload::Insert(1))
+        );
+    }
+
+    assert_eq!(
+        insert
+            .execute_async(glue, &[I64(4), Str("durian".to_owned())])
+            .await,
+        Err(PlanError::ParamCountMismatch {
+            expected: 3,
+            found: 2
+        }
+        .into())
+    );
+    assert_eq!(
+        insert
+            .execute_async(glue, &[Str("5".to_owned()), Str("egg".to_owned()), Null])
+            .await,
+        Err(PlanError::ParamTypeMismatch {
+            index: 1,
+            expected: DataType::Int,
+            found: Str("5".to_owned()),
+        }
+        .into())
+    );
+
+    let mut select = glue
+        .prepare_async("SELECT id, name FROM Item WHERE id > ? AND name LIKE ? ORDER BY id LIMIT ?")
+        .await
+        .unwrap();
+    test(
+        select
+            .execute_async(glue, &[I64(0), Str("%an%".to_owned()), I64(5)])
+            .await,
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            2     "banana".to_owned()
+        )),
+    );
+    test(
+        select
+            .execute_async(glue, &[I64(1), Str("%".to_owned()), I64(1)])
+            .await,
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            2     "banana".to_owned()
+        )),
+    );
+    assert_eq!(
+        select.execute_async(glue, &[I64(1), I64(1), I64(1)]).await,
+        Err(PlanError::ParamTypeMismatch {
+            index: 2,
+            expected: DataType::Text,
+            found: I64(1),
+        }
+        .into())
+    );
+
+    let mut update = glue
+        .prepare_async("UPDATE Item SET price = $2 WHERE id = $1")
+        .await
+        .unwrap();
+    assert_eq!(
+        update
+            .execute_async(glue, &[I64(3), Decimal("7.75".parse().unwrap())])
+            .await,
+        Ok(Payload::Update(1))
+    );
+
+    let mut projection = glue.prepare_async("SELECT $1 AS label").await.unwrap();
+    test(
+        projection
+            .execute_async(glue, &[Str("free".to_owned())])
+            .await,
+        Ok(select!(label Str; "free".to_owned())),
+    );
+    for v in [f64::INFINITY, f64::NEG_INFINITY] {
+        test(
+            projection.execute_async(glue, &[F64(v)]).await,
+            Ok(select!(label F64; v)),
+        );
+    }
+    assert!(matches!(
+        projection.execute_async(glue, &[F64(f64::NAN)]).await,
+        Ok(Payload::Select { rows, .. }) if matches!(rows[0].0[0], F64(v) if v.is_nan())
+    ));
+
+    let mut select = glue
+        .prepare_async("SELECT price FROM Item WHERE id = 3")
+        .await
+        .unwrap();
+    test(
+        select.execute_async(glue, &[]).await,
+        Ok(select!(price Decimal; "7.75".parse().unwrap())),
+    );
+
+    assert_eq!(
+        glue.execute_async("SELECT * FROM Item WHERE id = $1").await,
+        Err(EvaluateError::UnboundPlaceholder("$1".to_owned()).into())
+    );
+    assert_eq!(
+        glue.prepare_async("SELECT * FROM Item WHERE id = $1 OR id = ?")
+            .await
+            .map(|_| ()),
+        Err(PlanError::MixedPlaceholders.into())
+    );
+    assert_eq!(
+        glue.prepare_async("SELECT 1; SELECT 2;").await.map(|_| ()),
+        Err(PlanError::MultipleStatementsPrepared(2).into())
+    );
+});
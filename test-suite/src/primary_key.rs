@@ -4,6 +4,7 @@ use {
         data::{Value::*, ValueError},
         executor::{UpdateError, ValidateError},
         prelude::{Key, Payload},
+        translate::TranslateError,
     },
 };
 
@@ -126,3 +127,97 @@ test_case!(primary_key, async move {
         Err(UpdateError::UpdateOnPrimaryKeyNotSupported("id".to_owned()).into())
     );
 });
+
+test_case!(composite, async move {
+    run!(
+        "
+        CREATE TABLE Grid (
+            x INTEGER,
+            y INTEGER,
+            name TEXT,
+            PRIMARY KEY (x, y)
+        );
+    "
+    );
+    test!(
+        "INSERT INTO Grid VALUES (1, 1, 'a'), (1, 2, 'b'), (2, 1, 'c');",
+        Ok(Payload::Insert(3))
+    );
+
+    test!(
+        "SELECT x, y, name FROM Grid WHERE x = 1 AND y = 2",
+        Ok(select!(
+            x   | y   | name
+            I64 | I64 | Str;
+            1     2     "b".to_owned()
+        ))
+    );
+    test!(
+        "SELECT name FROM Grid G WHERE G.y = 1 AND G.x = 2 AND name = 'c'",
+        Ok(select!(name Str; "c".to_owned()))
+    );
+    test!(
+        "SELECT name FROM Grid WHERE x = 1 AND y = 3",
+        Ok(select!(name))
+    );
+    test!(
+        "SELECT name FROM Grid WHERE x = 1",
+        Ok(select!(name Str; "a".to_owned(); "b".to_owned()))
+    );
+
+    test!(
+        "INSERT INTO Grid VALUES (2, 1, 'another c');",
+        Err(
+            ValidateError::DuplicateEntryOnPrimaryKeyField(Key::Composite(vec![
+                Key::I64(2),
+                Key::I64(1)
+            ]))
+            .into()
+        )
+    );
+    test!(
+        "INSERT INTO Grid VALUES (3, 3, 'd'), (3, 3, 'e');",
+        Err(
+            ValidateError::DuplicateEntryOnPrimaryKeyField(Key::Composite(vec![
+                Key::I64(3),
+                Key::I64(3)
+            ]))
+            .into()
+        )
+    );
+    test!(
+        "INSERT INTO Grid VALUES (1, NULL, 'd');",
+        Err(ValueError::NullValueOnNotNullField.into())
+    );
+    test!(
+        "UPDATE Grid SET y = 100 WHERE x = 1",
+        Err(UpdateError::UpdateOnPrimaryKeyNotSupported("y".to_owned()).into())
+    );
+    test!(
+        "UPDATE Grid SET name = 'z' WHERE x = 2 AND y = 1",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT x, y, name FROM Grid",
+        Ok(select!(
+            x   | y   | name
+            I64 | I64 | Str;
+            1     1     "a".to_owned();
+            1     2     "b".to_owned();
+            2     1     "z".to_owned()
+        ))
+    );
+
+    test!(
+        "CREATE TABLE Err (a INTEGER PRIMARY KEY, b INTEGER, PRIMARY KEY (a, b));",
+        Err(TranslateError::MultiplePrimaryKeysNotAllowed.into())
+    );
+    test!(
+        "CREATE TABLE Err (a INTEGER, PRIMARY KEY (a, b));",
+        Err(TranslateError::PrimaryKeyColumnNotFound("b".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Err (a INTEGER, UNIQUE (a));",
+        Err(TranslateError::UnsupportedTableConstraint("UNIQUE (a)".to_owned()).into())
+    );
+});
@@ -0,0 +1,100 @@
+use {
+    crate::*,
+    gluesql_core::prelude::{Payload, Value::*},
+};
+
+test_case!(pushdown, async move {
+    run!("CREATE TABLE Item (id INTEGER, name TEXT, price INTEGER, category TEXT);");
+    run!(
+        "
+        INSERT INTO Item VALUES
+            (1, 'apple', 50, 'fruit'),
+            (2, 'carrot', 30, 'vegetable'),
+            (3, 'banana', 150, 'fruit'),
+            (4, 'potato', 20, 'vegetable');
+    "
+    );
+    run!("CREATE TABLE Category (name TEXT, label TEXT);");
+    run!("INSERT INTO Category VALUES ('fruit', 'Fruit'), ('meat', 'Meat');");
+
+    // columns only referred to by WHERE and ORDER BY
+    test!(
+        "SELECT name FROM Item WHERE price > 40 AND category = 'fruit' ORDER BY id DESC;",
+        Ok(select!(
+            name
+            Str;
+            "banana".to_owned();
+            "apple".to_owned()
+        ))
+    );
+    test!(
+        "SELECT COUNT(*) AS cnt FROM Item WHERE price < 100;",
+        Ok(select!(cnt I64; 3))
+    );
+    test!(
+        "SELECT name FROM Item WHERE price = 50 OR name = 'potato' ORDER BY id;",
+        Ok(select!(
+            name
+            Str;
+            "apple".to_owned();
+            "potato".to_owned()
+        ))
+    );
+
+    // conjuncts with subqueries stay in the executor
+    test!(
+        "SELECT name FROM Item WHERE price < 100 AND category IN (SELECT name FROM Category);",
+        Ok(select!(name Str; "apple".to_owned()))
+    );
+    test!(
+        "
+        SELECT i.name FROM Item i
+        WHERE i.price < 100 AND EXISTS (SELECT * FROM Category c WHERE c.name = i.category);
+    ",
+        Ok(select!(name Str; "apple".to_owned()))
+    );
+
+    // joins
+    test!(
+        "
+        SELECT i.name, c.label FROM Item i
+        LEFT JOIN Category c ON c.name = i.category
+        WHERE i.price < 60 AND (c.label IS NULL OR i.id = 1)
+        ORDER BY i.id;
+    ",
+        Ok(select_with_null!(
+            name                    | label;
+            Str("apple".to_owned())   Str("Fruit".to_owned());
+            Str("carrot".to_owned())  Null;
+            Str("potato".to_owned())  Null
+        ))
+    );
+
+    // UPDATE and DELETE fetch the whole rows
+    test!(
+        "UPDATE Item SET price = price + 1 WHERE category = 'vegetable' AND price < 25;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT * FROM Item WHERE id = 4;",
+        Ok(select!(
+            id  | name                | price | category
+            I64 | Str                 | I64   | Str;
+            4     "potato".to_owned()   21      "vegetable".to_owned()
+        ))
+    );
+    test!(
+        "DELETE FROM Item WHERE price > 100 AND name LIKE 'b%';",
+        Ok(Payload::Delete(1))
+    );
+    test!(
+        "SELECT id, name FROM Item ORDER BY id;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "apple".to_owned();
+            2     "carrot".to_owned();
+            4     "potato".to_owned()
+        ))
+    );
+});
@@ -0,0 +1,66 @@
+use {
+    crate::*,
+    gluesql_core::prelude::{Payload, Value::*},
+};
+
+test_case!(returning, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT, status TEXT DEFAULT 'new');");
+
+    test!(
+        "INSERT INTO Item (id, name) VALUES (1, 'a'), (2, 'b') RETURNING *;",
+        Ok(select!(
+            id  | name           | status
+            I64 | Str            | Str;
+            1     "a".to_owned()   "new".to_owned();
+            2     "b".to_owned()   "new".to_owned()
+        ))
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 'c', 'old') RETURNING id, status AS state;",
+        Ok(select!(
+            id  | state
+            I64 | Str;
+            3     "old".to_owned()
+        ))
+    );
+    test!(
+        "INSERT INTO Item VALUES (4, 'd', 'old');",
+        Ok(Payload::Insert(1))
+    );
+
+    test!(
+        "UPDATE Item SET status = 'done' WHERE id < 3 RETURNING id, Item.status, id * 10 AS num;",
+        Ok(select!(
+            id  | status            | num
+            I64 | Str               | I64;
+            1     "done".to_owned()   10;
+            2     "done".to_owned()   20
+        ))
+    );
+    test!(
+        "UPDATE Item SET status = 'done' WHERE id > 10 RETURNING *;",
+        Ok(Payload::Select {
+            labels: vec!["id".to_owned(), "name".to_owned(), "status".to_owned()],
+            rows: Vec::new(),
+        })
+    );
+
+    test!(
+        "DELETE FROM Item WHERE status = 'old' RETURNING name;",
+        Ok(select!(
+            name
+            Str;
+            "c".to_owned();
+            "d".to_owned()
+        ))
+    );
+    test!(
+        "SELECT id, name, status FROM Item;",
+        Ok(select!(
+            id  | name           | status
+            I64 | Str            | Str;
+            1     "a".to_owned()   "done".to_owned();
+            2     "b".to_owned()   "done".to_owned()
+        ))
+    );
+});
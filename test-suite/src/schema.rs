@@ -0,0 +1,92 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::{AlterError, FetchError},
+        prelude::{Payload, Value::*},
+        translate::TranslateError,
+    },
+};
+
+test_case!(schema, async move {
+    test!("CREATE SCHEMA analytics;", Ok(Payload::CreateSchema));
+    test!(
+        "CREATE SCHEMA analytics;",
+        Err(AlterError::SchemaAlreadyExists("analytics".to_owned()).into())
+    );
+    test!(
+        "CREATE SCHEMA IF NOT EXISTS analytics;",
+        Ok(Payload::CreateSchema)
+    );
+    test!(
+        "CREATE SCHEMA analytics.nested;",
+        Err(TranslateError::CompoundObjectNotSupported("analytics.nested".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE billing.events (id INTEGER);",
+        Err(AlterError::SchemaNotFound("billing".to_owned()).into())
+    );
+
+    run!("CREATE TABLE analytics.events (id INTEGER, name TEXT);");
+    run!("CREATE TABLE events (id INTEGER);");
+    run!("INSERT INTO analytics.events VALUES (1, 'click'), (2, 'view');");
+    run!("INSERT INTO events VALUES (10);");
+
+    test!(
+        "SELECT id, name FROM analytics.events;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "click".to_owned();
+            2     "view".to_owned()
+        ))
+    );
+    test!(
+        "SELECT events.name FROM analytics.events WHERE events.id = 2;",
+        Ok(select!(name Str; "view".to_owned()))
+    );
+    test!("SELECT id FROM events;", Ok(select!(id I64; 10)));
+    test!(
+        "UPDATE analytics.events SET name = 'scroll' WHERE id = 2;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "DELETE FROM analytics.events WHERE id = 1;",
+        Ok(Payload::Delete(1))
+    );
+    test!(
+        "SELECT e.id, e.name FROM analytics.events AS e;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            2     "scroll".to_owned()
+        ))
+    );
+    test!(
+        "SELECT * FROM GLUE_TABLES;",
+        Ok(select_with_null!(
+            TABLE_NAME                           | SCHEMA_NAME;
+            Str("analytics.events".to_owned())     Str("analytics".to_owned());
+            Str("events".to_owned())               Null
+        ))
+    );
+
+    test!(
+        "DROP SCHEMA analytics;",
+        Err(AlterError::SchemaNotEmpty("analytics".to_owned()).into())
+    );
+    test!(
+        "DROP SCHEMA billing;",
+        Err(AlterError::SchemaNotFound("billing".to_owned()).into())
+    );
+    test!("DROP SCHEMA IF EXISTS billing;", Ok(Payload::DropSchema));
+    test!("DROP SCHEMA analytics CASCADE;", Ok(Payload::DropSchema));
+    test!(
+        "SELECT id FROM analytics.events;",
+        Err(FetchError::TableNotFound("analytics.events".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE analytics.events (id INTEGER);",
+        Err(AlterError::SchemaNotFound("analytics".to_owned()).into())
+    );
+    test!("SELECT id FROM events;", Ok(select!(id I64; 10)));
+});
@@ -0,0 +1,231 @@
+use {
+    crate::*,
+    gluesql_core::prelude::{ExplainNode, Payload, Value::*},
+};
+
+fn node(name: &str, detail: &str, children: Vec<ExplainNode>) -> ExplainNode {
+    ExplainNode {
+        name: name.to_owned(),
+        detail: detail.to_owned(),
+        children,
+        analyzed: None,
+    }
+}
+
+fn scan(detail: &str) -> ExplainNode {
+    node("Table Scan", detail, Vec::new())
+}
+
+fn hash_join(detail: &str, left: &str, right: &str) -> ExplainNode {
+    node("Hash Join", detail, vec![scan(left), scan(right)])
+}
+
+test_case!(semi_join, async move {
+    run!("CREATE TABLE Item (id INTEGER, name TEXT, category TEXT);");
+    run!(
+        "
+        INSERT INTO Item VALUES
+            (1, 'apple', 'fruit'),
+            (2, 'carrot', 'vegetable'),
+            (3, 'banana', 'fruit'),
+            (4, 'potato', 'vegetable');
+    "
+    );
+    run!("CREATE TABLE Stock (item_id INTEGER NULL, store TEXT NULL);");
+    run!(
+        "
+        INSERT INTO Stock VALUES
+            (1, 'north'), (1, 'south'), (3, 'south'), (NULL, 'north'), (4, NULL);
+    "
+    );
+    run!("CREATE TABLE Category (name TEXT, label TEXT);");
+    run!("INSERT INTO Category VALUES ('fruit', 'Fruit'), ('meat', 'Meat');");
+
+    // the null item_id of the north store matches no item
+    let sql =
+        "SELECT name FROM Item WHERE id IN (SELECT item_id FROM Stock WHERE store = 'north');";
+    test!(
+        &format!("EXPLAIN {sql}"),
+        Ok(Payload::Explain(node(
+            "Project",
+            "name",
+            vec![hash_join(
+                "SEMI HASH item_id = id AND store = 'north'",
+                "Item",
+                "Stock"
+            )]
+        )))
+    );
+    test!(
+        sql,
+        Ok(select!(
+            name
+            Str;
+            "apple".to_owned()
+        ))
+    );
+
+    let sql = "
+        SELECT name FROM Item i
+        WHERE EXISTS (SELECT * FROM Stock s WHERE s.item_id = i.id AND s.store = 'south');
+    ";
+    test!(
+        &format!("EXPLAIN {sql}"),
+        Ok(Payload::Explain(node(
+            "Project",
+            "name",
+            vec![hash_join(
+                "SEMI HASH s.item_id = i.id AND s.store = 'south'",
+                "Item AS i",
+                "Stock AS s"
+            )]
+        )))
+    );
+    test!(
+        sql,
+        Ok(select!(
+            name
+            Str;
+            "apple".to_owned();
+            "banana".to_owned()
+        ))
+    );
+
+    let sql =
+        "SELECT name FROM Item i WHERE NOT EXISTS (SELECT 1 FROM Stock s WHERE s.item_id = i.id);";
+    test!(
+        &format!("EXPLAIN {sql}"),
+        Ok(Payload::Explain(node(
+            "Project",
+            "name",
+            vec![hash_join(
+                "ANTI HASH s.item_id = i.id",
+                "Item AS i",
+                "Stock AS s"
+            )]
+        )))
+    );
+    test!(
+        sql,
+        Ok(select!(
+            name
+            Str;
+            "carrot".to_owned()
+        ))
+    );
+
+    // conjuncts comparing the columns otherwise are checked for each hashed match
+    let sql = "
+        SELECT i.name FROM Item i
+        WHERE EXISTS (SELECT 1 FROM Item j WHERE j.category = i.category AND j.id > i.id);
+    ";
+    test!(
+        &format!("EXPLAIN {sql}"),
+        Ok(Payload::Explain(node(
+            "Project",
+            "i.name",
+            vec![hash_join(
+                "SEMI ON j.id > i.id HASH j.category = i.category",
+                "Item AS i",
+                "Item AS j"
+            )]
+        )))
+    );
+    test!(
+        sql,
+        Ok(select!(
+            name
+            Str;
+            "apple".to_owned();
+            "carrot".to_owned()
+        ))
+    );
+
+    // an uncorrelated subquery is hashed by a constant
+    let sql =
+        "SELECT name FROM Category WHERE NOT EXISTS (SELECT 1 FROM Stock WHERE store = 'east');";
+    test!(
+        &format!("EXPLAIN {sql}"),
+        Ok(Payload::Explain(node(
+            "Project",
+            "name",
+            vec![hash_join(
+                "ANTI HASH TRUE = TRUE AND store = 'east'",
+                "Category",
+                "Stock"
+            )]
+        )))
+    );
+    test!(
+        sql,
+        Ok(select!(
+            name
+            Str;
+            "fruit".to_owned();
+            "meat".to_owned()
+        ))
+    );
+
+    // columns of the subquery are left out of `*`
+    test!(
+        "SELECT * FROM Category c WHERE EXISTS (SELECT 1 FROM Item i WHERE i.category = c.name);",
+        Ok(select!(
+            name                | label
+            Str                 | Str;
+            "fruit".to_owned()    "Fruit".to_owned()
+        ))
+    );
+
+    // the other conjuncts of WHERE stay in the filter
+    test!(
+        "
+        SELECT name FROM Item
+        WHERE category = 'fruit' AND id IN (SELECT item_id FROM Stock WHERE store = 'south');
+    ",
+        Ok(select!(
+            name
+            Str;
+            "apple".to_owned();
+            "banana".to_owned()
+        ))
+    );
+
+    // uncorrelated scalar subqueries run once, correlated ones for each row
+    test!(
+        "SELECT name FROM Item WHERE id > (SELECT MIN(item_id) FROM Stock WHERE store = 'south');",
+        Ok(select!(
+            name
+            Str;
+            "carrot".to_owned();
+            "banana".to_owned();
+            "potato".to_owned()
+        ))
+    );
+    test!(
+        "
+        SELECT name, (SELECT label FROM Category c WHERE c.name = i.category) AS label
+        FROM Item i;
+    ",
+        Ok(select_with_null!(
+            name                      | label;
+            Str("apple".to_owned())     Str("Fruit".to_owned());
+            Str("carrot".to_owned())    Null;
+            Str("banana".to_owned())    Str("Fruit".to_owned());
+            Str("potato".to_owned())    Null
+        ))
+    );
+
+    // a subquery calling a volatile function runs for each row
+    run!("CREATE SEQUENCE Counter;");
+    test!(
+        "SELECT id, (SELECT NEXTVAL('Counter')) AS n FROM Item;",
+        Ok(select!(
+            id  | n
+            I64 | I64;
+            1     1;
+            2     2;
+            3     3;
+            4     4
+        ))
+    );
+});
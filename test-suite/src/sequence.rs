@@ -0,0 +1,184 @@
+use {
+    crate::*,
+    gluesql_core::{
+        data::{RowError, SequenceError},
+        executor::{AlterError, UpdateError},
+        prelude::{DataType, Payload, Value::*},
+    },
+};
+
+test_case!(sequence, async move {
+    test!("CREATE SEQUENCE Counter;", Ok(Payload::CreateSequence));
+    test!(
+        "CREATE SEQUENCE Counter;",
+        Err(SequenceError::AlreadyExists("Counter".to_owned()).into())
+    );
+    test!(
+        "CREATE SEQUENCE IF NOT EXISTS Counter;",
+        Ok(Payload::CreateSequence)
+    );
+    test!(
+        "CREATE SEQUENCE Zero INCREMENT BY 0;",
+        Err(SequenceError::ZeroIncrement("Zero".to_owned()).into())
+    );
+
+    test!(
+        "SELECT CURRVAL('Counter') AS v;",
+        Err(SequenceError::CurrentValueNotDefined("Counter".to_owned()).into())
+    );
+    test!("SELECT NEXTVAL('Counter') AS v;", Ok(select!(v I64; 1)));
+    test!("SELECT NEXTVAL('Counter') AS v;", Ok(select!(v I64; 2)));
+    test!("SELECT CURRVAL('Counter') AS v;", Ok(select!(v I64; 2)));
+    test!(
+        "SELECT NEXTVAL('Missing') AS v;",
+        Err(SequenceError::NotFound("Missing".to_owned()).into())
+    );
+
+    run!("CREATE SEQUENCE Step INCREMENT BY 5 START WITH 10;");
+    run!("CREATE TABLE Item (id INTEGER, name TEXT);");
+    test!(
+        "INSERT INTO Item VALUES (NEXTVAL('Step'), 'a'), (NEXTVAL('Step'), 'b');",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "SELECT id, name FROM Item;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            10    "a".to_owned();
+            15    "b".to_owned()
+        ))
+    );
+
+    test!("DROP SEQUENCE Counter, Step;", Ok(Payload::DropSequence));
+    test!(
+        "SELECT NEXTVAL('Step') AS v;",
+        Err(SequenceError::NotFound("Step".to_owned()).into())
+    );
+    test!(
+        "DROP SEQUENCE Step;",
+        Err(SequenceError::NotFound("Step".to_owned()).into())
+    );
+    test!("DROP SEQUENCE IF EXISTS Step;", Ok(Payload::DropSequence));
+});
+
+test_case!(identity, async move {
+    test!(
+        "CREATE TABLE Wrong (id TEXT AUTO_INCREMENT);",
+        Err(
+            AlterError::UnsupportedDataTypeForIdentityColumn("id".to_owned(), DataType::Text)
+                .into()
+        )
+    );
+
+    run!("CREATE TABLE Serial (id SERIAL PRIMARY KEY, name TEXT);");
+    test!(
+        "INSERT INTO Serial (name) VALUES ('a'), ('b');",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "INSERT INTO Serial VALUES (10, 'c');",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO Serial (name) VALUES ('d');",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "SELECT id, name FROM Serial ORDER BY id;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "a".to_owned();
+            2     "b".to_owned();
+            3     "d".to_owned();
+            10    "c".to_owned()
+        ))
+    );
+    test!(
+        "SELECT CURRVAL('Serial_id_seq') AS v;",
+        Ok(select!(v I64; 3))
+    );
+
+    run!("CREATE TABLE Auto (id INT8 AUTO_INCREMENT, name TEXT);");
+    run!("INSERT INTO Auto (name) VALUES ('a');");
+    test!("SELECT id FROM Auto;", Ok(select!(id I8; 1)));
+
+    run!("CREATE TABLE ByDefault (id INTEGER GENERATED BY DEFAULT AS IDENTITY, name TEXT);");
+    run!("INSERT INTO ByDefault (name) VALUES ('a');");
+    run!("INSERT INTO ByDefault VALUES (5, 'b');");
+    test!(
+        "SELECT id, name FROM ByDefault ORDER BY id;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "a".to_owned();
+            5     "b".to_owned()
+        ))
+    );
+
+    run!("CREATE TABLE Always (id INTEGER GENERATED ALWAYS AS IDENTITY, name TEXT);");
+    run!("INSERT INTO Always (name) VALUES ('a');");
+    test!(
+        "INSERT INTO Always VALUES (5, 'b');",
+        Err(RowError::ValueOnAlwaysIdentityColumn("id".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Always SELECT id + 10, name FROM ByDefault;",
+        Err(RowError::ValueOnAlwaysIdentityColumn("id".to_owned()).into())
+    );
+    test!(
+        "UPDATE Always SET id = 7;",
+        Err(UpdateError::UpdateOnAlwaysIdentityColumnNotSupported("id".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Always (id, name) OVERRIDING SYSTEM VALUE VALUES (5, 'b');",
+        Ok(Payload::Insert(1))
+    );
+    run!("INSERT INTO Always (name) VALUES ('c');");
+    test!(
+        "SELECT id, name FROM Always ORDER BY id;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "a".to_owned();
+            2     "c".to_owned();
+            5     "b".to_owned()
+        ))
+    );
+
+    run!(
+        "CREATE TABLE overriding (generated INTEGER GENERATED ALWAYS AS IDENTITY, overriding TEXT);"
+    );
+    test!(
+        "INSERT INTO overriding (overriding) VALUES ('a');",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO overriding (generated, overriding) OVERRIDING SYSTEM VALUE VALUES (5, 'b');",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO overriding (overriding) SELECT overriding FROM overriding WHERE generated = 5;",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "SELECT generated, overriding FROM overriding ORDER BY generated;",
+        Ok(select!(
+            generated | overriding
+            I64       | Str;
+            1           "a".to_owned();
+            2           "b".to_owned();
+            5           "b".to_owned()
+        ))
+    );
+
+    run!("DROP TABLE Serial;");
+    test!(
+        "SELECT NEXTVAL('Serial_id_seq') AS v;",
+        Err(SequenceError::NotFound("Serial_id_seq".to_owned()).into())
+    );
+    run!("CREATE TABLE Serial (id SERIAL, name TEXT);");
+    run!("INSERT INTO Serial (name) VALUES ('a');");
+    test!("SELECT id FROM Serial;", Ok(select!(id I64; 1)));
+});
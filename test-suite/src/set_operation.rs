@@ -0,0 +1,114 @@
+use {
+    crate::*,
+    gluesql_core::{
+        ast::{DataType, SetOperator},
+        executor::SelectError,
+        prelude::Value::*,
+    },
+};
+
+test_case!(set_operation, async move {
+    run!("CREATE TABLE SetA (id INTEGER, name TEXT);");
+    run!("CREATE TABLE SetB (id INTEGER, name TEXT);");
+    run!("INSERT INTO SetA VALUES (1, 'a'), (2, 'b'), (2, 'b'), (3, 'c');");
+    run!("INSERT INTO SetB VALUES (2, 'b'), (2, 'b'), (3, 'c'), (3, 'c'), (4, 'd');");
+
+    let test_cases = [
+        (
+            "SELECT * FROM SetA UNION SELECT * FROM SetB ORDER BY id",
+            Ok(select!(
+                id  | name
+                I64 | Str;
+                1     "a".to_owned();
+                2     "b".to_owned();
+                3     "c".to_owned();
+                4     "d".to_owned()
+            )),
+        ),
+        (
+            "SELECT id FROM SetA UNION ALL SELECT id FROM SetB ORDER BY id",
+            Ok(select!(id; I64; 1; 2; 2; 2; 2; 3; 3; 3; 4)),
+        ),
+        (
+            "SELECT id FROM SetA INTERSECT SELECT id FROM SetB ORDER BY id",
+            Ok(select!(id; I64; 2; 3)),
+        ),
+        (
+            "SELECT id FROM SetA INTERSECT ALL SELECT id FROM SetB ORDER BY id",
+            Ok(select!(id; I64; 2; 2; 3)),
+        ),
+        (
+            "SELECT id FROM SetB EXCEPT SELECT id FROM SetA",
+            Ok(select!(id; I64; 4)),
+        ),
+        (
+            "SELECT id FROM SetB EXCEPT ALL SELECT id FROM SetA ORDER BY id",
+            Ok(select!(id; I64; 3; 4)),
+        ),
+        (
+            "SELECT id FROM SetA UNION SELECT id FROM SetB ORDER BY id DESC LIMIT 2 OFFSET 1",
+            Ok(select!(id; I64; 3; 2)),
+        ),
+        (
+            "SELECT id AS num FROM SetA UNION SELECT id FROM SetB ORDER BY num LIMIT 1",
+            Ok(select!(num; I64; 1)),
+        ),
+        (
+            "SELECT id FROM SetA EXCEPT SELECT id FROM SetB UNION SELECT 5 ORDER BY id",
+            Ok(select!(id; I64; 1; 5)),
+        ),
+        (
+            "SELECT id FROM SetB EXCEPT (SELECT id FROM SetA UNION SELECT 3)",
+            Ok(select!(id; I64; 4)),
+        ),
+        (
+            "VALUES (1, 'a') UNION VALUES (1, 'a'), (2, 'b')",
+            Ok(select!(
+                column1 | column2
+                I64     | Str;
+                1         "a".to_owned();
+                2         "b".to_owned()
+            )),
+        ),
+        (
+            "SELECT name FROM SetA WHERE id IN (SELECT id FROM SetB EXCEPT SELECT 4)",
+            Ok(select!(name; Str; "b".to_owned(); "b".to_owned(); "c".to_owned())),
+        ),
+        (
+            "SELECT * FROM (SELECT id FROM SetA INTERSECT SELECT id FROM SetB) AS Sub ORDER BY id",
+            Ok(select!(id; I64; 2; 3)),
+        ),
+        (
+            "SELECT id FROM SetA UNION SELECT id, name FROM SetB",
+            Err(SelectError::SetOperationColumnCountMismatch {
+                operator: SetOperator::Union,
+                left: 1,
+                right: 2,
+            }
+            .into()),
+        ),
+        (
+            "SELECT id FROM SetA EXCEPT SELECT name FROM SetB",
+            Err(SelectError::SetOperationTypeMismatch {
+                operator: SetOperator::Except,
+                column: "id".to_owned(),
+                left: DataType::Int,
+                right: DataType::Text,
+            }
+            .into()),
+        ),
+        (
+            "SELECT 1.5 AS num UNION SELECT 2.5 UNION SELECT 1.5",
+            Ok(select!(num; F64; 1.5; 2.5)),
+        ),
+        (
+            "SELECT CAST('[1, 2]' AS LIST) AS items
+            INTERSECT SELECT CAST('[1, 2]' AS LIST)",
+            Ok(select!(items; List; vec![I64(1), I64(2)])),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});
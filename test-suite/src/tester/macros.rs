@@ -10,10 +10,43 @@ macro_rules! idx {
     () => {
         vec![]
     };
+    ($name: path, [ $( $prefix: literal ),+ ], $op: path, $sql_expr: literal) => {
+        vec![gluesql_core::ast::IndexItem::NonClustered {
+            name: stringify_label!($name).to_owned(),
+            asc: None,
+            prefix_exprs: vec![$(
+                gluesql_core::translate::translate_expr(
+                    &gluesql_core::parse_sql::parse_expr($prefix).unwrap(),
+                )
+                .unwrap()
+            ),+],
+            cmp_expr: Some((
+                $op,
+                gluesql_core::translate::translate_expr(
+                    &gluesql_core::parse_sql::parse_expr($sql_expr).unwrap(),
+                )
+                .unwrap(),
+            )),
+        }]
+    };
+    ($name: path, [ $( $prefix: literal ),+ ]) => {
+        vec![gluesql_core::ast::IndexItem::NonClustered {
+            name: stringify_label!($name).to_owned(),
+            asc: None,
+            prefix_exprs: vec![$(
+                gluesql_core::translate::translate_expr(
+                    &gluesql_core::parse_sql::parse_expr($prefix).unwrap(),
+                )
+                .unwrap()
+            ),+],
+            cmp_expr: None,
+        }]
+    };
     ($name: path, $op: path, $sql_expr: literal) => {
         vec![gluesql_core::ast::IndexItem::NonClustered {
             name: stringify_label!($name).to_owned(),
             asc: None,
+            prefix_exprs: Vec::new(),
             cmp_expr: Some((
                 $op,
                 gluesql_core::translate::translate_expr(
@@ -27,6 +60,7 @@ macro_rules! idx {
         vec![gluesql_core::ast::IndexItem::NonClustered {
             name: stringify_label!($name).to_owned(),
             asc: None,
+            prefix_exprs: Vec::new(),
             cmp_expr: None,
         }]
     };
@@ -34,6 +68,7 @@ macro_rules! idx {
         vec![gluesql_core::ast::IndexItem::NonClustered {
             name: stringify_label!($name).to_owned(),
             asc: Some(true),
+            prefix_exprs: Vec::new(),
             cmp_expr: None,
         }]
     };
@@ -41,6 +76,7 @@ macro_rules! idx {
         vec![gluesql_core::ast::IndexItem::NonClustered {
             name: stringify_label!($name).to_owned(),
             asc: Some(false),
+            prefix_exprs: Vec::new(),
             cmp_expr: None,
         }]
     };
@@ -95,8 +95,7 @@ pub async fn run<T: GStore + GStoreMut>(
     let storage = glue.storage.as_ref().unwrap();
 
     println!("[SQL] {}", sql);
-    let parsed = parse(sql)?;
-    let statement = translate(&parsed[0])?;
+    let statement = translate_sql(sql)?.remove(0);
     let statement = plan(storage, statement).await?;
 
     test_indexes(&statement, indexes);
@@ -130,7 +129,9 @@ pub fn test_indexes(statement: &Statement, indexes: Option<Vec<IndexItem>>) {
 fn find_indexes(statement: &Statement) -> Vec<&IndexItem> {
     fn find_expr_indexes(expr: &Expr) -> Vec<&IndexItem> {
         match expr {
-            Expr::Subquery(query)
+            Expr::Subquery {
+                subquery: query, ..
+            }
             | Expr::Exists {
                 subquery: query, ..
             }
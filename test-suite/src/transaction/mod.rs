@@ -4,7 +4,9 @@ mod alter_table;
 mod basic;
 mod dictionary;
 mod index;
+mod sequence;
 mod table;
+mod view;
 
 #[cfg(feature = "alter-table")]
 pub use alter_table::*;
@@ -12,4 +14,6 @@ pub use basic::basic;
 pub use dictionary::dictionary;
 #[cfg(feature = "index")]
 pub use index::*;
+pub use sequence::sequence;
 pub use table::*;
+pub use view::view;
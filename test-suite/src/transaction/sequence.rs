@@ -0,0 +1,45 @@
+use {
+    crate::*,
+    gluesql_core::{data::SequenceError, prelude::Value::*},
+};
+
+test_case!(sequence, async move {
+    run!("CREATE SEQUENCE Counter;");
+
+    // values are not rolled back
+    run!("BEGIN;");
+    test!("SELECT NEXTVAL('Counter') AS v;", Ok(select!(v I64; 1)));
+    run!("ROLLBACK;");
+    test!("SELECT NEXTVAL('Counter') AS v;", Ok(select!(v I64; 2)));
+
+    // CREATE SEQUENCE && ROLLBACK
+    run!("BEGIN;");
+    run!("CREATE SEQUENCE Temp;");
+    test!("SELECT NEXTVAL('Temp') AS v;", Ok(select!(v I64; 1)));
+    run!("ROLLBACK;");
+    test!(
+        "SELECT NEXTVAL('Temp') AS v;",
+        Err(SequenceError::NotFound("Temp".to_owned()).into())
+    );
+
+    // DROP SEQUENCE && ROLLBACK
+    run!("BEGIN;");
+    run!("DROP SEQUENCE Counter;");
+    run!("ROLLBACK;");
+    test!("SELECT NEXTVAL('Counter') AS v;", Ok(select!(v I64; 3)));
+
+    // identity values of rolled back rows are not reused
+    run!("CREATE TABLE Serial (id SERIAL, name TEXT);");
+    run!("BEGIN;");
+    run!("INSERT INTO Serial (name) VALUES ('a');");
+    run!("ROLLBACK;");
+    run!("INSERT INTO Serial (name) VALUES ('b');");
+    test!(
+        "SELECT id, name FROM Serial;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            2     "b".to_owned()
+        ))
+    );
+});
@@ -0,0 +1,49 @@
+use {
+    crate::*,
+    gluesql_core::{executor::FetchError, prelude::Value::*},
+};
+
+test_case!(view, async move {
+    run!("CREATE TABLE Item (id INTEGER, name TEXT);");
+    run!("INSERT INTO Item VALUES (1, 'apple'), (2, 'banana');");
+
+    // CREATE VIEW && ROLLBACK
+    run!("BEGIN;");
+    run!("CREATE VIEW Temp AS SELECT name FROM Item WHERE id = 1;");
+    test!(
+        "SELECT name FROM Temp;",
+        Ok(select!(name Str; "apple".to_owned()))
+    );
+    run!("ROLLBACK;");
+    test!(
+        "SELECT name FROM Temp;",
+        Err(FetchError::TableNotFound("Temp".to_owned()).into())
+    );
+
+    // CREATE OR REPLACE VIEW && ROLLBACK
+    run!("CREATE VIEW Named AS SELECT name FROM Item WHERE id = 1;");
+    run!("BEGIN;");
+    run!("CREATE OR REPLACE VIEW Named AS SELECT name FROM Item WHERE id = 2;");
+    test!(
+        "SELECT name FROM Named;",
+        Ok(select!(name Str; "banana".to_owned()))
+    );
+    run!("ROLLBACK;");
+    test!(
+        "SELECT name FROM Named;",
+        Ok(select!(name Str; "apple".to_owned()))
+    );
+
+    // DROP VIEW && ROLLBACK
+    run!("BEGIN;");
+    run!("DROP VIEW Named;");
+    test!(
+        "SELECT name FROM Named;",
+        Err(FetchError::TableNotFound("Named".to_owned()).into())
+    );
+    run!("ROLLBACK;");
+    test!(
+        "SELECT name FROM Named;",
+        Ok(select!(name Str; "apple".to_owned()))
+    );
+});
@@ -100,10 +100,6 @@ test_case!(update, async move {
     run!("INSERT INTO ErrTestTable (id) VALUES (1),(9);");
 
     let error_cases = [
-        (
-            "UPDATE TableA INNER JOIN ErrTestTable ON 1 = 1 SET 1 = 1",
-            Err(TranslateError::JoinOnUpdateNotSupported.into()),
-        ),
         (
             "UPDATE (SELECT * FROM ErrTestTable) SET 1 = 1",
             Err(
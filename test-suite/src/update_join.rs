@@ -0,0 +1,153 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::FetchError,
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(update_join, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT, price INTEGER);");
+    run!("CREATE TABLE Staging (item_id INTEGER, price INTEGER);");
+    run!("INSERT INTO Item VALUES (1, 'a', 10), (2, 'b', 20), (3, 'c', 30);");
+    run!("INSERT INTO Staging VALUES (1, 15), (3, 35);");
+
+    test!(
+        "
+        UPDATE Item SET price = Staging.price
+        FROM Staging
+        WHERE Item.id = Staging.item_id
+        RETURNING id, price;
+        ",
+        Ok(select!(
+            id  | price
+            I64 | I64;
+            1     15;
+            3     35
+        ))
+    );
+    test!(
+        "
+        UPDATE Item INNER JOIN Staging ON Item.id = Staging.item_id
+        SET name = 'updated'
+        WHERE Staging.price > 20;
+        ",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "
+        UPDATE Item LEFT JOIN Staging ON Item.id = Staging.item_id
+        SET price = 0
+        WHERE Staging.item_id IS NULL;
+        ",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "SELECT id, name, price FROM Item;",
+        Ok(select!(
+            id  | name                 | price
+            I64 | Str                  | I64;
+            1     "a".to_owned()         15;
+            2     "b".to_owned()         0;
+            3     "updated".to_owned()   35
+        ))
+    );
+
+    test!(
+        "DELETE FROM Item USING Staging WHERE Item.id = Staging.item_id AND Staging.price < 20;",
+        Ok(Payload::Delete(1))
+    );
+    test!(
+        "SELECT id, name, price FROM Item;",
+        Ok(select!(
+            id  | name                 | price
+            I64 | Str                  | I64;
+            2     "b".to_owned()         0;
+            3     "updated".to_owned()   35
+        ))
+    );
+
+    test!(
+        "
+        UPDATE Item AS i SET price = s.price + 1
+        FROM Staging AS s
+        WHERE i.id = s.item_id
+        RETURNING id, price;
+        ",
+        Ok(select!(
+            id  | price
+            I64 | I64;
+            3     36
+        ))
+    );
+    test!(
+        "UPDATE Item AS i SET name = 'renamed' WHERE i.id = 2;",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "DELETE FROM Item AS i USING Staging s WHERE i.id = s.item_id;",
+        Ok(Payload::Delete(1))
+    );
+    test!(
+        "SELECT id, name, price FROM Item;",
+        Ok(select!(
+            id  | name                 | price
+            I64 | Str                  | I64;
+            2     "renamed".to_owned()   0
+        ))
+    );
+
+    run!("INSERT INTO Staging VALUES (2, 5), (2, 6);");
+    test!(
+        "UPDATE Item SET price = Staging.price FROM Staging WHERE Item.id = Staging.item_id;",
+        Err(FetchError::MultipleJoinedRows("Item".to_owned()).into())
+    );
+    test!(
+        "
+        UPDATE Item SET price = Staging.price
+        FROM Staging
+        WHERE Item.id = Staging.item_id AND Staging.price > 5;
+        ",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "DELETE FROM Item USING Staging WHERE Item.id = Staging.item_id;",
+        Ok(Payload::Delete(1))
+    );
+
+    run!("CREATE SCHEMA shop;");
+    run!("CREATE TABLE shop.Item (id INTEGER PRIMARY KEY, price INTEGER);");
+    run!("INSERT INTO shop.Item VALUES (1, 10), (2, 20), (3, 30);");
+    test!(
+        "
+        UPDATE shop.Item SET price = Staging.price
+        FROM Staging
+        WHERE shop.Item.id = Staging.item_id AND Staging.price > 5;
+        ",
+        Ok(Payload::Update(3))
+    );
+    test!(
+        "
+        UPDATE shop.Item AS i SET price = i.price + s.price
+        FROM Staging AS s
+        WHERE i.id = s.item_id AND s.price = 35;
+        ",
+        Ok(Payload::Update(1))
+    );
+    test!(
+        "
+        DELETE FROM shop.Item USING Staging
+        WHERE shop.Item.id = Staging.item_id AND Staging.price = 15;
+        ",
+        Ok(Payload::Delete(1))
+    );
+    test!(
+        "SELECT id, price FROM shop.Item;",
+        Ok(select!(
+            id  | price
+            I64 | I64;
+            2     6;
+            3     70
+        ))
+    );
+});
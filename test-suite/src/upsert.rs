@@ -0,0 +1,136 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::{UpsertError, ValidateError},
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(upsert, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT UNIQUE, num INTEGER);");
+    run!("INSERT INTO Item VALUES (1, 'a', 10), (2, 'b', 20);");
+
+    test!(
+        "INSERT INTO Item VALUES (1, 'c', 30) ON CONFLICT (num) DO NOTHING;",
+        Err(UpsertError::ConflictTargetNotUnique("num".to_owned()).into())
+    );
+    test!(
+        "INSERT INTO Item VALUES (1, 'c', 30), (3, 'c', 30) ON CONFLICT (id) DO NOTHING;",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO Item VALUES (4, 'a', 40), (5, 'e', 50) ON CONFLICT DO NOTHING;",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO Item VALUES (6, 'b', 60) ON CONFLICT (id) DO NOTHING;",
+        Err(
+            ValidateError::DuplicateEntryOnUniqueField(Str("b".to_owned()), "name".to_owned())
+                .into()
+        )
+    );
+    test!(
+        "SELECT id, name, num FROM Item;",
+        Ok(select!(
+            id  | name           | num
+            I64 | Str            | I64;
+            1     "a".to_owned()   10;
+            2     "b".to_owned()   20;
+            3     "c".to_owned()   30;
+            5     "e".to_owned()   50
+        ))
+    );
+
+    test!(
+        "
+        INSERT INTO Item VALUES (1, 'a', 5), (6, 'f', 60)
+        ON CONFLICT (id) DO UPDATE SET num = num + EXCLUDED.num;
+        ",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "
+        INSERT INTO Item VALUES (7, 'b', 70)
+        ON CONFLICT (name) DO UPDATE SET num = EXCLUDED.num, name = 'B';
+        ",
+        Ok(Payload::Insert(1))
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 'c', 1) ON CONFLICT (id) DO UPDATE SET name = 'a';",
+        Err(
+            ValidateError::DuplicateEntryOnUniqueField(Str("a".to_owned()), "name".to_owned())
+                .into()
+        )
+    );
+    test!(
+        "INSERT INTO Item VALUES (3, 'c', 1), (3, 'c', 2) ON CONFLICT (id) DO UPDATE SET num = 0;",
+        Err(UpsertError::RowAffectedTwice.into())
+    );
+    test!(
+        "INSERT INTO Item VALUES (8, 'h', 1), (8, 'h', 2) ON CONFLICT (id) DO UPDATE SET num = 0;",
+        Err(UpsertError::RowAffectedTwice.into())
+    );
+    test!(
+        "SELECT id, name, num FROM Item;",
+        Ok(select!(
+            id  | name           | num
+            I64 | Str            | I64;
+            1     "a".to_owned()   15;
+            2     "B".to_owned()   70;
+            3     "c".to_owned()   30;
+            5     "e".to_owned()   50;
+            6     "f".to_owned()   60
+        ))
+    );
+
+    // the value released by the update of a stored row is free to the following rows
+    test!(
+        "
+        INSERT INTO Item VALUES (2, 'g', 0), (9, 'B', 90)
+        ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name;
+        ",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "SELECT id, name FROM Item WHERE id = 2 OR id = 9;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            2     "g".to_owned();
+            9     "B".to_owned()
+        ))
+    );
+});
+
+#[cfg(feature = "index")]
+test_case!(upsert_index, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT UNIQUE, num INTEGER);");
+    run!("CREATE INDEX idx_name ON Item (name);");
+    run!("INSERT INTO Item VALUES (1, 'a', 10), (2, 'b', 20);");
+
+    // conflicts on the unique column are looked up through its index
+    test!(
+        "
+        INSERT INTO Item VALUES (3, 'b', 5), (4, 'c', 40)
+        ON CONFLICT (name) DO UPDATE SET num = num + EXCLUDED.num;
+        ",
+        Ok(Payload::Insert(2))
+    );
+    test!(
+        "INSERT INTO Item VALUES (5, 'a', 50) ON CONFLICT (id) DO NOTHING;",
+        Err(
+            ValidateError::DuplicateEntryOnUniqueField(Str("a".to_owned()), "name".to_owned())
+                .into()
+        )
+    );
+    test!(
+        "SELECT id, name, num FROM Item;",
+        Ok(select!(
+            id  | name           | num
+            I64 | Str            | I64;
+            1     "a".to_owned()   10;
+            2     "b".to_owned()   25;
+            4     "c".to_owned()   40
+        ))
+    );
+});
@@ -0,0 +1,109 @@
+use {
+    crate::*,
+    gluesql_core::{
+        data::ViewError,
+        executor::{AlterError, FetchError},
+        prelude::{Payload, Value::*},
+    },
+};
+
+test_case!(view, async move {
+    run!("CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT, price INTEGER);");
+    run!("INSERT INTO Item VALUES (1, 'apple', 50), (2, 'banana', 150), (3, 'cherry', 30);");
+
+    test!(
+        "CREATE VIEW Cheap AS SELECT id, name FROM Item WHERE price < 100;",
+        Ok(Payload::CreateView)
+    );
+    test!(
+        "SELECT * FROM Cheap ORDER BY id;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            1     "apple".to_owned();
+            3     "cherry".to_owned()
+        ))
+    );
+    test!(
+        "CREATE VIEW Cheap AS SELECT id FROM Item;",
+        Err(ViewError::AlreadyExists("Cheap".to_owned()).into())
+    );
+    test!(
+        "CREATE VIEW Item AS SELECT id FROM Item;",
+        Err(AlterError::TableAlreadyExists("Item".to_owned()).into())
+    );
+    test!(
+        "CREATE TABLE Cheap (id INTEGER);",
+        Err(ViewError::AlreadyExists("Cheap".to_owned()).into())
+    );
+
+    // column aliases
+    run!("CREATE VIEW Named (item_id, item_name) AS SELECT id, name FROM Item;");
+    test!(
+        "SELECT item_name FROM Named WHERE item_id = 2;",
+        Ok(select!(item_name Str; "banana".to_owned()))
+    );
+
+    // views over views, joins and subqueries
+    run!("CREATE VIEW CheapCount AS SELECT COUNT(*) AS cnt FROM Cheap;");
+    test!("SELECT cnt FROM CheapCount;", Ok(select!(cnt I64; 2)));
+    test!(
+        "SELECT c.name, i.price FROM Cheap c JOIN Item i ON c.id = i.id WHERE i.price > 40;",
+        Ok(select!(
+            name                | price
+            Str                 | I64;
+            "apple".to_owned()    50
+        ))
+    );
+    test!(
+        "SELECT name FROM Item WHERE id NOT IN (SELECT id FROM Cheap);",
+        Ok(select!(name Str; "banana".to_owned()))
+    );
+
+    // common table expressions shadow the views
+    test!(
+        "WITH Cheap AS (SELECT 10 AS id) SELECT id FROM Cheap;",
+        Ok(select!(id I64; 10))
+    );
+
+    test!(
+        "CREATE OR REPLACE VIEW Cheap AS SELECT id, name FROM Item WHERE price < 40;",
+        Ok(Payload::CreateView)
+    );
+    test!(
+        "SELECT * FROM Cheap;",
+        Ok(select!(
+            id  | name
+            I64 | Str;
+            3     "cherry".to_owned()
+        ))
+    );
+    test!("SELECT cnt FROM CheapCount;", Ok(select!(cnt I64; 1)));
+    test!(
+        "CREATE OR REPLACE VIEW Cheap AS SELECT cnt FROM CheapCount;",
+        Err(ViewError::RecursiveReference("Cheap".to_owned()).into())
+    );
+
+    test!(
+        "SELECT OBJECT_NAME, OBJECT_TYPE FROM GLUE_OBJECTS WHERE OBJECT_TYPE = 'VIEW';",
+        Ok(select!(
+            OBJECT_NAME               | OBJECT_TYPE
+            Str                       | Str;
+            "Cheap".to_owned()          "VIEW".to_owned();
+            "CheapCount".to_owned()     "VIEW".to_owned();
+            "Named".to_owned()          "VIEW".to_owned()
+        ))
+    );
+
+    test!("DROP VIEW Named;", Ok(Payload::DropView));
+    test!(
+        "SELECT * FROM Named;",
+        Err(FetchError::TableNotFound("Named".to_owned()).into())
+    );
+    test!(
+        "DROP VIEW Named;",
+        Err(ViewError::NotFound("Named".to_owned()).into())
+    );
+    test!("DROP VIEW IF EXISTS Named;", Ok(Payload::DropView));
+    test!("DROP VIEW CheapCount, Cheap;", Ok(Payload::DropView));
+});
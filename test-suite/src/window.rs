@@ -0,0 +1,257 @@
+use {
+    crate::*,
+    gluesql_core::{
+        executor::{EvaluateError, WindowError},
+        prelude::Value::*,
+        translate::TranslateError,
+    },
+};
+
+test_case!(window, async move {
+    run!(
+        "
+        CREATE TABLE Sales (
+            id INTEGER,
+            item TEXT,
+            seq INTEGER,
+            amount INTEGER
+        );
+    "
+    );
+    run!(
+        "
+        INSERT INTO Sales VALUES
+            (1, 'apple', 1, 10),
+            (2, 'apple', 2, 30),
+            (3, 'apple', 3, 20),
+            (4, 'banana', 1, 5),
+            (5, 'banana', 2, 5),
+            (6, 'banana', 3, 15);
+    "
+    );
+
+    let test_cases = [
+        (
+            "SELECT id, ROW_NUMBER() OVER (PARTITION BY item ORDER BY amount DESC) AS rn
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | rn
+                I64 | I64;
+                1     3;
+                2     1;
+                3     2;
+                4     2;
+                5     3;
+                6     1
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                RANK() OVER (ORDER BY amount) AS rnk,
+                DENSE_RANK() OVER (ORDER BY amount) AS dense_rnk
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | rnk | dense_rnk
+                I64 | I64 | I64;
+                1     3     2;
+                2     6     5;
+                3     5     4;
+                4     1     1;
+                5     1     1;
+                6     4     3
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                LAG(amount) OVER (PARTITION BY item ORDER BY seq) AS prev_amount,
+                LEAD(amount, 1, 0) OVER (PARTITION BY item ORDER BY seq) AS next_amount
+            FROM Sales ORDER BY id",
+            Ok(select_with_null!(
+                id     | prev_amount | next_amount;
+                I64(1)   Null          I64(30);
+                I64(2)   I64(10)       I64(20);
+                I64(3)   I64(30)       I64(0);
+                I64(4)   Null          I64(5);
+                I64(5)   I64(5)        I64(15);
+                I64(6)   I64(5)        I64(0)
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                FIRST_VALUE(amount) OVER (PARTITION BY item ORDER BY seq) AS first_amount,
+                LAST_VALUE(amount) OVER (
+                    PARTITION BY item ORDER BY seq
+                    ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+                ) AS last_amount
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | first_amount | last_amount
+                I64 | I64          | I64;
+                1     10             20;
+                2     10             20;
+                3     10             20;
+                4     5              15;
+                5     5              15;
+                6     5              15
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                SUM(amount) OVER (PARTITION BY item ORDER BY seq) AS running_total,
+                MAX(amount) OVER (PARTITION BY item) AS max_amount
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | running_total | max_amount
+                I64 | I64           | I64;
+                1     10              30;
+                2     40              30;
+                3     60              30;
+                4     5               15;
+                5     10              15;
+                6     25              15
+            )),
+        ),
+        (
+            "SELECT id, SUM(amount) OVER (ORDER BY amount) AS total
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | total
+                I64 | I64;
+                1     20;
+                2     85;
+                3     55;
+                4     10;
+                5     10;
+                6     35
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                SUM(amount) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) AS total
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | total
+                I64 | I64;
+                1     40;
+                2     60;
+                3     55;
+                4     30;
+                5     25;
+                6     20
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                COUNT(*) OVER (ORDER BY amount RANGE BETWEEN 5 PRECEDING AND 5 FOLLOWING) AS cnt
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | cnt
+                I64 | I64;
+                1     4;
+                2     1;
+                3     2;
+                4     3;
+                5     3;
+                6     3
+            )),
+        ),
+        (
+            "SELECT item, seq, amount FROM (
+                SELECT
+                    item, seq, amount,
+                    ROW_NUMBER() OVER (PARTITION BY item ORDER BY amount DESC) AS rn
+                FROM Sales
+            ) AS Ranked
+            WHERE rn <= 2
+            ORDER BY item, rn",
+            Ok(select!(
+                item                | seq | amount
+                Str                 | I64 | I64;
+                "apple".to_owned()    2     30;
+                "apple".to_owned()    3     20;
+                "banana".to_owned()   3     15;
+                "banana".to_owned()   1     5
+            )),
+        ),
+        (
+            "SELECT item, SUM(amount) AS total, RANK() OVER (ORDER BY SUM(amount) DESC) AS rnk
+            FROM Sales GROUP BY item ORDER BY rnk",
+            Ok(select!(
+                item                | total | rnk
+                Str                 | I64   | I64;
+                "apple".to_owned()    60      1;
+                "banana".to_owned()   25      2
+            )),
+        ),
+        (
+            "SELECT id FROM Sales WHERE ROW_NUMBER() OVER () = 1",
+            Err(EvaluateError::WindowFunctionNotAllowed("ROW_NUMBER() OVER ()".to_owned()).into()),
+        ),
+        (
+            "SELECT NTILE(2) OVER (ORDER BY id) FROM Sales",
+            Err(TranslateError::UnsupportedWindowFunction("NTILE".to_owned()).into()),
+        ),
+        (
+            "SELECT
+                id,
+                SUM(DISTINCT amount) OVER () AS total,
+                COUNT(DISTINCT amount) OVER (PARTITION BY item ORDER BY seq) AS cnt,
+                SUM(DISTINCT amount) OVER (
+                    ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW
+                ) AS pair_total
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | total | cnt | pair_total
+                I64 | I64   | I64 | I64;
+                1     80      1     10;
+                2     80      2     40;
+                3     80      3     50;
+                4     80      1     25;
+                5     80      1     5;
+                6     80      2     20
+            )),
+        ),
+        (
+            "SELECT
+                id,
+                IFNULL(LAG(amount) OVER (PARTITION BY item ORDER BY seq), 0) AS prev_amount,
+                ROUND(AVG(amount) OVER (PARTITION BY item)) AS avg_amount,
+                LAG(amount) OVER (ORDER BY id) IS NULL AS is_first
+            FROM Sales ORDER BY id",
+            Ok(select!(
+                id  | prev_amount | avg_amount | is_first
+                I64 | I64         | F64        | Bool;
+                1     0             20.0         true;
+                2     10            20.0         false;
+                3     30            20.0         false;
+                4     0             8.0          false;
+                5     5             8.0          false;
+                6     5             8.0          false
+            )),
+        ),
+        (
+            "SELECT id FROM Sales
+            ORDER BY IFNULL(LEAD(amount) OVER (ORDER BY id), 100) DESC, id
+            LIMIT 2",
+            Ok(select!(id; I64; 6; 1)),
+        ),
+        (
+            "SELECT LAG(DISTINCT amount) OVER (ORDER BY id) FROM Sales",
+            Err(TranslateError::DistinctInWindowFunctionNotSupported("LAG".to_owned()).into()),
+        ),
+        (
+            "SELECT SUM(amount) OVER (ORDER BY item, seq RANGE 1 PRECEDING) FROM Sales",
+            Err(WindowError::RangeOffsetRequiresSingleOrderBy.into()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases {
+        test!(sql, expected);
+    }
+});